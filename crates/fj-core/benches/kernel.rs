@@ -0,0 +1,97 @@
+//! Benchmarks for the core operations of the kernel
+//!
+//! Uses the standard models from [`fj_core::bench_models`], so the results can
+//! be compared between releases. Run with `cargo bench -p fj-core`.
+
+// `criterion_group!` generates a public function without documentation.
+#![allow(missing_docs)]
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use fj_core::{
+    algorithms::{approx::Tolerance, triangulate::Triangulate},
+    bench_models::{perforated_plate, swept_spline},
+    objects::Solid,
+    operations::insert::Insert,
+    services::Services,
+    storage::Handle,
+    validate::Validate,
+};
+
+/// A function that builds one of the standard models
+type BuildModel = fn(&mut Services) -> Solid;
+
+/// The models to run each benchmark with
+fn models() -> [(&'static str, BuildModel); 2] {
+    [
+        ("perforated_plate", |services: &mut Services| {
+            perforated_plate(16, services)
+        }),
+        ("swept_spline", |services: &mut Services| {
+            swept_spline(32, 0.0001, services)
+        }),
+    ]
+}
+
+fn build(model: BuildModel) -> Handle<Solid> {
+    let mut services = Services::new();
+    let solid = model(&mut services).insert(&mut services);
+    services
+        .drop_and_validate()
+        .expect("Benchmark model must be valid");
+    solid
+}
+
+fn sweep(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sweep");
+    group.sample_size(10);
+
+    for (name, model) in models() {
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                let mut services = Services::new();
+                model(&mut services).insert(&mut services)
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn triangulate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("triangulate");
+    group.sample_size(10);
+
+    let tolerance =
+        Tolerance::from_scalar(0.001).expect("Tolerance is positive");
+
+    for (name, model) in models() {
+        let solid = build(model);
+
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| (&*solid, tolerance).triangulate())
+        });
+    }
+
+    group.finish();
+}
+
+fn validate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate");
+
+    for (name, model) in models() {
+        let solid = build(model);
+
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                let mut errors = Vec::new();
+                solid.validate(&mut errors);
+                errors
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, sweep, triangulate, validate);
+criterion_main!(benches);
@@ -0,0 +1,195 @@
+//! Caching of approximations by geometry
+//!
+//! See [`GeometryApproxCache`].
+
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    io::{self, Read, Write},
+};
+
+use super::ApproxPoint;
+
+/// Cache for approximations, keyed by the geometry they were computed from
+///
+/// Unlike the other approximation caches, this one doesn't depend on the
+/// identity of any objects. That means it can be persisted between runs of a
+/// model, using [`GeometryApproxCache::write`] and [`GeometryApproxCache::read`].
+///
+/// Curves and surfaces are cached separately, as their approximations have
+/// different dimensions.
+#[derive(Default)]
+pub struct GeometryApproxCache {
+    pub(super) curves: BTreeMap<GeometryKey, Vec<ApproxPoint<1>>>,
+    pub(super) surfaces: BTreeMap<GeometryKey, Vec<ApproxPoint<2>>>,
+}
+
+impl GeometryApproxCache {
+    /// Write the cached approximations
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        write_approximations(&mut writer, &self.curves)?;
+        write_approximations(&mut writer, &self.surfaces)?;
+
+        Ok(())
+    }
+
+    /// Read approximations that have been written by [`Self::write`]
+    pub fn read(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != *MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Not an approximation cache, or incompatible version",
+            ));
+        }
+
+        let curves = read_approximations(&mut reader)?;
+        let surfaces = read_approximations(&mut reader)?;
+
+        Ok(Self { curves, surfaces })
+    }
+}
+
+/// Identifies the geometry that an approximation was computed from
+///
+/// This is the debug representation of all the inputs of the approximation,
+/// which includes the exact value of every number. Unlike a hash, different
+/// inputs can't end up with the same key. Unlike object IDs, the key is the
+/// same between runs of a model.
+///
+/// Only use this with inputs whose `Debug` implementation is derived, so it
+/// doesn't leave out anything that the approximation depends on.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub(super) struct GeometryKey(String);
+
+impl GeometryKey {
+    pub(super) fn new(inputs: &impl Debug) -> Self {
+        Self(format!("{inputs:?}"))
+    }
+}
+
+/// Identifies the format of persisted approximation caches
+const MAGIC: &[u8; 8] = b"fjapprx2";
+
+fn write_approximations<const D: usize>(
+    writer: &mut impl Write,
+    approximations: &BTreeMap<GeometryKey, Vec<ApproxPoint<D>>>,
+) -> io::Result<()> {
+    write_u64(writer, approximations.len() as u64)?;
+
+    for (GeometryKey(key), points) in approximations {
+        write_u64(writer, key.len() as u64)?;
+        writer.write_all(key.as_bytes())?;
+
+        write_u64(writer, points.len() as u64)?;
+        for point in points {
+            let local = point.local_form.coords.components;
+            let global = point.global_form.coords.components;
+
+            for value in local.into_iter().chain(global) {
+                writer.write_all(&value.into_f64().to_le_bytes())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_approximations<const D: usize>(
+    reader: &mut impl Read,
+) -> io::Result<BTreeMap<GeometryKey, Vec<ApproxPoint<D>>>> {
+    let mut approximations = BTreeMap::new();
+
+    for _ in 0..read_u64(reader)? {
+        let mut key = vec![0; read_len(reader)?];
+        reader.read_exact(&mut key)?;
+        let key = String::from_utf8(key)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        let mut points = Vec::new();
+        for _ in 0..read_u64(reader)? {
+            let mut local = [0.; D];
+            for value in &mut local {
+                *value = read_f64(reader)?;
+            }
+            let mut global = [0.; 3];
+            for value in &mut global {
+                *value = read_f64(reader)?;
+            }
+
+            points.push(ApproxPoint::new(local, global));
+        }
+
+        approximations.insert(GeometryKey(key), points);
+    }
+
+    Ok(approximations)
+}
+
+fn write_u64(writer: &mut impl Write, value: u64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_len(reader: &mut impl Read) -> io::Result<usize> {
+    usize::try_from(read_u64(reader)?)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn read_f64(reader: &mut impl Read) -> io::Result<f64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(f64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::algorithms::approx::ApproxPoint;
+
+    use super::{GeometryApproxCache, GeometryKey};
+
+    #[test]
+    fn keys_of_different_geometry_differ() {
+        // Numbers that only differ in the last bit must not share a key.
+        let a = 0.1_f64;
+        let b = f64::from_bits(a.to_bits() + 1);
+
+        assert_ne!(GeometryKey::new(&a), GeometryKey::new(&b));
+        assert_eq!(GeometryKey::new(&a), GeometryKey::new(&0.1_f64));
+    }
+
+    #[test]
+    fn write_and_read() -> anyhow::Result<()> {
+        let mut cache = GeometryApproxCache::default();
+        cache.curves.insert(
+            GeometryKey::new(&"curve"),
+            vec![ApproxPoint::new([0.5], [1., 2., 3.])],
+        );
+        cache.surfaces.insert(
+            GeometryKey::new(&"surface"),
+            vec![ApproxPoint::new([0.5, 0.25], [1., 2., 3.])],
+        );
+
+        let mut bytes = Vec::new();
+        cache.write(&mut bytes)?;
+        let read = GeometryApproxCache::read(bytes.as_slice())?;
+
+        assert_eq!(read.curves, cache.curves);
+        assert_eq!(read.surfaces, cache.surfaces);
+
+        // Caches in an older format are rejected, not misread.
+        bytes[..8].copy_from_slice(b"fjapprx1");
+        assert!(GeometryApproxCache::read(bytes.as_slice()).is_err());
+
+        Ok(())
+    }
+}
@@ -10,7 +10,10 @@ use crate::{
     storage::{Handle, HandleWrapper},
 };
 
-use super::{Approx, ApproxPoint, Tolerance};
+use super::{
+    cache::{GeometryApproxCache, GeometryKey},
+    Approx, ApproxPoint, Tolerance,
+};
 
 impl Approx
     for (
@@ -29,16 +32,31 @@ impl Approx
         cache: &mut Self::Cache,
     ) -> Self::Approximation {
         let (curve, surface_path, surface, boundary) = self;
+        let tolerance = tolerance.into();
+
+        if let Some(approx) = cache.get(curve, boundary, tolerance) {
+            return approx;
+        }
 
-        match cache.get(curve, boundary) {
-            Some(approx) => approx,
+        let key = GeometryKey::new(&(
+            surface_path,
+            surface.geometry(),
+            boundary,
+            tolerance,
+        ));
+        let approx = match cache.geometry.curves.get(&key) {
+            Some(points) => CurveApprox {
+                points: points.clone(),
+            },
             None => {
                 let approx =
                     approx_curve(&surface_path, surface, boundary, tolerance);
-
-                cache.insert(curve.clone(), boundary, approx)
+                cache.geometry.curves.insert(key, approx.points.clone());
+                approx
             }
-        }
+        };
+
+        cache.insert(curve.clone(), boundary, tolerance, approx)
     }
 }
 
@@ -55,12 +73,70 @@ fn approx_curve(
     // `GlobalPath` grow APIs that are better suited to implementing this code
     // in a more abstract way.
     let points = match (path, surface.geometry().u) {
-        (SurfacePath::Circle(_), GlobalPath::Circle(_)) => {
-            todo!(
-                "Approximating a circle on a curved surface not supported yet."
-            )
+        (
+            SurfacePath::Circle(_)
+            | SurfacePath::Spiral(_)
+            | SurfacePath::Involute(_),
+            GlobalPath::Circle(_)
+            | GlobalPath::Ellipse(_)
+            | GlobalPath::Spiral(_)
+            | GlobalPath::Involute(_),
+        ) => {
+            // Approximate the path within the surface, then refine that
+            // approximation wherever the surface is curved in between two of
+            // its points. The boundary is not part of the approximation, but
+            // still required for refining the first and last segment.
+            let tolerance = tolerance.into();
+
+            let mut points_surface = vec![(
+                boundary.inner[0],
+                path.point_from_path_coords(boundary.inner[0]),
+            )];
+            points_surface
+                .extend((path, boundary).approx_with_cache(tolerance, &mut ()));
+            points_surface.push((
+                boundary.inner[1],
+                path.point_from_path_coords(boundary.inner[1]),
+            ));
+
+            let mut points = Vec::new();
+            for (i, segment) in points_surface.windows(2).enumerate() {
+                let [(t0, p0), (t1, p1)] = [segment[0], segment[1]];
+
+                if i > 0 {
+                    points.push((t0, p0));
+                }
+                if p0.u == p1.u {
+                    continue;
+                }
+
+                let range_u = CurveBoundary::from([[p0.u], [p1.u]]);
+                let approx_u = (surface.geometry().u, range_u)
+                    .approx_with_cache(tolerance, &mut ());
+
+                for (u, _) in approx_u {
+                    let s = (u.t - p0.u) / (p1.u - p0.u);
+                    let t = t0 + (t1 - t0) * s;
+                    points.push((t, path.point_from_path_coords(t)));
+                }
+            }
+
+            points
+                .into_iter()
+                .map(|(point_curve, point_surface)| {
+                    let point_global = surface
+                        .geometry()
+                        .point_from_surface_coords(point_surface);
+                    (point_curve, point_global)
+                })
+                .collect()
         }
-        (SurfacePath::Circle(_), GlobalPath::Line(_)) => {
+        (
+            SurfacePath::Circle(_)
+            | SurfacePath::Spiral(_)
+            | SurfacePath::Involute(_),
+            GlobalPath::Line(_),
+        ) => {
             (path, boundary)
                 .approx_with_cache(tolerance, &mut ())
                 .into_iter()
@@ -102,7 +178,7 @@ fn approx_curve(
                 let point_surface = path.point_from_path_coords([t]);
                 let point_global =
                     surface.geometry().point_from_surface_coords(point_surface);
-                points.push((u, point_global));
+                points.push((Point::from([t]), point_global));
             }
 
             points
@@ -133,10 +209,23 @@ impl CurveApprox {
 }
 
 /// Cache for curve approximations
+///
+/// Approximations are cached per curve and tolerance. This makes sure that all
+/// half-edges that share a curve are approximated in exactly the same way.
+///
+/// In addition, approximations are cached per geometry, in a
+/// [`GeometryApproxCache`]. Those don't depend on the identity of any objects,
+/// and can be persisted between runs of a model. See
+/// [`HalfEdgeApproxCache::save`].
+///
+/// [`HalfEdgeApproxCache::save`]: super::edge::HalfEdgeApproxCache::save
 #[derive(Default)]
 pub struct CurveApproxCache {
-    inner:
-        BTreeMap<(HandleWrapper<Curve>, CurveBoundary<Point<1>>), CurveApprox>,
+    inner: BTreeMap<
+        (HandleWrapper<Curve>, CurveBoundary<Point<1>>, Tolerance),
+        CurveApprox,
+    >,
+    pub(super) geometry: GeometryApproxCache,
 }
 
 impl CurveApproxCache {
@@ -144,13 +233,18 @@ impl CurveApproxCache {
         &self,
         handle: &Handle<Curve>,
         boundary: CurveBoundary<Point<1>>,
+        tolerance: Tolerance,
     ) -> Option<CurveApprox> {
         let handle = HandleWrapper::from(handle.clone());
 
-        if let Some(approx) = self.inner.get(&(handle.clone(), boundary)) {
+        if let Some(approx) =
+            self.inner.get(&(handle.clone(), boundary, tolerance))
+        {
             return Some(approx.clone());
         }
-        if let Some(approx) = self.inner.get(&(handle, boundary.reverse())) {
+        if let Some(approx) =
+            self.inner.get(&(handle, boundary.reverse(), tolerance))
+        {
             return Some(approx.clone().reverse());
         }
 
@@ -161,29 +255,36 @@ impl CurveApproxCache {
         &mut self,
         handle: Handle<Curve>,
         boundary: CurveBoundary<Point<1>>,
+        tolerance: Tolerance,
         approx: CurveApprox,
     ) -> CurveApprox {
         let handle = HandleWrapper::from(handle);
         self.inner
-            .insert((handle, boundary), approx.clone())
+            .insert((handle, boundary, tolerance), approx.clone())
             .unwrap_or(approx)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{f64::consts::TAU, ops::Deref};
+    use std::{
+        f64::consts::{PI, TAU},
+        ops::Deref,
+    };
 
+    use fj_math::Scalar;
     use pretty_assertions::assert_eq;
 
     use crate::{
-        algorithms::approx::{Approx, ApproxPoint},
+        algorithms::approx::{Approx, ApproxPoint, Tolerance},
         geometry::{CurveBoundary, GlobalPath, SurfaceGeometry, SurfacePath},
         objects::{Curve, Surface},
         operations::insert::Insert,
         services::Services,
     };
 
+    use super::{CurveApproxCache, GeometryApproxCache};
+
     #[test]
     fn approx_line_on_flat_surface() {
         let mut services = Services::new();
@@ -282,4 +383,87 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(approx.points, expected_approx);
     }
+
+    #[test]
+    fn approx_circle_on_curved_surface() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let curve = Curve::new().insert(&mut services);
+        let surface_path =
+            SurfacePath::circle_from_center_and_radius([PI, 1.], 0.5);
+        let boundary = CurveBoundary::from([[0.], [TAU]]);
+        let surface = Surface::new(SurfaceGeometry {
+            u: GlobalPath::circle_from_radius(1.),
+            v: [0., 0., 1.].into(),
+        });
+
+        let tolerance = Tolerance::from_scalar(0.01)?;
+        let approx =
+            (&curve, surface_path, &surface, boundary).approx(tolerance);
+
+        let points = approx
+            .points
+            .iter()
+            .map(|point| point.global_form)
+            .collect::<Vec<_>>();
+        assert!(!points.is_empty());
+
+        for point in &approx.points {
+            let expected = surface.geometry().point_from_surface_coords(
+                surface_path.point_from_path_coords(point.local_form),
+            );
+            assert_eq!(point.global_form, expected);
+        }
+
+        // The segments between the points must not cut into the cylinder by
+        // more than the tolerance.
+        for segment in points.windows(2) {
+            let center = (segment[0].coords + segment[1].coords) / 2.;
+            let distance_to_axis = center.xy().magnitude();
+            assert!(distance_to_axis >= Scalar::ONE - tolerance.inner());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn persisted_cache() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface_path =
+            SurfacePath::circle_from_center_and_radius([0., 0.], 1.);
+        let boundary = CurveBoundary::from([[0.], [TAU]]);
+        let surface = services.objects.surfaces.xz_plane();
+        let tolerance = Tolerance::from(1.);
+
+        let mut cache = CurveApproxCache::default();
+        let curve = Curve::new().insert(&mut services);
+        let approx = (&curve, surface_path, surface.deref(), boundary)
+            .approx_with_cache(tolerance, &mut cache);
+
+        let mut bytes = Vec::new();
+        cache.geometry.write(&mut bytes)?;
+        let mut cache = CurveApproxCache {
+            geometry: GeometryApproxCache::read(bytes.as_slice())?,
+            ..CurveApproxCache::default()
+        };
+
+        // Objects are different in every run of a model. The approximation
+        // must still be found, based on its geometry.
+        let curve = Curve::new().insert(&mut services);
+        assert!(cache.get(&curve, boundary, tolerance).is_none());
+
+        let cached = (&curve, surface_path, surface.deref(), boundary)
+            .approx_with_cache(tolerance, &mut cache);
+        assert_eq!(cached.points, approx.points);
+        assert_eq!(cache.geometry.curves.len(), 1);
+
+        // A different tolerance is a different geometry, as far as the cache
+        // is concerned.
+        (&curve, surface_path, surface.deref(), boundary)
+            .approx_with_cache(Tolerance::from(0.5), &mut cache);
+        assert_eq!(cache.geometry.curves.len(), 2);
+
+        Ok(())
+    }
 }
@@ -5,11 +5,17 @@
 //! approximations are usually used to build cycle approximations, and this way,
 //! the caller doesn't have to deal with duplicate vertices.
 
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
 use crate::objects::{HalfEdge, Surface};
 
 use super::{
-    curve::CurveApproxCache, vertex::VertexApproxCache, Approx, ApproxPoint,
-    Tolerance,
+    cache::GeometryApproxCache, curve::CurveApproxCache,
+    vertex::VertexApproxCache, Approx, ApproxPoint, Tolerance,
 };
 
 impl Approx for (&HalfEdge, &Surface) {
@@ -67,8 +73,88 @@ pub struct HalfEdgeApprox {
 }
 
 /// Cache for half-edge approximations
+///
+/// This is the cache used when approximating faces, shells, and solids. Pass
+/// the same instance to multiple calls of [`Approx::approx_with_cache`] to
+/// avoid approximating the same geometry more than once.
 #[derive(Default)]
 pub struct HalfEdgeApproxCache {
     start_position: VertexApproxCache,
     curve: CurveApproxCache,
 }
+
+impl HalfEdgeApproxCache {
+    /// Load a cache that has been saved using [`HalfEdgeApproxCache::save`]
+    ///
+    /// Pass the loaded cache to [`Approx::approx_with_cache`], or triangulate
+    /// with it (see [`Triangulate`]), to re-use the approximations it contains.
+    ///
+    /// [`Triangulate`]: crate::algorithms::triangulate::Triangulate
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+
+        let mut cache = Self::default();
+        cache.curve.geometry = GeometryApproxCache::read(file)?;
+
+        Ok(cache)
+    }
+
+    /// Save the cache to a file
+    ///
+    /// This makes it possible to re-use approximations between runs of a
+    /// model, so that only the geometry that has changed since the last run
+    /// needs to be approximated again.
+    ///
+    /// Only approximations that don't depend on the identity of objects are
+    /// saved, as objects are different in every run.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.curve.geometry.write(&mut file)?;
+        file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use crate::{
+        algorithms::approx::{Approx, Tolerance},
+        objects::{Face, Region},
+        operations::{build::BuildRegion, insert::Insert},
+        services::Services,
+    };
+
+    use super::HalfEdgeApproxCache;
+
+    #[test]
+    fn save_and_load() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let region =
+            Region::circle([0., 0.], 1., &mut services).insert(&mut services);
+        let face = Face::new(surface, region);
+        let tolerance = Tolerance::from(0.1);
+
+        let mut cache = HalfEdgeApproxCache::default();
+        let approx = (&face).approx_with_cache(tolerance, &mut cache);
+
+        let path =
+            env::temp_dir().join(format!("fj-approx-cache-{}", process::id()));
+        cache.save(&path)?;
+        let mut loaded = HalfEdgeApproxCache::load(&path)?;
+        fs::remove_file(&path)?;
+
+        // The loaded cache only knows the geometry. That is enough to produce
+        // the same approximation, without computing it again.
+        assert_eq!((&face).approx_with_cache(tolerance, &mut loaded), approx);
+        assert_eq!(
+            loaded.curve.geometry.curves.len(),
+            cache.curve.geometry.curves.len(),
+        );
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+}
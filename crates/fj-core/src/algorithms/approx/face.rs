@@ -5,9 +5,11 @@
 use std::{collections::BTreeSet, ops::Deref};
 
 use fj_interop::mesh::Color;
+use fj_math::{Point, Scalar, Vector};
 
 use crate::{
-    objects::{Face, Handedness, ObjectSet},
+    geometry::SurfaceGeometry,
+    objects::{Cycle, Face, Handedness, ObjectSet},
     validate::ValidationConfig,
 };
 
@@ -86,15 +88,29 @@ impl Approx for &Face {
         // would need to provide its own approximation, as the edges that bound
         // it have nothing to do with its curvature.
 
-        let exterior =
+        let periods = self.surface().geometry().periods();
+
+        let mut exterior =
             (self.region().exterior().deref(), self.surface().deref())
                 .approx_with_cache(tolerance, cache);
+        if let Err(err) = unwrap_seams(
+            &mut exterior,
+            boundary_ends(self.region().exterior()),
+            periods,
+        ) {
+            tracing::warn!("Approximating face: {err}");
+        }
 
         let mut interiors = BTreeSet::new();
         for cycle in self.region().interiors() {
-            let cycle = (cycle.deref(), self.surface().deref())
+            let mut approx = (cycle.deref(), self.surface().deref())
                 .approx_with_cache(tolerance, cache);
-            interiors.insert(cycle);
+            if let Err(err) =
+                unwrap_seams(&mut approx, boundary_ends(cycle), periods)
+            {
+                tracing::warn!("Approximating face: {err}");
+            }
+            interiors.insert(approx);
         }
 
         FaceApprox {
@@ -102,8 +118,115 @@ impl Approx for &Face {
             interiors,
             color: self.region().color(),
             coord_handedness: self.coord_handedness(),
+            surface: self.surface().geometry(),
+            tolerance,
+        }
+    }
+}
+
+/// Make the surface coordinates of a cycle approximation continuous
+///
+/// On a periodic surface, like a cylinder, the half-edges of a cycle can use
+/// different periods of the surface coordinates. A face that crosses the seam
+/// of its surface can be bounded by one half-edge that ends close to `2π`, and
+/// another one that starts right after `0`, for example. Triangulation works in
+/// surface coordinates, and would see a polygon that jumps across the whole
+/// surface.
+///
+/// This shifts the points of each half-edge by whole periods, until they
+/// connect to where the previous half-edge ends. `ends` are the end points of
+/// the half-edges' boundaries, in surface coordinates. The end point has to be
+/// used, rather than the last point of the approximation, because a half-edge
+/// can cover up to a whole period, and its approximation might consist of its
+/// start point alone.
+///
+/// # Errors
+///
+/// Returns an error, if the cycle doesn't close after unwrapping. That is the
+/// case for cycles that wind around the surface, like a circle around a
+/// cylinder, that isn't connected to the seam by a pair of half-edges. Faces
+/// bounded by such cycles have no representation as a polygon in surface
+/// coordinates. The cycle is left unchanged in that case.
+fn unwrap_seams(
+    cycle: &mut CycleApprox,
+    ends: impl IntoIterator<Item = Point<2>>,
+    periods: [Option<Scalar>; 2],
+) -> Result<(), CycleWindsAroundSurface> {
+    if periods == [None, None] {
+        return Ok(());
+    }
+
+    let mut offsets = Vec::new();
+    let mut previous_end: Option<Point<2>> = None;
+
+    for (half_edge, end) in cycle.half_edges.iter().zip(ends) {
+        let offset = match (previous_end, half_edge.points.first()) {
+            (Some(previous_end), Some(first)) => {
+                period_offset(previous_end, first.local_form, periods)
+            }
+            _ => Vector::from([0., 0.]),
+        };
+
+        offsets.push(offset);
+        previous_end = Some(end + offset);
+    }
+
+    let first = cycle
+        .half_edges
+        .first()
+        .and_then(|half_edge| half_edge.points.first());
+    if let (Some(first), Some(last)) = (first, previous_end) {
+        let offset = period_offset(last, first.local_form, periods);
+        if offset.magnitude() != Scalar::ZERO {
+            return Err(CycleWindsAroundSurface);
         }
     }
+
+    for (half_edge, offset) in cycle.half_edges.iter_mut().zip(offsets) {
+        for point in &mut half_edge.points {
+            point.local_form += offset;
+        }
+    }
+
+    Ok(())
+}
+
+/// Compute the end points of a cycle's half-edges, in surface coordinates
+fn boundary_ends(cycle: &Cycle) -> Vec<Point<2>> {
+    cycle
+        .half_edges()
+        .iter()
+        .map(|half_edge| {
+            half_edge
+                .path()
+                .point_from_path_coords(half_edge.boundary().inner[1])
+        })
+        .collect()
+}
+
+/// Error unwrapping a cycle approximation across the seam of its surface
+#[derive(Debug, thiserror::Error)]
+#[error("Cycle winds around periodic surface; this is not supported")]
+pub struct CycleWindsAroundSurface;
+
+/// Compute the multiple of the periods that moves `point` closest to `target`
+fn period_offset(
+    target: Point<2>,
+    point: Point<2>,
+    periods: [Option<Scalar>; 2],
+) -> Vector<2> {
+    let mut offset = Vector::from([0., 0.]);
+
+    for (i, period) in periods.into_iter().enumerate() {
+        let Some(period) = period else {
+            continue;
+        };
+
+        let distance = target.coords.components[i] - point.coords.components[i];
+        offset.components[i] = (distance / period).round() * period;
+    }
+
+    offset
 }
 
 /// An approximation of a [`Face`]
@@ -120,6 +243,12 @@ pub struct FaceApprox {
 
     /// The handedness of the approximated face's front-side coordinate system
     pub coord_handedness: Handedness,
+
+    /// The geometry of the approximated face's surface
+    pub surface: SurfaceGeometry,
+
+    /// The tolerance that the face was approximated with
+    pub tolerance: Tolerance,
 }
 
 impl FaceApprox {
@@ -136,3 +265,99 @@ impl FaceApprox {
         points
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{PI, TAU};
+
+    use fj_math::{Point, Scalar};
+
+    use crate::algorithms::approx::{
+        cycle::CycleApprox, edge::HalfEdgeApprox, ApproxPoint,
+    };
+
+    use super::unwrap_seams;
+
+    #[test]
+    fn unwrap_cycle_across_seam() -> anyhow::Result<()> {
+        // A square on a cylinder, centered on its seam. The second half-edge
+        // has been placed in the next period of the surface coordinates.
+        let (mut cycle, ends) = cycle([
+            [[-0.1, 0.], [0.1, 0.]],
+            [[0.1 + TAU, 0.], [0.1 + TAU, 1.]],
+            [[0.1, 1.], [-0.1, 1.]],
+            [[-0.1, 1.], [-0.1, 0.]],
+        ]);
+
+        unwrap_seams(&mut cycle, ends, [Some(Scalar::TAU), None])?;
+
+        for point in cycle.points() {
+            assert!(point.local_form.u.abs() <= Scalar::from(0.1 + 1e-12));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn unwrap_cycle_with_half_period_edges() -> anyhow::Result<()> {
+        // Half of a cylinder. The approximations of the straight half-edges
+        // consist of their start points only, which are half a period apart.
+        let (mut cycle, ends) = cycle([
+            [[0., 0.], [PI, 0.]],
+            [[PI, 0.], [PI, 1.]],
+            [[PI, 1.], [0., 1.]],
+            [[0., 1.], [0., 0.]],
+        ]);
+
+        unwrap_seams(&mut cycle, ends, [Some(Scalar::TAU), None])?;
+
+        for point in cycle.points() {
+            let u = point.local_form.u;
+            assert!(u >= Scalar::ZERO && u <= Scalar::PI);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_cycle_winding_around_surface() {
+        // A circle around a cylinder, approximated by three half-edges.
+        let (mut cycle, ends) = cycle([
+            [[0., 0.], [2., 0.]],
+            [[2., 0.], [4., 0.]],
+            [[4., 0.], [TAU, 0.]],
+        ]);
+        let points = cycle.points();
+
+        let result = unwrap_seams(&mut cycle, ends, [Some(Scalar::TAU), None]);
+
+        assert!(result.is_err());
+        assert_eq!(cycle.points(), points);
+    }
+
+    /// Build a cycle approximation from half-edges, given by start and end
+    ///
+    /// Like the approximation of a straight half-edge, each half-edge's
+    /// approximation consists of its start point. The end points are returned
+    /// separately.
+    fn cycle<const N: usize>(
+        half_edges: [[[f64; 2]; 2]; N],
+    ) -> (CycleApprox, Vec<Point<2>>) {
+        let ends = half_edges
+            .iter()
+            .map(|[_, end]| Point::from(*end))
+            .collect();
+        let half_edges = half_edges
+            .into_iter()
+            .map(|[start, _]| HalfEdgeApprox {
+                // The global form doesn't matter to the unwrapping.
+                points: vec![ApproxPoint::new(
+                    start,
+                    Point::from([0., 0., 0.]),
+                )],
+            })
+            .collect();
+
+        (CycleApprox { half_edges }, ends)
+    }
+}
@@ -1,14 +1,18 @@
 //! Approximation of objects
 
+pub mod cache;
 pub mod curve;
 pub mod cycle;
 pub mod edge;
 pub mod face;
 pub mod path;
+pub mod polyline;
 pub mod shell;
 pub mod sketch;
 pub mod solid;
+pub mod surface;
 pub mod tolerance;
+pub mod torus;
 pub mod vertex;
 
 use std::{
@@ -36,6 +40,8 @@ pub trait Approx: Sized {
     /// `tolerance` defines how far the approximation is allowed to deviate from
     /// the actual object.
     fn approx(self, tolerance: impl Into<Tolerance>) -> Self::Approximation {
+        let _span = tracing::info_span!("approx").entered();
+
         let mut cache = Self::Cache::default();
         self.approx_with_cache(tolerance, &mut cache)
     }
@@ -30,9 +30,9 @@
 
 use std::iter;
 
-use fj_math::{Circle, Point, Scalar, Sign};
+use fj_math::{Circle, Ellipse, Point, Scalar, Sign};
 
-use crate::geometry::{CurveBoundary, GlobalPath, SurfacePath};
+use crate::geometry::{CurveBoundary, CurveGeom, GlobalPath, SurfacePath};
 
 use super::{Approx, Tolerance};
 
@@ -51,6 +51,18 @@ impl Approx for (&SurfacePath, CurveBoundary<Point<1>>) {
             SurfacePath::Circle(circle) => {
                 approx_circle(circle, range, tolerance.into())
             }
+            SurfacePath::Spiral(spiral) => approx_by_curvature_bound(
+                range,
+                tolerance.into(),
+                |max| spiral.second_derivative_bound(max),
+                |point| spiral.point_from_spiral_coords(point),
+            ),
+            SurfacePath::Involute(involute) => approx_by_curvature_bound(
+                range,
+                tolerance.into(),
+                |max| involute.second_derivative_bound(max),
+                |point| involute.point_from_involute_coords(point),
+            ),
             SurfacePath::Line(_) => vec![],
         }
     }
@@ -71,11 +83,47 @@ impl Approx for (GlobalPath, CurveBoundary<Point<1>>) {
             GlobalPath::Circle(circle) => {
                 approx_circle(&circle, range, tolerance.into())
             }
+            GlobalPath::Ellipse(ellipse) => {
+                approx_ellipse(&ellipse, range, tolerance.into())
+            }
+            GlobalPath::Spiral(spiral) => approx_by_curvature_bound(
+                range,
+                tolerance.into(),
+                |max| spiral.second_derivative_bound(max),
+                |point| spiral.point_from_spiral_coords(point),
+            ),
+            GlobalPath::Involute(involute) => approx_by_curvature_bound(
+                range,
+                tolerance.into(),
+                |max| involute.second_derivative_bound(max),
+                |point| involute.point_from_involute_coords(point),
+            ),
             GlobalPath::Line(_) => vec![],
         }
     }
 }
 
+impl Approx for (&dyn CurveGeom, CurveBoundary<Point<1>>) {
+    type Approximation = Vec<(Point<1>, Point<3>)>;
+    type Cache = ();
+
+    fn approx_with_cache(
+        self,
+        tolerance: impl Into<Tolerance>,
+        (): &mut Self::Cache,
+    ) -> Self::Approximation {
+        let (curve, range) = self;
+
+        curve
+            .approximate(range, tolerance.into())
+            .into_iter()
+            .map(|point_curve| {
+                (point_curve, curve.point_from_curve_coords(point_curve))
+            })
+            .collect()
+    }
+}
+
 /// Approximate a circle
 ///
 /// `tolerance` specifies how much the approximation is allowed to deviate
@@ -98,7 +146,104 @@ fn approx_circle<const D: usize>(
     points
 }
 
-struct PathApproxParams {
+/// Approximate an ellipse
+///
+/// `tolerance` specifies how much the approximation is allowed to deviate
+/// from the ellipse.
+fn approx_ellipse<const D: usize>(
+    ellipse: &Ellipse<D>,
+    boundary: impl Into<CurveBoundary<Point<1>>>,
+    tolerance: Tolerance,
+) -> Vec<(Point<1>, Point<D>)> {
+    let boundary = boundary.into();
+
+    // An ellipse is the image of a circle under a linear map. If we choose the
+    // circle with the radius of the semi-major axis, that linear map can only
+    // shrink distances. Using the parameters of that circle is therefore
+    // guaranteed to stay within the tolerance.
+    let params =
+        PathApproxParams::for_radius(ellipse.semi_major_axis(), tolerance);
+    let mut points = Vec::new();
+
+    for point_curve in params.points(boundary) {
+        let point_global = ellipse.point_from_ellipse_coords(point_curve);
+        points.push((point_curve, point_global));
+    }
+
+    points
+}
+
+/// Approximate a path, based on a bound for the length of its second derivative
+///
+/// This is used for paths that don't have a constant curvature, like spirals
+/// and involutes. `bound` must return a value that is at least the length of
+/// the path's second derivative at any coordinate `t` with `|t| <= max`.
+///
+/// The deviation of a straight segment from the path it approximates is at
+/// most `bound * increment^2 / 8`, which determines the increment for a given
+/// tolerance. To keep the result deterministic (see module documentation), the
+/// increments always start at `t = 0` and grow outwards, in both directions.
+fn approx_by_curvature_bound<const D: usize>(
+    boundary: CurveBoundary<Point<1>>,
+    tolerance: Tolerance,
+    bound: impl Fn(Scalar) -> Scalar,
+    point_from_path_coords: impl Fn(Point<1>) -> Point<D>,
+) -> Vec<(Point<1>, Point<D>)> {
+    // Limit the increment, so a single segment never covers more than a
+    // quarter turn, even where the path is almost straight.
+    let max_increment = Scalar::PI / 2.;
+
+    let [a, b] = boundary.inner.map(|point| point.t);
+    let [min, max] = if a < b { [a, b] } else { [b, a] };
+    let extent = Scalar::max(min.abs(), max.abs());
+
+    let mut coords = Vec::new();
+    let mut t = Scalar::ZERO;
+    loop {
+        for t in [t, -t] {
+            // We can't generate a point exactly at the boundaries of the range
+            // as part of the approximation. Make sure we stay inside the range.
+            if min < t && t < max && !coords.contains(&t) {
+                coords.push(t);
+            }
+        }
+
+        if t >= extent {
+            break;
+        }
+
+        // The bound is evaluated at the far end of the largest possible
+        // increment, so it holds for the whole segment.
+        let bound = bound(t + max_increment);
+        let increment = if bound > Scalar::ZERO {
+            let increment = (tolerance.inner() * 8. / bound).sqrt();
+            if increment < max_increment {
+                increment
+            } else {
+                max_increment
+            }
+        } else {
+            max_increment
+        };
+
+        t += increment;
+    }
+
+    coords.sort();
+    if a > b {
+        coords.reverse();
+    }
+
+    coords
+        .into_iter()
+        .map(|t| {
+            let point_curve = Point::from([t]);
+            (point_curve, point_from_path_coords(point_curve))
+        })
+        .collect()
+}
+
+pub(super) struct PathApproxParams {
     increment: Scalar,
 }
 
@@ -107,8 +252,10 @@ impl PathApproxParams {
         circle: &Circle<D>,
         tolerance: impl Into<Tolerance>,
     ) -> Self {
-        let radius = circle.a().magnitude();
+        Self::for_radius(circle.a().magnitude(), tolerance)
+    }
 
+    pub fn for_radius(radius: Scalar, tolerance: impl Into<Tolerance>) -> Self {
         let num_vertices_to_approx_full_circle = Scalar::max(
             Scalar::PI
                 / (Scalar::ONE - (tolerance.into().inner() / radius)).acos(),
@@ -168,11 +315,11 @@ impl PathApproxParams {
 mod tests {
     use std::f64::consts::TAU;
 
-    use fj_math::{Circle, Point, Scalar};
+    use fj_math::{Circle, Point, Scalar, Spiral};
 
     use crate::algorithms::approx::{path::CurveBoundary, Tolerance};
 
-    use super::PathApproxParams;
+    use super::{approx_by_curvature_bound, PathApproxParams};
 
     #[test]
     fn increment_for_circle() {
@@ -193,6 +340,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn points_for_spiral() {
+        let spiral = Spiral::from_center_and_pitch([0., 0.], 1., 1.);
+        let tolerance = Tolerance::from_scalar(0.01).unwrap();
+
+        let approx = |boundary: [[f64; 1]; 2]| {
+            approx_by_curvature_bound(
+                CurveBoundary::from(boundary),
+                tolerance,
+                |max| spiral.second_derivative_bound(max),
+                |point| spiral.point_from_spiral_coords(point),
+            )
+        };
+
+        let forward = approx([[-1.], [TAU]]);
+        let mut backward = approx([[TAU], [-1.]]);
+        backward.reverse();
+        assert_eq!(forward, backward);
+
+        // The points are strictly inside the range, and a sub-range results in
+        // a subset of the points.
+        assert!(forward
+            .iter()
+            .all(|(t, _)| t.t > Scalar::from(-1.) && t.t < Scalar::TAU));
+        for point in approx([[1.], [2.]]) {
+            assert!(forward.contains(&point));
+        }
+
+        // Each segment stays within the tolerance, at its midpoint.
+        for segment in forward.windows(2) {
+            let [(t_a, a), (t_b, b)] = [segment[0], segment[1]];
+
+            let mid_t = Point::from([(t_a.t + t_b.t) / 2.]);
+            let mid_path = spiral.point_from_spiral_coords(mid_t);
+            let mid_segment = a + (b - a) / 2.;
+
+            assert!(mid_path.distance_to(&mid_segment) <= tolerance.inner());
+        }
+    }
+
     #[test]
     fn points_for_circle() {
         // At the chosen values for radius and tolerance (see below), the
@@ -0,0 +1,118 @@
+//! Approximation of edges as polylines
+//!
+//! See [`EdgePolylines`].
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use fj_math::PolyChain;
+use itertools::Itertools;
+
+use crate::objects::Assembly;
+
+use super::{face::FaceApprox, Approx, Tolerance};
+
+/// Approximate the edges of a shape as polylines
+///
+/// While a triangle mesh is enough to display or export the surface of a
+/// shape, it doesn't preserve the edges of the model. This approximation
+/// provides them, for drawing edges in a viewer or writing wireframe formats.
+pub trait EdgePolylines {
+    /// Approximate the edges of the shape as polylines
+    ///
+    /// Each edge is approximated once, regardless of how many faces it bounds.
+    fn edge_polylines(self) -> Vec<PolyChain<3>>;
+}
+
+impl<T> EdgePolylines for (T, Tolerance)
+where
+    T: Approx,
+    T::Approximation: IntoIterator<Item = FaceApprox>,
+{
+    fn edge_polylines(self) -> Vec<PolyChain<3>> {
+        let (approx, tolerance) = self;
+
+        // Sibling half-edges are approximated by the same points, in opposite
+        // order. Normalizing the order of the points lets us deduplicate them.
+        let mut polylines = BTreeSet::new();
+
+        for face in approx.approx(tolerance) {
+            for cycle in [&face.exterior].into_iter().chain(&face.interiors) {
+                for (current, next) in
+                    cycle.half_edges.iter().circular_tuple_windows()
+                {
+                    let mut points = current
+                        .points
+                        .iter()
+                        .map(|point| point.global_form)
+                        .collect::<Vec<_>>();
+                    if let Some(end) = next.points.first() {
+                        points.push(end.global_form);
+                    }
+
+                    let reversed =
+                        points.iter().rev().copied().collect::<Vec<_>>();
+                    polylines.insert(points.min(reversed));
+                }
+            }
+        }
+
+        polylines.into_iter().map(PolyChain::from_points).collect()
+    }
+}
+
+impl EdgePolylines for (&Assembly, Tolerance) {
+    fn edge_polylines(self) -> Vec<PolyChain<3>> {
+        let (assembly, tolerance) = self;
+
+        let mut solid_polylines = BTreeMap::new();
+        let mut polylines = Vec::new();
+
+        for instance in assembly.instances() {
+            let solid = instance.solid();
+            let solid_polylines = solid_polylines
+                .entry(solid.id())
+                .or_insert_with(|| (&**solid, tolerance).edge_polylines());
+
+            let transform = instance.transform();
+
+            for polyline in solid_polylines.iter() {
+                let points = transform.transform_points(polyline.points());
+                polylines.push(PolyChain::from_points(points));
+            }
+        }
+
+        polylines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::approx::Tolerance,
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    use super::EdgePolylines;
+
+    #[test]
+    fn box_edges() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = Solid::box_from_dims([1., 2., 3.], &mut services)
+            .insert(&mut services);
+        let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
+
+        let polylines = (&*solid, tolerance).edge_polylines();
+        assert_eq!(polylines.len(), 12);
+
+        for polyline in polylines {
+            assert_eq!(polyline.points().len(), 2);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+//! Surface approximation
+//!
+//! Approximates any surface geometry that implements [`SurfaceGeom`]. See the
+//! documentation of [`SurfaceGeom::approximate`] for details.
+
+use fj_math::Point;
+
+use crate::geometry::{CurveBoundary, SurfaceGeom};
+
+use super::{
+    cache::{GeometryApproxCache, GeometryKey},
+    Approx, ApproxPoint, Tolerance,
+};
+
+impl Approx for (&dyn SurfaceGeom, [CurveBoundary<Point<1>>; 2]) {
+    type Approximation = Vec<ApproxPoint<2>>;
+    type Cache = GeometryApproxCache;
+
+    fn approx_with_cache(
+        self,
+        tolerance: impl Into<Tolerance>,
+        cache: &mut Self::Cache,
+    ) -> Self::Approximation {
+        let (surface, boundaries) = self;
+        let tolerance = tolerance.into();
+
+        let key = GeometryKey::new(&(surface, boundaries, tolerance));
+        if let Some(approx) = cache.surfaces.get(&key) {
+            return approx.clone();
+        }
+
+        let approx = surface
+            .approximate(boundaries, tolerance)
+            .into_iter()
+            .map(|point_surface| {
+                let point_global =
+                    surface.point_from_surface_coords(point_surface);
+                ApproxPoint::new(point_surface, point_global)
+            })
+            .collect::<Vec<_>>();
+
+        cache.surfaces.insert(key, approx.clone());
+        approx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Circle, Torus};
+
+    use crate::{
+        algorithms::approx::{
+            cache::GeometryApproxCache, Approx, ApproxPoint, Tolerance,
+        },
+        geometry::{CurveBoundary, SurfaceGeom},
+    };
+
+    #[test]
+    fn cache_surface_approximations() {
+        let boundaries = [CurveBoundary::from([[0.], [1.]]); 2];
+        let tolerance = Tolerance::from(0.1);
+
+        let mut cache = GeometryApproxCache::default();
+        let mut approx = |surface: &dyn SurfaceGeom| -> Vec<ApproxPoint<2>> {
+            (surface, boundaries).approx_with_cache(tolerance, &mut cache)
+        };
+
+        let a =
+            Torus::new(Circle::from_center_and_radius([0., 0., 0.], 2.), 1.);
+        let b =
+            Torus::new(Circle::from_center_and_radius([0., 0., 0.], 2.), 0.5);
+
+        let approx_a = approx(&a);
+        assert_eq!(approx(&a), approx_a);
+        assert_ne!(approx(&b), approx_a);
+
+        assert_eq!(cache.surfaces.len(), 2);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! See [`Tolerance`].
 
-use fj_math::Scalar;
+use fj_math::{Aabb, Scalar};
 
 /// A tolerance value
 ///
@@ -40,6 +40,26 @@ impl Tolerance {
         Ok(Self(scalar))
     }
 
+    /// Derive a reasonable tolerance from the bounding box of a shape
+    ///
+    /// Uses a thousandth of the smallest non-zero extent of the bounding box.
+    /// If the bounding box has no non-zero extent, a thousandth of a unit is
+    /// used instead.
+    ///
+    /// Returns an error, if the resulting tolerance is too small to be
+    /// represented.
+    pub fn from_aabb(aabb: &Aabb<3>) -> Result<Self, InvalidTolerance> {
+        let min_extent = aabb
+            .size()
+            .components
+            .into_iter()
+            .filter(|extent| *extent > Scalar::ZERO)
+            .min()
+            .unwrap_or(Scalar::ONE);
+
+        Self::from_scalar(min_extent / Scalar::from_f64(1000.))
+    }
+
     /// Return the [`Scalar`] that defines the tolerance
     pub fn inner(&self) -> Scalar {
         self.0
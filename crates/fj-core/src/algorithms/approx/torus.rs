@@ -0,0 +1,134 @@
+//! # Torus approximation
+//!
+//! Like paths, a torus has an infinite coordinate space, so a range must be
+//! provided for each of its coordinates. The approximation then returns a grid
+//! of points within those ranges.
+//!
+//! The boundaries of the ranges are not included in the approximation, and the
+//! grid is deterministic for a given combination of torus and tolerance, for
+//! the same reasons as explained in the [path approximation] documentation.
+//!
+//! [path approximation]: super::path
+
+use fj_math::{Point, Scalar, Torus};
+
+use crate::geometry::CurveBoundary;
+
+use super::{path::PathApproxParams, Approx, ApproxPoint, Tolerance};
+
+impl Approx for (&Torus, [CurveBoundary<Point<1>>; 2]) {
+    type Approximation = Vec<ApproxPoint<2>>;
+    type Cache = ();
+
+    fn approx_with_cache(
+        self,
+        tolerance: impl Into<Tolerance>,
+        (): &mut Self::Cache,
+    ) -> Self::Approximation {
+        let (torus, [range_u, range_v]) = self;
+        let [params_u, params_v] = params(torus, tolerance.into());
+
+        let mut points = Vec::new();
+
+        for u in params_u.points(range_u) {
+            for v in params_v.points(range_v) {
+                let point_surface = Point::from([u.t, v.t]);
+                let point_global = torus.point_from_torus_coords(point_surface);
+
+                points.push(ApproxPoint::new(point_surface, point_global));
+            }
+        }
+
+        points
+    }
+}
+
+/// Approximate a whole torus
+///
+/// Returns a closed grid of points, that covers the full period of both
+/// coordinates. Each row contains the points around the tube, for a single
+/// u-coordinate.
+///
+/// Unlike the approximation of a range, this includes the points on the seams,
+/// where either coordinate is zero. Points at the end of a period are not
+/// repeated, so the last row connects back to the first one, and the last point
+/// of each row connects back to its first one.
+pub fn approx_closed_torus(
+    torus: &Torus,
+    tolerance: impl Into<Tolerance>,
+) -> Vec<Vec<ApproxPoint<2>>> {
+    let [params_u, params_v] = params(torus, tolerance.into());
+
+    let coords = |params: &PathApproxParams| {
+        // The increment divides the full period into an integer number of
+        // steps. Rounding just removes the floating-point error.
+        let num_steps = (Scalar::TAU / params.increment()).round().into_f64();
+        (0..num_steps as usize)
+            .map(|i| params.increment() * Scalar::from(i as f64))
+            .collect::<Vec<_>>()
+    };
+    let coords_v = coords(&params_v);
+
+    coords(&params_u)
+        .into_iter()
+        .map(|u| {
+            coords_v
+                .iter()
+                .map(|&v| {
+                    let point_surface = Point::from([u, v]);
+                    let point_global =
+                        torus.point_from_torus_coords(point_surface);
+
+                    ApproxPoint::new(point_surface, point_global)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn params(torus: &Torus, tolerance: Tolerance) -> [PathApproxParams; 2] {
+    // The largest circle around the axis is the one on the outside of the
+    // torus. If we approximate that one within tolerance, all the other ones
+    // will be too.
+    let params_u = PathApproxParams::for_radius(
+        torus.major_radius() + torus.minor_radius(),
+        tolerance,
+    );
+    let params_v =
+        PathApproxParams::for_radius(torus.minor_radius(), tolerance);
+
+    [params_u, params_v]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use fj_math::{Circle, Scalar, Torus};
+
+    use crate::{algorithms::approx::Approx, geometry::CurveBoundary};
+
+    #[test]
+    fn approx_torus() {
+        let torus =
+            Torus::new(Circle::from_center_and_radius([0., 0., 0.], 2.), 1.);
+        let boundary = CurveBoundary::from([[0.], [TAU]]);
+
+        let tolerance = 0.1;
+        let approx = (&torus, [boundary, boundary]).approx(tolerance);
+
+        assert!(!approx.is_empty());
+
+        for point in approx {
+            // All points must be on the torus.
+            let on_torus = torus.point_from_torus_coords(point.local_form);
+            assert_eq!(point.global_form, on_torus);
+
+            // And all of them must be within the ranges, boundaries excluded.
+            for coord in [point.local_form.u, point.local_form.v] {
+                assert!(coord > Scalar::ZERO);
+                assert!(coord < Scalar::TAU);
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use fj_math::Aabb;
+
+use crate::objects::Assembly;
+
+use super::BoundingVolume;
+
+impl BoundingVolume<3> for Assembly {
+    fn aabb(&self) -> Option<Aabb<3>> {
+        self.instances()
+            .iter()
+            .filter_map(|instance| {
+                let aabb = instance.solid().aabb()?;
+                let transform = instance.transform();
+
+                // Transforming only `min` and `max` would result in a wrong
+                // AABB, if the instance is rotated.
+                Aabb::<3>::from_point_slice(
+                    &transform.transform_points(&aabb.vertices()),
+                )
+            })
+            .reduce(|a, b| a.merged(&b))
+    }
+}
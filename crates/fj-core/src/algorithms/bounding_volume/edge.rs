@@ -1,29 +1,153 @@
-use fj_math::{Aabb, Vector};
+use fj_math::{Aabb, Scalar, Vector};
 
-use crate::{geometry::SurfacePath, objects::HalfEdge};
+use crate::{
+    geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::HalfEdge,
+};
+
+/// The number of samples used to compute the AABB of spirals and involutes
+const NUM_SAMPLES: u64 = 64;
 
 impl super::BoundingVolume<2> for HalfEdge {
     fn aabb(&self) -> Option<Aabb<2>> {
-        match self.path() {
-            SurfacePath::Circle(circle) => {
-                // Just calculate the AABB of the whole circle. This is not the
-                // most precise, but it should do for now.
-
-                let center_to_min_max =
-                    Vector::from([circle.radius(), circle.radius()]);
-
-                Some(Aabb {
-                    min: circle.center() - center_to_min_max,
-                    max: circle.center() + center_to_min_max,
-                })
+        let path = self.path();
+        let range = self.boundary().inner.map(|point| point.t);
+
+        let parameters = match path {
+            SurfacePath::Circle(circle) => sinusoid_extremes(
+                circle.a(),
+                circle.b(),
+                Vector::from([0., 0.]),
+                range,
+            ),
+            SurfacePath::Line(_) => Vec::new(),
+            SurfacePath::Spiral(_) | SurfacePath::Involute(_) => {
+                // There's no simple closed form for the extent of these
+                // curves, so we sample them. This is not precise, but it
+                // should do for now.
+                samples(range)
+            }
+        };
+
+        let points = range
+            .into_iter()
+            .chain(parameters)
+            .map(|t| path.point_from_path_coords([t]));
+
+        Some(Aabb::<2>::from_points(points))
+    }
+}
+
+/// Compute the AABB of a half-edge in 3D, given the surface it is on
+///
+/// The AABB is exact, except for the combinations of surface and path that
+/// are sampled.
+pub(super) fn aabb_on_surface(
+    half_edge: &HalfEdge,
+    surface: &SurfaceGeometry,
+) -> Aabb<3> {
+    let path = half_edge.path();
+    let range = half_edge.boundary().inner.map(|point| point.t);
+
+    // Circles and ellipses have the same form, as far as their extremes are
+    // concerned.
+    let sinusoid = match surface.u {
+        GlobalPath::Circle(circle) => Some([circle.a(), circle.b()]),
+        GlobalPath::Ellipse(ellipse) => Some([ellipse.a(), ellipse.b()]),
+        _ => None,
+    };
+
+    let parameters = match (surface.u, sinusoid, path) {
+        (GlobalPath::Line(_), _, SurfacePath::Line(_)) => Vec::new(),
+        (GlobalPath::Line(_), _, SurfacePath::Circle(circle)) => {
+            // On a plane, the circle is still a circle in 3D.
+            let [a, b] = [circle.a(), circle.b()]
+                .map(|vector| surface.vector_from_surface_coords(vector));
+            sinusoid_extremes(a, b, Vector::from([0., 0., 0.]), range)
+        }
+        (_, Some([a, b]), SurfacePath::Line(line)) => {
+            let origin = line.origin();
+            let direction = line.direction();
+
+            if direction.u == Scalar::ZERO {
+                // The line runs along the v-axis, which is straight.
+                Vec::new()
+            } else {
+                // Along the line, u and v change at a constant ratio. That
+                // makes the line a helix (or a section of the circle or
+                // ellipse, if v doesn't change at all).
+                let k = surface.v * (direction.v / direction.u);
+                let range_u = range.map(|t| origin.u + direction.u * t);
+
+                sinusoid_extremes(a, b, k, range_u)
+                    .into_iter()
+                    .map(|u| (u - origin.u) / direction.u)
+                    .collect()
             }
-            SurfacePath::Line(_) => {
-                let points = self.boundary().inner.map(|point_curve| {
-                    self.path().point_from_path_coords(point_curve)
-                });
+        }
+        _ => {
+            // No closed form for the remaining combinations is implemented
+            // yet, so sample them, like the spirals and involutes above.
+            samples(range)
+        }
+    };
+
+    let points = range.into_iter().chain(parameters).map(|t| {
+        surface.point_from_surface_coords(path.point_from_path_coords([t]))
+    });
+
+    Aabb::<3>::from_points(points)
+}
+
+/// Find the parameters at which a sinusoidal curve reaches its extremes
+///
+/// Each component of the curve has the form `a cos(s) + b sin(s) + k s`. The
+/// parameters of the extremes of all components within `range` are returned.
+fn sinusoid_extremes<const D: usize>(
+    a: Vector<D>,
+    b: Vector<D>,
+    k: Vector<D>,
+    range: [Scalar; 2],
+) -> Vec<Scalar> {
+    let [min, max] = if range[0] <= range[1] {
+        range
+    } else {
+        [range[1], range[0]]
+    };
+
+    let mut parameters = Vec::new();
 
-                Some(Aabb::<2>::from_points(points))
+    for ((a, b), k) in
+        a.components.into_iter().zip(b.components).zip(k.components)
+    {
+        // The derivative is `-a sin(s) + b cos(s) + k`. It is zero, where
+        // `r sin(s - phi) = k`.
+        let r = (a * a + b * b).sqrt();
+        if r == Scalar::ZERO || k.abs() > r {
+            continue;
+        }
+
+        let phi = b.atan2(a);
+        let alpha = Scalar::from_f64((k / r).into_f64().asin());
+
+        for s in [phi + alpha, phi + Scalar::PI - alpha] {
+            // The extremes repeat with every full turn.
+            let mut s = s + Scalar::TAU * ((min - s) / Scalar::TAU).ceil();
+            while s <= max {
+                parameters.push(s);
+                s += Scalar::TAU;
             }
         }
     }
+
+    parameters
+}
+
+fn samples([start, end]: [Scalar; 2]) -> Vec<Scalar> {
+    (1..NUM_SAMPLES)
+        .map(|i| {
+            let t = Scalar::from_u64(i) / Scalar::from_u64(NUM_SAMPLES);
+            start + (end - start) * t
+        })
+        .collect()
 }
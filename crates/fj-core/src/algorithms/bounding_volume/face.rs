@@ -1,30 +1,118 @@
 use fj_math::Aabb;
 
-use crate::{geometry::GlobalPath, objects::Face};
+use crate::objects::Face;
+
+use super::edge::aabb_on_surface;
 
 impl super::BoundingVolume<3> for Face {
     fn aabb(&self) -> Option<Aabb<3>> {
-        self.region().exterior().aabb().map(|aabb2| {
-            let surface = self.surface().geometry();
-
-            match surface.u {
-                GlobalPath::Circle(circle) => {
-                    // This is not the most precise way to calculate the AABB,
-                    // doing it for the whole circle, but it should do.
-
-                    let aabb_bottom = circle.aabb();
-                    let aabb_top = Aabb {
-                        min: aabb_bottom.min + surface.v,
-                        max: aabb_bottom.max + surface.v,
-                    };
-
-                    aabb_bottom.merged(&aabb_top)
-                }
-                GlobalPath::Line(_) => Aabb {
-                    min: surface.point_from_surface_coords(aabb2.min),
-                    max: surface.point_from_surface_coords(aabb2.max),
-                },
-            }
-        })
+        let surface = self.surface().geometry();
+
+        // All surfaces are straight along their v-axis. This means a face can
+        // only reach its extremes on its boundary, and since all holes are
+        // within the exterior, the exterior is enough.
+        self.region()
+            .exterior()
+            .half_edges()
+            .iter()
+            .map(|half_edge| aabb_on_surface(half_edge, &surface))
+            .reduce(|a, b| a.merged(&b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_4;
+
+    use fj_math::{Aabb, Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::{
+            bounding_volume::BoundingVolume, transform::TransformObject,
+        },
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    #[test]
+    fn aabb_of_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let cylinder =
+            Solid::cylinder(1., 2., &mut services).insert(&mut services);
+
+        let aabb = cylinder.aabb().expect("Cylinder has an AABB");
+        assert_close(aabb, [-1., -1., 0.], [1., 1., 2.]);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn aabb_of_rotated_box() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let cube = Solid::box_from_dims([1., 1., 1.], &mut services)
+            .rotate(Vector::unit_z() * FRAC_PI_4, &mut services)
+            .insert(&mut services);
+
+        let aabb = cube.aabb().expect("Box has an AABB");
+        let half_diagonal = 0.5_f64.sqrt();
+        assert_close(
+            aabb,
+            [-half_diagonal, -half_diagonal, 0.],
+            [half_diagonal, half_diagonal, 1.],
+        );
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn aabb_of_cone() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let cone = Solid::cone(1., 2., 8, &mut services).insert(&mut services);
+
+        let aabb = cone.aabb().expect("Cone has an AABB");
+        assert_close(aabb, [-1., -1., 0.], [1., 1., 2.]);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn aabb_of_sphere() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let sphere = Solid::sphere(1., 8, &mut services).insert(&mut services);
+
+        let aabb = sphere.aabb().expect("Sphere has an AABB");
+        assert_close(aabb, [-1., -1., -1.], [1., 1., 1.]);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn aabb_of_torus() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let torus =
+            Solid::torus(2., 0.5, 8, &mut services).insert(&mut services);
+
+        let aabb = torus.aabb().expect("Torus has an AABB");
+        assert_close(aabb, [-2.5, -2.5, -0.5], [2.5, 2.5, 0.5]);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    fn assert_close(aabb: Aabb<3>, min: [f64; 3], max: [f64; 3]) {
+        for (actual, expected) in [(aabb.min, min), (aabb.max, max)] {
+            let distance = actual.distance_to(&Point::from(expected));
+            assert!(distance < Scalar::from(1e-9), "{aabb:?}");
+        }
     }
 }
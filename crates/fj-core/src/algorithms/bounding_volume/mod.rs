@@ -1,13 +1,17 @@
 //! Compute a bounding volume for an object
 
+mod assembly;
 mod cycle;
 mod edge;
 mod face;
+mod oriented;
 mod shell;
 mod solid;
 
 use fj_math::Aabb;
 
+pub use self::oriented::OrientedBoundingVolume;
+
 /// Compute a bounding volume for an object
 pub trait BoundingVolume<const D: usize> {
     /// Compute an axis-aligned bounding box (AABB)
@@ -0,0 +1,104 @@
+use fj_math::{Obb, Point};
+
+use crate::{
+    algorithms::approx::{Approx, Tolerance},
+    objects::{Face, Shell, Solid},
+};
+
+/// Compute an oriented bounding box for an object
+///
+/// Unlike [`BoundingVolume`], this works on an approximation of the object,
+/// as the orientation of the box depends on the shape of the object as a
+/// whole.
+///
+/// [`BoundingVolume`]: super::BoundingVolume
+pub trait OrientedBoundingVolume {
+    /// Compute an oriented bounding box (OBB)
+    ///
+    /// The OBB is computed from the approximated boundaries of the object's
+    /// faces. Curved parts of the object might extend past the OBB by up to
+    /// `tolerance`.
+    ///
+    /// The axes of the OBB are sorted by decreasing extent. This makes it
+    /// possible to read the required stock size directly from the OBB.
+    ///
+    /// Return `None`, if no OBB can be computed (if the object is empty).
+    fn obb(&self, tolerance: impl Into<Tolerance>) -> Option<Obb>;
+}
+
+impl OrientedBoundingVolume for Face {
+    fn obb(&self, tolerance: impl Into<Tolerance>) -> Option<Obb> {
+        Obb::from_points(boundary_points(self, tolerance.into()))
+    }
+}
+
+impl OrientedBoundingVolume for Shell {
+    fn obb(&self, tolerance: impl Into<Tolerance>) -> Option<Obb> {
+        let tolerance = tolerance.into();
+        Obb::from_points(
+            self.faces()
+                .iter()
+                .flat_map(|face| boundary_points(face, tolerance)),
+        )
+    }
+}
+
+impl OrientedBoundingVolume for Solid {
+    fn obb(&self, tolerance: impl Into<Tolerance>) -> Option<Obb> {
+        let tolerance = tolerance.into();
+        Obb::from_points(
+            self.shells()
+                .iter()
+                .flat_map(|shell| shell.faces())
+                .flat_map(|face| boundary_points(face, tolerance)),
+        )
+    }
+}
+
+fn boundary_points(face: &Face, tolerance: Tolerance) -> Vec<Point<3>> {
+    // All surfaces are straight along their v-axis, so the extremes of a face
+    // in any direction are on its exterior boundary.
+    face.approx(tolerance)
+        .exterior
+        .points()
+        .into_iter()
+        .map(|point| point.global_form)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_4;
+
+    use fj_math::{Scalar, Transform, Vector};
+
+    use crate::{
+        algorithms::{approx::Tolerance, transform::TransformObject},
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    use super::OrientedBoundingVolume;
+
+    #[test]
+    fn obb_of_rotated_box() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let transform = Transform::rotation(Vector::unit_x() * FRAC_PI_4)
+            * Transform::rotation(Vector::unit_z() * FRAC_PI_4);
+        let solid = Solid::box_from_dims([3., 2., 1.], &mut services)
+            .transform(&transform, &mut services)
+            .insert(&mut services);
+
+        let obb = solid
+            .obb(Tolerance::from_scalar(0.001)?)
+            .expect("Box has an OBB");
+        for (size, expected) in obb.size().into_iter().zip([3., 2., 1.]) {
+            assert!((size - Scalar::from(expected)).abs() < Scalar::from(1e-9));
+        }
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+//! Classification of points and faces relative to a solid
+//!
+//! Boolean operations need to decide which parts of the operands end up in the
+//! result. This is done by classifying them as inside of, outside of, or on the
+//! boundary of the other operand.
+//!
+//! The classification works on the triangle mesh of a solid, which makes it
+//! work for any shape that can be triangulated, at the cost of only being as
+//! accurate as the triangulation. Until boolean operations are available in
+//! the kernel, this can be used to prototype them.
+
+use fj_interop::mesh::Mesh;
+use fj_math::{Point, Scalar, Triangle, Vector};
+
+use crate::objects::Face;
+
+use super::{
+    approx::{Approx, Tolerance},
+    triangulate::Triangulate,
+};
+
+/// The classification of a point or face, relative to a solid
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Classification {
+    /// The point or face is inside of the solid
+    Inside,
+
+    /// The point or face is outside of the solid
+    Outside,
+
+    /// The point or face is on the boundary of the solid
+    OnBoundary,
+}
+
+/// Classifies points and faces relative to a solid
+///
+/// Expects the triangle mesh of a closed solid, as produced by
+/// [`Triangulate`].
+#[derive(Clone, Debug)]
+pub struct Classifier {
+    triangles: Vec<Triangle<3>>,
+}
+
+impl Classifier {
+    /// Create a classifier from the triangle mesh of a solid
+    pub fn new(mesh: &Mesh<Point<3>>) -> Self {
+        let triangles =
+            mesh.triangles().map(|triangle| triangle.inner).collect();
+        Self { triangles }
+    }
+
+    /// Compute the signed distance from a point to the solid
+    ///
+    /// The distance is negative inside of the solid.
+    pub fn signed_distance(&self, point: impl Into<Point<3>>) -> Scalar {
+        let point = point.into();
+
+        let distance = self
+            .triangles
+            .iter()
+            .map(|triangle| triangle.closest_point(point).distance_to(&point))
+            .min()
+            .unwrap_or(Scalar::MAX);
+
+        if self.is_inside(point) {
+            -distance
+        } else {
+            distance
+        }
+    }
+
+    /// Classify a point
+    ///
+    /// Points that are closer to the boundary than `tolerance` are classified
+    /// as being on the boundary.
+    pub fn classify_point(
+        &self,
+        point: impl Into<Point<3>>,
+        tolerance: impl Into<Tolerance>,
+    ) -> Classification {
+        let distance = self.signed_distance(point);
+
+        if distance.abs() <= tolerance.into().inner() {
+            Classification::OnBoundary
+        } else if distance < Scalar::ZERO {
+            Classification::Inside
+        } else {
+            Classification::Outside
+        }
+    }
+
+    /// Classify a face
+    ///
+    /// Samples points in the interior of the face, and classifies them. If all
+    /// of them are on the boundary of the solid, so is the face. Otherwise, the
+    /// face is classified according to the majority of the remaining samples.
+    ///
+    /// This assumes that the face doesn't cross the boundary of the solid,
+    /// which is the case for the fragments that boolean operations work with,
+    /// once the faces of their operands have been split along their
+    /// intersections.
+    pub fn classify_face(
+        &self,
+        face: &Face,
+        tolerance: impl Into<Tolerance>,
+    ) -> Classification {
+        let tolerance = tolerance.into();
+
+        let mut num_inside = 0;
+        let mut num_outside = 0;
+
+        for triangle in face.approx(tolerance).triangulate().triangles() {
+            let [a, b, c] = triangle.inner.points();
+            let centroid = Point {
+                coords: (a.coords + b.coords + c.coords) / 3.,
+            };
+
+            match self.classify_point(centroid, tolerance) {
+                Classification::Inside => num_inside += 1,
+                Classification::Outside => num_outside += 1,
+                Classification::OnBoundary => {}
+            }
+        }
+
+        if num_inside == 0 && num_outside == 0 {
+            Classification::OnBoundary
+        } else if num_inside > num_outside {
+            Classification::Inside
+        } else {
+            Classification::Outside
+        }
+    }
+
+    fn is_inside(&self, point: Point<3>) -> bool {
+        // Count the crossings of a ray with the mesh. The direction of the ray
+        // is chosen to make it unlikely that it hits an edge or vertex of the
+        // mesh exactly.
+        //
+        // A crossing right at the origin of the ray is reported for triangles
+        // whose plane contains the point, even if the triangle itself doesn't.
+        // Those are ignored. Points that are actually on a triangle are on the
+        // boundary, and it doesn't matter which side they're counted on.
+        let direction = Vector::from([1., 0.414_213_562, 0.271_828_182]);
+        let num_crossings = self
+            .triangles
+            .iter()
+            .filter_map(|triangle| {
+                triangle.cast_local_ray(point, direction, f64::MAX, true)
+            })
+            .filter(|&distance| distance > Scalar::ZERO)
+            .count();
+
+        num_crossings % 2 == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::{approx::Tolerance, triangulate::Triangulate},
+        objects::{Face, Solid, Surface},
+        operations::{
+            build::{BuildFace, BuildSurface},
+            insert::Insert,
+            primitives::BuildPrimitive,
+        },
+        services::Services,
+    };
+
+    use super::{Classification, Classifier};
+
+    #[test]
+    fn classify() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = Solid::box_from_dims([2., 2., 2.], &mut services)
+            .insert(&mut services);
+        let tolerance = Tolerance::from_scalar(Scalar::from(0.001))?;
+
+        let classifier = Classifier::new(&(&*solid, tolerance).triangulate());
+
+        assert_eq!(
+            classifier.classify_point([0., 0., 1.], tolerance),
+            Classification::Inside
+        );
+        assert_eq!(
+            classifier.classify_point([0., 0., 3.], tolerance),
+            Classification::Outside
+        );
+        assert_eq!(
+            classifier.classify_point([1., 0., 1.], tolerance),
+            Classification::OnBoundary
+        );
+        assert_eq!(
+            classifier.classify_point([-2., -2., 2.], tolerance),
+            Classification::Outside
+        );
+        let distance = classifier.signed_distance([0., 0., 1.5]);
+        assert!((distance + 0.5).abs() < Scalar::from(1e-12));
+
+        let face_at = |z: f64, services: &mut Services| {
+            let (surface, points) = Surface::plane_from_points([
+                [-0.5, -0.5, z],
+                [0.5, -0.5, z],
+                [0., 0.5, z],
+            ]);
+            Face::polygon(surface.insert(services), points, services)
+        };
+
+        let inside = face_at(1., &mut services);
+        let outside = face_at(3., &mut services);
+        let on_boundary = face_at(2., &mut services);
+
+        assert_eq!(
+            classifier.classify_face(&inside, tolerance),
+            Classification::Inside
+        );
+        assert_eq!(
+            classifier.classify_face(&outside, tolerance),
+            Classification::Outside
+        );
+        assert_eq!(
+            classifier.classify_face(&on_boundary, tolerance),
+            Classification::OnBoundary
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,583 @@
+//! Geometric constraints for 2D sketches
+//!
+//! Instead of computing the coordinates of a sketch's points by hand, points
+//! can be added to a [`ConstraintSystem`] at approximate positions, together
+//! with [`Constraint`]s that express the design intent (this line is
+//! horizontal, those two points are 10 units apart, ...). The solver then
+//! moves the points, until all constraints are satisfied.
+//!
+//! The solved points can be used to build sketches, for example using
+//! [`BuildRegion::polygon`].
+//!
+//! [`BuildRegion::polygon`]: crate::operations::build::BuildRegion::polygon
+
+use fj_math::{Point, Scalar};
+
+/// A system of 2D points and the constraints between them
+#[derive(Clone, Debug, Default)]
+pub struct ConstraintSystem {
+    points: Vec<Point<2>>,
+    fixed: Vec<bool>,
+    constraints: Vec<Constraint>,
+}
+
+impl ConstraintSystem {
+    /// Create an empty constraint system
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a point that the solver is free to move
+    ///
+    /// The position of the point is used as the initial guess. The solver makes
+    /// the smallest change that satisfies all constraints, so providing a rough
+    /// approximation of the intended shape leads to predictable results.
+    pub fn add_point(&mut self, point: impl Into<Point<2>>) -> PointId {
+        self.push_point(point.into(), false)
+    }
+
+    /// Add a point that the solver must not move
+    pub fn add_fixed_point(&mut self, point: impl Into<Point<2>>) -> PointId {
+        self.push_point(point.into(), true)
+    }
+
+    /// Add a constraint to the system
+    pub fn add_constraint(&mut self, constraint: Constraint) -> &mut Self {
+        for point in constraint.points() {
+            assert!(
+                point.0 < self.points.len(),
+                "Constraint refers to unknown point"
+            );
+        }
+
+        self.constraints.push(constraint);
+        self
+    }
+
+    /// Access the current position of a point
+    pub fn point(&self, id: PointId) -> Point<2> {
+        self.points[id.0]
+    }
+
+    /// Access the current positions of the provided points
+    ///
+    /// This is a convenience method for building sketches from the solved
+    /// points.
+    pub fn points(
+        &self,
+        ids: impl IntoIterator<Item = PointId>,
+    ) -> Vec<Point<2>> {
+        ids.into_iter().map(|id| self.point(id)).collect()
+    }
+
+    /// Access the constraints of the system
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// Move the points of the system, until all constraints are satisfied
+    ///
+    /// Uses a damped Gauss-Newton iteration, which takes the minimum-norm step
+    /// in each iteration. Under-constrained systems are therefore solved with
+    /// the smallest possible change to the initial positions.
+    ///
+    /// If the solver doesn't converge, the points are left at the best
+    /// positions that were found.
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        let variables = self.variables();
+
+        let mut values = self.values(&variables);
+        let mut residuals = self.residuals(&variables, &values);
+        let mut error = norm(&residuals);
+
+        for _ in 0..MAX_ITERATIONS {
+            if max_abs(&residuals) <= TOLERANCE {
+                break;
+            }
+
+            let jacobian = self.jacobian(&variables, &values);
+            let Some(step) = minimum_norm_step(&jacobian, &residuals) else {
+                break;
+            };
+
+            // Only accept steps that improve the solution. If the full step
+            // doesn't, try shorter ones.
+            let mut factor = 1.;
+            let mut improved = false;
+
+            while factor > MIN_STEP_FACTOR {
+                let candidate: Vec<f64> = values
+                    .iter()
+                    .zip(&step)
+                    .map(|(value, step)| value + factor * step)
+                    .collect();
+                let candidate_residuals =
+                    self.residuals(&variables, &candidate);
+                let candidate_error = norm(&candidate_residuals);
+
+                if candidate_error < error {
+                    values = candidate;
+                    residuals = candidate_residuals;
+                    error = candidate_error;
+                    improved = true;
+                    break;
+                }
+
+                factor /= 2.;
+            }
+
+            if !improved {
+                break;
+            }
+        }
+
+        self.apply(&variables, &values);
+
+        let residual = max_abs(&residuals);
+        if residual > TOLERANCE {
+            return Err(SolveError::DidNotConverge {
+                residual: Scalar::from_f64(residual),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn push_point(&mut self, point: Point<2>, fixed: bool) -> PointId {
+        let id = PointId(self.points.len());
+
+        self.points.push(point);
+        self.fixed.push(fixed);
+
+        id
+    }
+
+    /// Return the index of the first variable of each point, if it is free
+    fn variables(&self) -> Vec<Option<usize>> {
+        let mut next = 0;
+
+        self.fixed
+            .iter()
+            .map(|&fixed| {
+                if fixed {
+                    return None;
+                }
+
+                let index = next;
+                next += 2;
+                Some(index)
+            })
+            .collect()
+    }
+
+    fn values(&self, variables: &[Option<usize>]) -> Vec<f64> {
+        variables
+            .iter()
+            .zip(&self.points)
+            .filter(|(variable, _)| variable.is_some())
+            .flat_map(|(_, point)| [point.u.into_f64(), point.v.into_f64()])
+            .collect()
+    }
+
+    fn apply(&mut self, variables: &[Option<usize>], values: &[f64]) {
+        for (point, variable) in self.points.iter_mut().zip(variables) {
+            if let Some(i) = variable {
+                *point = Point::from([values[*i], values[i + 1]]);
+            }
+        }
+    }
+
+    fn residuals(
+        &self,
+        variables: &[Option<usize>],
+        values: &[f64],
+    ) -> Vec<f64> {
+        let position = |id: PointId| match variables[id.0] {
+            Some(i) => [values[i], values[i + 1]],
+            None => {
+                let point = self.points[id.0];
+                [point.u.into_f64(), point.v.into_f64()]
+            }
+        };
+
+        let mut residuals = Vec::new();
+        for constraint in &self.constraints {
+            constraint.residuals(&position, &mut residuals);
+        }
+
+        residuals
+    }
+
+    fn jacobian(
+        &self,
+        variables: &[Option<usize>],
+        values: &[f64],
+    ) -> Vec<Vec<f64>> {
+        let num_residuals = self.residuals(variables, values).len();
+        let mut jacobian = vec![vec![0.; values.len()]; num_residuals];
+
+        let mut values = values.to_vec();
+
+        for j in 0..values.len() {
+            let value = values[j];
+
+            values[j] = value + DIFFERENTIATION_STEP;
+            let forward = self.residuals(variables, &values);
+
+            values[j] = value - DIFFERENTIATION_STEP;
+            let backward = self.residuals(variables, &values);
+
+            values[j] = value;
+
+            for (i, row) in jacobian.iter_mut().enumerate() {
+                row[j] =
+                    (forward[i] - backward[i]) / (2. * DIFFERENTIATION_STEP);
+            }
+        }
+
+        jacobian
+    }
+}
+
+/// Identifies a point within a [`ConstraintSystem`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct PointId(usize);
+
+/// A constraint between points of a [`ConstraintSystem`]
+///
+/// Lines are specified by two points on them. Angles are in radians.
+#[derive(Clone, Copy, Debug)]
+pub enum Constraint {
+    /// The two points are at the same position
+    Coincident(PointId, PointId),
+
+    /// The line between the two points is parallel to the u-axis
+    Horizontal(PointId, PointId),
+
+    /// The line between the two points is parallel to the v-axis
+    Vertical(PointId, PointId),
+
+    /// The two points are at the given distance from each other
+    Distance(PointId, PointId, Scalar),
+
+    /// The angle from the direction of line `a` to that of line `b`
+    ///
+    /// The angle is measured counter-clockwise.
+    Angle {
+        /// The first line
+        a: [PointId; 2],
+
+        /// The second line
+        b: [PointId; 2],
+
+        /// The angle between the lines
+        angle: Scalar,
+    },
+
+    /// The line is tangent to the circle with the given center and radius
+    Tangent {
+        /// The line
+        line: [PointId; 2],
+
+        /// The center of the circle
+        center: PointId,
+
+        /// The radius of the circle
+        radius: Scalar,
+    },
+}
+
+impl Constraint {
+    fn points(&self) -> Vec<PointId> {
+        match *self {
+            Self::Coincident(a, b)
+            | Self::Horizontal(a, b)
+            | Self::Vertical(a, b)
+            | Self::Distance(a, b, _) => vec![a, b],
+            Self::Angle { a, b, .. } => vec![a[0], a[1], b[0], b[1]],
+            Self::Tangent { line, center, .. } => {
+                vec![line[0], line[1], center]
+            }
+        }
+    }
+
+    fn residuals(
+        &self,
+        position: &impl Fn(PointId) -> [f64; 2],
+        residuals: &mut Vec<f64>,
+    ) {
+        let vector = |[a, b]: [PointId; 2]| {
+            let [ax, ay] = position(a);
+            let [bx, by] = position(b);
+            [bx - ax, by - ay]
+        };
+
+        match *self {
+            Self::Coincident(a, b) => {
+                residuals.extend(vector([a, b]));
+            }
+            Self::Horizontal(a, b) => {
+                residuals.push(vector([a, b])[1]);
+            }
+            Self::Vertical(a, b) => {
+                residuals.push(vector([a, b])[0]);
+            }
+            Self::Distance(a, b, distance) => {
+                let [x, y] = vector([a, b]);
+                residuals.push(x.hypot(y) - distance.into_f64());
+            }
+            Self::Angle { a, b, angle } => {
+                let [ux, uy] = vector(a);
+                let [vx, vy] = vector(b);
+
+                let length = ux.hypot(uy) * vx.hypot(vy);
+                if length == 0. {
+                    residuals.push(0.);
+                    return;
+                }
+
+                // This is the sine of the difference between the actual and
+                // the desired angle.
+                let (sin, cos) = angle.into_f64().sin_cos();
+                let cross = ux * vy - uy * vx;
+                let dot = ux * vx + uy * vy;
+                residuals.push((cross * cos - dot * sin) / length);
+            }
+            Self::Tangent {
+                line,
+                center,
+                radius,
+            } => {
+                let [dx, dy] = vector(line);
+                let [cx, cy] = vector([line[0], center]);
+
+                let length = dx.hypot(dy);
+                if length == 0. {
+                    residuals.push(0.);
+                    return;
+                }
+
+                let distance = (dx * cy - dy * cx).abs() / length;
+                residuals.push(distance - radius.into_f64());
+            }
+        }
+    }
+}
+
+/// Error solving a [`ConstraintSystem`]
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum SolveError {
+    /// The solver failed to satisfy all constraints
+    ///
+    /// This can be caused by contradictory constraints, or by an initial guess
+    /// that is too far from a solution.
+    #[error(
+        "Failed to satisfy constraints (largest remaining error: {residual})"
+    )]
+    DidNotConverge {
+        /// The largest remaining error of any constraint
+        residual: Scalar,
+    },
+}
+
+/// Compute the minimum-norm step that solves the linearized system
+///
+/// Solves `(J * J^T + λ * I) * y = -r` and returns `J^T * y`. The small damping
+/// term makes this robust against redundant constraints.
+fn minimum_norm_step(
+    jacobian: &[Vec<f64>],
+    residuals: &[f64],
+) -> Option<Vec<f64>> {
+    let num_residuals = jacobian.len();
+    let num_variables = jacobian.first().map(Vec::len).unwrap_or(0);
+
+    if num_variables == 0 {
+        return None;
+    }
+
+    let mut matrix = vec![vec![0.; num_residuals]; num_residuals];
+    for (i, row_i) in jacobian.iter().enumerate() {
+        for (j, row_j) in jacobian.iter().enumerate() {
+            matrix[i][j] =
+                row_i.iter().zip(row_j).map(|(a, b)| a * b).sum::<f64>();
+        }
+        matrix[i][i] += DAMPING;
+    }
+
+    let rhs = residuals.iter().map(|r| -r).collect();
+    let y = solve_linear(matrix, rhs)?;
+
+    let step = (0..num_variables)
+        .map(|j| {
+            jacobian
+                .iter()
+                .zip(&y)
+                .map(|(row, y)| row[j] * y)
+                .sum::<f64>()
+        })
+        .collect();
+
+    Some(step)
+}
+
+/// Solve a linear system using Gaussian elimination with partial pivoting
+fn solve_linear(
+    mut matrix: Vec<Vec<f64>>,
+    mut rhs: Vec<f64>,
+) -> Option<Vec<f64>> {
+    let n = rhs.len();
+
+    for column in 0..n {
+        let pivot = (column..n).max_by(|&a, &b| {
+            matrix[a][column].abs().total_cmp(&matrix[b][column].abs())
+        })?;
+        if matrix[pivot][column].abs() < f64::EPSILON {
+            return None;
+        }
+
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+
+        let pivot_row = matrix[column].clone();
+        let pivot_rhs = rhs[column];
+
+        for (row, value) in
+            matrix.iter_mut().zip(rhs.iter_mut()).skip(column + 1)
+        {
+            let factor = row[column] / pivot_row[column];
+            for (a, b) in row.iter_mut().zip(&pivot_row).skip(column) {
+                *a -= factor * b;
+            }
+            *value -= factor * pivot_rhs;
+        }
+    }
+
+    let mut solution = vec![0.; n];
+    for row in (0..n).rev() {
+        let sum = (row + 1..n)
+            .map(|k| matrix[row][k] * solution[k])
+            .sum::<f64>();
+        solution[row] = (rhs[row] - sum) / matrix[row][row];
+    }
+
+    Some(solution)
+}
+
+fn norm(values: &[f64]) -> f64 {
+    values.iter().map(|value| value * value).sum::<f64>().sqrt()
+}
+
+fn max_abs(values: &[f64]) -> f64 {
+    values.iter().fold(0., |max, value| value.abs().max(max))
+}
+
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-10;
+const DAMPING: f64 = 1e-12;
+const DIFFERENTIATION_STEP: f64 = 1e-7;
+const MIN_STEP_FACTOR: f64 = 1e-6;
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use super::{Constraint, ConstraintSystem, SolveError};
+
+    #[test]
+    fn rectangle() -> Result<(), SolveError> {
+        let mut system = ConstraintSystem::new();
+
+        let a = system.add_fixed_point([0., 0.]);
+        let b = system.add_point([2.1, 0.2]);
+        let c = system.add_point([1.8, 0.9]);
+        let d = system.add_point([-0.1, 1.2]);
+
+        system
+            .add_constraint(Constraint::Horizontal(a, b))
+            .add_constraint(Constraint::Vertical(b, c))
+            .add_constraint(Constraint::Horizontal(c, d))
+            .add_constraint(Constraint::Vertical(d, a))
+            .add_constraint(Constraint::Distance(a, b, Scalar::from(2.)))
+            .add_constraint(Constraint::Distance(b, c, Scalar::from(1.)));
+        system.solve()?;
+
+        let expected = [[0., 0.], [2., 0.], [2., 1.], [0., 1.]];
+        for (point, expected) in
+            system.points([a, b, c, d]).into_iter().zip(expected)
+        {
+            assert!(
+                point.distance_to(&Point::from(expected)) < Scalar::from(1e-8)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn angle_and_coincident() -> Result<(), SolveError> {
+        let mut system = ConstraintSystem::new();
+
+        let a = system.add_fixed_point([0., 0.]);
+        let b = system.add_fixed_point([1., 0.]);
+        let c = system.add_point([0.1, 0.1]);
+        let d = system.add_point([0.5, 0.9]);
+
+        system
+            .add_constraint(Constraint::Coincident(a, c))
+            .add_constraint(Constraint::Distance(c, d, Scalar::from(1.)))
+            .add_constraint(Constraint::Angle {
+                a: [a, b],
+                b: [c, d],
+                angle: Scalar::PI / 3.,
+            });
+        system.solve()?;
+
+        let expected = Point::from([0.5, 3_f64.sqrt() / 2.]);
+        assert!(
+            system.point(c).distance_to(&Point::origin()) < Scalar::from(1e-8)
+        );
+        assert!(system.point(d).distance_to(&expected) < Scalar::from(1e-8));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tangent() -> Result<(), SolveError> {
+        let mut system = ConstraintSystem::new();
+
+        let center = system.add_fixed_point([0., 0.]);
+        let a = system.add_point([-1., 1.5]);
+        let b = system.add_point([1., 1.5]);
+
+        system
+            .add_constraint(Constraint::Horizontal(a, b))
+            .add_constraint(Constraint::Tangent {
+                line: [a, b],
+                center,
+                radius: Scalar::from(1.),
+            });
+        system.solve()?;
+
+        assert!((system.point(a).v - Scalar::ONE).abs() < Scalar::from(1e-8));
+        assert!((system.point(b).v - Scalar::ONE).abs() < Scalar::from(1e-8));
+
+        Ok(())
+    }
+
+    #[test]
+    fn contradictory_constraints() {
+        let mut system = ConstraintSystem::new();
+
+        let a = system.add_fixed_point([0., 0.]);
+        let b = system.add_point([1., 0.]);
+
+        system
+            .add_constraint(Constraint::Distance(a, b, Scalar::from(1.)))
+            .add_constraint(Constraint::Distance(a, b, Scalar::from(2.)));
+
+        assert!(matches!(
+            system.solve(),
+            Err(SolveError::DidNotConverge { .. })
+        ));
+    }
+}
@@ -0,0 +1,698 @@
+//! Export of intermediate objects, for debugging
+//!
+//! When an operation fails, the objects it failed on are often hard to
+//! visualize. [`DebugDump`] collects approximations of individual objects, and
+//! writes them to OBJ or PLY files, or converts them into a [`Model`] that can
+//! be displayed as an overlay in the viewer.
+//!
+//! Objects are keyed by their kind and their [`ObjectId`], so they can be
+//! matched to the objects mentioned in validation errors or log output.
+//!
+//! Bugs in the triangulation of trimmed surfaces are best investigated in the
+//! parameter space of the face in question. [`parameter_space`] creates a model
+//! of that, which can be displayed in the viewer.
+//!
+//! [`ObjectId`]: crate::storage::ObjectId
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_interop::{
+    mesh::{Color, Mesh},
+    model::Model,
+};
+use fj_math::{Aabb, Point, PolyChain, Scalar, Unit, Vector};
+use itertools::Itertools;
+
+use crate::{
+    geometry::{CurveBoundary, SurfaceGeometry, SurfacePath},
+    objects::{Assembly, Cycle, Face, HalfEdge, Surface},
+    storage::Handle,
+};
+
+use super::{
+    approx::{cycle::CycleApprox, face::FaceApprox, Approx, Tolerance},
+    triangulate::{triangulate_face, MeshQuality, Triangulate},
+};
+
+/// A collection of objects, approximated for debugging
+///
+/// See [module documentation] for more information.
+///
+/// [module documentation]: self
+#[derive(Clone, Debug, Default)]
+pub struct DebugDump {
+    entries: BTreeMap<String, DebugEntry>,
+}
+
+impl DebugDump {
+    /// Create an empty `DebugDump`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a half-edge, approximated on the provided surface
+    pub fn add_half_edge(
+        &mut self,
+        half_edge: &Handle<HalfEdge>,
+        surface: &Surface,
+        tolerance: impl Into<Tolerance>,
+    ) -> &mut Self {
+        let approx = (&**half_edge, surface).approx(tolerance);
+        let end = surface.geometry().point_from_surface_coords(
+            half_edge
+                .path()
+                .point_from_path_coords(half_edge.boundary().inner[1]),
+        );
+
+        let mut points = approx
+            .points
+            .iter()
+            .map(|point| point.global_form)
+            .collect::<Vec<_>>();
+        points.push(end);
+
+        self.entry(key("half-edge", half_edge))
+            .polylines
+            .push(points);
+        self
+    }
+
+    /// Add a cycle, approximated on the provided surface
+    pub fn add_cycle(
+        &mut self,
+        cycle: &Handle<Cycle>,
+        surface: &Surface,
+        tolerance: impl Into<Tolerance>,
+    ) -> &mut Self {
+        let approx = (&**cycle, surface).approx(tolerance);
+        self.entry(key("cycle", cycle))
+            .polylines
+            .push(cycle_points(&approx));
+        self
+    }
+
+    /// Add a face, as its boundary and its triangulation
+    pub fn add_face(
+        &mut self,
+        face: &Handle<Face>,
+        tolerance: impl Into<Tolerance>,
+    ) -> &mut Self {
+        let approx = (&**face).approx(tolerance);
+
+        let mut polylines = vec![cycle_points(&approx.exterior)];
+        polylines.extend(approx.interiors.iter().map(cycle_points));
+
+        let triangles = approx
+            .triangulate()
+            .triangles()
+            .map(|triangle| triangle.inner.points())
+            .collect::<Vec<_>>();
+
+        let entry = self.entry(key("face", face));
+        entry.polylines.extend(polylines);
+        entry.triangles.extend(triangles);
+        self
+    }
+
+    /// Add a path on a surface, like the result of an intersection
+    ///
+    /// Paths are not objects, so the caller needs to provide a name.
+    pub fn add_surface_path(
+        &mut self,
+        name: impl Into<String>,
+        path: &SurfacePath,
+        surface: &SurfaceGeometry,
+        boundary: impl Into<CurveBoundary<Point<1>>>,
+        tolerance: impl Into<Tolerance>,
+    ) -> &mut Self {
+        let boundary = boundary.into();
+        let [start, end] = boundary.inner;
+
+        let mut points = vec![start];
+        points.extend(
+            (path, boundary)
+                .approx(tolerance)
+                .into_iter()
+                .map(|(point_curve, _)| point_curve),
+        );
+        points.push(end);
+
+        let points = points
+            .into_iter()
+            .map(|point| {
+                surface.point_from_surface_coords(
+                    path.point_from_path_coords(point),
+                )
+            })
+            .collect();
+
+        self.entry(name.into()).polylines.push(points);
+        self
+    }
+
+    /// Add a polyline with the provided name
+    pub fn add_polyline(
+        &mut self,
+        name: impl Into<String>,
+        points: impl IntoIterator<Item = impl Into<Point<3>>>,
+    ) -> &mut Self {
+        let points = points.into_iter().map(Into::into).collect();
+        self.entry(name.into()).polylines.push(points);
+        self
+    }
+
+    /// Add a triangle mesh with the provided name
+    pub fn add_mesh(
+        &mut self,
+        name: impl Into<String>,
+        mesh: &Mesh<Point<3>>,
+    ) -> &mut Self {
+        let triangles =
+            mesh.triangles().map(|triangle| triangle.inner.points());
+        self.entry(name.into()).triangles.extend(triangles);
+        self
+    }
+
+    /// Access the entry with the provided name, if it exists
+    ///
+    /// Objects are named after their kind and their ID, for example
+    /// `cycle-1f2e3d4c`.
+    pub fn get(&self, name: &str) -> Option<&DebugEntry> {
+        self.entries.get(name)
+    }
+
+    /// Iterate over all entries, ordered by name
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &DebugEntry)> {
+        self.entries
+            .iter()
+            .map(|(name, entry)| (name.as_str(), entry))
+    }
+
+    /// Save the dump to a file
+    ///
+    /// The format is selected using the file extension. Supported are `obj`
+    /// and `ply`.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let is_ply = match extension.as_deref() {
+            Some("obj") => false,
+            Some("ply") => true,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Unsupported debug export format: `{}`",
+                        path.display()
+                    ),
+                ))
+            }
+        };
+
+        let mut file = BufWriter::new(File::create(path)?);
+        if is_ply {
+            self.write_ply(&mut file)?;
+        } else {
+            self.write_obj(&mut file)?;
+        }
+        file.flush()
+    }
+
+    /// Write the dump in the OBJ format
+    ///
+    /// Each entry becomes a separate object, named after the entry.
+    pub fn write_obj(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "# Fornjot debug export")?;
+
+        // OBJ indices are 1-based and global across the whole file.
+        let mut next_index = 1;
+
+        for (name, entry) in &self.entries {
+            writeln!(writer, "o {name}")?;
+
+            for polyline in &entry.polylines {
+                for point in polyline {
+                    write_vertex(&mut writer, "v", point)?;
+                }
+
+                write!(writer, "l")?;
+                for i in 0..polyline.len() {
+                    write!(writer, " {}", next_index + i)?;
+                }
+                writeln!(writer)?;
+
+                next_index += polyline.len();
+            }
+
+            for triangle in &entry.triangles {
+                for point in triangle {
+                    write_vertex(&mut writer, "v", point)?;
+                }
+
+                writeln!(
+                    writer,
+                    "f {} {} {}",
+                    next_index,
+                    next_index + 1,
+                    next_index + 2
+                )?;
+
+                next_index += 3;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the dump in the ASCII PLY format
+    ///
+    /// PLY has no concept of separate objects, so all entries are merged.
+    /// Polylines are written as edges, triangles as faces.
+    pub fn write_ply(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut vertices = Vec::new();
+        let mut edges = Vec::new();
+        let mut faces = Vec::new();
+
+        for entry in self.entries.values() {
+            for polyline in &entry.polylines {
+                let first = vertices.len();
+                vertices.extend(polyline.iter().copied());
+                edges.extend(
+                    (first..vertices.len().saturating_sub(1))
+                        .map(|i| [i, i + 1]),
+                );
+            }
+
+            for triangle in &entry.triangles {
+                let first = vertices.len();
+                vertices.extend(triangle.iter().copied());
+                faces.push([first, first + 1, first + 2]);
+            }
+        }
+
+        writeln!(writer, "ply")?;
+        writeln!(writer, "format ascii 1.0")?;
+        writeln!(writer, "comment Fornjot debug export")?;
+        writeln!(writer, "element vertex {}", vertices.len())?;
+        writeln!(writer, "property double x")?;
+        writeln!(writer, "property double y")?;
+        writeln!(writer, "property double z")?;
+        writeln!(writer, "element face {}", faces.len())?;
+        writeln!(writer, "property list uchar int vertex_indices")?;
+        writeln!(writer, "element edge {}", edges.len())?;
+        writeln!(writer, "property int vertex1")?;
+        writeln!(writer, "property int vertex2")?;
+        writeln!(writer, "end_header")?;
+
+        for point in &vertices {
+            write_vertex(&mut writer, "", point)?;
+        }
+        for [a, b, c] in faces {
+            writeln!(writer, "3 {a} {b} {c}")?;
+        }
+        for [a, b] in edges {
+            writeln!(writer, "{a} {b}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Convert the dump into a model, to display it in the viewer
+    pub fn to_model(&self, unit: Unit) -> Model {
+        let mut mesh = Mesh::new();
+        let mut edges = Vec::new();
+
+        for entry in self.entries.values() {
+            for polyline in &entry.polylines {
+                edges.push(PolyChain::from_points(polyline.iter().copied()));
+            }
+            for &triangle in &entry.triangles {
+                mesh.push_triangle(triangle, Color::default());
+            }
+        }
+
+        let points = self
+            .entries
+            .values()
+            .flat_map(|entry| {
+                entry
+                    .polylines
+                    .iter()
+                    .flatten()
+                    .chain(entry.triangles.iter().flatten())
+                    .copied()
+            })
+            .collect::<Vec<_>>();
+        let aabb = if points.is_empty() {
+            Aabb::default()
+        } else {
+            Aabb::<3>::from_points(points)
+        };
+
+        Model {
+            mesh,
+            edges,
+            aabb,
+            unit,
+        }
+    }
+
+    fn entry(&mut self, name: String) -> &mut DebugEntry {
+        self.entries.entry(name).or_default()
+    }
+}
+
+/// An entry in a [`DebugDump`]
+#[derive(Clone, Debug, Default)]
+pub struct DebugEntry {
+    /// The polylines that make up the entry
+    pub polylines: Vec<Vec<Point<3>>>,
+
+    /// The triangles that make up the entry
+    pub triangles: Vec<[Point<3>; 3]>,
+}
+
+/// Create a model of a face's parameter space
+///
+/// The model shows the face in surface coordinates, with `u` and `v` mapped to
+/// `x` and `y`. It consists of the face's triangulation, and of ribbons along
+/// the approximations of its half-edges, with markers at each approximation
+/// point. The exterior cycle is blue, interior cycles are green.
+///
+/// The ribbons are also available as the edges of the model.
+pub fn parameter_space(face: &FaceApprox, unit: Unit) -> Model {
+    let triangles = triangulate_face(face, MeshQuality::default());
+
+    let mut half_edges = Vec::new();
+    let cycles = [(&face.exterior, EXTERIOR_COLOR)]
+        .into_iter()
+        .chain(face.interiors.iter().map(|cycle| (cycle, INTERIOR_COLOR)));
+    for (cycle, color) in cycles {
+        for (current, next) in cycle.half_edges.iter().circular_tuple_windows()
+        {
+            let mut points = current
+                .points
+                .iter()
+                .map(|point| point.local_form)
+                .collect::<Vec<_>>();
+            if let Some(end) = next.points.first() {
+                points.push(end.local_form);
+            }
+
+            half_edges.push((points, color));
+        }
+    }
+
+    let points = triangles
+        .iter()
+        .flatten()
+        .map(|point| point.point_surface)
+        .chain(half_edges.iter().flat_map(|(points, _)| points.clone()))
+        .map(to_plane)
+        .collect::<Vec<_>>();
+    let aabb = if points.is_empty() {
+        Aabb::default()
+    } else {
+        Aabb::<3>::from_points(points)
+    };
+
+    // The parameter space can have any size, so scale the ribbons to it.
+    let width = aabb
+        .size()
+        .components
+        .into_iter()
+        .fold(Scalar::ZERO, Scalar::max)
+        * RIBBON_WIDTH;
+
+    let mut mesh = Mesh::new();
+
+    let color = face.color.unwrap_or_default();
+    for triangle in triangles {
+        let [a, b, c] = triangle.map(|point| to_plane(point.point_surface));
+
+        // Depending on the handedness of the surface, triangles can be wound
+        // either way. Make them all face the viewer.
+        let triangle = if (b - a).cross(&(c - a)).z < Scalar::ZERO {
+            [a, c, b]
+        } else {
+            [a, b, c]
+        };
+        mesh.push_triangle(triangle, color);
+    }
+
+    let offset = Vector::from([Scalar::ZERO, Scalar::ZERO, width]);
+    for (points, color) in &half_edges {
+        for segment in points.windows(2) {
+            let [a, b] = [segment[0], segment[1]].map(to_plane);
+            if a == b {
+                continue;
+            }
+
+            let direction = (b - a).normalize();
+            let side = Vector::from([-direction.y, direction.x, Scalar::ZERO])
+                * width
+                / 2.;
+
+            let [a0, a1, b0, b1] =
+                [a - side, a + side, b - side, b + side].map(|p| p + offset);
+            mesh.push_triangle([a0, b0, b1], *color);
+            mesh.push_triangle([a0, b1, a1], *color);
+        }
+
+        for &point in points {
+            let point = to_plane(point) + offset * 2.;
+            let [dx, dy] = [[width, Scalar::ZERO], [Scalar::ZERO, width]]
+                .map(|[x, y]| Vector::from([x, y, Scalar::ZERO]));
+
+            let corners = [
+                point - dx - dy,
+                point + dx - dy,
+                point + dx + dy,
+                point - dx + dy,
+            ];
+            mesh.push_triangle(
+                [corners[0], corners[1], corners[2]],
+                MARKER_COLOR,
+            );
+            mesh.push_triangle(
+                [corners[0], corners[2], corners[3]],
+                MARKER_COLOR,
+            );
+        }
+    }
+
+    let edges = half_edges
+        .into_iter()
+        .map(|(points, _)| {
+            PolyChain::from_points(points.into_iter().map(to_plane))
+        })
+        .collect();
+
+    Model {
+        mesh,
+        edges,
+        aabb,
+        unit,
+    }
+}
+
+/// Create models of the parameter spaces of all faces of a shape
+///
+/// See [`parameter_space`].
+pub trait ParameterSpaces {
+    /// Create models of the parameter spaces of all faces
+    ///
+    /// The models are returned in a stable order, so the same face keeps its
+    /// index when the shape is modified in an unrelated way.
+    fn parameter_spaces(self, unit: Unit) -> Vec<Model>;
+}
+
+impl<T> ParameterSpaces for (T, Tolerance)
+where
+    T: Approx,
+    T::Approximation: IntoIterator<Item = FaceApprox>,
+{
+    fn parameter_spaces(self, unit: Unit) -> Vec<Model> {
+        let (approx, tolerance) = self;
+
+        approx
+            .approx(tolerance)
+            .into_iter()
+            .map(|face| parameter_space(&face, unit))
+            .collect()
+    }
+}
+
+impl ParameterSpaces for (&Assembly, Tolerance) {
+    fn parameter_spaces(self, unit: Unit) -> Vec<Model> {
+        let (assembly, tolerance) = self;
+
+        // Parameter spaces are independent of the placement of a solid, so
+        // each solid only needs to show up once.
+        let mut solids = BTreeSet::new();
+        let mut models = Vec::new();
+
+        for instance in assembly.instances() {
+            let solid = instance.solid();
+            if solids.insert(solid.id()) {
+                models.extend((&**solid, tolerance).parameter_spaces(unit));
+            }
+        }
+
+        models
+    }
+}
+
+/// The width of parameter space ribbons, relative to the size of the face
+const RIBBON_WIDTH: f64 = 0.005;
+
+const EXTERIOR_COLOR: Color = Color([0, 0, 255, 255]);
+const INTERIOR_COLOR: Color = Color([0, 160, 0, 255]);
+const MARKER_COLOR: Color = Color([0, 0, 0, 255]);
+
+fn to_plane(point: Point<2>) -> Point<3> {
+    Point::from([point.u, point.v, Scalar::ZERO])
+}
+
+fn key<T>(kind: &str, handle: &Handle<T>) -> String {
+    format!("{kind}-{:x}", handle.id().0)
+}
+
+fn cycle_points(approx: &CycleApprox) -> Vec<Point<3>> {
+    approx
+        .points()
+        .into_iter()
+        .map(|point| point.global_form)
+        .collect()
+}
+
+fn write_vertex(
+    writer: &mut impl Write,
+    prefix: &str,
+    point: &Point<3>,
+) -> io::Result<()> {
+    let [x, y, z] = point.coords.components.map(|s| s.into_f64());
+    if prefix.is_empty() {
+        writeln!(writer, "{x} {y} {z}")
+    } else {
+        writeln!(writer, "{prefix} {x} {y} {z}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Aabb;
+
+    use crate::{
+        algorithms::approx::{Approx, Tolerance},
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    use super::{parameter_space, DebugDump, ParameterSpaces};
+
+    #[test]
+    fn dump_faces() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let solid = Solid::box_from_dims([1., 1., 1.], &mut services)
+            .insert(&mut services);
+        let shell = solid.shells().first();
+
+        let mut dump = DebugDump::new();
+        for face in shell.faces() {
+            dump.add_face(face, tolerance);
+        }
+
+        let face = shell.faces().first();
+        let name = format!("face-{:x}", face.id().0);
+        let entry = dump.get(&name).expect("Face should have been added");
+        assert_eq!(entry.polylines.len(), 1);
+        assert_eq!(entry.polylines[0].len(), 5);
+        assert!(!entry.triangles.is_empty());
+        let num_triangles = dump
+            .entries()
+            .map(|(_, entry)| entry.triangles.len())
+            .sum::<usize>();
+
+        let mut obj = Vec::new();
+        dump.write_obj(&mut obj)?;
+        let obj = String::from_utf8(obj)?;
+        assert_eq!(obj.lines().filter(|l| l.starts_with("o ")).count(), 6);
+        assert_eq!(
+            obj.lines().filter(|l| l.starts_with("f ")).count(),
+            num_triangles
+        );
+
+        let mut ply = Vec::new();
+        dump.write_ply(&mut ply)?;
+        let ply = String::from_utf8(ply)?;
+        assert!(ply.contains(&format!("element face {num_triangles}")));
+        assert!(ply.contains("element edge 24"));
+
+        let model = dump.to_model(fj_math::Unit::Millimeter);
+        assert_eq!(model.edges.len(), 6);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn empty_dump_to_model() {
+        let model = DebugDump::new().to_model(fj_math::Unit::Millimeter);
+        assert!(model.mesh.triangles().next().is_none());
+        assert!(model.edges.is_empty());
+    }
+
+    #[test]
+    fn parameter_spaces() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let solid = Solid::box_from_dims([1., 2., 3.], &mut services)
+            .insert(&mut services);
+
+        let models =
+            (&*solid, tolerance).parameter_spaces(fj_math::Unit::Millimeter);
+        assert_eq!(models.len(), 6);
+
+        for model in models {
+            assert_eq!(model.edges.len(), 4);
+            assert_eq!(model.aabb.size().z, fj_math::Scalar::ZERO);
+        }
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn parameter_space_of_empty_face() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let solid = Solid::box_from_dims([1., 2., 3.], &mut services)
+            .insert(&mut services);
+
+        let mut face = solid.shells().first().faces().first().approx(tolerance);
+        face.exterior.half_edges.clear();
+        face.interiors.clear();
+
+        let model = parameter_space(&face, fj_math::Unit::Millimeter);
+        assert!(model.mesh.triangles().next().is_none());
+        assert_eq!(model.aabb, Aabb::default());
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,602 @@
+use fj_interop::drawing::{Annotation, Drawing};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    geometry::{GlobalPath, SurfacePath},
+    objects::{Face, HalfEdge},
+    storage::Handle,
+};
+
+use super::{strokes_aabb, StandardView};
+
+/// A dimension that can be added to a drawing
+#[derive(Clone, Debug)]
+pub enum Dimension {
+    /// The distance between two points, as it appears in the view
+    Linear {
+        /// The points between which the distance is measured
+        points: [Point<3>; 2],
+
+        /// The direction in which the distance is measured
+        orientation: LinearOrientation,
+    },
+
+    /// The distance between two parallel, planar faces
+    Distance {
+        /// The faces between which the distance is measured
+        faces: [Handle<Face>; 2],
+    },
+
+    /// The diameter of a circular half-edge
+    Diameter {
+        /// The planar face that is bounded by the half-edge
+        face: Handle<Face>,
+
+        /// The half-edge whose diameter is measured
+        half_edge: Handle<HalfEdge>,
+    },
+
+    /// The angle between two planar faces
+    Angle {
+        /// The faces between which the angle is measured
+        faces: [Handle<Face>; 2],
+    },
+}
+
+/// The direction in which a linear dimension is measured
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum LinearOrientation {
+    /// Measure the horizontal distance within the view
+    Horizontal,
+
+    /// Measure the vertical distance within the view
+    Vertical,
+
+    /// Measure the direct distance within the view
+    Aligned,
+}
+
+/// Add dimensions to a drawing
+pub trait Annotate {
+    /// Add a dimension to the provided view of the drawing
+    ///
+    /// Dimensions are only added to views in which they appear at their true
+    /// size. A distance between faces, for example, can't be measured in a
+    /// view that looks along the normal of those faces.
+    fn annotate(
+        &mut self,
+        view: StandardView,
+        dimension: &Dimension,
+    ) -> Result<(), AnnotateError>;
+}
+
+impl Annotate for Drawing {
+    fn annotate(
+        &mut self,
+        view: StandardView,
+        dimension: &Dimension,
+    ) -> Result<(), AnnotateError> {
+        let drawing_view = self
+            .views
+            .iter_mut()
+            .find(|drawing_view| drawing_view.name == view.name())
+            .ok_or(AnnotateError::ViewNotInDrawing(view))?;
+
+        let size = strokes_aabb(&drawing_view.strokes)
+            .map(|aabb| (aabb.max - aabb.min).magnitude())
+            .filter(|size| *size > Scalar::ZERO)
+            .unwrap_or(Scalar::ONE);
+        let frame = Frame {
+            view,
+            offset: drawing_view.offset,
+            size,
+        };
+
+        let annotation = match dimension {
+            Dimension::Linear {
+                points,
+                orientation,
+            } => frame.linear_between_points(*points, *orientation)?,
+            Dimension::Distance { faces } => frame.distance(faces)?,
+            Dimension::Diameter { face, half_edge } => {
+                frame.diameter(face, half_edge)?
+            }
+            Dimension::Angle { faces } => frame.angle(faces)?,
+        };
+
+        drawing_view.annotations.push(annotation);
+
+        Ok(())
+    }
+}
+
+/// An error adding a dimension to a drawing
+///
+/// Returned by [`Annotate::annotate`].
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum AnnotateError {
+    /// The drawing doesn't have the view that the dimension should be added to
+    #[error("Drawing doesn't have a {0:?} view")]
+    ViewNotInDrawing(StandardView),
+
+    /// A face that the dimension refers to is not planar
+    #[error("Face is not planar")]
+    NotPlanar(Handle<Face>),
+
+    /// The half-edge is not a circle that bounds the provided face
+    #[error("Half-edge is not a circle that bounds the provided face")]
+    NotCircular(Handle<HalfEdge>),
+
+    /// The faces of a distance are not parallel
+    #[error("Faces are not parallel")]
+    NotParallel,
+
+    /// The faces of an angle are parallel
+    #[error("Faces are parallel and don't form an angle")]
+    Parallel,
+
+    /// The dimension doesn't appear at its true size in the view
+    #[error("Dimension can't be measured in the {0:?} view")]
+    NotMeasurableInView(StandardView),
+}
+
+/// The geometry of a view, that dimensions are placed in
+struct Frame {
+    view: StandardView,
+    offset: Vector<2>,
+    size: Scalar,
+}
+
+impl Frame {
+    fn linear_between_points(
+        &self,
+        points: [Point<3>; 2],
+        orientation: LinearOrientation,
+    ) -> Result<Annotation, AnnotateError> {
+        let [a, b] = points.map(|point| self.point(point));
+
+        let direction = match orientation {
+            LinearOrientation::Horizontal => Vector::unit_u(),
+            LinearOrientation::Vertical => Vector::unit_v(),
+            LinearOrientation::Aligned => {
+                if (b - a).magnitude() < Scalar::from(EPSILON) {
+                    return Err(AnnotateError::NotMeasurableInView(self.view));
+                }
+                (b - a).normalize()
+            }
+        };
+        let value = (b - a).dot(&direction).abs();
+
+        Ok(self.linear([a, b], direction, format_length(value)))
+    }
+
+    fn distance(
+        &self,
+        [a, b]: &[Handle<Face>; 2],
+    ) -> Result<Annotation, AnnotateError> {
+        let [(origin_a, normal_a), (origin_b, normal_b)] =
+            [plane(a)?, plane(b)?];
+
+        if normal_a.cross(&normal_b).magnitude() > Scalar::from(EPSILON) {
+            return Err(AnnotateError::NotParallel);
+        }
+        self.check_in_view_plane(normal_a)?;
+
+        // Measure from the center of the first face, so the dimension ends up
+        // close to it.
+        let start = centroid(a);
+        let end = start + normal_a * (origin_b - start).dot(&normal_a);
+        let value = (origin_b - origin_a).dot(&normal_a).abs();
+
+        let direction = self.vector(normal_a).normalize();
+        Ok(self.linear(
+            [self.point(start), self.point(end)],
+            direction,
+            format_length(value),
+        ))
+    }
+
+    fn diameter(
+        &self,
+        face: &Handle<Face>,
+        half_edge: &Handle<HalfEdge>,
+    ) -> Result<Annotation, AnnotateError> {
+        let (_, normal) = plane(face)?;
+
+        let bounds_face = face
+            .region()
+            .all_cycles()
+            .any(|cycle| cycle.half_edges().contains(half_edge));
+        let SurfacePath::Circle(circle) = half_edge.path() else {
+            return Err(AnnotateError::NotCircular(half_edge.clone()));
+        };
+        if !bounds_face {
+            return Err(AnnotateError::NotCircular(half_edge.clone()));
+        }
+
+        let surface = face.surface().geometry();
+        let center = surface.point_from_surface_coords(circle.center());
+        let radius = surface.vector_from_surface_coords(circle.a()).magnitude();
+
+        // Measure along a direction that is parallel to both the circle and
+        // the view, so the diameter appears at its true size. If the circle
+        // faces the viewer, any direction within it works.
+        let [right, _, towards_viewer] = self.view.axes();
+        let across = normal.cross(&towards_viewer);
+        let across = if across.magnitude() > Scalar::from(EPSILON) {
+            across.normalize()
+        } else {
+            right
+        };
+
+        let [a, b] = [center - across * radius, center + across * radius]
+            .map(|point| self.point(point));
+        let direction = (b - a).normalize();
+        let normal = perpendicular(direction);
+
+        let mut lines = vec![[a, b]];
+        lines.extend(self.arrowhead(a, direction, normal));
+        lines.extend(self.arrowhead(b, -direction, normal));
+
+        Ok(Annotation {
+            lines,
+            text: format!("⌀{}", format_length(radius * 2.)),
+            text_position: a + (b - a) / 2. + normal * self.text_height(),
+            text_height: self.text_height(),
+        })
+    }
+
+    fn angle(
+        &self,
+        [a, b]: &[Handle<Face>; 2],
+    ) -> Result<Annotation, AnnotateError> {
+        let [(origin_a, normal_a), (origin_b, normal_b)] =
+            [plane(a)?, plane(b)?];
+
+        if normal_a.cross(&normal_b).magnitude() < Scalar::from(EPSILON) {
+            return Err(AnnotateError::Parallel);
+        }
+        self.check_in_view_plane(normal_a)?;
+        self.check_in_view_plane(normal_b)?;
+
+        // Both faces appear as lines in the view. Their intersection is the
+        // vertex of the angle.
+        let [_, _, towards_viewer] = self.view.axes();
+        let [trace_a, trace_b] = [normal_a, normal_b]
+            .map(|normal| self.vector(towards_viewer.cross(&normal)));
+        let [origin_a, origin_b] =
+            [origin_a, origin_b].map(|origin| self.point(origin));
+
+        let s =
+            (origin_b - origin_a).cross2d(&trace_b) / trace_a.cross2d(&trace_b);
+        let vertex = origin_a + trace_a * s;
+
+        // Point each trace towards its face, so the angle is measured between
+        // the faces, not between their extensions.
+        let [trace_a, trace_b] =
+            [(a, trace_a), (b, trace_b)].map(|(face, trace)| {
+                if (self.point(centroid(face)) - vertex).dot(&trace)
+                    < Scalar::ZERO
+                {
+                    -trace
+                } else {
+                    trace
+                }
+            });
+
+        let value = trace_a.dot(&trace_b).acos();
+        let sweep = if trace_a.cross2d(&trace_b) < Scalar::ZERO {
+            -value
+        } else {
+            value
+        };
+
+        let radius = self.size * 0.1;
+        let start = trace_a.v.atan2(trace_a.u);
+        let arc_point = |t: Scalar| {
+            let (sin, cos) = (start + sweep * t).sin_cos();
+            vertex + Vector::from([cos, sin]) * radius
+        };
+
+        let mut lines = vec![
+            [vertex, vertex + trace_a * radius * 1.2],
+            [vertex, vertex + trace_b * radius * 1.2],
+        ];
+        lines.extend((0..ARC_SEGMENTS).map(|i| {
+            let [t0, t1] =
+                [i, i + 1].map(|i| Scalar::from_u64(i) / ARC_SEGMENTS as f64);
+            [arc_point(t0), arc_point(t1)]
+        }));
+
+        let middle = arc_point(Scalar::from(0.5)) - vertex;
+        let degrees = value * 180. / Scalar::PI;
+
+        Ok(Annotation {
+            lines,
+            text: format!("{:.1}°", degrees.into_f64()),
+            text_position: vertex
+                + middle.normalize() * (radius + self.text_height()),
+            text_height: self.text_height(),
+        })
+    }
+
+    /// Build a linear dimension between two points in the view
+    ///
+    /// `direction` must be normalized. The dimension line is placed next to the
+    /// points, on the left side of `direction`.
+    fn linear(
+        &self,
+        [a, b]: [Point<2>; 2],
+        direction: Vector<2>,
+        text: String,
+    ) -> Annotation {
+        let normal = perpendicular(direction);
+        let gap = self.size * 0.05;
+
+        let level =
+            Scalar::max(a.coords.dot(&normal), b.coords.dot(&normal)) + gap;
+        let [end_a, end_b] = [a, b]
+            .map(|point| point + normal * (level - point.coords.dot(&normal)));
+
+        let inwards = if (end_b - end_a).dot(&direction) < Scalar::ZERO {
+            -direction
+        } else {
+            direction
+        };
+
+        let mut lines = vec![
+            [a + normal * (gap / 4.), end_a + normal * (gap / 4.)],
+            [b + normal * (gap / 4.), end_b + normal * (gap / 4.)],
+            [end_a, end_b],
+        ];
+        lines.extend(self.arrowhead(end_a, inwards, normal));
+        lines.extend(self.arrowhead(end_b, -inwards, normal));
+
+        Annotation {
+            lines,
+            text,
+            text_position: end_a
+                + (end_b - end_a) / 2.
+                + normal * self.text_height(),
+            text_height: self.text_height(),
+        }
+    }
+
+    /// Build the lines of an arrowhead
+    ///
+    /// The arrowhead points at `tip`, while `direction` points from the tip
+    /// into the arrowhead.
+    fn arrowhead(
+        &self,
+        tip: Point<2>,
+        direction: Vector<2>,
+        normal: Vector<2>,
+    ) -> [[Point<2>; 2]; 2] {
+        let length = self.size * 0.02;
+        let back = tip + direction * length;
+
+        [
+            [tip, back + normal * (length / 3.)],
+            [tip, back - normal * (length / 3.)],
+        ]
+    }
+
+    fn check_in_view_plane(
+        &self,
+        normal: Vector<3>,
+    ) -> Result<(), AnnotateError> {
+        let [_, _, towards_viewer] = self.view.axes();
+
+        if normal.dot(&towards_viewer).abs() > Scalar::from(EPSILON) {
+            return Err(AnnotateError::NotMeasurableInView(self.view));
+        }
+
+        Ok(())
+    }
+
+    fn text_height(&self) -> Scalar {
+        self.size * 0.03
+    }
+
+    fn point(&self, point: Point<3>) -> Point<2> {
+        self.view.project(point) + self.offset
+    }
+
+    fn vector(&self, vector: Vector<3>) -> Vector<2> {
+        let [right, up, _] = self.view.axes();
+        Vector::from([vector.dot(&right), vector.dot(&up)])
+    }
+}
+
+/// The number of segments that the arc of an angle is drawn with
+const ARC_SEGMENTS: u64 = 16;
+
+/// The tolerance for checking whether directions are parallel or orthogonal
+const EPSILON: f64 = 1e-6;
+
+/// Compute a point on and the normal of a planar face
+fn plane(face: &Handle<Face>) -> Result<(Point<3>, Vector<3>), AnnotateError> {
+    let surface = face.surface().geometry();
+
+    let GlobalPath::Line(line) = surface.u else {
+        return Err(AnnotateError::NotPlanar(face.clone()));
+    };
+
+    Ok((
+        line.origin(),
+        line.direction().cross(&surface.v).normalize(),
+    ))
+}
+
+fn centroid(face: &Face) -> Point<3> {
+    let surface = face.surface().geometry();
+    let half_edges = face.region().exterior().half_edges();
+
+    let sum =
+        half_edges
+            .iter()
+            .fold(Vector::from([0., 0., 0.]), |sum, half_edge| {
+                let point = surface
+                    .point_from_surface_coords(half_edge.start_position());
+                sum + point.coords
+            });
+
+    Point::origin() + sum / half_edges.len() as f64
+}
+
+fn perpendicular(vector: Vector<2>) -> Vector<2> {
+    Vector::from([-vector.v, vector.u])
+}
+
+fn format_length(value: Scalar) -> String {
+    format!("{:.2}", value.into_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::drawing::Drawing;
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::approx::Tolerance,
+        objects::{Face, Solid},
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        queries::oriented_normal,
+        services::Services,
+        storage::Handle,
+    };
+
+    use super::{
+        super::{GenerateDrawing, StandardView},
+        Annotate, AnnotateError, Dimension, LinearOrientation,
+    };
+
+    #[test]
+    fn dimensions_of_box() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = Solid::box_from_dims([3., 2., 1.], &mut services)
+            .insert(&mut services);
+        let mut drawing = solid.drawing(
+            [StandardView::Front, StandardView::Right],
+            Tolerance::from_scalar(0.01)?,
+        );
+
+        let face = |normal: [f64; 3]| face_with_normal(&solid, normal);
+        let [left, right, top] =
+            [[-1., 0., 0.], [1., 0., 0.], [0., 0., 1.]].map(face);
+
+        let front = StandardView::Front;
+        drawing.annotate(
+            front,
+            &Dimension::Distance {
+                faces: [left.clone(), right.clone()],
+            },
+        )?;
+        drawing.annotate(
+            front,
+            &Dimension::Angle {
+                faces: [top.clone(), right.clone()],
+            },
+        )?;
+        for orientation in
+            [LinearOrientation::Horizontal, LinearOrientation::Vertical]
+        {
+            drawing.annotate(
+                front,
+                &Dimension::Linear {
+                    points: [[0., 0., 0.], [3., 0., 1.]].map(Point::from),
+                    orientation,
+                },
+            )?;
+        }
+        assert_eq!(texts(&drawing, 0), ["3.00", "90.0°", "3.00", "1.00"]);
+
+        // The distance between left and right is not visible from the right.
+        assert!(matches!(
+            drawing.annotate(
+                StandardView::Right,
+                &Dimension::Distance {
+                    faces: [left.clone(), right.clone()]
+                },
+            ),
+            Err(AnnotateError::NotMeasurableInView(StandardView::Right))
+        ));
+        assert!(matches!(
+            drawing.annotate(
+                front,
+                &Dimension::Distance {
+                    faces: [left.clone(), top.clone()]
+                },
+            ),
+            Err(AnnotateError::NotParallel)
+        ));
+        assert!(matches!(
+            drawing.annotate(
+                StandardView::Top,
+                &Dimension::Distance {
+                    faces: [left, right]
+                },
+            ),
+            Err(AnnotateError::ViewNotInDrawing(StandardView::Top))
+        ));
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn diameter_of_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid =
+            Solid::cylinder(1., 2., &mut services).insert(&mut services);
+        let mut drawing = solid.drawing(
+            [StandardView::Front, StandardView::Top],
+            Tolerance::from_scalar(0.01)?,
+        );
+
+        let top = face_with_normal(&solid, [0., 0., 1.]);
+        let half_edge = top.region().exterior().half_edges().first().clone();
+
+        // The circle faces the viewer in the top view, and is seen edge-on in
+        // the front view. Both show its true diameter.
+        for view in [StandardView::Front, StandardView::Top] {
+            drawing.annotate(
+                view,
+                &Dimension::Diameter {
+                    face: top.clone(),
+                    half_edge: half_edge.clone(),
+                },
+            )?;
+        }
+        assert_eq!(texts(&drawing, 0), ["⌀2.00"]);
+        assert_eq!(texts(&drawing, 1), ["⌀2.00"]);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    fn face_with_normal(solid: &Solid, normal: [f64; 3]) -> Handle<Face> {
+        let normal = Vector::from(normal);
+
+        solid
+            .shells()
+            .iter()
+            .flat_map(|shell| shell.faces())
+            .find(|face| {
+                (oriented_normal(face) - normal).magnitude()
+                    < Scalar::from(1e-9)
+            })
+            .expect("Solid has face with normal")
+            .clone()
+    }
+
+    fn texts(drawing: &Drawing, view: usize) -> Vec<&str> {
+        drawing.views[view]
+            .annotations
+            .iter()
+            .map(|annotation| annotation.text.as_str())
+            .collect()
+    }
+}
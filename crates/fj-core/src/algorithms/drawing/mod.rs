@@ -0,0 +1,676 @@
+//! Technical drawings
+//!
+//! Generates orthographic views of a solid, as they are used in technical
+//! drawings. Each view shows the edges and silhouettes of the solid, split into
+//! visible and hidden lines.
+//!
+//! # Implementation Note
+//!
+//! Hidden lines are determined by sampling each line and casting a ray from
+//! each sample towards the viewer, against a triangulation of the solid. This
+//! is simple and robust. Rays are only tested against the triangles in the
+//! cell of a uniform grid that they pass through, which keeps this fast for
+//! detailed models. Lines are only split at the sample points, so the
+//! transition between a visible and a hidden part of a line can be off by up
+//! to the sample distance.
+
+mod dimension;
+
+use std::collections::{BTreeSet, HashMap};
+
+use fj_interop::drawing::{Drawing, LineStyle, Stroke, View};
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    objects::{Shell, Solid},
+    queries::{dihedral_angle, SiblingOfHalfEdge},
+};
+
+use super::{
+    approx::{Approx, Tolerance},
+    triangulate::Triangulate,
+};
+
+pub use self::dimension::{
+    Annotate, AnnotateError, Dimension, LinearOrientation,
+};
+
+/// Generate a technical drawing of an object
+pub trait GenerateDrawing {
+    /// Generate a drawing with the provided views
+    ///
+    /// The views are arranged in third-angle projection, relative to the front
+    /// view. Views that are requested more than once are only drawn once.
+    fn drawing(
+        &self,
+        views: impl IntoIterator<Item = StandardView>,
+        tolerance: impl Into<Tolerance>,
+    ) -> Drawing;
+}
+
+impl GenerateDrawing for Solid {
+    fn drawing(
+        &self,
+        views: impl IntoIterator<Item = StandardView>,
+        tolerance: impl Into<Tolerance>,
+    ) -> Drawing {
+        let tolerance = tolerance.into();
+
+        let triangles = (self, tolerance)
+            .triangulate()
+            .triangles()
+            .map(|triangle| triangle.inner.points())
+            .collect::<Vec<_>>();
+        let Some(aabb) = triangles_aabb(&triangles) else {
+            return Drawing::default();
+        };
+
+        let scene = Scene {
+            edges: self
+                .shells()
+                .iter()
+                .flat_map(|shell| edges(shell, tolerance))
+                .collect(),
+            mesh_edges: mesh_edges(&triangles),
+            triangles,
+            size: aabb.size().magnitude(),
+        };
+
+        let views = views.into_iter().collect::<BTreeSet<_>>();
+        let mut projections = views
+            .into_iter()
+            .map(|view| (view, Vector::from([0., 0.]), scene.project(view)))
+            .collect::<Vec<_>>();
+
+        layout(&mut projections, scene.size * GAP);
+
+        Drawing {
+            views: projections
+                .into_iter()
+                .map(|(view, offset, strokes)| View {
+                    name: view.name().to_string(),
+                    offset,
+                    strokes: strokes
+                        .into_iter()
+                        .map(|stroke| Stroke {
+                            points: stroke.points.map(|point| point + offset),
+                            style: stroke.style,
+                        })
+                        .collect(),
+                    annotations: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// One of the standard views of a technical drawing
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum StandardView {
+    /// The view from the front, looking in the positive y direction
+    Front,
+
+    /// The view from above, looking in the negative z direction
+    Top,
+
+    /// The view from the right, looking in the negative x direction
+    Right,
+
+    /// The view from the left, looking in the positive x direction
+    Left,
+
+    /// The view from below, looking in the positive z direction
+    Bottom,
+
+    /// The view from the back, looking in the negative y direction
+    Back,
+}
+
+impl StandardView {
+    /// The standard views that are usually shown in a drawing
+    pub const DEFAULT: [Self; 3] = [Self::Front, Self::Top, Self::Right];
+
+    /// Access the name of the view
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Front => "front",
+            Self::Top => "top",
+            Self::Right => "right",
+            Self::Left => "left",
+            Self::Bottom => "bottom",
+            Self::Back => "back",
+        }
+    }
+
+    /// Access the axes of the view
+    ///
+    /// Returns the directions that point right and up in the view, followed by
+    /// the direction that points towards the viewer.
+    pub fn axes(&self) -> [Vector<3>; 3] {
+        let [x, y, z] = [Vector::unit_x(), Vector::unit_y(), Vector::unit_z()];
+
+        match self {
+            Self::Front => [x, z, -y],
+            Self::Top => [x, y, z],
+            Self::Right => [y, z, x],
+            Self::Left => [-y, z, -x],
+            Self::Bottom => [x, -y, -z],
+            Self::Back => [-x, z, y],
+        }
+    }
+
+    /// Project a point into the view
+    pub fn project(&self, point: impl Into<Point<3>>) -> Point<2> {
+        let point = point.into();
+        let [right, up, _] = self.axes();
+
+        Point::from([point.coords.dot(&right), point.coords.dot(&up)])
+    }
+}
+
+/// The gap between views, relative to the size of the object
+const GAP: f64 = 0.25;
+
+/// The number of samples per line, along the size of the object
+const SAMPLES: f64 = 200.;
+
+/// Edges whose faces meet at an angle closer than this to a straight angle are
+/// considered tangent, and are not drawn
+const TANGENT_EPSILON: f64 = 1e-4;
+
+/// Everything needed to project an object into a view
+struct Scene {
+    /// The polylines that approximate the sharp edges of the object
+    edges: Vec<Vec<Point<3>>>,
+
+    /// The edges of the triangles, and the triangles they are adjacent to
+    mesh_edges: HashMap<[Point<3>; 2], Vec<usize>>,
+
+    /// The triangles that approximate the object
+    triangles: Vec<[Point<3>; 3]>,
+
+    /// The length of the diagonal of the object's AABB
+    size: Scalar,
+}
+
+impl Scene {
+    fn project(&self, view: StandardView) -> Vec<Stroke> {
+        let [_, _, towards_viewer] = view.axes();
+
+        let edges = self
+            .edges
+            .iter()
+            .flat_map(|polyline| polyline.windows(2).map(|s| [s[0], s[1]]));
+        let silhouettes = self.silhouettes(towards_viewer);
+        let grid = Grid::new(&self.triangles, view);
+
+        let mut strokes = BTreeSet::new();
+        for segment in edges.chain(silhouettes) {
+            for (segment, style) in self.classify(segment, view, &grid) {
+                let points = segment.map(|point| view.project(point));
+                if points[0].distance_to(&points[1]) <= self.epsilon() {
+                    continue;
+                }
+
+                strokes.insert(Stroke {
+                    points: normalized(points),
+                    style,
+                });
+            }
+        }
+
+        // Drop hidden strokes that are covered by visible ones.
+        let visible = strokes
+            .iter()
+            .filter(|stroke| stroke.style == LineStyle::Visible)
+            .map(|stroke| stroke.points)
+            .collect::<BTreeSet<_>>();
+        strokes
+            .into_iter()
+            .filter(|stroke| {
+                stroke.style == LineStyle::Visible
+                    || !visible.contains(&stroke.points)
+            })
+            .collect()
+    }
+
+    /// Find the silhouettes of the object, as seen from the provided direction
+    ///
+    /// Silhouettes are the edges of the triangulation, where a triangle that
+    /// faces the viewer meets one that doesn't.
+    fn silhouettes(
+        &self,
+        towards_viewer: Vector<3>,
+    ) -> impl Iterator<Item = [Point<3>; 2]> + '_ {
+        let faces_viewer = move |i: usize| {
+            let [a, b, c] = self.triangles[i];
+            (b - a).cross(&(c - a)).normalize().dot(&towards_viewer)
+                > Scalar::from_f64(EPSILON)
+        };
+
+        self.mesh_edges
+            .iter()
+            .filter(move |(_, triangles)| match triangles.as_slice() {
+                [a, b] => faces_viewer(*a) != faces_viewer(*b),
+                _ => false,
+            })
+            .map(|(&segment, _)| segment)
+    }
+
+    /// Split a segment into parts that are visible or hidden
+    fn classify(
+        &self,
+        [start, end]: [Point<3>; 2],
+        view: StandardView,
+        grid: &Grid,
+    ) -> Vec<([Point<3>; 2], LineStyle)> {
+        let step = self.size / SAMPLES;
+        let num_samples =
+            (start.distance_to(&end) / step).ceil().into_u64().max(1);
+
+        let mut parts: Vec<([Point<3>; 2], LineStyle)> = Vec::new();
+        for i in 0..num_samples {
+            let [a, b] = [i, i + 1].map(|j| {
+                start
+                    + (end - start)
+                        * (Scalar::from_u64(j) / Scalar::from_u64(num_samples))
+            });
+            let middle = a + (b - a) / 2.;
+
+            let style = if self.is_occluded(middle, view, grid) {
+                LineStyle::Hidden
+            } else {
+                LineStyle::Visible
+            };
+
+            match parts.last_mut() {
+                Some((part, last_style)) if *last_style == style => {
+                    part[1] = b;
+                }
+                _ => parts.push(([a, b], style)),
+            }
+        }
+
+        parts
+    }
+
+    /// Determine whether a triangle is between the point and the viewer
+    fn is_occluded(
+        &self,
+        point: Point<3>,
+        view: StandardView,
+        grid: &Grid,
+    ) -> bool {
+        let [_, _, direction] = view.axes();
+
+        // Rays that graze the boundary of a triangle don't count as
+        // intersections. This prevents edges from being hidden by the faces
+        // they bound.
+        let epsilon = Scalar::from_f64(EPSILON);
+
+        grid.triangles_at(view.project(point)).iter().any(|&i| {
+            let [a, b, c] = self.triangles[i];
+
+            // Möller-Trumbore ray-triangle intersection
+            let ab = b - a;
+            let ac = c - a;
+
+            let p = direction.cross(&ac);
+            let determinant = ab.dot(&p);
+            if determinant.abs() <= epsilon * ab.magnitude() * ac.magnitude() {
+                return false;
+            }
+
+            let s = point - a;
+            let u = s.dot(&p) / determinant;
+            if u <= epsilon || u >= Scalar::ONE - epsilon {
+                return false;
+            }
+
+            let q = s.cross(&ab);
+            let v = direction.dot(&q) / determinant;
+            if v <= epsilon || u + v >= Scalar::ONE - epsilon {
+                return false;
+            }
+
+            let t = ac.dot(&q) / determinant;
+            t > self.epsilon()
+        })
+    }
+
+    fn epsilon(&self) -> Scalar {
+        self.size * EPSILON
+    }
+}
+
+/// Used for comparisons, relative to the size of the object where applicable
+const EPSILON: f64 = 1e-9;
+
+/// A uniform grid over the triangles of a scene, as projected into a view
+///
+/// All rays that are cast for a view are parallel to the viewing direction. A
+/// ray can only hit the triangles whose projections overlap the cell that the
+/// ray's projection falls into.
+struct Grid {
+    min: Point<2>,
+    cell_size: Scalar,
+    resolution: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    fn new(triangles: &[[Point<3>; 3]], view: StandardView) -> Self {
+        let triangles = triangles
+            .iter()
+            .map(|triangle| triangle.map(|point| view.project(point)))
+            .collect::<Vec<_>>();
+
+        // Scenes are only created for objects that have triangles.
+        let aabb = Aabb::<2>::from_points(triangles.iter().flatten().copied());
+        let size = aabb.max - aabb.min;
+
+        // About one triangle per cell, for evenly distributed triangles.
+        let resolution =
+            (triangles.len() as f64).sqrt().ceil().max(1.) as usize;
+        let cell_size =
+            size.u.max(size.v) / Scalar::from_u64(resolution as u64);
+
+        let mut grid = Self {
+            min: aabb.min,
+            cell_size: if cell_size > Scalar::ZERO {
+                cell_size
+            } else {
+                Scalar::ONE
+            },
+            resolution,
+            cells: vec![Vec::new(); resolution * resolution],
+        };
+
+        for (i, triangle) in triangles.into_iter().enumerate() {
+            let aabb = Aabb::<2>::from_points(triangle);
+            let [min, max] = [aabb.min, aabb.max].map(|point| grid.cell(point));
+
+            for u in min[0]..=max[0] {
+                for v in min[1]..=max[1] {
+                    grid.cells[v * resolution + u].push(i);
+                }
+            }
+        }
+
+        grid
+    }
+
+    /// Access the triangles whose projections might contain the point
+    fn triangles_at(&self, point: Point<2>) -> &[usize] {
+        let [u, v] = self.cell(point);
+        &self.cells[v * self.resolution + u]
+    }
+
+    fn cell(&self, point: Point<2>) -> [usize; 2] {
+        (point - self.min).components.map(|component| {
+            let cell = (component / self.cell_size)
+                .floor()
+                .max(Scalar::ZERO)
+                .into_u64() as usize;
+            cell.min(self.resolution - 1)
+        })
+    }
+}
+
+/// Collect the sharp edges of a shell
+///
+/// Edges that are shared by two faces are only returned once.
+fn edges(shell: &Shell, tolerance: Tolerance) -> Vec<Vec<Point<3>>> {
+    let mut edges = Vec::new();
+
+    for face in shell.faces() {
+        let surface = face.surface().geometry();
+
+        for half_edge in face
+            .region()
+            .all_cycles()
+            .flat_map(|cycle| cycle.half_edges().iter())
+        {
+            if let Some(sibling) = shell.get_sibling_of(half_edge) {
+                if sibling.id() < half_edge.id() {
+                    continue;
+                }
+            }
+            if let Some(angle) = dihedral_angle(half_edge, face, shell) {
+                if (Scalar::PI - angle).abs()
+                    < Scalar::from_f64(TANGENT_EPSILON)
+                {
+                    continue;
+                }
+            }
+
+            let mut polyline = (&**half_edge, &**face.surface())
+                .approx(tolerance)
+                .points
+                .into_iter()
+                .map(|point| point.global_form)
+                .collect::<Vec<_>>();
+
+            let [_, end] = half_edge.boundary().inner;
+            polyline.push(surface.point_from_surface_coords(
+                half_edge.path().point_from_path_coords(end),
+            ));
+
+            edges.push(polyline);
+        }
+    }
+
+    edges
+}
+
+/// Build a map from the edges of the triangles to the triangles
+fn mesh_edges(
+    triangles: &[[Point<3>; 3]],
+) -> HashMap<[Point<3>; 2], Vec<usize>> {
+    let mut edges = HashMap::<_, Vec<_>>::new();
+
+    for (i, &[a, b, c]) in triangles.iter().enumerate() {
+        for edge in [[a, b], [b, c], [c, a]] {
+            edges.entry(normalized(edge)).or_default().push(i);
+        }
+    }
+
+    edges
+}
+
+fn triangles_aabb(triangles: &[[Point<3>; 3]]) -> Option<Aabb<3>> {
+    if triangles.is_empty() {
+        return None;
+    }
+
+    Some(Aabb::<3>::from_points(triangles.iter().flatten().copied()))
+}
+
+/// Arrange the views in third-angle projection, relative to the front view
+fn layout(
+    projections: &mut [(StandardView, Vector<2>, Vec<Stroke>)],
+    gap: Scalar,
+) {
+    // Each view is placed next to the first of its neighbors that is part of
+    // the drawing. The views are processed in an order that makes sure that
+    // neighbor has already been placed.
+    let neighbors: [(StandardView, &[StandardView]); 5] = [
+        (StandardView::Top, &[StandardView::Front]),
+        (StandardView::Bottom, &[StandardView::Front]),
+        (StandardView::Right, &[StandardView::Front]),
+        (StandardView::Left, &[StandardView::Front]),
+        (
+            StandardView::Back,
+            &[StandardView::Right, StandardView::Front],
+        ),
+    ];
+
+    for (view, neighbors) in neighbors {
+        let Some(this) = placed_aabb(projections, view) else {
+            continue;
+        };
+        let Some(other) = neighbors
+            .iter()
+            .find_map(|&neighbor| placed_aabb(projections, neighbor))
+        else {
+            continue;
+        };
+
+        let offset = match view {
+            StandardView::Top => [Scalar::ZERO, other.max.v - this.min.v + gap],
+            StandardView::Bottom => {
+                [Scalar::ZERO, other.min.v - this.max.v - gap]
+            }
+            StandardView::Left => {
+                [other.min.u - this.max.u - gap, Scalar::ZERO]
+            }
+            StandardView::Right | StandardView::Back => {
+                [other.max.u - this.min.u + gap, Scalar::ZERO]
+            }
+            StandardView::Front => continue,
+        };
+
+        for (v, o, _) in projections.iter_mut() {
+            if *v == view {
+                *o = *o + Vector::from(offset);
+            }
+        }
+    }
+}
+
+/// Compute the AABB of a view, at its current offset
+fn placed_aabb(
+    projections: &[(StandardView, Vector<2>, Vec<Stroke>)],
+    view: StandardView,
+) -> Option<Aabb<2>> {
+    let (_, offset, strokes) =
+        projections.iter().find(|(v, _, _)| *v == view)?;
+    let aabb = strokes_aabb(strokes)?;
+
+    Some(Aabb {
+        min: aabb.min + *offset,
+        max: aabb.max + *offset,
+    })
+}
+
+fn strokes_aabb(strokes: &[Stroke]) -> Option<Aabb<2>> {
+    if strokes.is_empty() {
+        return None;
+    }
+
+    Some(Aabb::<2>::from_points(
+        strokes.iter().flat_map(|stroke| stroke.points),
+    ))
+}
+
+/// Sort the points of a segment, so it can be compared regardless of direction
+fn normalized<const D: usize>(mut points: [Point<D>; 2]) -> [Point<D>; 2] {
+    points.sort();
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::drawing::LineStyle;
+    use fj_math::{Aabb, Point, Scalar};
+
+    use crate::{
+        algorithms::approx::Tolerance, bench_models::perforated_plate,
+        operations::insert::Insert, services::Services,
+    };
+
+    use super::{GenerateDrawing, Grid, StandardView};
+
+    #[test]
+    fn standard_view_axes() {
+        for view in [
+            StandardView::Front,
+            StandardView::Top,
+            StandardView::Right,
+            StandardView::Left,
+            StandardView::Bottom,
+            StandardView::Back,
+        ] {
+            let [right, up, towards_viewer] = view.axes();
+            assert_eq!(right.cross(&up), towards_viewer);
+        }
+    }
+
+    #[test]
+    fn grid_cells() {
+        let triangles = [
+            [[0., 0., 0.], [1., 0., 0.], [0., 0., 1.]],
+            [[4., 1., 4.], [3., 1., 4.], [4., 1., 3.]],
+            [[0., 2., 0.], [4., 2., 0.], [0., 2., 4.]],
+        ]
+        .map(|triangle| triangle.map(Point::from));
+        let grid = Grid::new(&triangles, StandardView::Front);
+
+        // The cell of a point contains the triangles it is projected into, but
+        // not those that are far away.
+        let at = |point: [f64; 2]| grid.triangles_at(Point::from(point));
+        assert!(at([0.1, 0.1]).contains(&0));
+        assert!(at([0.1, 0.1]).contains(&2));
+        assert!(!at([0.1, 0.1]).contains(&1));
+        assert!(at([3.9, 3.9]).contains(&1));
+        assert!(!at([3.9, 3.9]).contains(&0));
+    }
+
+    #[test]
+    fn hidden_lines_of_plate() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // A 1x1 plate with a thickness of 0.2, with a hole of radius 0.25 in
+        // its center.
+        let plate = perforated_plate(1, &mut services).insert(&mut services);
+        let drawing =
+            plate.drawing(StandardView::DEFAULT, Tolerance::from_scalar(0.01)?);
+
+        let names = drawing
+            .views
+            .iter()
+            .map(|view| view.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["front", "top", "right"]);
+
+        let front = &drawing.views[0];
+        let close = |a: Scalar, b: f64| {
+            (a - Scalar::from(b)).abs() < Scalar::from(0.02)
+        };
+
+        let visible = front
+            .strokes
+            .iter()
+            .filter(|stroke| stroke.style == LineStyle::Visible)
+            .flat_map(|stroke| stroke.points);
+        let aabb = Aabb::<2>::from_points(visible);
+        assert!(close(aabb.min.u, 0.) && close(aabb.min.v, 0.));
+        assert!(close(aabb.max.u, 1.) && close(aabb.max.v, 0.2));
+
+        // The walls of the hole are hidden behind the front of the plate.
+        let hidden = front
+            .strokes
+            .iter()
+            .filter(|stroke| stroke.style == LineStyle::Hidden)
+            .collect::<Vec<_>>();
+        for x in [0.25, 0.75] {
+            assert!(hidden.iter().any(|stroke| {
+                stroke.points.iter().all(|point| close(point.u, x))
+            }));
+        }
+        for stroke in hidden {
+            for point in stroke.points {
+                assert!(point.u > Scalar::from(0.2));
+                assert!(point.u < Scalar::from(0.8));
+            }
+        }
+
+        // The top view is placed above the front view.
+        let top = &drawing.views[1];
+        assert!(top.offset.v > Scalar::from(0.2));
+        assert_eq!(top.offset.u, Scalar::ZERO);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,257 @@
+use fj_math::{Circle, Line, Point, Scalar, Vector};
+
+use crate::geometry::SurfacePath;
+
+/// The intersection between two curves that are defined in the same surface
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum CurveCurveIntersection {
+    /// The curves intersect at one or more points
+    Points {
+        /// The intersection points, in curve coordinates on both curves
+        ///
+        /// The curve coordinates in each array are given in the same order as
+        /// the curves that were passed to [`CurveCurveIntersection::compute`].
+        points: Vec<[Point<1>; 2]>,
+    },
+
+    /// The curves are coincident
+    Coincident,
+}
+
+impl CurveCurveIntersection {
+    /// Compute the intersection between two curves
+    ///
+    /// Returns `None`, if the curves don't intersect.
+    ///
+    /// Spirals and involutes are not supported, and `None` is returned for
+    /// them too. Since their coordinates are not periodic, they can intersect
+    /// another curve any number of times, and there is no boundary here to
+    /// limit the search to.
+    ///
+    /// # Implementation Note
+    ///
+    /// Like the other intersection algorithms, this one compares values
+    /// exactly, without using an epsilon value. Curves that are just barely
+    /// touching might or might not be found to intersect.
+    pub fn compute(paths: [&SurfacePath; 2]) -> Option<Self> {
+        match paths {
+            [SurfacePath::Line(a), SurfacePath::Line(b)] => line_line(a, b),
+            [SurfacePath::Line(line), SurfacePath::Circle(circle)] => {
+                line_circle(line, circle, false)
+            }
+            [SurfacePath::Circle(circle), SurfacePath::Line(line)] => {
+                line_circle(line, circle, true)
+            }
+            [SurfacePath::Circle(a), SurfacePath::Circle(b)] => {
+                circle_circle(a, b)
+            }
+            [SurfacePath::Spiral(_) | SurfacePath::Involute(_), _]
+            | [_, SurfacePath::Spiral(_) | SurfacePath::Involute(_)] => None,
+        }
+    }
+}
+
+fn line_line(a: &Line<2>, b: &Line<2>) -> Option<CurveCurveIntersection> {
+    let denom = a.direction().cross2d(&b.direction());
+
+    if denom == Scalar::ZERO {
+        // The lines are parallel.
+
+        if a.is_coincident_with(b) {
+            return Some(CurveCurveIntersection::Coincident);
+        }
+
+        return None;
+    }
+
+    let origin_to_origin = b.origin() - a.origin();
+    let t_a = origin_to_origin.cross2d(&b.direction()) / denom;
+    let t_b = origin_to_origin.cross2d(&a.direction()) / denom;
+
+    Some(CurveCurveIntersection::Points {
+        points: vec![[Point::from([t_a]), Point::from([t_b])]],
+    })
+}
+
+fn line_circle(
+    line: &Line<2>,
+    circle: &Circle<2>,
+    circle_first: bool,
+) -> Option<CurveCurveIntersection> {
+    // Insert the line equation into the circle equation, resulting in a
+    // quadratic equation for the line coordinate.
+    let origin_to_center = line.origin() - circle.center();
+
+    let a = line.direction().dot(&line.direction());
+    let b = line.direction().dot(&origin_to_center) * 2.;
+    let c = origin_to_center.dot(&origin_to_center)
+        - circle.radius() * circle.radius();
+
+    let ts = solve_quadratic(a, b, c)?;
+
+    let points = ts
+        .into_iter()
+        .map(|t| {
+            let point_on_line = Point::from([t]);
+            let point_on_circle = circle_coords(
+                circle,
+                line.point_from_line_coords(point_on_line),
+            );
+
+            if circle_first {
+                [point_on_circle, point_on_line]
+            } else {
+                [point_on_line, point_on_circle]
+            }
+        })
+        .collect();
+
+    Some(CurveCurveIntersection::Points { points })
+}
+
+fn circle_circle(
+    a: &Circle<2>,
+    b: &Circle<2>,
+) -> Option<CurveCurveIntersection> {
+    let center_to_center = b.center() - a.center();
+    let distance = center_to_center.magnitude();
+
+    let [r_a, r_b] = [a, b].map(|circle| circle.radius());
+
+    if distance == Scalar::ZERO {
+        if r_a == r_b {
+            return Some(CurveCurveIntersection::Coincident);
+        }
+
+        return None;
+    }
+    if distance > r_a + r_b || distance < (r_a - r_b).abs() {
+        return None;
+    }
+
+    // Distance from the center of `a` to the line that connects the
+    // intersection points, and half the distance between those points.
+    let d = (r_a * r_a - r_b * r_b + distance * distance) / (distance * 2.);
+    let h = (r_a * r_a - d * d).max(Scalar::ZERO).sqrt();
+
+    let direction = center_to_center / distance;
+    let perpendicular = Vector::from([-direction.v, direction.u]);
+    let base = a.center() + direction * d;
+
+    let mut points_global = vec![base + perpendicular * h];
+    if h != Scalar::ZERO {
+        points_global.push(base - perpendicular * h);
+    }
+
+    let points = points_global
+        .into_iter()
+        .map(|point| [circle_coords(a, point), circle_coords(b, point)])
+        .collect();
+
+    Some(CurveCurveIntersection::Points { points })
+}
+
+fn solve_quadratic(a: Scalar, b: Scalar, c: Scalar) -> Option<Vec<Scalar>> {
+    let discriminant = b * b - a * c * 4.;
+
+    if discriminant < Scalar::ZERO {
+        return None;
+    }
+    if discriminant == Scalar::ZERO {
+        return Some(vec![-b / (a * 2.)]);
+    }
+
+    let root = discriminant.sqrt();
+    Some(vec![(-b - root) / (a * 2.), (-b + root) / (a * 2.)])
+}
+
+/// Convert a point on a circle into circle coordinates
+///
+/// Unlike [`Circle::point_to_circle_coords`], this takes the orientation of the
+/// circle's coordinate system into account.
+fn circle_coords(circle: &Circle<2>, point: Point<2>) -> Point<1> {
+    let vector = point - circle.center();
+
+    let u = vector.dot(&circle.a());
+    let v = vector.dot(&circle.b());
+
+    let atan = Scalar::atan2(v, u);
+    let coord = if atan >= Scalar::ZERO {
+        atan
+    } else {
+        atan + Scalar::TAU
+    };
+
+    Point::from([coord])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use fj_math::Point;
+
+    use crate::geometry::SurfacePath;
+
+    use super::CurveCurveIntersection;
+
+    #[test]
+    fn line_line() {
+        let (a, _) = SurfacePath::line_from_points([[0., 0.], [2., 0.]]);
+        let (b, _) = SurfacePath::line_from_points([[1., -1.], [1., 1.]]);
+        let (c, _) = SurfacePath::line_from_points([[0., 1.], [1., 1.]]);
+
+        assert_eq!(
+            CurveCurveIntersection::compute([&a, &b]),
+            Some(CurveCurveIntersection::Points {
+                points: vec![[Point::from([0.5]), Point::from([0.5])]],
+            }),
+        );
+        assert_eq!(CurveCurveIntersection::compute([&a, &c]), None);
+        assert_eq!(
+            CurveCurveIntersection::compute([&a, &a]),
+            Some(CurveCurveIntersection::Coincident),
+        );
+    }
+
+    #[test]
+    fn line_circle() {
+        let (line, _) = SurfacePath::line_from_points([[-2., 0.], [2., 0.]]);
+        let circle = SurfacePath::circle_from_center_and_radius([0., 0.], 1.);
+
+        assert_eq!(
+            CurveCurveIntersection::compute([&line, &circle]),
+            Some(CurveCurveIntersection::Points {
+                points: vec![
+                    [Point::from([0.25]), Point::from([FRAC_PI_2 * 2.])],
+                    [Point::from([0.75]), Point::from([0.])],
+                ],
+            }),
+        );
+
+        let (miss, _) = SurfacePath::line_from_points([[-2., 2.], [2., 2.]]);
+        assert_eq!(CurveCurveIntersection::compute([&miss, &circle]), None);
+    }
+
+    #[test]
+    fn circle_circle() {
+        let a = SurfacePath::circle_from_center_and_radius([0., 0.], 1.);
+        let b = SurfacePath::circle_from_center_and_radius([2., 0.], 1.);
+        let c = SurfacePath::circle_from_center_and_radius([3., 0.], 1.);
+
+        assert_eq!(
+            CurveCurveIntersection::compute([&a, &b]),
+            Some(CurveCurveIntersection::Points {
+                points: vec![[
+                    Point::from([0.]),
+                    Point::from([FRAC_PI_2 * 2.])
+                ]],
+            }),
+        );
+        assert_eq!(CurveCurveIntersection::compute([&a, &c]), None);
+        assert_eq!(
+            CurveCurveIntersection::compute([&a, &a]),
+            Some(CurveCurveIntersection::Coincident),
+        );
+    }
+}
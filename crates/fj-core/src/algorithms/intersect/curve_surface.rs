@@ -0,0 +1,247 @@
+use fj_math::{Plane, Point, Scalar, Vector};
+
+use crate::{geometry::GlobalPath, objects::Surface};
+
+/// The intersection between a curve and a [`Surface`]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum CurveSurfaceIntersection {
+    /// The curve and the surface intersect at one or more points
+    Points {
+        /// The intersection points
+        ///
+        /// Each point is given in curve coordinates on the curve, and in
+        /// surface coordinates on the surface, in that order.
+        points: Vec<(Point<1>, Point<2>)>,
+    },
+
+    /// The curve lies in the surface
+    Coincident,
+}
+
+impl CurveSurfaceIntersection {
+    /// Compute the intersection between a curve and a surface
+    ///
+    /// Returns `None`, if the curve and the surface don't intersect. For
+    /// spirals and involutes, only the case of them lying in the surface is
+    /// supported. `None` is returned for them otherwise.
+    ///
+    /// Currently, only intersections with planes are supported. `None` is
+    /// returned, if the surface is curved.
+    pub fn compute(path: &GlobalPath, surface: &Surface) -> Option<Self> {
+        let plane = match surface.geometry().u {
+            GlobalPath::Line(line) => Plane::from_parametric(
+                line.origin(),
+                line.direction(),
+                surface.geometry().v,
+            ),
+            _ => return None,
+        };
+        let normal = plane.normal();
+
+        let points_on_curve = match path {
+            GlobalPath::Line(line) => {
+                let n_dot_direction = normal.dot(&line.direction());
+                let n_dot_origin =
+                    normal.dot(&(plane.origin() - line.origin()));
+
+                if n_dot_direction == Scalar::ZERO {
+                    // The line is parallel to the plane.
+
+                    if n_dot_origin == Scalar::ZERO {
+                        return Some(Self::Coincident);
+                    }
+
+                    return None;
+                }
+
+                vec![Point::from([n_dot_origin / n_dot_direction])]
+            }
+            GlobalPath::Circle(circle) => intersect_conic(
+                normal,
+                plane.origin(),
+                [circle.center().coords, circle.a(), circle.b()],
+            )?,
+            GlobalPath::Ellipse(ellipse) => intersect_conic(
+                normal,
+                plane.origin(),
+                [ellipse.center().coords, ellipse.a(), ellipse.b()],
+            )?,
+            GlobalPath::Spiral(spiral) => intersect_planar_curve(
+                normal,
+                plane.origin(),
+                [spiral.center().coords, spiral.a(), spiral.b()],
+            )?,
+            GlobalPath::Involute(involute) => intersect_planar_curve(
+                normal,
+                plane.origin(),
+                [involute.center().coords, involute.a(), involute.b()],
+            )?,
+        };
+
+        if points_on_curve.is_empty() {
+            return Some(Self::Coincident);
+        }
+
+        let points = points_on_curve
+            .into_iter()
+            .map(|point_on_curve| {
+                let point_global = path.point_from_path_coords(point_on_curve);
+                let point_on_surface =
+                    surface.geometry().project_global_point(point_global);
+
+                (point_on_curve, point_on_surface)
+            })
+            .collect();
+
+        Some(Self::Points { points })
+    }
+}
+
+/// Intersect a circle or ellipse with a plane
+///
+/// The conic is defined by its center, and the two vectors that define its
+/// coordinate system. Returns an empty `Vec`, if the conic lies in the plane.
+fn intersect_conic(
+    normal: Vector<3>,
+    plane_origin: Point<3>,
+    [center, a, b]: [Vector<3>; 3],
+) -> Option<Vec<Point<1>>> {
+    // The points on the conic are `center + a * cos(t) + b * sin(t)`. Inserting
+    // that into the plane equation results in an equation of the form
+    // `n_dot_a * cos(t) + n_dot_b * sin(t) = -n_dot_center`.
+    let n_dot_a = normal.dot(&a);
+    let n_dot_b = normal.dot(&b);
+    let n_dot_center = normal.dot(&(center - plane_origin.coords));
+
+    let amplitude = (n_dot_a * n_dot_a + n_dot_b * n_dot_b).sqrt();
+
+    if amplitude == Scalar::ZERO {
+        // The conic is parallel to the plane.
+
+        if n_dot_center == Scalar::ZERO {
+            return Some(Vec::new());
+        }
+
+        return None;
+    }
+
+    let cos = -n_dot_center / amplitude;
+    if cos.abs() > Scalar::ONE {
+        return None;
+    }
+
+    let phase = Scalar::atan2(n_dot_b, n_dot_a);
+    let offset = cos.acos();
+
+    let mut points = vec![(phase - offset).rem_euclid(Scalar::TAU)];
+    if offset != Scalar::ZERO {
+        points.push((phase + offset).rem_euclid(Scalar::TAU));
+    }
+    points.sort();
+
+    Some(points.into_iter().map(|t| Point::from([t])).collect())
+}
+
+/// Intersect a spiral or involute with a plane
+///
+/// Like a conic, the curve is defined by its center, and the two vectors that
+/// define the plane it lies in. Returns an empty `Vec`, if the curve lies in
+/// the plane.
+///
+/// Returns `None`, if the curve is parallel to the plane, or if it crosses the
+/// plane. Since the coordinates of these curves are not periodic, they cross
+/// the plane any number of times, and intersecting them is not supported.
+fn intersect_planar_curve(
+    normal: Vector<3>,
+    plane_origin: Point<3>,
+    [center, a, b]: [Vector<3>; 3],
+) -> Option<Vec<Point<1>>> {
+    let is_parallel =
+        normal.dot(&a) == Scalar::ZERO && normal.dot(&b) == Scalar::ZERO;
+    let is_in_plane =
+        normal.dot(&(center - plane_origin.coords)) == Scalar::ZERO;
+
+    if is_parallel && is_in_plane {
+        return Some(Vec::new());
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{
+        geometry::{GlobalPath, SurfaceGeometry},
+        objects::Surface,
+        services::Services,
+    };
+
+    use super::CurveSurfaceIntersection;
+
+    #[test]
+    fn line_plane() {
+        let services = Services::new();
+        let xy_plane = services.objects.surfaces.xy_plane();
+
+        let (line, _) =
+            GlobalPath::line_from_points([[1., 2., -1.], [1., 2., 1.]]);
+        assert_eq!(
+            CurveSurfaceIntersection::compute(&line, &xy_plane),
+            Some(CurveSurfaceIntersection::Points {
+                points: vec![(Point::from([0.5]), Point::from([1., 2.]))],
+            }),
+        );
+
+        let (parallel, _) =
+            GlobalPath::line_from_points([[0., 0., 1.], [1., 0., 1.]]);
+        assert_eq!(
+            CurveSurfaceIntersection::compute(&parallel, &xy_plane),
+            None
+        );
+
+        let (coincident, _) =
+            GlobalPath::line_from_points([[0., 0., 0.], [1., 0., 0.]]);
+        assert_eq!(
+            CurveSurfaceIntersection::compute(&coincident, &xy_plane),
+            Some(CurveSurfaceIntersection::Coincident),
+        );
+    }
+
+    #[test]
+    fn circle_plane() {
+        let services = Services::new();
+        let xy_plane = services.objects.surfaces.xy_plane();
+        let yz_plane = services.objects.surfaces.yz_plane();
+
+        let circle = GlobalPath::circle_from_radius(1.);
+        assert_eq!(
+            CurveSurfaceIntersection::compute(&circle, &xy_plane),
+            Some(CurveSurfaceIntersection::Coincident),
+        );
+
+        let Some(CurveSurfaceIntersection::Points { points }) =
+            CurveSurfaceIntersection::compute(&circle, &yz_plane)
+        else {
+            panic!("Expected circle to intersect plane at points");
+        };
+        assert_eq!(points.len(), 2);
+        for (point_on_curve, _) in points {
+            let point = circle.point_from_path_coords(point_on_curve);
+            assert!(point.x.abs() < Scalar::from(1e-12));
+        }
+    }
+
+    #[test]
+    fn line_cylinder() {
+        let cylinder = Surface::new(SurfaceGeometry {
+            u: GlobalPath::circle_from_radius(1.),
+            v: Vector::unit_z(),
+        });
+
+        let (line, _) =
+            GlobalPath::line_from_points([[-2., 0., 0.5], [2., 0., 0.5]]);
+        assert_eq!(CurveSurfaceIntersection::compute(&line, &cylinder), None);
+    }
+}
@@ -26,12 +26,20 @@ impl Intersect for (&Face, &Point<2>) {
             // "seam" of the polygon, i.e. the vertex between the last and the
             // first segment. The logic in the loop properly takes care of that,
             // as long as we initialize the `previous_hit` variable with the
-            // result of the last segment.
+            // result of the last segment that isn't parallel to the ray. The
+            // loop ignores parallel segments, so this is the last result it
+            // would have seen before wrapping around.
             let mut previous_hit = cycle
                 .half_edges()
                 .iter()
-                .last()
-                .and_then(|edge| (&ray, edge).intersect());
+                .rev()
+                .map(|edge| (&ray, edge).intersect())
+                .find(|hit| {
+                    *hit != Some(
+                        RaySegmentIntersection::RayHitsSegmentAndAreParallel,
+                    )
+                })
+                .flatten();
 
             for (edge, next_edge) in cycle.half_edges().pairs() {
                 let hit = (&ray, edge).intersect();
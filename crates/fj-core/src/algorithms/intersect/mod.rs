@@ -5,8 +5,10 @@ pub mod ray_edge;
 pub mod ray_face;
 pub mod ray_segment;
 
+mod curve_curve;
 mod curve_edge;
 mod curve_face;
+mod curve_surface;
 mod face_face;
 mod line_segment;
 mod surface_surface;
@@ -14,8 +16,10 @@ mod surface_surface;
 use fj_math::{Point, Vector};
 
 pub use self::{
+    curve_curve::CurveCurveIntersection,
     curve_edge::CurveEdgeIntersection,
     curve_face::{CurveFaceIntersection, CurveFaceIntersectionInterval},
+    curve_surface::CurveSurfaceIntersection,
     face_face::FaceFaceIntersection,
     line_segment::LineSegmentIntersection,
     surface_surface::SurfaceSurfaceIntersection,
@@ -22,6 +22,12 @@ impl Intersect for (&HorizontalRayToTheRight<2>, &Handle<HalfEdge>) {
             SurfacePath::Circle(_) => {
                 todo!("Casting rays against circles is not supported yet")
             }
+            SurfacePath::Spiral(_) | SurfacePath::Involute(_) => {
+                // A ray can hit a spiral or involute any number of times, which
+                // a single `RaySegmentIntersection` can't express. This is not
+                // supported, and treated as the ray missing the edge.
+                return None;
+            }
         };
 
         let points = edge
@@ -18,9 +18,15 @@ impl Intersect for (&HorizontalRayToTheRight<3>, &Face) {
         let (ray, face) = self;
 
         let plane = match face.surface().geometry().u {
-            GlobalPath::Circle(_) => todo!(
-                "Casting a ray against a swept circle is not supported yet"
+            GlobalPath::Circle(_) | GlobalPath::Ellipse(_) => todo!(
+                "Casting a ray against a swept curve is not supported yet"
             ),
+            GlobalPath::Spiral(_) | GlobalPath::Involute(_) => {
+                // A ray can hit a surface that is swept from a spiral or
+                // involute any number of times. This is not supported, and
+                // treated as the ray missing the face.
+                return None;
+            }
             GlobalPath::Line(line) => Plane::from_parametric(
                 line.origin(),
                 line.direction(),
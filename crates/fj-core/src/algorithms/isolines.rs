@@ -0,0 +1,315 @@
+//! Extraction of iso-parameter curves from faces
+//!
+//! An isoline is a curve on a surface, along which one of the surface's
+//! parameters is constant. Isolines that are clipped to the boundary of a face
+//! are useful for displaying the shape of the face, and as construction
+//! geometry, for example to place features along the axis of a cylinder.
+
+use std::iter;
+
+use fj_math::{Point, Scalar};
+
+use crate::{geometry::CurveBoundary, objects::Face};
+
+use super::approx::{face::FaceApprox, Approx, Tolerance};
+
+/// Extract isolines from a face
+pub trait Isolines {
+    /// Extract the isolines at the provided parameter values
+    ///
+    /// Returns one [`Isoline`] per value, in the same order. Isolines that
+    /// don't touch the face have no polylines.
+    fn isolines(
+        &self,
+        parameter: Parameter,
+        values: impl IntoIterator<Item = impl Into<Scalar>>,
+        tolerance: impl Into<Tolerance>,
+    ) -> Vec<Isoline>;
+
+    /// Extract evenly spaced isolines
+    ///
+    /// Places `count` isolines within the range of the parameter that the face
+    /// covers, keeping the same distance from each other as from the ends of
+    /// that range.
+    fn isolines_evenly_spaced(
+        &self,
+        parameter: Parameter,
+        count: usize,
+        tolerance: impl Into<Tolerance>,
+    ) -> Vec<Isoline>;
+}
+
+impl Isolines for Face {
+    fn isolines(
+        &self,
+        parameter: Parameter,
+        values: impl IntoIterator<Item = impl Into<Scalar>>,
+        tolerance: impl Into<Tolerance>,
+    ) -> Vec<Isoline> {
+        let tolerance = tolerance.into();
+        let approx = self.approx(tolerance);
+
+        values
+            .into_iter()
+            .map(|value| isoline(&approx, parameter, value.into(), tolerance))
+            .collect()
+    }
+
+    fn isolines_evenly_spaced(
+        &self,
+        parameter: Parameter,
+        count: usize,
+        tolerance: impl Into<Tolerance>,
+    ) -> Vec<Isoline> {
+        let tolerance = tolerance.into();
+        let approx = self.approx(tolerance);
+
+        let values = boundary(&approx)
+            .map(|[a, _]| parameter.of(a))
+            .collect::<Vec<_>>();
+        let (Some(min), Some(max)) =
+            (values.iter().min().copied(), values.iter().max().copied())
+        else {
+            return Vec::new();
+        };
+
+        let step = (max - min) / Scalar::from_u64(count as u64 + 1);
+        (1..=count)
+            .map(|i| {
+                let value = min + step * Scalar::from_u64(i as u64);
+                isoline(&approx, parameter, value, tolerance)
+            })
+            .collect()
+    }
+}
+
+/// The surface parameter that is constant along an isoline
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Parameter {
+    /// The u-parameter is constant; the isoline runs along the v-axis
+    U,
+
+    /// The v-parameter is constant; the isoline runs along the u-axis
+    V,
+}
+
+impl Parameter {
+    /// Access the value of this parameter in the provided surface point
+    pub fn of(&self, point: Point<2>) -> Scalar {
+        match self {
+            Self::U => point.u,
+            Self::V => point.v,
+        }
+    }
+
+    fn other(&self) -> Self {
+        match self {
+            Self::U => Self::V,
+            Self::V => Self::U,
+        }
+    }
+
+    fn surface_point(&self, value: Scalar, other: Scalar) -> Point<2> {
+        match self {
+            Self::U => Point::from([value, other]),
+            Self::V => Point::from([other, value]),
+        }
+    }
+}
+
+/// An isoline, clipped to the boundary of a face
+#[derive(Clone, Debug)]
+pub struct Isoline {
+    /// The parameter that is constant along the isoline
+    pub parameter: Parameter,
+
+    /// The value of the constant parameter
+    pub value: Scalar,
+
+    /// The ranges of the other parameter that are within the face
+    ///
+    /// Sorted in ascending order. Each range is also in ascending order.
+    pub ranges: Vec<[Scalar; 2]>,
+
+    /// Polylines that approximate the isoline, one per range
+    pub polylines: Vec<Vec<Point<3>>>,
+}
+
+fn isoline(
+    face: &FaceApprox,
+    parameter: Parameter,
+    value: Scalar,
+    tolerance: Tolerance,
+) -> Isoline {
+    // Points that are exactly on the isoline are treated as being above it.
+    // That way, crossings at vertices of the boundary are only counted once.
+    let mut crossings = boundary(face)
+        .filter_map(|[a, b]| {
+            let [value_a, value_b] = [a, b].map(|point| parameter.of(point));
+            if (value_a >= value) == (value_b >= value) {
+                return None;
+            }
+
+            let other = parameter.other();
+            let t = (value - value_a) / (value_b - value_a);
+            Some(other.of(a) + (other.of(b) - other.of(a)) * t)
+        })
+        .collect::<Vec<_>>();
+    crossings.sort();
+
+    let ranges = crossings
+        .chunks_exact(2)
+        .map(|range| [range[0], range[1]])
+        .filter(|[start, end]| start < end)
+        .collect::<Vec<_>>();
+
+    let polylines = ranges
+        .iter()
+        .map(|&range| polyline(face, parameter, value, range, tolerance))
+        .collect();
+
+    Isoline {
+        parameter,
+        value,
+        ranges,
+        polylines,
+    }
+}
+
+fn polyline(
+    face: &FaceApprox,
+    parameter: Parameter,
+    value: Scalar,
+    [start, end]: [Scalar; 2],
+    tolerance: Tolerance,
+) -> Vec<Point<3>> {
+    // Surfaces are straight along the v-axis, so only isolines that run along
+    // the u-axis can be curved.
+    let interior = match parameter {
+        Parameter::U => Vec::new(),
+        Parameter::V => {
+            let range = CurveBoundary::from([[start], [end]]);
+            (face.surface.u, range)
+                .approx(tolerance)
+                .into_iter()
+                .map(|(point, _)| point.t)
+                .collect()
+        }
+    };
+
+    iter::once(start)
+        .chain(interior)
+        .chain(iter::once(end))
+        .map(|other| {
+            face.surface.point_from_surface_coords(
+                parameter.surface_point(value, other),
+            )
+        })
+        .collect()
+}
+
+/// Iterate over the segments of all boundary cycles, in surface coordinates
+fn boundary(face: &FaceApprox) -> impl Iterator<Item = [Point<2>; 2]> + '_ {
+    iter::once(&face.exterior)
+        .chain(&face.interiors)
+        .flat_map(|cycle| {
+            let points = cycle
+                .points()
+                .into_iter()
+                .map(|point| point.local_form)
+                .collect::<Vec<_>>();
+
+            points
+                .windows(2)
+                .map(|points| [points[0], points[1]])
+                .collect::<Vec<_>>()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::approx::Tolerance,
+        geometry::GlobalPath,
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    use super::{Isolines, Parameter};
+
+    #[test]
+    fn isolines_of_planar_face() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let solid = Solid::box_from_dims([1., 2., 3.], &mut services)
+            .insert(&mut services);
+        let face = solid.shells().first().faces().first();
+
+        for parameter in [Parameter::U, Parameter::V] {
+            let isolines = face.isolines_evenly_spaced(parameter, 3, tolerance);
+            assert_eq!(isolines.len(), 3);
+
+            for isoline in isolines {
+                assert_eq!(isoline.ranges.len(), 1);
+                assert_eq!(isoline.polylines.len(), 1);
+                assert_eq!(isoline.polylines[0].len(), 2);
+            }
+        }
+
+        // Far outside of the face.
+        let isolines = face.isolines(Parameter::U, [100.], tolerance);
+        assert!(isolines[0].polylines.is_empty());
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn isolines_of_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let solid =
+            Solid::cylinder(1., 2., &mut services).insert(&mut services);
+        let side = solid
+            .shells()
+            .first()
+            .faces()
+            .iter()
+            .find(|face| {
+                matches!(face.surface().geometry().u, GlobalPath::Circle(_))
+            })
+            .expect("Expected cylinder to have curved side");
+
+        // Isolines along the v-axis are straight, and run along the axis of the
+        // cylinder.
+        for isoline in side.isolines_evenly_spaced(Parameter::U, 4, tolerance) {
+            let [polyline] = isoline.polylines.as_slice() else {
+                panic!("Expected one polyline per isoline");
+            };
+            let [a, b] = polyline.as_slice() else {
+                panic!("Expected straight isoline");
+            };
+            assert_eq!(a.distance_to(b), Scalar::from(2.));
+        }
+
+        // Isolines along the u-axis are circular arcs.
+        for isoline in side.isolines_evenly_spaced(Parameter::V, 2, tolerance) {
+            for polyline in &isoline.polylines {
+                assert!(polyline.len() > 2);
+
+                for point in polyline {
+                    let radius = point.coords.xy().magnitude();
+                    assert!((radius - Scalar::ONE).abs() < Scalar::from(1e-9));
+                }
+            }
+        }
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,357 @@
+//! Lattice infill
+//!
+//! Fills the interior of a shape with a lattice structure, as is commonly done
+//! to make 3D-printed parts lighter, while keeping them stiff.
+//!
+//! The lattice is defined as an implicit field, which is intersected with the
+//! shape and converted into a triangle mesh using marching tetrahedra. The
+//! result is a closed mesh that is suitable for export.
+//!
+//! # Implementation Note
+//!
+//! Only mesh output is supported right now. Lattices are made up of a huge
+//! number of faces, most of which are curved. Representing them in the b-rep
+//! kernel would require support for more general surfaces than it currently
+//! provides.
+
+use fj_interop::mesh::{Color, Mesh};
+use fj_math::{Aabb, Point, Scalar, Triangle, Vector};
+
+use super::{classify::Classifier, triangulate::Triangulate};
+
+/// Fill a shape with a lattice
+pub trait Infill: Sized {
+    /// Fill the shape with the provided lattice
+    ///
+    /// Returns the part of the lattice that is inside of the shape. The shape
+    /// itself is not part of the result.
+    fn infill(self, lattice: Lattice) -> Mesh<Point<3>>;
+}
+
+impl<T> Infill for T
+where
+    T: Triangulate,
+{
+    fn infill(self, lattice: Lattice) -> Mesh<Point<3>> {
+        let mesh = self.triangulate();
+        if mesh.triangles().next().is_none() {
+            return Mesh::new();
+        }
+        let classifier = Classifier::new(&mesh);
+
+        let field = |point: Point<3>| {
+            // The intersection of two implicit shapes is the maximum of their
+            // fields.
+            Scalar::max(lattice.field(point), classifier.signed_distance(point))
+        };
+
+        let aabb = Aabb::<3>::from_points(mesh.vertices());
+        let grid = Grid::new(aabb, lattice.cell_size() / SAMPLES_PER_CELL);
+        grid.polygonize(field)
+    }
+}
+
+/// A lattice that can fill a shape
+#[derive(Clone, Copy, Debug)]
+pub enum Lattice {
+    /// A gyroid, thickened into a sheet
+    ///
+    /// The gyroid is a triply periodic minimal surface. It is self-supporting,
+    /// which makes it popular as infill for 3D printing.
+    Gyroid {
+        /// The size of a single cell of the lattice
+        cell_size: Scalar,
+
+        /// The thickness of the sheet
+        thickness: Scalar,
+    },
+
+    /// A cubic grid of struts, with square cells
+    Struts {
+        /// The size of a single cell of the lattice
+        cell_size: Scalar,
+
+        /// The diameter of the struts
+        diameter: Scalar,
+    },
+}
+
+impl Lattice {
+    /// Access the size of a single cell of the lattice
+    pub fn cell_size(&self) -> Scalar {
+        match self {
+            Self::Gyroid { cell_size, .. } | Self::Struts { cell_size, .. } => {
+                *cell_size
+            }
+        }
+    }
+
+    /// Evaluate the field of the lattice at the provided point
+    ///
+    /// The field is negative inside of the lattice and positive outside. Its
+    /// value approximates the distance to the surface of the lattice.
+    pub fn field(&self, point: Point<3>) -> Scalar {
+        match *self {
+            Self::Gyroid {
+                cell_size,
+                thickness,
+            } => {
+                let k = Scalar::TAU / cell_size;
+                let [(sin_x, cos_x), (sin_y, cos_y), (sin_z, cos_z)] =
+                    [point.x, point.y, point.z].map(|c| (c * k).sin_cos());
+
+                let gyroid = sin_x * cos_y + sin_y * cos_z + sin_z * cos_x;
+
+                // The gradient of the gyroid function has a length of roughly
+                // `k` near its surface, so dividing by that results in an
+                // approximate distance.
+                gyroid.abs() / k - thickness / 2.
+            }
+            Self::Struts {
+                cell_size,
+                diameter,
+            } => {
+                let [dx, dy, dz] = [point.x, point.y, point.z].map(|c| {
+                    let c = c.rem_euclid(cell_size);
+                    if c < cell_size - c {
+                        c
+                    } else {
+                        cell_size - c
+                    }
+                });
+
+                let distance = [[dy, dz], [dx, dz], [dx, dy]]
+                    .map(|[a, b]| (a * a + b * b).sqrt())
+                    .into_iter()
+                    .min()
+                    .expect("Array is not empty");
+
+                distance - diameter / 2.
+            }
+        }
+    }
+}
+
+/// The number of samples per lattice cell, along each axis
+const SAMPLES_PER_CELL: f64 = 16.;
+
+/// A regular grid of samples, that covers a bounding box
+struct Grid {
+    origin: Point<3>,
+    step: Scalar,
+    size: [usize; 3],
+}
+
+impl Grid {
+    fn new(aabb: Aabb<3>, step: Scalar) -> Self {
+        // Make sure that the samples at the border of the grid are outside of
+        // the shape, so the resulting mesh is closed.
+        let margin = Vector::from([step, step, step]);
+        let origin = aabb.min - margin;
+        let extent = aabb.max + margin - origin;
+
+        let size = [extent.x, extent.y, extent.z]
+            .map(|extent| (extent / step).ceil().into_u64() as usize + 1);
+
+        Self { origin, step, size }
+    }
+
+    fn index(&self, [i, j, k]: [usize; 3]) -> usize {
+        let [nx, ny, _] = self.size;
+        i + nx * (j + ny * k)
+    }
+
+    fn position(&self, [i, j, k]: [usize; 3]) -> Point<3> {
+        self.origin
+            + Vector::from([i, j, k].map(|i| Scalar::from_u64(i as u64)))
+                * self.step
+    }
+
+    /// Convert the surface of the field into a mesh, using marching tetrahedra
+    fn polygonize(&self, field: impl Fn(Point<3>) -> Scalar) -> Mesh<Point<3>> {
+        let [nx, ny, nz] = self.size;
+
+        let mut values = vec![Scalar::ZERO; nx * ny * nz];
+        for k in 0..nz {
+            for j in 0..ny {
+                for i in 0..nx {
+                    values[self.index([i, j, k])] =
+                        field(self.position([i, j, k]));
+                }
+            }
+        }
+
+        // Each cube of the grid is split into six tetrahedra around its main
+        // diagonal. Neighboring cubes split their shared faces the same way,
+        // which keeps the resulting mesh closed.
+        const TETRAHEDRA: [[usize; 4]; 6] = [
+            [0, 1, 3, 7],
+            [0, 3, 2, 7],
+            [0, 2, 6, 7],
+            [0, 6, 4, 7],
+            [0, 4, 5, 7],
+            [0, 5, 1, 7],
+        ];
+
+        let mut mesh = Mesh::new();
+
+        for k in 0..nz - 1 {
+            for j in 0..ny - 1 {
+                for i in 0..nx - 1 {
+                    let corners = [0, 1, 2, 3, 4, 5, 6, 7].map(|corner| {
+                        let index = [
+                            i + (corner & 1),
+                            j + (corner >> 1 & 1),
+                            k + (corner >> 2 & 1),
+                        ];
+
+                        let position = self.position(index);
+                        let index = self.index(index);
+
+                        Sample {
+                            index,
+                            position,
+                            value: values[index],
+                        }
+                    });
+
+                    for tetrahedron in TETRAHEDRA {
+                        let tetrahedron =
+                            tetrahedron.map(|corner| corners[corner]);
+                        polygonize_tetrahedron(tetrahedron, &mut mesh);
+                    }
+                }
+            }
+        }
+
+        mesh
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Sample {
+    index: usize,
+    position: Point<3>,
+    value: Scalar,
+}
+
+impl Sample {
+    fn is_inside(&self) -> bool {
+        self.value < Scalar::ZERO
+    }
+}
+
+fn polygonize_tetrahedron(samples: [Sample; 4], mesh: &mut Mesh<Point<3>>) {
+    let (inside, outside): (Vec<_>, Vec<_>) =
+        samples.into_iter().partition(Sample::is_inside);
+
+    let points = match (inside.as_slice(), outside.as_slice()) {
+        ([a], [b, c, d]) | ([b, c, d], [a]) => {
+            vec![crossing(a, b), crossing(a, c), crossing(a, d)]
+        }
+        ([a, b], [c, d]) => {
+            vec![
+                crossing(a, c),
+                crossing(a, d),
+                crossing(b, d),
+                crossing(b, c),
+            ]
+        }
+        _ => return,
+    };
+
+    // Orient the triangles, so they face from the inside of the field to the
+    // outside.
+    let center = |samples: &[Sample]| {
+        samples
+            .iter()
+            .fold(Vector::from([0., 0., 0.]), |sum, sample| {
+                sum + sample.position.coords
+            })
+            / Scalar::from_u64(samples.len() as u64)
+    };
+    let outwards = center(&outside) - center(&inside);
+
+    for [a, b, c] in [[0, 1, 2], [0, 2, 3]] {
+        let (Some(&a), Some(&b), Some(&c)) =
+            (points.get(a), points.get(b), points.get(c))
+        else {
+            continue;
+        };
+
+        let Ok(triangle) = Triangle::from_points([a, b, c]) else {
+            continue;
+        };
+        let triangle = if triangle.normal().dot(&outwards) < Scalar::ZERO {
+            Triangle::from_points([a, c, b])
+                .expect("Reversing a valid triangle keeps it valid")
+        } else {
+            triangle
+        };
+
+        mesh.push_triangle(triangle, Color::default());
+    }
+}
+
+/// Compute the point where the field crosses zero, between two samples
+///
+/// The samples are ordered first, so the result is exactly the same for all
+/// tetrahedra that share the edge.
+fn crossing(a: &Sample, b: &Sample) -> Point<3> {
+    let [a, b] = if a.index < b.index { [a, b] } else { [b, a] };
+
+    let t = a.value / (a.value - b.value);
+    a.position + (b.position - a.position) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::approx::Tolerance,
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    use super::{Infill, Lattice};
+
+    #[test]
+    fn infill_box() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = Solid::box_from_dims([4., 4., 4.], &mut services)
+            .insert(&mut services);
+        let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
+
+        let lattice = Lattice::Struts {
+            cell_size: Scalar::from(2.),
+            diameter: Scalar::from(0.5),
+        };
+        let mesh = (&*solid, tolerance).infill(lattice);
+
+        assert!(mesh.triangles().next().is_some());
+        for vertex in mesh.vertices() {
+            assert!(vertex.x.abs() <= Scalar::from(2.1));
+            assert!(vertex.y.abs() <= Scalar::from(2.1));
+            assert!(vertex.z >= Scalar::from(-0.1));
+            assert!(vertex.z <= Scalar::from(4.1));
+        }
+
+        // Sum of the signed volumes of the tetrahedra that are formed by each
+        // triangle and the origin. This is only positive, if the triangles are
+        // facing outwards.
+        let volume = mesh
+            .triangles()
+            .map(|triangle| {
+                let [a, b, c] = triangle.inner.points();
+                a.coords.dot(&b.coords.cross(&c.coords)) / 6.
+            })
+            .fold(Scalar::ZERO, |volume, v| volume + v);
+        assert!(volume > Scalar::ZERO);
+        assert!(volume < Scalar::from(64.));
+
+        Ok(())
+    }
+}
@@ -12,6 +12,14 @@
 
 pub mod approx;
 pub mod bounding_volume;
+pub mod classify;
+pub mod constraints;
+pub mod debug;
+pub mod drawing;
 pub mod intersect;
+pub mod isolines;
+pub mod lattice;
+pub mod nest;
+pub mod slice;
 pub mod transform;
 pub mod triangulate;
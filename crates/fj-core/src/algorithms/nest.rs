@@ -0,0 +1,366 @@
+//! Nesting of 2D regions
+//!
+//! Arranges the regions of a sketch on a rectangular sheet, as is commonly done
+//! when preparing parts for laser cutting.
+//!
+//! # Implementation Note
+//!
+//! Regions are nested by their bounding boxes, using a first-fit
+//! decreasing-height shelf algorithm. This is fast and predictable, but wastes
+//! material for parts that are far from rectangular. Placing parts by their
+//! actual outlines would require polygon offsetting and collision detection,
+//! which are not available yet.
+
+use fj_math::{Aabb, Point, Scalar, Transform, Vector};
+
+use crate::objects::Sketch;
+
+use super::bounding_volume::BoundingVolume;
+
+/// Arrange the regions of a sketch on a sheet
+pub trait Nest {
+    /// Arrange the regions on the provided sheet
+    ///
+    /// Returns one placement per region, in the order of the regions. Regions
+    /// that don't fit on the sheet are not placed.
+    fn nest(&self, sheet: Sheet) -> Nesting;
+}
+
+impl Nest for Sketch {
+    fn nest(&self, sheet: Sheet) -> Nesting {
+        let parts = self
+            .regions()
+            .iter()
+            .map(|region| region.exterior().aabb())
+            .collect::<Vec<_>>();
+
+        let orientations: &[Orientation] = if sheet.allow_rotation {
+            &[Orientation::Flat, Orientation::Upright]
+        } else {
+            &[Orientation::Original]
+        };
+
+        // Place the tallest parts first, so they define the height of the
+        // shelves that the smaller parts can then fill.
+        let mut order = (0..parts.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| {
+            parts[i].map(|aabb| -orientations[0].size(&aabb).v)
+        });
+
+        let mut shelves = Vec::<Shelf>::new();
+        let mut placements = vec![None; parts.len()];
+
+        for i in order {
+            let Some(aabb) = parts[i] else {
+                continue;
+            };
+
+            placements[i] = orientations.iter().find_map(|orientation| {
+                let size = orientation.size(&aabb);
+                let position = sheet.place(&mut shelves, size)?;
+                Some(Placement::new(&aabb, *orientation, position))
+            });
+        }
+
+        Nesting { placements }
+    }
+}
+
+/// A rectangular sheet that regions can be nested on
+#[derive(Clone, Copy, Debug)]
+pub struct Sheet {
+    /// The size of the sheet
+    ///
+    /// The sheet extends from the origin into the positive x and y directions.
+    pub size: Vector<2>,
+
+    /// The minimum distance between parts, and between parts and the edges of
+    /// the sheet
+    pub spacing: Scalar,
+
+    /// Whether parts may be rotated by 90 degrees, to make them fit better
+    pub allow_rotation: bool,
+}
+
+impl Sheet {
+    /// Construct a sheet of the provided size
+    ///
+    /// The sheet has no spacing, and allows parts to be rotated.
+    pub fn new(size: impl Into<Vector<2>>) -> Self {
+        Self {
+            size: size.into(),
+            spacing: Scalar::ZERO,
+            allow_rotation: true,
+        }
+    }
+
+    /// Set the spacing between parts
+    pub fn with_spacing(mut self, spacing: impl Into<Scalar>) -> Self {
+        self.spacing = spacing.into();
+        self
+    }
+
+    /// Set whether parts may be rotated
+    pub fn with_rotation(mut self, allow_rotation: bool) -> Self {
+        self.allow_rotation = allow_rotation;
+        self
+    }
+
+    fn place(
+        &self,
+        shelves: &mut Vec<Shelf>,
+        size: Vector<2>,
+    ) -> Option<Point<2>> {
+        let max_x = self.size.u - self.spacing;
+        let max_y = self.size.v - self.spacing;
+
+        for shelf in shelves.iter_mut() {
+            if size.v <= shelf.height && shelf.next_x + size.u <= max_x {
+                let position = Point::from([shelf.next_x, shelf.y]);
+                shelf.next_x = shelf.next_x + size.u + self.spacing;
+                return Some(position);
+            }
+        }
+
+        let y = shelves
+            .last()
+            .map(|shelf| shelf.y + shelf.height + self.spacing)
+            .unwrap_or(self.spacing);
+        if self.spacing + size.u > max_x || y + size.v > max_y {
+            return None;
+        }
+
+        shelves.push(Shelf {
+            y,
+            height: size.v,
+            next_x: self.spacing + size.u + self.spacing,
+        });
+        Some(Point::from([self.spacing, y]))
+    }
+}
+
+/// The result of nesting regions on a sheet
+///
+/// Returned by [`Nest::nest`].
+#[derive(Clone, Debug)]
+pub struct Nesting {
+    /// The placements of the regions, in the order of the regions
+    ///
+    /// `None`, if the region didn't fit on the sheet.
+    pub placements: Vec<Option<Placement>>,
+}
+
+impl Nesting {
+    /// Indicate whether all regions have been placed
+    pub fn is_complete(&self) -> bool {
+        self.placements.iter().all(Option::is_some)
+    }
+
+    /// Iterate over the indices of the regions that have not been placed
+    pub fn unplaced(&self) -> impl Iterator<Item = usize> + '_ {
+        self.placements
+            .iter()
+            .enumerate()
+            .filter_map(|(i, placement)| placement.is_none().then_some(i))
+    }
+}
+
+/// The placement of a region on a sheet
+#[derive(Clone, Copy, Debug)]
+pub struct Placement {
+    /// The transform that moves the region from the sketch onto the sheet
+    pub transform: Transform,
+
+    /// Whether the region has been rotated by 90 degrees
+    pub rotated: bool,
+
+    /// The bounding box of the placed region, in sheet coordinates
+    pub aabb: Aabb<2>,
+}
+
+impl Placement {
+    fn new(
+        aabb: &Aabb<2>,
+        orientation: Orientation,
+        position: Point<2>,
+    ) -> Self {
+        let rotated = orientation.is_rotated(aabb);
+
+        // Rotating by 90 degrees maps `(x, y)` to `(-y, x)`.
+        let (rotation, min) = if rotated {
+            (
+                Transform::rotation(Vector::unit_z() * Scalar::PI / 2.),
+                Point::from([-aabb.max.v, aabb.min.u]),
+            )
+        } else {
+            (Transform::identity(), aabb.min)
+        };
+
+        let offset = position - min;
+        let transform =
+            Transform::translation([offset.u, offset.v, Scalar::ZERO])
+                * rotation;
+
+        Self {
+            transform,
+            rotated,
+            aabb: Aabb {
+                min: position,
+                max: position + orientation.size(aabb),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Orientation {
+    /// As the region is in the sketch
+    Original,
+
+    /// Rotated, if necessary, so the region is at least as wide as it is high
+    Flat,
+
+    /// Rotated, if necessary, so the region is at least as high as it is wide
+    Upright,
+}
+
+impl Orientation {
+    fn is_rotated(&self, aabb: &Aabb<2>) -> bool {
+        let size = aabb.max - aabb.min;
+
+        match self {
+            Self::Original => false,
+            Self::Flat => size.v > size.u,
+            Self::Upright => size.u > size.v,
+        }
+    }
+
+    fn size(&self, aabb: &Aabb<2>) -> Vector<2> {
+        let size = aabb.max - aabb.min;
+
+        if self.is_rotated(aabb) {
+            Vector::from([size.v, size.u])
+        } else {
+            size
+        }
+    }
+}
+
+struct Shelf {
+    y: Scalar,
+    height: Scalar,
+    next_x: Scalar,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        objects::{Region, Sketch},
+        operations::{build::BuildRegion, insert::Insert},
+        services::Services,
+        storage::Handle,
+    };
+
+    use super::{Nest, Nesting, Sheet};
+
+    #[test]
+    fn nest_rectangles() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let sketch = Sketch::new(
+            [[2., 1.], [1., 2.], [2., 1.], [6., 1.]]
+                .map(|size| rectangle(size, &mut services)),
+        );
+
+        let sheet = Sheet::new([7., 4.]).with_spacing(0.5);
+        let nesting = sketch.nest(sheet);
+        assert_eq!(nesting.unplaced().collect::<Vec<_>>(), vec![3]);
+        assert_valid(&nesting, sheet);
+
+        // The second part only fits, if it is rotated.
+        let sheet = Sheet::new([5., 1.5]).with_spacing(0.25);
+        let nesting = sketch.nest(sheet.with_rotation(false));
+        assert_eq!(nesting.unplaced().collect::<Vec<_>>(), vec![1, 3]);
+        let nesting = sketch.nest(sheet);
+        assert!(nesting.placements[1].is_some_and(|p| p.rotated));
+        assert_valid(&nesting, sheet);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn nest_transforms() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let sketch = Sketch::new(
+            [[2., 1.], [1., 2.], [3., 3.]]
+                .map(|size| rectangle(size, &mut services)),
+        );
+
+        let nesting = sketch.nest(Sheet::new([10., 10.]));
+        assert!(nesting.is_complete());
+
+        // The transforms must move the regions to where the placements say.
+        for (region, placement) in
+            sketch.regions().iter().zip(&nesting.placements)
+        {
+            let placement = placement.expect("All regions are placed");
+
+            for half_edge in region.exterior().half_edges() {
+                let point = half_edge.start_position();
+                let point =
+                    placement.transform.transform_point(&Point::from([
+                        point.u,
+                        point.v,
+                        Scalar::ZERO,
+                    ]));
+
+                let [min, max] = [placement.aabb.min, placement.aabb.max];
+                let epsilon = Scalar::from(1e-9);
+                assert!(point.x > min.u - epsilon && point.x < max.u + epsilon);
+                assert!(point.y > min.v - epsilon && point.y < max.v + epsilon);
+            }
+        }
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    fn rectangle(
+        [width, height]: [f64; 2],
+        services: &mut Services,
+    ) -> Handle<Region> {
+        Region::polygon(
+            [[0., 0.], [width, 0.], [width, height], [0., height]],
+            services,
+        )
+        .insert(services)
+    }
+
+    fn assert_valid(nesting: &Nesting, sheet: Sheet) {
+        let aabbs = nesting
+            .placements
+            .iter()
+            .flatten()
+            .map(|placement| placement.aabb)
+            .collect::<Vec<_>>();
+        let spacing = sheet.spacing;
+
+        for (i, a) in aabbs.iter().enumerate() {
+            assert!(a.min.u >= spacing && a.min.v >= spacing);
+            assert!(a.max.u <= sheet.size.u - spacing);
+            assert!(a.max.v <= sheet.size.v - spacing);
+
+            for b in &aabbs[i + 1..] {
+                let separated = a.max.u + spacing <= b.min.u
+                    || b.max.u + spacing <= a.min.u
+                    || a.max.v + spacing <= b.min.v
+                    || b.max.v + spacing <= a.min.v;
+                assert!(separated, "{a:?} overlaps {b:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Slicing of shapes into layers
+//!
+//! Sections a shape at a sequence of heights along the z-axis, resulting in
+//! closed 2D polygons for each layer. This is groundwork for generating
+//! toolpaths for 3D printing, or showing layer previews.
+
+use std::collections::BTreeMap;
+
+use fj_interop::mesh::Mesh;
+use fj_math::{Point, PolyChain, Scalar, Vector};
+
+use super::triangulate::Triangulate;
+
+/// Slice a shape into layers
+pub trait Slice: Sized {
+    /// Section the shape at the provided heights
+    ///
+    /// Returns one [`Layer`] per height, in the same order.
+    fn slice(
+        self,
+        heights: impl IntoIterator<Item = impl Into<Scalar>>,
+    ) -> Vec<Layer>;
+}
+
+impl<T> Slice for T
+where
+    T: Triangulate,
+{
+    fn slice(
+        self,
+        heights: impl IntoIterator<Item = impl Into<Scalar>>,
+    ) -> Vec<Layer> {
+        let mesh = self.triangulate();
+
+        heights
+            .into_iter()
+            .map(|z| slice_mesh(&mesh, z.into()))
+            .collect()
+    }
+}
+
+/// A layer of a sliced shape
+#[derive(Clone, Debug)]
+pub struct Layer {
+    /// The height of the layer
+    pub z: Scalar,
+
+    /// The polygons that make up the layer
+    ///
+    /// Each polygon is closed, meaning its last point is the same as its first.
+    /// Polygons that bound the outside of the shape are counter-clockwise,
+    /// polygons that bound holes are clockwise, when viewed from above.
+    pub polygons: Vec<PolyChain<2>>,
+}
+
+fn slice_mesh(mesh: &Mesh<Point<3>>, z: Scalar) -> Layer {
+    // Maps the start point of each segment to the end points of all segments
+    // that start there. Where polygons touch, there is more than one.
+    let mut segments = BTreeMap::<_, Vec<_>>::new();
+
+    for triangle in mesh.triangles() {
+        let points = triangle.inner.points();
+
+        // Points that are exactly on the plane are treated as being above it.
+        // That way, we don't have to handle triangles that just touch the
+        // plane as special cases.
+        let mut crossings = Vec::new();
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+
+            if (a.z >= z) != (b.z >= z) {
+                crossings.push(intersect_edge(a, b, z));
+            }
+        }
+
+        let [start, end] = match crossings.as_slice() {
+            [start, end] => [*start, *end],
+            _ => continue,
+        };
+        if start == end {
+            continue;
+        }
+
+        // Orient the segment, so that the inside of the shape is on its left.
+        let direction = Vector::unit_z().cross(&triangle.inner.normal());
+        let [start, end] = if (end - start).dot(&direction.xy()) >= Scalar::ZERO
+        {
+            [start, end]
+        } else {
+            [end, start]
+        };
+
+        segments.entry(start).or_default().push(end);
+    }
+
+    let mut polygons = Vec::new();
+
+    while let Some(&start) = segments.keys().next() {
+        let mut points = vec![start];
+        let mut next = take_segment(&mut segments, start, None)
+            .expect("Start point was taken from the map of segments");
+
+        while next != start {
+            let previous = points[points.len() - 1];
+            points.push(next);
+
+            let Some(after) =
+                take_segment(&mut segments, next, Some(next - previous))
+            else {
+                // The mesh is not closed. Nothing we can do about that here.
+                break;
+            };
+            next = after;
+        }
+
+        if next == start && points.len() >= 3 {
+            polygons.push(PolyChain::from_points(points).close());
+        }
+    }
+
+    Layer { z, polygons }
+}
+
+/// Remove a segment that starts at `start`, returning its end point
+///
+/// If multiple segments start there, because polygons touch at that point, the
+/// one that turns furthest to the left of the `incoming` direction is chosen.
+/// Since the inside of the shape is on the left of each segment, this keeps
+/// the touching polygons apart.
+fn take_segment(
+    segments: &mut BTreeMap<Point<2>, Vec<Point<2>>>,
+    start: Point<2>,
+    incoming: Option<Vector<2>>,
+) -> Option<Point<2>> {
+    let ends = segments.get_mut(&start)?;
+
+    let index = match incoming {
+        Some(incoming) => (0..ends.len())
+            .max_by_key(|&i| {
+                let outgoing = ends[i] - start;
+                incoming.cross2d(&outgoing).atan2(incoming.dot(&outgoing))
+            })
+            .unwrap_or_default(),
+        None => 0,
+    };
+    let end = ends.swap_remove(index);
+
+    if ends.is_empty() {
+        segments.remove(&start);
+    }
+
+    Some(end)
+}
+
+/// Compute the point where an edge crosses the plane at height `z`
+///
+/// The endpoints are ordered first, so the result is exactly the same for both
+/// triangles that share an edge.
+fn intersect_edge(a: Point<3>, b: Point<3>, z: Scalar) -> Point<2> {
+    let [a, b] = if a <= b { [a, b] } else { [b, a] };
+
+    let t = (z - a.z) / (b.z - a.z);
+    let point = a + (b - a) * t;
+
+    Point::from([point.x, point.y])
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::{PolyChain, Scalar};
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, transform::TransformObject,
+            triangulate::Triangulate,
+        },
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    use super::{slice_mesh, Slice};
+
+    #[test]
+    fn slice_box() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = Solid::box_from_dims([2., 2., 2.], &mut services)
+            .insert(&mut services);
+        let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
+
+        let layers = (&*solid, tolerance).slice([-0.5, 0.5, 1., 1.5, 2.5]);
+        assert_eq!(layers.len(), 5);
+
+        for layer in &layers[1..4] {
+            let [polygon] = layer.polygons.as_slice() else {
+                panic!("Expected one polygon per layer");
+            };
+
+            assert_eq!(signed_area(polygon), Scalar::from(4.));
+        }
+
+        assert!(layers[0].polygons.is_empty());
+        assert!(layers[4].polygons.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn slice_touching_boxes() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // Two boxes that touch along a vertical edge. Their sections touch at
+        // a point, where two segments start.
+        let a = Solid::box_from_dims([2., 2., 2.], &mut services)
+            .insert(&mut services);
+        let b = a.clone().translate([2., 2., 0.], &mut services);
+        let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
+
+        let mut mesh = Mesh::new();
+        (&*a, tolerance).triangulate_into_mesh(&mut mesh);
+        (&*b, tolerance).triangulate_into_mesh(&mut mesh);
+
+        let layer = slice_mesh(&mesh, Scalar::ONE);
+
+        let [p, q] = layer.polygons.as_slice() else {
+            panic!("Expected one polygon per box");
+        };
+        assert_eq!(signed_area(p), Scalar::from(4.));
+        assert_eq!(signed_area(q), Scalar::from(4.));
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    /// Compute the signed area of a polygon, using the shoelace formula
+    fn signed_area(polygon: &PolyChain<2>) -> Scalar {
+        polygon
+            .segments()
+            .into_iter()
+            .map(|segment| {
+                let [a, b] = segment.points();
+                a.coords.cross2d(&b.coords)
+            })
+            .fold(Scalar::ZERO, |area, a| area + a)
+            / 2.
+    }
+}
@@ -0,0 +1,26 @@
+use fj_math::{Plane, Transform};
+
+use crate::{objects::Datum, services::Services};
+
+use super::{TransformCache, TransformObject};
+
+impl TransformObject for Datum {
+    fn transform_with_cache(
+        self,
+        transform: &Transform,
+        _: &mut Services,
+        _: &mut TransformCache,
+    ) -> Self {
+        match self {
+            Self::Point(point) => {
+                Self::Point(transform.transform_point(&point))
+            }
+            Self::Axis(axis) => Self::Axis(transform.transform_line(&axis)),
+            Self::Plane(plane) => Self::Plane(Plane::from_parametric(
+                transform.transform_point(&plane.origin()),
+                transform.transform_vector(&plane.u()),
+                transform.transform_vector(&plane.v()),
+            )),
+        }
+    }
+}
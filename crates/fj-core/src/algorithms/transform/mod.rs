@@ -2,8 +2,10 @@
 
 mod curve;
 mod cycle;
+mod datum;
 mod edge;
 mod face;
+mod region;
 mod shell;
 mod solid;
 mod surface;
@@ -11,10 +13,11 @@ mod vertex;
 
 use std::collections::BTreeMap;
 
-use fj_math::{Transform, Vector};
+use fj_math::{Scalar, Transform, Vector};
 use type_map::TypeMap;
 
 use crate::{
+    objects::{BehindHandle, Datum, Object},
     operations::insert::Insert,
     services::Services,
     storage::{Handle, ObjectId},
@@ -22,6 +25,11 @@ use crate::{
 
 /// Transform an object
 ///
+/// Objects that are referenced from multiple places within the transformed
+/// object, like the curves and vertices that neighboring faces share, are only
+/// transformed once. The transformed objects are shared in the same way as the
+/// original ones were.
+///
 /// # Implementation Note
 ///
 /// So far, a general `transform` method is available, along some convenience
@@ -32,6 +40,8 @@ use crate::{
 pub trait TransformObject: Sized {
     /// Transform the object
     fn transform(self, transform: &Transform, services: &mut Services) -> Self {
+        let _span = tracing::info_span!("transform").entered();
+
         let mut cache = TransformCache::default();
         self.transform_with_cache(transform, services, &mut cache)
     }
@@ -65,11 +75,35 @@ pub trait TransformObject: Sized {
     ) -> Self {
         self.transform(&Transform::rotation(axis_angle), services)
     }
+
+    /// Rotate the object around a datum axis
+    ///
+    /// Convenience wrapper around [`TransformObject::transform`].
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `axis` is not a [`Datum::Axis`].
+    fn rotate_around(
+        self,
+        axis: &Datum,
+        angle: impl Into<Scalar>,
+        services: &mut Services,
+    ) -> Self {
+        let axis = axis.as_axis().expect("Expected datum to be an axis");
+        let origin = axis.origin().coords;
+
+        let transform = Transform::translation(origin)
+            * Transform::rotation(axis.direction().normalize() * angle.into())
+            * Transform::translation(-origin);
+
+        self.transform(&transform, services)
+    }
 }
 
 impl<T> TransformObject for Handle<T>
 where
     T: Clone + Insert<Inserted = Handle<T>> + TransformObject + 'static,
+    Handle<T>: Into<Object<BehindHandle>>,
 {
     fn transform_with_cache(
         self,
@@ -86,6 +120,7 @@ where
             .transform_with_cache(transform, services, cache)
             .insert(services);
 
+        services.record_derivation(transformed.clone(), [self.clone()]);
         cache.insert(self.clone(), transformed.clone());
 
         transformed
@@ -123,3 +158,135 @@ impl TransformCache {
         map.insert(key.id(), value);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, f64::consts::PI};
+
+    use fj_math::{Point, Scalar, Transform};
+
+    use crate::{
+        algorithms::bounding_volume::BoundingVolume,
+        objects::{BehindHandle, Curve, Datum, Object, Shell, Solid, Vertex},
+        operations::{
+            build::{BuildShell, BuildSolid},
+            insert::Insert,
+            update::UpdateSolid,
+        },
+        queries::{VisitObjects, Visitor},
+        services::Services,
+        storage::{Handle, ObjectId},
+        test_support,
+    };
+
+    use super::{TransformCache, TransformObject};
+
+    #[derive(Default)]
+    struct Shared {
+        curves: BTreeSet<ObjectId>,
+        vertices: BTreeSet<ObjectId>,
+    }
+
+    impl Visitor for Shared {
+        fn visit_curve(
+            &mut self,
+            curve: &Handle<Curve>,
+            _: Option<&Object<BehindHandle>>,
+        ) {
+            self.curves.insert(curve.id());
+        }
+
+        fn visit_vertex(
+            &mut self,
+            vertex: &Handle<Vertex>,
+            _: Option<&Object<BehindHandle>>,
+        ) {
+            self.vertices.insert(vertex.id());
+        }
+    }
+
+    #[test]
+    fn transform_preserves_sharing() {
+        let mut services = Services::new();
+
+        let tetrahedron = Shell::tetrahedron(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services)
+        .shell;
+        let solid = Solid::empty()
+            .add_shells([tetrahedron])
+            .insert(&mut services);
+
+        let transformed = solid.clone().translate([1., 2., 3.], &mut services);
+
+        let mut original = Shared::default();
+        solid.visit_objects(&mut original);
+        let mut shared = Shared::default();
+        transformed.visit_objects(&mut shared);
+
+        assert_eq!(shared.curves.len(), 6);
+        assert_eq!(shared.vertices.len(), 4);
+        assert!(shared.curves.is_disjoint(&original.curves));
+        assert!(shared.vertices.is_disjoint(&original.vertices));
+    }
+
+    #[test]
+    fn transform_with_shared_cache_returns_same_object() {
+        let mut services = Services::new();
+
+        let tetrahedron = Shell::tetrahedron(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services);
+        let face = tetrahedron.abc.face;
+
+        let transform = Transform::translation([1., 0., 0.]);
+        let mut cache = TransformCache::default();
+
+        let a = face.clone().transform_with_cache(
+            &transform,
+            &mut services,
+            &mut cache,
+        );
+        let b =
+            face.transform_with_cache(&transform, &mut services, &mut cache);
+
+        assert_eq!(a.id(), b.id());
+    }
+
+    #[test]
+    fn rotate_around_datum_axis() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // The cube extends from -0.5 to 0.5 in x and y, and from 0 to 1 in z.
+        let cube = test_support::cube(1., &mut services);
+        let axis = Datum::axis([1., 0., 0.], [0., 0., 1.]);
+
+        let rotated = cube.rotate_around(&axis, PI, &mut services);
+
+        let aabb = rotated.aabb().expect("Cube has an AABB");
+        for (actual, expected) in
+            [(aabb.min, [1.5, -0.5, 0.]), (aabb.max, [2.5, 0.5, 1.])]
+        {
+            let distance = actual.distance_to(&Point::from(expected));
+            assert!(distance < Scalar::from(1e-9), "{aabb:?}");
+        }
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn rotate_around_datum_that_is_no_axis() {
+        let mut services = Services::new();
+
+        let cube = test_support::cube(1., &mut services);
+        let point = Datum::point([0., 0., 0.]);
+
+        let _ = cube.rotate_around(&point, PI, &mut services);
+    }
+}
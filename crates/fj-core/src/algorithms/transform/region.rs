@@ -0,0 +1,27 @@
+use fj_math::Transform;
+
+use crate::{objects::Region, services::Services};
+
+use super::{TransformCache, TransformObject};
+
+impl TransformObject for Region {
+    fn transform_with_cache(
+        self,
+        transform: &Transform,
+        services: &mut Services,
+        cache: &mut TransformCache,
+    ) -> Self {
+        // Color does not need to be transformed.
+        let color = self.color();
+
+        let exterior = self
+            .exterior()
+            .clone()
+            .transform_with_cache(transform, services, cache);
+        let interiors = self.interiors().iter().cloned().map(|interior| {
+            interior.transform_with_cache(transform, services, cache)
+        });
+
+        Self::new(exterior, interiors, color)
+    }
+}
@@ -3,11 +3,15 @@ use std::collections::BTreeMap;
 use fj_math::{Point, Scalar, Triangle, Winding};
 use spade::HasPosition;
 
-use crate::{algorithms::approx::cycle::CycleApprox, objects::Handedness};
+use crate::{algorithms::approx::ApproxPoint, objects::Handedness};
 
 /// Create a Delaunay triangulation of all points
+///
+/// The edges of the cycles are inserted as constraints. The additional points
+/// are inserted without any constraints, to refine the triangulation.
 pub fn triangulate(
-    cycles: impl IntoIterator<Item = CycleApprox>,
+    cycles: &[Vec<ApproxPoint<2>>],
+    additional_points: &[TriangulationPoint],
     coord_handedness: Handedness,
 ) -> Vec<[TriangulationPoint; 3]> {
     use spade::Triangulation as _;
@@ -16,10 +20,10 @@ pub fn triangulate(
 
     let mut points = BTreeMap::new();
 
-    for cycle_approx in cycles {
+    for cycle in cycles {
         let mut handle_prev = None;
 
-        for point in cycle_approx.points() {
+        for &point in cycle {
             let handle = match points.get(&point) {
                 Some(handle) => *handle,
                 None => {
@@ -44,6 +48,12 @@ pub fn triangulate(
         }
     }
 
+    for &point in additional_points {
+        triangulation
+            .insert(point)
+            .expect("Inserted invalid point into triangulation");
+    }
+
     let mut triangles = Vec::new();
     for triangle in triangulation.inner_faces() {
         let [v0, v1, v2] = triangle.vertices().map(|vertex| *vertex.data());
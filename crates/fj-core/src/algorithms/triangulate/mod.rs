@@ -2,23 +2,72 @@
 
 mod delaunay;
 mod polygon;
+mod quality;
+mod torus;
+
+use std::{collections::BTreeMap, ops::ControlFlow};
 
 use fj_interop::mesh::Mesh;
-use fj_math::Point;
+use fj_math::{Point, Scalar, Vector};
+
+use crate::objects::{Assembly, Handedness};
+
+use self::{
+    delaunay::TriangulationPoint,
+    polygon::Polygon,
+    quality::{subdivide_cycle, Refinement, MAX_REFINEMENT_STEPS},
+};
 
-use self::polygon::Polygon;
+use super::approx::{
+    edge::HalfEdgeApproxCache, face::FaceApprox, Approx, Tolerance,
+};
 
-use super::approx::{face::FaceApprox, Approx, Tolerance};
+pub use self::quality::MeshQuality;
 
 /// Triangulate a shape
 pub trait Triangulate: Sized {
     /// Triangulate the shape
     fn triangulate(self) -> Mesh<Point<3>> {
+        let _span = tracing::info_span!("triangulate").entered();
+
         let mut mesh = Mesh::new();
         self.triangulate_into_mesh(&mut mesh);
         mesh
     }
 
+    /// Prepare the triangulation of the shape in batches
+    ///
+    /// The returned [`Batches`] triangulate one batch of the shape at a time,
+    /// as they are iterated over. Together, the batches make up the same mesh
+    /// as [`Triangulate::triangulate`].
+    ///
+    /// [`Batches`] don't borrow the shape, and can be sent to another thread.
+    /// This makes it possible to display large shapes progressively, while
+    /// they are still being triangulated, without requiring the shape itself
+    /// to be shared between threads.
+    ///
+    /// By default, the whole shape is triangulated right away, as a single
+    /// batch.
+    fn batches(self) -> Batches {
+        Batches::from_mesh(self.triangulate())
+    }
+
+    /// Triangulate the shape in batches
+    ///
+    /// Calls `f` with a mesh for each batch of the shape that has been
+    /// triangulated, until the whole shape has been triangulated, or `f`
+    /// returns [`ControlFlow::Break`]. See [`Triangulate::batches`].
+    fn triangulate_in_batches(
+        self,
+        mut f: impl FnMut(Mesh<Point<3>>) -> ControlFlow<()>,
+    ) {
+        for batch in self.batches() {
+            if f(batch).is_break() {
+                break;
+            }
+        }
+    }
+
     /// Triangulate a partial shape into the provided mesh
     ///
     /// This is a low-level method, intended for implementation of
@@ -31,6 +80,14 @@ where
     T: Approx,
     T::Approximation: IntoIterator<Item = FaceApprox>,
 {
+    /// Approximate the shape, to triangulate it one face at a time
+    ///
+    /// See [`Batches`] for the order in which the faces are triangulated.
+    fn batches(self) -> Batches {
+        let (approx, tolerance) = self;
+        Batches::from_faces(approx.approx(tolerance))
+    }
+
     fn triangulate_into_mesh(self, mesh: &mut Mesh<Point<3>>) {
         let (approx, tolerance) = self;
 
@@ -42,53 +99,277 @@ where
     }
 }
 
+impl<T> Triangulate for (T, Tolerance, &mut HalfEdgeApproxCache)
+where
+    T: Approx<Cache = HalfEdgeApproxCache>,
+    T::Approximation: IntoIterator<Item = FaceApprox>,
+{
+    /// Approximate the shape, to triangulate it one face at a time
+    ///
+    /// Same as without a cache, except that approximations are taken from the
+    /// cache, where available. Use this with a cache that has been loaded from
+    /// a file (see [`HalfEdgeApproxCache::load`]), to avoid approximating
+    /// geometry that hasn't changed since the last run.
+    fn batches(self) -> Batches {
+        let (approx, tolerance, cache) = self;
+        Batches::from_faces(approx.approx_with_cache(tolerance, cache))
+    }
+
+    fn triangulate_into_mesh(self, mesh: &mut Mesh<Point<3>>) {
+        let (approx, tolerance, cache) = self;
+
+        let approx = approx.approx_with_cache(tolerance, cache);
+
+        for approx in approx {
+            approx.triangulate_into_mesh(mesh);
+        }
+    }
+}
+
+/// Batches of a triangulation
+///
+/// Returned by [`Triangulate::batches`]. Iterate over this, to triangulate one
+/// batch at a time.
+///
+/// Shapes that are approximated by faces are triangulated one face at a time,
+/// in order of decreasing area. This doesn't take into account which faces are
+/// visible from where the shape is viewed, but large faces make up most of the
+/// shape's outline, so the overall shape becomes visible early on, while the
+/// details are filled in later.
+pub struct Batches {
+    faces: Vec<FaceApprox>,
+    mesh: Option<Mesh<Point<3>>>,
+}
+
+impl Batches {
+    fn from_mesh(mesh: Mesh<Point<3>>) -> Self {
+        Self {
+            faces: Vec::new(),
+            mesh: Some(mesh),
+        }
+    }
+
+    fn from_faces(faces: impl IntoIterator<Item = FaceApprox>) -> Self {
+        let mut faces = faces
+            .into_iter()
+            .map(|face| (approx_area(&face), face))
+            .collect::<Vec<_>>();
+
+        // Sort by increasing area, so the largest face can be popped first.
+        faces.sort_by_key(|(area, _)| *area);
+
+        Self {
+            faces: faces.into_iter().map(|(_, face)| face).collect(),
+            mesh: None,
+        }
+    }
+}
+
+impl Iterator for Batches {
+    type Item = Mesh<Point<3>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(mesh) = self.mesh.take() {
+            return Some(mesh);
+        }
+
+        let face = self.faces.pop()?;
+
+        let _span = tracing::info_span!("triangulate").entered();
+        Some(face.triangulate())
+    }
+}
+
+impl<T> Triangulate for (T, Tolerance, MeshQuality)
+where
+    T: Approx,
+    T::Approximation: IntoIterator<Item = FaceApprox>,
+{
+    fn triangulate_into_mesh(self, mesh: &mut Mesh<Point<3>>) {
+        let (approx, tolerance, quality) = self;
+
+        let approx = approx.approx(tolerance);
+
+        for approx in approx {
+            (approx, quality).triangulate_into_mesh(mesh);
+        }
+    }
+}
+
+impl Triangulate for (&Assembly, Tolerance) {
+    /// Triangulate all instances of the assembly into a single mesh
+    ///
+    /// Solids that are referenced by multiple instances are only triangulated
+    /// once. The resulting triangles are then transformed for each instance.
+    fn triangulate_into_mesh(self, mesh: &mut Mesh<Point<3>>) {
+        let (assembly, tolerance) = self;
+
+        let mut solid_meshes = BTreeMap::new();
+
+        for instance in assembly.instances() {
+            let solid = instance.solid();
+            let solid_mesh = solid_meshes
+                .entry(solid.id())
+                .or_insert_with(|| (&**solid, tolerance).triangulate());
+
+            let transform = instance.transform();
+
+            let triangles =
+                solid_mesh.triangles_with_normals().collect::<Vec<_>>();
+            let points = triangles
+                .iter()
+                .flat_map(|(triangle, _)| triangle.inner.points())
+                .collect::<Vec<_>>();
+            let points = transform.transform_points(&points);
+
+            for ((triangle, normals), points) in
+                triangles.iter().zip(points.chunks(3))
+            {
+                let points: [Point<3>; 3] =
+                    points.try_into().expect("Each triangle has three points");
+
+                match normals {
+                    Some(normals) => mesh.push_triangle_with_normals(
+                        points,
+                        normals
+                            .map(|normal| transform.transform_normal(&normal)),
+                        triangle.color,
+                    ),
+                    None => mesh.push_triangle(points, triangle.color),
+                }
+            }
+        }
+    }
+}
+
+/// Compute the area enclosed by the exterior of a face approximation
+fn approx_area(face: &FaceApprox) -> Scalar {
+    let points = face
+        .exterior
+        .points()
+        .into_iter()
+        .map(|point| point.global_form)
+        .collect::<Vec<_>>();
+
+    let Some(&origin) = points.first() else {
+        return Scalar::ZERO;
+    };
+
+    let area = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .fold(Vector::from([0., 0., 0.]), |area, (a, b)| {
+            area + (a - origin).cross(&(b - origin))
+        });
+
+    area.magnitude() / 2.
+}
+
 impl Triangulate for FaceApprox {
     fn triangulate_into_mesh(self, mesh: &mut Mesh<Point<3>>) {
-        let face_as_polygon = Polygon::new()
-            .with_exterior(
-                self.exterior
-                    .points()
-                    .into_iter()
-                    .map(|point| point.local_form),
-            )
-            .with_interiors(self.interiors.iter().map(|interior| {
-                interior.points().into_iter().map(|point| point.local_form)
-            }));
-
-        let cycles = [self.exterior].into_iter().chain(self.interiors);
-        let mut triangles =
-            delaunay::triangulate(cycles, self.coord_handedness);
+        (self, MeshQuality::default()).triangulate_into_mesh(mesh);
+    }
+}
+
+impl Triangulate for (FaceApprox, MeshQuality) {
+    fn triangulate_into_mesh(self, mesh: &mut Mesh<Point<3>>) {
+        let (face, quality) = self;
+
+        let color = face.color.unwrap_or_default();
+
+        for triangle in triangulate_face(&face, quality) {
+            let points = triangle.map(|point| point.point_global);
+            let normals = triangle.map(|point| {
+                let normal = face.surface.normal_at(point.point_surface);
+                match face.coord_handedness {
+                    Handedness::RightHanded => normal,
+                    Handedness::LeftHanded => -normal,
+                }
+            });
+
+            mesh.push_triangle_with_normals(points, normals, color);
+        }
+    }
+}
+
+/// Triangulate a face, returning triangles in surface and global coordinates
+pub(crate) fn triangulate_face(
+    face: &FaceApprox,
+    quality: MeshQuality,
+) -> Vec<[TriangulationPoint; 3]> {
+    let cycles = [&face.exterior]
+        .into_iter()
+        .chain(&face.interiors)
+        .map(|cycle| subdivide_cycle(cycle.points(), quality))
+        .collect::<Vec<_>>();
+
+    let face_as_polygon = Polygon::new()
+        .with_exterior(cycles[0].iter().map(|point| point.local_form))
+        .with_interiors(
+            cycles[1..]
+                .iter()
+                .map(|cycle| cycle.iter().map(|point| point.local_form)),
+        );
+
+    let refinement = Refinement {
+        surface: &face.surface,
+        tolerance: face.tolerance,
+        quality,
+        polygon: &face_as_polygon,
+    };
+
+    // Triangles that don't meet the tolerance or the quality constraints are
+    // refined by inserting an additional point. Then we try again.
+    let mut additional_points = Vec::new();
+    let mut triangles = Vec::new();
+
+    for _ in 0..MAX_REFINEMENT_STEPS {
+        triangles = delaunay::triangulate(
+            &cycles,
+            &additional_points,
+            face.coord_handedness,
+        );
         triangles.retain(|triangle| {
             face_as_polygon
                 .contains_triangle(triangle.map(|point| point.point_surface))
         });
 
-        let color = self.color.unwrap_or_default();
+        let points = triangles
+            .iter()
+            .filter_map(|triangle| refinement.refine_triangle(triangle))
+            .collect::<Vec<_>>();
 
-        for triangle in triangles {
-            let points = triangle.map(|point| point.point_global);
-            mesh.push_triangle(points, color);
+        if points.is_empty() {
+            break;
         }
+
+        additional_points.extend(points);
     }
+
+    triangles
 }
 
 #[cfg(test)]
 mod tests {
+    use std::f64::consts::PI;
+
     use fj_interop::mesh::Mesh;
-    use fj_math::{Point, Scalar};
+    use fj_math::{Point, Scalar, Vector};
 
     use crate::{
-        algorithms::approx::{Approx, Tolerance},
-        objects::{Cycle, Face},
+        algorithms::approx::{edge::HalfEdgeApproxCache, Approx, Tolerance},
+        geometry::{GlobalPath, SurfaceGeometry},
+        objects::{Assembly, Cycle, Face, Solid, Surface},
         operations::{
-            build::{BuildCycle, BuildFace},
+            build::{BuildAssembly, BuildCycle, BuildFace},
             insert::Insert,
+            primitives::BuildPrimitive,
             update::{UpdateFace, UpdateRegion},
         },
         services::Services,
     };
 
-    use super::Triangulate;
+    use super::{MeshQuality, Triangulate};
 
     #[test]
     fn simple() -> anyhow::Result<()> {
@@ -236,6 +517,185 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn assembly_instances() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = Solid::box_from_dims([1., 1., 1.], &mut services)
+            .insert(&mut services);
+        let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
+
+        let single = (&*solid, tolerance).triangulate();
+        let pattern = Assembly::linear_pattern("box", solid, [2., 0., 0.], 3);
+        let mesh = (&pattern, tolerance).triangulate();
+
+        assert_eq!(mesh.triangles().count(), single.triangles().count() * 3);
+        assert!(mesh.contains_triangle(
+            single
+                .triangles()
+                .next()
+                .expect("Box mesh has triangles")
+                .inner
+                .points()
+                .map(|point| point + Vector::from([4., 0., 0.])),
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn curved_surface() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let radius = Scalar::ONE;
+        let surface = Surface::new(SurfaceGeometry {
+            u: GlobalPath::circle_from_radius(radius),
+            v: Vector::unit_z(),
+        })
+        .insert(&mut services);
+
+        let face =
+            Face::unbound(surface, &mut services).update_region(|region| {
+                region
+                    .update_exterior(|_| {
+                        Cycle::polygon(
+                            [[0., 0.], [PI, 0.], [PI, 1.], [0., 1.]],
+                            &mut services,
+                        )
+                        .insert(&mut services)
+                    })
+                    .insert(&mut services)
+            });
+
+        let tolerance = Tolerance::from_scalar(0.05)?;
+        let mesh = face.approx(tolerance).triangulate();
+
+        for triangle in mesh.triangles() {
+            let [a, b, c] = triangle.inner.points();
+            let centroid = (a.coords + b.coords + c.coords) / 3.;
+
+            let distance_to_axis = centroid.xy().magnitude();
+            assert!(distance_to_axis >= radius - tolerance.inner());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn surface_normals() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid =
+            Solid::cylinder(1., 2., &mut services).insert(&mut services);
+        let tolerance = Tolerance::from_scalar(0.01)?;
+        let mesh = (&*solid, tolerance).triangulate();
+
+        // The normals of the surfaces must point in the same direction as the
+        // triangles that approximate them.
+        for (triangle, normals) in mesh.triangles_with_normals() {
+            let [a, b, c] = triangle.inner.points();
+            let normal = (b - a).cross(&(c - a)).normalize();
+
+            let normals = normals.expect("Expected surface normals");
+            for surface_normal in normals {
+                assert!(surface_normal.dot(&normal) > Scalar::from(0.9));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn batches_make_up_full_triangulation() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid =
+            Solid::cylinder(1., 2., &mut services).insert(&mut services);
+        let tolerance = Tolerance::from_scalar(0.1)?;
+
+        let batches = (&*solid, tolerance).batches().collect::<Vec<_>>();
+
+        // One batch per face: the side, and the top and bottom.
+        assert_eq!(batches.len(), 3);
+
+        let mut triangles = batches
+            .iter()
+            .flat_map(|batch| batch.triangles())
+            .collect::<Vec<_>>();
+        let mut expected = (&*solid, tolerance)
+            .triangulate()
+            .triangles()
+            .collect::<Vec<_>>();
+
+        triangles.sort();
+        expected.sort();
+        assert_eq!(triangles, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn triangulate_with_cache() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid =
+            Solid::cylinder(1., 2., &mut services).insert(&mut services);
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let mut cache = HalfEdgeApproxCache::default();
+        let expected = (&*solid, tolerance).triangulate();
+
+        // The second triangulation takes the approximations from the cache.
+        for _ in 0..2 {
+            let mesh = (&*solid, tolerance, &mut cache).triangulate();
+            assert_eq!(
+                mesh.triangles().collect::<Vec<_>>(),
+                expected.triangles().collect::<Vec<_>>(),
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn mesh_quality() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let face =
+            Face::unbound(services.objects.surfaces.xy_plane(), &mut services)
+                .update_region(|region| {
+                    region
+                        .update_exterior(|_| {
+                            Cycle::polygon(
+                                [[0., 0.], [4., 0.], [4., 4.], [0., 4.]],
+                                &mut services,
+                            )
+                            .insert(&mut services)
+                        })
+                        .insert(&mut services)
+                });
+
+        let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
+        let quality = MeshQuality::default().with_max_edge_length(1.);
+
+        let coarse = face.approx(tolerance).triangulate();
+        let fine = (face.approx(tolerance), quality).triangulate();
+
+        assert!(fine.triangles().count() > coarse.triangles().count());
+        assert!(longest_edge(&fine) < longest_edge(&coarse));
+
+        Ok(())
+    }
+
+    fn longest_edge(mesh: &Mesh<Point<3>>) -> Scalar {
+        mesh.triangles()
+            .flat_map(|triangle| {
+                let [a, b, c] = triangle.inner.points();
+                [a.distance_to(&b), b.distance_to(&c), c.distance_to(&a)]
+            })
+            .fold(Scalar::ZERO, |longest, length| longest.max(length))
+    }
+
     fn triangulate(face: Face) -> anyhow::Result<Mesh<Point<3>>> {
         let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
         Ok(face.approx(tolerance).triangulate())
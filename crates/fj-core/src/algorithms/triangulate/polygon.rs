@@ -113,7 +113,7 @@ impl Polygon {
     /// This code is being duplicated by the `Contains<Point<2>>` implementation
     /// for `Face`. It would be nice to be able to consolidate the duplication,
     /// but this has turned out to be difficult.
-    fn contains_point(&self, point: impl Into<Point<2>>) -> bool {
+    pub fn contains_point(&self, point: impl Into<Point<2>>) -> bool {
         let ray = HorizontalRayToTheRight {
             origin: point.into(),
         };
@@ -127,11 +127,19 @@ impl Polygon {
             // "seam" of the polygon, i.e. the vertex between the last and the
             // first segment. The logic in the loop properly takes care of that,
             // as long as we initialize the `previous_hit` variable with the
-            // result of the last segment.
+            // result of the last segment that isn't parallel to the ray. The
+            // loop ignores parallel segments, so this is the last result it
+            // would have seen before wrapping around.
             let mut previous_hit = edges
-                .last()
-                .copied()
-                .and_then(|edge| (&ray, &edge).intersect());
+                .iter()
+                .rev()
+                .map(|edge| (&ray, edge).intersect())
+                .find(|hit| {
+                    *hit != Some(
+                        RaySegmentIntersection::RayHitsSegmentAndAreParallel,
+                    )
+                })
+                .flatten();
 
             for edge in edges {
                 let hit = (&ray, &edge).intersect();
@@ -279,6 +287,18 @@ mod tests {
             Polygon::new().with_exterior(PolyChain::from([a, b, c, d]).close());
         assert_contains_point(polygon, [1., 1.]);
 
+        // Ray passes polygon boundary along parallel edges at the seam.
+        let a = [1., 0.];
+        let b = [1., 2.];
+        let c = [0., 0.];
+        let d = [0., -1.];
+        let e = [4., -1.];
+        let f = [4., 0.];
+        let g = [3., 0.];
+        let polygon = Polygon::new()
+            .with_exterior(PolyChain::from([a, b, c, d, e, f, g]).close());
+        assert_contains_point(polygon, [0.5, 0.]);
+
         // Ray hits a vertex, but doesn't pass polygon boundary there.
         let a = [0., 0.];
         let b = [2., 1.];
@@ -0,0 +1,239 @@
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    algorithms::approx::{ApproxPoint, Tolerance},
+    geometry::SurfaceGeom,
+};
+
+use super::{delaunay::TriangulationPoint, polygon::Polygon};
+
+/// Parameters that control the quality of a triangle mesh
+///
+/// The tolerance only limits how far a mesh may deviate from the shape it
+/// approximates. For rendering or export to 3D printers, that is all that
+/// matters. Simulation tools (FEA, CFD, ...) also care about the shape of the
+/// triangles, as long or thin triangles lead to badly conditioned results.
+///
+/// By default, no quality constraints are applied.
+///
+/// The edges of faces are subdivided to meet the maximum edge length. Since the
+/// new points only depend on the edge itself, neighboring faces that share an
+/// edge end up with the same points, and the mesh stays watertight.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MeshQuality {
+    /// The maximum length of triangle edges
+    pub max_edge_length: Option<Scalar>,
+
+    /// The minimum angle within each triangle, in radians
+    ///
+    /// Values up to about 20 degrees are achievable in general. Larger values
+    /// can lead to refinement being aborted, before all triangles meet the
+    /// constraint.
+    pub min_angle: Option<Scalar>,
+}
+
+impl MeshQuality {
+    /// Limit the length of triangle edges
+    #[must_use]
+    pub fn with_max_edge_length(mut self, length: impl Into<Scalar>) -> Self {
+        self.max_edge_length = Some(length.into());
+        self
+    }
+
+    /// Limit the minimum angle within each triangle, in radians
+    #[must_use]
+    pub fn with_min_angle(mut self, angle: impl Into<Scalar>) -> Self {
+        self.min_angle = Some(angle.into());
+        self
+    }
+}
+
+/// Decides where to refine the triangulation of a face
+pub struct Refinement<'r> {
+    pub surface: &'r dyn SurfaceGeom,
+    pub tolerance: Tolerance,
+    pub quality: MeshQuality,
+    pub polygon: &'r Polygon,
+}
+
+impl Refinement<'_> {
+    /// Return the point that refines the triangle, if it needs refinement
+    pub fn refine_triangle(
+        &self,
+        triangle: &[TriangulationPoint; 3],
+    ) -> Option<TriangulationPoint> {
+        let [a, b, c] = triangle;
+
+        let centroid = self.point(Point {
+            coords: (a.point_surface.coords
+                + b.point_surface.coords
+                + c.point_surface.coords)
+                / 3.,
+        });
+
+        // On a curved surface, a triangle that is fine in surface coordinates
+        // can deviate from the surface by more than the tolerance in global
+        // coordinates. We compare the centroid of the triangle to the point on
+        // the surface that has the same surface coordinates.
+        let centroid_global = Point {
+            coords: (a.point_global.coords
+                + b.point_global.coords
+                + c.point_global.coords)
+                / 3.,
+        };
+        if centroid.point_global.distance_to(&centroid_global)
+            > self.tolerance.inner()
+        {
+            return Some(centroid);
+        }
+
+        if let Some(max_edge_length) = self.quality.max_edge_length {
+            let is_too_long =
+                [[a, b], [b, c], [c, a]].into_iter().any(|[p, q]| {
+                    p.point_global.distance_to(&q.point_global)
+                        > max_edge_length
+                });
+
+            if is_too_long {
+                return Some(centroid);
+            }
+        }
+
+        if let Some(min_angle) = self.quality.min_angle {
+            let is_too_sharp = [[a, b, c], [b, c, a], [c, a, b]]
+                .into_iter()
+                .any(|[corner, p, q]| {
+                    let u = p.point_global - corner.point_global;
+                    let v = q.point_global - corner.point_global;
+                    u.cross(&v).magnitude().atan2(u.dot(&v)) < min_angle
+                });
+
+            if is_too_sharp {
+                // Inserting the circumcenter removes the triangle from the
+                // Delaunay triangulation, which is the basis of Ruppert's and
+                // Chew's refinement algorithms.
+                if let Some(circumcenter) = circumcenter(
+                    a.point_surface,
+                    b.point_surface,
+                    c.point_surface,
+                ) {
+                    if self.polygon.contains_point(circumcenter) {
+                        return Some(self.point(circumcenter));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn point(&self, point_surface: Point<2>) -> TriangulationPoint {
+        TriangulationPoint {
+            point_surface,
+            point_global: self.surface.point_from_surface_coords(point_surface),
+        }
+    }
+}
+
+/// The maximum number of times a face's triangulation is refined
+///
+/// This is a safeguard against surfaces and quality constraints that never
+/// converge, not a limit that is expected to be reached.
+pub const MAX_REFINEMENT_STEPS: usize = 16;
+
+/// Subdivide the segments of a cycle that exceed the maximum edge length
+///
+/// The new points are interpolated from the endpoints of each segment in a
+/// canonical order, so the same segment is subdivided into exactly the same
+/// points, regardless of the direction it is traversed in.
+pub fn subdivide_cycle(
+    points: Vec<ApproxPoint<2>>,
+    quality: MeshQuality,
+) -> Vec<ApproxPoint<2>> {
+    let Some(max_edge_length) = quality.max_edge_length else {
+        return points;
+    };
+
+    let mut subdivided = Vec::new();
+
+    for segment in points.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+        subdivided.push(a);
+
+        let length = a.global_form.distance_to(&b.global_form);
+        let num_segments = (length / max_edge_length).ceil();
+        if num_segments <= Scalar::ONE {
+            continue;
+        }
+
+        let (start, end, reversed) = if a.global_form <= b.global_form {
+            (a, b, false)
+        } else {
+            (b, a, true)
+        };
+
+        let num_segments = num_segments.into_u64();
+        let mut new_points = (1..num_segments)
+            .map(|i| {
+                let t = Scalar::from_u64(i) / Scalar::from_u64(num_segments);
+                ApproxPoint::new(
+                    start.local_form + (end.local_form - start.local_form) * t,
+                    start.global_form
+                        + (end.global_form - start.global_form) * t,
+                )
+            })
+            .collect::<Vec<_>>();
+        if reversed {
+            new_points.reverse();
+        }
+
+        subdivided.extend(new_points);
+    }
+
+    subdivided.extend(points.last().copied());
+    subdivided
+}
+
+fn circumcenter(a: Point<2>, b: Point<2>, c: Point<2>) -> Option<Point<2>> {
+    let b = b - a;
+    let c = c - a;
+
+    let denominator = b.cross2d(&c) * 2.;
+    if denominator == Scalar::ZERO {
+        return None;
+    }
+
+    let b_sq = b.dot(&b);
+    let c_sq = c.dot(&c);
+
+    let offset = Vector::from([
+        (c.v * b_sq - b.v * c_sq) / denominator,
+        (b.u * c_sq - c.u * b_sq) / denominator,
+    ]);
+
+    Some(a + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use super::circumcenter;
+
+    #[test]
+    fn circumcenter_of_right_triangle() {
+        let center = circumcenter(
+            Point::from([0., 0.]),
+            Point::from([2., 0.]),
+            Point::from([0., 2.]),
+        );
+        assert_eq!(center, Some(Point::from([1., 1.])));
+
+        let degenerate = circumcenter(
+            Point::from([0., 0.]),
+            Point::from([1., 0.]),
+            Point::from([2., 0.]),
+        );
+        assert_eq!(degenerate, None);
+    }
+}
@@ -0,0 +1,76 @@
+use fj_interop::mesh::{Color, Mesh};
+use fj_math::{Point, Torus};
+
+use crate::algorithms::approx::{torus::approx_closed_torus, Tolerance};
+
+use super::Triangulate;
+
+impl Triangulate for (&Torus, Tolerance) {
+    /// Triangulate the whole torus
+    ///
+    /// The triangles face away from the tube center, in the direction of the
+    /// torus normal.
+    fn triangulate_into_mesh(self, mesh: &mut Mesh<Point<3>>) {
+        let (torus, tolerance) = self;
+
+        let grid = approx_closed_torus(torus, tolerance);
+
+        let rows = grid.iter().zip(grid.iter().cycle().skip(1));
+        for (row, next_row) in rows {
+            let columns = (0..row.len()).zip((0..row.len()).cycle().skip(1));
+            for (i, j) in columns {
+                // The u-coordinate runs along the rows, the v-coordinate along
+                // the columns. Their cross product points away from the tube
+                // center, so this is the counter-clockwise order, as seen from
+                // outside of the torus.
+                let quad = [row[i], next_row[i], next_row[j], row[j]];
+
+                for [a, b, c] in [[0, 1, 2], [0, 2, 3]] {
+                    let triangle = [quad[a], quad[b], quad[c]];
+
+                    mesh.push_triangle_with_normals(
+                        triangle.map(|point| point.global_form),
+                        triangle.map(|point| torus.normal_at(point.local_form)),
+                        Color::default(),
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Circle, Scalar, Torus};
+
+    use crate::algorithms::{
+        approx::{torus::approx_closed_torus, Tolerance},
+        triangulate::Triangulate,
+    };
+
+    #[test]
+    fn triangulate_torus() {
+        let torus =
+            Torus::new(Circle::from_center_and_radius([0., 0., 0.], 2.), 1.);
+        let tolerance = Tolerance::from(0.1);
+
+        let mesh = (&torus, tolerance).triangulate();
+
+        // The mesh is closed. Every grid point is shared by the triangles
+        // around it, and every grid cell is covered by two triangles.
+        let grid = approx_closed_torus(&torus, tolerance);
+        let num_points = grid.iter().map(|row| row.len()).sum::<usize>();
+        assert_eq!(mesh.vertices().count(), num_points);
+        assert_eq!(mesh.triangles().count(), num_points * 2);
+
+        for (triangle, normals) in mesh.triangles_with_normals() {
+            let normals = normals.unwrap();
+            let [a, b, c] = triangle.inner.points();
+            let normal = (b - a).cross(&(c - a));
+
+            for surface_normal in normals {
+                assert!(normal.dot(&surface_normal) > Scalar::ZERO);
+            }
+        }
+    }
+}
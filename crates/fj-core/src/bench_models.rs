@@ -0,0 +1,131 @@
+//! # Standard models for benchmarking
+//!
+//! The models in this module are designed to stress specific parts of the
+//! kernel. They are used by the benchmarks in the `benches/` directory of this
+//! crate, but are available publicly, so users can compare the performance of
+//! different releases with the same models, or use them in their own
+//! benchmarks.
+//!
+//! All models are parameterized, so their size can be scaled up or down. The
+//! same parameters always result in the same model.
+
+use std::f64::consts::TAU;
+
+use fj_math::Point;
+
+use crate::{
+    algorithms::approx::Tolerance,
+    objects::Solid,
+    operations::{build::SketchBuilder, sweep::SweepSketch},
+    services::Services,
+};
+
+/// Build a square plate with a regular grid of circular holes
+///
+/// The plate has `holes_per_side * holes_per_side` holes, each in the center of
+/// a square cell with a side length of `1`. This results in a large number of
+/// faces that share a single surface, which stresses sweeping, triangulation,
+/// and the validation checks that compare all edges of a face or shell.
+///
+/// The plate is in the xy-plane, with one of its corners at the origin, and
+/// extends in the positive z direction, with a thickness of `0.2`.
+pub fn perforated_plate(holes_per_side: u32, services: &mut Services) -> Solid {
+    let size = f64::from(holes_per_side);
+
+    let mut sketch = SketchBuilder::start_at([0., 0.])
+        .line_to([size, 0.])
+        .line_to([size, size])
+        .line_to([0., size])
+        .close();
+
+    for i in 0..holes_per_side {
+        for j in 0..holes_per_side {
+            let center = [f64::from(i) + 0.5, f64::from(j) + 0.5];
+            sketch = sketch.circle(center, 0.25);
+        }
+    }
+
+    let surface = services.objects.surfaces.xy_plane();
+    sketch
+        .build(services)
+        .sweep_sketch(surface, [0., 0., 0.2], services)
+}
+
+/// Build a solid by sweeping a profile made of splines
+///
+/// The profile is a closed, flower-like outline, consisting of `petals` cubic
+/// Bézier splines. Since the kernel has no native representation of splines
+/// yet, they are approximated by line segments within `tolerance`. Reducing the
+/// tolerance results in a high-resolution profile with a huge number of edges,
+/// which stresses every part of the kernel that operates on a per-edge basis.
+///
+/// The profile is in the xy-plane, centered on the origin, with a radius of
+/// about `1`. It is swept in the positive z direction, by a distance of `1`.
+///
+/// # Panics
+///
+/// Panics, if `petals` is smaller than `3`.
+pub fn swept_spline(
+    petals: u32,
+    tolerance: impl Into<Tolerance>,
+    services: &mut Services,
+) -> Solid {
+    assert!(petals >= 3, "Need at least 3 petals to form a profile");
+
+    let tolerance = tolerance.into();
+    let point = |radius: f64, angle: f64| {
+        let (sin, cos) = angle.sin_cos();
+        Point::from([cos * radius, sin * radius])
+    };
+
+    let step = TAU / f64::from(petals);
+    let start = point(0.5, 0.);
+
+    let mut sketch = SketchBuilder::start_at(start);
+    for i in 0..petals {
+        let angle = step * f64::from(i);
+
+        let control_points = [
+            point(1.2, angle + step / 3.),
+            point(1.2, angle + step * 2. / 3.),
+        ];
+        let end = if i + 1 == petals {
+            start
+        } else {
+            point(0.5, angle + step)
+        };
+
+        sketch = sketch.spline_to(control_points, end, tolerance);
+    }
+
+    let surface = services.objects.surfaces.xy_plane();
+    sketch
+        .close()
+        .build(services)
+        .sweep_sketch(surface, [0., 0., 1.], services)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{operations::insert::Insert, services::Services};
+
+    use super::{perforated_plate, swept_spline};
+
+    #[test]
+    fn bench_models_are_valid() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let plate = perforated_plate(3, &mut services).insert(&mut services);
+        assert_eq!(
+            plate.shells().only().faces().len(),
+            // top, bottom, 4 outer sides, and one side per hole
+            2 + 4 + 3 * 3
+        );
+
+        let spline = swept_spline(5, 0.01, &mut services).insert(&mut services);
+        assert_eq!(spline.shells().len(), 1);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+}
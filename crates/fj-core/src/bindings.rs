@@ -0,0 +1,507 @@
+//! Shared implementation of the language bindings
+//!
+//! Fornjot can be used from Python (`fj-python`), from JavaScript
+//! (`fj-wasm`), and from Rhai scripts (the `scripting` feature of `fj`). All of
+//! them provide the same small API for sketching, sweeping, and transforming
+//! shapes. This module implements that API, so each binding only has to
+//! convert arguments, results, and errors.
+//!
+//! Unlike most of the kernel, the methods here don't panic on invalid input,
+//! including numbers that are NaN or infinite. They return a [`BindingsError`]
+//! instead, which the bindings can report in whatever way is appropriate for
+//! their language.
+
+use std::{f64::consts::TAU, mem};
+
+use fj_interop::mesh::Mesh;
+use fj_math::Point;
+
+use crate::{
+    algorithms::{
+        approx::{InvalidTolerance, Tolerance},
+        bounding_volume::BoundingVolume,
+        transform::TransformObject,
+        triangulate::Triangulate,
+    },
+    objects::Solid,
+    operations::{
+        build::SketchBuilder, insert::Insert, primitives::BuildPrimitive,
+        sweep::SweepSketch,
+    },
+    services::Services,
+    storage::Handle,
+    validate::ValidationErrors,
+};
+
+/// The kernel, which creates shapes and operates on them
+///
+/// All shapes are validated as they are created. Validation errors are
+/// reported by [`Kernel::validate`], and by the methods that produce output.
+///
+/// Dropping a `Kernel` never panics, even if there are validation errors that
+/// haven't been reported. Those errors are discarded instead.
+pub struct Kernel {
+    services: Option<Services>,
+}
+
+impl Kernel {
+    /// Construct a new instance of `Kernel`
+    pub fn new() -> Self {
+        Self {
+            services: Some(Services::new()),
+        }
+    }
+
+    /// Access the services that shapes are created with
+    pub fn services(&mut self) -> &mut Services {
+        self.services
+            .as_mut()
+            .expect("`Services` is only taken out on drop or conversion")
+    }
+
+    /// Convert the kernel into the services that shapes were created with
+    ///
+    /// The returned [`Services`] have not been validated yet. This is left to
+    /// the caller, for example by calling [`Services::drop_and_validate`].
+    pub fn into_services(mut self) -> Services {
+        self.services
+            .take()
+            .expect("`Services` is only taken out on drop or conversion")
+    }
+
+    /// Sweep a sketch in the xy-plane along the provided path
+    pub fn sweep(
+        &mut self,
+        sketch: &Sketch,
+        path: [f64; 3],
+    ) -> Result<Handle<Solid>, BindingsError> {
+        sketch.ensure_closed()?;
+        let path = finite(path)?;
+        let services = self.services();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let solid = sketch
+            .builder
+            .clone()
+            .build(services)
+            .sweep_sketch(surface, path, services)
+            .insert(services);
+
+        Ok(solid)
+    }
+
+    /// Create a box, centered on the origin in x and y
+    pub fn cuboid(
+        &mut self,
+        size: [f64; 3],
+    ) -> Result<Handle<Solid>, BindingsError> {
+        if finite(size)?.iter().any(|extent| *extent <= 0.) {
+            return Err(BindingsError::NotPositive("Box dimensions"));
+        }
+
+        let services = self.services();
+        Ok(Solid::box_from_dims(size, services).insert(services))
+    }
+
+    /// Create a cylinder, standing on the xy-plane
+    pub fn cylinder(
+        &mut self,
+        radius: f64,
+        height: f64,
+    ) -> Result<Handle<Solid>, BindingsError> {
+        let [radius, height] = finite([radius, height])?;
+        if radius <= 0. || height <= 0. {
+            return Err(BindingsError::NotPositive(
+                "Cylinder radius and height",
+            ));
+        }
+
+        let services = self.services();
+        Ok(Solid::cylinder(radius, height, services).insert(services))
+    }
+
+    /// Translate a solid by the provided offset
+    pub fn translate(
+        &mut self,
+        solid: &Handle<Solid>,
+        offset: [f64; 3],
+    ) -> Result<Handle<Solid>, BindingsError> {
+        let offset = finite(offset)?;
+        Ok(solid.clone().translate(offset, self.services()))
+    }
+
+    /// Rotate a solid around the origin
+    ///
+    /// The direction of `axis_angle` defines the rotation axis, its length the
+    /// angle of the rotation, in radians.
+    pub fn rotate(
+        &mut self,
+        solid: &Handle<Solid>,
+        axis_angle: [f64; 3],
+    ) -> Result<Handle<Solid>, BindingsError> {
+        let axis_angle = finite(axis_angle)?;
+        Ok(solid.clone().rotate(axis_angle, self.services()))
+    }
+
+    /// Return an error, if any shape that was created so far is invalid
+    pub fn validate(&mut self) -> Result<(), ValidationErrors> {
+        let errors = ValidationErrors(
+            self.services()
+                .validation
+                .errors
+                .values()
+                .cloned()
+                .collect(),
+        );
+
+        if errors.0.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Validate all shapes, then triangulate a solid
+    ///
+    /// If no `tolerance` is provided, [`default_tolerance`] is used.
+    pub fn triangulate(
+        &mut self,
+        solid: &Handle<Solid>,
+        tolerance: Option<f64>,
+    ) -> Result<Mesh<Point<3>>, BindingsError> {
+        self.validate()?;
+
+        let tolerance = match tolerance {
+            Some(tolerance) => {
+                let [tolerance] = finite([tolerance])?;
+                Tolerance::from_scalar(tolerance)?
+            }
+            None => default_tolerance(solid)?,
+        };
+
+        Ok((&**solid, tolerance).triangulate())
+    }
+}
+
+impl Default for Kernel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Kernel {
+    fn drop(&mut self) {
+        if let Some(services) = self.services.take() {
+            if services.validation.errors.is_empty() {
+                drop(services);
+            } else {
+                // Dropping `Services` with unhandled validation errors panics.
+                // That's not an appropriate way to report errors to a script
+                // or another language, which has had its chance to call
+                // `validate` anyway.
+                mem::forget(services);
+            }
+        }
+    }
+}
+
+/// A sketch, defined by tracing its outline
+///
+/// Wraps [`SketchBuilder`], but returns errors instead of panicking. Every
+/// method returns a new sketch, which makes it possible to chain calls.
+#[derive(Clone, Debug)]
+pub struct Sketch {
+    builder: SketchBuilder,
+}
+
+impl Sketch {
+    /// Start a sketch at the provided point
+    pub fn new(point: [f64; 2]) -> Result<Self, BindingsError> {
+        Ok(Self {
+            builder: SketchBuilder::start_at(finite(point)?),
+        })
+    }
+
+    /// Start a new cycle at the provided point
+    pub fn move_to(&self, point: [f64; 2]) -> Result<Self, BindingsError> {
+        self.ensure_closed()?;
+        let point = finite(point)?;
+        Ok(self.with(|builder| builder.move_to(point)))
+    }
+
+    /// Add a line from the current point to the provided one
+    pub fn line_to(&self, point: [f64; 2]) -> Result<Self, BindingsError> {
+        let point = finite(point)?;
+        Ok(self.with(|builder| builder.line_to(point)))
+    }
+
+    /// Add an arc from the current point to the provided one
+    ///
+    /// The angle is in radians. Positive angles result in counter-clockwise
+    /// arcs, negative angles in clockwise ones.
+    pub fn arc_to(
+        &self,
+        point: [f64; 2],
+        angle: f64,
+    ) -> Result<Self, BindingsError> {
+        let point = finite(point)?;
+        if !(-TAU < angle && angle < TAU) {
+            return Err(BindingsError::ArcAngle);
+        }
+
+        Ok(self.with(|builder| builder.arc_to(point, angle)))
+    }
+
+    /// Close the current cycle
+    pub fn close(&self) -> Result<Self, BindingsError> {
+        if self.builder.is_closed() {
+            return Err(BindingsError::EmptyCycle);
+        }
+
+        Ok(self.with(SketchBuilder::close))
+    }
+
+    /// Add a circle as a separate cycle
+    pub fn circle(
+        &self,
+        center: [f64; 2],
+        radius: f64,
+    ) -> Result<Self, BindingsError> {
+        self.ensure_closed()?;
+        let center = finite(center)?;
+        let [radius] = finite([radius])?;
+        if radius <= 0. {
+            return Err(BindingsError::NotPositive("Circle radius"));
+        }
+
+        Ok(self.with(|builder| builder.circle(center, radius)))
+    }
+
+    fn with(&self, f: impl FnOnce(SketchBuilder) -> SketchBuilder) -> Self {
+        Self {
+            builder: f(self.builder.clone()),
+        }
+    }
+
+    fn ensure_closed(&self) -> Result<(), BindingsError> {
+        if self.builder.is_closed() {
+            Ok(())
+        } else {
+            Err(BindingsError::UnclosedCycle)
+        }
+    }
+}
+
+/// Return an error, if any of the provided numbers is NaN or infinite
+///
+/// The kernel's math types panic on NaN, and infinite values turn into NaN as
+/// soon as they are used in a calculation.
+fn finite<const N: usize>(values: [f64; N]) -> Result<[f64; N], BindingsError> {
+    if values.iter().all(|value| value.is_finite()) {
+        Ok(values)
+    } else {
+        Err(BindingsError::NotFinite)
+    }
+}
+
+/// Compute a reasonable default tolerance for triangulating a solid
+///
+/// See [`Tolerance::from_aabb`].
+pub fn default_tolerance(solid: &Solid) -> Result<Tolerance, InvalidTolerance> {
+    Tolerance::from_aabb(&solid.aabb().unwrap_or_default())
+}
+
+/// Error returned by the methods in this module
+#[derive(Debug, thiserror::Error)]
+pub enum BindingsError {
+    /// A cycle must be closed first
+    #[error("Must close the current cycle first")]
+    UnclosedCycle,
+
+    /// Tried to close a cycle that has no segments
+    #[error("Can't close empty cycle")]
+    EmptyCycle,
+
+    /// The angle of an arc is out of range
+    #[error("Arc angle must be in the range (-2pi, 2pi)")]
+    ArcAngle,
+
+    /// A dimension must be positive
+    #[error("{0} must be positive")]
+    NotPositive(&'static str),
+
+    /// A number is NaN or infinite
+    #[error("Numbers must be finite, not NaN or infinite")]
+    NotFinite,
+
+    /// A shape is invalid
+    #[error(transparent)]
+    Validation(#[from] ValidationErrors),
+
+    /// The tolerance is invalid
+    #[error(transparent)]
+    Tolerance(#[from] InvalidTolerance),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::approx::Tolerance,
+        objects::HalfEdge,
+        operations::{build::BuildHalfEdge, insert::Insert},
+    };
+
+    use super::{default_tolerance, BindingsError, Kernel, Sketch};
+
+    fn rectangle() -> Sketch {
+        Sketch::new([0., 0.])
+            .and_then(|sketch| sketch.line_to([2., 0.]))
+            .and_then(|sketch| sketch.line_to([2., 1.]))
+            .and_then(|sketch| sketch.line_to([0., 1.]))
+            .expect("Rectangle is valid")
+    }
+
+    #[test]
+    fn sweep_sketch() -> anyhow::Result<()> {
+        let mut kernel = Kernel::new();
+
+        let sketch = rectangle().close()?.circle([1., 0.5], 0.25)?;
+        let solid = kernel.sweep(&sketch, [0., 0., 0.5])?;
+        let mesh = kernel.triangulate(&solid, None)?;
+
+        assert!(mesh.triangles().count() > 0);
+        kernel.into_services().drop_and_validate()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn sketch_errors() -> anyhow::Result<()> {
+        let mut kernel = Kernel::new();
+
+        assert!(matches!(
+            kernel.sweep(&rectangle(), [0., 0., 1.]),
+            Err(BindingsError::UnclosedCycle)
+        ));
+        assert!(matches!(
+            rectangle().move_to([3., 3.]),
+            Err(BindingsError::UnclosedCycle)
+        ));
+        assert!(matches!(
+            rectangle().close()?.close(),
+            Err(BindingsError::EmptyCycle)
+        ));
+        assert!(matches!(
+            rectangle().arc_to([0., 0.], 7.),
+            Err(BindingsError::ArcAngle)
+        ));
+        assert!(matches!(
+            rectangle().close()?.circle([0., 0.], 0.),
+            Err(BindingsError::NotPositive(_))
+        ));
+        assert!(matches!(
+            kernel.cylinder(1., -1.),
+            Err(BindingsError::NotPositive(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn non_finite_numbers() -> anyhow::Result<()> {
+        let mut kernel = Kernel::new();
+        let solid = kernel.cuboid([1., 1., 1.])?;
+
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let results = [
+                Sketch::new([value, 0.]).map(drop),
+                rectangle().line_to([0., value]).map(drop),
+                rectangle().arc_to([value, 0.], 1.).map(drop),
+                rectangle().arc_to([0., 0.], value).map(drop),
+                rectangle().close()?.move_to([value, 0.]).map(drop),
+                rectangle().close()?.circle([0., 0.], value).map(drop),
+                kernel
+                    .sweep(&rectangle().close()?, [0., 0., value])
+                    .map(drop),
+                kernel.cuboid([1., value, 1.]).map(drop),
+                kernel.cylinder(value, 1.).map(drop),
+                kernel.translate(&solid, [value, 0., 0.]).map(drop),
+                kernel.rotate(&solid, [0., 0., value]).map(drop),
+                kernel.triangulate(&solid, Some(value)).map(drop),
+            ];
+
+            for result in results {
+                assert!(
+                    matches!(
+                        result,
+                        Err(BindingsError::NotFinite | BindingsError::ArcAngle)
+                    ),
+                    "{value}: {result:?}"
+                );
+            }
+        }
+
+        kernel.into_services().drop_and_validate()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_tolerance() -> anyhow::Result<()> {
+        let mut kernel = Kernel::new();
+        let solid = kernel.cuboid([1., 1., 1.])?;
+
+        assert!(matches!(
+            kernel.triangulate(&solid, Some(0.)),
+            Err(BindingsError::Tolerance(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn default_tolerance_uses_smallest_extent() -> anyhow::Result<()> {
+        let mut kernel = Kernel::new();
+        let solid = kernel.cuboid([1., 2., 0.5])?;
+
+        assert_eq!(
+            default_tolerance(&solid)?,
+            Tolerance::from_scalar(Scalar::from(0.0005))?,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn drop_with_validation_errors() -> anyhow::Result<()> {
+        let mut kernel = Kernel::new();
+        let solid = kernel.cuboid([1., 1., 1.])?;
+
+        // A half-edge whose vertices are coincident is invalid.
+        let services = kernel.services();
+        let valid =
+            HalfEdge::line_segment([[0., 0.], [1., 0.]], None, services);
+        let _invalid = HalfEdge::new(
+            valid.path(),
+            [Point::from([0.]); 2],
+            valid.curve().clone(),
+            valid.start_vertex().clone(),
+        )
+        .insert(services);
+
+        assert!(kernel.validate().is_err());
+        assert!(matches!(
+            kernel.triangulate(&solid, None),
+            Err(BindingsError::Validation(_))
+        ));
+
+        // Dropping must not panic, even though the errors remain.
+        mem::drop(kernel);
+
+        Ok(())
+    }
+}
@@ -2,10 +2,14 @@
 
 mod boundary;
 mod path;
+mod ruled;
 mod surface;
+mod traits;
 
 pub use self::{
     boundary::{CurveBoundary, CurveBoundaryElement},
     path::{GlobalPath, SurfacePath},
+    ruled::RuledSurface,
     surface::SurfaceGeometry,
+    traits::{CurveGeom, SurfaceGeom},
 };
@@ -2,7 +2,11 @@
 //!
 //! See [`SurfacePath`] and [`GlobalPath`].
 
-use fj_math::{Circle, Line, Point, Scalar, Transform, Vector};
+use fj_math::{
+    Circle, Ellipse, Involute, Line, Point, Scalar, Spiral, Transform, Vector,
+};
+
+use super::CurveBoundary;
 
 /// A path through surface (2D) space
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -12,6 +16,12 @@ pub enum SurfacePath {
 
     /// A line
     Line(Line<2>),
+
+    /// An Archimedean spiral
+    Spiral(Spiral<2>),
+
+    /// The involute of a circle
+    Involute(Involute<2>),
 }
 
 impl SurfacePath {
@@ -23,6 +33,25 @@ impl SurfacePath {
         Self::Circle(Circle::from_center_and_radius(center, radius))
     }
 
+    /// Build an Archimedean spiral from its center, start radius, and pitch
+    ///
+    /// See [`Spiral::from_center_and_pitch`].
+    pub fn spiral_from_center_and_pitch(
+        center: impl Into<Point<2>>,
+        start_radius: impl Into<Scalar>,
+        pitch: impl Into<Scalar>,
+    ) -> Self {
+        Self::Spiral(Spiral::from_center_and_pitch(center, start_radius, pitch))
+    }
+
+    /// Build the involute of the circle with the given center and radius
+    pub fn involute_from_center_and_radius(
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+    ) -> Self {
+        Self::Involute(Involute::from_center_and_radius(center, radius))
+    }
+
     /// Build a line that represents the u-axis of the surface its on
     pub fn u_axis() -> Self {
         let a = Point::origin();
@@ -58,14 +87,34 @@ impl SurfacePath {
         Self::Line(Line::from_points_with_line_coords(points))
     }
 
+    /// Access the period of the path's coordinate system
+    ///
+    /// Returns `None`, if the path is not periodic.
+    pub fn period(&self) -> Option<Scalar> {
+        match self {
+            Self::Circle(_) => Some(Scalar::TAU),
+            Self::Line(_) | Self::Spiral(_) | Self::Involute(_) => None,
+        }
+    }
+
     /// Convert a point on the path into surface coordinates
+    ///
+    /// For periodic paths, the point is wrapped into the first period before
+    /// conversion. This makes sure that coordinates that are one period apart
+    /// result in the exact same surface point.
     pub fn point_from_path_coords(
         &self,
         point: impl Into<Point<1>>,
     ) -> Point<2> {
+        let point = wrap(point.into(), self.period());
+
         match self {
             Self::Circle(circle) => circle.point_from_circle_coords(point),
             Self::Line(line) => line.point_from_line_coords(point),
+            Self::Spiral(spiral) => spiral.point_from_spiral_coords(point),
+            Self::Involute(involute) => {
+                involute.point_from_involute_coords(point)
+            }
         }
     }
 
@@ -75,6 +124,8 @@ impl SurfacePath {
         match self {
             Self::Circle(circle) => Self::Circle(circle.reverse()),
             Self::Line(line) => Self::Line(line.reverse()),
+            Self::Spiral(spiral) => Self::Spiral(spiral.reverse()),
+            Self::Involute(involute) => Self::Involute(involute.reverse()),
         }
     }
 }
@@ -85,8 +136,17 @@ pub enum GlobalPath {
     /// A circle
     Circle(Circle<3>),
 
+    /// An ellipse
+    Ellipse(Ellipse<3>),
+
     /// A line
     Line(Line<3>),
+
+    /// An Archimedean spiral
+    Spiral(Spiral<3>),
+
+    /// The involute of a circle
+    Involute(Involute<3>),
 }
 
 impl GlobalPath {
@@ -121,6 +181,22 @@ impl GlobalPath {
         Self::Circle(Circle::from_center_and_radius(Point::origin(), radius))
     }
 
+    /// Build an ellipse from the provided center and semi-axes
+    ///
+    /// Returns a circle instead, if both semi-axes have the same length.
+    pub fn ellipse_from_center_and_axes(
+        center: impl Into<Point<3>>,
+        a: impl Into<Vector<3>>,
+        b: impl Into<Vector<3>>,
+    ) -> Self {
+        let ellipse = Ellipse::new(center, a, b);
+
+        match ellipse.to_circle() {
+            Some(circle) => Self::Circle(circle),
+            None => Self::Ellipse(ellipse),
+        }
+    }
+
     /// Construct a line from two points
     ///
     /// Also returns the coordinates of the points on the path.
@@ -135,18 +211,45 @@ impl GlobalPath {
     pub fn origin(&self) -> Point<3> {
         match self {
             Self::Circle(circle) => circle.center() + circle.a(),
+            Self::Ellipse(ellipse) => ellipse.center() + ellipse.a(),
             Self::Line(line) => line.origin(),
+            Self::Spiral(spiral) => spiral.point_from_spiral_coords([0.]),
+            Self::Involute(involute) => {
+                involute.point_from_involute_coords([0.])
+            }
+        }
+    }
+
+    /// Access the period of the path's coordinate system
+    ///
+    /// Returns `None`, if the path is not periodic.
+    pub fn period(&self) -> Option<Scalar> {
+        match self {
+            Self::Circle(_) | Self::Ellipse(_) => Some(Scalar::TAU),
+            Self::Line(_) | Self::Spiral(_) | Self::Involute(_) => None,
         }
     }
 
     /// Convert a point on the path into global coordinates
+    ///
+    /// For periodic paths, the point is wrapped into the first period before
+    /// conversion. This makes sure that coordinates that are one period apart
+    /// result in the exact same global point, which is required to prevent
+    /// cracks along the seam of closed curves and surfaces.
     pub fn point_from_path_coords(
         &self,
         point: impl Into<Point<1>>,
     ) -> Point<3> {
+        let point = wrap(point.into(), self.period());
+
         match self {
             Self::Circle(circle) => circle.point_from_circle_coords(point),
+            Self::Ellipse(ellipse) => ellipse.point_from_ellipse_coords(point),
             Self::Line(line) => line.point_from_line_coords(point),
+            Self::Spiral(spiral) => spiral.point_from_spiral_coords(point),
+            Self::Involute(involute) => {
+                involute.point_from_involute_coords(point)
+            }
         }
     }
 
@@ -157,10 +260,67 @@ impl GlobalPath {
     ) -> Vector<3> {
         match self {
             Self::Circle(circle) => circle.vector_from_circle_coords(vector),
+            Self::Ellipse(ellipse) => {
+                ellipse.vector_from_ellipse_coords(vector)
+            }
             Self::Line(line) => line.vector_from_line_coords(vector),
+            Self::Spiral(spiral) => spiral.vector_from_spiral_coords(vector),
+            Self::Involute(involute) => {
+                involute.vector_from_involute_coords(vector)
+            }
         }
     }
 
+    /// Compute the boundary of the arc between the two provided points
+    ///
+    /// The points are expected to be on the path. For circles and ellipses,
+    /// the arc goes from the first to the second point, in the direction of
+    /// the path's coordinate system. The resulting boundary accounts for the
+    /// periodicity of the coordinate system, meaning its end is always larger
+    /// than its start, even if the arc crosses the zero coordinate. Lines,
+    /// spirals, and involutes are not periodic, and there is only one arc
+    /// between any two points on them.
+    ///
+    /// If both points are identical, the boundary covers the full circle or
+    /// ellipse.
+    pub fn arc_boundary(
+        &self,
+        points: [impl Into<Point<3>>; 2],
+    ) -> CurveBoundary<Point<1>> {
+        let [start, end] = points.map(Into::into);
+
+        let [start, end] =
+            match self {
+                Self::Circle(circle) => [start, end]
+                    .map(|point| circle.point_to_circle_coords(point)),
+                Self::Ellipse(ellipse) => [start, end]
+                    .map(|point| ellipse.point_to_ellipse_coords(point)),
+                Self::Line(line) => {
+                    let boundary = [start, end]
+                        .map(|point| line.point_to_line_coords(point));
+                    return CurveBoundary::from(boundary);
+                }
+                Self::Spiral(spiral) => {
+                    let boundary = [start, end]
+                        .map(|point| spiral.point_to_spiral_coords(point));
+                    return CurveBoundary::from(boundary);
+                }
+                Self::Involute(involute) => {
+                    let boundary = [start, end]
+                        .map(|point| involute.point_to_involute_coords(point));
+                    return CurveBoundary::from(boundary);
+                }
+            };
+
+        let end = if end <= start {
+            end + Vector::from([Scalar::TAU])
+        } else {
+            end
+        };
+
+        CurveBoundary::from([start, end])
+    }
+
     /// Transform the path
     #[must_use]
     pub fn transform(self, transform: &Transform) -> Self {
@@ -168,7 +328,122 @@ impl GlobalPath {
             Self::Circle(curve) => {
                 Self::Circle(transform.transform_circle(&curve))
             }
+            Self::Ellipse(curve) => {
+                Self::Ellipse(transform.transform_ellipse(&curve))
+            }
             Self::Line(curve) => Self::Line(transform.transform_line(&curve)),
+            Self::Spiral(curve) => {
+                Self::Spiral(transform.transform_spiral(&curve))
+            }
+            Self::Involute(curve) => {
+                Self::Involute(transform.transform_involute(&curve))
+            }
         }
     }
 }
+
+/// Wrap a path coordinate into the first period, if there is one
+fn wrap(point: Point<1>, period: Option<Scalar>) -> Point<1> {
+    match period {
+        Some(period) => Point::from([point.t.rem_euclid(period)]),
+        None => point,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+    use fj_math::{Point, Scalar, Spiral, Vector};
+
+    use super::{GlobalPath, SurfacePath};
+
+    #[test]
+    fn period() {
+        assert_eq!(GlobalPath::x_axis().period(), None);
+        assert_eq!(
+            GlobalPath::circle_from_radius(1.).period(),
+            Some(Scalar::TAU)
+        );
+        assert_eq!(SurfacePath::u_axis().period(), None);
+        assert_eq!(
+            SurfacePath::circle_from_center_and_radius([0., 0.], 1.).period(),
+            Some(Scalar::TAU)
+        );
+    }
+
+    #[test]
+    fn point_from_path_coords_is_periodic() {
+        let circle = GlobalPath::circle_from_radius(1.);
+        assert_eq!(
+            circle.point_from_path_coords([0.]),
+            circle.point_from_path_coords([TAU]),
+        );
+        assert_eq!(
+            circle.point_from_path_coords([PI]),
+            circle.point_from_path_coords([PI - TAU]),
+        );
+
+        let circle = SurfacePath::circle_from_center_and_radius([0., 0.], 1.);
+        assert_eq!(
+            circle.point_from_path_coords([0.]),
+            circle.point_from_path_coords([TAU]),
+        );
+    }
+
+    #[test]
+    fn arc_boundary_across_zero_coordinate() {
+        let circle = GlobalPath::circle_from_radius(1.);
+
+        let boundary = circle.arc_boundary([[0., -1., 0.], [0., 1., 0.]]);
+        assert_eq!(
+            boundary.inner,
+            [Point::from([FRAC_PI_2 * 3.]), Point::from([FRAC_PI_2 * 5.])]
+        );
+
+        let boundary = circle.arc_boundary([[-1., 0., 0.], [-1., 0., 0.]]);
+        assert_eq!(boundary.inner, [Point::from([PI]), Point::from([PI * 3.])]);
+    }
+
+    #[test]
+    fn arc_boundary_on_spiral() {
+        let spiral = GlobalPath::Spiral(Spiral::from_center_and_pitch(
+            [0., 0., 0.],
+            1.,
+            1.,
+        ));
+
+        let [start, end] = [FRAC_PI_2, FRAC_PI_2 + TAU]
+            .map(|t| spiral.point_from_path_coords([t]));
+        let boundary = spiral.arc_boundary([start, end]);
+
+        for (point, expected) in
+            boundary.inner.into_iter().zip([FRAC_PI_2, FRAC_PI_2 + TAU])
+        {
+            assert!(
+                (point.t - Scalar::from(expected)).abs() < Scalar::from(1e-12)
+            );
+        }
+    }
+
+    #[test]
+    fn ellipse_from_center_and_axes() {
+        let ellipse = GlobalPath::ellipse_from_center_and_axes(
+            [0., 0., 0.],
+            [2., 0., 0.],
+            [0., 1., 0.],
+        );
+        assert!(matches!(ellipse, GlobalPath::Ellipse(_)));
+        assert_eq!(
+            ellipse.point_from_path_coords([Scalar::ZERO]),
+            Point::from([2., 0., 0.])
+        );
+
+        let circle = GlobalPath::ellipse_from_center_and_axes(
+            [0., 0., 0.],
+            Vector::unit_x(),
+            Vector::unit_y(),
+        );
+        assert!(matches!(circle, GlobalPath::Circle(_)));
+    }
+}
@@ -0,0 +1,144 @@
+//! Ruled surfaces
+
+use fj_math::{Point, Transform, Vector};
+
+use crate::algorithms::approx::Tolerance;
+
+use super::{CurveBoundary, CurveGeom, SurfaceGeom};
+
+/// A ruled surface, that linearly interpolates between two curves
+///
+/// The u-coordinate of the surface is the coordinate on both curves. The
+/// v-coordinate interpolates between the curves, from `a` at `0` to `b` at `1`.
+/// This makes ruled surfaces a simple building block for lofts and transitions
+/// between two profiles.
+///
+/// If `b` is a translated copy of `a`, the ruled surface is the same as a
+/// [`SurfaceGeometry`], which the kernel's objects can represent directly. See
+/// [`SurfaceGeometry::ruled`]. Otherwise, faces on the ruled surface have to be
+/// approximated. See [`BuildFace::ruled`].
+///
+/// [`SurfaceGeometry`]: super::SurfaceGeometry
+/// [`SurfaceGeometry::ruled`]: super::SurfaceGeometry::ruled
+/// [`BuildFace::ruled`]: crate::operations::build::BuildFace::ruled
+#[derive(Debug)]
+pub struct RuledSurface {
+    a: Box<dyn CurveGeom>,
+    b: Box<dyn CurveGeom>,
+}
+
+impl RuledSurface {
+    /// Construct a ruled surface between two curves
+    pub fn new(
+        a: impl CurveGeom + 'static,
+        b: impl CurveGeom + 'static,
+    ) -> Self {
+        Self {
+            a: Box::new(a),
+            b: Box::new(b),
+        }
+    }
+
+    /// Access the curve at `v = 0`
+    pub fn a(&self) -> &dyn CurveGeom {
+        self.a.as_ref()
+    }
+
+    /// Access the curve at `v = 1`
+    pub fn b(&self) -> &dyn CurveGeom {
+        self.b.as_ref()
+    }
+
+    /// Approximate the surface within the provided boundary
+    ///
+    /// Returns the u-coordinates of the rulings that approximate the surface,
+    /// in the order defined by the boundary, including the boundary itself.
+    /// Between two neighboring rulings, both curves are within the tolerance
+    /// of the straight line that connects their points.
+    pub fn approximate(
+        &self,
+        boundary: CurveBoundary<Point<1>>,
+        tolerance: Tolerance,
+    ) -> Vec<Point<1>> {
+        let [start, end] = boundary.inner;
+
+        let mut rulings = vec![start, end];
+        rulings.extend(self.a.approximate(boundary, tolerance));
+        rulings.extend(self.b.approximate(boundary, tolerance));
+
+        rulings.sort();
+        rulings.dedup();
+        if start > end {
+            rulings.reverse();
+        }
+
+        rulings
+    }
+}
+
+impl SurfaceGeom for RuledSurface {
+    fn point_from_surface_coords(&self, point: Point<2>) -> Point<3> {
+        let u = Point::from([point.u]);
+
+        let a = self.a.point_from_curve_coords(u);
+        let b = self.b.point_from_curve_coords(u);
+
+        a + (b - a) * point.v
+    }
+
+    fn derivatives(&self, point: Point<2>) -> [Vector<3>; 2] {
+        let u = Point::from([point.u]);
+
+        let da = self.a.derivative(u);
+        let db = self.b.derivative(u);
+
+        let du = da + (db - da) * point.v;
+        let dv = self.b.point_from_curve_coords(u)
+            - self.a.point_from_curve_coords(u);
+
+        [du, dv]
+    }
+
+    fn transform(&self, transform: &Transform) -> Box<dyn SurfaceGeom> {
+        Box::new(Self {
+            a: self.a.transform(transform),
+            b: self.b.transform(transform),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Line, Point, Scalar, Vector};
+
+    use crate::geometry::{GlobalPath, SurfaceGeom};
+
+    use super::RuledSurface;
+
+    #[test]
+    fn point_and_derivatives() {
+        // A twisted surface between two skew lines.
+        let surface = RuledSurface::new(
+            GlobalPath::x_axis(),
+            GlobalPath::Line(Line::from_origin_and_direction(
+                Point::from([0., 0., 1.]),
+                Vector::from([0., 1., 0.]),
+            )),
+        );
+
+        assert_eq!(
+            surface.point_from_surface_coords(Point::from([2., 0.5])),
+            Point::from([1., 1., 0.5])
+        );
+
+        let [du, dv] = surface.derivatives(Point::from([2., 0.5]));
+        assert_eq!(du, Vector::from([0.5, 0.5, 0.]));
+        assert_eq!(dv, Vector::from([-2., 2., 1.]));
+
+        let normal = surface.normal_at(Point::from([0., 0.]));
+        assert!(
+            (normal - Vector::from([0., -1., 0.])).magnitude()
+                < Scalar::from(1e-12)
+        );
+    }
+}
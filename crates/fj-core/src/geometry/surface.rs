@@ -1,8 +1,8 @@
 //! The geometry that defines a surface
 
-use fj_math::{Line, Plane, Point, Transform, Vector};
+use fj_math::{Line, Plane, Point, Scalar, Transform, Vector};
 
-use super::GlobalPath;
+use super::{GlobalPath, SurfaceGeom};
 
 /// The geometry that defines a surface
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -15,6 +15,65 @@ pub struct SurfaceGeometry {
 }
 
 impl SurfaceGeometry {
+    /// Construct the ruled surface between two paths, if possible
+    ///
+    /// The resulting surface linearly interpolates between `a`, at `v = 0`,
+    /// and `b`, at `v = 1`. This is only possible, if `b` is a translated copy
+    /// of `a`. Returns `None` otherwise, or if both paths are the same.
+    ///
+    /// See [`RuledSurface`] for ruled surfaces between arbitrary curves, and
+    /// [`BuildFace::ruled`] for building faces on either.
+    ///
+    /// [`RuledSurface`]: super::RuledSurface
+    /// [`BuildFace::ruled`]: crate::operations::build::BuildFace::ruled
+    pub fn ruled(a: GlobalPath, b: GlobalPath) -> Option<Self> {
+        let is_translated_copy = match (a, b) {
+            (GlobalPath::Circle(a), GlobalPath::Circle(b)) => {
+                a.a() == b.a() && a.b() == b.b()
+            }
+            (GlobalPath::Ellipse(a), GlobalPath::Ellipse(b)) => {
+                a.a() == b.a() && a.b() == b.b()
+            }
+            (GlobalPath::Line(a), GlobalPath::Line(b)) => {
+                a.direction() == b.direction()
+            }
+            (GlobalPath::Spiral(a), GlobalPath::Spiral(b)) => {
+                a.a() == b.a()
+                    && a.b() == b.b()
+                    && a.start() == b.start()
+                    && a.growth() == b.growth()
+            }
+            (GlobalPath::Involute(a), GlobalPath::Involute(b)) => {
+                a.a() == b.a() && a.b() == b.b()
+            }
+            _ => false,
+        };
+
+        let v = b.origin() - a.origin();
+
+        if !is_translated_copy || v.magnitude() == Scalar::ZERO {
+            return None;
+        }
+        if let GlobalPath::Line(line) = a {
+            // Both lines are the same line, just with different origins.
+            if line.direction().cross(&v).magnitude() == Scalar::ZERO {
+                return None;
+            }
+        }
+
+        Some(Self { u: a, v })
+    }
+
+    /// Access the periods of the surface's coordinate system
+    ///
+    /// Returns the period of the u and v coordinates, in that order. The
+    /// period of a coordinate is `None`, if the surface is not periodic in that
+    /// direction. Surfaces that are swept from a circle, like cylinders, are
+    /// periodic in u, with the seam at the zero coordinate.
+    pub fn periods(&self) -> [Option<Scalar>; 2] {
+        [self.u.period(), None]
+    }
+
     /// Convert a point in surface coordinates to model coordinates
     pub fn point_from_surface_coords(
         &self,
@@ -39,6 +98,14 @@ impl SurfaceGeometry {
         Line::from_origin_and_direction(self.u.origin(), self.v)
     }
 
+    /// Compute the normal of the surface at the provided point
+    ///
+    /// The normal is normalized, and points in the direction of the cross
+    /// product of the u- and v-axes. See [`SurfaceGeom::normal_at`].
+    pub fn normal_at(&self, point: impl Into<Point<2>>) -> Vector<3> {
+        SurfaceGeom::normal_at(self, point.into())
+    }
+
     /// Project the global point into the surface
     pub fn project_global_point(&self, point: impl Into<Point<3>>) -> Point<2> {
         let GlobalPath::Line(line) = self.u else {
@@ -61,7 +128,9 @@ impl SurfaceGeometry {
 
 #[cfg(test)]
 mod tests {
-    use fj_math::{Line, Point, Vector};
+    use std::f64::consts::TAU;
+
+    use fj_math::{Line, Point, Scalar, Transform, Vector};
     use pretty_assertions::assert_eq;
 
     use crate::geometry::{GlobalPath, SurfaceGeometry};
@@ -97,4 +166,56 @@ mod tests {
             Vector::from([0., 4., 8.]),
         );
     }
+
+    #[test]
+    fn normal_at() {
+        let plane = SurfaceGeometry {
+            u: GlobalPath::x_axis(),
+            v: Vector::from([0., 2., 0.]),
+        };
+        assert_eq!(plane.normal_at([1., 1.]), Vector::from([0., 0., 1.]));
+
+        let cylinder = SurfaceGeometry {
+            u: GlobalPath::circle_from_radius(1.),
+            v: Vector::from([0., 0., 1.]),
+        };
+        let normal = cylinder.normal_at([0., 1.]);
+        assert!(
+            (normal - Vector::from([1., 0., 0.])).magnitude()
+                < Scalar::from(1e-9)
+        );
+    }
+
+    #[test]
+    fn seam_of_periodic_surface() {
+        let surface = SurfaceGeometry {
+            u: GlobalPath::circle_from_radius(1.),
+            v: Vector::from([0., 0., 1.]),
+        };
+
+        assert_eq!(surface.periods(), [Some(Scalar::TAU), None]);
+        assert_eq!(
+            surface.point_from_surface_coords([0., 1.]),
+            surface.point_from_surface_coords([TAU, 1.]),
+        );
+    }
+
+    #[test]
+    fn ruled() {
+        let a = GlobalPath::circle_from_radius(1.);
+        let b = a.transform(&Transform::translation([1., 2., 3.]));
+
+        let surface = SurfaceGeometry::ruled(a, b);
+        assert_eq!(
+            surface,
+            Some(SurfaceGeometry {
+                u: a,
+                v: Vector::from([1., 2., 3.]),
+            })
+        );
+
+        let c = GlobalPath::circle_from_radius(2.);
+        assert_eq!(SurfaceGeometry::ruled(a, c), None);
+        assert_eq!(SurfaceGeometry::ruled(a, a), None);
+    }
 }
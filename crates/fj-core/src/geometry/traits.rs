@@ -0,0 +1,462 @@
+//! Object-safe traits for curve and surface geometry
+//!
+//! The kernel's own geometry ([`GlobalPath`], [`SurfaceGeometry`], and the
+//! geometric primitives from `fj-math` that they are built on) implements these
+//! traits. Code that needs to be generic over geometry can use `dyn CurveGeom`
+//! and `dyn SurfaceGeom`, which allows downstream crates to plug in their own
+//! analytic geometry (involutes or superellipses, for example) by implementing
+//! the traits for their own types.
+//!
+//! The kernel uses these traits too, wherever it doesn't depend on the concrete
+//! type of geometry. [`SurfaceGeometry::normal_at`] is computed from the exact
+//! partial derivatives, for example, and triangulation refines faces based on
+//! `dyn SurfaceGeom`.
+
+use std::fmt::Debug;
+
+use fj_math::{Point, Scalar, Torus, Transform, Vector};
+
+use crate::algorithms::approx::{Approx, Tolerance};
+
+use super::{CurveBoundary, GlobalPath, SurfaceGeometry};
+
+/// The geometry of a curve in global (3D) space
+pub trait CurveGeom: Debug {
+    /// Convert a point in curve coordinates into global coordinates
+    fn point_from_curve_coords(&self, point: Point<1>) -> Point<3>;
+
+    /// Compute the derivative of the curve at the provided point
+    fn derivative(&self, point: Point<1>) -> Vector<3>;
+
+    /// Access the period of the curve's coordinate system
+    ///
+    /// Returns `None`, which is the default, if the curve is not periodic.
+    /// Implementations for closed curves should return their period, so
+    /// approximation and triangulation can treat their seam correctly.
+    fn period(&self) -> Option<Scalar> {
+        None
+    }
+
+    /// Approximate the curve within the provided boundary
+    ///
+    /// Returns the curve coordinates of the points that approximate the curve,
+    /// in the order defined by the boundary. The boundary itself is not
+    /// included in the approximation.
+    ///
+    /// The default implementation recursively subdivides the boundary, until
+    /// each segment is within the tolerance of the curve. Implementations for
+    /// which a deterministic approximation is required (see [path
+    /// approximation]) should override it.
+    ///
+    /// [path approximation]: crate::algorithms::approx::path
+    fn approximate(
+        &self,
+        boundary: CurveBoundary<Point<1>>,
+        tolerance: Tolerance,
+    ) -> Vec<Point<1>> {
+        subdivide(
+            &|point| self.point_from_curve_coords(point),
+            boundary,
+            tolerance,
+        )
+    }
+
+    /// Transform the curve
+    fn transform(&self, transform: &Transform) -> Box<dyn CurveGeom>;
+}
+
+/// The geometry of a surface in global (3D) space
+///
+/// Surface approximations are cached by the debug representation of the
+/// surface (see [`GeometryApproxCache`]). Implementations should derive
+/// `Debug`, or make sure otherwise that it includes everything that defines
+/// the surface.
+///
+/// [`GeometryApproxCache`]: crate::algorithms::approx::cache::GeometryApproxCache
+pub trait SurfaceGeom: Debug {
+    /// Convert a point in surface coordinates into global coordinates
+    fn point_from_surface_coords(&self, point: Point<2>) -> Point<3>;
+
+    /// Compute the partial derivatives of the surface at the provided point
+    ///
+    /// Returns the derivatives in u and v direction, in that order.
+    fn derivatives(&self, point: Point<2>) -> [Vector<3>; 2];
+
+    /// Access the periods of the surface's coordinate system
+    ///
+    /// Returns the periods of the u and v coordinates, in that order. By
+    /// default, the surface is not periodic in either direction.
+    fn periods(&self) -> [Option<Scalar>; 2] {
+        [None, None]
+    }
+
+    /// Compute the normal of the surface at the provided point
+    ///
+    /// The normal is normalized. Its direction is defined by the partial
+    /// derivatives, according to the right-hand rule.
+    fn normal_at(&self, point: Point<2>) -> Vector<3> {
+        let [du, dv] = self.derivatives(point);
+        du.cross(&dv).normalize()
+    }
+
+    /// Approximate the surface within the provided boundaries
+    ///
+    /// Returns the surface coordinates of a grid of points that approximates
+    /// the surface. The boundaries themselves are not included in the
+    /// approximation.
+    ///
+    /// The default implementation determines the grid by approximating the
+    /// iso-curves through the center of the boundaries, using the same
+    /// approach as [`CurveGeom::approximate`].
+    fn approximate(
+        &self,
+        boundaries: [CurveBoundary<Point<1>>; 2],
+        tolerance: Tolerance,
+    ) -> Vec<Point<2>> {
+        let [range_u, range_v] = boundaries;
+        let [mid_u, mid_v] =
+            boundaries.map(|boundary| midpoint(boundary.inner).t);
+
+        let us = subdivide(
+            &|u| self.point_from_surface_coords(Point::from([u.t, mid_v])),
+            range_u,
+            tolerance,
+        );
+        let vs = subdivide(
+            &|v| self.point_from_surface_coords(Point::from([mid_u, v.t])),
+            range_v,
+            tolerance,
+        );
+
+        grid(&us, &vs)
+    }
+
+    /// Transform the surface
+    fn transform(&self, transform: &Transform) -> Box<dyn SurfaceGeom>;
+}
+
+impl CurveGeom for GlobalPath {
+    fn point_from_curve_coords(&self, point: Point<1>) -> Point<3> {
+        self.point_from_path_coords(point)
+    }
+
+    fn derivative(&self, point: Point<1>) -> Vector<3> {
+        match self {
+            Self::Circle(circle) => {
+                // The derivative of a circle is its radius vector, rotated by
+                // a quarter turn in the direction of the circle.
+                circle.vector_from_circle_coords([point.t + Scalar::PI / 2.])
+            }
+            Self::Ellipse(ellipse) => {
+                // Same as for the circle, since an ellipse is just a circle
+                // that has been transformed by a linear map.
+                ellipse.vector_from_ellipse_coords([point.t + Scalar::PI / 2.])
+            }
+            Self::Line(line) => line.direction(),
+            Self::Spiral(spiral) => spiral.derivative(point),
+            Self::Involute(involute) => involute.derivative(point),
+        }
+    }
+
+    fn period(&self) -> Option<Scalar> {
+        GlobalPath::period(self)
+    }
+
+    fn approximate(
+        &self,
+        boundary: CurveBoundary<Point<1>>,
+        tolerance: Tolerance,
+    ) -> Vec<Point<1>> {
+        (*self, boundary)
+            .approx_with_cache(tolerance, &mut ())
+            .into_iter()
+            .map(|(point_curve, _)| point_curve)
+            .collect()
+    }
+
+    fn transform(&self, transform: &Transform) -> Box<dyn CurveGeom> {
+        Box::new(GlobalPath::transform(*self, transform))
+    }
+}
+
+impl SurfaceGeom for SurfaceGeometry {
+    fn point_from_surface_coords(&self, point: Point<2>) -> Point<3> {
+        SurfaceGeometry::point_from_surface_coords(self, point)
+    }
+
+    fn derivatives(&self, point: Point<2>) -> [Vector<3>; 2] {
+        [self.u.derivative(Point::from([point.u])), self.v]
+    }
+
+    fn periods(&self) -> [Option<Scalar>; 2] {
+        SurfaceGeometry::periods(self)
+    }
+
+    fn approximate(
+        &self,
+        _: [CurveBoundary<Point<1>>; 2],
+        _: Tolerance,
+    ) -> Vec<Point<2>> {
+        // The surface is swept along a straight line. Any point in its interior
+        // would be on a straight line between two points on its boundary, so
+        // none are required.
+        Vec::new()
+    }
+
+    fn transform(&self, transform: &Transform) -> Box<dyn SurfaceGeom> {
+        Box::new(SurfaceGeometry::transform(*self, transform))
+    }
+}
+
+impl SurfaceGeom for Torus {
+    fn point_from_surface_coords(&self, point: Point<2>) -> Point<3> {
+        self.point_from_torus_coords(point)
+    }
+
+    fn derivatives(&self, point: Point<2>) -> [Vector<3>; 2] {
+        let major = self.major();
+        let radial = major.vector_from_circle_coords([point.u]).normalize();
+        let tangent = major
+            .vector_from_circle_coords([point.u + Scalar::PI / 2.])
+            .normalize();
+        let (sin, cos) = point.v.sin_cos();
+
+        let du = tangent * (self.major_radius() + self.minor_radius() * cos);
+        let dv = (radial * -sin + self.axis() * cos) * self.minor_radius();
+
+        [du, dv]
+    }
+
+    fn periods(&self) -> [Option<Scalar>; 2] {
+        [Some(Scalar::TAU); 2]
+    }
+
+    fn approximate(
+        &self,
+        boundaries: [CurveBoundary<Point<1>>; 2],
+        tolerance: Tolerance,
+    ) -> Vec<Point<2>> {
+        (self, boundaries)
+            .approx_with_cache(tolerance, &mut ())
+            .into_iter()
+            .map(|point| point.local_form)
+            .collect()
+    }
+
+    fn transform(&self, transform: &Transform) -> Box<dyn SurfaceGeom> {
+        Box::new(transform.transform_torus(self))
+    }
+}
+
+/// The maximum depth of the subdivision in the default approximations
+///
+/// This is a safeguard against geometry that never converges (because it has
+/// a discontinuity, for example), not a limit that is expected to be reached.
+const MAX_DEPTH: u32 = 16;
+
+/// The minimum depth of the subdivision in the default approximations
+///
+/// Without this, a curve whose midpoint happens to lie on the chord between
+/// the boundary points (an S-shaped curve, for example) would not be
+/// subdivided at all.
+const MIN_DEPTH: u32 = 2;
+
+fn subdivide(
+    curve: &dyn Fn(Point<1>) -> Point<3>,
+    boundary: CurveBoundary<Point<1>>,
+    tolerance: Tolerance,
+) -> Vec<Point<1>> {
+    let [a, b] = boundary.inner;
+
+    let mut points = Vec::new();
+    subdivide_inner(curve, [a, b], tolerance.inner(), 0, &mut points);
+
+    points
+}
+
+fn subdivide_inner(
+    curve: &dyn Fn(Point<1>) -> Point<3>,
+    [a, b]: [Point<1>; 2],
+    tolerance: Scalar,
+    depth: u32,
+    points: &mut Vec<Point<1>>,
+) {
+    if depth >= MAX_DEPTH || a == b {
+        return;
+    }
+
+    let m = midpoint([a, b]);
+    let [a_global, m_global, b_global] = [a, m, b].map(curve);
+
+    let deviation = distance_to_segment(m_global, [a_global, b_global]);
+    if depth < MIN_DEPTH || deviation > tolerance {
+        subdivide_inner(curve, [a, m], tolerance, depth + 1, points);
+        points.push(m);
+        subdivide_inner(curve, [m, b], tolerance, depth + 1, points);
+    }
+}
+
+fn midpoint([a, b]: [Point<1>; 2]) -> Point<1> {
+    Point::from([(a.t + b.t) / 2.])
+}
+
+fn distance_to_segment(point: Point<3>, [a, b]: [Point<3>; 2]) -> Scalar {
+    let ab = b - a;
+    let length_squared = ab.dot(&ab);
+
+    if length_squared == Scalar::ZERO {
+        return point.distance_to(&a);
+    }
+
+    let mut t = (point - a).dot(&ab) / length_squared;
+    if t < Scalar::ZERO {
+        t = Scalar::ZERO;
+    }
+    if t > Scalar::ONE {
+        t = Scalar::ONE;
+    }
+
+    point.distance_to(&(a + ab * t))
+}
+
+fn grid(us: &[Point<1>], vs: &[Point<1>]) -> Vec<Point<2>> {
+    us.iter()
+        .flat_map(|u| vs.iter().map(move |v| Point::from([u.t, v.t])))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use fj_math::{Circle, Point, Scalar, Torus, Transform, Vector};
+
+    use crate::{
+        algorithms::approx::{Approx, Tolerance},
+        geometry::{CurveBoundary, GlobalPath, SurfaceGeometry},
+    };
+
+    use super::{CurveGeom, SurfaceGeom};
+
+    /// The involute of the unit circle, as an example of user-defined geometry
+    #[derive(Debug)]
+    struct Involute {
+        transform: Transform,
+    }
+
+    impl CurveGeom for Involute {
+        fn point_from_curve_coords(&self, point: Point<1>) -> Point<3> {
+            let t = point.t;
+            let (sin, cos) = t.sin_cos();
+
+            let point =
+                Point::from([cos + t * sin, sin - t * cos, Scalar::ZERO]);
+            self.transform.transform_point(&point)
+        }
+
+        fn derivative(&self, point: Point<1>) -> Vector<3> {
+            let t = point.t;
+            let (sin, cos) = t.sin_cos();
+
+            let vector = Vector::from([t * cos, t * sin, Scalar::ZERO]);
+            self.transform.transform_vector(&vector)
+        }
+
+        fn transform(&self, transform: &Transform) -> Box<dyn CurveGeom> {
+            Box::new(Self {
+                transform: *transform * self.transform,
+            })
+        }
+    }
+
+    #[test]
+    fn approximate_user_defined_curve() {
+        let involute: Box<dyn CurveGeom> = Box::new(Involute {
+            transform: Transform::identity(),
+        });
+        let boundary = CurveBoundary::from([[0.], [TAU]]);
+        let tolerance = Tolerance::from(0.01);
+
+        let points = involute.approximate(boundary, tolerance);
+        assert!(!points.is_empty());
+
+        // Points must be ordered and within the boundary.
+        let mut previous = Scalar::ZERO;
+        for point in &points {
+            assert!(point.t > previous);
+            assert!(point.t < Scalar::TAU);
+            previous = point.t;
+        }
+
+        // Transforming the curve must not change its curve coordinates.
+        let translation = Vector::from([1., 2., 3.]);
+        let transformed =
+            involute.transform(&Transform::translation(translation));
+        for point in points {
+            let expected =
+                involute.point_from_curve_coords(point) + translation;
+            let actual = transformed.point_from_curve_coords(point);
+
+            assert!(actual.distance_to(&expected) < Scalar::from(1e-12));
+        }
+    }
+
+    #[test]
+    fn global_path_approximation_matches_path_approximation() {
+        let path = GlobalPath::circle_from_radius(1.);
+        let boundary = CurveBoundary::from([[0.], [TAU]]);
+        let tolerance = Tolerance::from(0.1);
+
+        let expected = (path, boundary)
+            .approx(tolerance)
+            .into_iter()
+            .map(|(point_curve, _)| point_curve)
+            .collect::<Vec<_>>();
+        let geom: &dyn CurveGeom = &path;
+
+        assert_eq!(geom.approximate(boundary, tolerance), expected);
+    }
+
+    #[test]
+    fn periods() {
+        let circle: &dyn CurveGeom = &GlobalPath::circle_from_radius(1.);
+        let involute: &dyn CurveGeom = &Involute {
+            transform: Transform::identity(),
+        };
+        assert_eq!(circle.period(), Some(Scalar::TAU));
+        assert_eq!(involute.period(), None);
+
+        let cylinder: &dyn SurfaceGeom = &SurfaceGeometry {
+            u: GlobalPath::circle_from_radius(1.),
+            v: Vector::from([0., 0., 1.]),
+        };
+        let torus: &dyn SurfaceGeom =
+            &Torus::new(Circle::from_center_and_radius([0., 0., 0.], 2.), 1.);
+        assert_eq!(cylinder.periods(), [Some(Scalar::TAU), None]);
+        assert_eq!(torus.periods(), [Some(Scalar::TAU); 2]);
+    }
+
+    #[test]
+    fn surface_derivatives() {
+        let surface = SurfaceGeometry {
+            u: GlobalPath::circle_from_radius(1.),
+            v: Vector::from([0., 0., 2.]),
+        };
+        let geom: &dyn SurfaceGeom = &surface;
+
+        let [du, dv] = geom.derivatives(Point::from([0., 0.]));
+        assert!(
+            (du - Vector::from([0., 1., 0.])).magnitude() < Scalar::from(1e-12)
+        );
+        assert_eq!(dv, Vector::from([0., 0., 2.]));
+
+        let torus =
+            Torus::new(Circle::from_center_and_radius([0., 0., 0.], 2.), 1.);
+        let geom: &dyn SurfaceGeom = &torus;
+        let normal = geom.normal_at(Point::from([0., 0.]));
+        assert!(
+            (normal - Vector::from([1., 0., 0.])).magnitude()
+                < Scalar::from(1e-12)
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! # Parametric feature history
+//!
+//! The operations API builds shapes directly: every call produces new objects,
+//! and the parameters that went into them are lost. This module provides an
+//! optional layer on top of that, which records the steps that build a shape
+//! (sketch, sweep, transform, ...) as a replayable [`History`].
+//!
+//! Each step is a **feature**, a closure that builds its output from named
+//! **parameters** and the outputs of earlier features. While a feature is
+//! built, the history keeps track of which parameters and features it uses.
+//! When a parameter changes, only the features that depend on it (directly or
+//! through other features) are rebuilt, on the next call to
+//! [`History::regenerate`].
+
+use std::{
+    any::Any,
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+};
+
+use fj_math::Scalar;
+
+use crate::services::Services;
+
+/// A replayable history of features
+#[derive(Default)]
+pub struct History {
+    parameters: BTreeMap<String, Scalar>,
+    features: Vec<Feature>,
+}
+
+impl History {
+    /// Create an empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the value of a parameter
+    ///
+    /// All features that use the parameter will be rebuilt on the next call to
+    /// [`History::regenerate`].
+    pub fn set_parameter(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<Scalar>,
+    ) -> &mut Self {
+        let name = name.into();
+        let value = value.into();
+
+        if self.parameters.get(&name) == Some(&value) {
+            return self;
+        }
+
+        for feature in &mut self.features {
+            if feature.parameters.contains(&name) {
+                feature.output = None;
+            }
+        }
+
+        self.parameters.insert(name, value);
+        self
+    }
+
+    /// Access the value of a parameter
+    pub fn parameter(&self, name: &str) -> Option<Scalar> {
+        self.parameters.get(name).copied()
+    }
+
+    /// Add a feature to the end of the history
+    ///
+    /// The feature is built on the next call to [`History::regenerate`]. The
+    /// returned [`FeatureId`] can be used to access its output, from later
+    /// features or through [`History::get`].
+    pub fn add_feature<T>(
+        &mut self,
+        name: impl Into<String>,
+        build: impl Fn(&mut Context) -> T + 'static,
+    ) -> FeatureId<T>
+    where
+        T: Clone + 'static,
+    {
+        let index = self.features.len();
+
+        self.features.push(Feature {
+            name: name.into(),
+            build: Box::new(move |context: &mut Context| -> Box<dyn Any> {
+                Box::new(build(context))
+            }),
+            output: None,
+            parameters: BTreeSet::new(),
+            dependencies: BTreeSet::new(),
+        });
+
+        FeatureId {
+            index,
+            _output: PhantomData,
+        }
+    }
+
+    /// Iterate over the names of all features, in order
+    pub fn feature_names(&self) -> impl Iterator<Item = &str> {
+        self.features.iter().map(|feature| feature.name.as_str())
+    }
+
+    /// Access the output of a feature
+    ///
+    /// Returns `None`, if the feature needs to be rebuilt.
+    pub fn get<T>(&self, id: FeatureId<T>) -> Option<T>
+    where
+        T: Clone + 'static,
+    {
+        self.features[id.index]
+            .output
+            .as_ref()
+            .map(|output| downcast(&**output))
+    }
+
+    /// Rebuild all features whose inputs have changed
+    ///
+    /// Features are rebuilt in the order they were added. A feature is rebuilt,
+    /// if it has not been built yet, if a parameter it uses has changed, or if
+    /// a feature it uses has been rebuilt.
+    ///
+    /// Returns the number of features that were rebuilt.
+    pub fn regenerate(&mut self, services: &mut Services) -> usize {
+        let mut rebuilt = BTreeSet::new();
+
+        for index in 0..self.features.len() {
+            let (earlier, later) = self.features.split_at_mut(index);
+            let feature = &mut later[0];
+
+            let dependency_rebuilt =
+                !feature.dependencies.is_disjoint(&rebuilt);
+            if feature.output.is_some() && !dependency_rebuilt {
+                continue;
+            }
+
+            let mut context = Context {
+                parameters: &self.parameters,
+                features: earlier,
+                services,
+                used_parameters: BTreeSet::new(),
+                used_features: BTreeSet::new(),
+            };
+            let output = (feature.build)(&mut context);
+
+            feature.parameters = context.used_parameters;
+            feature.dependencies = context.used_features;
+            feature.output = Some(output);
+
+            rebuilt.insert(index);
+        }
+
+        rebuilt.len()
+    }
+}
+
+/// Identifies a feature within a [`History`]
+///
+/// The type parameter is the type of the feature's output.
+pub struct FeatureId<T> {
+    index: usize,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for FeatureId<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FeatureId<T> {}
+
+/// The context in which a feature is built
+///
+/// Provides access to parameters, the outputs of earlier features, and the
+/// [`Services`] instance that the feature's objects are inserted into.
+pub struct Context<'r> {
+    parameters: &'r BTreeMap<String, Scalar>,
+    features: &'r [Feature],
+    services: &'r mut Services,
+    used_parameters: BTreeSet<String>,
+    used_features: BTreeSet<usize>,
+}
+
+impl Context<'_> {
+    /// Access the value of a parameter
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the parameter has not been set.
+    pub fn parameter(&mut self, name: &str) -> Scalar {
+        let Some(value) = self.parameters.get(name) else {
+            panic!("Parameter `{name}` has not been set");
+        };
+
+        self.used_parameters.insert(name.to_string());
+        *value
+    }
+
+    /// Access the output of an earlier feature
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the feature has not been added before the one currently
+    /// being built.
+    pub fn feature<T>(&mut self, id: FeatureId<T>) -> T
+    where
+        T: Clone + 'static,
+    {
+        let feature = self
+            .features
+            .get(id.index)
+            .expect("Features can only use the outputs of earlier features");
+        let output = feature
+            .output
+            .as_ref()
+            .expect("Earlier features have been built");
+
+        self.used_features.insert(id.index);
+        downcast(&**output)
+    }
+
+    /// Access the services
+    pub fn services(&mut self) -> &mut Services {
+        self.services
+    }
+}
+
+struct Feature {
+    name: String,
+    build: BuildFn,
+    output: Option<Box<dyn Any>>,
+    parameters: BTreeSet<String>,
+    dependencies: BTreeSet<usize>,
+}
+
+type BuildFn = Box<dyn Fn(&mut Context) -> Box<dyn Any>>;
+
+fn downcast<T>(output: &dyn Any) -> T
+where
+    T: Clone + 'static,
+{
+    output
+        .downcast_ref::<T>()
+        .expect("`FeatureId` guarantees output type")
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use crate::{
+        algorithms::transform::TransformObject,
+        objects::Shell,
+        operations::{build::BuildShell, insert::Insert},
+        services::Services,
+    };
+
+    use super::History;
+
+    #[test]
+    fn regenerate_only_affected_features() {
+        let mut services = Services::new();
+        let mut history = History::new();
+
+        let builds = Rc::new(Cell::new(0));
+
+        let tetrahedron = history.add_feature("tetrahedron", {
+            let builds = builds.clone();
+            move |context| {
+                builds.set(builds.get() + 1);
+
+                let size = context.parameter("size").into_f64();
+                Shell::tetrahedron(
+                    [
+                        [0., 0., 0.],
+                        [size, 0., 0.],
+                        [0., size, 0.],
+                        [0., 0., size],
+                    ],
+                    context.services(),
+                )
+                .insert(context.services())
+                .shell
+            }
+        });
+        let moved = history.add_feature("moved", move |context| {
+            let offset = context.parameter("offset");
+            let shell = context.feature(tetrahedron);
+            shell.translate([offset, offset, offset], context.services())
+        });
+
+        history
+            .set_parameter("size", 1.)
+            .set_parameter("offset", 2.);
+        assert_eq!(history.regenerate(&mut services), 2);
+        assert_eq!(builds.get(), 1);
+
+        history.set_parameter("offset", 3.);
+        assert!(history.get(moved).is_none());
+        assert_eq!(history.regenerate(&mut services), 1);
+        assert_eq!(builds.get(), 1);
+
+        history.set_parameter("size", 2.);
+        assert_eq!(history.regenerate(&mut services), 2);
+        assert_eq!(builds.get(), 2);
+
+        assert!(history.get(tetrahedron).is_some());
+        assert!(history.get(moved).is_some());
+        assert_eq!(
+            history.feature_names().collect::<Vec<_>>(),
+            ["tetrahedron", "moved"]
+        );
+    }
+}
@@ -82,10 +82,16 @@
 //! [Fornjot]: https://www.fornjot.app/
 
 pub mod algorithms;
+pub mod bench_models;
+pub mod bindings;
 pub mod geometry;
+pub mod history;
 pub mod objects;
 pub mod operations;
 pub mod queries;
 pub mod services;
 pub mod storage;
 pub mod validate;
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
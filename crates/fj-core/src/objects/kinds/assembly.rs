@@ -0,0 +1,94 @@
+use fj_math::{Transform, Vector};
+
+use crate::{objects::Solid, storage::Handle};
+
+/// A collection of placed, named solids
+///
+/// An assembly references each of its parts as an [`Instance`]. Multiple
+/// instances can reference the same [`Solid`], placing copies of it in
+/// different positions, without having to transform the solid itself.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Assembly {
+    instances: Vec<Instance>,
+}
+
+impl Assembly {
+    /// Construct an instance of `Assembly`
+    pub fn new(instances: impl IntoIterator<Item = Instance>) -> Self {
+        Self {
+            instances: instances.into_iter().collect(),
+        }
+    }
+
+    /// Access the instances of the assembly
+    pub fn instances(&self) -> &[Instance] {
+        &self.instances
+    }
+}
+
+/// A [`Solid`] that has been placed within an [`Assembly`]
+///
+/// The placement of an instance is a rigid transform. It consists of a rotation
+/// around the origin, followed by a translation.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Instance {
+    name: String,
+    solid: Handle<Solid>,
+    rotation: Vector<3>,
+    translation: Vector<3>,
+}
+
+impl Instance {
+    /// Construct an instance that places the solid at its original position
+    pub fn new(name: impl Into<String>, solid: Handle<Solid>) -> Self {
+        Self {
+            name: name.into(),
+            solid,
+            rotation: Vector::from([0., 0., 0.]),
+            translation: Vector::from([0., 0., 0.]),
+        }
+    }
+
+    /// Replace the rotation of the instance
+    ///
+    /// The direction of the vector defines the rotation axis. Its length
+    /// defines the angle of the rotation.
+    #[must_use]
+    pub fn with_rotation(mut self, axis_angle: impl Into<Vector<3>>) -> Self {
+        self.rotation = axis_angle.into();
+        self
+    }
+
+    /// Replace the translation of the instance
+    #[must_use]
+    pub fn with_translation(mut self, offset: impl Into<Vector<3>>) -> Self {
+        self.translation = offset.into();
+        self
+    }
+
+    /// Access the name of the instance
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Access the solid that the instance places
+    pub fn solid(&self) -> &Handle<Solid> {
+        &self.solid
+    }
+
+    /// Access the rotation of the instance, as an axis-angle vector
+    pub fn rotation(&self) -> Vector<3> {
+        self.rotation
+    }
+
+    /// Access the translation of the instance
+    pub fn translation(&self) -> Vector<3> {
+        self.translation
+    }
+
+    /// Compute the transform that places the solid
+    pub fn transform(&self) -> Transform {
+        Transform::translation(self.translation)
+            * Transform::rotation(self.rotation)
+    }
+}
@@ -40,32 +40,52 @@ impl Cycle {
                 .next()
                 .expect("Invalid cycle: expected at least one edge");
 
-            let [a, b] = first.boundary().inner;
-            let edge_direction_positive = a < b;
-
-            let circle = match first.path() {
-                SurfacePath::Circle(circle) => circle,
-                SurfacePath::Line(_) => unreachable!(
-                    "Invalid cycle: less than 3 edges, but not all are circles"
-                ),
-            };
-            let cross_positive = circle.a().cross2d(&circle.b()) > Scalar::ZERO;
-
-            if edge_direction_positive == cross_positive {
-                return Winding::Ccw;
-            } else {
-                return Winding::Cw;
+            if let SurfacePath::Circle(circle) = first.path() {
+                let [a, b] = first.boundary().inner;
+                let edge_direction_positive = a < b;
+
+                let cross_positive =
+                    circle.a().cross2d(&circle.b()) > Scalar::ZERO;
+
+                if edge_direction_positive == cross_positive {
+                    return Winding::Ccw;
+                } else {
+                    return Winding::Cw;
+                }
             }
         }
 
         // Now that we got the special case out of the way, we can treat the
         // cycle as a polygon:
         // https://stackoverflow.com/a/1165943
+        //
+        // Spirals and involutes don't connect to themselves, so a cycle that
+        // contains them can have less than 3 edges. We add some points along
+        // those edges, to make sure the polygon is not degenerate.
+
+        let points = self
+            .half_edges()
+            .iter()
+            .flat_map(|half_edge| {
+                let mut points = vec![half_edge.start_position()];
+
+                if let SurfacePath::Spiral(_) | SurfacePath::Involute(_) =
+                    half_edge.path()
+                {
+                    let [a, b] = half_edge.boundary().inner;
+                    points.extend([0.25, 0.5, 0.75].map(|t| {
+                        half_edge.path().point_from_path_coords(a + (b - a) * t)
+                    }));
+                }
+
+                points
+            })
+            .collect::<Vec<_>>();
 
         let mut sum = Scalar::ZERO;
 
-        for (a, b) in self.half_edges().pairs() {
-            let [a, b] = [a, b].map(|edge| edge.start_position());
+        for (i, a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
 
             sum += (b.u - a.u) * (b.v + a.v);
         }
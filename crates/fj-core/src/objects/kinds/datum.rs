@@ -0,0 +1,72 @@
+use fj_math::{Line, Plane, Point, Vector};
+
+/// Reference geometry that doesn't contribute to the shape of a model
+///
+/// Datums define points, axes, and planes that operations can target, like
+/// mirroring across a plane, or rotating around an axis. Defining them once, as
+/// objects, makes it possible to share them between operations, instead of
+/// passing around raw geometry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Datum {
+    /// A datum point
+    Point(Point<3>),
+
+    /// A datum axis
+    Axis(Line<3>),
+
+    /// A datum plane
+    Plane(Plane),
+}
+
+impl Datum {
+    /// Construct a datum point
+    pub fn point(point: impl Into<Point<3>>) -> Self {
+        Self::Point(point.into())
+    }
+
+    /// Construct a datum axis from a point on the axis and its direction
+    pub fn axis(
+        origin: impl Into<Point<3>>,
+        direction: impl Into<Vector<3>>,
+    ) -> Self {
+        Self::Axis(Line::from_origin_and_direction(
+            origin.into(),
+            direction.into(),
+        ))
+    }
+
+    /// Construct a datum plane from its origin and two vectors within it
+    ///
+    /// The normal of the plane is the cross product of `u` and `v`.
+    pub fn plane(
+        origin: impl Into<Point<3>>,
+        u: impl Into<Vector<3>>,
+        v: impl Into<Vector<3>>,
+    ) -> Self {
+        Self::Plane(Plane::from_parametric(origin, u, v))
+    }
+
+    /// Access the datum's point, if it is a datum point
+    pub fn as_point(&self) -> Option<Point<3>> {
+        match self {
+            Self::Point(point) => Some(*point),
+            _ => None,
+        }
+    }
+
+    /// Access the datum's axis, if it is a datum axis
+    pub fn as_axis(&self) -> Option<Line<3>> {
+        match self {
+            Self::Axis(axis) => Some(*axis),
+            _ => None,
+        }
+    }
+
+    /// Access the datum's plane, if it is a datum plane
+    pub fn as_plane(&self) -> Option<Plane> {
+        match self {
+            Self::Plane(plane) => Some(*plane),
+            _ => None,
+        }
+    }
+}
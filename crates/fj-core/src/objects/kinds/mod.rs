@@ -1,5 +1,7 @@
+pub mod assembly;
 pub mod curve;
 pub mod cycle;
+pub mod datum;
 pub mod face;
 pub mod half_edge;
 pub mod region;
@@ -42,12 +42,15 @@
 mod kinds;
 mod object;
 mod object_set;
+mod stats;
 mod stores;
 
 pub use self::{
     kinds::{
+        assembly::{Assembly, Instance},
         curve::Curve,
         cycle::Cycle,
+        datum::Datum,
         face::{Face, Handedness},
         half_edge::HalfEdge,
         region::Region,
@@ -59,5 +62,6 @@ pub use self::{
     },
     object::{Bare, BehindHandle, Form, Object, WithHandle},
     object_set::ObjectSet,
+    stats::{ObjectStats, StoreStats},
     stores::{Objects, Surfaces},
 };
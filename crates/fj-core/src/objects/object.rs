@@ -1,14 +1,16 @@
+use std::any::Any;
+
 use crate::{
     objects::{
-        Curve, Cycle, Face, HalfEdge, Objects, Region, Shell, Sketch, Solid,
-        Surface, Vertex,
+        Assembly, Curve, Cycle, Datum, Face, HalfEdge, Objects, Region, Shell,
+        Sketch, Solid, Surface, Vertex,
     },
     storage::{Handle, HandleWrapper, ObjectId},
     validate::{Validate, ValidationError},
 };
 
 macro_rules! object {
-    ($($ty:ident, $name:expr, $store:ident;)*) => {
+    ($($ty:ident, $doc:expr, $store:ident;)*) => {
         /// An object
         ///
         /// This enum is generic over the form that the object takes. An
@@ -17,7 +19,7 @@ macro_rules! object {
         #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
         pub enum Object<F: Form> {
             $(
-                #[doc = concat!("A ", $name)]
+                #[doc = $doc]
                 $ty(F::Form<$ty>),
             )*
         }
@@ -32,6 +34,18 @@ macro_rules! object {
                 }
             }
 
+            /// Access the object as [`Any`], to downcast it to its type
+            pub(crate) fn as_any(&self) -> &dyn Any {
+                match self {
+                    $(
+                        Self::$ty(handle) => {
+                            let object: &$ty = handle;
+                            object
+                        }
+                    )*
+                }
+            }
+
             /// Validate the object
             pub fn validate(&self, errors: &mut Vec<ValidationError>) {
                 match self {
@@ -91,16 +105,18 @@ macro_rules! object {
 }
 
 object!(
-    Curve, "curve", curves;
-    Cycle, "cycle", cycles;
-    Face, "face", faces;
-    HalfEdge, "half-edge", half_edges;
-    Region, "region", regions;
-    Shell, "shell", shells;
-    Sketch, "sketch", sketches;
-    Solid, "solid", solids;
-    Surface, "surface", surfaces;
-    Vertex, "vertex", vertices;
+    Assembly, "An assembly", assemblies;
+    Curve, "A curve", curves;
+    Cycle, "A cycle", cycles;
+    Datum, "A datum", datums;
+    Face, "A face", faces;
+    HalfEdge, "A half-edge", half_edges;
+    Region, "A region", regions;
+    Shell, "A shell", shells;
+    Sketch, "A sketch", sketches;
+    Solid, "A solid", solids;
+    Surface, "A surface", surfaces;
+    Vertex, "A vertex", vertices;
 );
 
 /// The form that an object can take
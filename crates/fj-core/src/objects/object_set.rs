@@ -1,15 +1,24 @@
-use std::{collections::BTreeSet, fmt::Debug, slice, vec};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    slice, vec,
+};
 
 use itertools::Itertools;
 
-use crate::storage::Handle;
+use crate::storage::{Handle, ObjectId};
 
 /// An ordered set of objects
 ///
 /// This is the data structure used by all objects that reference multiple
 /// objects of the same type. It is a set, not containing any duplicate
 /// elements, and it maintains the insertion order of those elements.
-#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+///
+/// Looking up an object in the set takes constant time, regardless of the
+/// number of objects in it.
+#[derive(Clone)]
 pub struct ObjectSet<T> {
     // This is supposed to be a set data structure, so what is that `Vec` doing
     // here? Well, it's here because we need it to preserve insertion order, but
@@ -20,6 +29,10 @@ pub struct ObjectSet<T> {
     // immutable). We need to make sure there are no duplicates when this is
     // constructed (see the constructor below), but after that, we're fine.
     inner: Vec<Handle<T>>,
+
+    // Maps the ID of each object to its position in `inner`. This is derived
+    // from `inner`, and not considered for comparisons.
+    positions: HashMap<ObjectId, usize>,
 }
 
 impl<T> ObjectSet<T> {
@@ -47,7 +60,18 @@ impl<T> ObjectSet<T> {
             inner.push(handle);
         }
 
-        Self { inner }
+        Self::from_vec(inner)
+    }
+
+    /// Create an instance from a `Vec` that is known to contain no duplicates
+    fn from_vec(inner: Vec<Handle<T>>) -> Self {
+        let positions = inner
+            .iter()
+            .enumerate()
+            .map(|(position, handle)| (handle.id(), position))
+            .collect();
+
+        Self { inner, positions }
     }
 
     /// Return the number of objects in this set
@@ -118,7 +142,7 @@ impl<T> ObjectSet<T> {
 
     /// Return the index of the item, if available
     pub fn index_of(&self, handle: &Handle<T>) -> Option<usize> {
-        self.inner.iter().position(|h| h.id() == handle.id())
+        self.positions.get(&handle.id()).copied()
     }
 
     /// Access the item after the provided one
@@ -155,38 +179,147 @@ impl<T> ObjectSet<T> {
     where
         T: Debug + Ord,
     {
-        let mut iter = self.iter().cloned().peekable();
-
-        // Collect all items before the item we want to update.
-        let mut before = Vec::new();
-        loop {
-            let next = match iter.next() {
-                Some(handle) => handle,
-                None => {
-                    // We went through the whole iterator without finding the
-                    // item we were looking for.
-                    return None;
-                }
-            };
+        let position = self.index_of(original)?;
+
+        let mut inner = Vec::with_capacity(self.len() + N - 1);
+        inner.extend_from_slice(&self.inner[..position]);
+        inner.extend(replacements);
+        inner.extend_from_slice(&self.inner[position + 1..]);
+
+        // Only the replacements can be duplicates. Checking just them keeps
+        // this linear in the size of the set.
+        for i in position..position + N {
+            let duplicate = inner
+                .iter()
+                .enumerate()
+                .any(|(j, handle)| j != i && handle == &inner[i]);
+
+            if duplicate {
+                panic!(
+                    "Replacing object in `ObjectSet` results in duplicate \
+                    handle: {:?}",
+                    inner[i]
+                );
+            }
+        }
+
+        Some(Self::from_vec(inner))
+    }
 
-            if next.id() == original.id() {
-                break;
+    /// Create a new instance in which multiple objects have been replaced
+    ///
+    /// `replacements` maps the IDs of the original objects to the objects that
+    /// replace them. All replacements happen in a single pass over the set.
+    ///
+    /// Returns `None`, if none of the original objects are present.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the update results in a duplicate item.
+    #[must_use]
+    pub fn replace_many(
+        &self,
+        replacements: &BTreeMap<ObjectId, Vec<Handle<T>>>,
+    ) -> Option<Self>
+    where
+        T: Debug + Ord,
+    {
+        let mut replacement_happened = false;
+
+        let mut inner = Vec::with_capacity(self.len());
+        for handle in &self.inner {
+            if let Some(replacements) = replacements.get(&handle.id()) {
+                inner.extend(replacements.iter().cloned());
+                replacement_happened = true;
+            } else {
+                inner.push(handle.clone());
             }
+        }
 
-            before.push(next.clone());
+        if replacement_happened {
+            Some(Self::new(inner))
+        } else {
+            None
         }
+    }
 
-        // What's left in the iterator is what comes after the replaced item.
-        // Let's make that a bit more explicit by renaming the variable.
-        let after = iter;
+    /// Assert that the internal invariants of this set hold
+    ///
+    /// The set must not contain duplicates, and the position of each object, as
+    /// used for constant-time lookups, must match its place in the set.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if any invariant is violated.
+    #[cfg(any(test, feature = "test-support"))]
+    #[track_caller]
+    pub fn assert_invariants(&self)
+    where
+        T: Debug,
+    {
+        assert_eq!(
+            self.positions.len(),
+            self.inner.len(),
+            "`ObjectSet` contains duplicate handles: {:?}",
+            self.inner
+        );
 
-        Some(
-            before
-                .into_iter()
-                .chain(replacements)
-                .chain(after)
-                .collect(),
-        )
+        for (position, handle) in self.inner.iter().enumerate() {
+            assert_eq!(
+                self.positions.get(&handle.id()),
+                Some(&position),
+                "Position of {handle:?} in `ObjectSet` is out of date"
+            );
+        }
+    }
+}
+
+impl<T> Debug for ObjectSet<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ObjectSet")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<T> Eq for ObjectSet<T> where T: Eq {}
+
+impl<T> PartialEq for ObjectSet<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.eq(&other.inner)
+    }
+}
+
+impl<T> Hash for ObjectSet<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
+
+impl<T> Ord for ObjectSet<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.inner.cmp(&other.inner)
+    }
+}
+
+impl<T> PartialOrd for ObjectSet<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.inner.partial_cmp(&other.inner)
     }
 }
 
@@ -224,3 +357,188 @@ impl<'r, T> IntoIterator for &'r ObjectSet<T> {
         self.iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use proptest::{
+        prelude::*,
+        sample::Index,
+        test_runner::{TestCaseResult, TestRunner},
+    };
+
+    use crate::{
+        geometry::{GlobalPath, SurfaceGeometry},
+        objects::Surface,
+        operations::insert::Insert,
+        services::Services,
+    };
+
+    use super::ObjectSet;
+
+    #[test]
+    fn replace() {
+        let mut services = Services::new();
+
+        let [a, b, c, d, e] = [1., 2., 3., 4., 5.].map(|z| {
+            Surface::new(SurfaceGeometry {
+                u: GlobalPath::x_axis(),
+                v: [0., 0., z].into(),
+            })
+            .insert(&mut services)
+        });
+        let set = ObjectSet::new([a.clone(), b.clone(), c.clone()]);
+
+        let replaced = set
+            .replace(&b, [d.clone(), e.clone()])
+            .expect("`b` is in the set");
+        assert_eq!(replaced.index_of(&a), Some(0));
+        assert_eq!(replaced.index_of(&d), Some(1));
+        assert_eq!(replaced.index_of(&e), Some(2));
+        assert_eq!(replaced.index_of(&c), Some(3));
+        assert!(!replaced.contains(&b));
+
+        assert!(replaced.replace(&b, [b.clone()]).is_none());
+    }
+
+    #[test]
+    fn replace_many() {
+        let mut services = Services::new();
+
+        let [a, b, c, d, e] = [1., 2., 3., 4., 5.].map(|z| {
+            Surface::new(SurfaceGeometry {
+                u: GlobalPath::x_axis(),
+                v: [0., 0., z].into(),
+            })
+            .insert(&mut services)
+        });
+        let set = ObjectSet::new([a.clone(), b.clone(), c.clone()]);
+
+        let replacements = BTreeMap::from([
+            (a.id(), vec![d.clone()]),
+            (c.id(), vec![e.clone()]),
+        ]);
+        let replaced = set
+            .replace_many(&replacements)
+            .expect("Objects are in the set");
+        assert_eq!(replaced.iter().cloned().collect::<Vec<_>>(), [d, b, e]);
+
+        assert!(replaced.replace_many(&replacements).is_none());
+    }
+
+    #[test]
+    fn replace_preserves_invariants() {
+        let operations = prop::collection::vec(operation(), 1..32);
+        TestRunner::default()
+            .run(&operations, check_replace_preserves_invariants)
+            .unwrap();
+    }
+
+    fn check_replace_preserves_invariants(
+        operations: Vec<Operation>,
+    ) -> TestCaseResult {
+        let mut services = Services::new();
+
+        let mut z = 0.;
+        let mut surface = || {
+            z += 1.;
+            Surface::new(SurfaceGeometry {
+                u: GlobalPath::x_axis(),
+                v: [0., 0., z].into(),
+            })
+            .insert(&mut services)
+        };
+
+        // The set is checked against a plain `Vec`, which serves as a model
+        // of what the set should contain, and in which order.
+        let mut model = vec![surface(), surface(), surface()];
+        let mut set = ObjectSet::new(model.clone());
+
+        for operation in operations {
+            let position = operation.index().index(model.len());
+            let original = model[position].clone();
+
+            match operation {
+                Operation::Remove(_) => {
+                    if model.len() == 1 {
+                        continue;
+                    }
+
+                    set = set.replace(&original, []).unwrap();
+                    model.remove(position);
+                }
+                Operation::ReplaceWithOne(_) => {
+                    let replacement = surface();
+                    set =
+                        set.replace(&original, [replacement.clone()]).unwrap();
+                    model[position] = replacement;
+                }
+                Operation::ReplaceWithTwo(_) => {
+                    let [a, b] = [surface(), surface()];
+                    set =
+                        set.replace(&original, [a.clone(), b.clone()]).unwrap();
+                    model[position] = a;
+                    model.insert(position + 1, b);
+                }
+                Operation::ReplaceMany(_, other) => {
+                    let other = model[other.index(model.len())].clone();
+                    let replacements = [original.clone(), other]
+                        .into_iter()
+                        .map(|handle| (handle.id(), vec![surface()]))
+                        .collect::<BTreeMap<_, _>>();
+
+                    set = set.replace_many(&replacements).unwrap();
+                    model = model
+                        .into_iter()
+                        .flat_map(|handle| {
+                            replacements
+                                .get(&handle.id())
+                                .cloned()
+                                .unwrap_or_else(|| vec![handle])
+                        })
+                        .collect();
+                }
+            }
+
+            set.assert_invariants();
+            prop_assert_eq!(
+                set.iter().collect::<Vec<_>>(),
+                model.iter().collect::<Vec<_>>()
+            );
+            prop_assert!(!set.contains(&original));
+            prop_assert!(set.replace(&original, []).is_none());
+        }
+
+        Ok(())
+    }
+
+    #[derive(Clone, Debug)]
+    enum Operation {
+        Remove(Index),
+        ReplaceWithOne(Index),
+        ReplaceWithTwo(Index),
+        ReplaceMany(Index, Index),
+    }
+
+    impl Operation {
+        fn index(&self) -> &Index {
+            match self {
+                Self::Remove(index)
+                | Self::ReplaceWithOne(index)
+                | Self::ReplaceWithTwo(index)
+                | Self::ReplaceMany(index, _) => index,
+            }
+        }
+    }
+
+    fn operation() -> impl Strategy<Value = Operation> {
+        prop_oneof![
+            any::<Index>().prop_map(Operation::Remove),
+            any::<Index>().prop_map(Operation::ReplaceWithOne),
+            any::<Index>().prop_map(Operation::ReplaceWithTwo),
+            (any::<Index>(), any::<Index>())
+                .prop_map(|(a, b)| Operation::ReplaceMany(a, b)),
+        ]
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+
+/// Statistics about the objects in all stores
+///
+/// Returned by [`Objects::stats`].
+///
+/// [`Objects::stats`]: super::Objects::stats
+#[derive(Clone, Debug)]
+pub struct ObjectStats {
+    /// The statistics for each store
+    pub stores: Vec<StoreStats>,
+}
+
+impl ObjectStats {
+    /// Return the number of objects in all stores
+    pub fn count(&self) -> usize {
+        self.stores.iter().map(|store| store.count).sum()
+    }
+
+    /// Return the estimated memory usage of all stores, in bytes
+    pub fn memory_usage(&self) -> usize {
+        self.stores.iter().map(|store| store.memory_usage).sum()
+    }
+}
+
+impl fmt::Display for ObjectStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let row = |f: &mut fmt::Formatter,
+                   name: &str,
+                   count: &str,
+                   memory_usage: &str| {
+            writeln!(f, "{name:<12} {count:>12} {memory_usage:>14}")
+        };
+
+        row(f, "object", "count", "memory (bytes)")?;
+        for store in &self.stores {
+            row(
+                f,
+                store.name,
+                &store.count.to_string(),
+                &store.memory_usage.to_string(),
+            )?;
+        }
+        row(
+            f,
+            "total",
+            &self.count().to_string(),
+            &self.memory_usage().to_string(),
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Statistics about the objects in one store
+#[derive(Clone, Copy, Debug)]
+pub struct StoreStats {
+    /// The name of the type of object in the store
+    pub name: &'static str,
+
+    /// The number of objects in the store
+    ///
+    /// See [`Store::len`].
+    ///
+    /// [`Store::len`]: crate::storage::Store::len
+    pub count: usize,
+
+    /// The estimated memory usage of the store, in bytes
+    ///
+    /// See [`Store::memory_usage`].
+    ///
+    /// [`Store::memory_usage`]: crate::storage::Store::memory_usage
+    pub memory_usage: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    #[test]
+    fn stats_and_most_referenced() {
+        let mut services = Services::new();
+
+        let _ = Solid::box_from_dims([1., 1., 1.], &mut services)
+            .insert(&mut services);
+
+        let stats = services.objects.stats();
+        let faces = stats
+            .stores
+            .iter()
+            .find(|store| store.name == "Face")
+            .expect("Stats contain faces");
+        assert!(faces.count >= 6);
+        assert!(faces.memory_usage > 0);
+        assert_eq!(
+            stats.count(),
+            stats.stores.iter().map(|store| store.count).sum::<usize>()
+        );
+
+        let most_referenced = services.objects.most_referenced(5);
+        assert_eq!(most_referenced.len(), 5);
+        for pair in most_referenced.windows(2) {
+            assert!(pair[0].1 >= pair[1].1);
+        }
+    }
+}
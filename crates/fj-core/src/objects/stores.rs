@@ -1,23 +1,32 @@
+use std::collections::BTreeMap;
+
 use fj_math::Vector;
 
 use crate::{
     geometry::{GlobalPath, SurfaceGeometry},
-    storage::{Handle, Store},
+    storage::{Handle, Iter, Store},
 };
 
 use super::{
-    Curve, Cycle, Face, HalfEdge, Region, Shell, Sketch, Solid, Surface, Vertex,
+    Assembly, BehindHandle, Curve, Cycle, Datum, Face, HalfEdge, Object,
+    ObjectStats, Region, Shell, Sketch, Solid, StoreStats, Surface, Vertex,
 };
 
 /// The available object stores
 #[derive(Debug, Default)]
 pub struct Objects {
+    /// Store for [`Assembly`] objects
+    pub assemblies: Store<Assembly>,
+
     /// Store for [`Curve`]s
     pub curves: Store<Curve>,
 
     /// Store for [`Cycle`]s
     pub cycles: Store<Cycle>,
 
+    /// Store for [`Datum`]s
+    pub datums: Store<Datum>,
+
     /// Store for [`Face`]s
     pub faces: Store<Face>,
 
@@ -48,6 +57,104 @@ impl Objects {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Gather statistics about the objects in all stores
+    ///
+    /// This can help with debugging models that use unexpectedly large amounts
+    /// of memory.
+    pub fn stats(&self) -> ObjectStats {
+        fn stats<T>(name: &'static str, store: &Store<T>) -> StoreStats {
+            StoreStats {
+                name,
+                count: store.len(),
+                memory_usage: store.memory_usage(),
+            }
+        }
+
+        let stores = vec![
+            stats("Assembly", &self.assemblies),
+            stats("Curve", &self.curves),
+            stats("Cycle", &self.cycles),
+            stats("Datum", &self.datums),
+            stats("Face", &self.faces),
+            stats("HalfEdge", &self.half_edges),
+            stats("Region", &self.regions),
+            stats("Shell", &self.shells),
+            stats("Sketch", &self.sketches),
+            stats("Solid", &self.solids),
+            StoreStats {
+                name: "Surface",
+                count: self.surfaces.len(),
+                memory_usage: self.surfaces.memory_usage(),
+            },
+            stats("Vertex", &self.vertices),
+        ];
+
+        ObjectStats { stores }
+    }
+
+    /// Find the objects that are referenced by the most other objects
+    ///
+    /// Returns up to `n` objects, each with the number of objects that
+    /// reference it, sorted by that number in descending order. This includes
+    /// references from objects that are no longer used, like intermediate
+    /// results of operations.
+    pub fn most_referenced(
+        &self,
+        n: usize,
+    ) -> Vec<(Object<BehindHandle>, usize)> {
+        let mut references = BTreeMap::new();
+        let mut count = |object: Object<BehindHandle>| {
+            let (_, count) =
+                references.entry(object.id()).or_insert_with(|| (object, 0));
+            *count += 1;
+        };
+
+        for assembly in &self.assemblies {
+            for instance in assembly.instances() {
+                count(instance.solid().clone().into());
+            }
+        }
+        for cycle in &self.cycles {
+            for half_edge in cycle.half_edges() {
+                count(half_edge.clone().into());
+            }
+        }
+        for face in &self.faces {
+            count(face.surface().clone().into());
+            count(face.region().clone().into());
+        }
+        for half_edge in &self.half_edges {
+            count(half_edge.curve().clone().into());
+            count(half_edge.start_vertex().clone().into());
+        }
+        for region in &self.regions {
+            for cycle in region.all_cycles() {
+                count(cycle.clone().into());
+            }
+        }
+        for shell in &self.shells {
+            for face in shell.faces() {
+                count(face.clone().into());
+            }
+        }
+        for sketch in &self.sketches {
+            for region in sketch.regions() {
+                count(region.clone().into());
+            }
+        }
+        for solid in &self.solids {
+            for shell in solid.shells() {
+                count(shell.clone().into());
+            }
+        }
+
+        let mut most_referenced = references.into_values().collect::<Vec<_>>();
+        most_referenced.sort_by(|(_, a), (_, b)| b.cmp(a));
+        most_referenced.truncate(n);
+
+        most_referenced
+    }
 }
 
 /// Store for [`Surface`]s
@@ -71,6 +178,30 @@ impl Surfaces {
         self.store.insert(handle, surface);
     }
 
+    /// Return the number of slots that have been reserved in the store
+    ///
+    /// See [`Store::len`].
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Indicate whether no slots have been reserved in the store
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Estimate the memory used by the store, in bytes
+    ///
+    /// See [`Store::memory_usage`].
+    pub fn memory_usage(&self) -> usize {
+        self.store.memory_usage()
+    }
+
+    /// Iterate over all surfaces in the store
+    pub fn iter(&self) -> Iter<Surface> {
+        self.store.iter()
+    }
+
     /// Access the xy-plane
     pub fn xy_plane(&self) -> Handle<Surface> {
         self.xy_plane.clone()
@@ -0,0 +1,721 @@
+//! # Boolean operations on solids
+//!
+//! See [`BooleanOps`].
+
+use std::collections::BTreeMap;
+
+use fj_interop::{boolean::BooleanOp, mesh::Mesh};
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    algorithms::{approx::Tolerance, triangulate::Triangulate},
+    geometry::{GlobalPath, SurfacePath},
+    objects::{Face, Shell, Solid},
+    operations::{
+        build::{BuildShell, PolyhedronError},
+        insert::Insert,
+        reverse::Reverse,
+        simplify::SimplifyShell,
+    },
+    services::Services,
+    storage::Handle,
+};
+
+/// Combine solids using boolean operations
+///
+/// The operations triangulate both operands, combine the resulting triangle
+/// meshes, and build a new solid from the result. Coplanar faces and collinear
+/// edges are merged afterwards (see [`SimplifyShell`]), so the faces of the
+/// result are not fragmented by the triangulation.
+///
+/// Each connected part of the result becomes its own shell. Cavities, which
+/// result from subtracting an operand that is fully enclosed by the other,
+/// become shells that point inward.
+///
+/// ## Implementation Note
+///
+/// Only operands whose faces are planar and bounded by line segments are
+/// supported. The triangle mesh represents those exactly, while curved faces
+/// would need to be approximated.
+///
+/// The result doesn't retain the colors of the operands' faces.
+pub trait BooleanOps {
+    /// Compute the union of both operands
+    ///
+    /// The result covers all space that is covered by either operand.
+    fn union(
+        &self,
+        other: &Self,
+        services: &mut Services,
+    ) -> Result<Solid, BooleanError>;
+
+    /// Subtract the other operand from this one
+    ///
+    /// The result covers all space that is covered by this operand, but not
+    /// the other one.
+    fn difference(
+        &self,
+        other: &Self,
+        services: &mut Services,
+    ) -> Result<Solid, BooleanError>;
+
+    /// Compute the intersection of both operands
+    ///
+    /// The result covers all space that is covered by both operands.
+    fn intersection(
+        &self,
+        other: &Self,
+        services: &mut Services,
+    ) -> Result<Solid, BooleanError>;
+}
+
+impl BooleanOps for Solid {
+    fn union(
+        &self,
+        other: &Self,
+        services: &mut Services,
+    ) -> Result<Solid, BooleanError> {
+        boolean(
+            solid_mesh(self)?,
+            BooleanOp::Union,
+            solid_mesh(other)?,
+            services,
+        )
+    }
+
+    fn difference(
+        &self,
+        other: &Self,
+        services: &mut Services,
+    ) -> Result<Solid, BooleanError> {
+        boolean(
+            solid_mesh(self)?,
+            BooleanOp::Difference,
+            solid_mesh(other)?,
+            services,
+        )
+    }
+
+    fn intersection(
+        &self,
+        other: &Self,
+        services: &mut Services,
+    ) -> Result<Solid, BooleanError> {
+        boolean(
+            solid_mesh(self)?,
+            BooleanOp::Intersection,
+            solid_mesh(other)?,
+            services,
+        )
+    }
+}
+
+impl BooleanOps for Shell {
+    fn union(
+        &self,
+        other: &Self,
+        services: &mut Services,
+    ) -> Result<Solid, BooleanError> {
+        boolean(
+            shell_mesh(self)?,
+            BooleanOp::Union,
+            shell_mesh(other)?,
+            services,
+        )
+    }
+
+    fn difference(
+        &self,
+        other: &Self,
+        services: &mut Services,
+    ) -> Result<Solid, BooleanError> {
+        boolean(
+            shell_mesh(self)?,
+            BooleanOp::Difference,
+            shell_mesh(other)?,
+            services,
+        )
+    }
+
+    fn intersection(
+        &self,
+        other: &Self,
+        services: &mut Services,
+    ) -> Result<Solid, BooleanError> {
+        boolean(
+            shell_mesh(self)?,
+            BooleanOp::Intersection,
+            shell_mesh(other)?,
+            services,
+        )
+    }
+}
+
+/// Error performing a boolean operation
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum BooleanError {
+    /// An operand has a face that is not supported
+    ///
+    /// See the implementation note on [`BooleanOps`].
+    #[error(
+        "Boolean operations only support faces that are planar and bounded by \
+        line segments"
+    )]
+    UnsupportedFace {
+        /// The unsupported face
+        face: Handle<Face>,
+    },
+
+    /// The result could not be built into a valid solid
+    ///
+    /// This can happen, if the operands only touch along an edge, for example,
+    /// which results in a solid that is not manifold.
+    #[error("Failed to build the result of a boolean operation")]
+    InvalidResult(#[from] PolyhedronError),
+}
+
+/// Relative to the size of the result, the distance below which points are
+/// considered to be identical
+const EPSILON: f64 = 1e-9;
+
+fn solid_mesh(solid: &Solid) -> Result<Mesh<Point<3>>, BooleanError> {
+    for shell in solid.shells() {
+        check_faces(shell)?;
+    }
+
+    Ok((solid, exact_tolerance()).triangulate())
+}
+
+fn shell_mesh(shell: &Shell) -> Result<Mesh<Point<3>>, BooleanError> {
+    check_faces(shell)?;
+    Ok((shell, exact_tolerance()).triangulate())
+}
+
+fn check_faces(shell: &Shell) -> Result<(), BooleanError> {
+    for face in shell.faces() {
+        let is_plane =
+            matches!(face.surface().geometry().u, GlobalPath::Line(_));
+        let is_polygon = face.region().all_cycles().all(|cycle| {
+            cycle.half_edges().iter().all(|half_edge| {
+                matches!(half_edge.path(), SurfacePath::Line(_))
+            })
+        });
+
+        if !is_plane || !is_polygon {
+            return Err(BooleanError::UnsupportedFace { face: face.clone() });
+        }
+    }
+
+    Ok(())
+}
+
+fn exact_tolerance() -> Tolerance {
+    // Faces that are planar and bounded by line segments are approximated
+    // exactly, regardless of the tolerance. Any valid value does the job.
+    Tolerance::from(1.)
+}
+
+fn boolean(
+    a: Mesh<Point<3>>,
+    op: BooleanOp,
+    b: Mesh<Point<3>>,
+    services: &mut Services,
+) -> Result<Solid, BooleanError> {
+    let _span = tracing::info_span!("boolean").entered();
+
+    let mesh = a.boolean(op, &b);
+
+    let points = mesh
+        .triangles()
+        .flat_map(|triangle| triangle.inner.points())
+        .collect::<Vec<_>>();
+    if points.is_empty() {
+        return Ok(Solid::new([]));
+    }
+    let tolerance = Aabb::<3>::from_points(points).size().magnitude() * EPSILON;
+    if tolerance == Scalar::ZERO {
+        // All points are identical, so all triangles are degenerate.
+        return Ok(Solid::new([]));
+    }
+
+    let (positions, triangles) = weld(&mesh, tolerance);
+    let polygons = split_at_t_junctions(&positions, &triangles, tolerance);
+
+    let shells = connected_components(polygons)
+        .into_iter()
+        .map(|polygons| build_shell(&positions, polygons, services))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Solid::new(shells))
+}
+
+/// Merge identical vertices of the mesh, and drop degenerate triangles
+///
+/// Returns the positions of the vertices, and the triangles as indices into
+/// those.
+fn weld(
+    mesh: &Mesh<Point<3>>,
+    tolerance: Scalar,
+) -> (Vec<Point<3>>, Vec<[usize; 3]>) {
+    let mut positions: Vec<Point<3>> = Vec::new();
+    let mut triangles = Vec::new();
+
+    // Identical vertices are in the same or neighboring cells, so only those
+    // need to be checked.
+    let mut grid = PointGrid::new(tolerance);
+    let offset = Vector::from([tolerance; 3]);
+
+    for triangle in mesh.triangles() {
+        let indices = triangle.inner.points().map(|point| {
+            grid.candidates(point - offset, point + offset)
+                .into_iter()
+                .filter(|&index| {
+                    positions[index].distance_to(&point) <= tolerance
+                })
+                .min()
+                .unwrap_or_else(|| {
+                    positions.push(point);
+                    grid.insert(positions.len() - 1, point);
+                    positions.len() - 1
+                })
+        });
+
+        let [a, b, c] = indices.map(|index| positions[index]);
+        let longest_edge =
+            [a.distance_to(&b), b.distance_to(&c), c.distance_to(&a)]
+                .into_iter()
+                .fold(Scalar::ZERO, Scalar::max);
+
+        // Triangles that are collinear have no area. Those come from
+        // splitting polygons, and would only get in the way.
+        let is_degenerate =
+            (b - a).cross(&(c - a)).magnitude() <= longest_edge * tolerance;
+        if is_degenerate {
+            continue;
+        }
+
+        triangles.push(indices);
+    }
+
+    (positions, triangles)
+}
+
+/// Add vertices that lie on the edges of triangles to those triangles
+///
+/// Splitting polygons results in vertices that lie on the edges of
+/// neighboring polygons. A valid shell requires those edges to be split too,
+/// so they can be shared between faces.
+fn split_at_t_junctions(
+    positions: &[Point<3>],
+    triangles: &[[usize; 3]],
+    tolerance: Scalar,
+) -> Vec<Vec<usize>> {
+    let edges = |triangle: &[usize; 3]| {
+        let [a, b, c] = *triangle;
+        [[a, b], [b, c], [c, a]]
+    };
+
+    // Only vertices near an edge can lie on it. With cells about as large as
+    // the edges, only a few cells need to be checked for each edge.
+    let (sum, num) = triangles
+        .iter()
+        .flat_map(edges)
+        .map(|[a, b]| positions[a].distance_to(&positions[b]))
+        .fold((Scalar::ZERO, 0usize), |(sum, num), length| {
+            (sum + length, num + 1)
+        });
+    let cell_size = if num > 0 {
+        (sum / Scalar::from(num as f64)).max(tolerance)
+    } else {
+        tolerance
+    };
+    let mut grid = PointGrid::new(cell_size);
+    for (index, position) in positions.iter().enumerate() {
+        grid.insert(index, *position);
+    }
+
+    // Most edges are shared by two triangles. Only find the vertices on each
+    // of them once.
+    let mut vertices_on_edges = BTreeMap::new();
+
+    triangles
+        .iter()
+        .map(|triangle| {
+            let mut polygon = Vec::new();
+
+            for [a, b] in edges(triangle) {
+                polygon.push(a);
+
+                let key = if a < b { [a, b] } else { [b, a] };
+                let on_edge =
+                    vertices_on_edges.entry(key).or_insert_with(|| {
+                        vertices_on_edge(key, positions, &grid, tolerance)
+                    });
+
+                // The vertices are sorted from the first to the second vertex
+                // of the key.
+                if a < b {
+                    polygon.extend(on_edge.iter().copied());
+                } else {
+                    polygon.extend(on_edge.iter().rev().copied());
+                }
+            }
+
+            polygon
+        })
+        .collect()
+}
+
+/// Find the vertices that lie on the edge between two vertices
+///
+/// Returns the vertices sorted from `a` to `b`, not including those.
+fn vertices_on_edge(
+    [a, b]: [usize; 2],
+    positions: &[Point<3>],
+    grid: &PointGrid,
+    tolerance: Scalar,
+) -> Vec<usize> {
+    let start = positions[a];
+    let edge = positions[b] - start;
+
+    let aabb = Aabb::<3>::from_points([positions[a], positions[b]]);
+    let offset = Vector::from([tolerance; 3]);
+
+    let mut on_edge = grid
+        .candidates(aabb.min - offset, aabb.max + offset)
+        .into_iter()
+        .filter(|&index| index != a && index != b)
+        .filter_map(|index| {
+            let position = positions[index];
+
+            let t = (position - start).dot(&edge) / edge.dot(&edge);
+            let closest = start + edge * t;
+
+            let is_on_edge = t > Scalar::ZERO
+                && t < Scalar::ONE
+                && closest.distance_to(&position) <= tolerance;
+            is_on_edge.then_some((t, index))
+        })
+        .collect::<Vec<_>>();
+    on_edge.sort();
+
+    on_edge.into_iter().map(|(_, index)| index).collect()
+}
+
+/// Vertices, sorted into the cells of a uniform grid
+///
+/// Allows finding the vertices near a location, without checking all of them.
+struct PointGrid {
+    cell_size: Scalar,
+    cells: BTreeMap<[i64; 3], Vec<usize>>,
+}
+
+impl PointGrid {
+    fn new(cell_size: Scalar) -> Self {
+        Self {
+            cell_size,
+            cells: BTreeMap::new(),
+        }
+    }
+
+    fn insert(&mut self, index: usize, position: Point<3>) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push(index);
+    }
+
+    /// Find the vertices in all cells that overlap the box between `min` and
+    /// `max`
+    ///
+    /// This includes vertices outside of the box, which the caller needs to
+    /// filter out.
+    fn candidates(&self, min: Point<3>, max: Point<3>) -> Vec<usize> {
+        let [min, max] = [min, max].map(|point| self.cell(point));
+        let is_in_range = |cell: &[i64; 3]| {
+            (0..3).all(|i| min[i] <= cell[i] && cell[i] <= max[i])
+        };
+
+        // A large box can overlap more cells than there are vertices. Checking
+        // all occupied cells is faster then.
+        let num_cells = (0..3)
+            .map(|i| (max[i] - min[i] + 1) as u64)
+            .fold(1u64, u64::saturating_mul);
+        if num_cells > self.cells.len() as u64 {
+            return self
+                .cells
+                .iter()
+                .filter(|(cell, _)| is_in_range(cell))
+                .flat_map(|(_, indices)| indices.iter().copied())
+                .collect();
+        }
+
+        let mut candidates = Vec::new();
+        for x in min[0]..=max[0] {
+            for y in min[1]..=max[1] {
+                for z in min[2]..=max[2] {
+                    if let Some(indices) = self.cells.get(&[x, y, z]) {
+                        candidates.extend(indices.iter().copied());
+                    }
+                }
+            }
+        }
+
+        candidates
+    }
+
+    fn cell(&self, position: Point<3>) -> [i64; 3] {
+        position
+            .coords
+            .components
+            .map(|coord| (coord / self.cell_size).floor().into_f64() as i64)
+    }
+}
+
+/// Group polygons that are connected via shared edges
+fn connected_components(polygons: Vec<Vec<usize>>) -> Vec<Vec<Vec<usize>>> {
+    let mut parents = (0..polygons.len()).collect::<Vec<_>>();
+
+    let mut edges = BTreeMap::new();
+    for (index, polygon) in polygons.iter().enumerate() {
+        for (&a, &b) in polygon.iter().zip(polygon.iter().cycle().skip(1)) {
+            let edge = if a < b { [a, b] } else { [b, a] };
+
+            if let Some(&other) = edges.get(&edge) {
+                let [a, b] = [index, other].map(|i| root(&mut parents, i));
+                parents[a] = b;
+            } else {
+                edges.insert(edge, index);
+            }
+        }
+    }
+
+    let mut components = BTreeMap::<_, Vec<_>>::new();
+    for (index, polygon) in polygons.into_iter().enumerate() {
+        let root = root(&mut parents, index);
+        components.entry(root).or_default().push(polygon);
+    }
+
+    components.into_values().collect()
+}
+
+/// Find the root of a polygon's component, as part of a union-find
+fn root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+fn build_shell(
+    positions: &[Point<3>],
+    polygons: Vec<Vec<usize>>,
+    services: &mut Services,
+) -> Result<Handle<Shell>, BooleanError> {
+    // Only pass the vertices that this shell actually uses on to the
+    // polyhedron builder.
+    let mut indices = BTreeMap::new();
+    let mut vertices = Vec::new();
+    let mut faces = polygons
+        .into_iter()
+        .map(|polygon| {
+            polygon
+                .into_iter()
+                .map(|index| {
+                    *indices.entry(index).or_insert_with(|| {
+                        vertices.push(positions[index]);
+                        vertices.len() - 1
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // A cavity points inward, which the polyhedron builder won't accept. Build
+    // it inside out, then reverse the result.
+    let is_cavity = signed_volume(&vertices, &faces) < Scalar::ZERO;
+    if is_cavity {
+        for face in &mut faces {
+            face.reverse();
+        }
+    }
+
+    let shell = Shell::from_vertices_and_faces(vertices, faces, services)?;
+    let shell = if is_cavity {
+        shell.reverse(services)
+    } else {
+        shell
+    };
+
+    Ok(shell.simplify(services).insert(services))
+}
+
+/// Compute six times the signed volume enclosed by the faces
+fn signed_volume(vertices: &[Point<3>], faces: &[Vec<usize>]) -> Scalar {
+    let mut volume = Scalar::ZERO;
+
+    for face in faces {
+        let [first, rest @ ..] = face.as_slice() else {
+            continue;
+        };
+        let a = vertices[*first].coords;
+
+        for window in rest.windows(2) {
+            let [b, c] = [window[0], window[1]].map(|i| vertices[i].coords);
+            volume += a.dot(&b.cross(&c));
+        }
+    }
+
+    volume
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, transform::TransformObject,
+            triangulate::Triangulate,
+        },
+        objects::{Cycle, Region, Sketch, Solid},
+        operations::{
+            build::{BuildCycle, BuildSketch},
+            insert::Insert,
+            primitives::BuildPrimitive,
+            sweep::SweepSketch,
+            update::UpdateSketch,
+        },
+        services::Services,
+    };
+
+    use super::{BooleanError, BooleanOps};
+
+    #[test]
+    fn boolean_ops_of_overlapping_boxes() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let a = Solid::box_from_dims([2., 2., 2.], &mut services);
+        let b = Solid::box_from_dims([2., 2., 2.], &mut services)
+            .translate([1., 1., 1.], &mut services);
+
+        let union = a.union(&b, &mut services)?.insert(&mut services);
+        let difference = a.difference(&b, &mut services)?.insert(&mut services);
+        let intersection =
+            a.intersection(&b, &mut services)?.insert(&mut services);
+
+        for (solid, expected_volume) in
+            [(union, 15.), (difference, 7.), (intersection, 1.)]
+        {
+            assert_eq!(solid.shells().len(), 1);
+            assert_volume(&solid, expected_volume);
+        }
+
+        services.drop_and_validate()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn subtracting_enclosed_box_results_in_cavity() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let outer = Solid::box_from_dims([4., 4., 4.], &mut services);
+        let inner = Solid::box_from_dims([2., 2., 2.], &mut services)
+            .translate([0., 0., 1.], &mut services);
+
+        let solid = outer
+            .difference(&inner, &mut services)?
+            .insert(&mut services);
+
+        assert_eq!(solid.shells().len(), 2);
+        assert_volume(&solid, 56.);
+
+        services.drop_and_validate()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn boolean_ops_of_overlapping_shells() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let a = Solid::box_from_dims([2., 2., 2.], &mut services);
+        let b = Solid::box_from_dims([2., 2., 2.], &mut services)
+            .translate([1., 1., 1.], &mut services);
+        let [a, b] = [&a, &b].map(|solid| solid.shells().only().clone());
+
+        let union = a.union(&b, &mut services)?.insert(&mut services);
+        let difference = a.difference(&b, &mut services)?.insert(&mut services);
+        let intersection =
+            a.intersection(&b, &mut services)?.insert(&mut services);
+
+        for (solid, expected_volume) in
+            [(union, 15.), (difference, 7.), (intersection, 1.)]
+        {
+            assert_eq!(solid.shells().len(), 1);
+            assert_volume(&solid, expected_volume);
+        }
+
+        services.drop_and_validate()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn boxes_touching_along_edge_result_in_invalid_result() {
+        let mut services = Services::new();
+
+        let a = Solid::box_from_dims([2., 2., 2.], &mut services);
+        let b = Solid::box_from_dims([2., 2., 2.], &mut services)
+            .translate([2., 2., 0.], &mut services);
+
+        // The union would have an edge that is shared by four faces, which
+        // is not manifold.
+        assert!(matches!(
+            a.union(&b, &mut services),
+            Err(BooleanError::InvalidResult(_))
+        ));
+    }
+
+    #[test]
+    fn curved_faces_are_not_supported() {
+        let mut services = Services::new();
+
+        let a = Solid::box_from_dims([2., 2., 2.], &mut services);
+        let b = {
+            let region = Region::new(
+                Cycle::circle([0., 0.], 1., &mut services)
+                    .insert(&mut services),
+                [],
+                None,
+            )
+            .insert(&mut services);
+            let surface = services.objects.surfaces.xy_plane();
+
+            Sketch::empty().add_region(region).sweep_sketch(
+                surface,
+                [0., 0., 1.],
+                &mut services,
+            )
+        };
+
+        assert!(matches!(
+            a.union(&b, &mut services),
+            Err(BooleanError::UnsupportedFace { .. })
+        ));
+    }
+
+    fn assert_volume(solid: &Solid, expected: f64) {
+        let mesh = (solid, Tolerance::from(1.)).triangulate();
+        let volume = mesh
+            .triangles()
+            .map(|triangle| {
+                let [a, b, c] =
+                    triangle.inner.points().map(|point: Point<3>| point.coords);
+                a.dot(&b.cross(&c)) / 6.
+            })
+            .fold(Scalar::ZERO, |sum, volume| sum + volume);
+
+        assert!((volume - Scalar::from(expected)).abs() < Scalar::from(1e-9));
+    }
+}
@@ -0,0 +1,70 @@
+use std::f64::consts::TAU;
+
+use fj_math::Vector;
+
+use crate::{
+    objects::{Assembly, Instance, Solid},
+    storage::Handle,
+};
+
+/// Build an [`Assembly`]
+///
+/// See [module-level documentation] for context.
+///
+/// [module-level documentation]: super
+pub trait BuildAssembly {
+    /// Build an assembly that places a single solid
+    fn from_solid(name: impl Into<String>, solid: Handle<Solid>) -> Assembly {
+        Assembly::new([Instance::new(name, solid)])
+    }
+
+    /// Build a linear pattern of a solid
+    ///
+    /// Creates `count` instances of the solid, the first one at its original
+    /// position, each following one offset from the previous one by `offset`.
+    /// The instances are named `{name}-{i}`, where `i` is the index of the
+    /// instance.
+    ///
+    /// The solid is not copied. All instances refer to the same object, which
+    /// keeps the memory required for large patterns low.
+    fn linear_pattern(
+        name: &str,
+        solid: Handle<Solid>,
+        offset: impl Into<Vector<3>>,
+        count: usize,
+    ) -> Assembly {
+        let offset = offset.into();
+
+        Assembly::new((0..count).map(|i| {
+            Instance::new(format!("{name}-{i}"), solid.clone())
+                .with_translation(offset * i as f64)
+        }))
+    }
+
+    /// Build a circular pattern of a solid
+    ///
+    /// Creates `count` instances of the solid, evenly distributed around the
+    /// provided axis, which passes through the origin. The first instance is
+    /// at the solid's original position. Instances are named as they are by
+    /// [`BuildAssembly::linear_pattern`].
+    ///
+    /// The solid is not copied. All instances refer to the same object, which
+    /// keeps the memory required for large patterns low.
+    fn circular_pattern(
+        name: &str,
+        solid: Handle<Solid>,
+        axis: impl Into<Vector<3>>,
+        count: usize,
+    ) -> Assembly {
+        let axis = axis.into().normalize();
+
+        Assembly::new((0..count).map(|i| {
+            let angle = TAU * i as f64 / count as f64;
+
+            Instance::new(format!("{name}-{i}"), solid.clone())
+                .with_rotation(axis * angle)
+        }))
+    }
+}
+
+impl BuildAssembly for Assembly {}
@@ -1,9 +1,13 @@
 use std::{array, borrow::Borrow};
 
 use fj_interop::ext::ArrayExt;
-use fj_math::Point;
+use fj_math::{Point, Scalar};
 
 use crate::{
+    algorithms::approx::Tolerance,
+    geometry::{
+        CurveBoundary, GlobalPath, RuledSurface, SurfaceGeom, SurfaceGeometry,
+    },
     objects::{Cycle, Face, HalfEdge, Region, Surface, Vertex},
     operations::{
         build::{BuildCycle, BuildRegion, BuildSurface},
@@ -73,10 +77,117 @@ pub trait BuildFace {
         let region = Region::polygon(points, services).insert(services);
         Face::new(surface, region)
     }
+
+    /// Build faces on the ruled surface between two paths
+    ///
+    /// The faces cover the ruled surface within the provided boundary. They are
+    /// bounded by the paths themselves, and by the straight lines that connect
+    /// their endpoints.
+    ///
+    /// If `b` is a translated copy of `a`, the ruled surface can be represented
+    /// exactly (see [`SurfaceGeometry::ruled`]), and a single face is built.
+    /// Faces that cover a whole period of a periodic path, like the side of a
+    /// cylinder, can't be built this way, as they would need to share a seam
+    /// with themselves. Sweep a circle to build those.
+    ///
+    /// Otherwise, the [`RuledSurface`] between the paths is approximated by
+    /// triangles, between the rulings returned by [`RuledSurface::approximate`].
+    /// Triangles that degenerate, where the paths meet, are left out.
+    fn ruled(
+        a: GlobalPath,
+        b: GlobalPath,
+        boundary: impl Into<CurveBoundary<Point<1>>>,
+        tolerance: impl Into<Tolerance>,
+        services: &mut Services,
+    ) -> Result<Vec<Face>, RuledFaceError> {
+        let boundary = boundary.into();
+
+        let [start, end] = boundary.inner.map(|point| point.t);
+        if start == end {
+            return Err(RuledFaceError::EmptyBoundary);
+        }
+        if a == b {
+            return Err(RuledFaceError::IdenticalPaths);
+        }
+
+        if let Some(surface) = SurfaceGeometry::ruled(a, b) {
+            if let Some(period) = a.period() {
+                if (end - start).abs() >= period {
+                    return Err(RuledFaceError::FullPeriod);
+                }
+            }
+
+            let surface = Surface::new(surface).insert(services);
+            let face = Face::polygon(
+                surface,
+                [
+                    [start, Scalar::ZERO],
+                    [end, Scalar::ZERO],
+                    [end, Scalar::ONE],
+                    [start, Scalar::ONE],
+                ],
+                services,
+            );
+
+            return Ok(vec![face]);
+        }
+
+        let surface = RuledSurface::new(a, b);
+        let rulings = surface.approximate(boundary, tolerance.into());
+
+        let mut faces = Vec::new();
+        for rulings in rulings.windows(2) {
+            let [u0, u1] = [rulings[0].t, rulings[1].t];
+
+            // Same orientation as the face built on the exact surface above.
+            for triangle in [
+                [[u0, Scalar::ZERO], [u1, Scalar::ZERO], [u1, Scalar::ONE]],
+                [[u0, Scalar::ZERO], [u1, Scalar::ONE], [u0, Scalar::ONE]],
+            ] {
+                let [p0, p1, p2] = triangle.map(|point| {
+                    surface.point_from_surface_coords(Point::from(point))
+                });
+
+                if (p1 - p0).cross(&(p2 - p0)).magnitude() == Scalar::ZERO {
+                    continue;
+                }
+
+                faces.push(Face::triangle([p0, p1, p2], services).face);
+            }
+        }
+
+        if faces.is_empty() {
+            return Err(RuledFaceError::Degenerate);
+        }
+
+        Ok(faces)
+    }
 }
 
 impl BuildFace for Face {}
 
+/// Error building a face on a ruled surface
+///
+/// Returned by [`BuildFace::ruled`].
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum RuledFaceError {
+    /// Both paths are the same, so there's no surface between them
+    #[error("Paths of ruled face are identical")]
+    IdenticalPaths,
+
+    /// The paths coincide within the boundary, so the surface has no area
+    #[error("Ruled surface between paths is degenerate")]
+    Degenerate,
+
+    /// The boundary is empty
+    #[error("Boundary of ruled face is empty")]
+    EmptyBoundary,
+
+    /// The boundary covers a whole period of the paths
+    #[error("Boundary of ruled face covers a whole period")]
+    FullPeriod,
+}
+
 /// A polygon
 ///
 /// # Implementation Note
@@ -131,3 +242,134 @@ impl<const D: usize, I: IsInserted> Polygon<D, I> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_math::{Circle, Line, Point, Scalar, Transform, Vector};
+
+    use crate::{
+        algorithms::approx::Tolerance,
+        geometry::{GlobalPath, SurfaceGeometry},
+        objects::Face,
+        services::Services,
+    };
+
+    use super::{BuildFace, RuledFaceError};
+
+    #[test]
+    fn ruled_face_between_translated_copies() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let a = GlobalPath::circle_from_radius(1.);
+        let b = a.transform(&Transform::translation([0., 0., 1.]));
+
+        let faces = Face::ruled(a, b, [[0.], [PI]], 0.1, &mut services)?;
+
+        let [face] = faces.as_slice() else {
+            panic!("Expected exactly one face");
+        };
+        assert_eq!(
+            Some(face.surface().geometry()),
+            SurfaceGeometry::ruled(a, b)
+        );
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn ruled_faces_between_concentric_circles() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // The side of a truncated cone, with the radius growing from 1 to 2.
+        let a = GlobalPath::circle_from_radius(1.);
+        let b = GlobalPath::Circle(Circle::from_center_and_radius(
+            [0., 0., 1.],
+            2.,
+        ));
+
+        let faces = Face::ruled(a, b, [[0.], [PI]], 0.1, &mut services)?;
+        assert!(faces.len() > 2);
+
+        for face in &faces {
+            let surface = face.surface().geometry();
+
+            for half_edge in face.region().exterior().half_edges() {
+                let point = surface
+                    .point_from_surface_coords(half_edge.start_position());
+
+                // All corners of the triangles are on the cone.
+                let radius = (point.x * point.x + point.y * point.y).sqrt();
+                assert!((radius - (point.z + 1.)).abs() < Scalar::from(1e-12));
+                assert!(point.y >= Scalar::from(-1e-12));
+            }
+        }
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn ruled_faces_between_skew_lines() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let a = GlobalPath::x_axis();
+        let b = GlobalPath::Line(Line::from_origin_and_direction(
+            Point::from([0., 0., 1.]),
+            Vector::from([0., 1., 0.]),
+        ));
+
+        // Lines need no approximation, so there's just one strip of two
+        // triangles.
+        let faces = Face::ruled(a, b, [[-1.], [1.]], 0.1, &mut services)?;
+        assert_eq!(faces.len(), 2);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn ruled_face_errors() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let a = GlobalPath::x_axis();
+        let b = GlobalPath::Line(Line::from_origin_and_direction(
+            Point::from([1., 0., 0.]),
+            Vector::unit_x(),
+        ));
+        let c = GlobalPath::y_axis();
+        let tolerance = Tolerance::from_scalar(0.1)?;
+
+        assert_eq!(
+            Face::ruled(a, a, [[0.], [1.]], tolerance, &mut services),
+            Err(RuledFaceError::IdenticalPaths),
+        );
+        assert_eq!(
+            Face::ruled(a, c, [[1.], [1.]], tolerance, &mut services),
+            Err(RuledFaceError::EmptyBoundary),
+        );
+        assert_eq!(
+            Face::ruled(a, b, [[0.], [1.]], tolerance, &mut services),
+            Err(RuledFaceError::Degenerate),
+        );
+
+        let circle = GlobalPath::circle_from_radius(1.);
+        let translated =
+            circle.transform(&Transform::translation([0., 0., 1.]));
+        assert_eq!(
+            Face::ruled(
+                circle,
+                translated,
+                [[0.], [2. * PI]],
+                tolerance,
+                &mut services
+            ),
+            Err(RuledFaceError::FullPeriod),
+        );
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+}
@@ -79,6 +79,45 @@ pub trait BuildHalfEdge {
         HalfEdge::unjoined(path, boundary, services)
     }
 
+    /// Create a segment of an Archimedean spiral
+    ///
+    /// The spiral has the radius `start_radius` at the angle `0`, and its
+    /// radius grows by `pitch` with each full turn. The half-edge covers the
+    /// provided range of angles, which may span more than a full turn.
+    fn spiral(
+        center: impl Into<Point<2>>,
+        start_radius: impl Into<Scalar>,
+        pitch: impl Into<Scalar>,
+        angles: [impl Into<Scalar>; 2],
+        services: &mut Services,
+    ) -> HalfEdge {
+        let path = SurfacePath::spiral_from_center_and_pitch(
+            center,
+            start_radius,
+            pitch,
+        );
+        let boundary = angles.map(|angle| Point::from([angle.into()]));
+
+        HalfEdge::unjoined(path, boundary, services)
+    }
+
+    /// Create a segment of the involute of a circle
+    ///
+    /// The involute starts on the base circle, which is defined by `center`
+    /// and `radius`, at the angle `0`. The half-edge covers the provided range
+    /// of angles at which the involute leaves the base circle.
+    fn involute(
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        angles: [impl Into<Scalar>; 2],
+        services: &mut Services,
+    ) -> HalfEdge {
+        let path = SurfacePath::involute_from_center_and_radius(center, radius);
+        let boundary = angles.map(|angle| Point::from([angle.into()]));
+
+        HalfEdge::unjoined(path, boundary, services)
+    }
+
     /// Create a line segment
     fn line_segment(
         points_surface: [impl Into<Point<2>>; 2],
@@ -17,6 +17,7 @@
 //! the top-level object itself, but also to the other objects that make up its
 //! components.
 
+mod assembly;
 mod cycle;
 mod face;
 mod half_edge;
@@ -27,12 +28,13 @@ mod solid;
 mod surface;
 
 pub use self::{
+    assembly::BuildAssembly,
     cycle::BuildCycle,
-    face::{BuildFace, Polygon},
+    face::{BuildFace, Polygon, RuledFaceError},
     half_edge::BuildHalfEdge,
-    region::BuildRegion,
-    shell::{BuildShell, TetrahedronShell},
-    sketch::BuildSketch,
+    region::{BuildRegion, OutlineError},
+    shell::{BuildShell, PolyhedronError, TetrahedronShell},
+    sketch::{BuildSketch, SketchBuilder},
     solid::{BuildSolid, Tetrahedron},
     surface::BuildSurface,
 };
@@ -41,6 +41,414 @@ pub trait BuildRegion {
         let exterior = Cycle::polygon(points, services).insert(services);
         Region::new(exterior, [], None)
     }
+
+    /// Build a polygon from an outline, checking the input
+    ///
+    /// This is a more robust version of [`BuildRegion::polygon`], meant for
+    /// outlines that are generated procedurally. The outline is closed
+    /// automatically, meaning the last point may or may not repeat the first.
+    /// Consecutive duplicate points are ignored.
+    ///
+    /// The outline may be wound either way. Its winding is normalized to
+    /// counter-clockwise, which is what a region's exterior requires.
+    ///
+    /// Returns an error, if the outline has fewer than 3 distinct points,
+    /// intersects itself, or encloses no area. See [`OutlineError`].
+    fn from_outline(
+        points: impl IntoIterator<Item = impl Into<Point<2>>>,
+        services: &mut Services,
+    ) -> Result<Region, OutlineError> {
+        let points = normalize_outline(points.into_iter().map(Into::into))?;
+        Ok(Self::polygon(points, services))
+    }
+
+    /// Build a polygon that encloses an unordered set of points
+    ///
+    /// Computes a concave hull of the points, by starting with their convex
+    /// hull, then digging into it wherever an edge is long compared to its
+    /// distance from the nearest enclosed point. Every point ends up within
+    /// or on the boundary of the resulting region.
+    ///
+    /// `concavity` controls how deep the hull digs. An edge is replaced by two
+    /// edges through an enclosed point, if the edge's length divided by the
+    /// distance between that point and the nearer end of the edge exceeds
+    /// `concavity`. Larger values result in a more convex hull. Values between
+    /// 1 and 3 are typical.
+    ///
+    /// Returns an error, if there are fewer than 3 distinct points, or if all
+    /// points lie on a single line.
+    fn from_point_cloud(
+        points: impl IntoIterator<Item = impl Into<Point<2>>>,
+        concavity: impl Into<Scalar>,
+        services: &mut Services,
+    ) -> Result<Region, OutlineError> {
+        let mut points = points
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<Point<2>>>();
+        points.sort();
+        points.dedup();
+
+        if points.len() < 3 {
+            return Err(OutlineError::TooFewPoints {
+                num_points: points.len(),
+            });
+        }
+
+        let hull = concave_hull(&points, concavity.into())?;
+        Ok(Self::polygon(hull, services))
+    }
 }
 
 impl BuildRegion for Region {}
+
+/// Error building a region from an outline or a set of points
+///
+/// Returned by [`BuildRegion::from_outline`] and
+/// [`BuildRegion::from_point_cloud`]. Segments are referred to by the index of
+/// their start point, after duplicate points have been removed.
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum OutlineError {
+    /// There are fewer than 3 distinct points
+    #[error(
+        "Outline has {num_points} distinct points; at least 3 are required"
+    )]
+    TooFewPoints {
+        /// The number of distinct points
+        num_points: usize,
+    },
+
+    /// Two segments of the outline intersect or overlap
+    #[error(
+        "Segments {} and {} of the outline intersect",
+        .segments[0],
+        .segments[1]
+    )]
+    SelfIntersection {
+        /// The intersecting segments
+        segments: [usize; 2],
+    },
+
+    /// The outline encloses no area
+    #[error("Outline encloses no area; all points lie on a single line")]
+    ZeroArea,
+}
+
+/// Close an outline, remove duplicate points, and make it counter-clockwise
+fn normalize_outline(
+    points: impl IntoIterator<Item = Point<2>>,
+) -> Result<Vec<Point<2>>, OutlineError> {
+    let mut points = points.into_iter().collect::<Vec<_>>();
+    points.dedup();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    if points.len() < 3 {
+        return Err(OutlineError::TooFewPoints {
+            num_points: points.len(),
+        });
+    }
+
+    let num_segments = points.len();
+    let segment = |i: usize| [points[i], points[(i + 1) % num_segments]];
+
+    for i in 0..num_segments {
+        for j in i + 1..num_segments {
+            let is_adjacent = j == i + 1 || (i == 0 && j == num_segments - 1);
+
+            let intersect = if is_adjacent {
+                // Adjacent segments always share a point. They only intersect,
+                // if they double back on each other.
+                let [a, b] = if j == i + 1 { [i, j] } else { [j, i] };
+                let [start, shared] = segment(a);
+                let [_, end] = segment(b);
+
+                let [to_start, to_end] = [start - shared, end - shared];
+                to_start.cross2d(&to_end) == Scalar::ZERO
+                    && to_start.dot(&to_end) > Scalar::ZERO
+            } else {
+                segments_intersect(segment(i), segment(j))
+            };
+
+            if intersect {
+                return Err(OutlineError::SelfIntersection {
+                    segments: [i, j],
+                });
+            }
+        }
+    }
+
+    let area = signed_area(&points);
+    if area == Scalar::ZERO {
+        return Err(OutlineError::ZeroArea);
+    }
+    if area < Scalar::ZERO {
+        // Keep the first point where it is, so the outline still starts there.
+        points[1..].reverse();
+    }
+
+    Ok(points)
+}
+
+/// Compute a concave hull of sorted, distinct points
+///
+/// See [`BuildRegion::from_point_cloud`].
+fn concave_hull(
+    points: &[Point<2>],
+    concavity: Scalar,
+) -> Result<Vec<Point<2>>, OutlineError> {
+    let mut hull = convex_hull(points);
+    let hull_points = hull.iter().map(|&i| points[i]).collect::<Vec<_>>();
+    if signed_area(&hull_points) == Scalar::ZERO {
+        return Err(OutlineError::ZeroArea);
+    }
+
+    let mut is_on_hull = vec![false; points.len()];
+    for &i in &hull {
+        is_on_hull[i] = true;
+    }
+
+    let mut i = 0;
+    while i < hull.len() {
+        let hull_edge =
+            |j: usize| [hull[j], hull[(j + 1) % hull.len()]].map(|i| points[i]);
+        let edge = hull_edge(i);
+        let [a, b] = edge;
+
+        // The candidate is the enclosed point that is closest to the edge,
+        // among those that are not closer to any other edge.
+        let candidate = (0..points.len())
+            .filter(|&p| !is_on_hull[p])
+            .map(|p| (p, distance_to_segment(points[p], edge)))
+            .filter(|&(p, distance)| {
+                (0..hull.len()).all(|j| {
+                    distance_to_segment(points[p], hull_edge(j)) >= distance
+                })
+            })
+            .min_by_key(|&(_, distance)| distance)
+            .map(|(p, _)| p);
+
+        let dig = candidate.filter(|&p| {
+            let point = points[p];
+            let nearest_end = point.distance_to(&a).min(point.distance_to(&b));
+            if a.distance_to(&b) <= nearest_end * concavity {
+                return false;
+            }
+
+            // Digging must not leave any enclosed point outside of the hull,
+            // and the new edges must not intersect the rest of the hull.
+            let leaves_point_outside = (0..points.len())
+                .filter(|&q| q != p && !is_on_hull[q])
+                .any(|q| is_in_triangle(points[q], [a, point, b]));
+            let intersects_hull =
+                (0..hull.len()).filter(|&j| j != i).any(|j| {
+                    [[a, point], [point, b]].into_iter().any(|new| {
+                        segments_intersect_properly(new, hull_edge(j))
+                    })
+                });
+
+            !leaves_point_outside && !intersects_hull
+        });
+
+        match dig {
+            Some(p) => {
+                // Don't advance, so the new edge from `a` is checked next.
+                hull.insert(i + 1, p);
+                is_on_hull[p] = true;
+            }
+            None => {
+                i += 1;
+            }
+        }
+    }
+
+    Ok(hull.into_iter().map(|i| points[i]).collect())
+}
+
+/// Compute the counter-clockwise convex hull of sorted, distinct points
+///
+/// Returns the indices of the points on the hull, including those that lie on
+/// a straight edge of it. Uses Andrew's monotone chain algorithm.
+fn convex_hull(points: &[Point<2>]) -> Vec<usize> {
+    let turns_right = |hull: &[usize], p: usize| {
+        let [a, b] = [hull[hull.len() - 2], hull[hull.len() - 1]];
+        orientation(points[a], points[b], points[p]) < Scalar::ZERO
+    };
+
+    let mut lower = Vec::new();
+    for p in 0..points.len() {
+        while lower.len() >= 2 && turns_right(&lower, p) {
+            lower.pop();
+        }
+        lower.push(p);
+    }
+
+    let mut upper = Vec::new();
+    for p in (0..points.len()).rev() {
+        while upper.len() >= 2 && turns_right(&upper, p) {
+            upper.pop();
+        }
+        upper.push(p);
+    }
+
+    // The last point of each chain is the first point of the other one.
+    lower.pop();
+    upper.pop();
+    lower.extend(upper);
+
+    lower
+}
+
+/// Compute twice the signed area of a polygon
+///
+/// The result is positive, if the polygon is wound counter-clockwise.
+fn signed_area(points: &[Point<2>]) -> Scalar {
+    (0..points.len())
+        .map(|i| {
+            let [a, b] = [points[i], points[(i + 1) % points.len()]];
+            a.coords.cross2d(&b.coords)
+        })
+        .fold(Scalar::ZERO, |area, a| area + a)
+}
+
+fn distance_to_segment(point: Point<2>, [a, b]: [Point<2>; 2]) -> Scalar {
+    let ab = b - a;
+    let t = ((point - a).dot(&ab) / ab.dot(&ab))
+        .max(Scalar::ZERO)
+        .min(Scalar::ONE);
+    point.distance_to(&(a + ab * t))
+}
+
+/// Indicate whether a point is within a triangle, or on its boundary
+fn is_in_triangle(point: Point<2>, triangle: [Point<2>; 3]) -> bool {
+    let sides = [0, 1, 2]
+        .map(|i| orientation(triangle[i], triangle[(i + 1) % 3], point));
+
+    sides.iter().all(|&side| side >= Scalar::ZERO)
+        || sides.iter().all(|&side| side <= Scalar::ZERO)
+}
+
+/// Indicate whether two segments have any point in common
+fn segments_intersect(a: [Point<2>; 2], b: [Point<2>; 2]) -> bool {
+    let [d1, d2] = b.map(|point| orientation(a[0], a[1], point));
+    let [d3, d4] = a.map(|point| orientation(b[0], b[1], point));
+
+    if segments_intersect_properly(a, b) {
+        return true;
+    }
+
+    (d1 == Scalar::ZERO && is_within_bounds(b[0], a))
+        || (d2 == Scalar::ZERO && is_within_bounds(b[1], a))
+        || (d3 == Scalar::ZERO && is_within_bounds(a[0], b))
+        || (d4 == Scalar::ZERO && is_within_bounds(a[1], b))
+}
+
+/// Indicate whether two segments cross, at a point that is not an end point
+fn segments_intersect_properly(a: [Point<2>; 2], b: [Point<2>; 2]) -> bool {
+    let [d1, d2] = b.map(|point| orientation(a[0], a[1], point));
+    let [d3, d4] = a.map(|point| orientation(b[0], b[1], point));
+
+    d1 * d2 < Scalar::ZERO && d3 * d4 < Scalar::ZERO
+}
+
+fn orientation(a: Point<2>, b: Point<2>, point: Point<2>) -> Scalar {
+    (b - a).cross2d(&(point - a))
+}
+
+/// Indicate whether a point on the line of a segment is within the segment
+fn is_within_bounds(point: Point<2>, [a, b]: [Point<2>; 2]) -> bool {
+    (0..2).all(|i| {
+        let [a, b, p] = [a, b, point].map(|point| point.coords.components[i]);
+        a.min(b) <= p && p <= a.max(b)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{objects::Region, services::Services};
+
+    use super::{BuildRegion, OutlineError};
+
+    #[test]
+    fn from_outline() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // Clockwise, explicitly closed, and with a duplicate point.
+        let region = Region::from_outline(
+            [[0., 0.], [0., 1.], [1., 1.], [1., 1.], [1., 0.], [0., 0.]],
+            &mut services,
+        )?;
+        let points = region
+            .exterior()
+            .half_edges()
+            .iter()
+            .map(|half_edge| half_edge.start_position())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            [[0., 0.], [1., 0.], [1., 1.], [0., 1.]].map(Point::from)
+        );
+
+        let bow_tie = Region::from_outline(
+            [[0., 0.], [1., 1.], [1., 0.], [0., 1.]],
+            &mut services,
+        );
+        assert_eq!(
+            bow_tie.unwrap_err(),
+            OutlineError::SelfIntersection { segments: [0, 2] }
+        );
+
+        // Closing this outline doubles back over its first segment.
+        let line =
+            Region::from_outline([[0., 0.], [1., 0.], [2., 0.]], &mut services);
+        assert_eq!(
+            line.unwrap_err(),
+            OutlineError::SelfIntersection { segments: [0, 2] }
+        );
+
+        let too_few =
+            Region::from_outline([[0., 0.], [1., 0.], [0., 0.]], &mut services);
+        assert_eq!(
+            too_few.unwrap_err(),
+            OutlineError::TooFewPoints { num_points: 2 }
+        );
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn from_point_cloud() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // A square, with a point close to its top edge.
+        let points = [[0., 0.], [4., 0.], [4., 4.], [2., 3.], [0., 4.]];
+
+        let convex = Region::from_point_cloud(points, 2., &mut services)?;
+        assert_eq!(convex.exterior().half_edges().len(), 4);
+
+        let concave = Region::from_point_cloud(points, 1., &mut services)?;
+        let outline = concave
+            .exterior()
+            .half_edges()
+            .iter()
+            .map(|half_edge| half_edge.start_position())
+            .collect::<Vec<_>>();
+        assert_eq!(outline.len(), 5);
+        assert!(outline.contains(&Point::from([2., 3.])));
+
+        let area = (0..outline.len())
+            .map(|i| {
+                let [a, b] = [outline[i], outline[(i + 1) % outline.len()]];
+                a.coords.cross2d(&b.coords)
+            })
+            .fold(Scalar::ZERO, |area, a| area + a);
+        assert!(area > Scalar::ZERO);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+}
@@ -1,10 +1,10 @@
 use std::collections::BTreeMap;
 
 use fj_interop::ext::ArrayExt;
-use fj_math::Point;
+use fj_math::{Line, Point, Scalar, Vector};
 
 use crate::{
-    geometry::CurveBoundary,
+    geometry::{CurveBoundary, GlobalPath, SurfaceGeometry},
     objects::{Curve, Face, HalfEdge, Shell, Surface, Vertex},
     operations::{
         build::{BuildFace, BuildHalfEdge, BuildSurface, Polygon},
@@ -16,6 +16,7 @@ use crate::{
         },
     },
     services::Services,
+    storage::Handle,
 };
 
 /// Build a [`Shell`]
@@ -119,6 +120,112 @@ pub trait BuildShell {
         Shell::empty().add_faces(faces)
     }
 
+    /// Build a polyhedron from vertices and faces, checking the input
+    ///
+    /// This is a more general and more robust version of
+    /// [`BuildShell::from_vertices_and_indices`]. Each face is specified as a
+    /// list of indices into `vertices`, and may have any number of vertices, as
+    /// long as they are all in the same plane.
+    ///
+    /// The faces must be wound counter-clockwise, when viewed from outside of
+    /// the polyhedron. Edges and vertices that are shared between faces are
+    /// detected and built only once.
+    ///
+    /// Returns an error, if the input does not describe a closed, manifold,
+    /// and consistently wound polyhedron. See [`PolyhedronError`] for details.
+    fn from_vertices_and_faces<F>(
+        vertices: impl IntoIterator<Item = impl Into<Point<3>>>,
+        faces: impl IntoIterator<Item = F>,
+        services: &mut Services,
+    ) -> Result<Shell, PolyhedronError>
+    where
+        F: IntoIterator<Item = usize>,
+    {
+        let positions = vertices
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<Point<3>>>();
+        let faces = faces
+            .into_iter()
+            .map(|face| face.into_iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let planes = faces
+            .iter()
+            .enumerate()
+            .map(|(index, face)| face_plane(index, face, &positions))
+            .collect::<Result<Vec<_>, _>>()?;
+        check_edges(&faces)?;
+        check_orientation(&faces, &positions)?;
+
+        let vertices = positions
+            .iter()
+            .map(|_| Vertex::new().insert(services))
+            .collect::<Vec<_>>();
+
+        let mut curves: BTreeMap<
+            [usize; 2],
+            (Handle<Curve>, CurveBoundary<Point<1>>),
+        > = BTreeMap::new();
+        let mut shell_faces = Vec::new();
+
+        for (face, (origin, [u, v])) in faces.iter().zip(planes) {
+            let surface = Surface::new(SurfaceGeometry {
+                u: GlobalPath::Line(Line::from_origin_and_direction(origin, u)),
+                v,
+            })
+            .insert(services);
+
+            let mut half_edges = Vec::new();
+            for (i, &a) in face.iter().enumerate() {
+                let b = face[(i + 1) % face.len()];
+
+                let (curve, boundary) = curves
+                    .get(&[b, a])
+                    .map(|(curve, boundary)| {
+                        (curve.clone(), boundary.reverse())
+                    })
+                    .unwrap_or_else(|| {
+                        let curve = Curve::new().insert(services);
+                        let boundary = CurveBoundary::from([[0.], [1.]]);
+
+                        curves.insert([a, b], (curve.clone(), boundary));
+
+                        (curve, boundary)
+                    });
+
+                let points_surface = [a, b].map(|index| {
+                    let vector = positions[index] - origin;
+                    Point::from([vector.dot(&u), vector.dot(&v)])
+                });
+
+                let half_edge = HalfEdge::line_segment(
+                    points_surface,
+                    Some(boundary.inner),
+                    services,
+                )
+                .update_start_vertex(|_| vertices[a].clone())
+                .update_curve(|_| curve)
+                .insert(services);
+
+                half_edges.push(half_edge);
+            }
+
+            let face = Face::unbound(surface, services)
+                .update_region(|region| {
+                    region
+                        .update_exterior(|cycle| {
+                            cycle.add_half_edges(half_edges).insert(services)
+                        })
+                        .insert(services)
+                })
+                .insert(services);
+            shell_faces.push(face);
+        }
+
+        Ok(Shell::empty().add_faces(shell_faces))
+    }
+
     /// Build a tetrahedron from the provided points
     ///
     /// Accepts 4 points, naturally. For the purposes of the following
@@ -266,6 +373,264 @@ pub trait BuildShell {
 
 impl BuildShell for Shell {}
 
+/// Error building a polyhedron
+///
+/// Returned by [`BuildShell::from_vertices_and_faces`]. Faces are referred to
+/// by their index in the list of faces, vertices by their index in the list of
+/// vertices.
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum PolyhedronError {
+    /// A face has fewer than 3 vertices
+    #[error(
+        "Face {face} has {num_vertices} vertices; at least 3 are required"
+    )]
+    TooFewVertices {
+        /// The face with too few vertices
+        face: usize,
+
+        /// The number of vertices of the face
+        num_vertices: usize,
+    },
+
+    /// A face refers to a vertex that does not exist
+    #[error(
+        "Face {face} refers to vertex {index}, but there are only \
+        {num_vertices} vertices"
+    )]
+    InvalidIndex {
+        /// The face that refers to the vertex
+        face: usize,
+
+        /// The invalid vertex index
+        index: usize,
+
+        /// The number of vertices
+        num_vertices: usize,
+    },
+
+    /// A face refers to the same vertex more than once
+    #[error("Face {face} refers to vertex {index} more than once")]
+    DuplicateVertex {
+        /// The face that refers to the vertex multiple times
+        face: usize,
+
+        /// The index of the duplicate vertex
+        index: usize,
+    },
+
+    /// A face has no area, or one of its edges has no length
+    #[error("Face {face} is degenerate")]
+    DegenerateFace {
+        /// The degenerate face
+        face: usize,
+    },
+
+    /// The vertices of a face are not in the same plane
+    #[error("Vertices of face {face} are not in the same plane")]
+    NonPlanarFace {
+        /// The non-planar face
+        face: usize,
+    },
+
+    /// An edge is only used by a single face
+    #[error(
+        "Edge between vertices {} and {} is only used by face {face}; \
+        polyhedron is not closed",
+        .edge[0],
+        .edge[1]
+    )]
+    OpenEdge {
+        /// The vertices of the edge
+        edge: [usize; 2],
+
+        /// The face that uses the edge
+        face: usize,
+    },
+
+    /// An edge is shared by more than two faces
+    #[error(
+        "Edge between vertices {} and {} is shared by more than two faces \
+        ({faces:?}); polyhedron is not manifold",
+        .edge[0],
+        .edge[1]
+    )]
+    NonManifoldEdge {
+        /// The vertices of the edge
+        edge: [usize; 2],
+
+        /// The faces that share the edge
+        faces: Vec<usize>,
+    },
+
+    /// Two faces traverse their shared edge in the same direction
+    #[error(
+        "Faces {} and {} traverse the edge between vertices {} and {} in the \
+        same direction; their winding is inconsistent",
+        .faces[0],
+        .faces[1],
+        .edge[0],
+        .edge[1]
+    )]
+    InconsistentWinding {
+        /// The vertices of the edge
+        edge: [usize; 2],
+
+        /// The faces that share the edge
+        faces: [usize; 2],
+    },
+
+    /// All faces are wound clockwise, when viewed from outside
+    #[error(
+        "Faces are wound clockwise, when viewed from outside of the \
+        polyhedron; reverse the order of the vertices in every face"
+    )]
+    InvertedWinding,
+}
+
+/// Compute the plane of a polyhedron face
+///
+/// Returns the origin of the plane, and two orthonormal vectors within it. The
+/// cross product of those is the face's normal.
+fn face_plane(
+    index: usize,
+    face: &[usize],
+    positions: &[Point<3>],
+) -> Result<(Point<3>, [Vector<3>; 2]), PolyhedronError> {
+    // Relative to the size of the face, how far a vertex can be from the plane
+    // of the face, before the face is no longer considered planar.
+    const PLANARITY_TOLERANCE: f64 = 1e-9;
+
+    if face.len() < 3 {
+        return Err(PolyhedronError::TooFewVertices {
+            face: index,
+            num_vertices: face.len(),
+        });
+    }
+    for (i, &vertex) in face.iter().enumerate() {
+        if vertex >= positions.len() {
+            return Err(PolyhedronError::InvalidIndex {
+                face: index,
+                index: vertex,
+                num_vertices: positions.len(),
+            });
+        }
+        if face[..i].contains(&vertex) {
+            return Err(PolyhedronError::DuplicateVertex {
+                face: index,
+                index: vertex,
+            });
+        }
+    }
+
+    let points = face.iter().map(|&i| positions[i]).collect::<Vec<_>>();
+    let edges = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(&a, &b)| [a, b])
+        .collect::<Vec<_>>();
+
+    let size = edges
+        .iter()
+        .map(|[a, b]| a.distance_to(b))
+        .fold(Scalar::ZERO, Scalar::max);
+    if edges.iter().any(|[a, b]| a == b) {
+        return Err(PolyhedronError::DegenerateFace { face: index });
+    }
+
+    // Newell's method. Works for all simple polygons, whether they are convex
+    // or not.
+    let normal = edges.iter().fold(Vector::from([0., 0., 0.]), |n, [a, b]| {
+        n + Vector::from([
+            (a.y - b.y) * (a.z + b.z),
+            (a.z - b.z) * (a.x + b.x),
+            (a.x - b.x) * (a.y + b.y),
+        ])
+    });
+    if normal.magnitude() == Scalar::ZERO {
+        return Err(PolyhedronError::DegenerateFace { face: index });
+    }
+    let normal = normal.normalize();
+
+    let origin = points[0];
+    let is_planar = points.iter().all(|point| {
+        (point - origin).dot(&normal).abs() <= size * PLANARITY_TOLERANCE
+    });
+    if !is_planar {
+        return Err(PolyhedronError::NonPlanarFace { face: index });
+    }
+
+    let u = points[1] - origin;
+    let u = (u - normal * u.dot(&normal)).normalize();
+    let v = normal.cross(&u);
+
+    Ok((origin, [u, v]))
+}
+
+/// Check that every edge is shared by exactly two faces, in opposite directions
+fn check_edges(faces: &[Vec<usize>]) -> Result<(), PolyhedronError> {
+    let mut edges = BTreeMap::<_, Vec<_>>::new();
+
+    for (index, face) in faces.iter().enumerate() {
+        for (&a, &b) in face.iter().zip(face.iter().cycle().skip(1)) {
+            let key = if a < b { [a, b] } else { [b, a] };
+            edges.entry(key).or_default().push((index, a < b));
+        }
+    }
+
+    for (edge, uses) in edges {
+        match uses.as_slice() {
+            [(face, _)] => {
+                return Err(PolyhedronError::OpenEdge { edge, face: *face })
+            }
+            [(a, a_forward), (b, b_forward)] => {
+                if a_forward == b_forward {
+                    return Err(PolyhedronError::InconsistentWinding {
+                        edge,
+                        faces: [*a, *b],
+                    });
+                }
+            }
+            _ => {
+                return Err(PolyhedronError::NonManifoldEdge {
+                    edge,
+                    faces: uses.iter().map(|(face, _)| *face).collect(),
+                })
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that the faces are wound counter-clockwise, when viewed from outside
+///
+/// Expects the winding of the faces to be consistent. In that case, the signed
+/// volume of the polyhedron is positive, if its faces point outward.
+fn check_orientation(
+    faces: &[Vec<usize>],
+    positions: &[Point<3>],
+) -> Result<(), PolyhedronError> {
+    let mut volume = Scalar::ZERO;
+
+    for face in faces {
+        let [first, rest @ ..] = face.as_slice() else {
+            continue;
+        };
+        let a = positions[*first].coords;
+
+        for window in rest.windows(2) {
+            let [b, c] = [window[0], window[1]].map(|i| positions[i].coords);
+            volume += a.dot(&b.cross(&c));
+        }
+    }
+
+    if volume < Scalar::ZERO {
+        return Err(PolyhedronError::InvertedWinding);
+    }
+
+    Ok(())
+}
+
 /// A tetrahedron
 ///
 /// A tetrahedron is constructed from 4 points and has 4 faces. For the purpose
@@ -289,3 +654,159 @@ pub struct TetrahedronShell<I: IsInserted = IsInsertedNo> {
     /// The face formed by the points `c`, `b`, and `d`.
     pub cbd: Polygon<3, IsInsertedYes>,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::Shell, operations::insert::Insert, services::Services,
+    };
+
+    use super::{BuildShell, PolyhedronError};
+
+    #[test]
+    fn from_vertices_and_faces() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let shell =
+            Shell::from_vertices_and_faces(CUBE, cube_faces(), &mut services)?
+                .insert(&mut services);
+        assert_eq!(shell.faces().len(), 6);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn from_vertices_and_faces_too_few_vertices() {
+        assert_eq!(
+            build([vec![0, 1]]),
+            Err(PolyhedronError::TooFewVertices {
+                face: 0,
+                num_vertices: 2,
+            }),
+        );
+    }
+
+    #[test]
+    fn from_vertices_and_faces_invalid_index() {
+        assert_eq!(
+            build([vec![0, 1, 8]]),
+            Err(PolyhedronError::InvalidIndex {
+                face: 0,
+                index: 8,
+                num_vertices: 8,
+            }),
+        );
+    }
+
+    #[test]
+    fn from_vertices_and_faces_duplicate_vertex() {
+        assert_eq!(
+            build([vec![0, 3, 0, 1]]),
+            Err(PolyhedronError::DuplicateVertex { face: 0, index: 0 }),
+        );
+    }
+
+    #[test]
+    fn from_vertices_and_faces_degenerate_face() {
+        let mut services = Services::new();
+
+        // The first three vertices are on the same line.
+        let result = Shell::from_vertices_and_faces(
+            [[0., 0., 0.], [1., 0., 0.], [2., 0., 0.]],
+            [[0, 1, 2]],
+            &mut services,
+        );
+        assert_eq!(result, Err(PolyhedronError::DegenerateFace { face: 0 }));
+    }
+
+    #[test]
+    fn from_vertices_and_faces_non_planar_face() {
+        assert_eq!(
+            build([vec![0, 1, 2, 7]]),
+            Err(PolyhedronError::NonPlanarFace { face: 0 }),
+        );
+    }
+
+    #[test]
+    fn from_vertices_and_faces_open_edge() {
+        let mut faces = cube_faces();
+        faces.remove(1);
+
+        assert_eq!(
+            build(faces),
+            Err(PolyhedronError::OpenEdge {
+                edge: [4, 5],
+                face: 1,
+            }),
+        );
+    }
+
+    #[test]
+    fn from_vertices_and_faces_non_manifold_edge() {
+        let mut faces = cube_faces();
+        faces.push(vec![0, 1, 3]);
+
+        assert_eq!(
+            build(faces),
+            Err(PolyhedronError::NonManifoldEdge {
+                edge: [0, 1],
+                faces: vec![0, 2, 6],
+            }),
+        );
+    }
+
+    #[test]
+    fn from_vertices_and_faces_inconsistent_winding() {
+        let mut faces = cube_faces();
+        faces[1].reverse();
+
+        assert_eq!(
+            build(faces),
+            Err(PolyhedronError::InconsistentWinding {
+                edge: [4, 5],
+                faces: [1, 2],
+            }),
+        );
+    }
+
+    #[test]
+    fn from_vertices_and_faces_inverted_winding() {
+        let mut faces = cube_faces();
+        for face in &mut faces {
+            face.reverse();
+        }
+
+        assert_eq!(build(faces), Err(PolyhedronError::InvertedWinding));
+    }
+
+    const CUBE: [[f64; 3]; 8] = [
+        [0., 0., 0.],
+        [1., 0., 0.],
+        [1., 1., 0.],
+        [0., 1., 0.],
+        [0., 0., 1.],
+        [1., 0., 1.],
+        [1., 1., 1.],
+        [0., 1., 1.],
+    ];
+
+    /// The faces of `CUBE`, wound counter-clockwise when viewed from outside
+    fn cube_faces() -> Vec<Vec<usize>> {
+        vec![
+            vec![0, 3, 2, 1], // bottom
+            vec![4, 5, 6, 7], // top
+            vec![0, 1, 5, 4], // front
+            vec![2, 3, 7, 6], // back
+            vec![0, 4, 7, 3], // left
+            vec![1, 2, 6, 5], // right
+        ]
+    }
+
+    fn build(
+        faces: impl IntoIterator<Item = Vec<usize>>,
+    ) -> Result<Shell, PolyhedronError> {
+        let mut services = Services::new();
+        Shell::from_vertices_and_faces(CUBE, faces, &mut services)
+    }
+}
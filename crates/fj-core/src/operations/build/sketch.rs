@@ -1,4 +1,16 @@
-use crate::objects::Sketch;
+use fj_math::{Arc, Point, Scalar, Vector};
+
+use crate::{
+    algorithms::approx::Tolerance,
+    objects::{Cycle, HalfEdge, Region, Sketch},
+    operations::{
+        build::{BuildCycle, BuildHalfEdge},
+        insert::Insert,
+        reverse::Reverse,
+        update::UpdateCycle,
+    },
+    services::Services,
+};
 
 /// Build a [`Sketch`]
 ///
@@ -13,3 +25,470 @@ pub trait BuildSketch {
 }
 
 impl BuildSketch for Sketch {}
+
+/// Build a [`Sketch`] by tracing its outline
+///
+/// Start with [`SketchBuilder::start_at`], add segments to the current cycle
+/// using the various `*_to` methods, then [`SketchBuilder::close`] it. Further
+/// cycles can be started with [`SketchBuilder::move_to`].
+///
+/// When the sketch is built, the builder determines which cycles are exterior
+/// cycles, and which ones are holes within them, based on how they are nested.
+/// It also makes sure that all cycles have the winding that is required for
+/// their role, so they can be specified in any direction.
+#[derive(Clone, Debug)]
+pub struct SketchBuilder {
+    cycles: Vec<Vec<Segment>>,
+    start: Point<2>,
+    current: Point<2>,
+    segments: Vec<Segment>,
+}
+
+impl SketchBuilder {
+    /// Start a sketch, with its first cycle starting at the provided point
+    pub fn start_at(point: impl Into<Point<2>>) -> Self {
+        let point = point.into();
+
+        Self {
+            cycles: Vec::new(),
+            start: point,
+            current: point,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Start a new cycle at the provided point
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the current cycle has not been closed.
+    pub fn move_to(mut self, point: impl Into<Point<2>>) -> Self {
+        assert!(
+            self.segments.is_empty(),
+            "Must close current cycle, before starting a new one"
+        );
+
+        let point = point.into();
+        self.start = point;
+        self.current = point;
+
+        self
+    }
+
+    /// Add a line segment from the current point to the provided one
+    pub fn line_to(mut self, point: impl Into<Point<2>>) -> Self {
+        let end = point.into();
+
+        self.segments.push(Segment::Line {
+            start: self.current,
+            end,
+        });
+        self.current = end;
+
+        self
+    }
+
+    /// Add a line segment through each of the provided points, in order
+    pub fn polyline_to(
+        self,
+        points: impl IntoIterator<Item = impl Into<Point<2>>>,
+    ) -> Self {
+        points.into_iter().fold(self, Self::line_to)
+    }
+
+    /// Add an arc from the current point to the provided one
+    ///
+    /// See [`BuildHalfEdge::arc`] for the meaning of `angle_rad`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given angle is not within the range (-2pi, 2pi) radians.
+    pub fn arc_to(
+        mut self,
+        point: impl Into<Point<2>>,
+        angle_rad: impl Into<Scalar>,
+    ) -> Self {
+        let end = point.into();
+        let angle_rad = angle_rad.into();
+        assert!(
+            -Scalar::TAU < angle_rad && angle_rad < Scalar::TAU,
+            "arc angle must be in the range (-2pi, 2pi) radians"
+        );
+
+        self.segments.push(Segment::Arc {
+            start: self.current,
+            end,
+            angle_rad,
+        });
+        self.current = end;
+
+        self
+    }
+
+    /// Add a cubic Bézier spline from the current point to the provided one
+    ///
+    /// The kernel has no native representation of splines yet. The spline is
+    /// approximated by line segments instead, within the provided tolerance.
+    pub fn spline_to(
+        self,
+        control_points: [impl Into<Point<2>>; 2],
+        point: impl Into<Point<2>>,
+        tolerance: impl Into<Tolerance>,
+    ) -> Self {
+        let [b, c] = control_points.map(Into::into);
+        let spline = [self.current, b, c, point.into()];
+
+        let mut points = Vec::new();
+        flatten_spline(spline, tolerance.into(), 0, &mut points);
+
+        self.polyline_to(points)
+    }
+
+    /// Determine whether the current cycle has been closed
+    ///
+    /// Returns `true`, if no segments have been added since the last cycle was
+    /// closed, or since the sketch was started.
+    pub fn is_closed(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Close the current cycle
+    ///
+    /// Adds a line segment back to the start of the cycle, unless the current
+    /// point already is the start.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the current cycle is empty.
+    pub fn close(mut self) -> Self {
+        if self.current != self.start {
+            let start = self.start;
+            self = self.line_to(start);
+        }
+
+        let segments = std::mem::take(&mut self.segments);
+        assert!(!segments.is_empty(), "Can't close empty cycle");
+        self.cycles.push(segments);
+
+        self
+    }
+
+    /// Add a circle as a separate cycle
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the current cycle has not been closed.
+    pub fn circle(
+        mut self,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+    ) -> Self {
+        assert!(
+            self.segments.is_empty(),
+            "Must close current cycle, before adding a circle"
+        );
+
+        self.cycles.push(vec![Segment::Circle {
+            center: center.into(),
+            radius: radius.into(),
+        }]);
+
+        self
+    }
+
+    /// Build the sketch
+    ///
+    /// Cycles that are contained in an even number of other cycles become the
+    /// exterior cycles of regions. All others become the interior cycles of the
+    /// region whose exterior cycle directly contains them.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the current cycle has not been closed.
+    pub fn build(self, services: &mut Services) -> Sketch {
+        assert!(
+            self.segments.is_empty(),
+            "Must close current cycle, before building the sketch"
+        );
+
+        let polygons = self
+            .cycles
+            .iter()
+            .map(|segments| {
+                segments
+                    .iter()
+                    .flat_map(Segment::approximate)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // For each cycle, the cycles that contain it.
+        let containers = polygons
+            .iter()
+            .enumerate()
+            .map(|(i, polygon)| {
+                (0..polygons.len())
+                    .filter(|&j| {
+                        j != i && polygon_contains(&polygons[j], polygon[0])
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let cycles = self
+            .cycles
+            .iter()
+            .zip(&polygons)
+            .zip(&containers)
+            .map(|((segments, polygon), containers)| {
+                let is_exterior = containers.len() % 2 == 0;
+                let is_ccw = signed_area(polygon) > Scalar::ZERO;
+
+                let cycle =
+                    segments.iter().fold(Cycle::empty(), |cycle, segment| {
+                        let half_edge =
+                            segment.build(services).insert(services);
+                        cycle.add_half_edges([half_edge])
+                    });
+
+                if is_exterior == is_ccw {
+                    cycle.insert(services)
+                } else {
+                    cycle.reverse(services).insert(services)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let regions = (0..cycles.len())
+            .filter(|&i| containers[i].len() % 2 == 0)
+            .map(|i| {
+                let interiors = (0..cycles.len())
+                    .filter(|&j| {
+                        containers[j].contains(&i)
+                            && containers[j].len() == containers[i].len() + 1
+                    })
+                    .map(|j| cycles[j].clone());
+
+                Region::new(cycles[i].clone(), interiors, None).insert(services)
+            })
+            .collect::<Vec<_>>();
+
+        Sketch::new(regions)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Segment {
+    Line {
+        start: Point<2>,
+        end: Point<2>,
+    },
+    Arc {
+        start: Point<2>,
+        end: Point<2>,
+        angle_rad: Scalar,
+    },
+    Circle {
+        center: Point<2>,
+        radius: Scalar,
+    },
+}
+
+impl Segment {
+    fn build(&self, services: &mut Services) -> HalfEdge {
+        match *self {
+            Self::Line { start, end } => {
+                HalfEdge::line_segment([start, end], None, services)
+            }
+            Self::Arc {
+                start,
+                end,
+                angle_rad,
+            } => HalfEdge::arc(start, end, angle_rad, services),
+            Self::Circle { center, radius } => {
+                HalfEdge::circle(center, radius, services)
+            }
+        }
+    }
+
+    /// Approximate the segment by points, for classifying its cycle
+    ///
+    /// Does not include the end point, as that is the start point of the next
+    /// segment.
+    fn approximate(&self) -> Vec<Point<2>> {
+        // This approximation is only used to determine winding and nesting of
+        // cycles, which doesn't require much precision.
+        const NUM_POINTS: usize = 16;
+
+        let (center, radius, [start_angle, end_angle]) = match *self {
+            Self::Line { start, .. } => return vec![start],
+            Self::Arc {
+                start,
+                end,
+                angle_rad,
+            } => {
+                let arc = Arc::from_endpoints_and_angle(start, end, angle_rad);
+                (arc.center, arc.radius, [arc.start_angle, arc.end_angle])
+            }
+            Self::Circle { center, radius } => {
+                (center, radius, [Scalar::ZERO, Scalar::TAU])
+            }
+        };
+
+        (0..NUM_POINTS)
+            .map(|i| {
+                let angle = start_angle
+                    + (end_angle - start_angle)
+                        * (i as f64 / NUM_POINTS as f64);
+                let (sin, cos) = angle.sin_cos();
+
+                center + Vector::from([cos, sin]) * radius
+            })
+            .collect()
+    }
+}
+
+/// Flatten a cubic Bézier spline into line segments
+///
+/// Pushes the end points of the line segments into `points`.
+fn flatten_spline(
+    [a, b, c, d]: [Point<2>; 4],
+    tolerance: Tolerance,
+    depth: usize,
+    points: &mut Vec<Point<2>>,
+) {
+    // Safeguard against infinite recursion, in case the tolerance is too small
+    // to ever be reached, due to floating-point accuracy.
+    const MAX_DEPTH: usize = 16;
+
+    // The distance of the control points from the chord is an upper bound for
+    // the distance of the spline from it.
+    let chord = d - a;
+    let deviation = [b, c]
+        .map(|point| {
+            let vector = point - a;
+
+            if chord.magnitude() == Scalar::ZERO {
+                vector.magnitude()
+            } else {
+                vector.cross2d(&chord).abs() / chord.magnitude()
+            }
+        })
+        .into_iter()
+        .fold(Scalar::ZERO, Scalar::max);
+
+    if deviation <= tolerance.inner() || depth >= MAX_DEPTH {
+        points.push(d);
+        return;
+    }
+
+    // Split the spline in half, using de Casteljau's algorithm.
+    let ab = midpoint(a, b);
+    let bc = midpoint(b, c);
+    let cd = midpoint(c, d);
+    let abc = midpoint(ab, bc);
+    let bcd = midpoint(bc, cd);
+    let abcd = midpoint(abc, bcd);
+
+    flatten_spline([a, ab, abc, abcd], tolerance, depth + 1, points);
+    flatten_spline([abcd, bcd, cd, d], tolerance, depth + 1, points);
+}
+
+fn midpoint(a: Point<2>, b: Point<2>) -> Point<2> {
+    a + (b - a) / 2.
+}
+
+fn signed_area(polygon: &[Point<2>]) -> Scalar {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.coords.cross2d(&b.coords))
+        .fold(Scalar::ZERO, |sum, area| sum + area)
+        / 2.
+}
+
+fn polygon_contains(polygon: &[Point<2>], point: Point<2>) -> bool {
+    // Even-odd rule, casting a ray from the point in positive u direction.
+    let mut contains = false;
+
+    for (a, b) in polygon.iter().zip(polygon.iter().cycle().skip(1)) {
+        if (a.v > point.v) != (b.v > point.v) {
+            let u = a.u + (point.v - a.v) / (b.v - a.v) * (b.u - a.u);
+            if point.u < u {
+                contains = !contains;
+            }
+        }
+    }
+
+    contains
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_math::Winding;
+
+    use crate::services::Services;
+
+    use super::SketchBuilder;
+
+    #[test]
+    fn exterior_and_interior_cycles() {
+        let mut services = Services::new();
+
+        // Exterior is specified clockwise, interior counter-clockwise. The
+        // builder needs to fix both.
+        let sketch = SketchBuilder::start_at([0., 0.])
+            .line_to([0., 5.])
+            .line_to([5., 5.])
+            .line_to([5., 0.])
+            .close()
+            .move_to([1., 1.])
+            .line_to([3., 1.])
+            .arc_to([3., 3.], PI)
+            .line_to([1., 3.])
+            .close()
+            .build(&mut services);
+
+        assert_eq!(sketch.regions().len(), 1);
+
+        let region = sketch.regions().first();
+        assert_eq!(region.exterior().winding(), Winding::Ccw);
+
+        let interiors = region.interiors().iter().collect::<Vec<_>>();
+        assert_eq!(interiors.len(), 1);
+        assert_eq!(interiors[0].winding(), Winding::Cw);
+    }
+
+    #[test]
+    fn nested_regions() {
+        let mut services = Services::new();
+
+        let sketch = SketchBuilder::start_at([0., 0.])
+            .polyline_to([[6., 0.], [6., 6.], [0., 6.]])
+            .close()
+            .circle([3., 3.], 2.)
+            .circle([3., 3.], 1.)
+            .build(&mut services);
+
+        // The outer square with the large circle as a hole, and the small
+        // circle as a separate region within that hole.
+        assert_eq!(sketch.regions().len(), 2);
+    }
+
+    #[test]
+    fn spline() {
+        let mut services = Services::new();
+
+        let sketch = SketchBuilder::start_at([0., 0.])
+            .line_to([2., 0.])
+            .spline_to([[3., 1.], [1., 2.]], [0., 2.], 0.01)
+            .close()
+            .build(&mut services);
+
+        let region = sketch.regions().first();
+        assert!(region.exterior().half_edges().len() > 3);
+        assert_eq!(region.exterior().winding(), Winding::Ccw);
+    }
+}
@@ -3,7 +3,7 @@ use fj_math::Point;
 use crate::{
     objects::{Shell, Solid},
     operations::{
-        build::{BuildShell, TetrahedronShell},
+        build::{BuildShell, PolyhedronError, TetrahedronShell},
         insert::{Insert, IsInsertedYes},
         update::UpdateSolid,
     },
@@ -21,6 +21,22 @@ pub trait BuildSolid {
         Solid::new([])
     }
 
+    /// Build a polyhedron from vertices and faces
+    ///
+    /// See [`BuildShell::from_vertices_and_faces`] for more information.
+    fn from_vertices_and_faces<F>(
+        vertices: impl IntoIterator<Item = impl Into<Point<3>>>,
+        faces: impl IntoIterator<Item = F>,
+        services: &mut Services,
+    ) -> Result<Solid, PolyhedronError>
+    where
+        F: IntoIterator<Item = usize>,
+    {
+        let shell = Shell::from_vertices_and_faces(vertices, faces, services)?
+            .insert(services);
+        Ok(Solid::empty().add_shells([shell]))
+    }
+
     /// Build a tetrahedron from the provided points
     ///
     /// See [`BuildShell::tetrahedron`] for more information.
@@ -0,0 +1,349 @@
+//! # Close open shells with caps
+//!
+//! See [`CapShell`].
+
+use std::collections::BTreeMap;
+
+use fj_math::{Circle, Line, Point, Scalar, Vector};
+
+use crate::{
+    geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{Cycle, Face, HalfEdge, Region, Shell, Solid, Surface},
+    queries::SiblingOfHalfEdge,
+    services::Services,
+    storage::{Handle, ObjectId},
+    validate::ValidationConfig,
+};
+
+use super::{
+    build::BuildCycle, insert::Insert, join::JoinCycle, update::UpdateShell,
+};
+
+/// Close the open boundaries of a shell with cap faces
+///
+/// This is useful after sweeping open profiles, or when dealing with shells
+/// that are missing faces for some other reason.
+///
+/// # Implementation Note
+///
+/// Each open boundary must be planar. Boundaries that consist of lines and
+/// circular arcs are supported, as long as those lines and arcs are also lines
+/// and arcs in global space.
+pub trait CapShell {
+    /// Close the open boundaries of the shell and build a solid from it
+    fn cap(&self, services: &mut Services) -> Result<Solid, CapError>;
+}
+
+impl CapShell for Shell {
+    fn cap(&self, services: &mut Services) -> Result<Solid, CapError> {
+        let boundaries = open_boundaries(self)?;
+
+        let caps = boundaries
+            .iter()
+            .map(|boundary| {
+                Ok(cap_boundary(boundary, services)?.insert(services))
+            })
+            .collect::<Result<Vec<_>, CapError>>()?;
+
+        let shell = self.add_faces(caps).insert(services);
+        Ok(Solid::new([shell]))
+    }
+}
+
+/// Error capping a shell
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum CapError {
+    /// The open half-edges of the shell don't form closed boundaries
+    #[error("Open half-edges of shell don't form closed boundaries")]
+    BoundaryNotClosed,
+
+    /// A boundary doesn't enclose any area
+    #[error("Boundary of shell doesn't enclose any area")]
+    Degenerate,
+
+    /// A boundary is not planar
+    #[error("Boundary of shell is not planar")]
+    NotPlanar,
+
+    /// A boundary contains an edge that can't be represented on a plane
+    #[error("Boundary of shell contains unsupported edge")]
+    UnsupportedEdge,
+}
+
+/// A half-edge on an open boundary, with the information needed to cap it
+struct BoundaryHalfEdge {
+    half_edge: Handle<HalfEdge>,
+    surface: SurfaceGeometry,
+}
+
+impl BoundaryHalfEdge {
+    /// Compute the global point at the provided fraction of the half-edge
+    fn sample(&self, fraction: f64) -> Point<3> {
+        let [start, end] = self.half_edge.boundary().inner;
+        let point_curve = start + (end - start) * fraction;
+        let point_surface =
+            self.half_edge.path().point_from_path_coords(point_curve);
+        self.surface.point_from_surface_coords(point_surface)
+    }
+}
+
+/// Find the open boundaries of the shell, as closed chains of half-edges
+fn open_boundaries(
+    shell: &Shell,
+) -> Result<Vec<Vec<BoundaryHalfEdge>>, CapError> {
+    let mut by_start_vertex = BTreeMap::new();
+    let mut end_vertices = BTreeMap::new();
+
+    for face in shell.faces() {
+        for cycle in face.region().all_cycles() {
+            for (half_edge, next) in cycle.half_edges().pairs() {
+                if shell.get_sibling_of(half_edge).is_some() {
+                    continue;
+                }
+
+                let boundary_half_edge = BoundaryHalfEdge {
+                    half_edge: half_edge.clone(),
+                    surface: face.surface().geometry(),
+                };
+
+                let start = half_edge.start_vertex().id();
+                if by_start_vertex.insert(start, boundary_half_edge).is_some() {
+                    // More than one open half-edge starts at this vertex. We
+                    // couldn't decide how to chain them.
+                    return Err(CapError::BoundaryNotClosed);
+                }
+                end_vertices.insert(start, next.start_vertex().id());
+            }
+        }
+    }
+
+    let mut boundaries = Vec::new();
+
+    while let Some((first, half_edge)) = by_start_vertex.pop_first() {
+        let mut boundary = vec![half_edge];
+        let mut next: ObjectId = end_vertices[&first];
+
+        while next != first {
+            let half_edge = by_start_vertex
+                .remove(&next)
+                .ok_or(CapError::BoundaryNotClosed)?;
+            boundary.push(half_edge);
+
+            next = end_vertices[&next];
+        }
+
+        boundaries.push(boundary);
+    }
+
+    Ok(boundaries)
+}
+
+fn cap_boundary(
+    boundary: &[BoundaryHalfEdge],
+    services: &mut Services,
+) -> Result<Face, CapError> {
+    let samples = boundary
+        .iter()
+        .flat_map(|half_edge| {
+            [0., 0.25, 0.5, 0.75].map(|fraction| half_edge.sample(fraction))
+        })
+        .collect::<Vec<_>>();
+
+    let plane = cap_plane(&samples)?;
+
+    // The cap runs along the boundary in the opposite direction. This is what
+    // `add_joined_edges` expects, as each of the new half-edges must start
+    // where the half-edge it is joined to ends.
+    let edges = boundary
+        .iter()
+        .rev()
+        .map(|half_edge| {
+            let path = path_on_plane(half_edge, &plane)?;
+            Ok((
+                half_edge.half_edge.clone(),
+                path,
+                half_edge.half_edge.boundary().reverse(),
+            ))
+        })
+        .collect::<Result<Vec<_>, CapError>>()?;
+
+    let exterior = Cycle::empty()
+        .add_joined_edges(edges, services)
+        .insert(services);
+    let region = Region::new(exterior, [], None).insert(services);
+    let surface = Surface::new(plane).insert(services);
+
+    Ok(Face::new(surface, region))
+}
+
+/// Compute the plane of a cap
+///
+/// Uses Newell's method to compute the normal of the boundary. The normal of
+/// the cap points the other way, as the cap runs along the boundary in the
+/// opposite direction.
+fn cap_plane(samples: &[Point<3>]) -> Result<SurfaceGeometry, CapError> {
+    let centroid = Point {
+        coords: samples
+            .iter()
+            .fold(Vector::from([0., 0., 0.]), |sum, point| sum + point.coords)
+            / Scalar::from_u64(samples.len() as u64),
+    };
+
+    let area = samples
+        .iter()
+        .zip(samples.iter().cycle().skip(1))
+        .fold(Vector::from([0., 0., 0.]), |area, (a, b)| {
+            area + (a - centroid).cross(&(b - centroid))
+        });
+    if area.magnitude() == Scalar::ZERO {
+        return Err(CapError::Degenerate);
+    }
+    let normal = -area.normalize();
+
+    let max_distance = ValidationConfig::default().identical_max_distance;
+    if samples
+        .iter()
+        .any(|point| (point - centroid).dot(&normal).abs() > max_distance)
+    {
+        return Err(CapError::NotPlanar);
+    }
+
+    let u = samples
+        .iter()
+        .map(|point| point - centroid)
+        .max_by_key(|vector| vector.magnitude())
+        .ok_or(CapError::Degenerate)?;
+    let u = (u - normal * u.dot(&normal)).normalize();
+    let v = normal.cross(&u);
+
+    Ok(SurfaceGeometry {
+        u: GlobalPath::Line(Line::from_origin_and_direction(centroid, u)),
+        v,
+    })
+}
+
+/// Compute the path of a boundary half-edge on the plane of the cap
+///
+/// The resulting path has the same coordinates as the original one, so the
+/// new half-edge can be joined to the original.
+fn path_on_plane(
+    half_edge: &BoundaryHalfEdge,
+    plane: &SurfaceGeometry,
+) -> Result<SurfacePath, CapError> {
+    let surface = &half_edge.surface;
+    let project = |point: Point<3>| plane.project_global_point(point);
+
+    let circle = match (half_edge.half_edge.path(), surface.u) {
+        (SurfacePath::Line(line), GlobalPath::Line(_)) => {
+            return Ok(line_on_plane(line, surface, plane));
+        }
+        (SurfacePath::Line(line), _) if line.direction().u == Scalar::ZERO => {
+            // The line runs along the straight direction of a curved surface.
+            return Ok(line_on_plane(line, surface, plane));
+        }
+        (SurfacePath::Circle(circle), GlobalPath::Line(_)) => {
+            let center = surface.point_from_surface_coords(circle.center());
+            let a = surface
+                .point_from_surface_coords(circle.center() + circle.a())
+                - center;
+            let b = surface
+                .point_from_surface_coords(circle.center() + circle.b())
+                - center;
+
+            [center, center + a, center + b]
+        }
+        (SurfacePath::Line(line), GlobalPath::Circle(circle))
+            if line.direction().v == Scalar::ZERO
+                && line.direction().u.abs() == Scalar::ONE =>
+        {
+            // The line runs around a cylinder. In global space, it's a circle
+            // whose coordinates are offset from the cylinder's.
+            let u = line.origin().u;
+            let direction = line.direction().u;
+
+            let center = circle.center() + surface.v * line.origin().v;
+            let a = circle.vector_from_circle_coords([u]);
+            let b = circle.vector_from_circle_coords([u + Scalar::PI / 2.])
+                * direction;
+
+            [center, center + a, center + b]
+        }
+        _ => return Err(CapError::UnsupportedEdge),
+    };
+
+    let [center, a, b] = circle.map(project);
+    let a = a - center;
+    let b_projected = b - center;
+
+    // Construct `b` from `a`, to make sure the circle is exactly round.
+    // Otherwise, the constructor of `Circle` would panic over the smallest
+    // inaccuracy.
+    let b = Vector::from([-a.v, a.u]);
+    let b = if b.dot(&b_projected) < Scalar::ZERO {
+        -b
+    } else {
+        b
+    };
+
+    let max_distance = ValidationConfig::default().identical_max_distance;
+    if (b - b_projected).magnitude() > max_distance {
+        // The circle is not round in global space, or not parallel to the cap.
+        return Err(CapError::UnsupportedEdge);
+    }
+
+    Ok(SurfacePath::Circle(Circle::new(center, a, b)))
+}
+
+fn line_on_plane(
+    line: Line<2>,
+    surface: &SurfaceGeometry,
+    plane: &SurfaceGeometry,
+) -> SurfacePath {
+    let [origin, direction] = [line.origin(), line.origin() + line.direction()]
+        .map(|point| {
+            plane.project_global_point(surface.point_from_surface_coords(point))
+        });
+
+    SurfacePath::Line(Line::from_origin_and_direction(
+        origin,
+        direction - origin,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::Solid,
+        operations::{
+            insert::Insert, primitives::BuildPrimitive, update::UpdateShell,
+        },
+        services::Services,
+    };
+
+    use super::CapShell;
+
+    #[test]
+    fn cap_open_box() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = Solid::box_from_dims([1., 2., 3.], &mut services)
+            .insert(&mut services);
+        let shell = solid.shells().only();
+
+        // Don't insert the open shell. It's not valid, and only serves as input
+        // to the cap operation.
+        let open = shell.remove_face(shell.faces().first());
+        assert_eq!(open.faces().len(), 5);
+
+        let capped = open.cap(&mut services)?.insert(&mut services);
+        assert_eq!(capped.shells().only().faces().len(), 6);
+
+        // A closed shell has nothing left to cap.
+        let solid = shell.cap(&mut services)?;
+        assert_eq!(solid.shells().only().faces().len(), 6);
+
+        services.drop_and_validate()?;
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     objects::{
-        Curve, Cycle, Face, HalfEdge, Region, Shell, Sketch, Solid, Surface,
-        Vertex,
+        Assembly, Curve, Cycle, Datum, Face, HalfEdge, Region, Shell, Sketch,
+        Solid, Surface, Vertex,
     },
     operations::build::{Polygon, TetrahedronShell},
     services::Services,
@@ -49,8 +49,10 @@ macro_rules! impl_insert {
 }
 
 impl_insert!(
+    Assembly, assemblies;
     Curve, curves;
     Cycle, cycles;
+    Datum, datums;
     Face, faces;
     HalfEdge, half_edges;
     Region, regions;
@@ -0,0 +1,487 @@
+//! Map all objects of a given type within an object graph
+//!
+//! See [`MapObjects`].
+
+use std::{collections::BTreeMap, ops::Deref};
+
+use type_map::TypeMap;
+
+use crate::{
+    objects::{
+        Curve, Cycle, Face, HalfEdge, Region, Shell, Sketch, Solid, Surface,
+        Vertex,
+    },
+    services::Services,
+    storage::{Handle, ObjectId},
+};
+
+use super::insert::Insert;
+
+/// Map all objects of a given type within an object graph
+///
+/// Applies a closure to every object of the given type that is referenced,
+/// directly or indirectly, by the object this operation is called on. All
+/// objects that reference a mapped object are rebuilt accordingly. Objects that
+/// reference no mapped objects are left as they are.
+///
+/// Sharing is preserved: An object that is referenced from multiple places is
+/// mapped only once, and all places that referenced the original reference the
+/// same new object afterwards. This is important, as the identity of objects
+/// carries meaning. Two half-edges that share a [`Curve`], for example, are
+/// only known to be coincident, because they share it.
+pub trait MapObjects: Sized {
+    /// Map all objects of type `T` using the provided closure
+    #[must_use]
+    fn map_objects<T: MapTarget>(
+        &self,
+        f: impl FnMut(&T) -> T,
+        services: &mut Services,
+    ) -> Self {
+        let mut mapper = T::mapper(f);
+        self.map_objects_with_cache(
+            mapper.as_mut(),
+            &mut MapCache::default(),
+            services,
+        )
+    }
+
+    /// Map objects using the provided mapper and cache
+    ///
+    /// This is a low-level method. Most callers should prefer
+    /// [`MapObjects::map_objects`]. Using the same cache for multiple calls
+    /// preserves sharing between the results of those calls.
+    #[must_use]
+    fn map_objects_with_cache(
+        &self,
+        mapper: &mut dyn Mapper,
+        cache: &mut MapCache,
+        services: &mut Services,
+    ) -> Self;
+}
+
+/// Maps objects, as part of [`MapObjects`]
+///
+/// Each method maps an object of a specific type. It returns `None`, which is
+/// the default, if the object should not be mapped.
+#[allow(unused_variables)]
+pub trait Mapper {
+    /// Map a [`Curve`]
+    fn map_curve(&mut self, curve: &Curve) -> Option<Curve> {
+        None
+    }
+
+    /// Map a [`Cycle`]
+    fn map_cycle(&mut self, cycle: &Cycle) -> Option<Cycle> {
+        None
+    }
+
+    /// Map a [`Face`]
+    fn map_face(&mut self, face: &Face) -> Option<Face> {
+        None
+    }
+
+    /// Map a [`HalfEdge`]
+    fn map_half_edge(&mut self, half_edge: &HalfEdge) -> Option<HalfEdge> {
+        None
+    }
+
+    /// Map a [`Region`]
+    fn map_region(&mut self, region: &Region) -> Option<Region> {
+        None
+    }
+
+    /// Map a [`Shell`]
+    fn map_shell(&mut self, shell: &Shell) -> Option<Shell> {
+        None
+    }
+
+    /// Map a [`Sketch`]
+    fn map_sketch(&mut self, sketch: &Sketch) -> Option<Sketch> {
+        None
+    }
+
+    /// Map a [`Solid`]
+    fn map_solid(&mut self, solid: &Solid) -> Option<Solid> {
+        None
+    }
+
+    /// Map a [`Surface`]
+    fn map_surface(&mut self, surface: &Surface) -> Option<Surface> {
+        None
+    }
+
+    /// Map a [`Vertex`]
+    fn map_vertex(&mut self, vertex: &Vertex) -> Option<Vertex> {
+        None
+    }
+}
+
+/// An object type that can be mapped by [`MapObjects::map_objects`]
+pub trait MapTarget: Sized {
+    /// Create a [`Mapper`] that maps objects of this type using a closure
+    fn mapper<'r>(f: impl FnMut(&Self) -> Self + 'r) -> Box<dyn Mapper + 'r>;
+}
+
+/// A cache for mapped objects
+///
+/// See [`MapObjects`].
+#[derive(Default)]
+pub struct MapCache(TypeMap);
+
+impl MapCache {
+    fn get<T: 'static>(&mut self, key: &Handle<T>) -> Option<&Handle<T>> {
+        // Silencing Clippy warning due to false positive in Rust 1.73.0. See:
+        // https://github.com/rust-lang/rust-clippy/issues/11390#issuecomment-1750951533
+        #[allow(clippy::unwrap_or_default)]
+        let map = self
+            .0
+            .entry::<BTreeMap<ObjectId, Handle<T>>>()
+            .or_insert_with(BTreeMap::new);
+
+        map.get(&key.id())
+    }
+
+    fn insert<T: 'static>(&mut self, key: Handle<T>, value: Handle<T>) {
+        // Silencing Clippy warning due to false positive in Rust 1.73.0. See:
+        // https://github.com/rust-lang/rust-clippy/issues/11390#issuecomment-1750951533
+        #[allow(clippy::unwrap_or_default)]
+        let map = self
+            .0
+            .entry::<BTreeMap<ObjectId, Handle<T>>>()
+            .or_insert_with(BTreeMap::new);
+
+        map.insert(key.id(), value);
+    }
+}
+
+/// Map the objects a bare object references, and the object itself
+trait MapChildren: Sized {
+    /// Map the referenced objects
+    ///
+    /// Returns `None`, if none of them have changed.
+    fn map_children(
+        &self,
+        mapper: &mut dyn Mapper,
+        cache: &mut MapCache,
+        services: &mut Services,
+    ) -> Option<Self>;
+
+    /// Map the object itself, by calling the respective method of `mapper`
+    fn map_self(&self, mapper: &mut dyn Mapper) -> Option<Self>;
+}
+
+fn map_handle<T>(
+    handle: &Handle<T>,
+    mapper: &mut dyn Mapper,
+    cache: &mut MapCache,
+    services: &mut Services,
+) -> Handle<T>
+where
+    T: MapChildren + Insert<Inserted = Handle<T>> + 'static,
+{
+    if let Some(mapped) = cache.get(handle) {
+        return mapped.clone();
+    }
+
+    let with_mapped_children = handle.map_children(mapper, cache, services);
+    let current = with_mapped_children.as_ref().unwrap_or(handle.deref());
+
+    let mapped = match (current.map_self(mapper), with_mapped_children) {
+        (Some(object), _) | (None, Some(object)) => object.insert(services),
+        (None, None) => handle.clone(),
+    };
+
+    cache.insert(handle.clone(), mapped.clone());
+    mapped
+}
+
+/// Map a list of handles
+///
+/// Returns `None`, if none of them have changed.
+fn map_handles<'a, T>(
+    handles: impl IntoIterator<Item = &'a Handle<T>>,
+    mapper: &mut dyn Mapper,
+    cache: &mut MapCache,
+    services: &mut Services,
+) -> Option<Vec<Handle<T>>>
+where
+    T: MapChildren + Insert<Inserted = Handle<T>> + 'static,
+{
+    let mut changed = false;
+
+    let mapped = handles
+        .into_iter()
+        .map(|handle| {
+            let mapped = map_handle(handle, mapper, cache, services);
+            changed |= mapped.id() != handle.id();
+            mapped
+        })
+        .collect();
+
+    changed.then_some(mapped)
+}
+
+macro_rules! impl_map {
+    ($($ty:ident, $method:ident;)*) => {
+        $(
+            impl MapObjects for Handle<$ty> {
+                fn map_objects_with_cache(
+                    &self,
+                    mapper: &mut dyn Mapper,
+                    cache: &mut MapCache,
+                    services: &mut Services,
+                ) -> Self {
+                    map_handle(self, mapper, cache, services)
+                }
+            }
+
+            impl MapTarget for $ty {
+                fn mapper<'r>(
+                    f: impl FnMut(&Self) -> Self + 'r,
+                ) -> Box<dyn Mapper + 'r> {
+                    struct ClosureMapper<F>(F);
+
+                    impl<F> Mapper for ClosureMapper<F>
+                    where
+                        F: FnMut(&$ty) -> $ty,
+                    {
+                        fn $method(&mut self, object: &$ty) -> Option<$ty> {
+                            Some((self.0)(object))
+                        }
+                    }
+
+                    Box::new(ClosureMapper(f))
+                }
+            }
+        )*
+    };
+}
+
+impl_map!(
+    Curve, map_curve;
+    Cycle, map_cycle;
+    Face, map_face;
+    HalfEdge, map_half_edge;
+    Region, map_region;
+    Shell, map_shell;
+    Sketch, map_sketch;
+    Solid, map_solid;
+    Surface, map_surface;
+    Vertex, map_vertex;
+);
+
+macro_rules! impl_map_children_for_leaf {
+    ($($ty:ident, $method:ident;)*) => {
+        $(
+            impl MapChildren for $ty {
+                fn map_children(
+                    &self,
+                    _: &mut dyn Mapper,
+                    _: &mut MapCache,
+                    _: &mut Services,
+                ) -> Option<Self> {
+                    None
+                }
+
+                fn map_self(&self, mapper: &mut dyn Mapper) -> Option<Self> {
+                    mapper.$method(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_map_children_for_leaf!(
+    Curve, map_curve;
+    Surface, map_surface;
+    Vertex, map_vertex;
+);
+
+impl MapChildren for HalfEdge {
+    fn map_children(
+        &self,
+        mapper: &mut dyn Mapper,
+        cache: &mut MapCache,
+        services: &mut Services,
+    ) -> Option<Self> {
+        let curve = map_handle(self.curve(), mapper, cache, services);
+        let start_vertex =
+            map_handle(self.start_vertex(), mapper, cache, services);
+
+        let changed = curve.id() != self.curve().id()
+            || start_vertex.id() != self.start_vertex().id();
+
+        changed.then(|| {
+            HalfEdge::new(self.path(), self.boundary(), curve, start_vertex)
+        })
+    }
+
+    fn map_self(&self, mapper: &mut dyn Mapper) -> Option<Self> {
+        mapper.map_half_edge(self)
+    }
+}
+
+impl MapChildren for Cycle {
+    fn map_children(
+        &self,
+        mapper: &mut dyn Mapper,
+        cache: &mut MapCache,
+        services: &mut Services,
+    ) -> Option<Self> {
+        map_handles(self.half_edges(), mapper, cache, services).map(Cycle::new)
+    }
+
+    fn map_self(&self, mapper: &mut dyn Mapper) -> Option<Self> {
+        mapper.map_cycle(self)
+    }
+}
+
+impl MapChildren for Region {
+    fn map_children(
+        &self,
+        mapper: &mut dyn Mapper,
+        cache: &mut MapCache,
+        services: &mut Services,
+    ) -> Option<Self> {
+        let exterior = map_handle(self.exterior(), mapper, cache, services);
+        let interiors = map_handles(self.interiors(), mapper, cache, services);
+
+        if exterior.id() == self.exterior().id() && interiors.is_none() {
+            return None;
+        }
+
+        let interiors = interiors
+            .unwrap_or_else(|| self.interiors().iter().cloned().collect());
+
+        Some(Region::new(exterior, interiors, self.color()))
+    }
+
+    fn map_self(&self, mapper: &mut dyn Mapper) -> Option<Self> {
+        mapper.map_region(self)
+    }
+}
+
+impl MapChildren for Face {
+    fn map_children(
+        &self,
+        mapper: &mut dyn Mapper,
+        cache: &mut MapCache,
+        services: &mut Services,
+    ) -> Option<Self> {
+        let surface = map_handle(self.surface(), mapper, cache, services);
+        let region = map_handle(self.region(), mapper, cache, services);
+
+        let changed = surface.id() != self.surface().id()
+            || region.id() != self.region().id();
+
+        changed.then(|| Face::new(surface, region))
+    }
+
+    fn map_self(&self, mapper: &mut dyn Mapper) -> Option<Self> {
+        mapper.map_face(self)
+    }
+}
+
+impl MapChildren for Shell {
+    fn map_children(
+        &self,
+        mapper: &mut dyn Mapper,
+        cache: &mut MapCache,
+        services: &mut Services,
+    ) -> Option<Self> {
+        map_handles(self.faces(), mapper, cache, services).map(Shell::new)
+    }
+
+    fn map_self(&self, mapper: &mut dyn Mapper) -> Option<Self> {
+        mapper.map_shell(self)
+    }
+}
+
+impl MapChildren for Sketch {
+    fn map_children(
+        &self,
+        mapper: &mut dyn Mapper,
+        cache: &mut MapCache,
+        services: &mut Services,
+    ) -> Option<Self> {
+        map_handles(self.regions(), mapper, cache, services).map(Sketch::new)
+    }
+
+    fn map_self(&self, mapper: &mut dyn Mapper) -> Option<Self> {
+        mapper.map_sketch(self)
+    }
+}
+
+impl MapChildren for Solid {
+    fn map_children(
+        &self,
+        mapper: &mut dyn Mapper,
+        cache: &mut MapCache,
+        services: &mut Services,
+    ) -> Option<Self> {
+        map_handles(self.shells(), mapper, cache, services).map(Solid::new)
+    }
+
+    fn map_self(&self, mapper: &mut dyn Mapper) -> Option<Self> {
+        mapper.map_solid(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::{
+        objects::{BehindHandle, Curve, Object, Shell},
+        operations::{build::BuildShell, insert::Insert},
+        queries::{VisitObjects, Visitor},
+        services::Services,
+        storage::{Handle, ObjectId},
+    };
+
+    use super::MapObjects;
+
+    #[derive(Default)]
+    struct Curves(BTreeSet<ObjectId>);
+
+    impl Visitor for Curves {
+        fn visit_curve(
+            &mut self,
+            curve: &Handle<Curve>,
+            _: Option<&Object<BehindHandle>>,
+        ) {
+            self.0.insert(curve.id());
+        }
+    }
+
+    #[test]
+    fn map_curves_preserves_sharing() {
+        let mut services = Services::new();
+
+        let tetrahedron = Shell::tetrahedron(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services)
+        .shell;
+
+        let mut calls = 0;
+        let mapped = tetrahedron.map_objects(
+            |_: &Curve| {
+                calls += 1;
+                Curve::new()
+            },
+            &mut services,
+        );
+
+        let mut before = Curves::default();
+        tetrahedron.visit_objects(&mut before);
+        let mut after = Curves::default();
+        mapped.visit_objects(&mut after);
+
+        assert_eq!(calls, 6);
+        assert_eq!(before.0.len(), 6);
+        assert_eq!(after.0.len(), 6);
+        assert!(before.0.is_disjoint(&after.0));
+    }
+}
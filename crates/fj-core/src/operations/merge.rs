@@ -3,19 +3,92 @@
 //! See [`Merge`], which is currently the only trait in this module, for more
 //! information.
 
-use crate::objects::Solid;
+use crate::objects::{Sketch, Solid};
 
 use super::update::UpdateSolid;
 
-/// Merge two [`Solid`]s
+/// Merge two objects
 pub trait Merge {
-    /// Merge this solid with another
+    /// Merge this object with another
     #[must_use]
     fn merge(&self, other: &Self) -> Self;
 }
 
+impl Merge for Sketch {
+    /// Merge this sketch with another, combining their regions
+    ///
+    /// Sketches don't reference a surface. Both sketches are going to end up
+    /// on whatever surface the merged sketch is placed on later.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if both sketches contain the same region.
+    ///
+    /// # Implementation Note
+    ///
+    /// The regions are combined as they are. Overlapping regions are not
+    /// unified, as there is no support for 2D boolean operations yet.
+    fn merge(&self, other: &Self) -> Self {
+        Sketch::new(self.regions().iter().chain(other.regions()).cloned())
+    }
+}
+
 impl Merge for Solid {
     fn merge(&self, other: &Self) -> Self {
         self.add_shells(other.shells().iter().cloned())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::{Region, Sketch},
+        operations::{
+            build::{BuildRegion, BuildSketch},
+            insert::Insert,
+            update::UpdateSketch,
+        },
+        services::Services,
+    };
+
+    use super::Merge;
+
+    #[test]
+    fn merge_sketches() {
+        let mut services = Services::new();
+
+        let [a, b, c] = [0., 2., 4.].map(|x| {
+            Region::polygon(
+                [[x, 0.], [x + 1., 0.], [x + 1., 1.], [x, 1.]],
+                &mut services,
+            )
+            .insert(&mut services)
+        });
+
+        let sketch = Sketch::empty().add_region(a.clone());
+        let other = Sketch::empty().add_region(b.clone()).add_region(c.clone());
+
+        let merged = sketch.merge(&other);
+        let ids = merged
+            .regions()
+            .iter()
+            .map(|region| region.id())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [a.id(), b.id(), c.id()]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_sketches_with_shared_region() {
+        let mut services = Services::new();
+
+        let region = Region::polygon(
+            [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services);
+        let sketch = Sketch::empty().add_region(region);
+
+        let _ = sketch.merge(&sketch);
+    }
+}
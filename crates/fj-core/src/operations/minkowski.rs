@@ -0,0 +1,360 @@
+//! # Minkowski sum of solids
+//!
+//! See [`MinkowskiSum`].
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    algorithms::approx::{Approx, Tolerance},
+    objects::Solid,
+    operations::build::{BuildSolid, PolyhedronError},
+    services::Services,
+};
+
+/// Compute the Minkowski sum of two solids
+///
+/// The Minkowski sum of two shapes is the shape that results from adding every
+/// point of one to every point of the other. Sweeping a sphere around a box,
+/// for example, results in a box with rounded edges and corners. This is
+/// useful for computing clearances and offsets.
+///
+/// # Implementation Note
+///
+/// Only convex operands are supported. The sum is computed as the convex hull
+/// of the pairwise sums of the operands' points, which means that non-convex
+/// operands are treated as if they were their convex hull.
+///
+/// Curved operands are approximated according to the provided tolerance, and
+/// the result is always a polyhedron.
+pub trait MinkowskiSum {
+    /// Compute the Minkowski sum of this solid and another
+    fn minkowski_sum(
+        &self,
+        other: &Solid,
+        tolerance: impl Into<Tolerance>,
+        services: &mut Services,
+    ) -> Result<Solid, MinkowskiSumError>;
+}
+
+impl MinkowskiSum for Solid {
+    fn minkowski_sum(
+        &self,
+        other: &Solid,
+        tolerance: impl Into<Tolerance>,
+        services: &mut Services,
+    ) -> Result<Solid, MinkowskiSumError> {
+        let tolerance = tolerance.into();
+
+        let a = points_of(self, tolerance);
+        let b = points_of(other, tolerance);
+
+        let points = a
+            .iter()
+            .flat_map(|a| b.iter().map(move |b| *a + b.coords))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let epsilon = epsilon(&points);
+        let triangles = convex_hull(&points, epsilon)
+            .ok_or(MinkowskiSumError::Degenerate)?;
+        let faces = merge_coplanar_triangles(&points, &triangles, epsilon);
+
+        // Not all points end up as vertices of the polyhedron.
+        let used = faces.iter().flatten().copied().collect::<BTreeSet<_>>();
+        let indices = used
+            .iter()
+            .enumerate()
+            .map(|(new, &old)| (old, new))
+            .collect::<BTreeMap<_, _>>();
+
+        let solid = Solid::from_vertices_and_faces(
+            used.iter().map(|&index| points[index]),
+            faces
+                .iter()
+                .map(|face| face.iter().map(|index| indices[index])),
+            services,
+        )?;
+
+        Ok(solid)
+    }
+}
+
+/// Error computing a Minkowski sum
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum MinkowskiSumError {
+    /// The result has no volume
+    #[error("Minkowski sum has no volume")]
+    Degenerate,
+
+    /// The result could not be built as a polyhedron
+    #[error("Could not build result of Minkowski sum")]
+    Polyhedron(#[from] PolyhedronError),
+}
+
+fn points_of(solid: &Solid, tolerance: Tolerance) -> BTreeSet<Point<3>> {
+    solid
+        .approx(tolerance)
+        .iter()
+        .flat_map(|face| face.points())
+        .map(|point| point.global_form)
+        .collect()
+}
+
+/// Compute the distance within which points are considered to be coincident
+fn epsilon(points: &[Point<3>]) -> Scalar {
+    const RELATIVE_EPSILON: f64 = 1e-10;
+
+    let Some(first) = points.first() else {
+        return Scalar::ZERO;
+    };
+    let size = points
+        .iter()
+        .map(|point| point.distance_to(first))
+        .fold(Scalar::ZERO, Scalar::max);
+
+    size * RELATIVE_EPSILON
+}
+
+/// Compute the convex hull of a set of points
+///
+/// Returns the triangles of the hull, as indices into `points`, wound
+/// counter-clockwise when viewed from outside. Returns `None`, if the points
+/// don't span a volume.
+fn convex_hull(
+    points: &[Point<3>],
+    epsilon: Scalar,
+) -> Option<Vec<[usize; 3]>> {
+    let tetrahedron = initial_tetrahedron(points, epsilon)?;
+
+    let [a, b, c, d] = tetrahedron;
+    let mut faces = vec![[a, b, c], [a, c, d], [a, d, b], [b, d, c]];
+    if distance_to_face(points, [a, b, c], &points[d]) > Scalar::ZERO {
+        for face in &mut faces {
+            face.reverse();
+        }
+    }
+
+    for (i, point) in points.iter().enumerate() {
+        if tetrahedron.contains(&i) {
+            continue;
+        }
+
+        let (visible, hidden): (Vec<_>, Vec<_>) = faces
+            .into_iter()
+            .partition(|&face| distance_to_face(points, face, point) > epsilon);
+        faces = hidden;
+
+        let visible_edges = visible
+            .iter()
+            .flat_map(|&[a, b, c]| [[a, b], [b, c], [c, a]])
+            .collect::<BTreeSet<_>>();
+        let horizon = visible_edges
+            .iter()
+            .filter(|&&[a, b]| !visible_edges.contains(&[b, a]));
+
+        faces.extend(horizon.map(|&[a, b]| [a, b, i]));
+    }
+
+    Some(faces)
+}
+
+fn initial_tetrahedron(
+    points: &[Point<3>],
+    epsilon: Scalar,
+) -> Option<[usize; 4]> {
+    let a = 0;
+    let origin = *points.get(a)?;
+
+    let b = farthest(points, |point| point.distance_to(&origin))?;
+    let direction = (points[b] - origin).normalize();
+
+    let c = farthest(points, |point| {
+        (point - origin).cross(&direction).magnitude()
+    })?;
+    let normal = (points[b] - origin).cross(&(points[c] - origin));
+    if normal.magnitude() <= epsilon {
+        return None;
+    }
+    let normal = normal.normalize();
+
+    let d = farthest(points, |point| (point - origin).dot(&normal).abs())?;
+    if (points[d] - origin).dot(&normal).abs() <= epsilon {
+        return None;
+    }
+
+    Some([a, b, c, d])
+}
+
+fn farthest(
+    points: &[Point<3>],
+    distance: impl Fn(&Point<3>) -> Scalar,
+) -> Option<usize> {
+    points
+        .iter()
+        .enumerate()
+        .max_by_key(|&(_, point)| distance(point))
+        .map(|(index, _)| index)
+}
+
+fn distance_to_face(
+    points: &[Point<3>],
+    face: [usize; 3],
+    point: &Point<3>,
+) -> Scalar {
+    let [a, b, c] = face.map(|index| points[index]);
+    let normal = (b - a).cross(&(c - a)).normalize();
+
+    normal.dot(&(point - a))
+}
+
+/// Merge coplanar triangles of a convex hull into polygonal faces
+///
+/// Vertices that lie on a straight edge of the resulting faces are removed.
+fn merge_coplanar_triangles(
+    points: &[Point<3>],
+    triangles: &[[usize; 3]],
+    epsilon: Scalar,
+) -> Vec<Vec<usize>> {
+    let mut groups: Vec<(Vector<3>, Point<3>, Vec<[usize; 3]>)> = Vec::new();
+
+    for &triangle in triangles {
+        let [a, b, c] = triangle.map(|index| points[index]);
+        let normal = (b - a).cross(&(c - a)).normalize();
+
+        let group = groups.iter_mut().find(|(n, origin, _)| {
+            n.dot(&normal) > Scalar::ZERO
+                && [a, b, c]
+                    .iter()
+                    .all(|point| n.dot(&(point - *origin)).abs() <= epsilon)
+        });
+
+        match group {
+            Some((_, _, group)) => group.push(triangle),
+            None => groups.push((normal, a, vec![triangle])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(_, _, triangles)| {
+            let face = boundary_of(&triangles);
+            remove_collinear_vertices(points, face, epsilon)
+        })
+        .collect()
+}
+
+/// Find the boundary of a convex polygon that is made up of triangles
+fn boundary_of(triangles: &[[usize; 3]]) -> Vec<usize> {
+    let edges = triangles
+        .iter()
+        .flat_map(|&[a, b, c]| [[a, b], [b, c], [c, a]])
+        .collect::<BTreeSet<_>>();
+    let next = edges
+        .iter()
+        .filter(|&&[a, b]| !edges.contains(&[b, a]))
+        .map(|&[a, b]| (a, b))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut boundary = Vec::new();
+
+    let Some(&start) = next.keys().next() else {
+        return boundary;
+    };
+    let mut current = start;
+    loop {
+        boundary.push(current);
+        current = next[&current];
+
+        if current == start || boundary.len() > next.len() {
+            break;
+        }
+    }
+
+    boundary
+}
+
+fn remove_collinear_vertices(
+    points: &[Point<3>],
+    mut face: Vec<usize>,
+    epsilon: Scalar,
+) -> Vec<usize> {
+    let mut i = 0;
+
+    while i < face.len() && face.len() > 3 {
+        let prev = points[face[(i + face.len() - 1) % face.len()]];
+        let next = points[face[(i + 1) % face.len()]];
+        let point = points[face[i]];
+
+        let line = next - prev;
+        let distance =
+            line.cross(&(point - prev)).magnitude() / line.magnitude();
+
+        if distance <= epsilon {
+            face.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+
+    face
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::approx::Tolerance,
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    use super::MinkowskiSum;
+
+    #[test]
+    fn sum_of_boxes() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let a = Solid::box_from_dims([1., 1., 1.], &mut services);
+        let b = Solid::box_from_dims([2., 2., 2.], &mut services);
+
+        let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
+        let sum = a
+            .minkowski_sum(&b, tolerance, &mut services)?
+            .insert(&mut services);
+
+        let shell = sum.shells().only();
+        assert_eq!(shell.faces().len(), 6);
+
+        let vertices = shell
+            .faces()
+            .iter()
+            .flat_map(|face| {
+                face.region()
+                    .exterior()
+                    .half_edges()
+                    .iter()
+                    .map(|half_edge| {
+                        face.surface().geometry().point_from_surface_coords(
+                            half_edge.start_position(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vertices.len(), 24);
+        for vertex in vertices {
+            for coord in [vertex.x, vertex.y] {
+                assert!((coord.abs() - 1.5).abs() < Scalar::from(1e-9));
+            }
+        }
+
+        services.drop_and_validate()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+//! # Operations to mirror objects
+//!
+//! See [`Mirror`].
+
+use fj_math::Transform;
+
+use crate::{
+    algorithms::transform::TransformObject,
+    objects::{Datum, Face, Shell, Solid},
+    services::Services,
+};
+
+use super::reverse::Reverse;
+
+/// Mirror an object across a datum plane
+///
+/// A reflection turns the affected objects inside out. This operation fixes
+/// that, by reversing the mirrored objects, so they end up with the same
+/// orientation as the original ones.
+pub trait Mirror {
+    /// Mirror the object across the provided datum plane
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `plane` is not a [`Datum::Plane`].
+    #[must_use]
+    fn mirror(&self, plane: &Datum, services: &mut Services) -> Self;
+}
+
+macro_rules! impl_mirror {
+    ($($ty:ty;)*) => {
+        $(
+            impl Mirror for $ty {
+                fn mirror(
+                    &self,
+                    plane: &Datum,
+                    services: &mut Services,
+                ) -> Self {
+                    let plane =
+                        plane.as_plane().expect("Expected datum to be a plane");
+
+                    self.clone()
+                        .transform(&Transform::reflection(&plane), services)
+                        .reverse(services)
+                }
+            }
+        )*
+    };
+}
+
+impl_mirror!(
+    Face;
+    Shell;
+    Solid;
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::{Datum, Shell},
+        operations::{build::BuildShell, insert::Insert},
+        services::Services,
+    };
+
+    use super::Mirror;
+
+    #[test]
+    fn mirror_tetrahedron() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let tetrahedron = Shell::tetrahedron(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services)
+        .shell;
+        let plane = Datum::plane([2., 0., 0.], [0., 1., 0.], [0., 0., 1.]);
+
+        let _ = tetrahedron
+            .mirror(&plane, &mut services)
+            .insert(&mut services);
+
+        services.drop_and_validate()?;
+
+        Ok(())
+    }
+}
@@ -38,13 +38,24 @@
 //! assume that the code in question is outdated. Feel free to open an issue or
 //! send a pull request!
 
+pub mod boolean;
 pub mod build;
+pub mod cap;
 pub mod holes;
 pub mod insert;
 pub mod join;
+pub mod map;
 pub mod merge;
+pub mod minkowski;
+pub mod mirror;
+pub mod orientation;
+pub mod primitives;
+pub mod reparameterize;
 pub mod replace;
 pub mod reverse;
+pub mod simplify;
 pub mod split;
 pub mod sweep;
+pub mod unfold;
 pub mod update;
+pub mod wrap;
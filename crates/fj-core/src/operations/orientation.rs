@@ -0,0 +1,406 @@
+//! # Audit and repair the orientation of shells
+//!
+//! See [`AuditOrientation`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt,
+};
+
+use fj_math::{Point, Scalar};
+
+use crate::{
+    objects::{Face, HalfEdge, Shell},
+    operations::{insert::Insert, reverse::Reverse},
+    queries::BoundingVerticesOfHalfEdge,
+    services::Services,
+    storage::{Handle, ObjectId},
+    validate::{ShellValidationError, ValidationError},
+};
+
+/// Audit the orientation of a [`Shell`]
+///
+/// In a consistently oriented shell, each edge is shared by exactly two
+/// half-edges, which run in opposite directions. The faces of a closed shell
+/// should additionally be wound such, that they face outward.
+///
+/// Shells that are built by the operations in this crate are oriented that way.
+/// Shells that are built by hand can easily end up with some faces reversed,
+/// and this trait helps to find and fix those.
+pub trait AuditOrientation {
+    /// Audit the shell for consistent half-edge pairing and face winding
+    fn audit_orientation(&self) -> OrientationAudit;
+
+    /// Validate the orientation of the shell
+    ///
+    /// This check is not part of the regular validation of shells, but this
+    /// method makes it available in the same form. If the audit finds any
+    /// problems, a [`ShellValidationError::InconsistentOrientation`] is added
+    /// to `errors`.
+    fn validate_orientation(&self, errors: &mut Vec<ValidationError>) {
+        let audit = self.audit_orientation();
+
+        if !audit.is_consistent() {
+            errors.push(
+                ShellValidationError::InconsistentOrientation(Box::new(audit))
+                    .into(),
+            );
+        }
+    }
+
+    /// Reverse all faces that the audit found to be wound the wrong way
+    ///
+    /// Problems that can't be fixed by reversing faces (unpaired half-edges,
+    /// non-manifold edges, non-orientable shells) are left as they are.
+    #[must_use]
+    fn fix_orientation(&self, services: &mut Services) -> Self;
+}
+
+impl AuditOrientation for Shell {
+    fn audit_orientation(&self) -> OrientationAudit {
+        let faces = self.faces().iter().collect::<Vec<_>>();
+
+        // Group half-edges by the edge they are on, regardless of direction.
+        let mut edges = BTreeMap::<_, Vec<_>>::new();
+        for (i, face) in faces.iter().enumerate() {
+            for cycle in face.region().all_cycles() {
+                for half_edge in cycle.half_edges() {
+                    let vertices = cycle
+                        .bounding_vertices_of_half_edge(half_edge)
+                        .expect(
+                            "`half_edge` came from `cycle`, must exist there",
+                        )
+                        .normalize();
+                    let boundary = half_edge.boundary();
+
+                    let key = (
+                        half_edge.curve().id(),
+                        boundary.normalize(),
+                        vertices,
+                    );
+                    edges.entry(key).or_default().push(OrientedHalfEdge {
+                        half_edge,
+                        face: i,
+                        is_forward: boundary.is_normalized(),
+                    });
+                }
+            }
+        }
+
+        let mut audit = OrientationAudit {
+            unpaired_half_edges: Vec::new(),
+            non_manifold_edges: Vec::new(),
+            same_direction_pairs: Vec::new(),
+            faces_to_reverse: Vec::new(),
+            is_orientable: true,
+        };
+
+        // For each face, the neighbors it shares an edge with, and whether
+        // its orientation must differ from that neighbor's.
+        let mut neighbors = vec![Vec::new(); faces.len()];
+        // Faces that are part of open or non-manifold edges. Their components
+        // aren't closed, so it's unclear which side is outside.
+        let mut faces_on_open_edges = BTreeSet::new();
+
+        for half_edges in edges.into_values() {
+            match half_edges.as_slice() {
+                [a] => {
+                    audit.unpaired_half_edges.push(a.half_edge.clone());
+                    faces_on_open_edges.insert(a.face);
+                }
+                [a, b] => {
+                    let must_differ = a.is_forward == b.is_forward;
+                    if must_differ {
+                        audit
+                            .same_direction_pairs
+                            .push([a.half_edge.clone(), b.half_edge.clone()]);
+                    }
+
+                    if a.face == b.face {
+                        // A face can't be oriented differently from itself.
+                        if must_differ {
+                            audit.is_orientable = false;
+                        }
+                        continue;
+                    }
+
+                    neighbors[a.face].push((b.face, must_differ));
+                    neighbors[b.face].push((a.face, must_differ));
+                }
+                half_edges => {
+                    audit.non_manifold_edges.push(
+                        half_edges
+                            .iter()
+                            .map(|h| h.half_edge.clone())
+                            .collect(),
+                    );
+                    faces_on_open_edges
+                        .extend(half_edges.iter().map(|h| h.face));
+                }
+            }
+        }
+
+        // Determine which faces have to be reversed, one connected component
+        // of faces at a time.
+        let mut reverse = vec![None; faces.len()];
+        for start in 0..faces.len() {
+            if reverse[start].is_some() {
+                continue;
+            }
+
+            let mut component = Vec::new();
+            let mut queue = VecDeque::from([(start, false)]);
+
+            while let Some((i, reverse_i)) = queue.pop_front() {
+                match reverse[i] {
+                    Some(existing) => {
+                        if existing != reverse_i {
+                            audit.is_orientable = false;
+                        }
+                        continue;
+                    }
+                    None => {
+                        reverse[i] = Some(reverse_i);
+                        component.push(i);
+                    }
+                }
+
+                for &(j, must_differ) in &neighbors[i] {
+                    queue.push_back((j, reverse_i != must_differ));
+                }
+            }
+
+            let is_closed =
+                component.iter().all(|i| !faces_on_open_edges.contains(i));
+            let volume = component
+                .iter()
+                .map(|&i| {
+                    let volume = signed_volume(faces[i]);
+                    if reverse[i] == Some(true) {
+                        -volume
+                    } else {
+                        volume
+                    }
+                })
+                .fold(Scalar::ZERO, |sum, volume| sum + volume);
+
+            // With the faces oriented consistently, the whole component is
+            // either oriented correctly, or turned inside out. For closed
+            // components, the sign of the enclosed volume tells us which one
+            // it is. Otherwise, change as little as possible.
+            let num_reversed = component
+                .iter()
+                .filter(|&&i| reverse[i] == Some(true))
+                .count();
+            let invert = if is_closed && volume != Scalar::ZERO {
+                volume < Scalar::ZERO
+            } else {
+                num_reversed * 2 > component.len()
+            };
+
+            if invert {
+                for &i in &component {
+                    reverse[i] = reverse[i].map(|reverse| !reverse);
+                }
+            }
+        }
+
+        audit.faces_to_reverse = faces
+            .iter()
+            .zip(reverse)
+            .filter(|(_, reverse)| *reverse == Some(true))
+            .map(|(face, _)| (*face).clone())
+            .collect();
+
+        audit
+    }
+
+    fn fix_orientation(&self, services: &mut Services) -> Self {
+        let faces_to_reverse = self
+            .audit_orientation()
+            .faces_to_reverse
+            .iter()
+            .map(|face| face.id())
+            .collect::<BTreeSet<ObjectId>>();
+
+        let faces = self.faces().iter().map(|face| {
+            if faces_to_reverse.contains(&face.id()) {
+                face.reverse(services).insert(services)
+            } else {
+                face.clone()
+            }
+        });
+
+        Shell::new(faces)
+    }
+}
+
+/// The result of auditing the orientation of a [`Shell`]
+///
+/// Returned by [`AuditOrientation::audit_orientation`].
+#[derive(Clone, Debug)]
+pub struct OrientationAudit {
+    /// Half-edges that don't share their edge with any other half-edge
+    pub unpaired_half_edges: Vec<Handle<HalfEdge>>,
+
+    /// Groups of more than two half-edges that share the same edge
+    pub non_manifold_edges: Vec<Vec<Handle<HalfEdge>>>,
+
+    /// Pairs of half-edges that share an edge, but run in the same direction
+    pub same_direction_pairs: Vec<[Handle<HalfEdge>; 2]>,
+
+    /// The faces that need to be reversed, to orient the shell consistently
+    ///
+    /// Closed shells are also oriented such, that their faces point outward.
+    pub faces_to_reverse: Vec<Handle<Face>>,
+
+    /// Indicate whether the faces of the shell can be oriented consistently
+    ///
+    /// This is not the case for shells that form a non-orientable surface,
+    /// like a Möbius strip.
+    pub is_orientable: bool,
+}
+
+impl OrientationAudit {
+    /// Indicate whether the audit found no problems
+    pub fn is_consistent(&self) -> bool {
+        self.unpaired_half_edges.is_empty()
+            && self.non_manifold_edges.is_empty()
+            && self.same_direction_pairs.is_empty()
+            && self.faces_to_reverse.is_empty()
+            && self.is_orientable
+    }
+}
+
+impl fmt::Display for OrientationAudit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_consistent() {
+            return write!(f, "Shell is oriented consistently");
+        }
+
+        if !self.is_orientable {
+            writeln!(f, "Shell can't be oriented consistently")?;
+        }
+        if !self.unpaired_half_edges.is_empty() {
+            writeln!(f, "Unpaired half-edges:")?;
+            for half_edge in &self.unpaired_half_edges {
+                writeln!(f, "    {:?}", half_edge.id())?;
+            }
+        }
+        if !self.non_manifold_edges.is_empty() {
+            writeln!(f, "Edges shared by more than two half-edges:")?;
+            for half_edges in &self.non_manifold_edges {
+                let ids = half_edges
+                    .iter()
+                    .map(|half_edge| format!("{:?}", half_edge.id()))
+                    .collect::<Vec<_>>();
+                writeln!(f, "    {}", ids.join(", "))?;
+            }
+        }
+        if !self.same_direction_pairs.is_empty() {
+            writeln!(f, "Half-edges that run in the same direction:")?;
+            for [a, b] in &self.same_direction_pairs {
+                writeln!(f, "    {:?}, {:?}", a.id(), b.id())?;
+            }
+        }
+        if !self.faces_to_reverse.is_empty() {
+            writeln!(f, "Faces that need to be reversed:")?;
+            for face in &self.faces_to_reverse {
+                writeln!(f, "    {:?}", face.id())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct OrientedHalfEdge<'r> {
+    half_edge: &'r Handle<HalfEdge>,
+    face: usize,
+    is_forward: bool,
+}
+
+/// Compute the contribution of a face to the volume that its shell encloses
+///
+/// Only the vertices of the face's cycles are taken into account, so the result
+/// is only exact for faces that are bounded by line segments. That is good
+/// enough to tell whether a closed shell is turned inside out.
+fn signed_volume(face: &Face) -> Scalar {
+    let surface = face.surface().geometry();
+
+    face.region()
+        .all_cycles()
+        .map(|cycle| {
+            let points = cycle
+                .half_edges()
+                .iter()
+                .map(|half_edge| {
+                    surface
+                        .point_from_surface_coords(half_edge.start_position())
+                })
+                .collect::<Vec<Point<3>>>();
+
+            let Some((first, rest)) = points.split_first() else {
+                return Scalar::ZERO;
+            };
+
+            rest.windows(2)
+                .map(|pair| {
+                    let [b, c] = [pair[0], pair[1]];
+                    first.coords.dot(&b.coords.cross(&c.coords)) / 6.
+                })
+                .fold(Scalar::ZERO, |sum, volume| sum + volume)
+        })
+        .fold(Scalar::ZERO, |sum, volume| sum + volume)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::Shell,
+        operations::{
+            build::BuildShell, insert::Insert, reverse::Reverse,
+            update::UpdateShell,
+        },
+        services::Services,
+    };
+
+    use super::AuditOrientation;
+
+    #[test]
+    fn audit_and_fix_tetrahedron() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let tetrahedron = Shell::tetrahedron(
+            [[0., 0., 0.], [0., 1., 0.], [1., 0., 0.], [0., 0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services)
+        .shell;
+
+        let audit = tetrahedron.audit_orientation();
+        assert!(audit.is_consistent(), "{audit}");
+
+        let inside_out = tetrahedron.reverse(&mut services);
+        let audit = inside_out.audit_orientation();
+        assert_eq!(audit.faces_to_reverse.len(), 4);
+        assert!(audit.same_direction_pairs.is_empty());
+
+        let face = tetrahedron.faces().first();
+        let one_reversed = tetrahedron.replace_face(face, |face| {
+            [face.reverse(&mut services).insert(&mut services)]
+        });
+        let audit = one_reversed.audit_orientation();
+        assert_eq!(audit.faces_to_reverse.len(), 1);
+        assert_eq!(audit.same_direction_pairs.len(), 3);
+
+        let fixed = one_reversed.fix_orientation(&mut services);
+        assert!(fixed.audit_orientation().is_consistent());
+
+        let fixed = inside_out.fix_orientation(&mut services);
+        assert!(fixed.audit_orientation().is_consistent());
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+//! Build primitive solids
+//!
+//! See [`BuildPrimitive`].
+
+use std::f64::consts::{PI, TAU};
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    objects::{Region, Shell, Sketch, Solid},
+    services::Services,
+};
+
+use super::{
+    build::{BuildRegion, BuildShell, BuildSketch, BuildSolid},
+    insert::Insert,
+    sweep::SweepSketch,
+    update::{UpdateSketch, UpdateSolid},
+};
+
+/// Build primitive solids with a single call
+///
+/// Building even simple solids from sketches requires some boilerplate. The
+/// methods of this trait take care of that, returning solids whose shells have
+/// been inserted (and thereby validated), with all edges and vertices properly
+/// shared between neighboring faces.
+///
+/// All primitives are built relative to the origin. Their bottom (or, for
+/// primitives that are symmetric in z, their center) is in the xy-plane. Use
+/// the [transform operations] to move them elsewhere.
+///
+/// # Implementation Note
+///
+/// The kernel currently only supports surfaces that are swept along a straight
+/// line. This means cones, spheres, and tori can't be represented exactly, and
+/// are approximated by planar faces instead. The number of segments that is
+/// used for this approximation is configurable.
+///
+/// [transform operations]: crate::algorithms::transform
+pub trait BuildPrimitive {
+    /// Build a box from its dimensions
+    ///
+    /// The box is centered on the z-axis, and extends from the xy-plane in the
+    /// positive z direction.
+    fn box_from_dims(
+        size: impl Into<Vector<3>>,
+        services: &mut Services,
+    ) -> Solid {
+        let [x, y, z] = size.into().components;
+        let [x, y] = [x, y].map(|coord| coord / 2.);
+
+        let region =
+            Region::polygon([[-x, -y], [x, -y], [x, y], [-x, y]], services);
+
+        extrude(region, z, services)
+    }
+
+    /// Build a cylinder from its radius and height
+    ///
+    /// The cylinder's axis is the z-axis, and it extends from the xy-plane in
+    /// the positive z direction.
+    fn cylinder(
+        radius: impl Into<Scalar>,
+        height: impl Into<Scalar>,
+        services: &mut Services,
+    ) -> Solid {
+        let region = Region::circle(Point::origin(), radius, services);
+        extrude(region, height, services)
+    }
+
+    /// Build a cone from the radius of its base and its height
+    ///
+    /// The cone's axis is the z-axis. Its base is in the xy-plane, its tip on
+    /// the positive z-axis.
+    ///
+    /// See the implementation note on [`BuildPrimitive`], regarding
+    /// `segments`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `segments` is less than 3.
+    fn cone(
+        radius: impl Into<Scalar>,
+        height: impl Into<Scalar>,
+        segments: usize,
+        services: &mut Services,
+    ) -> Solid {
+        assert!(segments >= 3, "Cone requires at least 3 segments");
+
+        let radius = radius.into();
+        let height = height.into();
+
+        let center = 0;
+        let tip = 1;
+        let base = |i: usize| 2 + i % segments;
+
+        let mut vertices = vec![
+            Point::origin(),
+            Point::from([Scalar::ZERO, Scalar::ZERO, height]),
+        ];
+        vertices.extend((0..segments).map(|i| {
+            let (sin, cos) =
+                Scalar::from(TAU * i as f64 / segments as f64).sin_cos();
+            Point::from([cos * radius, sin * radius, Scalar::ZERO])
+        }));
+
+        let indices = (0..segments).flat_map(|i| {
+            [[center, base(i + 1), base(i)], [base(i), base(i + 1), tip]]
+        });
+
+        polyhedron(vertices, indices, services)
+    }
+
+    /// Build a sphere from its radius
+    ///
+    /// The sphere's center is the origin.
+    ///
+    /// See the implementation note on [`BuildPrimitive`], regarding
+    /// `segments`. The sphere is divided into `segments` segments around the
+    /// z-axis, and half as many from pole to pole.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `segments` is less than 4.
+    fn sphere(
+        radius: impl Into<Scalar>,
+        segments: usize,
+        services: &mut Services,
+    ) -> Solid {
+        assert!(segments >= 4, "Sphere requires at least 4 segments");
+
+        let radius = radius.into();
+
+        let num_rings = segments / 2;
+
+        let north = 0;
+        let south = 1;
+        let ring = |i: usize, j: usize| {
+            // Rings are numbered from 1, as ring 0 is the north pole.
+            2 + (i - 1) * segments + j % segments
+        };
+
+        let mut vertices = vec![
+            Point::from([Scalar::ZERO, Scalar::ZERO, radius]),
+            Point::from([Scalar::ZERO, Scalar::ZERO, -radius]),
+        ];
+        for i in 1..num_rings {
+            let (sin_theta, cos_theta) =
+                Scalar::from(PI * i as f64 / num_rings as f64).sin_cos();
+
+            vertices.extend((0..segments).map(|j| {
+                let (sin_phi, cos_phi) =
+                    Scalar::from(TAU * j as f64 / segments as f64).sin_cos();
+
+                Point::from([
+                    sin_theta * cos_phi * radius,
+                    sin_theta * sin_phi * radius,
+                    cos_theta * radius,
+                ])
+            }));
+        }
+
+        let last = num_rings - 1;
+        let mut indices = Vec::new();
+        for j in 0..segments {
+            indices.push([north, ring(1, j), ring(1, j + 1)]);
+            indices.push([ring(last, j), south, ring(last, j + 1)]);
+
+            for i in 1..last {
+                let [a, b, c, d] = [
+                    ring(i, j),
+                    ring(i + 1, j),
+                    ring(i + 1, j + 1),
+                    ring(i, j + 1),
+                ];
+                indices.extend([[a, b, c], [a, c, d]]);
+            }
+        }
+
+        polyhedron(vertices, indices, services)
+    }
+
+    /// Build a torus from its major and minor radii
+    ///
+    /// The torus' axis is the z-axis, and its center is the origin.
+    ///
+    /// See the implementation note on [`BuildPrimitive`], regarding
+    /// `segments`. The torus is divided into `segments` segments around both
+    /// its axis and its tube.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `segments` is less than 3, or if `minor_radius` is not
+    /// smaller than `major_radius`.
+    fn torus(
+        major_radius: impl Into<Scalar>,
+        minor_radius: impl Into<Scalar>,
+        segments: usize,
+        services: &mut Services,
+    ) -> Solid {
+        assert!(segments >= 3, "Torus requires at least 3 segments");
+
+        let major_radius = major_radius.into();
+        let minor_radius = minor_radius.into();
+        assert!(
+            minor_radius < major_radius,
+            "Minor radius of torus must be smaller than its major radius"
+        );
+
+        let index = |i: usize, j: usize| i % segments * segments + j % segments;
+        let angle =
+            |i: usize| Scalar::from(TAU * i as f64 / segments as f64).sin_cos();
+
+        let vertices = (0..segments).flat_map(|i| {
+            let (sin_phi, cos_phi) = angle(i);
+
+            (0..segments).map(move |j| {
+                let (sin_theta, cos_theta) = angle(j);
+                let distance = major_radius + cos_theta * minor_radius;
+
+                Point::from([
+                    cos_phi * distance,
+                    sin_phi * distance,
+                    sin_theta * minor_radius,
+                ])
+            })
+        });
+
+        let indices = (0..segments).flat_map(|i| {
+            (0..segments).flat_map(move |j| {
+                let [a, b, c, d] = [
+                    index(i, j),
+                    index(i + 1, j),
+                    index(i + 1, j + 1),
+                    index(i, j + 1),
+                ];
+                [[a, b, c], [a, c, d]]
+            })
+        });
+
+        polyhedron(vertices.collect::<Vec<_>>(), indices, services)
+    }
+}
+
+impl BuildPrimitive for Solid {}
+
+fn extrude(
+    region: Region,
+    height: impl Into<Scalar>,
+    services: &mut Services,
+) -> Solid {
+    let bottom_surface = services.objects.surfaces.xy_plane();
+    let path = Vector::from([Scalar::ZERO, Scalar::ZERO, height.into()]);
+
+    Sketch::empty()
+        .add_region(region.insert(services))
+        .sweep_sketch(bottom_surface, path, services)
+}
+
+fn polyhedron(
+    vertices: Vec<Point<3>>,
+    indices: impl IntoIterator<Item = [usize; 3]>,
+    services: &mut Services,
+) -> Solid {
+    let shell = Shell::from_vertices_and_indices(vertices, indices, services)
+        .insert(services);
+    Solid::empty().add_shells([shell])
+}
@@ -0,0 +1,372 @@
+//! # Change the parameterization of a face's surface
+//!
+//! See [`Reparameterize`].
+
+use fj_math::{Circle, Involute, Line, Point, Scalar, Spiral, Vector};
+
+use crate::{
+    geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{Cycle, Face, HalfEdge, Region, Surface},
+    services::Services,
+    storage::Handle,
+};
+
+use super::insert::Insert;
+
+/// Change the parameterization of a face's surface
+///
+/// The geometry of the face doesn't change. Only the coordinate system of its
+/// surface does, and the paths of the face's half-edges are updated to match.
+/// This is needed, when faces whose parameterizations don't line up need to be
+/// sewn together.
+///
+/// Curves, vertices, and the boundaries of half-edges on their curves are not
+/// affected, as none of them depend on the surface's coordinate system.
+pub trait Reparameterize: Sized {
+    /// Apply the provided transformation to the coordinate system of the
+    /// surface
+    ///
+    /// Returns an error, if the transformation is not possible for the surface
+    /// or one of the half-edges. See [`ReparameterizeError`] for details.
+    fn reparameterize(
+        &self,
+        transform: UvTransform,
+        services: &mut Services,
+    ) -> Result<Self, ReparameterizeError>;
+}
+
+impl Reparameterize for Face {
+    fn reparameterize(
+        &self,
+        transform: UvTransform,
+        services: &mut Services,
+    ) -> Result<Self, ReparameterizeError> {
+        if let UvTransform::ScaleU(factor) | UvTransform::ScaleV(factor) =
+            transform
+        {
+            if factor == Scalar::ZERO {
+                return Err(ReparameterizeError::ZeroScale);
+            }
+        }
+
+        let geometry =
+            transform.transform_surface(self.surface().geometry())?;
+        let surface = Surface::new(geometry).insert(services);
+
+        let region = self.region();
+        let exterior = transform_cycle(region.exterior(), transform, services)?;
+        let interiors = region
+            .interiors()
+            .iter()
+            .map(|cycle| transform_cycle(cycle, transform, services))
+            .collect::<Result<Vec<_>, _>>()?;
+        let region =
+            Region::new(exterior, interiors, region.color()).insert(services);
+
+        Ok(Face::new(surface, region))
+    }
+}
+
+/// A transformation of a surface's coordinate system
+///
+/// All transformations are linear, meaning the origin of the coordinate system
+/// stays where it is.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum UvTransform {
+    /// Swap the u- and v-axes
+    ///
+    /// Only possible, if the u-axis of the surface is a line.
+    SwapUv,
+
+    /// Reverse the direction of the u-axis
+    ///
+    /// Only possible, if the u-axis of the surface is a line, circle, or
+    /// ellipse.
+    ReverseU,
+
+    /// Reverse the direction of the v-axis
+    ReverseV,
+
+    /// Multiply all u-coordinates by the provided factor
+    ///
+    /// Only possible, if the u-axis of the surface is a line, and if no
+    /// half-edge is a circle.
+    ScaleU(Scalar),
+
+    /// Multiply all v-coordinates by the provided factor
+    ///
+    /// Only possible, if no half-edge is a circle.
+    ScaleV(Scalar),
+}
+
+impl UvTransform {
+    /// Transform a point from the original to the new coordinate system
+    pub fn transform_point(&self, point: impl Into<Point<2>>) -> Point<2> {
+        Point {
+            coords: self.transform_vector(point.into().coords),
+        }
+    }
+
+    /// Transform a vector from the original to the new coordinate system
+    pub fn transform_vector(&self, vector: impl Into<Vector<2>>) -> Vector<2> {
+        let vector = vector.into();
+
+        match *self {
+            Self::SwapUv => Vector::from([vector.v, vector.u]),
+            Self::ReverseU => Vector::from([-vector.u, vector.v]),
+            Self::ReverseV => Vector::from([vector.u, -vector.v]),
+            Self::ScaleU(factor) => Vector::from([vector.u * factor, vector.v]),
+            Self::ScaleV(factor) => Vector::from([vector.u, vector.v * factor]),
+        }
+    }
+
+    /// Transform a path from the original to the new coordinate system
+    ///
+    /// The path coordinates of all points on the path stay the same.
+    pub fn transform_path(
+        &self,
+        path: SurfacePath,
+    ) -> Result<SurfacePath, ReparameterizeError> {
+        let path = match path {
+            SurfacePath::Circle(circle) => {
+                let [a, b] =
+                    [circle.a(), circle.b()].map(|v| self.transform_vector(v));
+                if a.magnitude() != b.magnitude() {
+                    return Err(ReparameterizeError::NonUniformScaleOfCircle);
+                }
+
+                SurfacePath::Circle(Circle::new(
+                    self.transform_point(circle.center()),
+                    a,
+                    b,
+                ))
+            }
+            SurfacePath::Line(line) => {
+                SurfacePath::Line(Line::from_origin_and_direction(
+                    self.transform_point(line.origin()),
+                    self.transform_vector(line.direction()),
+                ))
+            }
+            SurfacePath::Spiral(spiral) => SurfacePath::Spiral(Spiral::new(
+                self.transform_point(spiral.center()),
+                self.transform_vector(spiral.a()),
+                self.transform_vector(spiral.b()),
+                spiral.start(),
+                spiral.growth(),
+            )),
+            SurfacePath::Involute(involute) => {
+                SurfacePath::Involute(Involute::new(
+                    self.transform_point(involute.center()),
+                    self.transform_vector(involute.a()),
+                    self.transform_vector(involute.b()),
+                ))
+            }
+        };
+
+        Ok(path)
+    }
+
+    /// Build the surface geometry with the new coordinate system
+    ///
+    /// The resulting surface describes the same geometry as the original one.
+    pub fn transform_surface(
+        &self,
+        surface: SurfaceGeometry,
+    ) -> Result<SurfaceGeometry, ReparameterizeError> {
+        let SurfaceGeometry { u, v } = surface;
+
+        let surface = match (*self, u) {
+            (Self::SwapUv, GlobalPath::Line(line)) => SurfaceGeometry {
+                u: GlobalPath::Line(Line::from_origin_and_direction(
+                    line.origin(),
+                    v,
+                )),
+                v: line.direction(),
+            },
+            (Self::ReverseU, GlobalPath::Line(line)) => SurfaceGeometry {
+                u: GlobalPath::Line(Line::from_origin_and_direction(
+                    line.origin(),
+                    -line.direction(),
+                )),
+                v,
+            },
+            (Self::ReverseU, GlobalPath::Circle(circle)) => SurfaceGeometry {
+                u: GlobalPath::Circle(circle.reverse()),
+                v,
+            },
+            (Self::ReverseU, GlobalPath::Ellipse(ellipse)) => SurfaceGeometry {
+                u: GlobalPath::Ellipse(ellipse.reverse()),
+                v,
+            },
+            (Self::ReverseV, u) => SurfaceGeometry { u, v: -v },
+            (Self::ScaleU(factor), GlobalPath::Line(line)) => SurfaceGeometry {
+                u: GlobalPath::Line(Line::from_origin_and_direction(
+                    line.origin(),
+                    line.direction() / factor,
+                )),
+                v,
+            },
+            (Self::ScaleV(factor), u) => SurfaceGeometry { u, v: v / factor },
+            _ => return Err(ReparameterizeError::UnsupportedSurface(surface)),
+        };
+
+        Ok(surface)
+    }
+}
+
+/// Error reparameterizing a face
+///
+/// Returned by [`Reparameterize::reparameterize`].
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum ReparameterizeError {
+    /// The transformation is not supported for the u-axis of the surface
+    #[error("Transformation is not supported for surface: {0:#?}")]
+    UnsupportedSurface(SurfaceGeometry),
+
+    /// A circular half-edge would no longer be circular
+    #[error(
+        "Scaling one axis would turn a circular half-edge into an ellipse"
+    )]
+    NonUniformScaleOfCircle,
+
+    /// The scale factor is zero
+    #[error("Can't scale coordinates by zero")]
+    ZeroScale,
+}
+
+fn transform_cycle(
+    cycle: &Handle<Cycle>,
+    transform: UvTransform,
+    services: &mut Services,
+) -> Result<Handle<Cycle>, ReparameterizeError> {
+    let half_edges = cycle
+        .half_edges()
+        .iter()
+        .map(|half_edge| {
+            let half_edge = HalfEdge::new(
+                transform.transform_path(half_edge.path())?,
+                half_edge.boundary(),
+                half_edge.curve().clone(),
+                half_edge.start_vertex().clone(),
+            );
+            Ok(half_edge.insert(services))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Cycle::new(half_edges).insert(services))
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Scalar, Vector};
+
+    use crate::{
+        geometry::GlobalPath,
+        objects::{Face, Handedness, Solid},
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        queries::oriented_normal,
+        services::Services,
+    };
+
+    use super::{Reparameterize, ReparameterizeError, UvTransform};
+
+    #[test]
+    fn reparameterize_faces_of_box() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = Solid::box_from_dims([1., 2., 3.], &mut services)
+            .insert(&mut services);
+        let shell = solid.shells().first();
+
+        for face in shell.faces() {
+            for transform in [
+                UvTransform::SwapUv,
+                UvTransform::ReverseU,
+                UvTransform::ReverseV,
+                UvTransform::ScaleU(Scalar::from(2.)),
+                UvTransform::ScaleV(Scalar::from(0.5)),
+            ] {
+                let reparameterized = face
+                    .reparameterize(transform, &mut services)?
+                    .insert(&mut services);
+
+                assert_eq!(
+                    global_points(face),
+                    global_points(&reparameterized)
+                );
+                assert!(
+                    front_normal(&reparameterized).dot(&oriented_normal(face))
+                        > Scalar::from(0.99)
+                );
+            }
+        }
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn reparameterize_side_of_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid =
+            Solid::cylinder(1., 2., &mut services).insert(&mut services);
+        let shell = solid.shells().first();
+
+        for face in shell.faces() {
+            let reparameterized = face
+                .reparameterize(UvTransform::ReverseU, &mut services)?
+                .insert(&mut services);
+            assert_eq!(global_points(face), global_points(&reparameterized));
+        }
+
+        // The u-axis of the side is a circle, which can't be scaled. The u-axes
+        // of the top and bottom are lines, but they are bounded by circles,
+        // which can't be scaled non-uniformly either.
+        for face in shell.faces() {
+            let result = face.reparameterize(
+                UvTransform::ScaleU(Scalar::from(2.)),
+                &mut services,
+            );
+            assert!(matches!(
+                result,
+                Err(ReparameterizeError::UnsupportedSurface(_)
+                    | ReparameterizeError::NonUniformScaleOfCircle)
+            ));
+        }
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    fn front_normal(face: &Face) -> Vector<3> {
+        let surface = face.surface().geometry();
+        let GlobalPath::Line(u) = surface.u else {
+            panic!("Expected planar face");
+        };
+
+        let normal = u.direction().cross(&surface.v).normalize();
+        match face.coord_handedness() {
+            Handedness::RightHanded => normal,
+            Handedness::LeftHanded => -normal,
+        }
+    }
+
+    fn global_points(face: &Face) -> Vec<[i64; 3]> {
+        // Rounding, to make the comparison robust against tiny differences
+        // in floating-point results.
+        let surface = face.surface().geometry();
+        face.region()
+            .all_cycles()
+            .flat_map(|cycle| cycle.half_edges().iter())
+            .map(|half_edge| {
+                let point = surface
+                    .point_from_surface_coords(half_edge.start_position());
+                point
+                    .coords
+                    .components
+                    .map(|c| (c.into_f64() * 1e9).round() as i64)
+            })
+            .collect()
+    }
+}
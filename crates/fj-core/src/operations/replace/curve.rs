@@ -1,10 +1,10 @@
-use std::ops::Deref;
+use std::{collections::BTreeMap, ops::Deref};
 
 use crate::{
     objects::{Curve, Cycle, Face, HalfEdge, Region, Shell, Sketch, Solid},
     operations::{insert::Insert, update::UpdateHalfEdge},
     services::Services,
-    storage::Handle,
+    storage::{Handle, ObjectId},
 };
 
 use super::ReplaceOutput;
@@ -25,20 +25,36 @@ pub trait ReplaceCurve: Sized {
         original: &Handle<Curve>,
         replacement: Handle<Curve>,
         services: &mut Services,
+    ) -> ReplaceOutput<Self, Self::BareObject> {
+        self.replace_curves(
+            &BTreeMap::from([(original.id(), replacement)]),
+            services,
+        )
+    }
+
+    /// Replace multiple curves at once
+    ///
+    /// `replacements` maps the IDs of the original curves to their
+    /// replacements. All of them are replaced in a single pass over the object
+    /// graph, which is much faster than replacing them one by one.
+    #[must_use]
+    fn replace_curves(
+        &self,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
+        services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject>;
 }
 
 impl ReplaceCurve for HalfEdge {
     type BareObject = Self;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         _: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
-        if original.id() == self.curve().id() {
-            ReplaceOutput::Updated(self.update_curve(|_| replacement))
+        if let Some(replacement) = replacements.get(&self.curve().id()) {
+            ReplaceOutput::Updated(self.update_curve(|_| replacement.clone()))
         } else {
             ReplaceOutput::Original(self.clone())
         }
@@ -48,21 +64,16 @@ impl ReplaceCurve for HalfEdge {
 impl ReplaceCurve for Cycle {
     type BareObject = Self;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut half_edges = Vec::new();
         for half_edge in self.half_edges() {
-            let half_edge = half_edge.replace_curve(
-                original,
-                replacement.clone(),
-                services,
-            );
+            let half_edge = half_edge.replace_curves(replacements, services);
             replacement_happened |= half_edge.was_updated();
             half_edges.push(
                 half_edge
@@ -82,25 +93,19 @@ impl ReplaceCurve for Cycle {
 impl ReplaceCurve for Region {
     type BareObject = Self;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
-        let exterior = self.exterior().replace_curve(
-            original,
-            replacement.clone(),
-            services,
-        );
+        let exterior = self.exterior().replace_curves(replacements, services);
         replacement_happened |= exterior.was_updated();
 
         let mut interiors = Vec::new();
         for cycle in self.interiors() {
-            let cycle =
-                cycle.replace_curve(original, replacement.clone(), services);
+            let cycle = cycle.replace_curves(replacements, services);
             replacement_happened |= cycle.was_updated();
             interiors.push(
                 cycle
@@ -126,18 +131,16 @@ impl ReplaceCurve for Region {
 impl ReplaceCurve for Sketch {
     type BareObject = Self;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut regions = Vec::new();
         for region in self.regions() {
-            let region =
-                region.replace_curve(original, replacement.clone(), services);
+            let region = region.replace_curves(replacements, services);
             replacement_happened |= region.was_updated();
             regions.push(
                 region
@@ -157,14 +160,12 @@ impl ReplaceCurve for Sketch {
 impl ReplaceCurve for Face {
     type BareObject = Self;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
-        let region =
-            self.region().replace_curve(original, replacement, services);
+        let region = self.region().replace_curves(replacements, services);
 
         if region.was_updated() {
             ReplaceOutput::Updated(Face::new(
@@ -182,18 +183,16 @@ impl ReplaceCurve for Face {
 impl ReplaceCurve for Shell {
     type BareObject = Self;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut faces = Vec::new();
         for face in self.faces() {
-            let face =
-                face.replace_curve(original, replacement.clone(), services);
+            let face = face.replace_curves(replacements, services);
             replacement_happened |= face.was_updated();
             faces.push(
                 face.map_updated(|updated| updated.insert(services))
@@ -212,18 +211,16 @@ impl ReplaceCurve for Shell {
 impl ReplaceCurve for Solid {
     type BareObject = Self;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut shells = Vec::new();
         for shell in self.shells() {
-            let shell =
-                shell.replace_curve(original, replacement.clone(), services);
+            let shell = shell.replace_curves(replacements, services);
             replacement_happened |= shell.was_updated();
             shells.push(
                 shell
@@ -243,14 +240,13 @@ impl ReplaceCurve for Solid {
 impl ReplaceCurve for Handle<HalfEdge> {
     type BareObject = HalfEdge;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_curve(original, replacement, services)
+            .replace_curves(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -258,14 +254,13 @@ impl ReplaceCurve for Handle<HalfEdge> {
 impl ReplaceCurve for Handle<Cycle> {
     type BareObject = Cycle;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_curve(original, replacement, services)
+            .replace_curves(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -273,14 +268,13 @@ impl ReplaceCurve for Handle<Cycle> {
 impl ReplaceCurve for Handle<Region> {
     type BareObject = Region;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_curve(original, replacement, services)
+            .replace_curves(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -288,14 +282,13 @@ impl ReplaceCurve for Handle<Region> {
 impl ReplaceCurve for Handle<Sketch> {
     type BareObject = Sketch;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_curve(original, replacement, services)
+            .replace_curves(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -303,14 +296,13 @@ impl ReplaceCurve for Handle<Sketch> {
 impl ReplaceCurve for Handle<Face> {
     type BareObject = Face;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_curve(original, replacement, services)
+            .replace_curves(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -318,14 +310,13 @@ impl ReplaceCurve for Handle<Face> {
 impl ReplaceCurve for Handle<Shell> {
     type BareObject = Shell;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_curve(original, replacement, services)
+            .replace_curves(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -333,14 +324,13 @@ impl ReplaceCurve for Handle<Shell> {
 impl ReplaceCurve for Handle<Solid> {
     type BareObject = Solid;
 
-    fn replace_curve(
+    fn replace_curves(
         &self,
-        original: &Handle<Curve>,
-        replacement: Handle<Curve>,
+        replacements: &BTreeMap<ObjectId, Handle<Curve>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_curve(original, replacement, services)
+            .replace_curves(replacements, services)
             .map_original(|_| self.clone())
     }
 }
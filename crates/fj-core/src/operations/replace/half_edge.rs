@@ -1,10 +1,10 @@
-use std::ops::Deref;
+use std::{collections::BTreeMap, ops::Deref};
 
 use crate::{
     objects::{Cycle, Face, HalfEdge, Region, Shell, Sketch, Solid},
     operations::insert::Insert,
     services::Services,
-    storage::Handle,
+    storage::{Handle, ObjectId},
 };
 
 use super::ReplaceOutput;
@@ -25,21 +25,35 @@ pub trait ReplaceHalfEdge: Sized {
         original: &Handle<HalfEdge>,
         replacements: [Handle<HalfEdge>; N],
         services: &mut Services,
+    ) -> ReplaceOutput<Self, Self::BareObject> {
+        self.replace_half_edges(
+            &BTreeMap::from([(original.id(), replacements.into())]),
+            services,
+        )
+    }
+
+    /// Replace multiple half-edges at once
+    ///
+    /// `replacements` maps the IDs of the original half-edges to their
+    /// replacements. All of them are replaced in a single pass over the object
+    /// graph, which is much faster than replacing them one by one.
+    #[must_use]
+    fn replace_half_edges(
+        &self,
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
+        services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject>;
 }
 
 impl ReplaceHalfEdge for Cycle {
     type BareObject = Self;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         _: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
-        if let Some(half_edges) =
-            self.half_edges().replace(original, replacements)
-        {
+        if let Some(half_edges) = self.half_edges().replace_many(replacements) {
             ReplaceOutput::Updated(Cycle::new(half_edges))
         } else {
             ReplaceOutput::Original(self.clone())
@@ -50,28 +64,20 @@ impl ReplaceHalfEdge for Cycle {
 impl ReplaceHalfEdge for Region {
     type BareObject = Self;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
-        let exterior = self.exterior().replace_half_edge(
-            original,
-            replacements.clone(),
-            services,
-        );
+        let exterior =
+            self.exterior().replace_half_edges(replacements, services);
         replacement_happened |= exterior.was_updated();
 
         let mut interiors = Vec::new();
         for cycle in self.interiors() {
-            let cycle = cycle.replace_half_edge(
-                original,
-                replacements.clone(),
-                services,
-            );
+            let cycle = cycle.replace_half_edges(replacements, services);
             replacement_happened |= cycle.was_updated();
             interiors.push(
                 cycle
@@ -97,21 +103,16 @@ impl ReplaceHalfEdge for Region {
 impl ReplaceHalfEdge for Sketch {
     type BareObject = Self;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut regions = Vec::new();
         for region in self.regions() {
-            let region = region.replace_half_edge(
-                original,
-                replacements.clone(),
-                services,
-            );
+            let region = region.replace_half_edges(replacements, services);
             replacement_happened |= region.was_updated();
             regions.push(
                 region
@@ -131,15 +132,12 @@ impl ReplaceHalfEdge for Sketch {
 impl ReplaceHalfEdge for Face {
     type BareObject = Self;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
-        let region =
-            self.region()
-                .replace_half_edge(original, replacements, services);
+        let region = self.region().replace_half_edges(replacements, services);
 
         if region.was_updated() {
             ReplaceOutput::Updated(Face::new(
@@ -157,21 +155,16 @@ impl ReplaceHalfEdge for Face {
 impl ReplaceHalfEdge for Shell {
     type BareObject = Self;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut faces = Vec::new();
         for face in self.faces() {
-            let face = face.replace_half_edge(
-                original,
-                replacements.clone(),
-                services,
-            );
+            let face = face.replace_half_edges(replacements, services);
             replacement_happened |= face.was_updated();
             faces.push(
                 face.map_updated(|updated| updated.insert(services))
@@ -190,21 +183,16 @@ impl ReplaceHalfEdge for Shell {
 impl ReplaceHalfEdge for Solid {
     type BareObject = Self;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut shells = Vec::new();
         for shell in self.shells() {
-            let shell = shell.replace_half_edge(
-                original,
-                replacements.clone(),
-                services,
-            );
+            let shell = shell.replace_half_edges(replacements, services);
             replacement_happened |= shell.was_updated();
             shells.push(
                 shell
@@ -224,14 +212,13 @@ impl ReplaceHalfEdge for Solid {
 impl ReplaceHalfEdge for Handle<Cycle> {
     type BareObject = Cycle;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_half_edge(original, replacements, services)
+            .replace_half_edges(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -239,14 +226,13 @@ impl ReplaceHalfEdge for Handle<Cycle> {
 impl ReplaceHalfEdge for Handle<Region> {
     type BareObject = Region;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_half_edge(original, replacements, services)
+            .replace_half_edges(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -254,14 +240,13 @@ impl ReplaceHalfEdge for Handle<Region> {
 impl ReplaceHalfEdge for Handle<Sketch> {
     type BareObject = Sketch;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_half_edge(original, replacements, services)
+            .replace_half_edges(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -269,14 +254,13 @@ impl ReplaceHalfEdge for Handle<Sketch> {
 impl ReplaceHalfEdge for Handle<Face> {
     type BareObject = Face;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_half_edge(original, replacements, services)
+            .replace_half_edges(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -284,14 +268,13 @@ impl ReplaceHalfEdge for Handle<Face> {
 impl ReplaceHalfEdge for Handle<Shell> {
     type BareObject = Shell;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_half_edge(original, replacements, services)
+            .replace_half_edges(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -299,14 +282,13 @@ impl ReplaceHalfEdge for Handle<Shell> {
 impl ReplaceHalfEdge for Handle<Solid> {
     type BareObject = Solid;
 
-    fn replace_half_edge<const N: usize>(
+    fn replace_half_edges(
         &self,
-        original: &Handle<HalfEdge>,
-        replacements: [Handle<HalfEdge>; N],
+        replacements: &BTreeMap<ObjectId, Vec<Handle<HalfEdge>>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_half_edge(original, replacements, services)
+            .replace_half_edges(replacements, services)
             .map_original(|_| self.clone())
     }
 }
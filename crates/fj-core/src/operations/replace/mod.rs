@@ -70,6 +70,11 @@
 //! calling the replace operation recursively. This might have performance
 //! implications for large object graphs.
 //!
+//! To keep the cost of this in check, each replace trait provides a bulk
+//! variant of its operation (like [`ReplaceHalfEdge::replace_half_edges`]),
+//! which replaces any number of objects in a single pass over the object graph.
+//! The single-object operations are implemented in terms of those.
+//!
 //! There are some update operations that are straight-up redundant with what
 //! replace operations are doing. Some of the methods even have the same names.
 //! Those haven't been removed yet, as update operations generally require a
@@ -92,10 +97,12 @@
 
 mod curve;
 mod half_edge;
+mod surface;
 mod vertex;
 
 pub use self::{
-    curve::ReplaceCurve, half_edge::ReplaceHalfEdge, vertex::ReplaceVertex,
+    curve::ReplaceCurve, half_edge::ReplaceHalfEdge, surface::ReplaceSurface,
+    vertex::ReplaceVertex,
 };
 
 /// The output of a replace operation
@@ -162,3 +169,482 @@ impl<T> ReplaceOutput<T, T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use fj_math::Point;
+    use proptest::{
+        prelude::*,
+        sample::Index,
+        test_runner::{TestCaseError, TestCaseResult, TestRunner},
+    };
+
+    use crate::{
+        geometry::{CurveBoundary, SurfacePath},
+        objects::{
+            Curve, Cycle, Face, HalfEdge, Region, Shell, Sketch, Surface,
+            Vertex,
+        },
+        operations::{
+            build::{BuildCycle, BuildFace},
+            insert::Insert,
+            update::{UpdateFace, UpdateRegion, UpdateShell, UpdateSketch},
+        },
+        queries::SiblingOfHalfEdge,
+        services::Services,
+        storage::Handle,
+        test_support::cube_shell,
+        validate::Validate,
+    };
+
+    use super::{
+        ReplaceCurve, ReplaceHalfEdge, ReplaceOutput, ReplaceSurface,
+        ReplaceVertex,
+    };
+
+    #[test]
+    fn replace_preserves_shell_invariants() {
+        let operations = prop::collection::vec(shell_operation(), 1..16);
+        TestRunner::new(ProptestConfig::with_cases(64))
+            .run(&operations, check_replace_preserves_shell_invariants)
+            .unwrap();
+    }
+
+    #[test]
+    fn replace_preserves_sketch_invariants() {
+        let operations = prop::collection::vec(sketch_operation(), 1..16);
+        TestRunner::default()
+            .run(&operations, check_replace_preserves_sketch_invariants)
+            .unwrap();
+    }
+
+    #[test]
+    fn replace_shared_surface() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let xy_plane = services.objects.surfaces.xy_plane();
+        let xz_plane = services.objects.surfaces.xz_plane();
+
+        let square = |x: f64| [[x, 0.], [x + 1., 0.], [x + 1., 1.], [x, 1.]];
+        let faces = [
+            Face::polygon(xy_plane.clone(), square(0.), &mut services),
+            Face::polygon(xy_plane.clone(), square(2.), &mut services),
+            Face::polygon(xz_plane.clone(), square(0.), &mut services),
+        ]
+        .map(|face| face.insert(&mut services));
+        let shell = Shell::new(faces.clone());
+
+        let replacement =
+            Surface::new(xy_plane.geometry()).insert(&mut services);
+        let ReplaceOutput::Updated(updated) = shell.replace_surface(
+            &xy_plane,
+            replacement.clone(),
+            &mut services,
+        ) else {
+            panic!("Expected shell to be updated");
+        };
+
+        let updated = updated.faces().iter().collect::<Vec<_>>();
+        assert_eq!(updated.len(), faces.len());
+
+        // Both faces on the replaced surface now share the replacement, while
+        // the face on the other surface is left as it was.
+        for (original, updated) in faces[..2].iter().zip(&updated) {
+            assert_eq!(updated.surface().id(), replacement.id());
+            assert_eq!(updated.region().id(), original.region().id());
+        }
+        assert_eq!(updated[2].id(), faces[2].id());
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    fn check_replace_preserves_shell_invariants(
+        operations: Vec<ShellOperation>,
+    ) -> TestCaseResult {
+        let mut services = Services::new();
+
+        let mut shell = cube_shell(1., &mut services);
+        let geometry = geometry_of(&shell);
+
+        for operation in operations {
+            let half_edges = half_edges_of(&shell);
+            let half_edge =
+                |index: &Index| &half_edges[index.index(half_edges.len())];
+            let face = |index: &Index| {
+                shell.faces().nth(index.index(shell.faces().len())).unwrap()
+            };
+
+            let updated = match operation {
+                ShellOperation::Vertex(index) => {
+                    let vertex = Vertex::new().insert(&mut services);
+                    expect_updated(shell.replace_vertex(
+                        half_edge(&index).start_vertex(),
+                        vertex,
+                        &mut services,
+                    ))?
+                }
+                ShellOperation::Vertices(indices) => {
+                    let replacements = indices
+                        .iter()
+                        .map(|index| {
+                            let vertex = half_edge(index).start_vertex();
+                            (vertex.id(), Vertex::new().insert(&mut services))
+                        })
+                        .collect::<BTreeMap<_, _>>();
+                    expect_updated(
+                        shell.replace_vertices(&replacements, &mut services),
+                    )?
+                }
+                ShellOperation::Curve(index) => {
+                    let curve = Curve::new().insert(&mut services);
+                    expect_updated(shell.replace_curve(
+                        half_edge(&index).curve(),
+                        curve,
+                        &mut services,
+                    ))?
+                }
+                ShellOperation::Curves(indices) => {
+                    let replacements = indices
+                        .iter()
+                        .map(|index| {
+                            let curve = half_edge(index).curve();
+                            (curve.id(), Curve::new().insert(&mut services))
+                        })
+                        .collect::<BTreeMap<_, _>>();
+                    expect_updated(
+                        shell.replace_curves(&replacements, &mut services),
+                    )?
+                }
+                ShellOperation::HalfEdge(index) => {
+                    let original = half_edge(&index);
+                    let copy = copy_half_edge(original, &mut services);
+                    expect_updated(shell.replace_half_edge(
+                        original,
+                        [copy],
+                        &mut services,
+                    ))?
+                }
+                ShellOperation::HalfEdges(indices) => {
+                    let replacements = indices
+                        .iter()
+                        .map(|index| {
+                            let original = half_edge(index);
+                            let copy = copy_half_edge(original, &mut services);
+                            (original.id(), vec![copy])
+                        })
+                        .collect::<BTreeMap<_, _>>();
+                    expect_updated(
+                        shell.replace_half_edges(&replacements, &mut services),
+                    )?
+                }
+                ShellOperation::Surface(index) => {
+                    let original = face(&index).surface();
+                    let copy =
+                        Surface::new(original.geometry()).insert(&mut services);
+                    expect_updated(shell.replace_surface(
+                        original,
+                        copy,
+                        &mut services,
+                    ))?
+                }
+                ShellOperation::Surfaces(indices) => {
+                    let replacements = indices
+                        .iter()
+                        .map(|index| {
+                            let original = face(index).surface();
+                            let copy = Surface::new(original.geometry())
+                                .insert(&mut services);
+                            (original.id(), copy)
+                        })
+                        .collect::<BTreeMap<_, _>>();
+                    expect_updated(
+                        shell.replace_surfaces(&replacements, &mut services),
+                    )?
+                }
+                ShellOperation::Face(index) => {
+                    shell.replace_face(face(&index), |face| {
+                        [Face::new(
+                            face.surface().clone(),
+                            face.region().clone(),
+                        )
+                        .insert(&mut services)]
+                    })
+                }
+                ShellOperation::Cycle(index) => {
+                    shell.update_face(face(&index), |face| {
+                        face.update_region(|region| {
+                            region
+                                .update_exterior(|cycle| {
+                                    Cycle::new(
+                                        cycle.half_edges().iter().cloned(),
+                                    )
+                                    .insert(&mut services)
+                                })
+                                .insert(&mut services)
+                        })
+                        .insert(&mut services)
+                    })
+                }
+            };
+
+            shell = updated.insert(&mut services);
+
+            // None of the replacements change the geometry, so faces, cycles,
+            // and half-edges must still be in the same order.
+            prop_assert_eq!(geometry_of(&shell), geometry.clone());
+
+            let half_edges = half_edges_of(&shell);
+            let ids = half_edges
+                .iter()
+                .map(|half_edge| half_edge.id())
+                .collect::<BTreeSet<_>>();
+            prop_assert_eq!(ids.len(), half_edges.len());
+
+            shell.faces().assert_invariants();
+            for face in shell.faces() {
+                for cycle in face.region().all_cycles() {
+                    cycle.half_edges().assert_invariants();
+                }
+            }
+
+            for half_edge in &half_edges {
+                let sibling = shell
+                    .get_sibling_of(half_edge)
+                    .expect("Every half-edge of a closed shell has one");
+                prop_assert!(shell.are_siblings(half_edge, &sibling));
+                prop_assert_eq!(
+                    shell.get_sibling_of(&sibling).map(|sibling| sibling.id()),
+                    Some(half_edge.id())
+                );
+            }
+
+            if let Err(err) = shell.validate_and_return_first_error() {
+                return Err(TestCaseError::fail(err.to_string()));
+            }
+        }
+
+        if let Err(err) = services.drop_and_validate() {
+            return Err(TestCaseError::fail(err.to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn check_replace_preserves_sketch_invariants(
+        operations: Vec<SketchOperation>,
+    ) -> TestCaseResult {
+        let mut services = Services::new();
+        let mut num_regions = 0;
+
+        // The sketch is checked against a plain `Vec`, which serves as a model
+        // of which regions it should contain, and in which order.
+        let mut model = (0..3)
+            .map(|_| region_with_hole(&mut num_regions, &mut services))
+            .collect::<Vec<_>>();
+        let mut sketch = Sketch::new(model.clone()).insert(&mut services);
+
+        for operation in operations {
+            let position = operation.index().index(model.len());
+            let original = model[position].clone();
+
+            sketch = match operation {
+                SketchOperation::Remove(_) => {
+                    if model.len() == 1 {
+                        continue;
+                    }
+
+                    model.remove(position);
+                    sketch.replace_region(&original, |_| [])
+                }
+                SketchOperation::ReplaceWithOne(_) => {
+                    let replacement =
+                        region_with_hole(&mut num_regions, &mut services);
+                    model[position] = replacement.clone();
+                    sketch.replace_region(&original, |_| [replacement])
+                }
+                SketchOperation::ReplaceWithTwo(_) => {
+                    let [a, b] = [(), ()].map(|()| {
+                        region_with_hole(&mut num_regions, &mut services)
+                    });
+                    model[position] = a.clone();
+                    model.insert(position + 1, b.clone());
+                    sketch.replace_region(&original, |_| [a, b])
+                }
+                SketchOperation::ReplaceInterior(_) => {
+                    let interior = original.interiors().first();
+                    let copy =
+                        Cycle::new(interior.half_edges().iter().cloned())
+                            .insert(&mut services);
+                    let replacement = original
+                        .replace_interior(interior, |_| [copy])
+                        .insert(&mut services);
+                    model[position] = replacement.clone();
+                    sketch.update_region(&original, |_| replacement)
+                }
+            }
+            .insert(&mut services);
+
+            sketch.regions().assert_invariants();
+            for region in sketch.regions() {
+                region.interiors().assert_invariants();
+            }
+
+            prop_assert_eq!(
+                sketch
+                    .regions()
+                    .iter()
+                    .map(|region| region.id())
+                    .collect::<Vec<_>>(),
+                model.iter().map(|region| region.id()).collect::<Vec<_>>()
+            );
+            prop_assert!(!sketch.regions().contains(&original));
+        }
+
+        if let Err(err) = services.drop_and_validate() {
+            return Err(TestCaseError::fail(err.to_string()));
+        }
+
+        Ok(())
+    }
+
+    #[derive(Clone, Debug)]
+    enum ShellOperation {
+        Vertex(Index),
+        Vertices(Vec<Index>),
+        Curve(Index),
+        Curves(Vec<Index>),
+        HalfEdge(Index),
+        HalfEdges(Vec<Index>),
+        Surface(Index),
+        Surfaces(Vec<Index>),
+        Face(Index),
+        Cycle(Index),
+    }
+
+    fn shell_operation() -> impl Strategy<Value = ShellOperation> {
+        let indices = || prop::collection::vec(any::<Index>(), 1..4);
+
+        prop_oneof![
+            any::<Index>().prop_map(ShellOperation::Vertex),
+            indices().prop_map(ShellOperation::Vertices),
+            any::<Index>().prop_map(ShellOperation::Curve),
+            indices().prop_map(ShellOperation::Curves),
+            any::<Index>().prop_map(ShellOperation::HalfEdge),
+            indices().prop_map(ShellOperation::HalfEdges),
+            any::<Index>().prop_map(ShellOperation::Surface),
+            indices().prop_map(ShellOperation::Surfaces),
+            any::<Index>().prop_map(ShellOperation::Face),
+            any::<Index>().prop_map(ShellOperation::Cycle),
+        ]
+    }
+
+    #[derive(Clone, Debug)]
+    enum SketchOperation {
+        Remove(Index),
+        ReplaceWithOne(Index),
+        ReplaceWithTwo(Index),
+        ReplaceInterior(Index),
+    }
+
+    impl SketchOperation {
+        fn index(&self) -> &Index {
+            match self {
+                Self::Remove(index)
+                | Self::ReplaceWithOne(index)
+                | Self::ReplaceWithTwo(index)
+                | Self::ReplaceInterior(index) => index,
+            }
+        }
+    }
+
+    fn sketch_operation() -> impl Strategy<Value = SketchOperation> {
+        prop_oneof![
+            any::<Index>().prop_map(SketchOperation::Remove),
+            any::<Index>().prop_map(SketchOperation::ReplaceWithOne),
+            any::<Index>().prop_map(SketchOperation::ReplaceWithTwo),
+            any::<Index>().prop_map(SketchOperation::ReplaceInterior),
+        ]
+    }
+
+    fn expect_updated(
+        output: ReplaceOutput<Handle<Shell>, Shell>,
+    ) -> Result<Shell, TestCaseError> {
+        match output {
+            ReplaceOutput::Updated(shell) => Ok(shell),
+            ReplaceOutput::Original(_) => {
+                Err(TestCaseError::fail("Replace operation had no effect"))
+            }
+        }
+    }
+
+    fn copy_half_edge(
+        half_edge: &Handle<HalfEdge>,
+        services: &mut Services,
+    ) -> Handle<HalfEdge> {
+        HalfEdge::new(
+            half_edge.path(),
+            half_edge.boundary(),
+            half_edge.curve().clone(),
+            half_edge.start_vertex().clone(),
+        )
+        .insert(services)
+    }
+
+    /// Build a square region with a square hole, next to all previous ones
+    fn region_with_hole(
+        num_regions: &mut u32,
+        services: &mut Services,
+    ) -> Handle<Region> {
+        let x = f64::from(*num_regions) * 4.;
+        *num_regions += 1;
+
+        let exterior = Cycle::polygon(
+            [[x, 0.], [x + 3., 0.], [x + 3., 3.], [x, 3.]],
+            services,
+        )
+        .insert(services);
+        let interior = Cycle::polygon(
+            [[x + 1., 1.], [x + 1., 2.], [x + 2., 2.], [x + 2., 1.]],
+            services,
+        )
+        .insert(services);
+
+        Region::new(exterior, [interior], None).insert(services)
+    }
+
+    fn half_edges_of(shell: &Shell) -> Vec<Handle<HalfEdge>> {
+        shell
+            .faces()
+            .iter()
+            .flat_map(|face| face.region().all_cycles())
+            .flat_map(|cycle| cycle.half_edges().iter().cloned())
+            .collect()
+    }
+
+    /// The geometry of each half-edge, grouped by cycle and face
+    type ShellGeometry = Vec<Vec<Vec<(SurfacePath, CurveBoundary<Point<1>>)>>>;
+
+    fn geometry_of(shell: &Shell) -> ShellGeometry {
+        shell
+            .faces()
+            .iter()
+            .map(|face| {
+                face.region()
+                    .all_cycles()
+                    .map(|cycle| {
+                        cycle
+                            .half_edges()
+                            .iter()
+                            .map(|half_edge| {
+                                (half_edge.path(), half_edge.boundary())
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,169 @@
+use std::{collections::BTreeMap, ops::Deref};
+
+use crate::{
+    objects::{Face, Shell, Solid, Surface},
+    operations::insert::Insert,
+    services::Services,
+    storage::{Handle, ObjectId},
+};
+
+use super::ReplaceOutput;
+
+/// Replace a [`Surface`] in the referenced object graph
+///
+/// See [module documentation] for more information.
+///
+/// [module documentation]: super
+pub trait ReplaceSurface: Sized {
+    /// The bare object type that this trait is implemented for
+    type BareObject;
+
+    /// Replace the surface
+    ///
+    /// Only the surface itself is replaced. The half-edges of any face that
+    /// references the surface are defined in the surface's coordinates, and
+    /// are left as they are. It is the caller's responsibility to provide a
+    /// replacement whose coordinates are compatible with those half-edges.
+    #[must_use]
+    fn replace_surface(
+        &self,
+        original: &Handle<Surface>,
+        replacement: Handle<Surface>,
+        services: &mut Services,
+    ) -> ReplaceOutput<Self, Self::BareObject> {
+        self.replace_surfaces(
+            &BTreeMap::from([(original.id(), replacement)]),
+            services,
+        )
+    }
+
+    /// Replace multiple surfaces at once
+    ///
+    /// `replacements` maps the IDs of the original surfaces to their
+    /// replacements. All of them are replaced in a single pass over the object
+    /// graph, which is much faster than replacing them one by one.
+    #[must_use]
+    fn replace_surfaces(
+        &self,
+        replacements: &BTreeMap<ObjectId, Handle<Surface>>,
+        services: &mut Services,
+    ) -> ReplaceOutput<Self, Self::BareObject>;
+}
+
+impl ReplaceSurface for Face {
+    type BareObject = Self;
+
+    fn replace_surfaces(
+        &self,
+        replacements: &BTreeMap<ObjectId, Handle<Surface>>,
+        _: &mut Services,
+    ) -> ReplaceOutput<Self, Self::BareObject> {
+        if let Some(replacement) = replacements.get(&self.surface().id()) {
+            ReplaceOutput::Updated(Face::new(
+                replacement.clone(),
+                self.region().clone(),
+            ))
+        } else {
+            ReplaceOutput::Original(self.clone())
+        }
+    }
+}
+
+impl ReplaceSurface for Shell {
+    type BareObject = Self;
+
+    fn replace_surfaces(
+        &self,
+        replacements: &BTreeMap<ObjectId, Handle<Surface>>,
+        services: &mut Services,
+    ) -> ReplaceOutput<Self, Self::BareObject> {
+        let mut replacement_happened = false;
+
+        let mut faces = Vec::new();
+        for face in self.faces() {
+            let face = face.replace_surfaces(replacements, services);
+            replacement_happened |= face.was_updated();
+            faces.push(
+                face.map_updated(|updated| updated.insert(services))
+                    .into_inner(),
+            );
+        }
+
+        if replacement_happened {
+            ReplaceOutput::Updated(Shell::new(faces))
+        } else {
+            ReplaceOutput::Original(self.clone())
+        }
+    }
+}
+
+impl ReplaceSurface for Solid {
+    type BareObject = Self;
+
+    fn replace_surfaces(
+        &self,
+        replacements: &BTreeMap<ObjectId, Handle<Surface>>,
+        services: &mut Services,
+    ) -> ReplaceOutput<Self, Self::BareObject> {
+        let mut replacement_happened = false;
+
+        let mut shells = Vec::new();
+        for shell in self.shells() {
+            let shell = shell.replace_surfaces(replacements, services);
+            replacement_happened |= shell.was_updated();
+            shells.push(
+                shell
+                    .map_updated(|updated| updated.insert(services))
+                    .into_inner(),
+            );
+        }
+
+        if replacement_happened {
+            ReplaceOutput::Updated(Solid::new(shells))
+        } else {
+            ReplaceOutput::Original(self.clone())
+        }
+    }
+}
+
+impl ReplaceSurface for Handle<Face> {
+    type BareObject = Face;
+
+    fn replace_surfaces(
+        &self,
+        replacements: &BTreeMap<ObjectId, Handle<Surface>>,
+        services: &mut Services,
+    ) -> ReplaceOutput<Self, Self::BareObject> {
+        self.deref()
+            .replace_surfaces(replacements, services)
+            .map_original(|_| self.clone())
+    }
+}
+
+impl ReplaceSurface for Handle<Shell> {
+    type BareObject = Shell;
+
+    fn replace_surfaces(
+        &self,
+        replacements: &BTreeMap<ObjectId, Handle<Surface>>,
+        services: &mut Services,
+    ) -> ReplaceOutput<Self, Self::BareObject> {
+        self.deref()
+            .replace_surfaces(replacements, services)
+            .map_original(|_| self.clone())
+    }
+}
+
+impl ReplaceSurface for Handle<Solid> {
+    type BareObject = Solid;
+
+    fn replace_surfaces(
+        &self,
+        replacements: &BTreeMap<ObjectId, Handle<Surface>>,
+        services: &mut Services,
+    ) -> ReplaceOutput<Self, Self::BareObject> {
+        self.deref()
+            .replace_surfaces(replacements, services)
+            .map_original(|_| self.clone())
+    }
+}
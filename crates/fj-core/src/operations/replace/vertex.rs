@@ -1,10 +1,10 @@
-use std::ops::Deref;
+use std::{collections::BTreeMap, ops::Deref};
 
 use crate::{
     objects::{Cycle, Face, HalfEdge, Region, Shell, Sketch, Solid, Vertex},
     operations::{insert::Insert, update::UpdateHalfEdge},
     services::Services,
-    storage::Handle,
+    storage::{Handle, ObjectId},
 };
 
 use super::ReplaceOutput;
@@ -25,20 +25,38 @@ pub trait ReplaceVertex: Sized {
         original: &Handle<Vertex>,
         replacement: Handle<Vertex>,
         services: &mut Services,
+    ) -> ReplaceOutput<Self, Self::BareObject> {
+        self.replace_vertices(
+            &BTreeMap::from([(original.id(), replacement)]),
+            services,
+        )
+    }
+
+    /// Replace multiple vertices at once
+    ///
+    /// `replacements` maps the IDs of the original vertices to their
+    /// replacements. All of them are replaced in a single pass over the object
+    /// graph, which is much faster than replacing them one by one.
+    #[must_use]
+    fn replace_vertices(
+        &self,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
+        services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject>;
 }
 
 impl ReplaceVertex for HalfEdge {
     type BareObject = Self;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         _: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
-        if original.id() == self.start_vertex().id() {
-            ReplaceOutput::Updated(self.update_start_vertex(|_| replacement))
+        if let Some(replacement) = replacements.get(&self.start_vertex().id()) {
+            ReplaceOutput::Updated(
+                self.update_start_vertex(|_| replacement.clone()),
+            )
         } else {
             ReplaceOutput::Original(self.clone())
         }
@@ -48,21 +66,16 @@ impl ReplaceVertex for HalfEdge {
 impl ReplaceVertex for Cycle {
     type BareObject = Self;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut half_edges = Vec::new();
         for half_edge in self.half_edges() {
-            let half_edge = half_edge.replace_vertex(
-                original,
-                replacement.clone(),
-                services,
-            );
+            let half_edge = half_edge.replace_vertices(replacements, services);
             replacement_happened |= half_edge.was_updated();
             half_edges.push(
                 half_edge
@@ -82,25 +95,19 @@ impl ReplaceVertex for Cycle {
 impl ReplaceVertex for Region {
     type BareObject = Self;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
-        let exterior = self.exterior().replace_vertex(
-            original,
-            replacement.clone(),
-            services,
-        );
+        let exterior = self.exterior().replace_vertices(replacements, services);
         replacement_happened |= exterior.was_updated();
 
         let mut interiors = Vec::new();
         for cycle in self.interiors() {
-            let cycle =
-                cycle.replace_vertex(original, replacement.clone(), services);
+            let cycle = cycle.replace_vertices(replacements, services);
             replacement_happened |= cycle.was_updated();
             interiors.push(
                 cycle
@@ -126,18 +133,16 @@ impl ReplaceVertex for Region {
 impl ReplaceVertex for Sketch {
     type BareObject = Self;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut regions = Vec::new();
         for region in self.regions() {
-            let region =
-                region.replace_vertex(original, replacement.clone(), services);
+            let region = region.replace_vertices(replacements, services);
             replacement_happened |= region.was_updated();
             regions.push(
                 region
@@ -157,15 +162,12 @@ impl ReplaceVertex for Sketch {
 impl ReplaceVertex for Face {
     type BareObject = Self;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
-        let region =
-            self.region()
-                .replace_vertex(original, replacement, services);
+        let region = self.region().replace_vertices(replacements, services);
 
         if region.was_updated() {
             ReplaceOutput::Updated(Face::new(
@@ -183,18 +185,16 @@ impl ReplaceVertex for Face {
 impl ReplaceVertex for Shell {
     type BareObject = Self;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut faces = Vec::new();
         for face in self.faces() {
-            let face =
-                face.replace_vertex(original, replacement.clone(), services);
+            let face = face.replace_vertices(replacements, services);
             replacement_happened |= face.was_updated();
             faces.push(
                 face.map_updated(|updated| updated.insert(services))
@@ -213,18 +213,16 @@ impl ReplaceVertex for Shell {
 impl ReplaceVertex for Solid {
     type BareObject = Self;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut shells = Vec::new();
         for shell in self.shells() {
-            let shell =
-                shell.replace_vertex(original, replacement.clone(), services);
+            let shell = shell.replace_vertices(replacements, services);
             replacement_happened |= shell.was_updated();
             shells.push(
                 shell
@@ -244,14 +242,13 @@ impl ReplaceVertex for Solid {
 impl ReplaceVertex for Handle<HalfEdge> {
     type BareObject = HalfEdge;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_vertex(original, replacement, services)
+            .replace_vertices(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -259,14 +256,13 @@ impl ReplaceVertex for Handle<HalfEdge> {
 impl ReplaceVertex for Handle<Cycle> {
     type BareObject = Cycle;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_vertex(original, replacement, services)
+            .replace_vertices(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -274,14 +270,13 @@ impl ReplaceVertex for Handle<Cycle> {
 impl ReplaceVertex for Handle<Region> {
     type BareObject = Region;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_vertex(original, replacement, services)
+            .replace_vertices(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -289,18 +284,16 @@ impl ReplaceVertex for Handle<Region> {
 impl ReplaceVertex for Handle<Sketch> {
     type BareObject = Sketch;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         let mut replacement_happened = false;
 
         let mut regions = Vec::new();
         for region in self.regions() {
-            let region =
-                region.replace_vertex(original, replacement.clone(), services);
+            let region = region.replace_vertices(replacements, services);
             replacement_happened |= region.was_updated();
             regions.push(
                 region
@@ -320,14 +313,13 @@ impl ReplaceVertex for Handle<Sketch> {
 impl ReplaceVertex for Handle<Face> {
     type BareObject = Face;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_vertex(original, replacement, services)
+            .replace_vertices(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -335,14 +327,13 @@ impl ReplaceVertex for Handle<Face> {
 impl ReplaceVertex for Handle<Shell> {
     type BareObject = Shell;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_vertex(original, replacement, services)
+            .replace_vertices(replacements, services)
             .map_original(|_| self.clone())
     }
 }
@@ -350,14 +341,13 @@ impl ReplaceVertex for Handle<Shell> {
 impl ReplaceVertex for Handle<Solid> {
     type BareObject = Solid;
 
-    fn replace_vertex(
+    fn replace_vertices(
         &self,
-        original: &Handle<Vertex>,
-        replacement: Handle<Vertex>,
+        replacements: &BTreeMap<ObjectId, Handle<Vertex>>,
         services: &mut Services,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         self.deref()
-            .replace_vertex(original, replacement, services)
+            .replace_vertices(replacements, services)
             .map_original(|_| self.clone())
     }
 }
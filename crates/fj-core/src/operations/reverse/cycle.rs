@@ -12,13 +12,17 @@ impl Reverse for Cycle {
             .half_edges()
             .pairs()
             .map(|(current, next)| {
-                HalfEdge::new(
+                let edge = HalfEdge::new(
                     current.path(),
                     current.boundary().reverse(),
                     current.curve().clone(),
                     next.start_vertex().clone(),
                 )
-                .insert(services)
+                .insert(services);
+
+                services.record_derivation(edge.clone(), [current.clone()]);
+
+                edge
             })
             .collect::<Vec<_>>();
 
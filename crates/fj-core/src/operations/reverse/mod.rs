@@ -6,6 +6,8 @@ mod cycle;
 mod edge;
 mod face;
 mod region;
+mod shell;
+mod solid;
 
 /// Reverse the direction/orientation of an object
 pub trait Reverse {
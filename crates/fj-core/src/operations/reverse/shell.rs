@@ -0,0 +1,53 @@
+use crate::{objects::Shell, operations::insert::Insert, services::Services};
+
+use super::Reverse;
+
+impl Reverse for Shell {
+    /// Turn the shell inside out, by reversing all of its faces
+    ///
+    /// Each pair of sibling half-edges stays a pair of siblings, as reversing
+    /// both of them swaps their direction, but not their relation to each
+    /// other.
+    fn reverse(&self, services: &mut Services) -> Self {
+        let faces = self
+            .faces()
+            .iter()
+            .map(|face| face.reverse(services).insert(services));
+
+        Shell::new(faces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::Shell,
+        operations::{build::BuildShell, insert::Insert, reverse::Reverse},
+        queries::SiblingOfHalfEdge,
+        services::Services,
+    };
+
+    #[test]
+    fn reverse_tetrahedron() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let tetrahedron = Shell::tetrahedron(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services)
+        .shell;
+
+        let reversed = tetrahedron.reverse(&mut services).insert(&mut services);
+
+        for face in reversed.faces() {
+            for half_edge in face.region().exterior().half_edges() {
+                assert!(reversed.get_sibling_of(half_edge).is_some());
+            }
+        }
+
+        services.drop_and_validate()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+use crate::{objects::Solid, operations::insert::Insert, services::Services};
+
+use super::Reverse;
+
+impl Reverse for Solid {
+    fn reverse(&self, services: &mut Services) -> Self {
+        let shells = self
+            .shells()
+            .iter()
+            .map(|shell| shell.reverse(services).insert(services));
+
+        Solid::new(shells)
+    }
+}
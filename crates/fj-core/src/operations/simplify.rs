@@ -0,0 +1,415 @@
+//! # Operations to simplify objects
+//!
+//! See [`SimplifyShell`].
+
+use std::collections::BTreeMap;
+
+use fj_interop::ext::ArrayExt;
+use fj_math::{Point, Scalar};
+
+use crate::{
+    geometry::{GlobalPath, SurfacePath},
+    objects::{Cycle, Face, HalfEdge, Shell},
+    operations::{
+        build::BuildHalfEdge,
+        insert::Insert,
+        update::{UpdateFace, UpdateHalfEdge, UpdateRegion},
+    },
+    queries::{oriented_normal, SiblingOfHalfEdge},
+    services::Services,
+    storage::{Handle, ObjectId},
+};
+
+/// Simplify a [`Shell`]
+///
+/// Operations like sweeps, splits, and boolean operations tend to leave behind
+/// more faces and edges than necessary to describe a shape. This operation
+/// cleans that up, by merging objects where that doesn't change the shape.
+pub trait SimplifyShell {
+    /// Merge adjacent coplanar faces and adjacent collinear edges
+    ///
+    /// Two faces are merged, if they are coplanar, have the same orientation,
+    /// and share exactly one edge. Afterwards, two edges are merged, if they
+    /// are collinear, and the vertex between them is not shared with any other
+    /// edges.
+    ///
+    /// Faces or edges that don't fulfill the requirements of the
+    /// implementation (see below) are left as they are.
+    ///
+    /// # Implementation Note
+    ///
+    /// Only faces that are bounded by line segments, that don't have any
+    /// holes, and whose surfaces are planes are considered for merging. These
+    /// restrictions can be lifted, once the need arises.
+    ///
+    /// Coplanarity and collinearity are checked against a fixed tolerance,
+    /// which might need to be made configurable later.
+    #[must_use]
+    fn simplify(&self, services: &mut Services) -> Self;
+}
+
+impl SimplifyShell for Shell {
+    fn simplify(&self, services: &mut Services) -> Self {
+        let mut shell = self.clone();
+
+        while let Some(simplified) = merge_coplanar_faces(&shell, services) {
+            shell = simplified;
+        }
+        while let Some(simplified) = merge_collinear_edges(&shell, services) {
+            shell = simplified;
+        }
+
+        shell
+    }
+}
+
+const EPSILON: f64 = 1e-9;
+
+/// Merge the first pair of mergeable faces that can be found
+///
+/// Returns `None`, if no such pair exists.
+fn merge_coplanar_faces(
+    shell: &Shell,
+    services: &mut Services,
+) -> Option<Shell> {
+    let faces_by_half_edge = faces_by_half_edge(shell);
+
+    for a in shell.faces() {
+        if !is_simple_planar_polygon(a) {
+            continue;
+        }
+
+        for half_edge in a.region().exterior().half_edges() {
+            let Some(sibling) = shell.get_sibling_of(half_edge) else {
+                continue;
+            };
+            let b = &faces_by_half_edge[&sibling.id()];
+
+            if a.id() == b.id() || !is_simple_planar_polygon(b) {
+                continue;
+            }
+
+            let num_shared_edges = a
+                .region()
+                .exterior()
+                .half_edges()
+                .iter()
+                .filter_map(|half_edge| shell.get_sibling_of(half_edge))
+                .filter(|sibling| {
+                    faces_by_half_edge[&sibling.id()].id() == b.id()
+                })
+                .count();
+            if num_shared_edges != 1 || !are_coplanar(a, b) {
+                continue;
+            }
+
+            // The shared edge is dropped, and the remaining half-edges of both
+            // faces are joined into a single cycle. The half-edges of `b` need
+            // to be converted into the surface coordinates of `a`.
+            let half_edges_of_a = half_edges_after(a, half_edge);
+            let half_edges_of_b =
+                half_edges_after(b, &sibling).into_iter().map(|half_edge| {
+                    convert_to_surface(&half_edge, b, a).insert(services)
+                });
+
+            let exterior =
+                Cycle::new(half_edges_of_a.into_iter().chain(half_edges_of_b))
+                    .insert(services);
+            let merged = a
+                .update_region(|region| {
+                    region.update_exterior(|_| exterior).insert(services)
+                })
+                .insert(services);
+
+            let faces = shell
+                .faces()
+                .iter()
+                .filter(|face| face.id() != a.id() && face.id() != b.id())
+                .cloned()
+                .chain([merged]);
+
+            return Some(Shell::new(faces));
+        }
+    }
+
+    None
+}
+
+/// Merge the first pair of mergeable edges that can be found
+///
+/// Returns `None`, if no such pair exists.
+fn merge_collinear_edges(
+    shell: &Shell,
+    services: &mut Services,
+) -> Option<Shell> {
+    let faces_by_half_edge = faces_by_half_edge(shell);
+
+    for f in shell.faces() {
+        if !is_simple_planar_polygon(f) {
+            continue;
+        }
+
+        for (a, b) in f.region().exterior().half_edges().pairs() {
+            let (Some(sibling_a), Some(sibling_b)) =
+                (shell.get_sibling_of(a), shell.get_sibling_of(b))
+            else {
+                continue;
+            };
+
+            // The vertex between the two half-edges must only be shared with
+            // their siblings. This is the case, if the siblings are also
+            // neighbors, in the same face.
+            let g = &faces_by_half_edge[&sibling_a.id()];
+            if g.id() != faces_by_half_edge[&sibling_b.id()].id()
+                || g.region().exterior().half_edges().after(&sibling_b)
+                    != Some(&sibling_a)
+                || !is_simple_planar_polygon(g)
+            {
+                continue;
+            }
+
+            // Merging edges of a triangle, or of two faces that share more than
+            // one edge, would result in degenerate faces.
+            if f.id() == g.id()
+                || f.region().exterior().half_edges().len() <= 3
+                || g.region().exterior().half_edges().len() <= 3
+            {
+                continue;
+            }
+
+            let [start, middle, end] =
+                [a.start_position(), b.start_position(), end_position(b)].map(
+                    |point| {
+                        f.surface().geometry().point_from_surface_coords(point)
+                    },
+                );
+            if !are_collinear(start, middle, end) {
+                continue;
+            }
+
+            let merged = HalfEdge::line_segment(
+                [a.start_position(), end_position(b)],
+                None,
+                services,
+            )
+            .update_start_vertex(|_| a.start_vertex().clone())
+            .insert(services);
+            let merged_sibling = HalfEdge::line_segment(
+                [sibling_b.start_position(), end_position(&sibling_a)],
+                Some(merged.boundary().reverse().inner),
+                services,
+            )
+            .update_curve(|_| merged.curve().clone())
+            .update_start_vertex(|_| sibling_b.start_vertex().clone())
+            .insert(services);
+
+            let f_updated = replace_pair(f, [a, b], merged, services);
+            let g_updated = replace_pair(
+                g,
+                [&sibling_b, &sibling_a],
+                merged_sibling,
+                services,
+            );
+
+            let faces = shell.faces().iter().map(|face| {
+                if face.id() == f.id() {
+                    f_updated.clone()
+                } else if face.id() == g.id() {
+                    g_updated.clone()
+                } else {
+                    face.clone()
+                }
+            });
+
+            return Some(Shell::new(faces));
+        }
+    }
+
+    None
+}
+
+fn faces_by_half_edge(shell: &Shell) -> BTreeMap<ObjectId, Handle<Face>> {
+    let mut faces = BTreeMap::new();
+
+    for face in shell.faces() {
+        for cycle in face.region().all_cycles() {
+            for half_edge in cycle.half_edges() {
+                faces.insert(half_edge.id(), face.clone());
+            }
+        }
+    }
+
+    faces
+}
+
+fn is_simple_planar_polygon(face: &Face) -> bool {
+    let is_plane = matches!(face.surface().geometry().u, GlobalPath::Line(_));
+    let has_only_line_segments = face
+        .region()
+        .exterior()
+        .half_edges()
+        .iter()
+        .all(|half_edge| matches!(half_edge.path(), SurfacePath::Line(_)));
+
+    is_plane && has_only_line_segments && face.region().interiors().is_empty()
+}
+
+fn are_coplanar(a: &Face, b: &Face) -> bool {
+    let normal_a = oriented_normal(a);
+    let normal_b = oriented_normal(b);
+
+    let origin_a = a.surface().geometry().u.origin();
+    let origin_b = b.surface().geometry().u.origin();
+
+    (normal_a - normal_b).magnitude() < Scalar::from(EPSILON)
+        && normal_a.dot(&(origin_b - origin_a)).abs() < Scalar::from(EPSILON)
+}
+
+fn are_collinear(a: Point<3>, b: Point<3>, c: Point<3>) -> bool {
+    let ab = b - a;
+    let bc = c - b;
+
+    ab.cross(&bc).magnitude() < Scalar::from(EPSILON) * ab.magnitude()
+        && ab.dot(&bc) > Scalar::ZERO
+}
+
+/// Return all half-edges of the face's exterior that follow the provided one
+///
+/// The provided half-edge itself is not included.
+fn half_edges_after(
+    face: &Face,
+    half_edge: &Handle<HalfEdge>,
+) -> Vec<Handle<HalfEdge>> {
+    let half_edges = face.region().exterior().half_edges();
+    let index = half_edges
+        .index_of(half_edge)
+        .expect("Half-edge must be part of face");
+
+    (1..half_edges.len())
+        .map(|i| half_edges.nth_circular(index + i).clone())
+        .collect()
+}
+
+fn end_position(half_edge: &HalfEdge) -> Point<2> {
+    half_edge
+        .path()
+        .point_from_path_coords(half_edge.boundary().inner[1])
+}
+
+/// Convert a half-edge from the surface of one face to that of another
+///
+/// The faces must be coplanar, and the half-edge must be a line segment.
+fn convert_to_surface(
+    half_edge: &HalfEdge,
+    from: &Face,
+    to: &Face,
+) -> HalfEdge {
+    let boundary = half_edge.boundary().inner;
+    let points = boundary.map(|point| {
+        let point_surface = half_edge.path().point_from_path_coords(point);
+        let point_global = from
+            .surface()
+            .geometry()
+            .point_from_surface_coords(point_surface);
+        to.surface().geometry().project_global_point(point_global)
+    });
+    let path =
+        SurfacePath::line_from_points_with_coords(boundary.zip_ext(points));
+
+    HalfEdge::new(
+        path,
+        boundary,
+        half_edge.curve().clone(),
+        half_edge.start_vertex().clone(),
+    )
+}
+
+/// Replace two neighboring half-edges in a face's exterior with a single one
+fn replace_pair(
+    face: &Face,
+    [a, b]: [&Handle<HalfEdge>; 2],
+    merged: Handle<HalfEdge>,
+    services: &mut Services,
+) -> Handle<Face> {
+    let half_edges = face
+        .region()
+        .exterior()
+        .half_edges()
+        .iter()
+        .filter(|half_edge| half_edge.id() != b.id())
+        .map(|half_edge| {
+            if half_edge.id() == a.id() {
+                merged.clone()
+            } else {
+                half_edge.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    face.update_region(|region| {
+        region
+            .update_exterior(|_| Cycle::new(half_edges).insert(services))
+            .insert(services)
+    })
+    .insert(services)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        objects::{Shell, Solid},
+        operations::{
+            insert::Insert, primitives::BuildPrimitive, split::SplitFace,
+        },
+        services::Services,
+    };
+
+    use super::SimplifyShell;
+
+    #[test]
+    fn simplify_split_box() {
+        let mut services = Services::new();
+
+        let solid = Solid::box_from_dims([1., 1., 1.], &mut services);
+        let shell = solid.shells().first().clone();
+
+        let face = shell.faces().first().clone();
+        let corners = face
+            .region()
+            .exterior()
+            .half_edges()
+            .iter()
+            .map(|half_edge| half_edge.start_position())
+            .collect::<Vec<_>>();
+        let us = corners.iter().map(|corner| corner.u);
+        let min = us.clone().min().expect("Face has corners");
+        let max = us.max().expect("Face has corners");
+        let u = (min + max) / 2.;
+
+        let (split, _) = shell.split_face_along_line(
+            &face,
+            [
+                Point::from([u, Scalar::ZERO]),
+                Point::from([u, Scalar::ONE]),
+            ],
+            &mut services,
+        );
+        let split = split.insert(&mut services);
+        assert_eq!(split.faces().len(), 7);
+        assert_eq!(num_half_edges(&split), 30);
+
+        let simplified = split.simplify(&mut services).insert(&mut services);
+        assert_eq!(simplified.faces().len(), 6);
+        assert_eq!(num_half_edges(&simplified), 24);
+    }
+
+    fn num_half_edges(shell: &Shell) -> usize {
+        shell
+            .faces()
+            .iter()
+            .map(|face| face.region().exterior().half_edges().len())
+            .sum()
+    }
+}
@@ -1,3 +1,4 @@
+use fj_interop::ext::SliceExt;
 use fj_math::Point;
 
 use crate::{
@@ -25,6 +26,29 @@ pub trait SplitEdge: Sized {
         point: impl Into<Point<1>>,
         services: &mut Services,
     ) -> (Self, [[Handle<HalfEdge>; 2]; 2]);
+
+    /// Split the provided [`HalfEdge`], as well as its sibling, at all points
+    ///
+    /// This is a generalization of [`SplitEdge::split_edge`], which can split
+    /// the edge into any number of pieces. The points are given in curve
+    /// coordinates, and don't need to be sorted.
+    ///
+    /// Returns the pieces that replace the half-edge, and those that replace
+    /// its sibling, each in the order in which they appear in their cycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the provided half-edge is not a part of this shell.
+    ///
+    /// Panics, if any of the points is not strictly within the boundary of the
+    /// half-edge, or if points are duplicated.
+    #[must_use]
+    fn split_edge_at(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+        points: impl IntoIterator<Item = impl Into<Point<1>>>,
+        services: &mut Services,
+    ) -> (Self, [Vec<Handle<HalfEdge>>; 2]);
 }
 
 impl SplitEdge for Shell {
@@ -64,4 +88,124 @@ impl SplitEdge for Shell {
 
         (shell, [[half_edge_a, half_edge_b], siblings])
     }
+
+    fn split_edge_at(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+        points: impl IntoIterator<Item = impl Into<Point<1>>>,
+        services: &mut Services,
+    ) -> (Self, [Vec<Handle<HalfEdge>>; 2]) {
+        let [start, end] = half_edge.boundary().inner;
+
+        // Each split needs to happen on the piece that remains after the
+        // previous split, so the points must be ordered from start to end.
+        let mut points = points
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<Point<1>>>();
+        points.sort();
+        if start > end {
+            points.reverse();
+        }
+
+        for point in &points {
+            assert!(
+                start.min(end) < *point && *point < start.max(end),
+                "Point must be within boundary of half-edge"
+            );
+        }
+        for [a, b] in points.as_slice().array_windows_ext() {
+            assert_ne!(a, b, "Can't split edge at the same point twice");
+        }
+
+        let sibling = self
+            .get_sibling_of(half_edge)
+            .expect("Expected half-edge and its sibling to be part of shell");
+
+        let mut shell = self.clone();
+        let mut half_edges = Vec::new();
+        let mut remaining = half_edge.clone();
+
+        // The sibling pieces are collected in reverse order. The last one is
+        // always the sibling of the remaining piece, which is the one that's
+        // going to be split next.
+        let mut siblings = vec![sibling];
+
+        for point in points {
+            let (updated, [[a, b], [sibling_a, sibling_b]]) =
+                shell.split_edge(&remaining, point, services);
+
+            shell = updated;
+            half_edges.push(a);
+            remaining = b;
+
+            siblings.pop();
+            siblings.extend([sibling_b, sibling_a]);
+        }
+
+        half_edges.push(remaining);
+        siblings.reverse();
+
+        (shell, [half_edges, siblings])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use crate::{
+        operations::insert::Insert, queries::SiblingOfHalfEdge,
+        services::Services, test_support,
+    };
+
+    use super::SplitEdge;
+
+    #[test]
+    fn split_edge_at() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let shell = test_support::cube_shell(1., &mut services);
+        let half_edge = shell
+            .faces()
+            .first()
+            .region()
+            .exterior()
+            .half_edges()
+            .first()
+            .clone();
+
+        let [start, end] = half_edge.boundary().inner;
+        let at = |t: f64| Point::from([start.t + (end.t - start.t) * t]);
+
+        // The points don't need to be sorted.
+        let (shell, [half_edges, siblings]) = shell.split_edge_at(
+            &half_edge,
+            [at(0.75), at(0.25)],
+            &mut services,
+        );
+        let shell = shell.insert(&mut services);
+
+        let boundaries = half_edges
+            .iter()
+            .map(|half_edge| half_edge.boundary().inner)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            boundaries,
+            [[start, at(0.25)], [at(0.25), at(0.75)], [at(0.75), end],]
+        );
+
+        // The sibling pieces are in the order of their own cycle, which is the
+        // reverse of the other pieces' order.
+        for (half_edge, sibling) in half_edges.iter().zip(siblings.iter().rev())
+        {
+            assert_eq!(
+                shell.get_sibling_of(half_edge).map(|s| s.id()),
+                Some(sibling.id()),
+            );
+        }
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
 }
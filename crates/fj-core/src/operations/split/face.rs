@@ -3,6 +3,8 @@ use fj_math::Point;
 use itertools::Itertools;
 
 use crate::{
+    algorithms::intersect::CurveEdgeIntersection,
+    geometry::SurfacePath,
     objects::{Face, HalfEdge, Shell},
     operations::{
         build::{BuildFace, BuildHalfEdge},
@@ -41,6 +43,32 @@ pub trait SplitFace: Sized {
         line: [(&Handle<HalfEdge>, impl Into<Point<1>>); 2],
         services: &mut Services,
     ) -> (Self, [Handle<Face>; 2]);
+
+    /// Split the face into two, along a line
+    ///
+    /// The line is defined by two points, in surface coordinates of the
+    /// face's surface. It is extended infinitely in both directions, and must
+    /// intersect the exterior boundary of the face exactly twice. The face is
+    /// split at those intersections, using [`SplitFace::split_face`].
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the line doesn't intersect the face's exterior boundary
+    /// exactly twice, or if it passes through a vertex of the boundary.
+    ///
+    /// # Implementation Note
+    ///
+    /// Only lines are supported right now, and only faces that are bounded by
+    /// line segments. Splitting along arbitrary curves, or along a sketch that
+    /// is projected onto the face, requires more general intersection code,
+    /// and can be provided later.
+    #[must_use]
+    fn split_face_along_line(
+        &self,
+        face: &Handle<Face>,
+        line: [impl Into<Point<2>>; 2],
+        services: &mut Services,
+    ) -> (Self, [Handle<Face>; 2]);
 }
 
 impl SplitFace for Shell {
@@ -169,4 +197,100 @@ impl SplitFace for Shell {
 
         (self_, faces)
     }
+
+    fn split_face_along_line(
+        &self,
+        face: &Handle<Face>,
+        line: [impl Into<Point<2>>; 2],
+        services: &mut Services,
+    ) -> (Self, [Handle<Face>; 2]) {
+        let (path, _) = SurfacePath::line_from_points(line);
+
+        let mut intersections = Vec::new();
+        for half_edge in face.region().exterior().half_edges() {
+            let point_on_curve =
+                match CurveEdgeIntersection::compute(&path, half_edge) {
+                    Some(CurveEdgeIntersection::Point { point_on_curve }) => {
+                        point_on_curve
+                    }
+                    Some(CurveEdgeIntersection::Coincident { .. }) => {
+                        panic!("Line coincides with boundary of face")
+                    }
+                    None => continue,
+                };
+
+            let SurfacePath::Line(edge_line) = half_edge.path() else {
+                unreachable!(
+                    "Intersection only computed for edges that are lines"
+                );
+            };
+            let point_on_edge = edge_line.point_to_line_coords(
+                path.point_from_path_coords(point_on_curve),
+            );
+
+            assert!(
+                !half_edge.boundary().inner.contains(&point_on_edge),
+                "Line passes through vertex of face boundary"
+            );
+
+            intersections.push((half_edge, point_on_edge));
+        }
+
+        assert_eq!(
+            intersections.len(),
+            2,
+            "Expected line to intersect face boundary exactly twice"
+        );
+        let [a, b] = [intersections[0], intersections[1]];
+
+        self.split_face(face, [a, b], services)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use crate::{operations::insert::Insert, services::Services, test_support};
+
+    use super::SplitFace;
+
+    #[test]
+    fn split_face_along_line() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let shell = test_support::cube_shell(1., &mut services);
+        let face = shell.faces().first().clone();
+
+        // Split the face along the line between the midpoints of two opposite
+        // edges.
+        let line = {
+            let positions = face
+                .region()
+                .exterior()
+                .half_edges()
+                .iter()
+                .map(|half_edge| half_edge.start_position())
+                .collect::<Vec<_>>();
+            let midpoint = |a: Point<2>, b: Point<2>| a + (b - a) * 0.5;
+
+            [
+                midpoint(positions[0], positions[1]),
+                midpoint(positions[2], positions[3]),
+            ]
+        };
+
+        let (shell, faces) =
+            shell.split_face_along_line(&face, line, &mut services);
+        let shell = shell.insert(&mut services);
+
+        assert_eq!(shell.faces().len(), 7);
+        for face in faces {
+            assert!(shell.faces().contains(&face));
+            assert_eq!(face.region().exterior().half_edges().len(), 4);
+        }
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
 }
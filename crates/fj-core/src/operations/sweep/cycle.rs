@@ -72,6 +72,15 @@ impl SweepCycle for Cycle {
                 services,
             );
 
+            services.record_derivation(
+                side_face.region().clone(),
+                [bottom_half_edge.clone()],
+            );
+            services.record_derivation(
+                top_edge.clone(),
+                [bottom_half_edge.clone()],
+            );
+
             faces.push(side_face);
 
             top_edges.push((
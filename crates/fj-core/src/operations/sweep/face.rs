@@ -51,12 +51,30 @@ impl SweepFace for Handle<Face> {
         let bottom_face = self.clone();
         faces.push(bottom_face.clone());
 
-        let side_faces = bottom_face
-            .region()
-            .sweep_region(bottom_face.surface(), path, cache, services)
-            .all_faces()
-            .map(|side_face| side_face.insert(services));
-        faces.extend(side_faces);
+        let swept_region = bottom_face.region().sweep_region(
+            bottom_face.surface(),
+            path,
+            cache,
+            services,
+        );
+
+        for side_face in swept_region.side_faces {
+            // The sweep has recorded what the region of each side face was
+            // derived from. That applies to the face as a whole.
+            let sources = services
+                .provenance
+                .sources_of(side_face.region().id())
+                .to_vec();
+
+            let side_face = side_face.insert(services);
+            services.record_derivation(side_face.clone(), sources);
+
+            faces.push(side_face);
+        }
+
+        let top_face = swept_region.top_face.insert(services);
+        services.record_derivation(top_face.clone(), [bottom_face.clone()]);
+        faces.push(top_face);
 
         Shell::new(faces)
     }
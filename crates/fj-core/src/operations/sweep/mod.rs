@@ -1,13 +1,15 @@
 //! Sweep objects along a path to create new objects
 //!
 //! Sweeps 1D or 2D objects along a straight path, creating a 2D or 3D object,
-//! respectively.
+//! respectively. Faces and sketches can also be revolved around an axis, which
+//! is how turned parts, like shafts and flanges, are created.
 
 mod cycle;
 mod face;
 mod half_edge;
 mod path;
 mod region;
+mod revolve;
 mod shell_face;
 mod sketch;
 mod vertex;
@@ -18,6 +20,7 @@ pub use self::{
     half_edge::SweepHalfEdge,
     path::SweepSurfacePath,
     region::{SweepRegion, SweptRegion},
+    revolve::{RevolveError, RevolveFace, RevolveSketch},
     shell_face::SweepFaceOfShell,
     sketch::SweepSketch,
     vertex::SweepVertex,
@@ -1,4 +1,4 @@
-use fj_math::{Circle, Line, Vector};
+use fj_math::{Involute, Line, Spiral, Vector};
 
 use crate::{
     geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
@@ -35,7 +35,10 @@ impl SweepSurfacePath for SurfacePath {
         path: impl Into<Vector<3>>,
     ) -> Surface {
         match surface.geometry().u {
-            GlobalPath::Circle(_) => {
+            GlobalPath::Circle(_)
+            | GlobalPath::Ellipse(_)
+            | GlobalPath::Spiral(_)
+            | GlobalPath::Involute(_) => {
                 // Sweeping a `Curve` creates a `Surface`. The u-axis of that
                 // `Surface` is a `GlobalPath`, which we are computing below.
                 // That computation might or might not work with an arbitrary
@@ -68,9 +71,10 @@ impl SweepSurfacePath for SurfacePath {
                 let b =
                     surface.geometry().vector_from_surface_coords(circle.b());
 
-                let circle = Circle::new(center, a, b);
-
-                GlobalPath::Circle(circle)
+                // The surface might be scaled differently in its two
+                // directions, in which case the circle becomes an ellipse in
+                // global coordinates.
+                GlobalPath::ellipse_from_center_and_axes(center, a, b)
             }
             SurfacePath::Line(line) => {
                 let origin =
@@ -83,6 +87,34 @@ impl SweepSurfacePath for SurfacePath {
 
                 GlobalPath::Line(line)
             }
+            SurfacePath::Spiral(spiral) => {
+                let center = surface
+                    .geometry()
+                    .point_from_surface_coords(spiral.center());
+                let a =
+                    surface.geometry().vector_from_surface_coords(spiral.a());
+                let b =
+                    surface.geometry().vector_from_surface_coords(spiral.b());
+
+                GlobalPath::Spiral(Spiral::new(
+                    center,
+                    a,
+                    b,
+                    spiral.start(),
+                    spiral.growth(),
+                ))
+            }
+            SurfacePath::Involute(involute) => {
+                let center = surface
+                    .geometry()
+                    .point_from_surface_coords(involute.center());
+                let a =
+                    surface.geometry().vector_from_surface_coords(involute.a());
+                let b =
+                    surface.geometry().vector_from_surface_coords(involute.b());
+
+                GlobalPath::Involute(Involute::new(center, a, b))
+            }
         };
 
         Surface::new(SurfaceGeometry { u, v: path.into() })
@@ -21,6 +21,11 @@ pub trait SweepRegion {
     ///
     /// Sweep the region into multiple sets of faces. Each set of faces is
     /// formed by sweeping one of the region's cycles, then adding a top face.
+    /// Sweeping the interior cycles results in the inner walls of the holes
+    /// that they represent. The top face has the same holes.
+    ///
+    /// Expects the interior cycles to be wound opposite to the exterior cycle,
+    /// as is required for any valid region.
     ///
     /// Requires the surface that the face that the region belongs to is defined
     /// in.
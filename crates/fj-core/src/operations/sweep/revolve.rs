@@ -0,0 +1,1156 @@
+use std::{collections::BTreeMap, slice};
+
+use fj_interop::{ext::ArrayExt, mesh::Color};
+use fj_math::{Circle, Line, Point, Scalar, Transform, Vector};
+
+use crate::{
+    geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{
+        Curve, Cycle, Face, HalfEdge, Region, Shell, Sketch, Solid, Surface,
+        Vertex,
+    },
+    operations::{insert::Insert, reverse::Reverse},
+    services::Services,
+    storage::{Handle, ObjectId},
+};
+
+use super::sketch::normalize_winding;
+
+/// # Revolve a [`Face`]
+///
+/// See [module documentation] for more information.
+///
+/// [module documentation]: super
+pub trait RevolveFace {
+    /// # Revolve the [`Face`] around an axis
+    ///
+    /// The face is rotated around `axis` by `angle`, in radians, following the
+    /// right-hand rule around the direction of the axis. A negative angle
+    /// revolves in the opposite direction.
+    ///
+    /// Revolving by a full turn (`2π`) results in a closed ring, without any
+    /// face at the start or end of the revolution. Smaller angles result in a
+    /// shell that includes the face itself, and a rotated copy of it.
+    ///
+    /// The axis must lie in the plane of the face, and must not cross it. It
+    /// may touch the face though, which results in a solid without a hole.
+    ///
+    /// ## Implementation Note
+    ///
+    /// Only faces whose edges are line segments can be revolved. Curved edges
+    /// would result in tori and other surfaces, which the kernel can't
+    /// represent yet.
+    ///
+    /// Line segments that are parallel or perpendicular to the axis result in
+    /// cylinders and planes, which are represented exactly. Sloped line
+    /// segments result in cones, which are approximated by planar facets, like
+    /// the ones built by [`BuildPrimitive::cone`]. If a face has any sloped
+    /// edges, its whole revolution is approximated this way, so the facets
+    /// line up with their neighbors.
+    ///
+    /// [`BuildPrimitive::cone`]: crate::operations::primitives::BuildPrimitive::cone
+    fn revolve_face(
+        &self,
+        axis: Line<3>,
+        angle: impl Into<Scalar>,
+        services: &mut Services,
+    ) -> Result<Shell, RevolveError>;
+}
+
+impl RevolveFace for Handle<Face> {
+    fn revolve_face(
+        &self,
+        axis: Line<3>,
+        angle: impl Into<Scalar>,
+        services: &mut Services,
+    ) -> Result<Shell, RevolveError> {
+        let mut shells =
+            revolve_faces(slice::from_ref(self), axis, angle.into(), services)?;
+        Ok(shells.remove(0))
+    }
+}
+
+/// # Revolve a [`Sketch`]
+///
+/// See [module documentation] for more information.
+///
+/// [module documentation]: super
+pub trait RevolveSketch {
+    /// # Revolve the [`Sketch`] around an axis
+    ///
+    /// Each region of the sketch is revolved into its own shell. See
+    /// [`RevolveFace::revolve_face`] for the requirements on the regions, the
+    /// axis, and the angle.
+    ///
+    /// The cycles of the sketch's regions may be wound in either direction.
+    /// They are reversed as required, to make sure the resulting shells point
+    /// outward.
+    fn revolve_sketch(
+        &self,
+        surface: Handle<Surface>,
+        axis: Line<3>,
+        angle: impl Into<Scalar>,
+        services: &mut Services,
+    ) -> Result<Solid, RevolveError>;
+}
+
+impl RevolveSketch for Sketch {
+    fn revolve_sketch(
+        &self,
+        surface: Handle<Surface>,
+        axis: Line<3>,
+        angle: impl Into<Scalar>,
+        services: &mut Services,
+    ) -> Result<Solid, RevolveError> {
+        let _span = tracing::info_span!("revolve").entered();
+
+        let faces = self
+            .regions()
+            .iter()
+            .map(|region| {
+                Face::new(surface.clone(), region.clone()).insert(services)
+            })
+            .collect::<Vec<_>>();
+        let shells = revolve_faces(&faces, axis, angle.into(), services)?
+            .into_iter()
+            .map(|shell| shell.insert(services));
+
+        Ok(Solid::new(shells))
+    }
+}
+
+/// Error revolving a face or sketch
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum RevolveError {
+    /// The angle is zero, or larger than a full turn
+    #[error("Can't revolve by `{angle}` radians")]
+    InvalidAngle {
+        /// The angle
+        angle: Scalar,
+    },
+
+    /// The face is defined on a curved surface
+    #[error("Revolving a face on a curved surface is not supported")]
+    CurvedSurface,
+
+    /// The axis does not lie in the plane of the face
+    #[error("Axis of revolution is not in the plane of the face")]
+    AxisNotInPlane,
+
+    /// The axis crosses the face, or the face lies entirely on the axis
+    #[error("Face is not entirely on one side of the axis of revolution")]
+    AxisCrossesFace,
+
+    /// An edge of the face would result in a surface that can't be represented
+    #[error(
+        "Can't revolve edge from `{start:?}` to `{end:?}`; only line segments \
+        are supported"
+    )]
+    UnsupportedEdge {
+        /// The point where the edge starts
+        start: Point<3>,
+
+        /// The point where the edge ends
+        end: Point<3>,
+    },
+}
+
+/// A cache used for revolving
+///
+/// Makes sure that faces which share vertices or curves end up sharing the
+/// objects created from them.
+#[derive(Default)]
+struct RevolveCache {
+    /// The curves of the arcs that vertices are revolved along
+    arcs: BTreeMap<ObjectId, Handle<Curve>>,
+
+    /// The copies of curves at the end of the revolution
+    curves: BTreeMap<ObjectId, Handle<Curve>>,
+
+    /// The copies of vertices at the end of the revolution
+    vertices: BTreeMap<ObjectId, Handle<Vertex>>,
+
+    /// The copies of vertices at each step of a faceted revolution
+    facet_vertices: BTreeMap<ObjectId, Vec<Handle<Vertex>>>,
+
+    /// The curves between the copies of vertices, in a faceted revolution
+    facet_arcs: BTreeMap<ObjectId, Vec<Handle<Curve>>>,
+
+    /// The copies of curves at each step of a faceted revolution
+    facet_curves: BTreeMap<ObjectId, Vec<Handle<Curve>>>,
+}
+
+/// The number of facets that a faceted revolution has per full turn
+const SEGMENTS_PER_TURN: usize = 32;
+
+fn revolve_faces(
+    faces: &[Handle<Face>],
+    axis: Line<3>,
+    angle: Scalar,
+    services: &mut Services,
+) -> Result<Vec<Shell>, RevolveError> {
+    let mut revolutions = faces
+        .iter()
+        .map(|face| {
+            Revolution::new(face, face.surface().geometry(), axis, angle)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The faces of a sketch can share vertices and curves. If any of them
+    // needs to be approximated, all of them need to be, or the faces that
+    // share those would end up with geometry that doesn't match.
+    let mut is_faceted = false;
+    for (face, revolution) in faces.iter().zip(&revolutions) {
+        is_faceted |= revolution.has_sloped_edges(face)?;
+    }
+    if is_faceted {
+        for revolution in &mut revolutions {
+            revolution.is_faceted = true;
+        }
+    }
+
+    let mut cache = RevolveCache::default();
+    Ok(faces
+        .iter()
+        .zip(&revolutions)
+        .map(|(face, revolution)| {
+            revolve_face(face, revolution, &mut cache, services)
+        })
+        .collect())
+}
+
+fn revolve_face(
+    face: &Handle<Face>,
+    revolution: &Revolution,
+    cache: &mut RevolveCache,
+    services: &mut Services,
+) -> Shell {
+    let surface = face.surface().geometry();
+
+    // The following code assumes that the start face points away from the
+    // direction of the revolution, and that its exterior is wound
+    // counter-clockwise, its interiors clockwise. Let's make sure that's the
+    // case, instead of requiring it from the caller.
+    let region = {
+        let region = normalize_winding(face.region(), services);
+
+        if revolution.normal.dot(&revolution.tangent) > Scalar::ZERO {
+            region.reverse(services).insert(services)
+        } else {
+            region
+        }
+    };
+
+    let mut faces = Vec::new();
+
+    let start_face = if region.id() == face.region().id() {
+        face.clone()
+    } else {
+        Face::new(face.surface().clone(), region.clone()).insert(services)
+    };
+    if !revolution.is_full_turn {
+        faces.push(start_face.clone());
+    }
+
+    for cycle in region.all_cycles() {
+        for (half_edge, next) in cycle.half_edges().pairs() {
+            let side_faces = revolution.revolve_half_edge(
+                half_edge,
+                next,
+                &surface,
+                region.color(),
+                cache,
+                services,
+            );
+
+            for side_face in side_faces {
+                let side_face = side_face.insert(services);
+                services
+                    .record_derivation(side_face.clone(), [half_edge.clone()]);
+
+                faces.push(side_face);
+            }
+        }
+    }
+
+    if !revolution.is_full_turn {
+        let end_face = revolution
+            .end_face(&start_face, cache, services)
+            .insert(services);
+        services.record_derivation(end_face.clone(), [start_face]);
+
+        faces.push(end_face);
+    }
+
+    Shell::new(faces)
+}
+
+/// A revolution around an axis, relative to a specific face
+struct Revolution {
+    /// A point on the axis
+    origin: Point<3>,
+
+    /// The normalized direction of the axis
+    axis: Vector<3>,
+
+    /// The normalized direction from the axis towards the face
+    radial: Vector<3>,
+
+    /// The normalized direction that the face starts moving in
+    tangent: Vector<3>,
+
+    /// The normal of the surface that the face is defined on
+    normal: Vector<3>,
+
+    /// The angle of the revolution, which is always positive
+    angle: Scalar,
+
+    /// Whether the revolution is a full turn
+    is_full_turn: bool,
+
+    /// The distance below which points are considered to be identical
+    tolerance: Scalar,
+
+    /// Whether the revolution is approximated by planar facets
+    is_faceted: bool,
+}
+
+impl Revolution {
+    fn new(
+        face: &Face,
+        surface: SurfaceGeometry,
+        axis: Line<3>,
+        angle: Scalar,
+    ) -> Result<Self, RevolveError> {
+        const RELATIVE_EPSILON: f64 = 1e-9;
+
+        let GlobalPath::Line(line) = surface.u else {
+            return Err(RevolveError::CurvedSurface);
+        };
+        let normal = line.direction().cross(&surface.v).normalize();
+
+        // Revolving in the negative direction is the same as revolving in the
+        // positive direction, around the reversed axis.
+        let (direction, positive_angle) = if angle < Scalar::ZERO {
+            (-axis.direction(), -angle)
+        } else {
+            (axis.direction(), angle)
+        };
+        let full_turn_epsilon = Scalar::TAU * RELATIVE_EPSILON;
+        if positive_angle == Scalar::ZERO
+            || positive_angle > Scalar::TAU + full_turn_epsilon
+        {
+            return Err(RevolveError::InvalidAngle { angle });
+        }
+        let is_full_turn =
+            (Scalar::TAU - positive_angle).abs() <= full_turn_epsilon;
+
+        let origin = axis.origin();
+        let axis = direction.normalize();
+
+        let points = face
+            .region()
+            .all_cycles()
+            .flat_map(|cycle| cycle.half_edges().iter())
+            .map(|half_edge| {
+                surface.point_from_surface_coords(half_edge.start_position())
+            })
+            .collect::<Vec<_>>();
+        let size = points
+            .iter()
+            .map(|point| (point - origin).magnitude())
+            .fold(Scalar::ZERO, Scalar::max);
+        let tolerance = size * RELATIVE_EPSILON;
+
+        if normal.dot(&axis).abs() > Scalar::from(RELATIVE_EPSILON)
+            || normal.dot(&(origin - line.origin())).abs() > tolerance
+        {
+            return Err(RevolveError::AxisNotInPlane);
+        }
+
+        let radial_vector = |point: &Point<3>| {
+            let offset = point - origin;
+            offset - axis * offset.dot(&axis)
+        };
+        let Some(furthest) = points
+            .iter()
+            .map(radial_vector)
+            .max_by_key(|radial| radial.magnitude())
+            .filter(|radial| radial.magnitude() > tolerance)
+        else {
+            return Err(RevolveError::AxisCrossesFace);
+        };
+        let radial = furthest.normalize();
+
+        if points
+            .iter()
+            .any(|point| radial_vector(point).dot(&radial) < -tolerance)
+        {
+            return Err(RevolveError::AxisCrossesFace);
+        }
+
+        Ok(Self {
+            origin,
+            axis,
+            radial,
+            tangent: axis.cross(&radial),
+            normal,
+            angle: if is_full_turn {
+                Scalar::TAU
+            } else {
+                positive_angle
+            },
+            is_full_turn,
+            tolerance,
+            is_faceted: false,
+        })
+    }
+
+    /// Indicate whether the face has edges that are sloped relative to the
+    /// axis, meaning the revolution needs to be approximated by facets
+    ///
+    /// Returns an error, if any edge can't be revolved at all.
+    fn has_sloped_edges(&self, face: &Face) -> Result<bool, RevolveError> {
+        let surface = face.surface().geometry();
+
+        let mut has_sloped_edges = false;
+        for cycle in face.region().all_cycles() {
+            for (half_edge, next) in cycle.half_edges().pairs() {
+                let [start, end] = [half_edge, next].map(|half_edge| {
+                    surface
+                        .point_from_surface_coords(half_edge.start_position())
+                });
+
+                let SurfacePath::Line(_) = half_edge.path() else {
+                    return Err(RevolveError::UnsupportedEdge { start, end });
+                };
+
+                let is_parallel = (self.radius(end) - self.radius(start)).abs()
+                    <= self.tolerance;
+                let is_perpendicular = (self.height(end) - self.height(start))
+                    .abs()
+                    <= self.tolerance;
+
+                has_sloped_edges |= !is_parallel && !is_perpendicular;
+            }
+        }
+
+        Ok(has_sloped_edges)
+    }
+
+    /// The number of planar facets that each half-edge is revolved into, if
+    /// the revolution is faceted
+    fn num_segments(&self) -> usize {
+        let turns = (self.angle / Scalar::TAU).into_f64();
+        let segments = (turns * SEGMENTS_PER_TURN as f64).ceil() as usize;
+        segments.max(1)
+    }
+
+    fn radius(&self, point: Point<3>) -> Scalar {
+        (point - self.origin).dot(&self.radial)
+    }
+
+    fn height(&self, point: Point<3>) -> Scalar {
+        (point - self.origin).dot(&self.axis)
+    }
+
+    fn center(&self, point: Point<3>) -> Point<3> {
+        self.origin + self.axis * self.height(point)
+    }
+
+    fn is_on_axis(&self, point: Point<3>) -> bool {
+        self.radius(point) <= self.tolerance
+    }
+
+    fn transform(&self) -> Transform {
+        self.rotation(self.angle)
+    }
+
+    fn rotation(&self, angle: Scalar) -> Transform {
+        Transform::translation(self.origin.coords)
+            * Transform::rotation(self.axis * angle)
+            * Transform::translation(-self.origin.coords)
+    }
+
+    /// Revolve a half-edge into faces
+    ///
+    /// Returns a single face, unless the revolution is approximated by
+    /// facets. Returns no faces, if the half-edge lies on the axis, as it
+    /// doesn't result in any then.
+    fn revolve_half_edge(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+        next: &Handle<HalfEdge>,
+        surface: &SurfaceGeometry,
+        color: Option<Color>,
+        cache: &mut RevolveCache,
+        services: &mut Services,
+    ) -> Vec<Face> {
+        let [start, end] = [half_edge, next].map(|half_edge| {
+            surface.point_from_surface_coords(half_edge.start_position())
+        });
+        let [r_a, r_b] = [start, end].map(|point| self.radius(point));
+
+        let is_parallel = (r_b - r_a).abs() <= self.tolerance;
+
+        if is_parallel && (self.is_on_axis(start) || self.is_on_axis(end)) {
+            return Vec::new();
+        }
+        if self.is_faceted {
+            return self.facets(
+                half_edge,
+                [start, end],
+                next.start_vertex(),
+                color,
+                cache,
+                services,
+            );
+        }
+
+        // Please note that this method uses the following names for the
+        // corners of the resulting face:
+        //
+        // - `a` and `b` are the start and end of the half-edge.
+        // - `a_end` and `b_end` are where those end up, at the end of the
+        //   revolution.
+        //
+        // Any of those that lie on the axis, don't move, and there is no arc
+        // for them to be revolved along.
+        let a = half_edge.start_vertex().clone();
+        let b = next.start_vertex().clone();
+        let a_end =
+            self.end_vertex(&a, self.is_on_axis(start), cache, services);
+        let b_end = self.end_vertex(&b, self.is_on_axis(end), cache, services);
+
+        let [arc_a, arc_b] = [(&a, start), (&b, end)].map(|(vertex, point)| {
+            (!self.is_on_axis(point)).then(|| self.arc(vertex, cache, services))
+        });
+
+        let curve = half_edge.curve().clone();
+        let curve_end = self.end_curve(&curve, false, cache, services);
+
+        let [t_a, t_b] = half_edge.boundary().inner;
+        let [zero, angle] =
+            [Scalar::ZERO, self.angle].map(|coord| Point::from([coord]));
+
+        let (surface, region) = if is_parallel {
+            // The half-edge revolves into a cylinder, which we represent as a
+            // circle, swept along the half-edge. The circle's coordinates are
+            // angles, so the coordinates of the face's corners are simple.
+            let center = self.center(start);
+            let radius = start - center;
+            let u = GlobalPath::ellipse_from_center_and_axes(
+                center,
+                radius,
+                self.axis.cross(&radius),
+            );
+
+            let surface = Surface::new(SurfaceGeometry { u, v: end - start })
+                .insert(services);
+
+            let [p_a, p_b, p_b_end, p_a_end] = [
+                [Scalar::ZERO, Scalar::ZERO],
+                [Scalar::ZERO, Scalar::ONE],
+                [self.angle, Scalar::ONE],
+                [self.angle, Scalar::ZERO],
+            ]
+            .map(Point::from);
+
+            let mut half_edges =
+                vec![line_segment([p_b, p_a], [t_b, t_a], curve, b)];
+            if let Some(arc_a) = arc_a {
+                half_edges.push(line_segment(
+                    [p_a, p_a_end],
+                    [zero, angle],
+                    arc_a,
+                    a,
+                ));
+            }
+            half_edges.push(line_segment(
+                [p_a_end, p_b_end],
+                [t_a, t_b],
+                curve_end,
+                a_end,
+            ));
+            if let Some(arc_b) = arc_b {
+                half_edges.push(line_segment(
+                    [p_b_end, p_b],
+                    [angle, zero],
+                    arc_b,
+                    b_end,
+                ));
+            }
+
+            let exterior = Cycle::new(
+                half_edges
+                    .into_iter()
+                    .map(|half_edge| half_edge.insert(services)),
+            )
+            .insert(services);
+
+            (surface, Region::new(exterior, [], color))
+        } else {
+            // The half-edge revolves into a flat ring or disk, or a sector of
+            // one. The plane it lies in is oriented such that the face's
+            // exterior is always counter-clockwise.
+            let sign = if r_b < r_a { Scalar::ONE } else { -Scalar::ONE };
+
+            let surface = Surface::new(SurfaceGeometry {
+                u: GlobalPath::Line(Line::from_origin_and_direction(
+                    self.center(start),
+                    self.radial,
+                )),
+                v: self.tangent * sign,
+            })
+            .insert(services);
+
+            let arc_path = |radius: Scalar| {
+                SurfacePath::Circle(Circle::new(
+                    Point::origin(),
+                    [radius, Scalar::ZERO],
+                    [Scalar::ZERO, radius * sign],
+                ))
+            };
+
+            if self.is_full_turn {
+                let mut cycles = [
+                    (r_a, arc_a, a, [zero, angle]),
+                    (r_b, arc_b, b, [angle, zero]),
+                ]
+                .into_iter()
+                .filter_map(|(radius, arc, vertex, boundary)| {
+                    let arc = arc?;
+                    let half_edge =
+                        HalfEdge::new(arc_path(radius), boundary, arc, vertex)
+                            .insert(services);
+
+                    Some((radius, Cycle::new([half_edge]).insert(services)))
+                })
+                .collect::<Vec<_>>();
+
+                // The outer circle is the exterior, the inner one (if any)
+                // the interior.
+                cycles.sort_by_key(|(radius, _)| -*radius);
+                let mut cycles = cycles.into_iter().map(|(_, cycle)| cycle);
+
+                let exterior = cycles.next().expect(
+                    "Half-edge is not on the axis, so at least one of its \
+                    vertices is not",
+                );
+                (surface, Region::new(exterior, cycles, color))
+            } else {
+                let point = |radius: Scalar, angle: Scalar| {
+                    let (sin, cos) = angle.sin_cos();
+                    Point::from([radius * cos, radius * sin * sign])
+                };
+                let p_a = point(r_a, Scalar::ZERO);
+                let p_b = point(r_b, Scalar::ZERO);
+                let p_a_end = point(r_a, self.angle);
+                let p_b_end = point(r_b, self.angle);
+
+                let mut half_edges =
+                    vec![line_segment([p_b, p_a], [t_b, t_a], curve, b)];
+                if let Some(arc_a) = arc_a {
+                    half_edges.push(HalfEdge::new(
+                        arc_path(r_a),
+                        [zero, angle],
+                        arc_a,
+                        a,
+                    ));
+                }
+                half_edges.push(line_segment(
+                    [p_a_end, p_b_end],
+                    [t_a, t_b],
+                    curve_end,
+                    a_end,
+                ));
+                if let Some(arc_b) = arc_b {
+                    half_edges.push(HalfEdge::new(
+                        arc_path(r_b),
+                        [angle, zero],
+                        arc_b,
+                        b_end,
+                    ));
+                }
+
+                let exterior = Cycle::new(
+                    half_edges
+                        .into_iter()
+                        .map(|half_edge| half_edge.insert(services)),
+                )
+                .insert(services);
+
+                (surface, Region::new(exterior, [], color))
+            }
+        };
+
+        vec![Face::new(surface, region.insert(services))]
+    }
+
+    /// Revolve a half-edge into planar facets
+    ///
+    /// Each facet connects the half-edge at one step of the revolution to the
+    /// half-edge at the next. Where the half-edge is sloped relative to the
+    /// axis, this approximates a cone.
+    fn facets(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+        [start, end]: [Point<3>; 2],
+        end_vertex: &Handle<Vertex>,
+        color: Option<Color>,
+        cache: &mut RevolveCache,
+        services: &mut Services,
+    ) -> Vec<Face> {
+        // This method uses the same names for the corners of the facets as
+        // `revolve_half_edge`, with `a` and `b` being the start and end of the
+        // half-edge. Here, they refer to the copies of those at each step.
+        let segments = self.num_segments();
+        let a = half_edge.start_vertex();
+        let b = end_vertex;
+        let vertices_a = self.facet_vertices(
+            a,
+            self.is_on_axis(start),
+            segments,
+            cache,
+            services,
+        );
+        let vertices_b = self.facet_vertices(
+            b,
+            self.is_on_axis(end),
+            segments,
+            cache,
+            services,
+        );
+        let [arcs_a, arcs_b] = [(a, start), (b, end)].map(|(vertex, point)| {
+            (!self.is_on_axis(point))
+                .then(|| self.facet_arcs(vertex, segments, cache, services))
+        });
+        let curves =
+            self.facet_curves(half_edge.curve(), segments, cache, services);
+
+        let [t_a, t_b] = half_edge.boundary().inner;
+        let [r_a, r_b] = [start, end].map(|point| {
+            if self.is_on_axis(point) {
+                Scalar::ZERO
+            } else {
+                self.radius(point)
+            }
+        });
+
+        // The distance that a point at the given radius moves in each step.
+        let step = self.angle / Scalar::from(segments as f64);
+        let (sin, _) = (step / 2.).sin_cos();
+        let chord = |radius: Scalar| radius * sin * 2.;
+
+        (0..segments)
+            .map(|i| {
+                let [angle, next_angle] = [i, i + 1]
+                    .map(|i| Point::from([step * Scalar::from(i as f64)]));
+
+                // The facet is oriented like the cylinder that a parallel
+                // half-edge revolves into: Its u-axis points in the direction
+                // of the revolution, its v-axis along the half-edge.
+                let rotation = self.rotation(angle.t);
+                let origin = rotation.transform_point(&start);
+                let direction = self
+                    .rotation(angle.t + step / 2.)
+                    .transform_vector(&self.tangent);
+                let surface = Surface::new(SurfaceGeometry {
+                    u: GlobalPath::Line(Line::from_origin_and_direction(
+                        origin, direction,
+                    )),
+                    v: rotation.transform_point(&end) - origin,
+                })
+                .insert(services);
+
+                let [p_a, p_b, p_b_next, p_a_next] = [
+                    [Scalar::ZERO, Scalar::ZERO],
+                    [Scalar::ZERO, Scalar::ONE],
+                    [chord(r_b), Scalar::ONE],
+                    [chord(r_a), Scalar::ZERO],
+                ]
+                .map(Point::from);
+
+                let mut half_edges = vec![line_segment(
+                    [p_b, p_a],
+                    [t_b, t_a],
+                    curves[i].clone(),
+                    vertices_b[i].clone(),
+                )];
+                if let Some(arcs_a) = &arcs_a {
+                    half_edges.push(line_segment(
+                        [p_a, p_a_next],
+                        [angle, next_angle],
+                        arcs_a[i].clone(),
+                        vertices_a[i].clone(),
+                    ));
+                }
+                half_edges.push(line_segment(
+                    [p_a_next, p_b_next],
+                    [t_a, t_b],
+                    curves[i + 1].clone(),
+                    vertices_a[i + 1].clone(),
+                ));
+                if let Some(arcs_b) = &arcs_b {
+                    half_edges.push(line_segment(
+                        [p_b_next, p_b],
+                        [next_angle, angle],
+                        arcs_b[i].clone(),
+                        vertices_b[i + 1].clone(),
+                    ));
+                }
+
+                let exterior = Cycle::new(
+                    half_edges
+                        .into_iter()
+                        .map(|half_edge| half_edge.insert(services)),
+                )
+                .insert(services);
+
+                Face::new(
+                    surface,
+                    Region::new(exterior, [], color).insert(services),
+                )
+            })
+            .collect()
+    }
+
+    /// Create the face at the end of the revolution, from the one at the start
+    fn end_face(
+        &self,
+        start_face: &Face,
+        cache: &mut RevolveCache,
+        services: &mut Services,
+    ) -> Face {
+        let region = start_face.region();
+        let surface = start_face.surface().geometry();
+
+        let exterior =
+            self.end_cycle(region.exterior(), &surface, cache, services);
+        let interiors = region
+            .interiors()
+            .iter()
+            .map(|cycle| self.end_cycle(cycle, &surface, cache, services))
+            .collect::<Vec<_>>();
+
+        // The end face needs to point in the direction of the revolution, the
+        // opposite of the start face.
+        let region = Region::new(exterior, interiors, region.color())
+            .reverse(services)
+            .insert(services);
+        let surface =
+            Surface::new(surface.transform(&self.transform())).insert(services);
+
+        Face::new(surface, region)
+    }
+
+    fn end_cycle(
+        &self,
+        cycle: &Cycle,
+        surface: &SurfaceGeometry,
+        cache: &mut RevolveCache,
+        services: &mut Services,
+    ) -> Handle<Cycle> {
+        let half_edges = cycle
+            .half_edges()
+            .pairs()
+            .map(|(half_edge, next)| {
+                let [start, end] = [half_edge, next].map(|half_edge| {
+                    surface
+                        .point_from_surface_coords(half_edge.start_position())
+                });
+
+                let curve = self.end_curve(
+                    half_edge.curve(),
+                    self.is_on_axis(start) && self.is_on_axis(end),
+                    cache,
+                    services,
+                );
+                let start_vertex = self.end_vertex(
+                    half_edge.start_vertex(),
+                    self.is_on_axis(start),
+                    cache,
+                    services,
+                );
+
+                // The surface is going to be rotated along with the
+                // half-edge, so the path doesn't change.
+                let end_half_edge = HalfEdge::new(
+                    half_edge.path(),
+                    half_edge.boundary(),
+                    curve,
+                    start_vertex,
+                )
+                .insert(services);
+                services.record_derivation(
+                    end_half_edge.clone(),
+                    [half_edge.clone()],
+                );
+
+                end_half_edge
+            })
+            .collect::<Vec<_>>();
+
+        Cycle::new(half_edges).insert(services)
+    }
+
+    fn arc(
+        &self,
+        vertex: &Handle<Vertex>,
+        cache: &mut RevolveCache,
+        services: &mut Services,
+    ) -> Handle<Curve> {
+        cache
+            .arcs
+            .entry(vertex.id())
+            .or_insert_with(|| Curve::new().insert(services))
+            .clone()
+    }
+
+    /// The copies of a vertex at each step of a faceted revolution
+    ///
+    /// Includes the vertex itself at the start, and its copy at the end of
+    /// the revolution.
+    fn facet_vertices(
+        &self,
+        vertex: &Handle<Vertex>,
+        is_on_axis: bool,
+        segments: usize,
+        cache: &mut RevolveCache,
+        services: &mut Services,
+    ) -> Vec<Handle<Vertex>> {
+        if is_on_axis {
+            return vec![vertex.clone(); segments + 1];
+        }
+        if let Some(vertices) = cache.facet_vertices.get(&vertex.id()) {
+            return vertices.clone();
+        }
+
+        let mut vertices = vec![vertex.clone()];
+        vertices.extend((1..segments).map(|_| Vertex::new().insert(services)));
+        vertices.push(self.end_vertex(vertex, false, cache, services));
+
+        cache.facet_vertices.insert(vertex.id(), vertices.clone());
+        vertices
+    }
+
+    /// The curves that a vertex is revolved along, one per step of a faceted
+    /// revolution
+    fn facet_arcs(
+        &self,
+        vertex: &Handle<Vertex>,
+        segments: usize,
+        cache: &mut RevolveCache,
+        services: &mut Services,
+    ) -> Vec<Handle<Curve>> {
+        cache
+            .facet_arcs
+            .entry(vertex.id())
+            .or_insert_with(|| {
+                (0..segments)
+                    .map(|_| Curve::new().insert(services))
+                    .collect()
+            })
+            .clone()
+    }
+
+    /// The copies of a curve at each step of a faceted revolution
+    ///
+    /// Includes the curve itself at the start, and its copy at the end of the
+    /// revolution.
+    fn facet_curves(
+        &self,
+        curve: &Handle<Curve>,
+        segments: usize,
+        cache: &mut RevolveCache,
+        services: &mut Services,
+    ) -> Vec<Handle<Curve>> {
+        if let Some(curves) = cache.facet_curves.get(&curve.id()) {
+            return curves.clone();
+        }
+
+        let mut curves = vec![curve.clone()];
+        curves.extend((1..segments).map(|_| Curve::new().insert(services)));
+        curves.push(self.end_curve(curve, false, cache, services));
+
+        cache.facet_curves.insert(curve.id(), curves.clone());
+        curves
+    }
+
+    fn end_curve(
+        &self,
+        curve: &Handle<Curve>,
+        is_on_axis: bool,
+        cache: &mut RevolveCache,
+        services: &mut Services,
+    ) -> Handle<Curve> {
+        if self.is_full_turn || is_on_axis {
+            return curve.clone();
+        }
+
+        cache
+            .curves
+            .entry(curve.id())
+            .or_insert_with(|| Curve::new().insert(services))
+            .clone()
+    }
+
+    fn end_vertex(
+        &self,
+        vertex: &Handle<Vertex>,
+        is_on_axis: bool,
+        cache: &mut RevolveCache,
+        services: &mut Services,
+    ) -> Handle<Vertex> {
+        if self.is_full_turn || is_on_axis {
+            return vertex.clone();
+        }
+
+        cache
+            .vertices
+            .entry(vertex.id())
+            .or_insert_with(|| Vertex::new().insert(services))
+            .clone()
+    }
+}
+
+fn line_segment(
+    points: [Point<2>; 2],
+    boundary: [Point<1>; 2],
+    curve: Handle<Curve>,
+    start_vertex: Handle<Vertex>,
+) -> HalfEdge {
+    let path =
+        SurfacePath::line_from_points_with_coords(boundary.zip_ext(points));
+    HalfEdge::new(path, boundary, curve, start_vertex)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Aabb, Line, Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::bounding_volume::BoundingVolume,
+        objects::{Region, Sketch},
+        operations::{
+            build::{BuildRegion, BuildSketch},
+            insert::Insert,
+            update::UpdateSketch,
+        },
+        services::Services,
+    };
+
+    use super::{RevolveError, RevolveSketch, SEGMENTS_PER_TURN};
+
+    #[test]
+    fn revolve_rectangle() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let axis = Line::from_origin_and_direction(
+            Point::from([0., 0., 0.]),
+            Vector::from([0., 1., 0.]),
+        );
+
+        for ([min, max], angle, num_faces, aabb) in [
+            ([1., 2.], Scalar::TAU, 4, [[-2., 0., -2.], [2., 1., 2.]]),
+            ([1., 2.], Scalar::PI / 2., 6, [[0., 0., -2.], [2., 1., 0.]]),
+            ([0., 1.], Scalar::TAU, 3, [[-1., 0., -1.], [1., 1., 1.]]),
+            ([0., 1.], -Scalar::PI / 2., 5, [[0., 0., 0.], [1., 1., 1.]]),
+        ] {
+            let region = Region::polygon(
+                [[min, 0.], [max, 0.], [max, 1.], [min, 1.]],
+                &mut services,
+            )
+            .insert(&mut services);
+            let sketch = Sketch::empty().add_region(region);
+
+            let solid = sketch
+                .revolve_sketch(surface.clone(), axis, angle, &mut services)?
+                .insert(&mut services);
+
+            assert_eq!(solid.shells().only().faces().len(), num_faces);
+            assert_close(solid.aabb(), aabb);
+        }
+
+        services.drop_and_validate()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn revolve_sloped_edges() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let axis = Line::from_origin_and_direction(
+            Point::from([0., 0., 0.]),
+            Vector::from([0., 1., 0.]),
+        );
+
+        let segments = SEGMENTS_PER_TURN;
+        for (triangle, angle, num_faces, aabb) in [
+            // A ring with a triangular cross-section. Each edge is revolved
+            // into facets.
+            (
+                [[1., 0.], [2., 0.], [1., 1.]],
+                Scalar::TAU,
+                3 * segments,
+                [[-2., 0., -2.], [2., 1., 2.]],
+            ),
+            // A quarter of a cone. The edge on the axis doesn't result in any
+            // faces, but the start and end faces are there.
+            (
+                [[0., 0.], [1., 0.], [0., 1.]],
+                Scalar::PI / 2.,
+                2 * segments / 4 + 2,
+                [[0., 0., -1.], [1., 1., 0.]],
+            ),
+        ] {
+            let region =
+                Region::polygon(triangle, &mut services).insert(&mut services);
+            let sketch = Sketch::empty().add_region(region);
+
+            let solid = sketch
+                .revolve_sketch(surface.clone(), axis, angle, &mut services)?
+                .insert(&mut services);
+
+            assert_eq!(solid.shells().only().faces().len(), num_faces);
+            assert_close(solid.aabb(), aabb);
+        }
+
+        services.drop_and_validate()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn revolve_unsupported_edge() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let axis = Line::from_origin_and_direction(
+            Point::from([0., 0., 0.]),
+            Vector::from([0., 1., 0.]),
+        );
+
+        let region =
+            Region::circle([3., 0.], 1., &mut services).insert(&mut services);
+        let result = Sketch::empty().add_region(region).revolve_sketch(
+            surface,
+            axis,
+            Scalar::TAU,
+            &mut services,
+        );
+
+        assert!(matches!(result, Err(RevolveError::UnsupportedEdge { .. })));
+    }
+
+    fn assert_close(aabb: Option<Aabb<3>>, [min, max]: [[f64; 3]; 2]) {
+        let aabb = aabb.expect("Revolved solid has an AABB");
+        for (actual, expected) in [(aabb.min, min), (aabb.max, max)] {
+            let distance = actual.distance_to(&Point::from(expected));
+            assert!(distance < Scalar::from(1e-9), "{aabb:?}");
+        }
+    }
+}
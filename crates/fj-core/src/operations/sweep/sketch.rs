@@ -1,11 +1,12 @@
-use fj_math::{Scalar, Vector};
+use std::{collections::BTreeSet, num::NonZeroUsize, panic, thread};
+
+use fj_math::{Point, Scalar, Vector};
 
 use crate::{
-    geometry::GlobalPath,
-    objects::{Face, Sketch, Solid, Surface},
+    objects::{Face, ObjectSet, Region, Shell, Sketch, Solid, Surface},
     operations::{insert::Insert, reverse::Reverse},
     services::Services,
-    storage::Handle,
+    storage::{Handle, ObjectId},
 };
 
 use super::{face::SweepFace, SweepCache};
@@ -17,6 +18,17 @@ use super::{face::SweepFace, SweepCache};
 /// [module documentation]: super
 pub trait SweepSketch {
     /// # Sweep the [`Sketch`]
+    ///
+    /// Each region of the sketch is swept into its own shell. Interior cycles
+    /// of a region result in holes, with inner side walls connecting the
+    /// bottom and top faces.
+    ///
+    /// The cycles of the sketch's regions may be wound in either direction.
+    /// They are reversed as required, to make sure the resulting shells point
+    /// outward.
+    ///
+    /// Regions that don't share any vertices or curves are swept in parallel,
+    /// on multiple threads.
     fn sweep_sketch(
         &self,
         surface: Handle<Surface>,
@@ -32,45 +44,331 @@ impl SweepSketch for Sketch {
         path: impl Into<Vector<3>>,
         services: &mut Services,
     ) -> Solid {
+        let _span = tracing::info_span!("sweep").entered();
+
         let path = path.into();
-        let mut cache = SweepCache::default();
-
-        let mut shells = Vec::new();
-        for region in self.regions() {
-            let region = {
-                // The following code assumes that the sketch is winded counter-
-                // clockwise. Let's check that real quick.
-                assert!(region.exterior().winding().is_ccw());
-
-                let is_negative_sweep = {
-                    let u = match surface.geometry().u {
-                        GlobalPath::Circle(_) => todo!(
-                            "Sweeping sketch from a rounded surfaces is not \
-                            supported"
-                        ),
-                        GlobalPath::Line(line) => line.direction(),
-                    };
-                    let v = surface.geometry().v;
-
-                    let normal = u.cross(&v);
-
-                    normal.dot(&path) < Scalar::ZERO
-                };
-
-                if is_negative_sweep {
-                    region.clone()
-                } else {
-                    region.reverse(services).insert(services)
-                }
+
+        let groups = independent_groups(self.regions());
+        let num_threads = thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1)
+            .min(groups.len());
+
+        let mut shells = if num_threads <= 1 {
+            let regions = groups.into_iter().flatten().collect::<Vec<_>>();
+            sweep_regions(&regions, &surface, path, services)
+        } else {
+            // Each batch of groups is swept on its own thread, using its own
+            // instance of `Services`. Those are merged afterwards.
+            let mut batches = vec![Vec::new(); num_threads];
+            for (i, group) in groups.into_iter().enumerate() {
+                batches[i % num_threads].extend(group);
+            }
+
+            let results = thread::scope(|scope| {
+                let threads = batches
+                    .iter()
+                    .map(|regions| {
+                        let surface = &surface;
+                        scope.spawn(move || {
+                            let mut services = Services::new();
+                            let shells = sweep_regions(
+                                regions,
+                                surface,
+                                path,
+                                &mut services,
+                            );
+                            (shells, services)
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                threads
+                    .into_iter()
+                    .map(|thread| {
+                        thread
+                            .join()
+                            .unwrap_or_else(|err| panic::resume_unwind(err))
+                    })
+                    .collect::<Vec<_>>()
+            });
+
+            let mut shells = Vec::new();
+            for (batch, batch_services) in results {
+                shells.extend(batch);
+                services.merge(batch_services);
+            }
+
+            shells
+        };
+
+        // Keep the shells in the order of the regions they were swept from,
+        // regardless of how the regions were distributed.
+        shells.sort_by_key(|(index, _)| *index);
+
+        Solid::new(shells.into_iter().map(|(_, shell)| shell))
+    }
+}
+
+/// Group the regions of a sketch, such that no two groups share any objects
+///
+/// Regions within a group must be swept using the same [`SweepCache`], to
+/// make sure their shared vertices and curves end up shared in the result too.
+/// Different groups can be swept independently.
+///
+/// Each region is returned together with its index in the sketch.
+fn independent_groups(
+    regions: &ObjectSet<Region>,
+) -> Vec<Vec<(usize, Handle<Region>)>> {
+    let mut groups = Vec::<(BTreeSet<ObjectId>, Vec<_>)>::new();
+
+    for (index, region) in regions.iter().enumerate() {
+        let mut ids = BTreeSet::new();
+        for cycle in region.all_cycles() {
+            for half_edge in cycle.half_edges() {
+                ids.insert(half_edge.start_vertex().id());
+                ids.insert(half_edge.curve().id());
+            }
+        }
+
+        let mut members = vec![(index, region.clone())];
+
+        let (connected, unconnected): (Vec<_>, Vec<_>) = groups
+            .into_iter()
+            .partition(|(group_ids, _)| !group_ids.is_disjoint(&ids));
+        groups = unconnected;
+
+        for (group_ids, group_members) in connected {
+            ids.extend(group_ids);
+            members.extend(group_members);
+        }
+
+        groups.push((ids, members));
+    }
+
+    groups.into_iter().map(|(_, members)| members).collect()
+}
+
+fn sweep_regions(
+    regions: &[(usize, Handle<Region>)],
+    surface: &Handle<Surface>,
+    path: Vector<3>,
+    services: &mut Services,
+) -> Vec<(usize, Handle<Shell>)> {
+    let mut cache = SweepCache::default();
+
+    let mut shells = Vec::new();
+    for (index, region) in regions {
+        let region = {
+            // The following code assumes that the exterior of the region is
+            // wound counter-clockwise, and its interiors clockwise. Since that
+            // is easy to get wrong when building a sketch manually, let's not
+            // require it and fix it up here instead.
+            let region = normalize_winding(region, services);
+
+            let is_negative_sweep = {
+                // The normal of a curved surface depends on where it's
+                // evaluated. The start of the region's exterior is used, which
+                // is good enough, as long as the path doesn't run along the
+                // surface anywhere within the region.
+                let point = region
+                    .exterior()
+                    .half_edges()
+                    .iter()
+                    .next()
+                    .map(|half_edge| half_edge.start_position())
+                    .unwrap_or_else(Point::origin);
+                let normal = surface.geometry().normal_at(point);
+
+                normal.dot(&path) < Scalar::ZERO
             };
 
-            let face =
-                Face::new(surface.clone(), region.clone()).insert(services);
-            let shell =
-                face.sweep_face(path, &mut cache, services).insert(services);
-            shells.push(shell);
+            if is_negative_sweep {
+                region
+            } else {
+                region.reverse(services).insert(services)
+            }
+        };
+
+        let face = Face::new(surface.clone(), region.clone()).insert(services);
+        let shell =
+            face.sweep_face(path, &mut cache, services).insert(services);
+        shells.push((*index, shell));
+    }
+
+    shells
+}
+
+/// Make sure the region's exterior is wound counter-clockwise, its interiors
+/// clockwise
+pub(super) fn normalize_winding(
+    region: &Handle<Region>,
+    services: &mut Services,
+) -> Handle<Region> {
+    let exterior_is_ccw = region.exterior().winding().is_ccw();
+    let interiors_are_cw = region
+        .interiors()
+        .iter()
+        .all(|interior| interior.winding().is_cw());
+
+    if exterior_is_ccw && interiors_are_cw {
+        return region.clone();
+    }
+
+    let exterior = if exterior_is_ccw {
+        region.exterior().clone()
+    } else {
+        region.exterior().reverse(services).insert(services)
+    };
+    let interiors = region
+        .interiors()
+        .iter()
+        .map(|interior| {
+            if interior.winding().is_cw() {
+                interior.clone()
+            } else {
+                interior.reverse(services).insert(services)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Region::new(exterior, interiors, region.color()).insert(services)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::{approx::Tolerance, triangulate::Triangulate},
+        objects::{Cycle, HalfEdge, Region, Sketch},
+        operations::{
+            build::{BuildCycle, BuildHalfEdge, BuildRegion, BuildSketch},
+            insert::Insert,
+            update::UpdateSketch,
+        },
+        services::Services,
+    };
+
+    use super::{normalize_winding, SweepSketch};
+
+    #[test]
+    fn sweep_independent_regions() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let sketch = (0..4).fold(Sketch::empty(), |sketch, i| {
+            let x = f64::from(i) * 2.;
+            let region = Region::polygon(
+                [[x, 0.], [x + 1., 0.], [x + 1., 1.], [x, 1.]],
+                &mut services,
+            )
+            .insert(&mut services);
+            sketch.add_region(region)
+        });
+
+        let surface = services.objects.surfaces.xy_plane();
+        let solid = sketch
+            .sweep_sketch(surface, [0., 0., 1.], &mut services)
+            .insert(&mut services);
+
+        assert_eq!(solid.shells().len(), 4);
+        for shell in solid.shells().iter() {
+            assert_eq!(shell.faces().len(), 6);
         }
 
-        Solid::new(shells)
+        services.drop_and_validate()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn sweep_region_bounded_by_spiral() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // Half a turn of a spiral, from `[1, 0]` to `[-1.5, 0]`, closed by a
+        // line segment through its center.
+        let spiral =
+            HalfEdge::spiral([0., 0.], 1., 1., [0., PI], &mut services);
+        let end = spiral.path().point_from_path_coords([PI]);
+        let line =
+            HalfEdge::line_segment([end, [1., 0.].into()], None, &mut services);
+        let exterior = Cycle::new(
+            [spiral, line].map(|half_edge| half_edge.insert(&mut services)),
+        )
+        .insert(&mut services);
+        let region = Region::new(exterior, [], None).insert(&mut services);
+
+        let surface = services.objects.surfaces.xy_plane();
+        let solid = Sketch::empty()
+            .add_region(region)
+            .sweep_sketch(surface, [0., 0., 1.], &mut services)
+            .insert(&mut services);
+
+        let tolerance = Tolerance::from_scalar(0.001)?;
+        let mesh = (&*solid, tolerance).triangulate();
+
+        // The area of the region is that of the spiral's sector, which is the
+        // integral of `r^2 / 2` over the angle, for `r = 1 + angle / 2pi`.
+        let area = PI * 19. / 24.;
+        let volume = mesh
+            .triangles()
+            .map(|triangle| {
+                let [a, b, c] = triangle.inner.points();
+                a.coords.dot(&b.coords.cross(&c.coords)) / 6.
+            })
+            .fold(Scalar::ZERO, |volume, v| volume + v);
+        assert!((volume - Scalar::from(area)).abs() < Scalar::from(0.01));
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn normalize_winding_of_misoriented_cycles() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let exterior = Cycle::polygon(
+            [[0., 0.], [0., 4.], [4., 4.], [4., 0.]],
+            &mut services,
+        )
+        .insert(&mut services);
+        let interior = Cycle::polygon(
+            [[1., 1.], [3., 1.], [3., 3.], [1., 3.]],
+            &mut services,
+        )
+        .insert(&mut services);
+        assert!(exterior.winding().is_cw());
+        assert!(interior.winding().is_ccw());
+
+        let region =
+            Region::new(exterior, [interior], None).insert(&mut services);
+        let normalized = normalize_winding(&region, &mut services);
+
+        assert!(normalized.exterior().winding().is_ccw());
+        for interior in normalized.interiors() {
+            assert!(interior.winding().is_cw());
+        }
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn normalize_winding_keeps_normalized_region() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let region = Region::polygon(
+            [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services);
+        let normalized = normalize_winding(&region, &mut services);
+
+        assert_eq!(normalized.id(), region.id());
+
+        services.drop_and_validate()?;
+        Ok(())
     }
 }
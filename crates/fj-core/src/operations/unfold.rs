@@ -0,0 +1,722 @@
+//! # Unfold shells into flat patterns
+//!
+//! See [`Unfold`].
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    ops::Deref,
+};
+
+use fj_interop::drawing::{Annotation, Drawing, LineStyle, Stroke, View};
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    algorithms::approx::{Approx, Tolerance},
+    geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{Cycle, Face, HalfEdge, Handedness, Region, Shell, Sketch},
+    queries::{dihedral_angle, SiblingOfHalfEdge},
+    services::Services,
+    storage::Handle,
+};
+
+use super::{build::BuildCycle, insert::Insert};
+
+/// Unfold a shell into a flat pattern
+///
+/// This is meant for sheet-metal style parts, whose flat pattern can be cut
+/// out of a sheet, then bent into shape.
+///
+/// # Implementation Note
+///
+/// Only developable faces can be unfolded. Those are faces on planes and on
+/// right circular cylinders. Fornjot can't represent conical surfaces exactly,
+/// so cones consist of planar facets (see [`BuildPrimitive::cone`], for
+/// example). Those are unfolded as one piece, resulting in the circular sector
+/// that is the development of a cone.
+///
+/// Faces are only unfolded across edges that are straight on both adjacent
+/// faces. Faces that can't be reached that way are not part of the flat
+/// pattern, and don't need to be developable. The flat pattern is not checked
+/// for overlapping faces.
+///
+/// Of all the edges that faces could be unfolded across, the ones with the
+/// smallest bend angle are chosen first. This keeps the facets that approximate
+/// a curved surface, like those of a cone, together.
+///
+/// [`BuildPrimitive::cone`]: super::primitives::BuildPrimitive::cone
+pub trait Unfold {
+    /// Unfold the shell, starting at the provided face
+    ///
+    /// `root` keeps its place in the flat pattern, and all other faces are
+    /// unfolded around it. Curved edges are approximated within `tolerance`.
+    fn unfold(
+        &self,
+        root: &Handle<Face>,
+        tolerance: impl Into<Tolerance>,
+        services: &mut Services,
+    ) -> Result<FlatPattern, UnfoldError>;
+}
+
+impl Unfold for Shell {
+    fn unfold(
+        &self,
+        root: &Handle<Face>,
+        tolerance: impl Into<Tolerance>,
+        services: &mut Services,
+    ) -> Result<FlatPattern, UnfoldError> {
+        let tolerance = tolerance.into();
+
+        let faces = self.faces().iter().collect::<Vec<_>>();
+        let root = faces
+            .iter()
+            .position(|face| face.id() == root.id())
+            .ok_or(UnfoldError::RootNotInShell)?;
+        let mut developments = Developments {
+            faces: &faces,
+            developments: faces.iter().map(|_| None).collect(),
+        };
+
+        let mut face_of_half_edge = BTreeMap::new();
+        for (i, face) in faces.iter().enumerate() {
+            for cycle in face.region().all_cycles() {
+                for half_edge in cycle.half_edges() {
+                    face_of_half_edge.insert(half_edge.id(), i);
+                }
+            }
+        }
+
+        let mut placements = vec![None; faces.len()];
+        placements[root] = Some(Placement::identity());
+        developments.get(root)?;
+
+        let mut bend_lines = Vec::new();
+        let mut joints = Vec::new();
+        let mut queue = BinaryHeap::new();
+        let mut placed = Some(root);
+
+        while let Some(i) = placed.take() {
+            let face = faces[i];
+
+            for cycle in face.region().all_cycles() {
+                for half_edge in cycle.half_edges() {
+                    let Some(sibling) = self.get_sibling_of(half_edge) else {
+                        continue;
+                    };
+                    let Some(&j) = face_of_half_edge.get(&sibling.id()) else {
+                        continue;
+                    };
+                    if placements[j].is_some() {
+                        continue;
+                    }
+
+                    let is_straight = |half_edge: &HalfEdge| {
+                        matches!(half_edge.path(), SurfacePath::Line(_))
+                    };
+                    if !is_straight(half_edge) || !is_straight(&sibling) {
+                        continue;
+                    }
+
+                    let angle = dihedral_angle(half_edge, face, self)
+                        .map(|dihedral| Scalar::PI - dihedral)
+                        .unwrap_or(Scalar::ZERO);
+
+                    // Ties are broken by the order in which the joints were
+                    // found, to keep the result deterministic.
+                    queue.push(Reverse((angle.abs(), joints.len())));
+                    joints.push(Joint {
+                        faces: [i, j],
+                        half_edges: [half_edge.clone(), sibling],
+                        angle,
+                    });
+                }
+            }
+
+            while let Some(Reverse((_, joint))) = queue.pop() {
+                let Joint {
+                    faces: [i, j],
+                    half_edges: [half_edge, sibling],
+                    angle,
+                } = &joints[joint];
+                let [i, j] = [*i, *j];
+
+                if placements[j].is_some() {
+                    continue;
+                }
+
+                // The sibling runs in the opposite direction, so its end is
+                // where this half-edge starts, and vice versa.
+                let placement =
+                    placements[i].expect("Joints start at placed faces");
+                let development = developments.get(i)?;
+                let edge = end_points(half_edge)
+                    .map(|point| placement.apply(development.flatten(point)));
+                let development = developments.get(j)?;
+                let [end, start] =
+                    end_points(sibling).map(|point| development.flatten(point));
+                placements[j] =
+                    Some(Placement::from_segments([start, end], edge));
+
+                if angle.abs() > Scalar::from_f64(TANGENT_EPSILON) {
+                    bend_lines.push(BendLine {
+                        points: edge,
+                        angle: *angle,
+                        faces: [faces[i].clone(), faces[j].clone()],
+                    });
+                }
+
+                placed = Some(j);
+                break;
+            }
+        }
+
+        let mut regions = Vec::new();
+        let mut flat_faces = Vec::new();
+        let mut unplaced_faces = Vec::new();
+
+        for ((face, development), placement) in faces
+            .iter()
+            .zip(&developments.developments)
+            .zip(&placements)
+        {
+            let (Some(development), Some(placement)) = (development, placement)
+            else {
+                unplaced_faces.push((*face).clone());
+                continue;
+            };
+
+            let mut flatten_cycle = |cycle: &Handle<Cycle>| {
+                let mut points = (cycle.deref(), face.surface().deref())
+                    .approx(tolerance)
+                    .points()
+                    .into_iter()
+                    .map(|point| {
+                        placement.apply(development.flatten(point.local_form))
+                    })
+                    .collect::<Vec<_>>();
+
+                // The approximation repeats the first point at the end.
+                points.pop();
+                points.dedup();
+
+                Cycle::polygon(points, services).insert(services)
+            };
+
+            let exterior = flatten_cycle(face.region().exterior());
+            let interiors = face
+                .region()
+                .interiors()
+                .iter()
+                .map(&mut flatten_cycle)
+                .collect::<Vec<_>>();
+
+            let region =
+                Region::new(exterior, interiors, face.region().color())
+                    .insert(services);
+            regions.push(region);
+            flat_faces.push((*face).clone());
+        }
+
+        Ok(FlatPattern {
+            sketch: Sketch::new(regions),
+            faces: flat_faces,
+            bend_lines,
+            unplaced_faces,
+        })
+    }
+}
+
+/// A flat pattern, the result of unfolding a shell
+///
+/// Returned by [`Unfold::unfold`].
+#[derive(Clone, Debug)]
+pub struct FlatPattern {
+    /// The sketch that contains the unfolded faces
+    ///
+    /// Each face results in one region. Regions of adjacent faces touch along
+    /// the edges that were unfolded.
+    pub sketch: Sketch,
+
+    /// The faces that the regions of the sketch were unfolded from
+    ///
+    /// In the same order as the regions of the sketch.
+    pub faces: Vec<Handle<Face>>,
+
+    /// The lines along which the flat pattern needs to be bent
+    pub bend_lines: Vec<BendLine>,
+
+    /// The faces that could not be unfolded, and are not part of the sketch
+    pub unplaced_faces: Vec<Handle<Face>>,
+}
+
+impl FlatPattern {
+    /// Generate a drawing of the flat pattern, for export
+    ///
+    /// The drawing has a single view. The outlines of the regions, except for
+    /// the edges that regions share, are drawn as visible lines. Those are the
+    /// lines that the flat pattern is cut along. Bend lines are drawn in their
+    /// own style, and annotated with their angle in degrees.
+    pub fn drawing(&self) -> Drawing {
+        let mut segments = Vec::new();
+        for region in self.sketch.regions() {
+            for cycle in region.all_cycles() {
+                for (half_edge, next) in cycle.half_edges().pairs() {
+                    segments.push([
+                        half_edge.start_position(),
+                        next.start_position(),
+                    ]);
+                }
+            }
+        }
+
+        let Some(aabb) = points_aabb(segments.iter().flatten().copied()) else {
+            return Drawing::default();
+        };
+        let size = (aabb.max - aabb.min).magnitude();
+        let epsilon = size * Scalar::from_f64(JOINT_EPSILON);
+
+        // Adjacent regions share an edge, if the faces were unfolded across
+        // it. Their half-edges run in opposite directions.
+        let is_shared = |[a, b]: [Point<2>; 2], index: usize| {
+            segments.iter().enumerate().any(|(other_index, &[c, d])| {
+                other_index != index
+                    && a.distance_to(&d) <= epsilon
+                    && b.distance_to(&c) <= epsilon
+            })
+        };
+        let mut strokes = segments
+            .iter()
+            .enumerate()
+            .filter(|&(index, &segment)| !is_shared(segment, index))
+            .map(|(_, &points)| Stroke {
+                points,
+                style: LineStyle::Visible,
+            })
+            .collect::<Vec<_>>();
+
+        let mut annotations = Vec::new();
+        for bend_line in &self.bend_lines {
+            let [a, b] = bend_line.points;
+
+            strokes.push(Stroke {
+                points: [a, b],
+                style: LineStyle::Bend,
+            });
+            annotations.push(Annotation {
+                lines: Vec::new(),
+                text: format!(
+                    "{:.1}°",
+                    bend_line.angle.into_f64().to_degrees()
+                ),
+                text_position: a + (b - a) / 2.,
+                text_height: size * 0.03,
+            });
+        }
+
+        Drawing {
+            views: vec![View {
+                name: "flat-pattern".into(),
+                offset: Vector::from([0., 0.]),
+                strokes,
+                annotations,
+            }],
+        }
+    }
+}
+
+/// A line along which a flat pattern needs to be bent
+#[derive(Clone, Debug)]
+pub struct BendLine {
+    /// The end points of the bend line, in the coordinates of the sketch
+    pub points: [Point<2>; 2],
+
+    /// The angle by which the faces are bent against each other
+    ///
+    /// Positive for convex edges, negative for concave ones. An angle of zero
+    /// would mean the faces are not bent at all.
+    pub angle: Scalar,
+
+    /// The faces on either side of the bend line
+    pub faces: [Handle<Face>; 2],
+}
+
+/// Error unfolding a shell
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum UnfoldError {
+    /// The root face is not part of the shell
+    #[error("Root face is not part of the shell that is being unfolded")]
+    RootNotInShell,
+
+    /// A face of the shell is not developable
+    #[error("Surface can't be unfolded without distortion: {0:#?}")]
+    NotDevelopable(SurfaceGeometry),
+}
+
+/// Faces that meet at a smaller angle than this are considered tangent
+const TANGENT_EPSILON: f64 = 1e-4;
+
+/// The distance, relative to the size of the flat pattern, within which the
+/// edges of adjacent regions are considered to be the same
+const JOINT_EPSILON: f64 = 1e-9;
+
+/// An edge that a face could be unfolded across
+struct Joint {
+    /// The indices of the already placed face, and the one to unfold
+    faces: [usize; 2],
+
+    /// The half-edges of the edge, on the respective faces
+    half_edges: [Handle<HalfEdge>; 2],
+
+    /// The bend angle of the edge
+    angle: Scalar,
+}
+
+/// The developments of the faces of a shell, computed as they are needed
+struct Developments<'r> {
+    faces: &'r [&'r Handle<Face>],
+    developments: Vec<Option<Development>>,
+}
+
+impl Developments<'_> {
+    fn get(&mut self, i: usize) -> Result<&Development, UnfoldError> {
+        let development = &mut self.developments[i];
+        if development.is_none() {
+            *development = Some(Development::of(self.faces[i])?);
+        }
+
+        Ok(development.as_ref().expect("Development was just computed"))
+    }
+}
+
+/// The mapping of a face's surface coordinates into the plane
+struct Development {
+    kind: DevelopmentKind,
+    surface: SurfaceGeometry,
+
+    /// Whether the face is mirrored, to show its front side
+    mirror: bool,
+}
+
+enum DevelopmentKind {
+    Plane {
+        origin: Point<3>,
+        axes: [Vector<3>; 2],
+    },
+    Cylinder {
+        radius: Scalar,
+        height_per_v: Scalar,
+    },
+}
+
+impl Development {
+    fn of(face: &Face) -> Result<Self, UnfoldError> {
+        let surface = face.surface().geometry();
+
+        let kind = match surface.u {
+            GlobalPath::Line(line) => {
+                // The axes have the same handedness as the surface's
+                // coordinate system, so the winding of cycles is preserved.
+                let x = line.direction().normalize();
+                let normal = line.direction().cross(&surface.v).normalize();
+                let y = normal.cross(&x);
+
+                DevelopmentKind::Plane {
+                    origin: line.origin(),
+                    axes: [x, y],
+                }
+            }
+            GlobalPath::Circle(circle) => {
+                let axis = circle.a().cross(&circle.b()).normalize();
+                let is_right_cylinder = surface.v.cross(&axis).magnitude()
+                    <= surface.v.magnitude() * Scalar::from_f64(1e-9);
+                if !is_right_cylinder {
+                    return Err(UnfoldError::NotDevelopable(surface));
+                }
+
+                DevelopmentKind::Cylinder {
+                    radius: circle.radius(),
+                    height_per_v: surface.v.magnitude(),
+                }
+            }
+            _ => return Err(UnfoldError::NotDevelopable(surface)),
+        };
+
+        Ok(Self {
+            kind,
+            surface,
+            mirror: face.coord_handedness() == Handedness::LeftHanded,
+        })
+    }
+
+    fn flatten(&self, point: Point<2>) -> Point<2> {
+        let [x, y] = match self.kind {
+            DevelopmentKind::Plane { origin, axes } => {
+                let point = self.surface.point_from_surface_coords(point);
+                axes.map(|axis| (point - origin).dot(&axis))
+            }
+            DevelopmentKind::Cylinder {
+                radius,
+                height_per_v,
+            } => [point.u * radius, point.v * height_per_v],
+        };
+
+        let y = if self.mirror { -y } else { y };
+        Point::from([x, y])
+    }
+}
+
+/// The rotation and translation of a face within the flat pattern
+#[derive(Clone, Copy)]
+struct Placement {
+    sin_cos: (Scalar, Scalar),
+    translation: Vector<2>,
+}
+
+impl Placement {
+    fn identity() -> Self {
+        Self {
+            sin_cos: (Scalar::ZERO, Scalar::ONE),
+            translation: Vector::from([0., 0.]),
+        }
+    }
+
+    /// Compute the placement that moves one segment onto another
+    ///
+    /// The segments are expected to have the same length.
+    fn from_segments(from: [Point<2>; 2], to: [Point<2>; 2]) -> Self {
+        let [from_direction, to_direction] =
+            [from, to].map(|[start, end]| end - start);
+        let angle = to_direction.v.atan2(to_direction.u)
+            - from_direction.v.atan2(from_direction.u);
+
+        let rotation = Self {
+            sin_cos: angle.sin_cos(),
+            translation: Vector::from([0., 0.]),
+        };
+
+        Self {
+            translation: to[0] - rotation.apply(from[0]),
+            ..rotation
+        }
+    }
+
+    fn apply(&self, point: Point<2>) -> Point<2> {
+        let (sin, cos) = self.sin_cos;
+        Point::from([
+            point.u * cos - point.v * sin,
+            point.u * sin + point.v * cos,
+        ]) + self.translation
+    }
+}
+
+fn points_aabb(points: impl IntoIterator<Item = Point<2>>) -> Option<Aabb<2>> {
+    let points = points.into_iter().collect::<Vec<_>>();
+    if points.is_empty() {
+        return None;
+    }
+
+    Some(Aabb::<2>::from_points(points))
+}
+
+fn end_points(half_edge: &HalfEdge) -> [Point<2>; 2] {
+    half_edge
+        .boundary()
+        .inner
+        .map(|point| half_edge.path().point_from_path_coords(point))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use fj_interop::drawing::LineStyle;
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::approx::Tolerance,
+        geometry::GlobalPath,
+        objects::{Face, Region, Solid},
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    use super::Unfold;
+
+    #[test]
+    fn unfold_box() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let solid = Solid::box_from_dims([1., 2., 3.], &mut services)
+            .insert(&mut services);
+        let shell = solid.shells().first();
+        let root = shell.faces().first();
+
+        let pattern = shell.unfold(root, tolerance, &mut services)?;
+        assert_eq!(pattern.sketch.regions().len(), 6);
+        assert!(pattern.unplaced_faces.is_empty());
+
+        // The faces of a box form a tree with 5 edges, when unfolded.
+        assert_eq!(pattern.bend_lines.len(), 5);
+        for bend_line in &pattern.bend_lines {
+            assert!(
+                (bend_line.angle - Scalar::from(FRAC_PI_2)).abs()
+                    < Scalar::from(1e-3)
+            );
+        }
+
+        // All faces show their front side, and keep their area.
+        let areas = pattern
+            .sketch
+            .regions()
+            .iter()
+            .map(|region| area(region))
+            .collect::<Vec<_>>();
+        assert!(areas.iter().all(|&area| area > Scalar::ZERO));
+
+        let total = areas.into_iter().fold(Scalar::ZERO, |a, b| a + b);
+        assert!((total - Scalar::from(22.)).abs() < Scalar::from(1e-9));
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn unfold_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let solid =
+            Solid::cylinder(1., 2., &mut services).insert(&mut services);
+        let shell = solid.shells().first();
+        let side = shell
+            .faces()
+            .iter()
+            .find(|face| {
+                matches!(face.surface().geometry().u, GlobalPath::Circle(_))
+            })
+            .expect("Expected cylinder to have curved side");
+
+        let pattern = shell.unfold(side, tolerance, &mut services)?;
+
+        // The caps only touch the side along circular edges, so they can't be
+        // unfolded.
+        assert_eq!(pattern.sketch.regions().len(), 1);
+        assert_eq!(pattern.unplaced_faces.len(), 2);
+
+        let area = area(pattern.sketch.regions().first());
+        assert!((area - Scalar::from(4. * PI)).abs() < Scalar::from(1e-9));
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn unfold_cone() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let solid = Solid::cone(1., 1., 8, &mut services).insert(&mut services);
+        let shell = solid.shells().first();
+        let base = shell
+            .faces()
+            .iter()
+            .find(|face| is_base(face))
+            .expect("Expected cone to have a base");
+
+        let pattern = shell.unfold(base, tolerance, &mut services)?;
+        assert_eq!(pattern.sketch.regions().len(), 16);
+        assert!(pattern.unplaced_faces.is_empty());
+
+        // The facets of the base are coplanar, so there are no bend lines
+        // between them. The side is attached to the base along a single edge,
+        // and its facets form a circular sector around the tip of the cone.
+        let (base_bends, side_bends) = pattern
+            .bend_lines
+            .iter()
+            .partition::<Vec<_>, _>(|bend_line| {
+                bend_line.angle > Scalar::from(FRAC_PI_2)
+            });
+        assert_eq!(base_bends.len(), 1);
+        assert_eq!(side_bends.len(), 7);
+
+        let tip = side_bends[0].points.into_iter().find(|tip| {
+            side_bends.iter().all(|bend_line| {
+                bend_line
+                    .points
+                    .iter()
+                    .any(|point| point.distance_to(tip) < Scalar::from(1e-9))
+            })
+        });
+        assert!(tip.is_some());
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn flat_pattern_drawing() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let solid = Solid::box_from_dims([1., 2., 3.], &mut services)
+            .insert(&mut services);
+        let shell = solid.shells().first();
+        let root = shell.faces().first();
+
+        let pattern = shell.unfold(root, tolerance, &mut services)?;
+        let drawing = pattern.drawing();
+        let [view] = drawing.views.as_slice() else {
+            panic!("Expected drawing to have exactly one view");
+        };
+
+        // 6 rectangles have 24 edges, 5 of which are shared between two of
+        // them. Those are only drawn as bend lines.
+        let count = |style| {
+            view.strokes
+                .iter()
+                .filter(|stroke| stroke.style == style)
+                .count()
+        };
+        assert_eq!(count(LineStyle::Visible), 14);
+        assert_eq!(count(LineStyle::Bend), 5);
+        assert_eq!(count(LineStyle::Hidden), 0);
+
+        assert_eq!(view.annotations.len(), 5);
+        assert!(view
+            .annotations
+            .iter()
+            .all(|annotation| annotation.text == "90.0°"));
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    fn is_base(face: &Face) -> bool {
+        let surface = face.surface().geometry();
+        let GlobalPath::Line(line) = surface.u else {
+            return false;
+        };
+
+        let normal = line.direction().cross(&surface.v);
+        [normal.x, normal.y]
+            .into_iter()
+            .all(|component| component.abs() < Scalar::from(1e-9))
+    }
+
+    fn area(region: &Region) -> Scalar {
+        let points = region
+            .exterior()
+            .half_edges()
+            .iter()
+            .map(|half_edge| half_edge.start_position())
+            .collect::<Vec<Point<2>>>();
+
+        (0..points.len())
+            .map(|i| {
+                let [a, b] = [points[i], points[(i + 1) % points.len()]];
+                a.coords.cross2d(&b.coords) / 2.
+            })
+            .fold(Scalar::ZERO, |area, a| area + a)
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
 };
 
 /// Update a [`Cycle`]
-pub trait UpdateCycle {
+pub trait UpdateCycle: Sized {
     /// Add edges to the cycle
     #[must_use]
     fn add_half_edges(
@@ -42,6 +42,36 @@ pub trait UpdateCycle {
         handle: &Handle<HalfEdge>,
         replace: impl FnOnce(&Handle<HalfEdge>) -> [Handle<HalfEdge>; N],
     ) -> Self;
+
+    /// Fallible version of [`UpdateCycle::update_half_edge`]
+    ///
+    /// Returns the error, if `update` returns one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object can't be found.
+    ///
+    /// Panics, if the update results in a duplicate object.
+    fn try_update_half_edge<E>(
+        &self,
+        handle: &Handle<HalfEdge>,
+        update: impl FnOnce(&Handle<HalfEdge>) -> Result<Handle<HalfEdge>, E>,
+    ) -> Result<Self, E>;
+
+    /// Fallible version of [`UpdateCycle::replace_half_edge`]
+    ///
+    /// Returns the error, if `replace` returns one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object can't be found.
+    ///
+    /// Panics, if the update results in a duplicate object.
+    fn try_replace_half_edge<const N: usize, E>(
+        &self,
+        handle: &Handle<HalfEdge>,
+        replace: impl FnOnce(&Handle<HalfEdge>) -> Result<[Handle<HalfEdge>; N], E>,
+    ) -> Result<Self, E>;
 }
 
 impl UpdateCycle for Cycle {
@@ -76,4 +106,97 @@ impl UpdateCycle for Cycle {
             .expect("Half-edge not found");
         Cycle::new(edges)
     }
+
+    fn try_update_half_edge<E>(
+        &self,
+        handle: &Handle<HalfEdge>,
+        update: impl FnOnce(&Handle<HalfEdge>) -> Result<Handle<HalfEdge>, E>,
+    ) -> Result<Self, E> {
+        let edges = self
+            .half_edges()
+            .replace(handle, [update(handle)?])
+            .expect("Half-edge not found");
+        Ok(Cycle::new(edges))
+    }
+
+    fn try_replace_half_edge<const N: usize, E>(
+        &self,
+        handle: &Handle<HalfEdge>,
+        replace: impl FnOnce(&Handle<HalfEdge>) -> Result<[Handle<HalfEdge>; N], E>,
+    ) -> Result<Self, E> {
+        let edges = self
+            .half_edges()
+            .replace(handle, replace(handle)?)
+            .expect("Half-edge not found");
+        Ok(Cycle::new(edges))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::{Cycle, HalfEdge},
+        operations::{
+            build::{BuildCycle, BuildHalfEdge},
+            insert::Insert,
+        },
+        services::Services,
+    };
+
+    use super::UpdateCycle;
+
+    #[test]
+    fn try_update_half_edge() {
+        let mut services = Services::new();
+
+        let cycle =
+            Cycle::polygon([[0., 0.], [1., 0.], [0., 1.]], &mut services);
+        let half_edge = cycle.half_edges().first().clone();
+
+        let replacement =
+            HalfEdge::line_segment([[0., 0.], [1., 0.]], None, &mut services)
+                .insert(&mut services);
+        let updated = cycle
+            .try_update_half_edge(&half_edge, |_| {
+                Ok::<_, Failed>(replacement.clone())
+            })
+            .expect("Update succeeds");
+        assert_eq!(updated.half_edges().first().id(), replacement.id());
+
+        let result = cycle.try_update_half_edge(&half_edge, |_| Err(Failed));
+        assert_eq!(result.err(), Some(Failed));
+    }
+
+    #[test]
+    fn try_replace_half_edge() {
+        let mut services = Services::new();
+
+        let cycle =
+            Cycle::polygon([[0., 0.], [1., 0.], [0., 1.]], &mut services);
+        let half_edge = cycle.half_edges().first().clone();
+
+        let replacements =
+            [[[0., 0.], [0.5, 0.]], [[0.5, 0.], [1., 0.]]].map(|points| {
+                HalfEdge::line_segment(points, None, &mut services)
+                    .insert(&mut services)
+            });
+        let updated = cycle
+            .try_replace_half_edge(&half_edge, |_| {
+                Ok::<_, Failed>(replacements.clone())
+            })
+            .expect("Replacement succeeds");
+        assert_eq!(updated.half_edges().len(), 4);
+        for (updated, replacement) in
+            updated.half_edges().iter().zip(&replacements)
+        {
+            assert_eq!(updated.id(), replacement.id());
+        }
+
+        let result = cycle
+            .try_replace_half_edge(&half_edge, |_| Err::<[_; 2], _>(Failed));
+        assert_eq!(result.err(), Some(Failed));
+    }
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Failed;
 }
@@ -7,7 +7,7 @@ use crate::{
 };
 
 /// Update a [`HalfEdge`]
-pub trait UpdateHalfEdge {
+pub trait UpdateHalfEdge: Sized {
     /// Update the path of the edge
     #[must_use]
     fn update_path(
@@ -29,12 +29,28 @@ pub trait UpdateHalfEdge {
         update: impl FnOnce(&Handle<Curve>) -> Handle<Curve>,
     ) -> Self;
 
+    /// Fallible version of [`UpdateHalfEdge::update_curve`]
+    ///
+    /// Returns the error, if `update` returns one.
+    fn try_update_curve<E>(
+        &self,
+        update: impl FnOnce(&Handle<Curve>) -> Result<Handle<Curve>, E>,
+    ) -> Result<Self, E>;
+
     /// Update the start vertex of the edge
     #[must_use]
     fn update_start_vertex(
         &self,
         update: impl FnOnce(&Handle<Vertex>) -> Handle<Vertex>,
     ) -> Self;
+
+    /// Fallible version of [`UpdateHalfEdge::update_start_vertex`]
+    ///
+    /// Returns the error, if `update` returns one.
+    fn try_update_start_vertex<E>(
+        &self,
+        update: impl FnOnce(&Handle<Vertex>) -> Result<Handle<Vertex>, E>,
+    ) -> Result<Self, E>;
 }
 
 impl UpdateHalfEdge for HalfEdge {
@@ -74,6 +90,18 @@ impl UpdateHalfEdge for HalfEdge {
         )
     }
 
+    fn try_update_curve<E>(
+        &self,
+        update: impl FnOnce(&Handle<Curve>) -> Result<Handle<Curve>, E>,
+    ) -> Result<Self, E> {
+        Ok(HalfEdge::new(
+            self.path(),
+            self.boundary(),
+            update(self.curve())?,
+            self.start_vertex().clone(),
+        ))
+    }
+
     fn update_start_vertex(
         &self,
         update: impl FnOnce(&Handle<Vertex>) -> Handle<Vertex>,
@@ -85,4 +113,16 @@ impl UpdateHalfEdge for HalfEdge {
             update(self.start_vertex()),
         )
     }
+
+    fn try_update_start_vertex<E>(
+        &self,
+        update: impl FnOnce(&Handle<Vertex>) -> Result<Handle<Vertex>, E>,
+    ) -> Result<Self, E> {
+        Ok(HalfEdge::new(
+            self.path(),
+            self.boundary(),
+            self.curve().clone(),
+            update(self.start_vertex())?,
+        ))
+    }
 }
@@ -5,13 +5,21 @@ use crate::{
 };
 
 /// Update a [`Face`]
-pub trait UpdateFace {
+pub trait UpdateFace: Sized {
     /// Update the region of the face
     #[must_use]
     fn update_region(
         &self,
         update: impl FnOnce(&Handle<Region>) -> Handle<Region>,
     ) -> Self;
+
+    /// Fallible version of [`UpdateFace::update_region`]
+    ///
+    /// Returns the error, if `update` returns one.
+    fn try_update_region<E>(
+        &self,
+        update: impl FnOnce(&Handle<Region>) -> Result<Handle<Region>, E>,
+    ) -> Result<Self, E>;
 }
 
 impl UpdateFace for Face {
@@ -22,6 +30,14 @@ impl UpdateFace for Face {
         let region = update(self.region());
         Face::new(self.surface().clone(), region)
     }
+
+    fn try_update_region<E>(
+        &self,
+        update: impl FnOnce(&Handle<Region>) -> Result<Handle<Region>, E>,
+    ) -> Result<Self, E> {
+        let region = update(self.region())?;
+        Ok(Face::new(self.surface().clone(), region))
+    }
 }
 
 impl<const D: usize> UpdateFace for Polygon<D> {
@@ -31,4 +47,11 @@ impl<const D: usize> UpdateFace for Polygon<D> {
     ) -> Self {
         self.replace_face(self.face.update_region(update))
     }
+
+    fn try_update_region<E>(
+        &self,
+        update: impl FnOnce(&Handle<Region>) -> Result<Handle<Region>, E>,
+    ) -> Result<Self, E> {
+        Ok(self.replace_face(self.face.try_update_region(update)?))
+    }
 }
@@ -4,7 +4,7 @@ use crate::{
 };
 
 /// Update a [`Region`]
-pub trait UpdateRegion {
+pub trait UpdateRegion: Sized {
     /// Update the exterior of the region
     #[must_use]
     fn update_exterior(
@@ -12,6 +12,14 @@ pub trait UpdateRegion {
         update: impl FnOnce(&Handle<Cycle>) -> Handle<Cycle>,
     ) -> Self;
 
+    /// Fallible version of [`UpdateRegion::update_exterior`]
+    ///
+    /// Returns the error, if `update` returns one.
+    fn try_update_exterior<E>(
+        &self,
+        update: impl FnOnce(&Handle<Cycle>) -> Result<Handle<Cycle>, E>,
+    ) -> Result<Self, E>;
+
     /// Add the provided interiors to the region
     #[must_use]
     fn add_interiors(
@@ -49,6 +57,36 @@ pub trait UpdateRegion {
         handle: &Handle<Cycle>,
         replace: impl FnOnce(&Handle<Cycle>) -> [Handle<Cycle>; N],
     ) -> Self;
+
+    /// Fallible version of [`UpdateRegion::update_interior`]
+    ///
+    /// Returns the error, if `update` returns one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object can't be found.
+    ///
+    /// Panics, if the update results in a duplicate object.
+    fn try_update_interior<E>(
+        &self,
+        handle: &Handle<Cycle>,
+        update: impl FnOnce(&Handle<Cycle>) -> Result<Handle<Cycle>, E>,
+    ) -> Result<Self, E>;
+
+    /// Fallible version of [`UpdateRegion::replace_interior`]
+    ///
+    /// Returns the error, if `replace` returns one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object can't be found.
+    ///
+    /// Panics, if the update results in a duplicate object.
+    fn try_replace_interior<const N: usize, E>(
+        &self,
+        handle: &Handle<Cycle>,
+        replace: impl FnOnce(&Handle<Cycle>) -> Result<[Handle<Cycle>; N], E>,
+    ) -> Result<Self, E>;
 }
 
 impl UpdateRegion for Region {
@@ -60,6 +98,18 @@ impl UpdateRegion for Region {
         Region::new(exterior, self.interiors().iter().cloned(), self.color())
     }
 
+    fn try_update_exterior<E>(
+        &self,
+        update: impl FnOnce(&Handle<Cycle>) -> Result<Handle<Cycle>, E>,
+    ) -> Result<Self, E> {
+        let exterior = update(self.exterior())?;
+        Ok(Region::new(
+            exterior,
+            self.interiors().iter().cloned(),
+            self.color(),
+        ))
+    }
+
     fn add_interiors(
         &self,
         interiors: impl IntoIterator<Item = Handle<Cycle>>,
@@ -91,4 +141,93 @@ impl UpdateRegion for Region {
             .expect("Cycle not found");
         Region::new(self.exterior().clone(), interiors, self.color())
     }
+
+    fn try_update_interior<E>(
+        &self,
+        handle: &Handle<Cycle>,
+        update: impl FnOnce(&Handle<Cycle>) -> Result<Handle<Cycle>, E>,
+    ) -> Result<Self, E> {
+        let interiors = self
+            .interiors()
+            .replace(handle, [update(handle)?])
+            .expect("Cycle not found");
+        Ok(Region::new(
+            self.exterior().clone(),
+            interiors,
+            self.color(),
+        ))
+    }
+
+    fn try_replace_interior<const N: usize, E>(
+        &self,
+        handle: &Handle<Cycle>,
+        replace: impl FnOnce(&Handle<Cycle>) -> Result<[Handle<Cycle>; N], E>,
+    ) -> Result<Self, E> {
+        let interiors = self
+            .interiors()
+            .replace(handle, replace(handle)?)
+            .expect("Cycle not found");
+        Ok(Region::new(
+            self.exterior().clone(),
+            interiors,
+            self.color(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::{Cycle, Region},
+        operations::{
+            build::{BuildCycle, BuildRegion},
+            insert::Insert,
+        },
+        services::Services,
+    };
+
+    use super::UpdateRegion;
+
+    #[test]
+    fn try_update_exterior() {
+        let mut services = Services::new();
+
+        let region =
+            Region::polygon([[0., 0.], [1., 0.], [0., 1.]], &mut services);
+
+        let exterior =
+            Cycle::circle([0., 0.], 1., &mut services).insert(&mut services);
+        let updated = region
+            .try_update_exterior(|_| Ok::<_, Failed>(exterior.clone()))
+            .expect("Update succeeds");
+        assert_eq!(updated.exterior().id(), exterior.id());
+
+        let result = region.try_update_exterior(|_| Err(Failed));
+        assert_eq!(result.err(), Some(Failed));
+    }
+
+    #[test]
+    fn try_update_interior() {
+        let mut services = Services::new();
+
+        let interior =
+            Cycle::circle([0., 0.], 1., &mut services).insert(&mut services);
+        let region = Region::circle([0., 0.], 2., &mut services)
+            .add_interiors([interior.clone()]);
+
+        let replacement =
+            Cycle::circle([0., 0.], 0.5, &mut services).insert(&mut services);
+        let updated = region
+            .try_update_interior(&interior, |_| {
+                Ok::<_, Failed>(replacement.clone())
+            })
+            .expect("Update succeeds");
+        assert_eq!(updated.interiors().first().id(), replacement.id());
+
+        let result = region.try_update_interior(&interior, |_| Err(Failed));
+        assert_eq!(result.err(), Some(Failed));
+    }
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Failed;
 }
@@ -4,7 +4,7 @@ use crate::{
 };
 
 /// Update a [`Shell`]
-pub trait UpdateShell {
+pub trait UpdateShell: Sized {
     /// Add faces to the shell
     #[must_use]
     fn add_faces(&self, faces: impl IntoIterator<Item = Handle<Face>>) -> Self;
@@ -43,6 +43,36 @@ pub trait UpdateShell {
     /// Remove a face from the shell
     #[must_use]
     fn remove_face(&self, handle: &Handle<Face>) -> Self;
+
+    /// Fallible version of [`UpdateShell::update_face`]
+    ///
+    /// Returns the error, if `update` returns one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object can't be found.
+    ///
+    /// Panics, if the update results in a duplicate object.
+    fn try_update_face<E>(
+        &self,
+        handle: &Handle<Face>,
+        update: impl FnOnce(&Handle<Face>) -> Result<Handle<Face>, E>,
+    ) -> Result<Self, E>;
+
+    /// Fallible version of [`UpdateShell::replace_face`]
+    ///
+    /// Returns the error, if `replace` returns one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object can't be found.
+    ///
+    /// Panics, if the update results in a duplicate object.
+    fn try_replace_face<const N: usize, E>(
+        &self,
+        handle: &Handle<Face>,
+        replace: impl FnOnce(&Handle<Face>) -> Result<[Handle<Face>; N], E>,
+    ) -> Result<Self, E>;
 }
 
 impl UpdateShell for Shell {
@@ -84,4 +114,28 @@ impl UpdateShell for Shell {
 
         Shell::new(faces)
     }
+
+    fn try_update_face<E>(
+        &self,
+        handle: &Handle<Face>,
+        update: impl FnOnce(&Handle<Face>) -> Result<Handle<Face>, E>,
+    ) -> Result<Self, E> {
+        let faces = self
+            .faces()
+            .replace(handle, [update(handle)?])
+            .expect("Face not found");
+        Ok(Shell::new(faces))
+    }
+
+    fn try_replace_face<const N: usize, E>(
+        &self,
+        handle: &Handle<Face>,
+        replace: impl FnOnce(&Handle<Face>) -> Result<[Handle<Face>; N], E>,
+    ) -> Result<Self, E> {
+        let faces = self
+            .faces()
+            .replace(handle, replace(handle)?)
+            .expect("Face not found");
+        Ok(Shell::new(faces))
+    }
 }
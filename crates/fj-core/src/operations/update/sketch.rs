@@ -4,7 +4,7 @@ use crate::{
 };
 
 /// Update a [`Sketch`]
-pub trait UpdateSketch {
+pub trait UpdateSketch: Sized {
     /// Add a region to the sketch
     #[must_use]
     fn add_region(&self, region: Handle<Region>) -> Self;
@@ -39,6 +39,36 @@ pub trait UpdateSketch {
         handle: &Handle<Region>,
         replace: impl FnOnce(&Handle<Region>) -> [Handle<Region>; N],
     ) -> Self;
+
+    /// Fallible version of [`UpdateSketch::update_region`]
+    ///
+    /// Returns the error, if `update` returns one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object can't be found.
+    ///
+    /// Panics, if the update results in a duplicate object.
+    fn try_update_region<E>(
+        &self,
+        handle: &Handle<Region>,
+        update: impl FnOnce(&Handle<Region>) -> Result<Handle<Region>, E>,
+    ) -> Result<Self, E>;
+
+    /// Fallible version of [`UpdateSketch::replace_region`]
+    ///
+    /// Returns the error, if `replace` returns one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object can't be found.
+    ///
+    /// Panics, if the update results in a duplicate object.
+    fn try_replace_region<const N: usize, E>(
+        &self,
+        handle: &Handle<Region>,
+        replace: impl FnOnce(&Handle<Region>) -> Result<[Handle<Region>; N], E>,
+    ) -> Result<Self, E>;
 }
 
 impl UpdateSketch for Sketch {
@@ -69,4 +99,28 @@ impl UpdateSketch for Sketch {
             .expect("Region not found");
         Sketch::new(regions)
     }
+
+    fn try_update_region<E>(
+        &self,
+        handle: &Handle<Region>,
+        update: impl FnOnce(&Handle<Region>) -> Result<Handle<Region>, E>,
+    ) -> Result<Self, E> {
+        let regions = self
+            .regions()
+            .replace(handle, [update(handle)?])
+            .expect("Region not found");
+        Ok(Sketch::new(regions))
+    }
+
+    fn try_replace_region<const N: usize, E>(
+        &self,
+        handle: &Handle<Region>,
+        replace: impl FnOnce(&Handle<Region>) -> Result<[Handle<Region>; N], E>,
+    ) -> Result<Self, E> {
+        let regions = self
+            .regions()
+            .replace(handle, replace(handle)?)
+            .expect("Region not found");
+        Ok(Sketch::new(regions))
+    }
 }
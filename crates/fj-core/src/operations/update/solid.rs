@@ -4,7 +4,7 @@ use crate::{
 };
 
 /// Update a [`Solid`]
-pub trait UpdateSolid {
+pub trait UpdateSolid: Sized {
     /// Add a shell to the solid
     #[must_use]
     fn add_shells(
@@ -42,6 +42,36 @@ pub trait UpdateSolid {
         handle: &Handle<Shell>,
         replace: impl FnOnce(&Handle<Shell>) -> [Handle<Shell>; N],
     ) -> Self;
+
+    /// Fallible version of [`UpdateSolid::update_shell`]
+    ///
+    /// Returns the error, if `update` returns one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object can't be found.
+    ///
+    /// Panics, if the update results in a duplicate object.
+    fn try_update_shell<E>(
+        &self,
+        handle: &Handle<Shell>,
+        update: impl FnOnce(&Handle<Shell>) -> Result<Handle<Shell>, E>,
+    ) -> Result<Self, E>;
+
+    /// Fallible version of [`UpdateSolid::replace_shell`]
+    ///
+    /// Returns the error, if `replace` returns one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object can't be found.
+    ///
+    /// Panics, if the update results in a duplicate object.
+    fn try_replace_shell<const N: usize, E>(
+        &self,
+        handle: &Handle<Shell>,
+        replace: impl FnOnce(&Handle<Shell>) -> Result<[Handle<Shell>; N], E>,
+    ) -> Result<Self, E>;
 }
 
 impl UpdateSolid for Solid {
@@ -76,4 +106,28 @@ impl UpdateSolid for Solid {
             .expect("Shell not found");
         Solid::new(shells)
     }
+
+    fn try_update_shell<E>(
+        &self,
+        handle: &Handle<Shell>,
+        update: impl FnOnce(&Handle<Shell>) -> Result<Handle<Shell>, E>,
+    ) -> Result<Self, E> {
+        let shells = self
+            .shells()
+            .replace(handle, [update(handle)?])
+            .expect("Shell not found");
+        Ok(Solid::new(shells))
+    }
+
+    fn try_replace_shell<const N: usize, E>(
+        &self,
+        handle: &Handle<Shell>,
+        replace: impl FnOnce(&Handle<Shell>) -> Result<[Handle<Shell>; N], E>,
+    ) -> Result<Self, E> {
+        let shells = self
+            .shells()
+            .replace(handle, replace(handle)?)
+            .expect("Shell not found");
+        Ok(Solid::new(shells))
+    }
 }
@@ -0,0 +1,213 @@
+//! # Wrap sketches onto curved surfaces
+//!
+//! See [`WrapSketch`].
+
+use fj_math::{Circle, Involute, Line, Point, Scalar, Spiral, Vector};
+
+use crate::{
+    geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{HalfEdge, Sketch},
+    operations::map::{MapCache, MapObjects, MapTarget},
+    services::Services,
+};
+
+/// Wrap a sketch onto a curved surface
+///
+/// Wrapping a sketch maps it into the parameter space of the surface, such
+/// that lengths within the sketch are preserved, once the result is put on the
+/// surface. This is like wrapping a label around a bottle, and is useful for
+/// embossing text or cutting cam tracks into curved faces.
+pub trait WrapSketch: Sized {
+    /// Wrap the sketch onto a cylinder
+    ///
+    /// The sketch's u-axis is wrapped around the cylinder, its v-axis runs
+    /// along the cylinder's axis. The origin of the sketch ends up at the
+    /// origin of the surface.
+    ///
+    /// The result is a sketch in the parameter space of the surface. Use it
+    /// with the surface, to build faces that can be swept into a solid or
+    /// subtracted from one.
+    fn wrap_onto_cylinder(
+        &self,
+        surface: &SurfaceGeometry,
+        services: &mut Services,
+    ) -> Result<Self, WrapError>;
+}
+
+impl WrapSketch for Sketch {
+    fn wrap_onto_cylinder(
+        &self,
+        surface: &SurfaceGeometry,
+        services: &mut Services,
+    ) -> Result<Self, WrapError> {
+        let GlobalPath::Circle(circle) = surface.u else {
+            return Err(WrapError::NotACylinder);
+        };
+        if !is_perpendicular(&circle, surface.v) {
+            return Err(WrapError::NotACylinder);
+        }
+
+        // The length of one unit in each direction of the parameter space.
+        // Circle coordinates are angles, so it's the radius for the u-axis.
+        let scale = [circle.radius(), surface.v.magnitude()];
+
+        for region in self.regions() {
+            for cycle in region.all_cycles() {
+                for half_edge in cycle.half_edges() {
+                    if let SurfacePath::Circle(_) = half_edge.path() {
+                        if scale[0] != scale[1] {
+                            return Err(WrapError::CircleNotPreserved);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut mapper = HalfEdge::mapper(|half_edge: &HalfEdge| {
+            // The mapping is linear, which means the coordinates on each path
+            // stay the same. The boundary, curve, and vertices are unchanged.
+            HalfEdge::new(
+                wrap_path(half_edge.path(), scale),
+                half_edge.boundary(),
+                half_edge.curve().clone(),
+                half_edge.start_vertex().clone(),
+            )
+        });
+        let mut cache = MapCache::default();
+
+        let regions = self
+            .regions()
+            .iter()
+            .map(|region| {
+                region.map_objects_with_cache(
+                    mapper.as_mut(),
+                    &mut cache,
+                    services,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Sketch::new(regions))
+    }
+}
+
+/// Error wrapping a sketch onto a surface
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum WrapError {
+    /// The surface is not a cylinder
+    #[error("Surface is not a cylinder")]
+    NotACylinder,
+
+    /// A circle in the sketch can't be represented on the surface
+    ///
+    /// If the surface's parameter space is scaled differently in its two
+    /// directions, circles would turn into ellipses, which can't be represented
+    /// by a [`SurfacePath`] yet.
+    #[error("Wrapping circle onto surface would result in an ellipse")]
+    CircleNotPreserved,
+}
+
+fn is_perpendicular(circle: &Circle<3>, v: Vector<3>) -> bool {
+    const RELATIVE_EPSILON: f64 = 1e-9;
+
+    [circle.a(), circle.b()].into_iter().all(|axis| {
+        axis.dot(&v).abs()
+            <= axis.magnitude() * v.magnitude() * RELATIVE_EPSILON
+    })
+}
+
+fn wrap_path(path: SurfacePath, [u, v]: [Scalar; 2]) -> SurfacePath {
+    let point = |point: Point<2>| Point::from([point.u / u, point.v / v]);
+    let vector = |vector: Vector<2>| Vector::from([vector.u / u, vector.v / v]);
+
+    match path {
+        SurfacePath::Circle(circle) => SurfacePath::Circle(Circle::new(
+            point(circle.center()),
+            vector(circle.a()),
+            vector(circle.b()),
+        )),
+        SurfacePath::Line(line) => {
+            SurfacePath::Line(Line::from_origin_and_direction(
+                point(line.origin()),
+                vector(line.direction()),
+            ))
+        }
+        SurfacePath::Spiral(spiral) => SurfacePath::Spiral(Spiral::new(
+            point(spiral.center()),
+            vector(spiral.a()),
+            vector(spiral.b()),
+            spiral.start(),
+            spiral.growth(),
+        )),
+        SurfacePath::Involute(involute) => {
+            SurfacePath::Involute(Involute::new(
+                point(involute.center()),
+                vector(involute.a()),
+                vector(involute.b()),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        geometry::{GlobalPath, SurfaceGeometry},
+        operations::build::SketchBuilder,
+        services::Services,
+    };
+
+    use super::{WrapError, WrapSketch};
+
+    #[test]
+    fn wrap_onto_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let radius = 2.;
+        let surface = SurfaceGeometry {
+            u: GlobalPath::circle_from_radius(radius),
+            v: [0., 0., 4.].into(),
+        };
+
+        let sketch = SketchBuilder::start_at([0., 0.])
+            .line_to([PI, 0.])
+            .line_to([PI, 2.])
+            .line_to([0., 2.])
+            .close()
+            .build(&mut services);
+        let wrapped = sketch.wrap_onto_cylinder(&surface, &mut services)?;
+
+        let region = wrapped.regions().first();
+        for half_edge in region.exterior().half_edges() {
+            let point_surface = half_edge.start_position();
+            let point_sketch = Point::from([
+                point_surface.u * radius,
+                point_surface.v * Scalar::from(4.),
+            ]);
+
+            // The corners of the sketch must end up in the same places,
+            // measured along the surface.
+            let expected = [[0., 0.], [PI, 0.], [PI, 2.], [0., 2.]]
+                .map(Point::from)
+                .into_iter()
+                .any(|corner| {
+                    corner.distance_to(&point_sketch) < Scalar::from(1e-12)
+                });
+            assert!(expected, "Unexpected point: {point_sketch:?}");
+        }
+
+        let circle = SketchBuilder::start_at([0., 0.])
+            .circle([1., 1.], 1.)
+            .build(&mut services);
+        assert_eq!(
+            circle.wrap_onto_cylinder(&surface, &mut services),
+            Err(WrapError::CircleNotPreserved),
+        );
+
+        Ok(())
+    }
+}
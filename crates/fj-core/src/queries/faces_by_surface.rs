@@ -0,0 +1,320 @@
+use fj_math::{Line, Point, Scalar, Vector};
+
+use crate::{
+    geometry::{GlobalPath, SurfaceGeometry},
+    objects::{Face, Shell, Solid},
+    storage::Handle,
+};
+
+/// Group faces by the type and geometry of their surfaces
+///
+/// Faces end up in the same group, if their surfaces describe the same
+/// geometry, even if the surfaces are different objects with different
+/// coordinate systems. All coplanar faces end up in the same group, for
+/// example, as do all faces on the same cylinder.
+///
+/// This is useful for selecting faces, for merging faces during simplification,
+/// and for exporting faces to formats that support analytic surfaces.
+pub trait FacesBySurface {
+    /// Group the faces by the type and geometry of their surfaces
+    ///
+    /// `tolerance` is the maximum difference between the parameters of two
+    /// surfaces (like distances, radii, or the components of normalized
+    /// directions), for them to still be considered the same.
+    ///
+    /// Groups are returned in the order in which their first face appears.
+    fn faces_by_surface(
+        &self,
+        tolerance: impl Into<Scalar>,
+    ) -> Vec<SurfaceGroup>;
+}
+
+impl FacesBySurface for Shell {
+    fn faces_by_surface(
+        &self,
+        tolerance: impl Into<Scalar>,
+    ) -> Vec<SurfaceGroup> {
+        group_faces(self.faces(), tolerance.into())
+    }
+}
+
+impl FacesBySurface for Solid {
+    fn faces_by_surface(
+        &self,
+        tolerance: impl Into<Scalar>,
+    ) -> Vec<SurfaceGroup> {
+        let faces = self.shells().iter().flat_map(|shell| shell.faces());
+        group_faces(faces, tolerance.into())
+    }
+}
+
+/// A group of faces whose surfaces describe the same geometry
+///
+/// Returned by [`FacesBySurface::faces_by_surface`].
+#[derive(Clone, Debug)]
+pub struct SurfaceGroup {
+    /// The surface that all faces in the group share
+    pub surface: SurfaceType,
+
+    /// The faces in the group
+    pub faces: Vec<Handle<Face>>,
+}
+
+/// The type of a surface, and the parameters that define its geometry
+///
+/// Surfaces that describe the same geometry have the same parameters, up to
+/// floating-point accuracy, regardless of the coordinate systems they use.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SurfaceType {
+    /// A plane
+    Plane {
+        /// The normal of the plane
+        ///
+        /// This normal is not related to the orientation of any face. Its sign
+        /// is chosen such, that its largest component is positive.
+        normal: Vector<3>,
+
+        /// The distance of the plane from the origin, along the normal
+        distance: Scalar,
+    },
+
+    /// A circular cylinder
+    Cylinder {
+        /// The axis of the cylinder
+        ///
+        /// The origin of the axis is the point on it that is closest to the
+        /// origin of the coordinate system. Its direction is normalized, with
+        /// its largest component being positive.
+        axis: Line<3>,
+
+        /// The radius of the cylinder
+        radius: Scalar,
+    },
+
+    /// Any other surface, swept from a path along a straight line
+    ///
+    /// Faces on such surfaces are only grouped, if the geometry of their
+    /// surfaces is exactly the same.
+    Swept(SurfaceGeometry),
+}
+
+impl SurfaceType {
+    /// Determine the type of a surface
+    pub fn of(surface: &SurfaceGeometry) -> Self {
+        match surface.u {
+            GlobalPath::Line(line) => {
+                let normal =
+                    canonical_direction(line.direction().cross(&surface.v));
+                let distance = normal.dot(&line.origin().coords);
+
+                Self::Plane { normal, distance }
+            }
+            GlobalPath::Circle(circle) => {
+                let circle_normal = circle.a().cross(&circle.b()).normalize();
+                let is_right_cylinder =
+                    surface.v.cross(&circle_normal).magnitude()
+                        <= surface.v.magnitude() * Scalar::from_f64(EPSILON);
+
+                if !is_right_cylinder {
+                    return Self::Swept(*surface);
+                }
+
+                let direction = canonical_direction(circle_normal);
+                let center = circle.center();
+                let origin = center - direction * direction.dot(&center.coords);
+
+                Self::Cylinder {
+                    axis: Line::from_origin_and_direction(origin, direction),
+                    radius: circle.radius(),
+                }
+            }
+            _ => Self::Swept(*surface),
+        }
+    }
+
+    /// Indicate whether two surfaces describe the same geometry
+    ///
+    /// See [`FacesBySurface::faces_by_surface`] for the meaning of `tolerance`.
+    pub fn is_same_as(
+        &self,
+        other: &Self,
+        tolerance: impl Into<Scalar>,
+    ) -> bool {
+        let tolerance = tolerance.into();
+        let close = |a: Scalar, b: Scalar| (a - b).abs() <= tolerance;
+        let close_vectors =
+            |a: Vector<3>, b: Vector<3>| (a - b).magnitude() <= tolerance;
+        let close_points =
+            |a: Point<3>, b: Point<3>| a.distance_to(&b) <= tolerance;
+
+        match (self, other) {
+            (
+                Self::Plane { normal, distance },
+                Self::Plane {
+                    normal: other_normal,
+                    distance: other_distance,
+                },
+            ) => {
+                close_vectors(*normal, *other_normal)
+                    && close(*distance, *other_distance)
+            }
+            (
+                Self::Cylinder { axis, radius },
+                Self::Cylinder {
+                    axis: other_axis,
+                    radius: other_radius,
+                },
+            ) => {
+                close_vectors(axis.direction(), other_axis.direction())
+                    && close_points(axis.origin(), other_axis.origin())
+                    && close(*radius, *other_radius)
+            }
+            (Self::Swept(surface), Self::Swept(other_surface)) => {
+                surface == other_surface
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Used to determine whether vectors are parallel, relative to their length
+const EPSILON: f64 = 1e-9;
+
+fn group_faces<'r>(
+    faces: impl IntoIterator<Item = &'r Handle<Face>>,
+    tolerance: Scalar,
+) -> Vec<SurfaceGroup> {
+    let mut groups = Vec::<SurfaceGroup>::new();
+
+    for face in faces {
+        let surface = SurfaceType::of(&face.surface().geometry());
+
+        match groups
+            .iter_mut()
+            .find(|group| group.surface.is_same_as(&surface, tolerance))
+        {
+            Some(group) => group.faces.push(face.clone()),
+            None => groups.push(SurfaceGroup {
+                surface,
+                faces: vec![face.clone()],
+            }),
+        }
+    }
+
+    groups
+}
+
+/// Normalize a direction, and flip it, so its largest component is positive
+///
+/// Components whose magnitudes are within [`EPSILON`] of each other count as
+/// equally large. Of those, the first one (in x, y, z order) decides the sign.
+/// Otherwise, directions like `(1, -1, 0)` could end up flipped one way or the
+/// other, depending on floating-point noise.
+fn canonical_direction(direction: Vector<3>) -> Vector<3> {
+    let direction = direction.normalize();
+
+    let largest = direction
+        .components
+        .into_iter()
+        .map(|component| component.abs())
+        .max()
+        .unwrap_or(Scalar::ZERO);
+    let deciding = direction
+        .components
+        .into_iter()
+        .find(|component| {
+            component.abs() >= largest - Scalar::from_f64(EPSILON)
+        })
+        .unwrap_or(Scalar::ZERO);
+
+    if deciding < Scalar::ZERO {
+        -direction
+    } else {
+        direction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Scalar, Vector};
+
+    use crate::{
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    use super::{canonical_direction, FacesBySurface, SurfaceType};
+
+    #[test]
+    fn group_coplanar_faces() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let cone = Solid::cone(1., 1., 8, &mut services).insert(&mut services);
+
+        // The base of the cone consists of 8 triangles, in the same plane.
+        let groups = cone.faces_by_surface(1e-9);
+        assert_eq!(groups.len(), 9);
+
+        let base = groups
+            .iter()
+            .find(|group| group.faces.len() > 1)
+            .expect("Expected base of cone to be grouped");
+        assert_eq!(base.faces.len(), 8);
+        assert!(base.surface.is_same_as(
+            &SurfaceType::Plane {
+                normal: Vector::unit_z(),
+                distance: Scalar::ZERO,
+            },
+            1e-9
+        ));
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn group_faces_of_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let cylinder =
+            Solid::cylinder(1., 2., &mut services).insert(&mut services);
+
+        let groups = cylinder.faces_by_surface(1e-9);
+        assert_eq!(groups.len(), 3);
+
+        let side = groups
+            .iter()
+            .find_map(|group| match group.surface {
+                SurfaceType::Cylinder { axis, radius } => Some((axis, radius)),
+                _ => None,
+            })
+            .expect("Expected side of cylinder");
+        assert_eq!(side.0.direction(), Vector::unit_z());
+        assert_eq!(side.1, Scalar::ONE);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn canonical_direction_ties() {
+        let noise = 1e-12;
+        let directions = [
+            Vector::from([1., -1., 0.]),
+            Vector::from([1., -1. + noise, 0.]),
+            Vector::from([1. - noise, -1., 0.]),
+            Vector::from([-1., 1. - noise, 0.]),
+            Vector::from([-1. + noise, 1., 0.]),
+        ];
+
+        let expected = Vector::from([1., -1., 0.]).normalize();
+        for direction in directions {
+            let direction = canonical_direction(direction);
+            assert!(
+                (direction - expected).magnitude() < Scalar::from_f64(1e-9),
+                "Unexpected direction: {direction:?}"
+            );
+        }
+    }
+}
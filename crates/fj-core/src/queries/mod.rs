@@ -11,10 +11,18 @@
 
 mod all_half_edges_with_surface;
 mod bounding_vertices_of_half_edge;
+mod faces_by_surface;
+mod select;
 mod sibling_of_half_edge;
+mod visit_objects;
 
 pub use self::{
     all_half_edges_with_surface::AllHalfEdgesWithSurface,
     bounding_vertices_of_half_edge::BoundingVerticesOfHalfEdge,
+    faces_by_surface::{FacesBySurface, SurfaceGroup, SurfaceType},
+    select::{FaceSelector, HalfEdgeSelector, Select},
     sibling_of_half_edge::SiblingOfHalfEdge,
+    visit_objects::{VisitObjects, Visitor},
 };
+
+pub(crate) use self::select::{dihedral_angle, oriented_normal};
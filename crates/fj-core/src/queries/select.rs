@@ -0,0 +1,575 @@
+use std::ops;
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    geometry::{GlobalPath, SurfacePath},
+    objects::{Face, HalfEdge, Handedness, ObjectSet, Shell},
+    storage::Handle,
+};
+
+use super::SiblingOfHalfEdge;
+
+/// Select objects by geometric and topological criteria
+///
+/// Selectors describe the objects that an operation should apply to by intent
+/// ("all faces pointing up", "all edges of this face"), instead of by picking
+/// handles out of the object graph manually. That makes parametric models more
+/// robust, as the selection adapts when the shape changes.
+pub trait Select {
+    /// Select all faces that match the selector
+    fn select_faces(&self, selector: &FaceSelector) -> ObjectSet<Face>;
+
+    /// Select all half-edges that match the selector
+    fn select_half_edges(
+        &self,
+        selector: &HalfEdgeSelector,
+    ) -> ObjectSet<HalfEdge>;
+}
+
+impl Select for Shell {
+    fn select_faces(&self, selector: &FaceSelector) -> ObjectSet<Face> {
+        self.faces()
+            .iter()
+            .filter(|face| selector.matches(face, self))
+            .cloned()
+            .collect()
+    }
+
+    fn select_half_edges(
+        &self,
+        selector: &HalfEdgeSelector,
+    ) -> ObjectSet<HalfEdge> {
+        self.faces()
+            .iter()
+            .flat_map(|face| {
+                face.region()
+                    .all_cycles()
+                    .flat_map(|cycle| cycle.half_edges().iter())
+                    .filter(|half_edge| selector.matches(half_edge, face, self))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+/// Selects faces
+///
+/// Selectors can be combined using [`FaceSelector::and`],
+/// [`FaceSelector::or`], and negated using the `!` operator.
+#[derive(Clone, Debug)]
+pub enum FaceSelector {
+    /// Faces whose normal points in the given direction
+    ///
+    /// The tolerance is the maximum angle between the normal and the direction,
+    /// in radians. For faces that are not planar, their average normal is used.
+    /// If the direction is zero, no faces match.
+    Normal {
+        /// The direction that the normal should point in
+        direction: Vector<3>,
+
+        /// The maximum angle between the normal and the direction
+        tolerance: Scalar,
+    },
+
+    /// Faces that are bounded by the half-edge or its sibling
+    AdjacentToHalfEdge(Handle<HalfEdge>),
+
+    /// Faces that match all selectors
+    And(Vec<FaceSelector>),
+
+    /// Faces that match any selector
+    Or(Vec<FaceSelector>),
+
+    /// Faces that don't match the selector
+    Not(Box<FaceSelector>),
+}
+
+impl FaceSelector {
+    /// Select faces whose normal points in the given direction
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `direction` has a length of zero.
+    pub fn normal(
+        direction: impl Into<Vector<3>>,
+        tolerance: impl Into<Scalar>,
+    ) -> Self {
+        let direction = direction.into();
+        assert_ne!(
+            direction.magnitude(),
+            Scalar::ZERO,
+            "Direction of face normal must not be zero"
+        );
+
+        Self::Normal {
+            direction,
+            tolerance: tolerance.into(),
+        }
+    }
+
+    /// Select faces that match this selector and the other one
+    pub fn and(self, other: Self) -> Self {
+        Self::And(vec![self, other])
+    }
+
+    /// Select faces that match this selector or the other one
+    pub fn or(self, other: Self) -> Self {
+        Self::Or(vec![self, other])
+    }
+
+    fn matches(&self, face: &Handle<Face>, shell: &Shell) -> bool {
+        match self {
+            Self::Normal {
+                direction,
+                tolerance,
+            } => {
+                // The variant can be constructed directly, bypassing the check
+                // in the constructor. A zero direction doesn't point anywhere,
+                // so no face matches it.
+                if direction.magnitude() == Scalar::ZERO {
+                    return false;
+                }
+
+                let normal = oriented_normal(face);
+                normal.dot(&direction.normalize()) >= tolerance.cos()
+            }
+            Self::AdjacentToHalfEdge(half_edge) => {
+                face.region().all_cycles().any(|cycle| {
+                    cycle.half_edges().iter().any(|h| {
+                        h.id() == half_edge.id()
+                            || shell.are_siblings(h, half_edge)
+                    })
+                })
+            }
+            Self::And(selectors) => selectors
+                .iter()
+                .all(|selector| selector.matches(face, shell)),
+            Self::Or(selectors) => selectors
+                .iter()
+                .any(|selector| selector.matches(face, shell)),
+            Self::Not(selector) => !selector.matches(face, shell),
+        }
+    }
+}
+
+impl ops::Not for FaceSelector {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self::Not(Box::new(self))
+    }
+}
+
+/// Selects half-edges
+///
+/// Selectors can be combined using [`HalfEdgeSelector::and`],
+/// [`HalfEdgeSelector::or`], and negated using the `!` operator.
+#[derive(Clone, Debug)]
+pub enum HalfEdgeSelector {
+    /// Half-edges that are longer than the given length
+    LongerThan(Scalar),
+
+    /// Half-edges that are shorter than the given length
+    ShorterThan(Scalar),
+
+    /// Half-edges of the edges that bound the face
+    ///
+    /// This includes the half-edges of the face itself, as well as their
+    /// siblings in neighboring faces.
+    AdjacentToFace(Handle<Face>),
+
+    /// Half-edges whose adjacent faces meet at an angle within a range
+    ///
+    /// The angle is measured through the inside of the shell, in radians, at
+    /// the midpoint of the edge. Faces that meet at a right angle form an angle
+    /// of 90°, if the edge is convex (like the edges of a box), and of 270°, if
+    /// it is concave. Coplanar faces meet at 180°.
+    ///
+    /// This assumes that the faces of the shell point outward. Both half-edges
+    /// of a matching edge are selected. Half-edges that don't have a sibling
+    /// are never selected.
+    DihedralAngle {
+        /// The minimum angle, inclusive
+        min: Scalar,
+
+        /// The maximum angle, inclusive
+        max: Scalar,
+    },
+
+    /// Half-edges that match all selectors
+    And(Vec<HalfEdgeSelector>),
+
+    /// Half-edges that match any selector
+    Or(Vec<HalfEdgeSelector>),
+
+    /// Half-edges that don't match the selector
+    Not(Box<HalfEdgeSelector>),
+}
+
+impl HalfEdgeSelector {
+    /// Select half-edges whose adjacent faces meet at an angle within a range
+    ///
+    /// See [`HalfEdgeSelector::DihedralAngle`]. For example, this selects all
+    /// convex edges where faces meet at a right angle:
+    ///
+    /// ```
+    /// use std::f64::consts::FRAC_PI_2;
+    ///
+    /// use fj_core::queries::HalfEdgeSelector;
+    ///
+    /// let selector =
+    ///     HalfEdgeSelector::dihedral_angle(FRAC_PI_2 - 0.01, FRAC_PI_2 + 0.01);
+    /// ```
+    pub fn dihedral_angle(
+        min: impl Into<Scalar>,
+        max: impl Into<Scalar>,
+    ) -> Self {
+        Self::DihedralAngle {
+            min: min.into(),
+            max: max.into(),
+        }
+    }
+
+    /// Select half-edges of convex edges, whose faces meet at a sharp angle
+    ///
+    /// Selects all edges whose adjacent faces meet at an angle of less than
+    /// 180°, minus `tolerance`. Those are the edges that would typically be
+    /// rounded off by a fillet.
+    pub fn convex(tolerance: impl Into<Scalar>) -> Self {
+        Self::dihedral_angle(Scalar::ZERO, Scalar::PI - tolerance.into())
+    }
+
+    /// Select half-edges of concave edges
+    ///
+    /// Selects all edges whose adjacent faces meet at an angle of more than
+    /// 180°, plus `tolerance`.
+    pub fn concave(tolerance: impl Into<Scalar>) -> Self {
+        Self::dihedral_angle(Scalar::PI + tolerance.into(), Scalar::TAU)
+    }
+
+    /// Select half-edges that match this selector and the other one
+    pub fn and(self, other: Self) -> Self {
+        Self::And(vec![self, other])
+    }
+
+    /// Select half-edges that match this selector or the other one
+    pub fn or(self, other: Self) -> Self {
+        Self::Or(vec![self, other])
+    }
+
+    fn matches(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+        face: &Handle<Face>,
+        shell: &Shell,
+    ) -> bool {
+        match self {
+            Self::LongerThan(length) => length_of(half_edge, face) > *length,
+            Self::ShorterThan(length) => length_of(half_edge, face) < *length,
+            Self::AdjacentToFace(other) => {
+                if face.id() == other.id() {
+                    return true;
+                }
+
+                let Some(sibling) = shell.get_sibling_of(half_edge) else {
+                    return false;
+                };
+                other.region().all_cycles().any(|cycle| {
+                    cycle.half_edges().iter().any(|h| h.id() == sibling.id())
+                })
+            }
+            Self::DihedralAngle { min, max } => {
+                match dihedral_angle(half_edge, face, shell) {
+                    Some(angle) => angle >= *min && angle <= *max,
+                    None => false,
+                }
+            }
+            Self::And(selectors) => selectors
+                .iter()
+                .all(|selector| selector.matches(half_edge, face, shell)),
+            Self::Or(selectors) => selectors
+                .iter()
+                .any(|selector| selector.matches(half_edge, face, shell)),
+            Self::Not(selector) => !selector.matches(half_edge, face, shell),
+        }
+    }
+}
+
+impl ops::Not for HalfEdgeSelector {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self::Not(Box::new(self))
+    }
+}
+
+/// Compute the normal of a face, taking its orientation into account
+///
+/// Uses the exterior of the face. For faces that are not planar, this results
+/// in an average normal.
+///
+/// Curved half-edges are sampled at multiple points. Otherwise, the normal of
+/// a face that is bounded by only one or two curved half-edges, like the cap
+/// of a cylinder, could not be computed.
+pub(crate) fn oriented_normal(face: &Face) -> Vector<3> {
+    const NUM_SEGMENTS: u64 = 16;
+
+    let points = face
+        .region()
+        .exterior()
+        .half_edges()
+        .iter()
+        .flat_map(|half_edge| {
+            let mut points = points_along(half_edge, face, NUM_SEGMENTS);
+
+            // The end of a half-edge is the start of the next one.
+            points.pop();
+
+            points
+        })
+        .collect::<Vec<_>>();
+
+    // Newell's method, which works for any simple polygon.
+    let mut normal = Vector::from([0., 0., 0.]);
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        normal = normal + (a.coords).cross(&b.coords);
+    }
+
+    normal.normalize()
+}
+
+/// Compute the length of a half-edge in 3D space
+///
+/// Curved half-edges are approximated by a fixed number of line segments.
+fn length_of(half_edge: &HalfEdge, face: &Face) -> Scalar {
+    const NUM_SEGMENTS: u64 = 64;
+
+    points_along(half_edge, face, NUM_SEGMENTS)
+        .windows(2)
+        .map(|segment| segment[0].distance_to(&segment[1]))
+        .fold(Scalar::ZERO, |length, distance| length + distance)
+}
+
+/// Compute points along a half-edge in 3D space, including both ends
+///
+/// Straight half-edges are represented by their end points. Curved ones are
+/// split into the provided number of segments.
+fn points_along(
+    half_edge: &HalfEdge,
+    face: &Face,
+    num_segments: u64,
+) -> Vec<Point<3>> {
+    let surface = face.surface().geometry();
+    let is_straight = matches!(half_edge.path(), SurfacePath::Line(_))
+        && matches!(surface.u, GlobalPath::Line(_));
+    let num_segments = if is_straight { 1 } else { num_segments };
+
+    let [start, end] = half_edge.boundary().inner;
+    (0..=num_segments)
+        .map(|i| {
+            let t = start.t
+                + (end.t - start.t) * Scalar::from_u64(i)
+                    / Scalar::from_u64(num_segments);
+            let point = half_edge.path().point_from_path_coords([t]);
+            surface.point_from_surface_coords(point)
+        })
+        .collect()
+}
+
+/// Compute the angle at which the faces adjacent to a half-edge meet
+///
+/// See [`HalfEdgeSelector::DihedralAngle`]. Returns `None`, if the half-edge
+/// has no sibling.
+pub(crate) fn dihedral_angle(
+    half_edge: &Handle<HalfEdge>,
+    face: &Face,
+    shell: &Shell,
+) -> Option<Scalar> {
+    let sibling = shell.get_sibling_of(half_edge)?;
+    let sibling_face = shell.faces().iter().find(|other| {
+        other.region().all_cycles().any(|cycle| {
+            cycle.half_edges().iter().any(|h| h.id() == sibling.id())
+        })
+    })?;
+
+    let [start, end] = half_edge.boundary().inner;
+    let t = (start.t + end.t) / 2.;
+    let dt = (end.t - start.t) * EPSILON;
+
+    let surface = face.surface().geometry();
+    let point_on_surface =
+        |t: Scalar| half_edge.path().point_from_path_coords([t]);
+    let tangent = (surface.point_from_surface_coords(point_on_surface(t + dt))
+        - surface.point_from_surface_coords(point_on_surface(t - dt)))
+    .normalize();
+
+    // Siblings share the same curve coordinates, so this is the same point.
+    let sibling_point = sibling.path().point_from_path_coords([t]);
+
+    let normal = normal_at(face, point_on_surface(t));
+    let sibling_normal = normal_at(sibling_face, sibling_point);
+
+    // The inside of the shell is behind both faces. Whether the edge is convex
+    // depends on whether the sibling face bends toward the inside of `face`,
+    // or away from it.
+    let into_face = normal.cross(&tangent);
+    let cos = normal.dot(&sibling_normal).into_f64().clamp(-1., 1.);
+    let angle_between_normals = Scalar::from_f64(cos.acos());
+
+    let angle = if sibling_normal.dot(&into_face) <= Scalar::ZERO {
+        Scalar::PI - angle_between_normals
+    } else {
+        Scalar::PI + angle_between_normals
+    };
+
+    Some(angle)
+}
+
+/// Compute the normal of a face at a point, taking its orientation into account
+fn normal_at(face: &Face, point: Point<2>) -> Vector<3> {
+    let surface = face.surface().geometry();
+
+    let [du, dv] = [[EPSILON, 0.], [0., EPSILON]].map(|step| {
+        let step = Vector::from(step);
+        surface.point_from_surface_coords(point + step)
+            - surface.point_from_surface_coords(point - step)
+    });
+    let normal = du.cross(&dv).normalize();
+
+    match face.coord_handedness() {
+        Handedness::RightHanded => normal,
+        Handedness::LeftHanded => -normal,
+    }
+}
+
+/// The step size used for computing derivatives by finite differences
+const EPSILON: f64 = 1e-6;
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use fj_math::{Scalar, Vector};
+
+    use crate::{
+        objects::{Shell, Solid},
+        operations::{
+            build::BuildShell, insert::Insert, primitives::BuildPrimitive,
+        },
+        services::Services,
+    };
+
+    use super::{FaceSelector, HalfEdgeSelector, Select};
+
+    #[test]
+    fn select_tetrahedron() {
+        let mut services = Services::new();
+
+        let tetrahedron = Shell::tetrahedron(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services);
+        let shell = &tetrahedron.shell;
+        let bottom = &tetrahedron.abc.face;
+
+        // The order of the points makes the faces of this tetrahedron point
+        // inward, so the normal of the bottom face points up.
+        let faces =
+            shell.select_faces(&FaceSelector::normal([0., 0., 1.], 0.1));
+        assert_eq!(faces.only().id(), bottom.id());
+
+        let faces =
+            shell.select_faces(&!FaceSelector::normal([0., 0., 1.], 0.1));
+        assert_eq!(faces.len(), 3);
+
+        let long = HalfEdgeSelector::LongerThan(Scalar::from(1.2));
+        let adjacent = HalfEdgeSelector::AdjacentToFace(bottom.clone());
+
+        assert_eq!(shell.select_half_edges(&long).len(), 6);
+        assert_eq!(shell.select_half_edges(&adjacent).len(), 6);
+        assert_eq!(shell.select_half_edges(&long.and(adjacent)).len(), 2);
+
+        let half_edge = tetrahedron.abc.half_edges[0].clone();
+        let faces =
+            shell.select_faces(&FaceSelector::AdjacentToHalfEdge(half_edge));
+        assert_eq!(faces.len(), 2);
+    }
+
+    #[test]
+    fn select_cylinder_caps() {
+        let mut services = Services::new();
+
+        // Each cap is bounded by a single circular half-edge.
+        let cylinder =
+            Solid::cylinder(1., 2., &mut services).insert(&mut services);
+        let shell = cylinder.shells().only();
+
+        for direction in [[0., 0., 1.], [0., 0., -1.]] {
+            let faces =
+                shell.select_faces(&FaceSelector::normal(direction, 0.1));
+            let face = faces.only();
+            assert_eq!(face.region().exterior().half_edges().len(), 1);
+        }
+    }
+
+    #[test]
+    fn select_by_zero_normal() {
+        let mut services = Services::new();
+
+        let tetrahedron = Shell::tetrahedron(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services);
+
+        let selector = FaceSelector::Normal {
+            direction: Vector::from([0., 0., 0.]),
+            tolerance: Scalar::PI,
+        };
+        assert!(tetrahedron.shell.select_faces(&selector).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn face_selector_with_zero_normal() {
+        let _ = FaceSelector::normal([0., 0., 0.], 0.1);
+    }
+
+    #[test]
+    fn select_by_dihedral_angle() {
+        let mut services = Services::new();
+
+        // Unlike in the test above, the faces of this tetrahedron point
+        // outward, as the dihedral angle selectors require.
+        let tetrahedron = Shell::tetrahedron(
+            [[0., 0., 0.], [0., 1., 0.], [1., 0., 0.], [0., 0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services);
+        let shell = &tetrahedron.shell;
+
+        // The three edges at the origin are right angles. The others are
+        // sharper.
+        let right_angle = HalfEdgeSelector::dihedral_angle(
+            FRAC_PI_2 - 0.01,
+            FRAC_PI_2 + 0.01,
+        );
+        assert_eq!(shell.select_half_edges(&right_angle).len(), 6);
+        assert_eq!(
+            shell
+                .select_half_edges(&HalfEdgeSelector::convex(0.01))
+                .len(),
+            12
+        );
+        assert_eq!(
+            shell
+                .select_half_edges(&HalfEdgeSelector::concave(0.01))
+                .len(),
+            0
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    objects::{
+        Assembly, BehindHandle, Curve, Cycle, Datum, Face, HalfEdge, Object,
+        Region, Shell, Sketch, Solid, Surface, Vertex,
+    },
+    storage::Handle,
+};
+
+/// Callbacks for each kind of object, invoked by [`VisitObjects`]
+///
+/// All methods have default implementations that do nothing, so implementors
+/// only need to override the methods for the kinds of objects they are
+/// interested in.
+///
+/// Each method receives the object that is being visited, and the object
+/// through which it was reached. This parent is `None` for the object that the
+/// traversal started at. Objects that are referenced from multiple places are
+/// only visited once, so only the first parent they were reached through is
+/// provided.
+#[allow(unused_variables)]
+pub trait Visitor {
+    /// Visit an [`Assembly`]
+    fn visit_assembly(
+        &mut self,
+        assembly: &Handle<Assembly>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+
+    /// Visit a [`Curve`]
+    fn visit_curve(
+        &mut self,
+        curve: &Handle<Curve>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+
+    /// Visit a [`Cycle`]
+    fn visit_cycle(
+        &mut self,
+        cycle: &Handle<Cycle>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+
+    /// Visit a [`Datum`]
+    fn visit_datum(
+        &mut self,
+        datum: &Handle<Datum>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+
+    /// Visit a [`Face`]
+    fn visit_face(
+        &mut self,
+        face: &Handle<Face>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+
+    /// Visit a [`HalfEdge`]
+    fn visit_half_edge(
+        &mut self,
+        half_edge: &Handle<HalfEdge>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+
+    /// Visit a [`Region`]
+    fn visit_region(
+        &mut self,
+        region: &Handle<Region>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+
+    /// Visit a [`Shell`]
+    fn visit_shell(
+        &mut self,
+        shell: &Handle<Shell>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+
+    /// Visit a [`Sketch`]
+    fn visit_sketch(
+        &mut self,
+        sketch: &Handle<Sketch>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+
+    /// Visit a [`Solid`]
+    fn visit_solid(
+        &mut self,
+        solid: &Handle<Solid>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+
+    /// Visit a [`Surface`]
+    fn visit_surface(
+        &mut self,
+        surface: &Handle<Surface>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+
+    /// Visit a [`Vertex`]
+    fn visit_vertex(
+        &mut self,
+        vertex: &Handle<Vertex>,
+        parent: Option<&Object<BehindHandle>>,
+    ) {
+    }
+}
+
+/// Walk the object graph referenced by an object
+pub trait VisitObjects {
+    /// Visit the object and all objects it references, directly or indirectly
+    ///
+    /// Objects are visited depth-first, parents before their children, and
+    /// children in the order they are referenced by their parent. Every object
+    /// is visited exactly once, regardless of how many times it is referenced
+    /// within the graph.
+    fn visit_objects(&self, visitor: &mut impl Visitor);
+}
+
+impl<T> VisitObjects for Handle<T>
+where
+    Handle<T>: Into<Object<BehindHandle>>,
+{
+    fn visit_objects(&self, visitor: &mut impl Visitor) {
+        let mut visited = BTreeSet::new();
+        let root: Object<BehindHandle> = self.clone().into();
+        let mut stack = vec![(root, None)];
+
+        while let Some((object, parent)) = stack.pop() {
+            if !visited.insert(object.id()) {
+                continue;
+            }
+
+            visit(&object, parent.as_ref(), visitor);
+
+            // The stack is last-in, first-out. Pushing the children in reverse
+            // makes sure they are visited in order.
+            for child in children(&object).into_iter().rev() {
+                stack.push((child, Some(object.clone())));
+            }
+        }
+    }
+}
+
+fn visit(
+    object: &Object<BehindHandle>,
+    parent: Option<&Object<BehindHandle>>,
+    visitor: &mut impl Visitor,
+) {
+    match object {
+        Object::Assembly(assembly) => visitor.visit_assembly(assembly, parent),
+        Object::Curve(curve) => visitor.visit_curve(curve, parent),
+        Object::Cycle(cycle) => visitor.visit_cycle(cycle, parent),
+        Object::Datum(datum) => visitor.visit_datum(datum, parent),
+        Object::Face(face) => visitor.visit_face(face, parent),
+        Object::HalfEdge(half_edge) => {
+            visitor.visit_half_edge(half_edge, parent);
+        }
+        Object::Region(region) => visitor.visit_region(region, parent),
+        Object::Shell(shell) => visitor.visit_shell(shell, parent),
+        Object::Sketch(sketch) => visitor.visit_sketch(sketch, parent),
+        Object::Solid(solid) => visitor.visit_solid(solid, parent),
+        Object::Surface(surface) => visitor.visit_surface(surface, parent),
+        Object::Vertex(vertex) => visitor.visit_vertex(vertex, parent),
+    }
+}
+
+fn children(object: &Object<BehindHandle>) -> Vec<Object<BehindHandle>> {
+    match object {
+        Object::Assembly(assembly) => assembly
+            .instances()
+            .iter()
+            .map(|instance| instance.solid().clone().into())
+            .collect(),
+        Object::Curve(_)
+        | Object::Datum(_)
+        | Object::Surface(_)
+        | Object::Vertex(_) => Vec::new(),
+        Object::Cycle(cycle) => {
+            cycle.half_edges().iter().cloned().map(Into::into).collect()
+        }
+        Object::Face(face) => {
+            vec![face.surface().clone().into(), face.region().clone().into()]
+        }
+        Object::HalfEdge(half_edge) => vec![
+            half_edge.curve().clone().into(),
+            half_edge.start_vertex().clone().into(),
+        ],
+        Object::Region(region) => {
+            region.all_cycles().cloned().map(Into::into).collect()
+        }
+        Object::Shell(shell) => {
+            shell.faces().iter().cloned().map(Into::into).collect()
+        }
+        Object::Sketch(sketch) => {
+            sketch.regions().iter().cloned().map(Into::into).collect()
+        }
+        Object::Solid(solid) => {
+            solid.shells().iter().cloned().map(Into::into).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::{BehindHandle, Face, HalfEdge, Object, Shell},
+        operations::{build::BuildShell, insert::Insert},
+        services::Services,
+        storage::Handle,
+    };
+
+    use super::{VisitObjects, Visitor};
+
+    #[derive(Default)]
+    struct Counter {
+        faces: usize,
+        half_edges: usize,
+        half_edge_parents_are_cycles: bool,
+    }
+
+    impl Visitor for Counter {
+        fn visit_face(
+            &mut self,
+            _: &Handle<Face>,
+            _: Option<&Object<BehindHandle>>,
+        ) {
+            self.faces += 1;
+        }
+
+        fn visit_half_edge(
+            &mut self,
+            _: &Handle<HalfEdge>,
+            parent: Option<&Object<BehindHandle>>,
+        ) {
+            self.half_edges += 1;
+            self.half_edge_parents_are_cycles &=
+                matches!(parent, Some(Object::Cycle(_)));
+        }
+    }
+
+    #[test]
+    fn visit_tetrahedron() {
+        let mut services = Services::new();
+
+        let tetrahedron = Shell::tetrahedron(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services);
+
+        let mut counter = Counter {
+            half_edge_parents_are_cycles: true,
+            ..Counter::default()
+        };
+        tetrahedron.shell.visit_objects(&mut counter);
+
+        assert_eq!(counter.faces, 4);
+        assert_eq!(counter.half_edges, 12);
+        assert!(counter.half_edge_parents_are_cycles);
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    objects::{BehindHandle, Object},
+    storage::ObjectId,
+    validate::ValidationError,
+};
+
+use super::Provenance;
+
+/// A snapshot of the state of [`Services`], taken under a name
+///
+/// Created by [`Services::checkpoint`]. The object stores are append-only, so a
+/// checkpoint only needs to remember how many objects had been inserted. The
+/// validation errors and the recorded provenance are copied though, so taking a
+/// checkpoint costs time and memory proportional to their size.
+///
+/// [`Services`]: super::Services
+/// [`Services::checkpoint`]: super::Services::checkpoint
+#[derive(Clone)]
+pub struct Checkpoint {
+    pub(super) num_inserted: usize,
+    pub(super) num_validated: usize,
+    pub(super) errors: BTreeMap<ObjectId, ValidationError>,
+    pub(super) provenance: Provenance,
+}
+
+impl Checkpoint {
+    /// Access the number of objects that had been inserted at the checkpoint
+    pub fn num_inserted(&self) -> usize {
+        self.num_inserted
+    }
+}
+
+/// The changes to [`Services`] since a [`Checkpoint`] was taken
+///
+/// Returned by [`Services::diff_checkpoint`].
+///
+/// [`Services`]: super::Services
+/// [`Services::diff_checkpoint`]: super::Services::diff_checkpoint
+#[derive(Clone, Debug)]
+pub struct CheckpointDiff {
+    /// The objects that have been inserted since the checkpoint
+    pub inserted: Vec<Object<BehindHandle>>,
+
+    /// The validation errors that have occurred since the checkpoint
+    pub new_errors: BTreeMap<ObjectId, ValidationError>,
+}
+
+impl CheckpointDiff {
+    /// Indicate whether nothing has changed since the checkpoint
+    pub fn is_empty(&self) -> bool {
+        self.inserted.is_empty() && self.new_errors.is_empty()
+    }
+}
+
+/// No checkpoint with the given name exists
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[error("No checkpoint named `{0}`")]
+pub struct UnknownCheckpoint(pub String);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::Vertex, operations::insert::Insert, services::Services,
+    };
+
+    use super::UnknownCheckpoint;
+
+    #[test]
+    fn diff_and_restore_checkpoint() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let _vertex = Vertex::new().insert(&mut services);
+        services.checkpoint("start");
+
+        let a = Vertex::new().insert(&mut services);
+        let b = Vertex::new().insert(&mut services);
+
+        let diff = services.diff_checkpoint("start")?;
+        let inserted = diff
+            .inserted
+            .iter()
+            .map(|object| object.id())
+            .collect::<Vec<_>>();
+        assert_eq!(inserted, [a.id(), b.id()]);
+
+        services.restore_checkpoint("start")?;
+        assert!(services.diff_checkpoint("start")?.is_empty());
+        assert_eq!(services.validation.num_validated(), 1);
+
+        // The checkpoint is kept, and can be restored again later.
+        let _vertex = Vertex::new().insert(&mut services);
+        assert_eq!(services.diff_checkpoint("start")?.inserted.len(), 1);
+        services.restore_checkpoint("start")?;
+        assert!(services.diff_checkpoint("start")?.is_empty());
+
+        assert_eq!(
+            services.restore_checkpoint("end"),
+            Err(UnknownCheckpoint(String::from("end")))
+        );
+
+        Ok(())
+    }
+}
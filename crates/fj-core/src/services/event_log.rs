@@ -0,0 +1,1062 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use fj_interop::mesh::Color;
+use fj_math::{
+    Circle, Ellipse, Involute, Line, Plane, Point, Scalar, Spiral, Vector,
+};
+
+use crate::{
+    geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{
+        Assembly, BehindHandle, Curve, Cycle, Datum, Face, HalfEdge, Instance,
+        Object, Region, Shell, Sketch, Solid, Surface, Vertex,
+    },
+    operations::insert::Insert,
+    storage::{Handle, ObjectId},
+};
+
+use super::Services;
+
+/// A serializable record of the objects that were inserted into [`Services`]
+///
+/// Created by [`Services::event_log`]. The log lists every inserted object,
+/// preceded by the objects it refers to, followed by the derivations that were
+/// recorded between them. [`EventLog::replay`] inserts these objects into
+/// another instance of `Services`, reproducing the same object graph.
+///
+/// An event log can be converted into a string and parsed from one, to save it
+/// to a file. The format is line-based, with one object or derivation per line.
+/// Objects refer to each other by their position in the log, like `#3`, and to
+/// the predefined planes by name, like `xy_plane`.
+#[derive(Clone, Debug, Default)]
+pub struct EventLog {
+    objects: Vec<LoggedObject>,
+    derivations: Vec<Derivation>,
+}
+
+impl EventLog {
+    /// Record the objects that were inserted into `services`
+    pub(super) fn record(services: &Services) -> Self {
+        let surfaces = &services.objects.surfaces;
+        let mut recorder = Recorder {
+            log: Self::default(),
+            refs: BTreeMap::from([
+                (surfaces.xy_plane().id(), Ref::XyPlane),
+                (surfaces.xz_plane().id(), Ref::XzPlane),
+                (surfaces.yz_plane().id(), Ref::YzPlane),
+            ]),
+        };
+
+        for object in &services.inserted {
+            recorder.object(object);
+        }
+
+        // Derivations are recorded in the order of the objects they derive, so
+        // the log doesn't depend on the order of object IDs.
+        let mut derivations = services
+            .provenance
+            .derivations()
+            .filter_map(|(id, sources)| match recorder.refs.get(&id) {
+                Some(Ref::Object(index)) => Some((*index, sources)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        derivations.sort_by_key(|(index, _)| *index);
+
+        for (object, sources) in derivations {
+            let sources = sources
+                .iter()
+                .map(|source| recorder.object(source))
+                .collect();
+            recorder
+                .log
+                .derivations
+                .push(Derivation { object, sources });
+        }
+
+        recorder.log
+    }
+
+    /// Access the number of objects in the log
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Indicate whether the log contains no objects
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Insert the logged objects into `services`, and record their derivations
+    ///
+    /// Returns the inserted objects, in the order of the log.
+    pub fn replay(
+        &self,
+        services: &mut Services,
+    ) -> Result<Vec<Object<BehindHandle>>, EventLogError> {
+        let mut objects = Vec::with_capacity(self.objects.len());
+
+        for object in &self.objects {
+            let object: Object<BehindHandle> = match object {
+                LoggedObject::Assembly(instances) => {
+                    let instances = instances
+                        .iter()
+                        .map(|instance| {
+                            let solid =
+                                resolve(instance.solid, &objects, services)?;
+                            Ok(Instance::new(instance.name.clone(), solid)
+                                .with_rotation(instance.rotation)
+                                .with_translation(instance.translation))
+                        })
+                        .collect::<Result<Vec<_>, EventLogError>>()?;
+                    Assembly::new(instances).insert(services).into()
+                }
+                LoggedObject::Curve => Curve::new().insert(services).into(),
+                LoggedObject::Cycle(half_edges) => {
+                    let half_edges = resolve_all::<HalfEdge>(
+                        half_edges, &objects, services,
+                    )?;
+                    Cycle::new(half_edges).insert(services).into()
+                }
+                LoggedObject::Datum(datum) => (*datum).insert(services).into(),
+                LoggedObject::Face { surface, region } => {
+                    let surface = resolve(*surface, &objects, services)?;
+                    let region = resolve(*region, &objects, services)?;
+                    Face::new(surface, region).insert(services).into()
+                }
+                LoggedObject::HalfEdge {
+                    path,
+                    boundary,
+                    curve,
+                    start_vertex,
+                } => {
+                    let curve = resolve(*curve, &objects, services)?;
+                    let start_vertex =
+                        resolve(*start_vertex, &objects, services)?;
+                    HalfEdge::new(*path, *boundary, curve, start_vertex)
+                        .insert(services)
+                        .into()
+                }
+                LoggedObject::Region {
+                    exterior,
+                    interiors,
+                    color,
+                } => {
+                    let exterior = resolve(*exterior, &objects, services)?;
+                    let interiors =
+                        resolve_all::<Cycle>(interiors, &objects, services)?;
+                    Region::new(exterior, interiors, *color)
+                        .insert(services)
+                        .into()
+                }
+                LoggedObject::Shell(faces) => {
+                    let faces = resolve_all::<Face>(faces, &objects, services)?;
+                    Shell::new(faces).insert(services).into()
+                }
+                LoggedObject::Sketch(regions) => {
+                    let regions =
+                        resolve_all::<Region>(regions, &objects, services)?;
+                    Sketch::new(regions).insert(services).into()
+                }
+                LoggedObject::Solid(shells) => {
+                    let shells =
+                        resolve_all::<Shell>(shells, &objects, services)?;
+                    Solid::new(shells).insert(services).into()
+                }
+                LoggedObject::Surface(geometry) => {
+                    Surface::new(*geometry).insert(services).into()
+                }
+                LoggedObject::Vertex => Vertex::new().insert(services).into(),
+            };
+
+            objects.push(object);
+        }
+
+        for Derivation { object, sources } in &self.derivations {
+            let object =
+                resolve_object(Ref::Object(*object), &objects, services)?;
+            let sources = sources
+                .iter()
+                .map(|source| resolve_object(*source, &objects, services))
+                .collect::<Result<Vec<_>, _>>()?;
+            services.record_derivation(object, sources);
+        }
+
+        Ok(objects)
+    }
+}
+
+impl fmt::Display for EventLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for object in &self.objects {
+            match object {
+                LoggedObject::Assembly(instances) => {
+                    write!(f, "assembly")?;
+                    for instance in instances {
+                        write!(f, " ")?;
+                        write_name(f, &instance.name)?;
+                        write!(f, " {}", instance.solid)?;
+                        write_scalars(
+                            f,
+                            instance
+                                .rotation
+                                .components
+                                .into_iter()
+                                .chain(instance.translation.components),
+                        )?;
+                    }
+                }
+                LoggedObject::Curve => write!(f, "curve")?,
+                LoggedObject::Cycle(half_edges) => {
+                    write!(f, "cycle")?;
+                    write_refs(f, half_edges)?;
+                }
+                LoggedObject::Datum(datum) => {
+                    write!(f, "datum ")?;
+                    write_datum(f, datum)?;
+                }
+                LoggedObject::Face { surface, region } => {
+                    write!(f, "face {surface} {region}")?;
+                }
+                LoggedObject::HalfEdge {
+                    path,
+                    boundary,
+                    curve,
+                    start_vertex,
+                } => {
+                    write!(f, "half_edge ")?;
+                    write_surface_path(f, path)?;
+                    write_scalars(
+                        f,
+                        boundary.iter().map(|point| point.coords.components[0]),
+                    )?;
+                    write!(f, " {curve} {start_vertex}")?;
+                }
+                LoggedObject::Region {
+                    exterior,
+                    interiors,
+                    color,
+                } => {
+                    write!(f, "region {exterior} ")?;
+                    match color {
+                        Some(Color([r, g, b, a])) => {
+                            write!(f, "{r},{g},{b},{a}")?;
+                        }
+                        None => write!(f, "none")?,
+                    }
+                    write_refs(f, interiors)?;
+                }
+                LoggedObject::Shell(faces) => {
+                    write!(f, "shell")?;
+                    write_refs(f, faces)?;
+                }
+                LoggedObject::Sketch(regions) => {
+                    write!(f, "sketch")?;
+                    write_refs(f, regions)?;
+                }
+                LoggedObject::Solid(shells) => {
+                    write!(f, "solid")?;
+                    write_refs(f, shells)?;
+                }
+                LoggedObject::Surface(geometry) => {
+                    write!(f, "surface ")?;
+                    write_global_path(f, &geometry.u)?;
+                    write_scalars(f, geometry.v.components)?;
+                }
+                LoggedObject::Vertex => write!(f, "vertex")?,
+            }
+
+            writeln!(f)?;
+        }
+
+        for Derivation { object, sources } in &self.derivations {
+            write!(f, "derive {}", Ref::Object(*object))?;
+            write_refs(f, sources)?;
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for EventLog {
+    type Err = EventLogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut log = Self::default();
+
+        for (i, line) in s.lines().enumerate() {
+            let mut tokens = Tokens {
+                tokens: line.split_whitespace(),
+                line: i + 1,
+                num_objects: log.objects.len(),
+            };
+
+            let Some(kind) = tokens.tokens.next() else {
+                continue;
+            };
+
+            let object = match kind {
+                "assembly" => {
+                    let mut instances = Vec::new();
+                    while let Some(name) = tokens.tokens.next() {
+                        let name = parse_name(name)
+                            .ok_or_else(|| tokens.error("Invalid name"))?;
+                        instances.push(LoggedInstance {
+                            name,
+                            solid: tokens.reference()?,
+                            rotation: tokens.vector()?,
+                            translation: tokens.vector()?,
+                        });
+                    }
+                    LoggedObject::Assembly(instances)
+                }
+                "curve" => LoggedObject::Curve,
+                "cycle" => LoggedObject::Cycle(tokens.references()?),
+                "datum" => LoggedObject::Datum(tokens.datum()?),
+                "face" => LoggedObject::Face {
+                    surface: tokens.reference()?,
+                    region: tokens.reference()?,
+                },
+                "half_edge" => LoggedObject::HalfEdge {
+                    path: tokens.surface_path()?,
+                    boundary: [tokens.point()?, tokens.point()?],
+                    curve: tokens.reference()?,
+                    start_vertex: tokens.reference()?,
+                },
+                "region" => LoggedObject::Region {
+                    exterior: tokens.reference()?,
+                    color: tokens.color()?,
+                    interiors: tokens.references()?,
+                },
+                "shell" => LoggedObject::Shell(tokens.references()?),
+                "sketch" => LoggedObject::Sketch(tokens.references()?),
+                "solid" => LoggedObject::Solid(tokens.references()?),
+                "surface" => LoggedObject::Surface(SurfaceGeometry {
+                    u: tokens.global_path()?,
+                    v: tokens.vector()?,
+                }),
+                "vertex" => LoggedObject::Vertex,
+                "derive" => {
+                    let Ref::Object(object) = tokens.reference()? else {
+                        return Err(tokens.error("Expected derived object"));
+                    };
+                    let sources = tokens.references()?;
+                    log.derivations.push(Derivation { object, sources });
+                    continue;
+                }
+                kind => {
+                    return Err(tokens.error(format!("Unknown entry `{kind}`")));
+                }
+            };
+
+            tokens.end()?;
+            log.objects.push(object);
+        }
+
+        Ok(log)
+    }
+}
+
+/// Error replaying or parsing an [`EventLog`]
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum EventLogError {
+    /// A line of the event log could not be parsed
+    ///
+    /// This includes lines with invalid geometry, like a circle with a radius
+    /// of zero, which can only occur in logs that were edited by hand.
+    #[error("Error parsing line {line} of event log: {message}")]
+    Parse {
+        /// The number of the line, starting at 1
+        line: usize,
+
+        /// A description of the problem
+        message: String,
+    },
+
+    /// An object refers to another object of an unexpected kind
+    #[error("Expected reference to {expected}, found `{reference}`")]
+    UnexpectedKind {
+        /// The reference, as it appears in the event log
+        reference: String,
+
+        /// The kind of object that was expected
+        expected: &'static str,
+    },
+}
+
+#[derive(Clone, Debug)]
+enum LoggedObject {
+    Assembly(Vec<LoggedInstance>),
+    Curve,
+    Cycle(Vec<Ref>),
+    Datum(Datum),
+    Face {
+        surface: Ref,
+        region: Ref,
+    },
+    HalfEdge {
+        path: SurfacePath,
+        boundary: [Point<1>; 2],
+        curve: Ref,
+        start_vertex: Ref,
+    },
+    Region {
+        exterior: Ref,
+        interiors: Vec<Ref>,
+        color: Option<Color>,
+    },
+    Shell(Vec<Ref>),
+    Sketch(Vec<Ref>),
+    Solid(Vec<Ref>),
+    Surface(SurfaceGeometry),
+    Vertex,
+}
+
+#[derive(Clone, Debug)]
+struct LoggedInstance {
+    name: String,
+    solid: Ref,
+    rotation: Vector<3>,
+    translation: Vector<3>,
+}
+
+#[derive(Clone, Debug)]
+struct Derivation {
+    object: usize,
+    sources: Vec<Ref>,
+}
+
+/// A reference from one logged object to another
+#[derive(Clone, Copy, Debug)]
+enum Ref {
+    Object(usize),
+    XyPlane,
+    XzPlane,
+    YzPlane,
+}
+
+impl fmt::Display for Ref {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Object(index) => write!(f, "#{index}"),
+            Self::XyPlane => write!(f, "xy_plane"),
+            Self::XzPlane => write!(f, "xz_plane"),
+            Self::YzPlane => write!(f, "yz_plane"),
+        }
+    }
+}
+
+struct Recorder {
+    log: EventLog,
+    refs: BTreeMap<ObjectId, Ref>,
+}
+
+impl Recorder {
+    /// Log an object, after the objects it refers to
+    ///
+    /// Objects that have been logged before are not logged again.
+    fn object(&mut self, object: &Object<BehindHandle>) -> Ref {
+        if let Some(reference) = self.refs.get(&object.id()) {
+            return *reference;
+        }
+
+        let logged = match object {
+            Object::Assembly(assembly) => LoggedObject::Assembly(
+                assembly
+                    .instances()
+                    .iter()
+                    .map(|instance| LoggedInstance {
+                        name: instance.name().to_string(),
+                        solid: self.handle(instance.solid()),
+                        rotation: instance.rotation(),
+                        translation: instance.translation(),
+                    })
+                    .collect(),
+            ),
+            Object::Curve(_) => LoggedObject::Curve,
+            Object::Cycle(cycle) => {
+                LoggedObject::Cycle(self.handles(cycle.half_edges()))
+            }
+            Object::Datum(datum) => LoggedObject::Datum(datum.clone_object()),
+            Object::Face(face) => LoggedObject::Face {
+                surface: self.handle(face.surface()),
+                region: self.handle(face.region()),
+            },
+            Object::HalfEdge(half_edge) => LoggedObject::HalfEdge {
+                path: half_edge.path(),
+                boundary: half_edge.boundary().inner,
+                curve: self.handle(half_edge.curve()),
+                start_vertex: self.handle(half_edge.start_vertex()),
+            },
+            Object::Region(region) => LoggedObject::Region {
+                exterior: self.handle(region.exterior()),
+                interiors: self.handles(region.interiors()),
+                color: region.color(),
+            },
+            Object::Shell(shell) => {
+                LoggedObject::Shell(self.handles(shell.faces()))
+            }
+            Object::Sketch(sketch) => {
+                LoggedObject::Sketch(self.handles(sketch.regions()))
+            }
+            Object::Solid(solid) => {
+                LoggedObject::Solid(self.handles(solid.shells()))
+            }
+            Object::Surface(surface) => {
+                LoggedObject::Surface(surface.geometry())
+            }
+            Object::Vertex(_) => LoggedObject::Vertex,
+        };
+
+        let reference = Ref::Object(self.log.objects.len());
+        self.log.objects.push(logged);
+        self.refs.insert(object.id(), reference);
+
+        reference
+    }
+
+    fn handle<T>(&mut self, handle: &Handle<T>) -> Ref
+    where
+        Handle<T>: Into<Object<BehindHandle>>,
+    {
+        self.object(&handle.clone().into())
+    }
+
+    fn handles<'r, T: 'r>(
+        &mut self,
+        handles: impl IntoIterator<Item = &'r Handle<T>>,
+    ) -> Vec<Ref>
+    where
+        Handle<T>: Into<Object<BehindHandle>>,
+    {
+        handles
+            .into_iter()
+            .map(|handle| self.handle(handle))
+            .collect()
+    }
+}
+
+/// An object kind that logged objects can refer to
+trait Kind: Sized {
+    const NAME: &'static str;
+
+    fn from_object(object: &Object<BehindHandle>) -> Option<Handle<Self>>;
+}
+
+macro_rules! impl_kind {
+    ($($ty:ident, $name:expr;)*) => {
+        $(
+            impl Kind for $ty {
+                const NAME: &'static str = $name;
+
+                fn from_object(
+                    object: &Object<BehindHandle>,
+                ) -> Option<Handle<Self>> {
+                    match object {
+                        Object::$ty(handle) => Some(handle.0.clone()),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_kind!(
+    Curve, "curve";
+    Cycle, "cycle";
+    Face, "face";
+    HalfEdge, "half-edge";
+    Region, "region";
+    Shell, "shell";
+    Solid, "solid";
+    Surface, "surface";
+    Vertex, "vertex";
+);
+
+fn resolve_object(
+    reference: Ref,
+    objects: &[Object<BehindHandle>],
+    services: &Services,
+) -> Result<Object<BehindHandle>, EventLogError> {
+    let surfaces = &services.objects.surfaces;
+    let object = match reference {
+        Ref::Object(index) => objects.get(index).cloned(),
+        Ref::XyPlane => Some(surfaces.xy_plane().into()),
+        Ref::XzPlane => Some(surfaces.xz_plane().into()),
+        Ref::YzPlane => Some(surfaces.yz_plane().into()),
+    };
+
+    object.ok_or_else(|| EventLogError::UnexpectedKind {
+        reference: reference.to_string(),
+        expected: "earlier object",
+    })
+}
+
+fn resolve<T: Kind>(
+    reference: Ref,
+    objects: &[Object<BehindHandle>],
+    services: &Services,
+) -> Result<Handle<T>, EventLogError> {
+    let object = resolve_object(reference, objects, services)?;
+    T::from_object(&object).ok_or_else(|| EventLogError::UnexpectedKind {
+        reference: reference.to_string(),
+        expected: T::NAME,
+    })
+}
+
+fn resolve_all<T: Kind>(
+    references: &[Ref],
+    objects: &[Object<BehindHandle>],
+    services: &Services,
+) -> Result<Vec<Handle<T>>, EventLogError> {
+    references
+        .iter()
+        .map(|reference| resolve(*reference, objects, services))
+        .collect()
+}
+
+fn write_refs(f: &mut fmt::Formatter, references: &[Ref]) -> fmt::Result {
+    for reference in references {
+        write!(f, " {reference}")?;
+    }
+    Ok(())
+}
+
+fn write_scalars(
+    f: &mut fmt::Formatter,
+    scalars: impl IntoIterator<Item = Scalar>,
+) -> fmt::Result {
+    for scalar in scalars {
+        // The `Display` implementation of `f64` writes the shortest
+        // representation that parses back into the same value.
+        write!(f, " {}", scalar.into_f64())?;
+    }
+    Ok(())
+}
+
+fn write_curve<const D: usize>(
+    f: &mut fmt::Formatter,
+    kind: &str,
+    center: Point<D>,
+    a: Vector<D>,
+    b: Vector<D>,
+) -> fmt::Result {
+    write!(f, "{kind}")?;
+    write_scalars(
+        f,
+        center
+            .coords
+            .components
+            .into_iter()
+            .chain(a.components)
+            .chain(b.components),
+    )
+}
+
+fn write_line<const D: usize>(
+    f: &mut fmt::Formatter,
+    line: &Line<D>,
+) -> fmt::Result {
+    write!(f, "line")?;
+    write_scalars(
+        f,
+        line.origin()
+            .coords
+            .components
+            .into_iter()
+            .chain(line.direction().components),
+    )
+}
+
+fn write_spiral<const D: usize>(
+    f: &mut fmt::Formatter,
+    spiral: &Spiral<D>,
+) -> fmt::Result {
+    write_curve(f, "spiral", spiral.center(), spiral.a(), spiral.b())?;
+    write_scalars(f, [spiral.start(), spiral.growth()])
+}
+
+fn write_surface_path(
+    f: &mut fmt::Formatter,
+    path: &SurfacePath,
+) -> fmt::Result {
+    match path {
+        SurfacePath::Circle(c) => {
+            write_curve(f, "circle", c.center(), c.a(), c.b())
+        }
+        SurfacePath::Line(line) => write_line(f, line),
+        SurfacePath::Spiral(spiral) => write_spiral(f, spiral),
+        SurfacePath::Involute(i) => {
+            write_curve(f, "involute", i.center(), i.a(), i.b())
+        }
+    }
+}
+
+fn write_global_path(f: &mut fmt::Formatter, path: &GlobalPath) -> fmt::Result {
+    match path {
+        GlobalPath::Circle(c) => {
+            write_curve(f, "circle", c.center(), c.a(), c.b())
+        }
+        GlobalPath::Ellipse(e) => {
+            write_curve(f, "ellipse", e.center(), e.a(), e.b())
+        }
+        GlobalPath::Line(line) => write_line(f, line),
+        GlobalPath::Spiral(spiral) => write_spiral(f, spiral),
+        GlobalPath::Involute(i) => {
+            write_curve(f, "involute", i.center(), i.a(), i.b())
+        }
+    }
+}
+
+fn write_datum(f: &mut fmt::Formatter, datum: &Datum) -> fmt::Result {
+    match datum {
+        Datum::Point(point) => {
+            write!(f, "point")?;
+            write_scalars(f, point.coords.components)
+        }
+        Datum::Axis(line) => {
+            write!(f, "axis ")?;
+            write_line(f, line)
+        }
+        Datum::Plane(plane) => {
+            write_curve(f, "plane", plane.origin(), plane.u(), plane.v())
+        }
+    }
+}
+
+/// Write an instance name as a single token
+///
+/// The name is enclosed in quotes. Backslashes and whitespace are escaped, so
+/// the name can't be split into multiple tokens.
+fn write_name(f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in name.chars() {
+        if c == '\\' {
+            write!(f, "\\\\")?;
+        } else if c.is_whitespace() {
+            write!(f, "\\u{{{:x}}}", u32::from(c))?;
+        } else {
+            write!(f, "{c}")?;
+        }
+    }
+    write!(f, "\"")
+}
+
+fn parse_name(token: &str) -> Option<String> {
+    let escaped = token.strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut name = String::new();
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            name.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            '\\' => name.push('\\'),
+            'u' => {
+                let rest = chars.as_str().strip_prefix('{')?;
+                let (code, rest) = rest.split_once('}')?;
+                name.push(char::from_u32(u32::from_str_radix(code, 16).ok()?)?);
+                chars = rest.chars();
+            }
+            _ => return None,
+        }
+    }
+
+    Some(name)
+}
+
+/// The tokens of a line of an event log
+struct Tokens<'s> {
+    tokens: std::str::SplitWhitespace<'s>,
+    line: usize,
+    num_objects: usize,
+}
+
+impl<'s> Tokens<'s> {
+    fn error(&self, message: impl Into<String>) -> EventLogError {
+        EventLogError::Parse {
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    fn next(&mut self, expected: &str) -> Result<&'s str, EventLogError> {
+        self.tokens
+            .next()
+            .ok_or_else(|| self.error(format!("Expected {expected}")))
+    }
+
+    fn end(&mut self) -> Result<(), EventLogError> {
+        match self.tokens.next() {
+            Some(token) => Err(self.error(format!("Unexpected `{token}`"))),
+            None => Ok(()),
+        }
+    }
+
+    fn scalar(&mut self) -> Result<Scalar, EventLogError> {
+        let token = self.next("number")?;
+        token
+            .parse::<f64>()
+            .ok()
+            .filter(|value| !value.is_nan())
+            .map(Scalar::from_f64)
+            .ok_or_else(|| self.error(format!("Invalid number `{token}`")))
+    }
+
+    fn vector<const D: usize>(&mut self) -> Result<Vector<D>, EventLogError> {
+        let mut components = [Scalar::ZERO; D];
+        for component in &mut components {
+            *component = self.scalar()?;
+        }
+        Ok(Vector { components })
+    }
+
+    fn point<const D: usize>(&mut self) -> Result<Point<D>, EventLogError> {
+        Ok(Point {
+            coords: self.vector()?,
+        })
+    }
+
+    fn reference(&mut self) -> Result<Ref, EventLogError> {
+        let token = self.next("reference")?;
+        self.parse_reference(token)
+    }
+
+    fn references(&mut self) -> Result<Vec<Ref>, EventLogError> {
+        let mut references = Vec::new();
+        while let Some(token) = self.tokens.next() {
+            references.push(self.parse_reference(token)?);
+        }
+        Ok(references)
+    }
+
+    fn parse_reference(&self, token: &str) -> Result<Ref, EventLogError> {
+        match token {
+            "xy_plane" => return Ok(Ref::XyPlane),
+            "xz_plane" => return Ok(Ref::XzPlane),
+            "yz_plane" => return Ok(Ref::YzPlane),
+            _ => {}
+        }
+
+        let index = token
+            .strip_prefix('#')
+            .and_then(|index| index.parse::<usize>().ok())
+            .ok_or_else(|| {
+                self.error(format!("Invalid reference `{token}`"))
+            })?;
+
+        // Objects can only refer to objects that come before them in the log.
+        // Derivations come after all objects, but are parsed in line order too.
+        if index >= self.num_objects {
+            return Err(self.error(format!(
+                "Reference `{token}` to an object that hasn't been logged yet"
+            )));
+        }
+
+        Ok(Ref::Object(index))
+    }
+
+    fn color(&mut self) -> Result<Option<Color>, EventLogError> {
+        let token = self.next("color")?;
+        if token == "none" {
+            return Ok(None);
+        }
+
+        let channels = token
+            .split(',')
+            .map(|channel| channel.parse::<u8>().ok())
+            .collect::<Option<Vec<_>>>();
+        match channels.as_deref() {
+            Some(&[r, g, b, a]) => Ok(Some(Color([r, g, b, a]))),
+            _ => Err(self.error(format!("Invalid color `{token}`"))),
+        }
+    }
+
+    /// Parse a vector that must not have a length of zero
+    fn nonzero_vector<const D: usize>(
+        &mut self,
+        name: &str,
+    ) -> Result<Vector<D>, EventLogError> {
+        let vector = self.vector::<D>()?;
+        if vector.magnitude() == Scalar::ZERO {
+            return Err(self.error(format!("`{name}` must not be zero")));
+        }
+
+        Ok(vector)
+    }
+
+    /// Check that `a` and `b` are perpendicular, before passing them to a
+    /// constructor that asserts it
+    fn perpendicular<const D: usize>(
+        &self,
+        a: Vector<D>,
+        b: Vector<D>,
+    ) -> Result<(), EventLogError> {
+        // The same epsilon value that `Circle::new` and `Ellipse::new` use.
+        if a.dot(&b).abs() >= Scalar::from_f64(f64::EPSILON) {
+            return Err(
+                self.error("`a` and `b` must be perpendicular to each other")
+            );
+        }
+
+        Ok(())
+    }
+
+    fn circle<const D: usize>(&mut self) -> Result<Circle<D>, EventLogError> {
+        let center = self.point::<D>()?;
+        let a = self.nonzero_vector::<D>("a")?;
+        let b = self.nonzero_vector::<D>("b")?;
+
+        if a.magnitude() != b.magnitude() {
+            return Err(self.error("`a` and `b` must be of equal length"));
+        }
+        self.perpendicular(a, b)?;
+
+        Ok(Circle::new(center, a, b))
+    }
+
+    fn ellipse(&mut self) -> Result<Ellipse<3>, EventLogError> {
+        let center = self.point::<3>()?;
+        let a = self.nonzero_vector::<3>("a")?;
+        let b = self.nonzero_vector::<3>("b")?;
+        self.perpendicular(a, b)?;
+
+        Ok(Ellipse::new(center, a, b))
+    }
+
+    fn line<const D: usize>(&mut self) -> Result<Line<D>, EventLogError> {
+        Ok(Line::from_origin_and_direction(
+            self.point()?,
+            self.nonzero_vector("direction")?,
+        ))
+    }
+
+    fn spiral<const D: usize>(&mut self) -> Result<Spiral<D>, EventLogError> {
+        Ok(Spiral::new(
+            self.point::<D>()?,
+            self.nonzero_vector::<D>("a")?,
+            self.nonzero_vector::<D>("b")?,
+            self.scalar()?,
+            self.scalar()?,
+        ))
+    }
+
+    fn involute<const D: usize>(
+        &mut self,
+    ) -> Result<Involute<D>, EventLogError> {
+        Ok(Involute::new(
+            self.point::<D>()?,
+            self.nonzero_vector::<D>("a")?,
+            self.nonzero_vector::<D>("b")?,
+        ))
+    }
+
+    fn surface_path(&mut self) -> Result<SurfacePath, EventLogError> {
+        match self.next("path")? {
+            "circle" => Ok(SurfacePath::Circle(self.circle()?)),
+            "line" => Ok(SurfacePath::Line(self.line()?)),
+            "spiral" => Ok(SurfacePath::Spiral(self.spiral()?)),
+            "involute" => Ok(SurfacePath::Involute(self.involute()?)),
+            kind => Err(self.error(format!("Unknown path `{kind}`"))),
+        }
+    }
+
+    fn global_path(&mut self) -> Result<GlobalPath, EventLogError> {
+        match self.next("path")? {
+            "circle" => Ok(GlobalPath::Circle(self.circle()?)),
+            "ellipse" => Ok(GlobalPath::Ellipse(self.ellipse()?)),
+            "line" => Ok(GlobalPath::Line(self.line()?)),
+            "spiral" => Ok(GlobalPath::Spiral(self.spiral()?)),
+            "involute" => Ok(GlobalPath::Involute(self.involute()?)),
+            kind => Err(self.error(format!("Unknown path `{kind}`"))),
+        }
+    }
+
+    fn datum(&mut self) -> Result<Datum, EventLogError> {
+        match self.next("datum")? {
+            "point" => Ok(Datum::Point(self.point()?)),
+            "axis" => match self.next("axis")? {
+                "line" => Ok(Datum::Axis(self.line()?)),
+                kind => Err(self.error(format!("Unknown axis `{kind}`"))),
+            },
+            "plane" => Ok(Datum::Plane(Plane::from_parametric(
+                self.point::<3>()?,
+                self.vector::<3>()?,
+                self.vector::<3>()?,
+            ))),
+            kind => Err(self.error(format!("Unknown datum `{kind}`"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    use super::{parse_name, EventLog, EventLogError};
+
+    #[test]
+    fn replay_reproduces_object_graph() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let _solid = Solid::box_from_dims([1., 2., 3.], &mut services)
+            .insert(&mut services);
+
+        let log = services.event_log();
+        let text = log.to_string();
+
+        let mut replayed = Services::new();
+        let objects = text.parse::<EventLog>()?.replay(&mut replayed)?;
+        assert_eq!(objects.len(), log.len());
+
+        // Objects are logged after the objects they refer to, so recording the
+        // replayed objects again must result in the same log.
+        assert_eq!(replayed.event_log().to_string(), text);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_geometry_is_a_parse_error() {
+        for line in [
+            "curve\nvertex\nhalf_edge circle 0 0 1 0 0 2 0 0 1 0 #0 #1",
+            "curve\nvertex\nhalf_edge circle 0 0 0 0 0 0 0 0 1 0 #0 #1",
+            "curve\nvertex\nhalf_edge circle 0 0 1 0 1 0 0 1 #0 #1",
+            "curve\nvertex\nhalf_edge line 0 0 0 0 0 0 1 0 #0 #1",
+            "surface ellipse 0 0 0 1 0 0 1 1 0 0 0 1",
+            "surface spiral 0 0 0 0 0 0 0 1 0 1 1 0 0 1",
+            "datum axis line 0 0 0 0 0 0",
+        ] {
+            let result = line.parse::<EventLog>();
+            assert!(
+                matches!(result, Err(EventLogError::Parse { .. })),
+                "Expected parse error for `{line}`"
+            );
+        }
+    }
+
+    #[test]
+    fn instance_names_survive_escaping() {
+        struct Name<'a>(&'a str);
+
+        impl std::fmt::Display for Name<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                super::write_name(f, self.0)
+            }
+        }
+
+        for name in ["", "wheel", "front left\twheel", "back\\slash"] {
+            let token = Name(name).to_string();
+            assert_eq!(token.split_whitespace().count(), 1);
+            assert_eq!(parse_name(&token).as_deref(), Some(name));
+        }
+    }
+}
@@ -2,17 +2,25 @@
 //!
 //! See [`Service`].
 
+mod checkpoint;
+mod event_log;
 mod objects;
+mod provenance;
 mod service;
 mod validation;
 
+use std::{collections::BTreeMap, mem};
+
 use crate::{
-    objects::{Object, Objects, WithHandle},
-    validate::ValidationErrors,
+    objects::{BehindHandle, Object, Objects, WithHandle},
+    validate::{RegisteredCheck, ValidationCheck, ValidationErrors},
 };
 
 pub use self::{
+    checkpoint::{Checkpoint, CheckpointDiff, UnknownCheckpoint},
+    event_log::{EventLog, EventLogError},
     objects::{InsertObject, Operation},
+    provenance::{Provenance, ProvenanceCommand, ProvenanceEvent},
     service::{Service, State},
     validation::{Validation, ValidationCommand, ValidationEvent},
 };
@@ -28,6 +36,17 @@ pub struct Services {
     ///
     /// Validates objects that are inserted using the objects service.
     pub validation: Service<Validation>,
+
+    /// The provenance service
+    ///
+    /// Records which objects each object was derived from.
+    pub provenance: Service<Provenance>,
+
+    /// All objects that have been inserted, in the order of insertion
+    inserted: Vec<Object<BehindHandle>>,
+
+    /// The checkpoints that have been taken, by name
+    checkpoints: BTreeMap<String, Checkpoint>,
 }
 
 impl Services {
@@ -35,10 +54,14 @@ impl Services {
     pub fn new() -> Self {
         let objects = Service::<Objects>::default();
         let validation = Service::default();
+        let provenance = Service::default();
 
         Self {
             objects,
             validation,
+            provenance,
+            inserted: Vec::new(),
+            checkpoints: BTreeMap::new(),
         }
     }
 
@@ -48,18 +71,203 @@ impl Services {
         self.objects
             .execute(Operation::InsertObject { object }, &mut object_events);
 
-        for object_event in object_events {
-            let command = ValidationCommand::ValidateObject {
-                object: object_event.object.into(),
-            };
-            self.validation.execute(command, &mut Vec::new());
+        self.inserted.extend(
+            object_events
+                .into_iter()
+                .map(|object_event| object_event.object.into()),
+        );
+
+        self.validate_new_objects();
+    }
+
+    /// Validate all objects that have been inserted since the last validation
+    ///
+    /// This is done automatically on every insertion, so there is usually no
+    /// need to call this method manually.
+    pub fn validate_new_objects(&mut self) {
+        let first = self.validation.num_validated();
+        if first >= self.inserted.len() {
+            return;
         }
+
+        let command = ValidationCommand::ValidateObjects {
+            first,
+            objects: self.inserted[first..].to_vec(),
+        };
+        self.validation.execute(command, &mut Vec::new());
+    }
+
+    /// Register a custom validation check
+    ///
+    /// The check runs alongside the built-in validation checks, on all objects
+    /// that are inserted from now on. See [`ValidationCheck`].
+    pub fn register_validation_check(&mut self, check: impl ValidationCheck) {
+        self.validate_new_objects();
+
+        let check = RegisteredCheck::new(check);
+        self.validation.execute(
+            ValidationCommand::RegisterCheck { check },
+            &mut Vec::new(),
+        );
+    }
+
+    /// Record that an object was derived from other objects
+    ///
+    /// Does nothing, if `sources` is empty.
+    pub fn record_derivation<S>(
+        &mut self,
+        object: impl Into<Object<BehindHandle>>,
+        sources: impl IntoIterator<Item = S>,
+    ) where
+        S: Into<Object<BehindHandle>>,
+    {
+        let command = ProvenanceCommand::RecordDerivation {
+            object: object.into(),
+            sources: sources.into_iter().map(Into::into).collect(),
+        };
+        self.provenance.execute(command, &mut Vec::new());
+    }
+
+    /// Merge another instance of `Services` into this one
+    ///
+    /// This makes it possible to create objects on multiple threads, each with
+    /// its own instance of `Services`, and combine the results afterwards.
+    ///
+    /// Objects inserted through `other` remain in its stores, which are kept
+    /// alive by the handles that refer to them. They are considered inserted
+    /// into this instance from now on, and are validated by it, using the
+    /// built-in checks and any custom checks registered with this instance.
+    /// Recorded derivations are taken over by this instance.
+    pub fn merge(&mut self, other: Services) {
+        let Services {
+            objects: _,
+            validation,
+            provenance,
+            inserted,
+            checkpoints: _,
+        } = other;
+
+        // All objects are validated again below, so any errors that `other`
+        // has found are going to be found again. Discard them, so dropping
+        // the other validation service doesn't complain about them.
+        validation.into_state().errors.clear();
+
+        self.inserted.extend(inserted);
+        self.validate_new_objects();
+
+        let other = provenance.into_state();
+        self.provenance
+            .execute(ProvenanceCommand::Merge { other }, &mut Vec::new());
+    }
+
+    /// Take a snapshot of the current state, under the provided name
+    ///
+    /// Replaces any previous checkpoint of the same name. Use
+    /// [`Services::diff_checkpoint`] to find out what has changed since, and
+    /// [`Services::restore_checkpoint`] to return to this state.
+    pub fn checkpoint(&mut self, name: impl Into<String>) {
+        self.validate_new_objects();
+
+        let checkpoint = Checkpoint {
+            num_inserted: self.inserted.len(),
+            num_validated: self.validation.num_validated(),
+            errors: self.validation.errors.clone(),
+            provenance: (*self.provenance).clone(),
+        };
+        self.checkpoints.insert(name.into(), checkpoint);
+    }
+
+    /// Access the checkpoints that have been taken, by name
+    pub fn checkpoints(&self) -> &BTreeMap<String, Checkpoint> {
+        &self.checkpoints
+    }
+
+    /// Determine what has changed since the checkpoint with the provided name
+    pub fn diff_checkpoint(
+        &self,
+        name: &str,
+    ) -> Result<CheckpointDiff, UnknownCheckpoint> {
+        let checkpoint = self.get_checkpoint(name)?;
+
+        let inserted = self
+            .inserted
+            .get(checkpoint.num_inserted..)
+            .unwrap_or_default()
+            .to_vec();
+        let new_errors = self
+            .validation
+            .errors
+            .iter()
+            .filter(|(id, _)| !checkpoint.errors.contains_key(id))
+            .map(|(id, err)| (*id, err.clone()))
+            .collect();
+
+        Ok(CheckpointDiff {
+            inserted,
+            new_errors,
+        })
+    }
+
+    /// Return to the state of the checkpoint with the provided name
+    ///
+    /// Objects that have been inserted since the checkpoint are forgotten by
+    /// the services, together with their validation errors and recorded
+    /// derivations. The object stores are append-only, so the objects
+    /// themselves remain in there, and any handles to them stay valid.
+    ///
+    /// The checkpoint is kept, so it can be restored again later.
+    pub fn restore_checkpoint(
+        &mut self,
+        name: &str,
+    ) -> Result<(), UnknownCheckpoint> {
+        let checkpoint = self.get_checkpoint(name)?.clone();
+
+        self.inserted.truncate(checkpoint.num_inserted);
+        self.validation.execute(
+            ValidationCommand::Restore {
+                num_validated: checkpoint.num_validated,
+                errors: checkpoint.errors,
+            },
+            &mut Vec::new(),
+        );
+        self.provenance.execute(
+            ProvenanceCommand::Restore {
+                state: checkpoint.provenance,
+            },
+            &mut Vec::new(),
+        );
+
+        Ok(())
+    }
+
+    fn get_checkpoint(
+        &self,
+        name: &str,
+    ) -> Result<&Checkpoint, UnknownCheckpoint> {
+        self.checkpoints
+            .get(name)
+            .ok_or_else(|| UnknownCheckpoint(name.to_string()))
+    }
+
+    /// Record the inserted objects as an [`EventLog`]
+    ///
+    /// The log can be saved, and replayed into a fresh instance of `Services`
+    /// later, to reproduce the same objects.
+    pub fn event_log(&self) -> EventLog {
+        EventLog::record(self)
     }
 
     /// Drop `Services`; return any unhandled validation error
-    pub fn drop_and_validate(self) -> Result<(), ValidationErrors> {
+    ///
+    /// The returned errors count as handled, so unlike dropping `Services`
+    /// directly, this doesn't panic if there are any.
+    pub fn drop_and_validate(mut self) -> Result<(), ValidationErrors> {
+        self.validate_new_objects();
+
+        let Self { validation, .. } = self;
+        let mut validation = validation.into_state();
         let errors = ValidationErrors(
-            self.validation.errors.values().cloned().collect(),
+            mem::take(&mut validation.errors).into_values().collect(),
         );
 
         if errors.0.is_empty() {
@@ -75,3 +283,61 @@ impl Default for Services {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use crate::{
+        objects::HalfEdge,
+        operations::{build::BuildHalfEdge, insert::Insert},
+    };
+
+    use super::Services;
+
+    #[test]
+    fn merge_takes_over_and_validates_inserted_objects() {
+        let mut services = Services::new();
+        let mut other = Services::new();
+
+        let valid =
+            HalfEdge::line_segment([[0., 0.], [1., 0.]], None, &mut other);
+        let invalid = HalfEdge::new(
+            valid.path(),
+            [Point::from([0.]); 2],
+            valid.curve().clone(),
+            valid.start_vertex().clone(),
+        )
+        .insert(&mut other);
+        let num_inserted = other.inserted.len();
+
+        services.merge(other);
+        assert_eq!(services.inserted.len(), num_inserted);
+
+        let Services { validation, .. } = services;
+        let mut validation = validation.into_state();
+        assert_eq!(validation.errors.len(), 1);
+        assert!(validation.errors.contains_key(&invalid.id()));
+
+        // Handle the error, so dropping `Validation` doesn't panic.
+        validation.errors.clear();
+    }
+
+    #[test]
+    fn drop_and_validate_returns_errors_without_panicking() {
+        let mut services = Services::new();
+
+        let valid =
+            HalfEdge::line_segment([[0., 0.], [1., 0.]], None, &mut services);
+        let _invalid = HalfEdge::new(
+            valid.path(),
+            [Point::from([0.]); 2],
+            valid.curve().clone(),
+            valid.start_vertex().clone(),
+        )
+        .insert(&mut services);
+
+        let errors = services.drop_and_validate().unwrap_err();
+        assert_eq!(errors.0.len(), 1);
+    }
+}
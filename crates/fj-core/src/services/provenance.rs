@@ -0,0 +1,235 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    objects::{BehindHandle, Object},
+    storage::ObjectId,
+};
+
+use super::State;
+
+/// Records which objects each object was derived from
+///
+/// Operations record, for the objects they create, which of their input
+/// objects those were derived from. A side face created by a sweep, for
+/// example, is derived from the half-edge that was swept.
+///
+/// This makes it possible to relate objects in the result of an operation to
+/// the objects that were used to define it. A selection that refers to the
+/// original objects can then be resolved against the result, even after an
+/// upstream change created entirely new objects.
+#[derive(Clone, Default)]
+pub struct Provenance {
+    sources: BTreeMap<ObjectId, Vec<Object<BehindHandle>>>,
+    derived: BTreeMap<ObjectId, Vec<Object<BehindHandle>>>,
+}
+
+impl Provenance {
+    /// Access the objects that an object was directly derived from
+    ///
+    /// Returns an empty slice, if no derivation was recorded for the object.
+    pub fn sources_of(&self, object: ObjectId) -> &[Object<BehindHandle>] {
+        self.sources.get(&object).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Access the objects that were directly derived from an object
+    ///
+    /// Returns an empty slice, if no objects were derived from the object.
+    pub fn derived_from(&self, object: ObjectId) -> &[Object<BehindHandle>] {
+        self.derived.get(&object).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Find the objects that an object was ultimately derived from
+    ///
+    /// Follows the recorded derivations back, until objects are reached that
+    /// have not been derived from anything. Returns an empty `Vec`, if no
+    /// derivation was recorded for the object.
+    pub fn origins_of(&self, object: ObjectId) -> Vec<Object<BehindHandle>> {
+        let mut origins = Vec::new();
+        let mut visited = BTreeSet::new();
+        let mut stack = self.sources_of(object).to_vec();
+
+        while let Some(source) = stack.pop() {
+            if !visited.insert(source.id()) {
+                continue;
+            }
+
+            let sources = self.sources_of(source.id());
+            if sources.is_empty() {
+                origins.push(source);
+            } else {
+                stack.extend(sources.iter().cloned());
+            }
+        }
+
+        origins
+    }
+
+    /// Iterate over all recorded derivations
+    ///
+    /// Yields each derived object's ID, together with its sources.
+    pub(super) fn derivations(
+        &self,
+    ) -> impl Iterator<Item = (ObjectId, &[Object<BehindHandle>])> {
+        self.sources
+            .iter()
+            .map(|(id, sources)| (*id, sources.as_slice()))
+    }
+}
+
+impl State for Provenance {
+    type Command = ProvenanceCommand;
+    type Event = ProvenanceEvent;
+
+    fn decide(&self, command: Self::Command, events: &mut Vec<Self::Event>) {
+        match command {
+            ProvenanceCommand::RecordDerivation { object, sources } => {
+                if sources.is_empty() {
+                    return;
+                }
+
+                events.push(ProvenanceEvent::DerivationRecorded {
+                    object,
+                    sources,
+                });
+            }
+            ProvenanceCommand::Merge { other } => {
+                events.push(ProvenanceEvent::Merged { other });
+            }
+            ProvenanceCommand::Restore { state } => {
+                events.push(ProvenanceEvent::Restored { state });
+            }
+        }
+    }
+
+    fn evolve(&mut self, event: &Self::Event) {
+        match event {
+            ProvenanceEvent::DerivationRecorded { object, sources } => {
+                for source in sources {
+                    self.derived
+                        .entry(source.id())
+                        .or_default()
+                        .push(object.clone());
+                }
+                self.sources
+                    .entry(object.id())
+                    .or_default()
+                    .extend(sources.iter().cloned());
+            }
+            ProvenanceEvent::Merged { other } => {
+                for (id, sources) in &other.sources {
+                    self.sources
+                        .entry(*id)
+                        .or_default()
+                        .extend(sources.iter().cloned());
+                }
+                for (id, derived) in &other.derived {
+                    self.derived
+                        .entry(*id)
+                        .or_default()
+                        .extend(derived.iter().cloned());
+                }
+            }
+            ProvenanceEvent::Restored { state } => {
+                *self = state.clone();
+            }
+        }
+    }
+}
+
+/// The command accepted by the provenance service
+pub enum ProvenanceCommand {
+    /// Record that an object was derived from other objects
+    RecordDerivation {
+        /// The derived object
+        object: Object<BehindHandle>,
+
+        /// The objects it was derived from
+        sources: Vec<Object<BehindHandle>>,
+    },
+
+    /// Take over the derivations recorded by another provenance service
+    Merge {
+        /// The other provenance service's state
+        other: Provenance,
+    },
+
+    /// Return to a previous state, as recorded by a checkpoint
+    Restore {
+        /// The state to return to
+        state: Provenance,
+    },
+}
+
+/// The event produced by the provenance service
+#[derive(Clone)]
+pub enum ProvenanceEvent {
+    /// A derivation was recorded
+    DerivationRecorded {
+        /// The derived object
+        object: Object<BehindHandle>,
+
+        /// The objects it was derived from
+        sources: Vec<Object<BehindHandle>>,
+    },
+
+    /// The derivations recorded by another provenance service were taken over
+    Merged {
+        /// The other provenance service's state
+        other: Provenance,
+    },
+
+    /// A previous state was restored from a checkpoint
+    Restored {
+        /// The state that was restored
+        state: Provenance,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::{Object, Region, Sketch},
+        operations::{
+            build::{BuildRegion, BuildSketch},
+            insert::Insert,
+            sweep::SweepSketch,
+            update::UpdateSketch,
+        },
+        services::Services,
+    };
+
+    #[test]
+    fn side_faces_of_sweep_originate_from_sketch_edges() {
+        let mut services = Services::new();
+
+        let region = Region::polygon(
+            [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            &mut services,
+        )
+        .insert(&mut services);
+        let surface = services.objects.surfaces.xy_plane();
+        let solid = Sketch::empty()
+            .add_region(region.clone())
+            .sweep_sketch(surface, [0., 0., 1.], &mut services)
+            .insert(&mut services);
+
+        let sketch_half_edges = region.exterior().half_edges();
+
+        let mut num_side_faces = 0;
+        for face in solid.shells().first().faces() {
+            let origins = services.provenance.origins_of(face.id());
+
+            let from_sketch = origins.iter().any(|origin| {
+                let Object::HalfEdge(half_edge) = origin else {
+                    return false;
+                };
+                sketch_half_edges.iter().any(|h| h.id() == half_edge.id())
+            });
+            if from_sketch {
+                num_side_faces += 1;
+            }
+        }
+
+        assert_eq!(num_side_faces, 4);
+    }
+}
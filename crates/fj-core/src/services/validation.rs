@@ -3,16 +3,53 @@ use std::{collections::BTreeMap, error::Error, thread};
 use crate::{
     objects::{BehindHandle, Object},
     storage::ObjectId,
-    validate::ValidationError,
+    validate::{RegisteredCheck, ValidationConfig, ValidationError},
 };
 
 use super::State;
 
 /// Errors that occurred while validating the objects inserted into the stores
+///
+/// Objects are validated incrementally. `Validation` keeps track of how many of
+/// the inserted objects it has already seen, and only validates those that were
+/// inserted after that. This keeps the cost of inserting an object constant,
+/// regardless of how many objects have been inserted before.
 #[derive(Default)]
 pub struct Validation {
     /// All unhandled validation errors
     pub errors: BTreeMap<ObjectId, ValidationError>,
+
+    /// The number of inserted objects that have already been validated
+    ///
+    /// This is a high-water mark in the sequence of inserted objects. Objects
+    /// before it are never validated again.
+    validated: usize,
+
+    /// The custom validation checks that have been registered
+    checks: Vec<RegisteredCheck>,
+}
+
+impl Validation {
+    /// Access the number of inserted objects that have already been validated
+    pub fn num_validated(&self) -> usize {
+        self.validated
+    }
+
+    /// Access the number of custom validation checks that have been registered
+    pub fn num_checks(&self) -> usize {
+        self.checks.len()
+    }
+
+    fn run_custom_checks(
+        &self,
+        object: &Object<BehindHandle>,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let config = ValidationConfig::default();
+        for check in &self.checks {
+            check.check(object, &config, errors);
+        }
+    }
 }
 
 impl Drop for Validation {
@@ -53,15 +90,43 @@ impl State for Validation {
         let mut errors = Vec::new();
 
         match command {
-            ValidationCommand::ValidateObject { object } => {
-                object.validate(&mut errors);
-
-                for err in errors {
-                    events.push(ValidationEvent::ValidationFailed {
-                        object: object.clone(),
-                        err,
-                    });
+            ValidationCommand::ValidateObjects { first, objects } => {
+                let _span = tracing::debug_span!("validate").entered();
+
+                let end = first + objects.len();
+                if end <= self.validated {
+                    return;
                 }
+
+                // Some of the objects might have been validated already. Skip
+                // those.
+                let already_validated = self.validated.saturating_sub(first);
+
+                for object in objects.into_iter().skip(already_validated) {
+                    object.validate(&mut errors);
+                    self.run_custom_checks(&object, &mut errors);
+
+                    for err in errors.drain(..) {
+                        events.push(ValidationEvent::ValidationFailed {
+                            object: object.clone(),
+                            err,
+                        });
+                    }
+                }
+
+                events.push(ValidationEvent::ObjectsValidated { end });
+            }
+            ValidationCommand::RegisterCheck { check } => {
+                events.push(ValidationEvent::CheckRegistered { check });
+            }
+            ValidationCommand::Restore {
+                num_validated,
+                errors,
+            } => {
+                events.push(ValidationEvent::Restored {
+                    num_validated,
+                    errors,
+                });
             }
         }
     }
@@ -71,16 +136,54 @@ impl State for Validation {
             ValidationEvent::ValidationFailed { object, err } => {
                 self.errors.insert(object.id(), err.clone());
             }
+            ValidationEvent::ObjectsValidated { end } => {
+                self.validated = *end;
+            }
+            ValidationEvent::Restored {
+                num_validated,
+                errors,
+            } => {
+                self.validated = *num_validated;
+                self.errors = errors.clone();
+            }
+            ValidationEvent::CheckRegistered { check } => {
+                self.checks.push(check.clone());
+            }
         }
     }
 }
 
 /// The command accepted by the validation service
 pub enum ValidationCommand {
-    /// Validate the provided object
-    ValidateObject {
-        /// The object to validate
-        object: Object<BehindHandle>,
+    /// Validate the provided sequence of inserted objects
+    ///
+    /// Objects before the high-water mark (see
+    /// [`Validation::num_validated`]) have already been validated, and are
+    /// skipped.
+    ValidateObjects {
+        /// The position of the first object in the sequence of all inserted
+        /// objects
+        first: usize,
+
+        /// The objects to validate
+        objects: Vec<Object<BehindHandle>>,
+    },
+
+    /// Register a custom validation check
+    ///
+    /// The check runs on all objects that are validated from then on.
+    RegisterCheck {
+        /// The check to register
+        check: RegisteredCheck,
+    },
+
+    /// Return to a previous state, as recorded by a checkpoint
+    Restore {
+        /// The number of inserted objects that had been validated
+        num_validated: usize,
+
+        /// The unhandled errors at that point
+        errors: BTreeMap<ObjectId, ValidationError>,
     },
 }
 
@@ -95,4 +198,73 @@ pub enum ValidationEvent {
         /// The validation error
         err: ValidationError,
     },
+
+    /// All inserted objects up to the provided position have been validated
+    ObjectsValidated {
+        /// The position after the last object that has been validated
+        end: usize,
+    },
+
+    /// A previous state was restored from a checkpoint
+    Restored {
+        /// The number of inserted objects that had been validated
+        num_validated: usize,
+
+        /// The unhandled errors at that point
+        errors: BTreeMap<ObjectId, ValidationError>,
+    },
+
+    /// A custom validation check was registered
+    CheckRegistered {
+        /// The check that was registered
+        check: RegisteredCheck,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::{BehindHandle, Object, Vertex},
+        operations::insert::Insert,
+        services::{Services, State},
+    };
+
+    use super::{Validation, ValidationCommand};
+
+    #[test]
+    fn validate_only_new_objects() {
+        let mut services = Services::new();
+
+        let a = Vertex::new().insert(&mut services);
+        let b = Vertex::new().insert(&mut services);
+        assert_eq!(services.validation.num_validated(), 2);
+
+        // Nothing new was inserted, so there's nothing to do.
+        services.validate_new_objects();
+        assert_eq!(services.validation.num_validated(), 2);
+
+        let mut validation = Validation::default();
+        let objects: Vec<Object<BehindHandle>> = vec![a.into(), b.into()];
+
+        let mut events = Vec::new();
+        validation.decide(
+            ValidationCommand::ValidateObjects {
+                first: 0,
+                objects: objects.clone(),
+            },
+            &mut events,
+        );
+        for event in &events {
+            validation.evolve(event);
+        }
+        assert_eq!(validation.num_validated(), 2);
+
+        // Objects before the high-water mark are not validated again.
+        let mut events = Vec::new();
+        validation.decide(
+            ValidationCommand::ValidateObjects { first: 0, objects },
+            &mut events,
+        );
+        assert!(events.is_empty());
+    }
 }
@@ -0,0 +1,124 @@
+use std::iter;
+
+/// An arena that stores objects of one type
+///
+/// Objects are stored in chunks that are allocated once, and never moved or
+/// deallocated afterwards. Each new chunk is as large as all previous chunks
+/// combined, which means that, for large numbers of objects, almost all of
+/// them live in a few large chunks of contiguous memory.
+///
+/// Objects are addressed by their [`Index`], which counts all slots of the
+/// arena in the order they were reserved in.
+#[derive(Debug)]
+pub struct Arena<T> {
+    chunks: Vec<Box<[Option<T>]>>,
+    initial_capacity: usize,
+    len: usize,
+}
+
+impl<T> Arena<T> {
+    pub fn new(initial_capacity: usize) -> Self {
+        assert!(initial_capacity > 0, "Initial capacity must not be zero");
+
+        Self {
+            chunks: Vec::new(),
+            initial_capacity,
+            len: 0,
+        }
+    }
+
+    pub fn reserve(&mut self) -> (Index, *const Option<T>) {
+        let index = Index(self.len);
+        let (chunk, offset) = self.locate(index);
+
+        if chunk >= self.chunks.len() {
+            let size = self.initial_capacity << chunk;
+            let objects = iter::repeat_with(|| None)
+                .take(size)
+                .collect::<Vec<Option<T>>>()
+                .into_boxed_slice();
+            self.chunks.push(objects);
+        }
+
+        self.len += 1;
+        let ptr = &self.chunks[chunk][offset];
+
+        (index, ptr)
+    }
+
+    pub fn insert(&mut self, index: Index, object: T) {
+        let (chunk, offset) = self.locate(index);
+        let slot = &mut self.chunks[chunk][offset];
+
+        assert!(slot.is_none(), "Attempting to overwrite object in store");
+
+        *slot = Some(object);
+    }
+
+    pub fn get(&self, index: Index) -> Option<&Option<T>> {
+        if index.0 >= self.len {
+            return None;
+        }
+
+        let (chunk, offset) = self.locate(index);
+        Some(&self.chunks[chunk][offset])
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.chunks.iter().map(|chunk| chunk.len()).sum()
+    }
+
+    /// Compute the chunk an index refers to, and the offset within that chunk
+    ///
+    /// Chunk `k` has a size of `c * 2^k`, where `c` is the initial capacity,
+    /// and starts at index `c * (2^k - 1)`.
+    fn locate(&self, index: Index) -> (usize, usize) {
+        let chunk = (index.0 / self.initial_capacity + 1).ilog2() as usize;
+        let start = self.initial_capacity * ((1 << chunk) - 1);
+
+        (chunk, index.0 - start)
+    }
+}
+
+/// The index of a slot in an [`Arena`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Index(usize);
+
+impl Index {
+    pub fn zero() -> Self {
+        Self(0)
+    }
+
+    pub fn inc(&mut self) {
+        self.0 += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Arena;
+
+    #[test]
+    fn reserve_insert_get() {
+        let mut arena = Arena::new(3);
+
+        let indices = (0..20)
+            .map(|i| {
+                let (index, _) = arena.reserve();
+                arena.insert(index, i);
+                index
+            })
+            .collect::<Vec<_>>();
+
+        for (i, index) in indices.into_iter().enumerate() {
+            assert_eq!(arena.get(index), Some(&Some(i)));
+        }
+
+        // 20 objects fit into chunks of 3, 6, and 12 slots.
+        assert_eq!(arena.chunks.len(), 3);
+    }
+}
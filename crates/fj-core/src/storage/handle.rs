@@ -2,7 +2,7 @@ use std::{
     any::type_name, borrow::Borrow, cmp::Ordering, fmt, hash::Hash, ops::Deref,
 };
 
-use super::{blocks::Index, store::StoreInner};
+use super::{arena::Index, store::StoreInner};
 
 /// A handle for an object
 ///
@@ -23,6 +23,15 @@ use super::{blocks::Index, store::StoreInner};
 ///
 /// You can compare the identity of two objects through their `Handle`s, by
 /// comparing the values returned by [`Handle::id`].
+///
+/// # Implementation Note
+///
+/// Objects of each type are stored contiguously, in the arena of their
+/// [`Store`]. A handle refers to its object by its index within that arena. It
+/// also keeps a pointer to the object, which makes dereferencing it as cheap
+/// as dereferencing a reference, without requiring access to the store.
+///
+/// [`Store`]: super::Store
 pub struct Handle<T> {
     pub(super) store: StoreInner<T>,
     pub(super) index: Index,
@@ -70,7 +79,7 @@ impl<T> Deref for Handle<T> {
         // - The pointer points to an initialized instance of `T`.
         //
         // Further, there is no way to (safely) get a `&mut` reference to any
-        // object in a `Store`/`Block`. So we know that the aliasing rules for
+        // object in a `Store`/`Arena`. So we know that the aliasing rules for
         // the reference we return here are enforced.
         //
         // Furthermore, all of the code mentioned here is covered by unit tests,
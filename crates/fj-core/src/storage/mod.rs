@@ -1,6 +1,6 @@
 //! Append-only object storage
 
-mod blocks;
+mod arena;
 mod handle;
 mod store;
 
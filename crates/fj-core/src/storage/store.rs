@@ -21,12 +21,12 @@
 //!
 //! But in any case, this was fun to write, and not that much work.
 
-use std::{marker::PhantomData, sync::Arc};
+use std::{marker::PhantomData, mem, sync::Arc};
 
 use parking_lot::RwLock;
 
 use super::{
-    blocks::{Blocks, Index},
+    arena::{Arena, Index},
     Handle,
 };
 
@@ -39,16 +39,24 @@ pub struct Store<T> {
 impl<T> Store<T> {
     /// Construct a new instance of `Store`
     ///
-    /// Equivalent to calling [`Store::with_block_size`] with a default block
-    /// size.
+    /// Equivalent to calling [`Store::with_initial_capacity`] with a default
+    /// capacity.
     pub fn new() -> Self {
-        Self::with_block_size(16384)
+        Self::with_initial_capacity(1024)
     }
 
-    /// Construct a new instance of `Store` using the provided block size
-    pub fn with_block_size(block_size: usize) -> Self {
+    /// Construct a new instance of `Store` using the provided initial capacity
+    ///
+    /// Objects are stored contiguously in chunks of memory. The initial
+    /// capacity is the size of the first chunk. Each chunk after that is as
+    /// large as all previous chunks combined.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `initial_capacity` is zero.
+    pub fn with_initial_capacity(initial_capacity: usize) -> Self {
         let inner = Arc::new(RwLock::new(StoreInnerInner {
-            blocks: Blocks::new(block_size),
+            arena: Arena::new(initial_capacity),
         }));
 
         Self { inner }
@@ -67,7 +75,7 @@ impl<T> Store<T> {
     pub fn reserve(&self) -> Handle<T> {
         let mut inner = self.inner.write();
 
-        let (index, ptr) = inner.blocks.reserve();
+        let (index, ptr) = inner.arena.reserve();
 
         Handle {
             store: self.inner.clone(),
@@ -85,7 +93,29 @@ impl<T> Store<T> {
     /// before.
     pub fn insert(&mut self, handle: Handle<T>, object: T) {
         let mut inner = self.inner.write();
-        inner.blocks.insert(handle.index, object);
+        inner.arena.insert(handle.index, object);
+    }
+
+    /// Return the number of slots that have been reserved in this store
+    ///
+    /// This includes slots that have been reserved, but not used to insert an
+    /// object yet.
+    pub fn len(&self) -> usize {
+        self.inner.read().arena.len()
+    }
+
+    /// Indicate whether no slots have been reserved in this store
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Estimate the memory used by this store, in bytes
+    ///
+    /// This covers the memory allocated for all slots of the store, whether
+    /// they are used or not. Memory that is allocated by the objects
+    /// themselves, like the list of half-edges in a cycle, is not included.
+    pub fn memory_usage(&self) -> usize {
+        self.inner.read().arena.capacity() * mem::size_of::<Option<T>>()
     }
 
     /// Iterate over all objects in this store
@@ -128,7 +158,8 @@ impl<'a, T: 'a> Iterator for Iter<'a, T> {
 
         loop {
             let index = self.next_index;
-            let ptr = inner.blocks.get_and_inc(&mut self.next_index)?;
+            let ptr = inner.arena.get(index)?;
+            self.next_index.inc();
 
             if ptr.is_none() {
                 // This is a reserved slot.
@@ -148,7 +179,7 @@ pub type StoreInner<T> = Arc<RwLock<StoreInnerInner<T>>>;
 
 #[derive(Debug)]
 pub struct StoreInnerInner<T> {
-    blocks: Blocks<T>,
+    arena: Arena<T>,
 }
 
 #[cfg(test)]
@@ -159,7 +190,7 @@ mod tests {
 
     #[test]
     fn insert_and_handle() {
-        let mut store = Store::with_block_size(1);
+        let mut store = Store::with_initial_capacity(1);
 
         let handle: Handle<i32> = store.reserve();
         let object = 0;
@@ -171,7 +202,7 @@ mod tests {
 
     #[test]
     fn insert_and_iter() {
-        let mut store = Store::with_block_size(1);
+        let mut store = Store::with_initial_capacity(1);
 
         let a: Handle<i32> = store.reserve();
         let b = store.reserve();
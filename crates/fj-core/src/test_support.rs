@@ -0,0 +1,204 @@
+//! # Helpers for writing tests
+//!
+//! Testing an operation requires objects to operate on, and building even a
+//! simple, valid shape by hand takes a lot of setup. The functions in this
+//! module build known-valid objects in a single call.
+//!
+//! Results of geometric operations are subject to floating-point inaccuracy,
+//! so they rarely compare exactly equal to expected values. The assertions in
+//! this module compare geometry within a tolerance instead.
+//!
+//! This module is only available, if the `test-support` feature is enabled:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! fj-core = { version = "*", features = ["test-support"] }
+//! ```
+
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    objects::{Cycle, Face, Shell, Solid},
+    operations::{
+        build::{BuildCycle, BuildFace, BuildShell, Polygon, TetrahedronShell},
+        insert::{Insert, IsInsertedYes},
+        primitives::BuildPrimitive,
+    },
+    services::Services,
+    storage::Handle,
+};
+
+/// Build a cube with the provided edge length
+///
+/// The cube is centered on the z-axis, and extends from the xy-plane in the
+/// positive z direction.
+pub fn cube(size: impl Into<Scalar>, services: &mut Services) -> Handle<Solid> {
+    let size = size.into();
+    Solid::box_from_dims([size, size, size], services).insert(services)
+}
+
+/// Build the shell of a cube with the provided edge length
+///
+/// See [`cube`] for the position of the cube.
+pub fn cube_shell(
+    size: impl Into<Scalar>,
+    services: &mut Services,
+) -> Handle<Shell> {
+    cube(size, services).shells().only().clone()
+}
+
+/// Build a triangle with the corners `(0, 0, 0)`, `(1, 0, 0)`, `(0, 1, 0)`
+///
+/// Besides the face itself, the returned [`Polygon`] provides access to the
+/// half-edges and vertices of the triangle.
+pub fn triangle(services: &mut Services) -> Polygon<3, IsInsertedYes> {
+    Face::triangle([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]], services)
+        .insert(services)
+}
+
+/// Build a square cycle with the provided edge length
+///
+/// One corner of the square is at the origin, and its edges run along the
+/// positive axes, in counter-clockwise order.
+pub fn square_cycle(
+    size: impl Into<Scalar>,
+    services: &mut Services,
+) -> Handle<Cycle> {
+    let size = size.into();
+    let zero = Scalar::ZERO;
+
+    Cycle::polygon(
+        [[zero, zero], [size, zero], [size, size], [zero, size]],
+        services,
+    )
+    .insert(services)
+}
+
+/// Build a circular cycle with the provided radius around the origin
+pub fn circle_cycle(
+    radius: impl Into<Scalar>,
+    services: &mut Services,
+) -> Handle<Cycle> {
+    Cycle::circle([0., 0.], radius, services).insert(services)
+}
+
+/// Build a tetrahedron with corners at the origin and on each positive axis
+///
+/// Besides the shell itself, the returned [`TetrahedronShell`] provides access
+/// to the faces of the tetrahedron.
+pub fn tetrahedron(services: &mut Services) -> TetrahedronShell<IsInsertedYes> {
+    Shell::tetrahedron(
+        [[0., 0., 0.], [0., 1., 0.], [1., 0., 0.], [0., 0., 1.]],
+        services,
+    )
+    .insert(services)
+}
+
+/// Assert that two scalars are equal, within the provided tolerance
+#[track_caller]
+pub fn assert_scalar_approx_eq(
+    a: impl Into<Scalar>,
+    b: impl Into<Scalar>,
+    tolerance: impl Into<Scalar>,
+) {
+    let a = a.into();
+    let b = b.into();
+    let tolerance = tolerance.into();
+
+    let difference = (a - b).abs();
+    assert!(
+        difference <= tolerance,
+        "Scalars differ by `{difference}`, which exceeds tolerance \
+        `{tolerance}`\n- a: {a}\n- b: {b}"
+    );
+}
+
+/// Assert that two points are equal, within the provided tolerance
+#[track_caller]
+pub fn assert_point_approx_eq<const D: usize>(
+    a: impl Into<Point<D>>,
+    b: impl Into<Point<D>>,
+    tolerance: impl Into<Scalar>,
+) {
+    let a = a.into();
+    let b = b.into();
+    let tolerance = tolerance.into();
+
+    let distance = a.distance_to(&b);
+    assert!(
+        distance <= tolerance,
+        "Points are `{distance}` apart, which exceeds tolerance \
+        `{tolerance}`\n- a: {a:?}\n- b: {b:?}"
+    );
+}
+
+/// Assert that two vectors are equal, within the provided tolerance
+#[track_caller]
+pub fn assert_vector_approx_eq<const D: usize>(
+    a: impl Into<Vector<D>>,
+    b: impl Into<Vector<D>>,
+    tolerance: impl Into<Scalar>,
+) {
+    let a = a.into();
+    let b = b.into();
+    let tolerance = tolerance.into();
+
+    let difference = (a - b).magnitude();
+    assert!(
+        difference <= tolerance,
+        "Vectors differ by `{difference}`, which exceeds tolerance \
+        `{tolerance}`\n- a: {a:?}\n- b: {b:?}"
+    );
+}
+
+/// Assert that two AABBs are equal, within the provided tolerance
+///
+/// The tolerance applies to the minimum and maximum points individually.
+#[track_caller]
+pub fn assert_aabb_approx_eq<const D: usize>(
+    a: Aabb<D>,
+    b: Aabb<D>,
+    tolerance: impl Into<Scalar>,
+) {
+    let tolerance = tolerance.into();
+
+    assert_point_approx_eq(a.min, b.min, tolerance);
+    assert_point_approx_eq(a.max, b.max, tolerance);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::Services;
+
+    use super::{
+        assert_point_approx_eq, assert_scalar_approx_eq, circle_cycle,
+        cube_shell, square_cycle, tetrahedron, triangle,
+    };
+
+    #[test]
+    fn helpers_build_valid_objects() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let shell = cube_shell(2., &mut services);
+        assert_eq!(shell.faces().len(), 6);
+
+        triangle(&mut services);
+        square_cycle(1., &mut services);
+        circle_cycle(1., &mut services);
+        tetrahedron(&mut services);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn approx_eq_assertions_respect_tolerance() {
+        assert_scalar_approx_eq(1., 1. + 1e-9, 1e-8);
+        assert_point_approx_eq([0., 0., 0.], [0., 0., 1e-9], 1e-8);
+
+        let result = std::panic::catch_unwind(|| {
+            assert_point_approx_eq([0., 0.], [0., 1e-7], 1e-8);
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::BTreeSet;
+
+use crate::objects::Assembly;
+
+use super::{Validate, ValidationConfig, ValidationError};
+
+impl Validate for Assembly {
+    fn validate_with_config(
+        &self,
+        _: &ValidationConfig,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        AssemblyValidationError::check_names(self, errors);
+    }
+}
+
+/// [`Assembly`] validation failed
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum AssemblyValidationError {
+    /// [`Assembly`] contains multiple instances with the same name
+    #[error("Assembly contains multiple instances named `{name}`")]
+    DuplicateInstanceName {
+        /// The name that is used by multiple instances
+        name: String,
+    },
+}
+
+impl AssemblyValidationError {
+    fn check_names(assembly: &Assembly, errors: &mut Vec<ValidationError>) {
+        let mut names = BTreeSet::new();
+
+        for instance in assembly.instances() {
+            if !names.insert(instance.name()) {
+                errors.push(
+                    Self::DuplicateInstanceName {
+                        name: instance.name().to_string(),
+                    }
+                    .into(),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        assert_contains_err,
+        objects::{Assembly, Instance, Solid},
+        operations::{build::BuildSolid, insert::Insert},
+        services::Services,
+        validate::{AssemblyValidationError, Validate, ValidationError},
+    };
+
+    #[test]
+    fn duplicate_instance_name() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = Solid::empty().insert(&mut services);
+
+        let valid = Assembly::new([
+            Instance::new("a", solid.clone()),
+            Instance::new("b", solid.clone()).with_translation([1., 0., 0.]),
+        ]);
+        let invalid = Assembly::new([
+            Instance::new("a", solid.clone()),
+            Instance::new("a", solid).with_translation([1., 0., 0.]),
+        ]);
+
+        valid.validate_and_return_first_error()?;
+        assert_contains_err!(
+            invalid,
+            ValidationError::Assembly(
+                AssemblyValidationError::DuplicateInstanceName { .. }
+            )
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+use std::{error::Error, sync::Arc};
+
+use crate::objects::{BehindHandle, Object};
+
+use super::{ValidationConfig, ValidationError};
+
+/// A validation check that is defined outside of Fornjot
+///
+/// Custom checks can encode domain-specific requirements, like a minimum wall
+/// thickness for 3D printing. Register them using
+/// [`Services::register_validation_check`], to run them alongside the built-in
+/// validation checks, on every object of type [`ValidationCheck::Object`]
+/// that is inserted from then on.
+///
+/// [`Services::register_validation_check`]: crate::services::Services::register_validation_check
+pub trait ValidationCheck: Send + Sync + 'static {
+    /// The type of object that the check applies to, like `Face`
+    type Object: 'static;
+
+    /// The error that the check produces
+    type Error: Error + Send + Sync + 'static;
+
+    /// A name that identifies the check in validation errors
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Check the object, and push an error for every violated requirement
+    fn check(
+        &self,
+        object: &Self::Object,
+        config: &ValidationConfig,
+        errors: &mut Vec<Self::Error>,
+    );
+}
+
+/// A custom validation check, ready to be registered
+///
+/// Wraps a [`ValidationCheck`], independently of the type of object it
+/// applies to.
+#[derive(Clone)]
+pub struct RegisteredCheck {
+    inner: Arc<dyn ErasedCheck>,
+}
+
+impl RegisteredCheck {
+    /// Wrap a custom validation check
+    pub fn new(check: impl ValidationCheck) -> Self {
+        Self {
+            inner: Arc::new(check),
+        }
+    }
+
+    /// Run the check on the object, if it applies to objects of its type
+    pub fn check(
+        &self,
+        object: &Object<BehindHandle>,
+        config: &ValidationConfig,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        self.inner.check_object(object, config, errors);
+    }
+}
+
+/// A custom validation check failed
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("Custom validation check `{check}` failed")]
+pub struct CustomValidationError {
+    /// The name of the check that failed
+    pub check: &'static str,
+
+    /// The error that the check produced
+    #[source]
+    pub err: Arc<dyn Error + Send + Sync>,
+}
+
+trait ErasedCheck: Send + Sync {
+    fn check_object(
+        &self,
+        object: &Object<BehindHandle>,
+        config: &ValidationConfig,
+        errors: &mut Vec<ValidationError>,
+    );
+}
+
+impl<C: ValidationCheck> ErasedCheck for C {
+    fn check_object(
+        &self,
+        object: &Object<BehindHandle>,
+        config: &ValidationConfig,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let Some(object) = object.as_any().downcast_ref::<C::Object>() else {
+            return;
+        };
+
+        let mut check_errors = Vec::new();
+        self.check(object, config, &mut check_errors);
+
+        errors.extend(check_errors.into_iter().map(|err| {
+            ValidationError::from(CustomValidationError {
+                check: self.name(),
+                err: Arc::new(err),
+            })
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::{BehindHandle, Cycle, Object, Vertex},
+        operations::insert::Insert,
+        services::{
+            Services, State, Validation, ValidationCommand, ValidationEvent,
+        },
+        storage::ObjectId,
+        validate::{ValidationConfig, ValidationError},
+    };
+
+    use super::{RegisteredCheck, ValidationCheck};
+
+    #[test]
+    fn custom_checks_apply_to_their_object_type() {
+        let mut services = Services::new();
+        let vertex = Vertex::new().insert(&mut services);
+
+        let mut validation = Validation::default();
+        for check in [
+            RegisteredCheck::new(NoVertices),
+            RegisteredCheck::new(NoCycles),
+        ] {
+            let mut events = Vec::new();
+            validation.decide(
+                ValidationCommand::RegisterCheck { check },
+                &mut events,
+            );
+            for event in &events {
+                validation.evolve(event);
+            }
+        }
+
+        let objects: Vec<Object<BehindHandle>> = vec![vertex.into()];
+        let mut events = Vec::new();
+        validation.decide(
+            ValidationCommand::ValidateObjects { first: 0, objects },
+            &mut events,
+        );
+
+        let failed_checks = events
+            .iter()
+            .filter_map(|event| match event {
+                ValidationEvent::ValidationFailed {
+                    err: ValidationError::Custom(err),
+                    ..
+                } => Some(err.check),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(failed_checks, ["no vertices"]);
+    }
+
+    #[test]
+    fn registered_checks_run_on_inserted_objects() {
+        let mut services = Services::new();
+        services.register_validation_check(NoVertices);
+
+        let vertex = Vertex::new().insert(&mut services);
+        assert_failed_check(services, vertex.id(), "no vertices");
+    }
+
+    #[test]
+    fn registered_checks_run_on_merged_objects() {
+        let mut services = Services::new();
+        services.register_validation_check(NoVertices);
+
+        let mut other = Services::new();
+        let vertex = Vertex::new().insert(&mut other);
+        services.merge(other);
+
+        assert_failed_check(services, vertex.id(), "no vertices");
+    }
+
+    fn assert_failed_check(
+        services: Services,
+        object: ObjectId,
+        expected: &str,
+    ) {
+        let Services { validation, .. } = services;
+        let mut validation = validation.into_state();
+
+        assert_eq!(validation.errors.len(), 1);
+        assert!(matches!(
+            validation.errors.get(&object),
+            Some(ValidationError::Custom(err)) if err.check == expected
+        ));
+
+        // Handle the error, so dropping `Validation` doesn't panic.
+        validation.errors.clear();
+    }
+
+    struct NoVertices;
+
+    impl ValidationCheck for NoVertices {
+        type Object = Vertex;
+        type Error = Forbidden;
+
+        fn name(&self) -> &'static str {
+            "no vertices"
+        }
+
+        fn check(
+            &self,
+            _: &Vertex,
+            _: &ValidationConfig,
+            errors: &mut Vec<Forbidden>,
+        ) {
+            errors.push(Forbidden);
+        }
+    }
+
+    struct NoCycles;
+
+    impl ValidationCheck for NoCycles {
+        type Object = Cycle;
+        type Error = Forbidden;
+
+        fn check(
+            &self,
+            _: &Cycle,
+            _: &ValidationConfig,
+            errors: &mut Vec<Forbidden>,
+        ) {
+            errors.push(Forbidden);
+        }
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("Object is forbidden")]
+    struct Forbidden;
+}
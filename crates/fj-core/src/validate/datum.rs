@@ -0,0 +1,12 @@
+use crate::objects::Datum;
+
+use super::{Validate, ValidationConfig, ValidationError};
+
+impl Validate for Datum {
+    fn validate_with_config(
+        &self,
+        _: &ValidationConfig,
+        _: &mut Vec<ValidationError>,
+    ) {
+    }
+}
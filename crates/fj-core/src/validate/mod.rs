@@ -17,6 +17,10 @@
 //! check produces one of the types of validation error, that these nested enums
 //! represent).
 //!
+//! Downstream crates can define their own checks, by implementing
+//! [`ValidationCheck`]. Those run alongside the built-in checks, once they have
+//! been registered with the [`Services`] API.
+//!
 //! In principle, the absence of validation errors should guarantee, that an
 //! object can be exported to an external file format without problems (which
 //! falls under the purview of the [`fj-export`] crate). This has not yet been
@@ -72,8 +76,11 @@
 //! [issue tracker]: https://github.com/hannobraun/fornjot/issues
 //! [`Services`]: crate::services::Services
 
+mod assembly;
 mod curve;
+mod custom;
 mod cycle;
+mod datum;
 mod edge;
 mod face;
 mod region;
@@ -84,8 +91,12 @@ mod surface;
 mod vertex;
 
 pub use self::{
-    cycle::CycleValidationError, edge::EdgeValidationError,
-    face::FaceValidationError, shell::ShellValidationError,
+    assembly::AssemblyValidationError,
+    custom::{CustomValidationError, RegisteredCheck, ValidationCheck},
+    cycle::CycleValidationError,
+    edge::EdgeValidationError,
+    face::FaceValidationError,
+    shell::ShellValidationError,
     solid::SolidValidationError,
 };
 
@@ -171,6 +182,14 @@ impl Default for ValidationConfig {
 /// An error that can occur during a validation
 #[derive(Clone, Debug, thiserror::Error)]
 pub enum ValidationError {
+    /// `Assembly` validation error
+    #[error("`Assembly` validation error")]
+    Assembly(#[from] AssemblyValidationError),
+
+    /// Custom validation error
+    #[error("Custom validation error")]
+    Custom(#[from] CustomValidationError),
+
     /// `Cycle` validation error
     #[error("`Cycle` validation error")]
     Cycle(#[from] CycleValidationError),
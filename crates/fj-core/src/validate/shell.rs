@@ -5,6 +5,7 @@ use fj_math::{Point, Scalar};
 use crate::{
     geometry::{CurveBoundary, SurfaceGeometry},
     objects::{Curve, HalfEdge, Shell, Surface, Vertex},
+    operations::orientation::OrientationAudit,
     queries::{
         AllHalfEdgesWithSurface, BoundingVerticesOfHalfEdge, SiblingOfHalfEdge,
     },
@@ -69,6 +70,15 @@ pub enum ShellValidationError {
         /// The second half-edge
         half_edge_b: Handle<HalfEdge>,
     },
+
+    /// [`Shell`] contains faces that are not oriented consistently
+    ///
+    /// This is not checked by the regular validation of shells. See
+    /// [`AuditOrientation::validate_orientation`].
+    ///
+    /// [`AuditOrientation::validate_orientation`]: crate::operations::orientation::AuditOrientation::validate_orientation
+    #[error("`Shell` is not oriented consistently\n{0}")]
+    InconsistentOrientation(Box<OrientationAudit>),
 }
 
 impl ShellValidationError {
@@ -0,0 +1,352 @@
+//! Export of technical drawings to SVG and DXF
+
+use std::io::Write;
+
+use fj_interop::drawing::{Annotation, Drawing, LineStyle, Stroke};
+use fj_math::{Aabb, Point, Scalar};
+
+use crate::Error;
+
+/// The margin around the drawing, relative to its size
+const MARGIN: f64 = 0.05;
+
+/// The width of visible strokes, relative to the size of the drawing
+const STROKE_WIDTH: f64 = 0.002;
+
+pub fn write_svg(
+    drawing: &Drawing,
+    mut writer: impl Write,
+) -> Result<(), Error> {
+    let aabb = drawing.aabb().unwrap_or_default();
+    let size = aabb.max - aabb.min;
+    let diagonal = size.magnitude();
+    let margin = diagonal * MARGIN;
+
+    // SVG coordinates point down, drawing coordinates point up.
+    let [min_x, max_y] = [aabb.min.u - margin, aabb.max.v + margin];
+    let [width, height] = [size.u + margin * 2., size.v + margin * 2.];
+    let stroke_width = diagonal * STROKE_WIDTH;
+
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        min_x, -max_y, width, height,
+    )?;
+    writeln!(
+        writer,
+        r#"<g fill="none" stroke="black" stroke-linecap="round" stroke-width="{stroke_width}">"#,
+    )?;
+
+    // Hidden lines go first, so visible lines are drawn on top of them.
+    for style in [LineStyle::Hidden, LineStyle::Bend, LineStyle::Visible] {
+        let attributes = match style {
+            LineStyle::Visible => String::new(),
+            LineStyle::Hidden => format!(
+                r#" stroke-width="{}" stroke-dasharray="{} {}""#,
+                stroke_width / 2.,
+                stroke_width * 4.,
+                stroke_width * 2.,
+            ),
+            LineStyle::Bend => format!(
+                r#" stroke-width="{}" stroke-dasharray="{} {} 0 {}""#,
+                stroke_width / 2.,
+                stroke_width * 8.,
+                stroke_width * 2.,
+                stroke_width * 2.,
+            ),
+        };
+
+        for view in &drawing.views {
+            writeln!(writer, r#"<g class="{}"{}>"#, view.name, attributes)?;
+
+            for Stroke { points: [a, b], .. } in
+                view.strokes.iter().filter(|stroke| stroke.style == style)
+            {
+                writeln!(
+                    writer,
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+                    a.u, -a.v, b.u, -b.v,
+                )?;
+            }
+
+            writeln!(writer, "</g>")?;
+        }
+    }
+
+    writeln!(
+        writer,
+        r#"<g class="dimensions" stroke-width="{}">"#,
+        stroke_width / 2.,
+    )?;
+    for view in &drawing.views {
+        for Annotation {
+            lines,
+            text,
+            text_position,
+            text_height,
+        } in &view.annotations
+        {
+            for [a, b] in lines {
+                writeln!(
+                    writer,
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+                    a.u, -a.v, b.u, -b.v,
+                )?;
+            }
+            writeln!(
+                writer,
+                r#"<text x="{}" y="{}" font-size="{}" fill="black" stroke="none" text-anchor="middle" dominant-baseline="middle">{}</text>"#,
+                text_position.u,
+                -text_position.v,
+                text_height,
+                escape_xml(text),
+            )?;
+        }
+    }
+    writeln!(writer, "</g>")?;
+
+    writeln!(writer, "</g>")?;
+    writeln!(writer, "</svg>")?;
+
+    Ok(())
+}
+
+pub fn write_dxf(
+    drawing: &Drawing,
+    mut writer: impl Write,
+) -> Result<(), Error> {
+    let aabb = drawing.aabb().unwrap_or_default();
+    let dash = dash_length(&aabb);
+
+    // DXF files consist of pairs of group codes and values, one per line.
+    let mut pairs = Vec::<(u32, String)>::new();
+
+    pairs.extend(section("TABLES"));
+    pairs.extend(table("LTYPE", 3));
+    pairs.extend([
+        (0, "LTYPE".into()),
+        (2, "CONTINUOUS".into()),
+        (70, "0".into()),
+        (3, "Solid line".into()),
+        (72, "65".into()),
+        (73, "0".into()),
+        (40, "0.0".into()),
+        (0, "LTYPE".into()),
+        (2, "HIDDEN".into()),
+        (70, "0".into()),
+        (3, "Hidden line".into()),
+        (72, "65".into()),
+        (73, "2".into()),
+        (40, (dash * 3.).to_string()),
+        (49, (dash * 2.).to_string()),
+        (49, (-dash).to_string()),
+        (0, "LTYPE".into()),
+        (2, "DASHDOT".into()),
+        (70, "0".into()),
+        (3, "Dash dot line".into()),
+        (72, "65".into()),
+        (73, "4".into()),
+        (40, (dash * 6.).to_string()),
+        (49, (dash * 4.).to_string()),
+        (49, (-dash).to_string()),
+        (49, "0.0".into()),
+        (49, (-dash).to_string()),
+    ]);
+    pairs.push((0, "ENDTAB".into()));
+    pairs.extend(table("LAYER", 4));
+    for (layer, line_type) in [
+        ("VISIBLE", "CONTINUOUS"),
+        ("HIDDEN", "HIDDEN"),
+        ("BEND", "DASHDOT"),
+        ("DIMENSIONS", "CONTINUOUS"),
+    ] {
+        pairs.extend([
+            (0, "LAYER".into()),
+            (2, layer.into()),
+            (70, "0".into()),
+            (62, "7".into()),
+            (6, line_type.into()),
+        ]);
+    }
+    pairs.push((0, "ENDTAB".into()));
+    pairs.push((0, "ENDSEC".into()));
+
+    pairs.extend(section("ENTITIES"));
+    for view in &drawing.views {
+        for stroke in &view.strokes {
+            let layer = match stroke.style {
+                LineStyle::Visible => "VISIBLE",
+                LineStyle::Hidden => "HIDDEN",
+                LineStyle::Bend => "BEND",
+            };
+            let [a, b] = stroke.points;
+
+            pairs.extend(line(layer, [a, b]));
+        }
+
+        for annotation in &view.annotations {
+            for &points in &annotation.lines {
+                pairs.extend(line("DIMENSIONS", points));
+            }
+
+            // Text is centered on its second alignment point. The first one
+            // is required, but ignored.
+            let position = annotation.text_position;
+            pairs.extend([
+                (0, "TEXT".into()),
+                (8, "DIMENSIONS".into()),
+                (10, position.u.to_string()),
+                (20, position.v.to_string()),
+                (30, "0.0".into()),
+                (40, annotation.text_height.to_string()),
+                (1, escape_dxf(&annotation.text)),
+                (72, "1".into()),
+                (11, position.u.to_string()),
+                (21, position.v.to_string()),
+                (31, "0.0".into()),
+                (73, "2".into()),
+            ]);
+        }
+    }
+    pairs.push((0, "ENDSEC".into()));
+    pairs.push((0, "EOF".into()));
+
+    for (code, value) in pairs {
+        writeln!(writer, "{code}\n{value}")?;
+    }
+
+    Ok(())
+}
+
+fn line(layer: &str, [a, b]: [Point<2>; 2]) -> [(u32, String); 8] {
+    [
+        (0, "LINE".into()),
+        (8, layer.into()),
+        (10, a.u.to_string()),
+        (20, a.v.to_string()),
+        (30, "0.0".into()),
+        (11, b.u.to_string()),
+        (21, b.v.to_string()),
+        (31, "0.0".into()),
+    ]
+}
+
+fn section(name: &str) -> [(u32, String); 2] {
+    [(0, "SECTION".into()), (2, name.into())]
+}
+
+fn table(name: &str, num_entries: u32) -> [(u32, String); 3] {
+    [
+        (0, "TABLE".into()),
+        (2, name.into()),
+        (70, num_entries.to_string()),
+    ]
+}
+
+fn dash_length(aabb: &Aabb<2>) -> Scalar {
+    let size = (aabb.max - aabb.min).magnitude();
+    if size == Scalar::ZERO {
+        return Scalar::ONE;
+    }
+
+    size / 100.
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Replace symbols with the control codes that DXF uses for them
+fn escape_dxf(text: &str) -> String {
+    text.replace('⌀', "%%c").replace('°', "%%d")
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::drawing::{Annotation, Drawing, LineStyle, Stroke, View};
+    use fj_math::{Point, Scalar, Vector};
+
+    use super::{write_dxf, write_svg};
+
+    #[test]
+    fn svg() -> Result<(), crate::Error> {
+        let mut svg = Vec::new();
+        write_svg(&drawing(), &mut svg)?;
+        let svg = String::from_utf8(svg).expect("SVG is valid UTF-8");
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+
+        // One line per stroke, plus the line of the annotation.
+        assert_eq!(svg.matches("<line").count(), 4);
+        assert_eq!(svg.matches("stroke-dasharray").count(), 2);
+
+        // Text is escaped, and drawing coordinates point up.
+        assert!(svg.contains(">&lt;1&gt; &amp; ⌀2</text>"));
+        assert!(svg.contains(r#"y1="-2""#));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dxf() -> Result<(), crate::Error> {
+        let mut dxf = Vec::new();
+        write_dxf(&drawing(), &mut dxf)?;
+        let dxf = String::from_utf8(dxf).expect("DXF is valid UTF-8");
+
+        let lines = dxf.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len() % 2, 0);
+        assert_eq!(lines[lines.len() - 2..], ["0", "EOF"]);
+
+        // Every stroke ends up on the layer for its style.
+        let layers_of_lines = lines
+            .chunks(2)
+            .zip(lines.chunks(2).skip(1))
+            .filter(|(entity, _)| *entity == ["0", "LINE"])
+            .map(|(_, layer)| layer[1])
+            .collect::<Vec<_>>();
+        assert_eq!(
+            layers_of_lines,
+            ["VISIBLE", "HIDDEN", "BEND", "DIMENSIONS"]
+        );
+
+        assert!(lines.contains(&"<1> & %%c2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn empty_drawing() -> Result<(), crate::Error> {
+        write_svg(&Drawing::default(), Vec::new())?;
+        write_dxf(&Drawing::default(), Vec::new())?;
+
+        Ok(())
+    }
+
+    fn drawing() -> Drawing {
+        let stroke = |points: [[f64; 2]; 2], style| Stroke {
+            points: points.map(Point::from),
+            style,
+        };
+
+        Drawing {
+            views: vec![View {
+                name: "front".into(),
+                offset: Vector::from([0., 0.]),
+                strokes: vec![
+                    stroke([[0., 0.], [1., 0.]], LineStyle::Visible),
+                    stroke([[0., 1.], [1., 1.]], LineStyle::Hidden),
+                    stroke([[0., 2.], [1., 2.]], LineStyle::Bend),
+                ],
+                annotations: vec![Annotation {
+                    lines: vec![[Point::from([0., 3.]), Point::from([1., 3.])]],
+                    text: "<1> & ⌀2".into(),
+                    text_position: Point::from([0.5, 3.5]),
+                    text_height: Scalar::from(0.1),
+                }],
+            }],
+        }
+    }
+}
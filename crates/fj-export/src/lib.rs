@@ -8,12 +8,14 @@
 //!
 //! [Fornjot]: https://www.fornjot.app/
 
-use std::{fs::File, path::Path};
+mod drawing;
+
+use std::{fs::File, io::BufWriter, path::Path};
 
 use thiserror::Error;
 
-use fj_interop::mesh::Mesh;
-use fj_math::{Point, Triangle};
+use fj_interop::{drawing::Drawing, mesh::Mesh};
+use fj_math::{Point, Transform, Triangle, Unit};
 
 /// Export the provided mesh to the file at the given path.
 ///
@@ -21,6 +23,9 @@ use fj_math::{Point, Triangle};
 ///
 /// Currently 3MF & STL file types are supported. The case insensitive file extension of
 /// the provided path is used to switch between supported types.
+///
+/// The mesh is assumed to be specified in millimeters. Use
+/// [`export_with_unit`], if that is not the case.
 pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
     match path.extension() {
         Some(extension) if extension.to_ascii_uppercase() == "3MF" => {
@@ -39,6 +44,61 @@ pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
     }
 }
 
+/// Export the provided mesh, which is specified in `unit`, to the given path
+///
+/// All supported file formats are written in millimeters. 3MF declares this
+/// unit explicitly, while STL and OBJ files carry no unit information, and are
+/// conventionally interpreted as millimeters. The mesh is converted from
+/// `unit` before writing it, to prevent the unit from silently changing.
+///
+/// See [`export`] for more information.
+pub fn export_with_unit(
+    mesh: &Mesh<Point<3>>,
+    path: &Path,
+    unit: Unit,
+) -> Result<(), Error> {
+    if unit == Unit::Millimeter {
+        return export(mesh, path);
+    }
+
+    let factor = unit.conversion_factor(Unit::Millimeter);
+    let transform = Transform::scale(factor.into_f64());
+
+    let mut converted = Mesh::new();
+    for triangle in mesh.triangles() {
+        converted.push_triangle(
+            transform.transform_triangle(&triangle.inner),
+            triangle.color,
+        );
+    }
+
+    export(&converted, path)
+}
+
+/// Export the provided drawing to the file at the given path
+///
+/// This function will create a file if it does not exist, and will truncate it
+/// if it does.
+///
+/// Currently SVG & DXF file types are supported. The case insensitive file
+/// extension of the provided path is used to switch between supported types.
+pub fn export_drawing(drawing: &Drawing, path: &Path) -> Result<(), Error> {
+    match path.extension() {
+        Some(extension) if extension.to_ascii_uppercase() == "SVG" => {
+            let file = BufWriter::new(File::create(path)?);
+            drawing::write_svg(drawing, file)
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "DXF" => {
+            let file = BufWriter::new(File::create(path)?);
+            drawing::write_dxf(drawing, file)
+        }
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }
+}
+
 fn export_3mf(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
     let vertices = mesh
         .vertices()
@@ -0,0 +1,403 @@
+//! Boolean operations on triangle meshes
+//!
+//! Exact boolean operations belong into the b-rep kernel, where they can
+//! preserve the topology and geometry of the shapes involved. Until those are
+//! available for all cases, the operations here provide a pragmatic fallback,
+//! that works on the triangle meshes that result from triangulation.
+//!
+//! The implementation uses binary space partitioning (BSP) trees, following
+//! the well-known approach of [csg.js]. Both meshes are expected to be closed
+//! and consistently oriented, with their triangles facing outwards.
+//!
+//! [csg.js]: https://github.com/evanw/csg.js
+
+use fj_math::{Point, Scalar, Triangle, Vector};
+
+use crate::mesh::{Color, Mesh};
+
+/// A boolean operation on two meshes
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum BooleanOp {
+    /// The space that is covered by either mesh
+    Union,
+
+    /// The space that is covered by the first mesh, but not the second
+    Difference,
+
+    /// The space that is covered by both meshes
+    Intersection,
+}
+
+impl Mesh<Point<3>> {
+    /// Compute a boolean operation of this mesh and another one
+    pub fn boolean(&self, op: BooleanOp, other: &Self) -> Self {
+        let mut a = Node::new(polygons_from_mesh(self));
+        let mut b = Node::new(polygons_from_mesh(other));
+
+        match op {
+            BooleanOp::Union => {
+                a.clip_to(&b);
+                b.clip_to(&a);
+                b.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.build(b.all_polygons());
+            }
+            BooleanOp::Difference => {
+                a.invert();
+                a.clip_to(&b);
+                b.clip_to(&a);
+                b.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.build(b.all_polygons());
+                a.invert();
+            }
+            BooleanOp::Intersection => {
+                a.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.clip_to(&b);
+                b.clip_to(&a);
+                a.build(b.all_polygons());
+                a.invert();
+            }
+        }
+
+        mesh_from_polygons(a.all_polygons())
+    }
+}
+
+/// The distance within which points are considered to be on a plane
+const EPSILON: f64 = 1e-5;
+
+fn polygons_from_mesh(mesh: &Mesh<Point<3>>) -> Vec<Polygon> {
+    mesh.triangles()
+        .map(|triangle| {
+            let points = triangle.inner.points();
+            Polygon {
+                plane: Plane::from_points(points),
+                points: points.to_vec(),
+                color: triangle.color,
+            }
+        })
+        .collect()
+}
+
+fn mesh_from_polygons(polygons: Vec<Polygon>) -> Mesh<Point<3>> {
+    let mut mesh = Mesh::new();
+
+    for polygon in polygons {
+        // The polygons are convex, so a fan triangulation is valid.
+        let [first, rest @ ..] = polygon.points.as_slice() else {
+            continue;
+        };
+
+        for edge in rest.windows(2) {
+            if let Ok(triangle) =
+                Triangle::from_points([*first, edge[0], edge[1]])
+            {
+                mesh.push_triangle(triangle, polygon.color);
+            }
+        }
+    }
+
+    mesh
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Plane {
+    normal: Vector<3>,
+    distance: Scalar,
+}
+
+impl Plane {
+    fn from_points([a, b, c]: [Point<3>; 3]) -> Self {
+        let normal = (b - a).cross(&(c - a)).normalize();
+        let distance = normal.dot(&a.coords);
+
+        Self { normal, distance }
+    }
+
+    fn flip(&mut self) {
+        self.normal = -self.normal;
+        self.distance = -self.distance;
+    }
+
+    fn side_of(&self, point: &Point<3>) -> Side {
+        let t = self.normal.dot(&point.coords) - self.distance;
+
+        if t < -Scalar::from(EPSILON) {
+            Side::Back
+        } else if t > Scalar::from(EPSILON) {
+            Side::Front
+        } else {
+            Side::Coplanar
+        }
+    }
+
+    /// Split a polygon by this plane, sorting the results into `split`
+    fn split(&self, polygon: Polygon, split: &mut Split) {
+        let sides = polygon
+            .points
+            .iter()
+            .map(|point| self.side_of(point))
+            .collect::<Vec<_>>();
+
+        let has_front = sides.contains(&Side::Front);
+        let has_back = sides.contains(&Side::Back);
+
+        match (has_front, has_back) {
+            (false, false) => {
+                if self.normal.dot(&polygon.plane.normal) > Scalar::ZERO {
+                    split.coplanar_front.push(polygon);
+                } else {
+                    split.coplanar_back.push(polygon);
+                }
+            }
+            (true, false) => split.front.push(polygon),
+            (false, true) => split.back.push(polygon),
+            (true, true) => {
+                let mut front = Vec::new();
+                let mut back = Vec::new();
+
+                let n = polygon.points.len();
+                for i in 0..n {
+                    let j = (i + 1) % n;
+                    let (a, b) = (polygon.points[i], polygon.points[j]);
+                    let (side_a, side_b) = (sides[i], sides[j]);
+
+                    if side_a != Side::Back {
+                        front.push(a);
+                    }
+                    if side_a != Side::Front {
+                        back.push(a);
+                    }
+
+                    let is_spanning = matches!(
+                        (side_a, side_b),
+                        (Side::Front, Side::Back) | (Side::Back, Side::Front)
+                    );
+                    if is_spanning {
+                        let t = (self.distance - self.normal.dot(&a.coords))
+                            / self.normal.dot(&(b - a));
+                        let point = a + (b - a) * t;
+
+                        front.push(point);
+                        back.push(point);
+                    }
+                }
+
+                if front.len() >= 3 {
+                    split.front.push(Polygon {
+                        points: front,
+                        ..polygon
+                    });
+                }
+                if back.len() >= 3 {
+                    split.back.push(Polygon {
+                        points: back,
+                        ..polygon
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Side {
+    Coplanar,
+    Front,
+    Back,
+}
+
+#[derive(Default)]
+struct Split {
+    coplanar_front: Vec<Polygon>,
+    coplanar_back: Vec<Polygon>,
+    front: Vec<Polygon>,
+    back: Vec<Polygon>,
+}
+
+/// A convex polygon
+#[derive(Clone, Debug)]
+struct Polygon {
+    points: Vec<Point<3>>,
+    plane: Plane,
+    color: Color,
+}
+
+impl Polygon {
+    fn flip(&mut self) {
+        self.points.reverse();
+        self.plane.flip();
+    }
+}
+
+/// A node in a BSP tree
+#[derive(Default)]
+struct Node {
+    plane: Option<Plane>,
+    front: Option<Box<Node>>,
+    back: Option<Box<Node>>,
+    polygons: Vec<Polygon>,
+}
+
+impl Node {
+    fn new(polygons: Vec<Polygon>) -> Self {
+        let mut node = Self::default();
+        node.build(polygons);
+        node
+    }
+
+    /// Convert solid space to empty space and vice versa
+    fn invert(&mut self) {
+        for polygon in &mut self.polygons {
+            polygon.flip();
+        }
+        if let Some(plane) = &mut self.plane {
+            plane.flip();
+        }
+        if let Some(front) = &mut self.front {
+            front.invert();
+        }
+        if let Some(back) = &mut self.back {
+            back.invert();
+        }
+
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    /// Remove all parts of the polygons that are inside of this tree
+    fn clip_polygons(&self, polygons: Vec<Polygon>) -> Vec<Polygon> {
+        let Some(plane) = &self.plane else {
+            return polygons;
+        };
+
+        let mut split = Split::default();
+        for polygon in polygons {
+            plane.split(polygon, &mut split);
+        }
+
+        let mut front = split.front;
+        front.extend(split.coplanar_front);
+        let mut back = split.back;
+        back.extend(split.coplanar_back);
+
+        let mut polygons = match &self.front {
+            Some(node) => node.clip_polygons(front),
+            None => front,
+        };
+        if let Some(node) = &self.back {
+            polygons.extend(node.clip_polygons(back));
+        }
+
+        polygons
+    }
+
+    /// Remove all parts of the polygons in this tree that are inside of `other`
+    fn clip_to(&mut self, other: &Node) {
+        self.polygons = other.clip_polygons(std::mem::take(&mut self.polygons));
+
+        if let Some(front) = &mut self.front {
+            front.clip_to(other);
+        }
+        if let Some(back) = &mut self.back {
+            back.clip_to(other);
+        }
+    }
+
+    fn all_polygons(&self) -> Vec<Polygon> {
+        let mut polygons = self.polygons.clone();
+
+        if let Some(front) = &self.front {
+            polygons.extend(front.all_polygons());
+        }
+        if let Some(back) = &self.back {
+            polygons.extend(back.all_polygons());
+        }
+
+        polygons
+    }
+
+    /// Add polygons to the tree, splitting them where necessary
+    fn build(&mut self, polygons: Vec<Polygon>) {
+        let Some(first) = polygons.first() else {
+            return;
+        };
+        let plane = *self.plane.get_or_insert(first.plane);
+
+        let mut split = Split::default();
+        for polygon in polygons {
+            plane.split(polygon, &mut split);
+        }
+
+        self.polygons.extend(split.coplanar_front);
+        self.polygons.extend(split.coplanar_back);
+
+        if !split.front.is_empty() {
+            self.front
+                .get_or_insert_with(Default::default)
+                .build(split.front);
+        }
+        if !split.back.is_empty() {
+            self.back
+                .get_or_insert_with(Default::default)
+                .build(split.back);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Vector};
+
+    use crate::mesh::{Color, Mesh};
+
+    use super::BooleanOp;
+
+    #[test]
+    fn overlapping_cubes() {
+        let a = cube(Vector::from([0., 0., 0.]));
+        let b = cube(Vector::from([0.5, 0., 0.]));
+
+        assert_volume(&a.boolean(BooleanOp::Union, &b), 1.5);
+        assert_volume(&a.boolean(BooleanOp::Difference, &b), 0.5);
+        assert_volume(&a.boolean(BooleanOp::Intersection, &b), 0.5);
+    }
+
+    fn cube(offset: Vector<3>) -> Mesh<Point<3>> {
+        let quads = [
+            [[0., 0., 0.], [0., 1., 0.], [1., 1., 0.], [1., 0., 0.]],
+            [[0., 0., 1.], [1., 0., 1.], [1., 1., 1.], [0., 1., 1.]],
+            [[0., 0., 0.], [1., 0., 0.], [1., 0., 1.], [0., 0., 1.]],
+            [[0., 1., 0.], [0., 1., 1.], [1., 1., 1.], [1., 1., 0.]],
+            [[0., 0., 0.], [0., 0., 1.], [0., 1., 1.], [0., 1., 0.]],
+            [[1., 0., 0.], [1., 1., 0.], [1., 1., 1.], [1., 0., 1.]],
+        ];
+
+        let mut mesh = Mesh::new();
+        for quad in quads {
+            let [a, b, c, d] = quad.map(|point| Point::from(point) + offset);
+            mesh.push_triangle([a, b, c], Color::default());
+            mesh.push_triangle([a, c, d], Color::default());
+        }
+
+        mesh
+    }
+
+    fn assert_volume(mesh: &Mesh<Point<3>>, expected: f64) {
+        // Sum of the signed volumes of the tetrahedra that are formed by each
+        // triangle and the origin.
+        let volume = mesh
+            .triangles()
+            .map(|triangle| {
+                let [a, b, c] = triangle.inner.points();
+                a.coords.dot(&b.coords.cross(&c.coords)).into_f64() / 6.
+            })
+            .sum::<f64>();
+
+        assert!((volume - expected).abs() < 1e-9, "volume: {volume}");
+    }
+}
@@ -0,0 +1,103 @@
+//! A technical drawing
+
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+/// A technical drawing, consisting of multiple views of a model
+#[derive(Clone, Debug, Default)]
+pub struct Drawing {
+    /// The views of the drawing
+    pub views: Vec<View>,
+}
+
+impl Drawing {
+    /// Compute the AABB of all strokes and annotations in the drawing
+    ///
+    /// Returns `None`, if the drawing has no strokes and no annotations.
+    pub fn aabb(&self) -> Option<Aabb<2>> {
+        let strokes = self
+            .views
+            .iter()
+            .flat_map(|view| &view.strokes)
+            .flat_map(|stroke| stroke.points);
+        let annotations = self
+            .views
+            .iter()
+            .flat_map(|view| &view.annotations)
+            .flat_map(|annotation| {
+                annotation
+                    .lines
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .chain([annotation.text_position])
+            });
+        let points = strokes.chain(annotations).collect::<Vec<_>>();
+
+        if points.is_empty() {
+            return None;
+        }
+
+        Some(Aabb::<2>::from_points(points))
+    }
+}
+
+/// A single view within a drawing
+#[derive(Clone, Debug)]
+pub struct View {
+    /// The name of the view, like "front" or "top"
+    pub name: String,
+
+    /// The offset of the view within the drawing
+    ///
+    /// The strokes of the view have already been moved by this offset. It is
+    /// provided, so projected points can be moved into the drawing later.
+    pub offset: Vector<2>,
+
+    /// The strokes that make up the view
+    pub strokes: Vec<Stroke>,
+
+    /// The annotations of the view, like dimensions
+    pub annotations: Vec<Annotation>,
+}
+
+/// A straight line within a view
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Stroke {
+    /// The start and end points of the stroke
+    pub points: [Point<2>; 2],
+
+    /// The style of the stroke
+    pub style: LineStyle,
+}
+
+/// The style of a stroke
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum LineStyle {
+    /// A visible edge or silhouette, drawn as a solid line
+    Visible,
+
+    /// A hidden edge or silhouette, drawn as a dashed line
+    Hidden,
+
+    /// A line along which a flat pattern is bent, drawn as a dash-dotted line
+    Bend,
+}
+
+/// An annotation within a view, like a dimension
+#[derive(Clone, Debug)]
+pub struct Annotation {
+    /// The lines that make up the annotation
+    ///
+    /// For a dimension, these are its extension lines, its dimension line,
+    /// and its arrowheads.
+    pub lines: Vec<[Point<2>; 2]>,
+
+    /// The text of the annotation
+    pub text: String,
+
+    /// The position of the center of the text
+    pub text_position: Point<2>,
+
+    /// The height of the text
+    pub text_height: Scalar,
+}
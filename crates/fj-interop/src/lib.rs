@@ -7,8 +7,17 @@
 //! This library defines types that allow other components of Fornjot to
 //! interoperate, without having to depend on each other.
 //!
+//! ## Optional features
+//!
+//! - `mint`: Enables conversions between the math types that are used here,
+//!   and those of [mint].
+//!
 //! [Fornjot]: https://www.fornjot.app/
+//! [mint]: https://crates.io/crates/mint
 
+pub mod boolean;
+pub mod drawing;
 pub mod ext;
 pub mod mesh;
+pub mod mesh_builder;
 pub mod model;
@@ -2,7 +2,7 @@
 
 use std::{collections::HashMap, hash::Hash};
 
-use fj_math::Point;
+use fj_math::{Point, Vector};
 
 /// A triangle mesh
 #[derive(Clone, Debug)]
@@ -12,6 +12,7 @@ pub struct Mesh<V> {
 
     indices_by_vertex: HashMap<V, Index>,
     triangles: Vec<Triangle>,
+    normals: Vec<Option<[Vector<3>; 3]>>,
 }
 
 impl<V> Mesh<V>
@@ -69,6 +70,19 @@ where
     pub fn triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
         self.triangles.iter().copied()
     }
+
+    /// Access the triangles of the mesh, together with their normals
+    ///
+    /// The normals are those at the points of the triangle, if they are known.
+    /// See [`Mesh::push_triangle_with_normals`].
+    pub fn triangles_with_normals(
+        &self,
+    ) -> impl Iterator<Item = (Triangle, Option<[Vector<3>; 3]>)> + '_ {
+        self.triangles
+            .iter()
+            .copied()
+            .zip(self.normals.iter().copied())
+    }
 }
 
 impl Mesh<Point<3>> {
@@ -78,8 +92,29 @@ impl Mesh<Point<3>> {
         triangle: impl Into<fj_math::Triangle<3>>,
         color: Color,
     ) {
-        let triangle = triangle.into();
+        self.push_triangle_inner(triangle.into(), None, color);
+    }
 
+    /// Add a triangle to the mesh, including the normals at its points
+    ///
+    /// These are the normals of the exact surface that the triangle
+    /// approximates, not the normal of the triangle itself. They point towards
+    /// the front side of the surface.
+    pub fn push_triangle_with_normals(
+        &mut self,
+        triangle: impl Into<fj_math::Triangle<3>>,
+        normals: [Vector<3>; 3],
+        color: Color,
+    ) {
+        self.push_triangle_inner(triangle.into(), Some(normals), color);
+    }
+
+    fn push_triangle_inner(
+        &mut self,
+        triangle: fj_math::Triangle<3>,
+        normals: Option<[Vector<3>; 3]>,
+        color: Color,
+    ) {
         for point in triangle.points() {
             self.push_vertex(point);
         }
@@ -88,6 +123,7 @@ impl Mesh<Point<3>> {
             inner: triangle,
             color,
         });
+        self.normals.push(normals);
     }
 }
 
@@ -100,6 +136,7 @@ impl<V> Default for Mesh<V> {
             indices: Vec::default(),
             indices_by_vertex: HashMap::default(),
             triangles: Vec::default(),
+            normals: Vec::default(),
         }
     }
 }
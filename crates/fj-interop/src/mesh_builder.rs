@@ -0,0 +1,304 @@
+//! Configurable conversion of meshes into indexed triangle lists
+//!
+//! See [`MeshBuilder`].
+
+use std::collections::{HashMap, HashSet};
+
+use fj_math::{Point, Scalar};
+
+use crate::mesh::{Color, Mesh};
+
+/// Builds an indexed triangle list from a [`Mesh`]
+///
+/// [`Mesh`] only merges vertices that are exactly identical, and keeps every
+/// triangle that is pushed into it. Different consumers of a mesh need
+/// different trade-offs here: A viewer might want 16-bit indices to save
+/// memory, a slicer might need nearly coincident vertices to be welded, so the
+/// mesh ends up closed, and a glTF exporter might want to get rid of duplicate
+/// triangles.
+///
+/// By default, `MeshBuilder` produces the same vertices and triangles as the
+/// original mesh, with 32-bit indices. Use its methods to configure it.
+#[derive(Clone, Copy, Debug)]
+pub struct MeshBuilder {
+    weld_tolerance: Option<Scalar>,
+    remove_duplicate_triangles: bool,
+    index_type: IndexType,
+}
+
+impl MeshBuilder {
+    /// Construct a new instance of `MeshBuilder` with the default options
+    pub fn new() -> Self {
+        Self {
+            weld_tolerance: None,
+            remove_duplicate_triangles: false,
+            index_type: IndexType::U32,
+        }
+    }
+
+    /// Weld vertices that are within the provided distance of each other
+    ///
+    /// Triangles that become degenerate as a result of welding, because two of
+    /// their vertices have been merged, are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `tolerance` is not positive.
+    pub fn weld_vertices(mut self, tolerance: impl Into<Scalar>) -> Self {
+        let tolerance = tolerance.into();
+        assert!(tolerance > Scalar::ZERO, "Weld tolerance must be positive");
+
+        self.weld_tolerance = Some(tolerance);
+        self
+    }
+
+    /// Remove triangles that are duplicates of others
+    ///
+    /// Two triangles are considered duplicates, if they refer to the same
+    /// vertices with the same winding. Triangles with opposite winding face in
+    /// different directions, and are kept.
+    pub fn remove_duplicate_triangles(mut self) -> Self {
+        self.remove_duplicate_triangles = true;
+        self
+    }
+
+    /// Use the provided type for the indices of the result
+    pub fn index_type(mut self, index_type: IndexType) -> Self {
+        self.index_type = index_type;
+        self
+    }
+
+    /// Build an indexed triangle list from the provided mesh
+    pub fn build(
+        &self,
+        mesh: &Mesh<Point<3>>,
+    ) -> Result<IndexedMesh, MeshBuilderError> {
+        let mut vertices = Vertices::new(self.weld_tolerance);
+        let mut seen = HashSet::new();
+
+        let mut indices = Vec::new();
+        let mut colors = Vec::new();
+
+        for triangle in mesh.triangles() {
+            let [a, b, c] =
+                triangle.inner.points().map(|point| vertices.index(point));
+
+            if a == b || b == c || c == a {
+                // Degenerate, due to welding.
+                continue;
+            }
+
+            if self.remove_duplicate_triangles {
+                // Rotate the triangle, so the smallest index comes first. This
+                // keeps the winding intact.
+                let key = if a < b && a < c {
+                    [a, b, c]
+                } else if b < c {
+                    [b, c, a]
+                } else {
+                    [c, a, b]
+                };
+
+                if !seen.insert(key) {
+                    continue;
+                }
+            }
+
+            indices.extend([a, b, c]);
+            colors.push(triangle.color);
+        }
+
+        let indices = match self.index_type {
+            IndexType::U16 => Indices::U16(
+                indices
+                    .into_iter()
+                    .map(u16::try_from)
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| MeshBuilderError::TooManyVertices {
+                        num_vertices: vertices.points.len(),
+                        index_type: IndexType::U16,
+                    })?,
+            ),
+            IndexType::U32 => Indices::U32(indices),
+        };
+
+        Ok(IndexedMesh {
+            vertices: vertices.points,
+            indices,
+            colors,
+        })
+    }
+}
+
+impl Default for MeshBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The type of the indices produced by [`MeshBuilder`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum IndexType {
+    /// 16-bit indices
+    ///
+    /// Uses less memory, but can only address 65536 vertices.
+    U16,
+
+    /// 32-bit indices
+    U32,
+}
+
+/// An indexed triangle list, as produced by [`MeshBuilder`]
+#[derive(Clone, Debug)]
+pub struct IndexedMesh {
+    /// The vertices of the mesh
+    pub vertices: Vec<Point<3>>,
+
+    /// The indices of the mesh
+    ///
+    /// Each consecutive group of three indices forms a triangle.
+    pub indices: Indices,
+
+    /// The color of each triangle
+    pub colors: Vec<Color>,
+}
+
+/// The indices of an [`IndexedMesh`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Indices {
+    /// 16-bit indices
+    U16(Vec<u16>),
+
+    /// 32-bit indices
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    /// Access the indices, converted to `u32`
+    pub fn to_u32(&self) -> Vec<u32> {
+        match self {
+            Self::U16(indices) => {
+                indices.iter().copied().map(u32::from).collect()
+            }
+            Self::U32(indices) => indices.clone(),
+        }
+    }
+}
+
+/// Error building an [`IndexedMesh`]
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum MeshBuilderError {
+    /// The mesh has more vertices than the index type can address
+    #[error("Mesh has {num_vertices} vertices, too many for {index_type:?}")]
+    TooManyVertices {
+        /// The number of vertices in the mesh
+        num_vertices: usize,
+
+        /// The requested index type
+        index_type: IndexType,
+    },
+}
+
+/// The vertices of the mesh being built, optionally welded
+struct Vertices {
+    points: Vec<Point<3>>,
+    exact: HashMap<Point<3>, u32>,
+    weld: Option<(Scalar, WeldGrid)>,
+}
+
+/// The indices of the vertices in each cell of the welding grid
+type WeldGrid = HashMap<[i64; 3], Vec<u32>>;
+
+impl Vertices {
+    fn new(weld_tolerance: Option<Scalar>) -> Self {
+        Self {
+            points: Vec::new(),
+            exact: HashMap::new(),
+            weld: weld_tolerance.map(|tolerance| (tolerance, HashMap::new())),
+        }
+    }
+
+    fn index(&mut self, point: Point<3>) -> u32 {
+        if let Some(&index) = self.exact.get(&point) {
+            return index;
+        }
+
+        let index = match &mut self.weld {
+            Some((tolerance, grid)) => {
+                // Points within the tolerance are either in the same cell of
+                // the grid, or in one of the neighboring ones.
+                let cell = point
+                    .coords
+                    .components
+                    .map(|c| (c / *tolerance).floor().into_f64() as i64);
+
+                let existing = neighbors(cell)
+                    .filter_map(|cell| grid.get(&cell))
+                    .flatten()
+                    .copied()
+                    .find(|&index| {
+                        self.points[index as usize].distance_to(&point)
+                            <= *tolerance
+                    });
+
+                existing.unwrap_or_else(|| {
+                    let index = self.points.len() as u32;
+                    self.points.push(point);
+                    grid.entry(cell).or_default().push(index);
+                    index
+                })
+            }
+            None => {
+                let index = self.points.len() as u32;
+                self.points.push(point);
+                index
+            }
+        };
+
+        self.exact.insert(point, index);
+        index
+    }
+}
+
+fn neighbors([x, y, z]: [i64; 3]) -> impl Iterator<Item = [i64; 3]> {
+    (-1..=1).flat_map(move |dx| {
+        (-1..=1).flat_map(move |dy| {
+            (-1..=1).map(move |dz| [x + dx, y + dy, z + dz])
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use crate::mesh::{Color, Mesh};
+
+    use super::{IndexType, Indices, MeshBuilder};
+
+    #[test]
+    fn weld_and_deduplicate() -> Result<(), super::MeshBuilderError> {
+        let [a, b, c, d] =
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [1e-6, 1., 0.]]
+                .map(Point::from);
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, b, c], Color::default());
+        mesh.push_triangle([b, d, a], Color::default());
+
+        let indexed = MeshBuilder::new().build(&mesh)?;
+        assert_eq!(indexed.vertices.len(), 4);
+        assert_eq!(indexed.indices, Indices::U32(vec![0, 1, 2, 1, 3, 0]));
+
+        let indexed = MeshBuilder::new()
+            .weld_vertices(1e-3)
+            .remove_duplicate_triangles()
+            .index_type(IndexType::U16)
+            .build(&mesh)?;
+        assert_eq!(indexed.vertices.len(), 3);
+        assert_eq!(indexed.indices, Indices::U16(vec![0, 1, 2]));
+        assert_eq!(indexed.colors.len(), 1);
+
+        Ok(())
+    }
+}
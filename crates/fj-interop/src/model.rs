@@ -1,6 +1,6 @@
 //! An approximated model
 
-use fj_math::{Aabb, Point};
+use fj_math::{Aabb, Point, PolyChain, Unit};
 
 use crate::mesh::Mesh;
 
@@ -10,6 +10,12 @@ pub struct Model {
     /// The triangle mesh that approximates the model
     pub mesh: Mesh<Point<3>>,
 
+    /// The polylines that approximate the edges of the model
+    pub edges: Vec<PolyChain<3>>,
+
     /// The axis-aligned bounding box of the model
     pub aabb: Aabb<3>,
+
+    /// The unit that the model's values are specified in
+    pub unit: Unit,
 }
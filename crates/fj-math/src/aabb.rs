@@ -97,6 +97,15 @@ impl Aabb<3> {
         parry3d_f64::bounding_volume::Aabb::from_points(&points).into()
     }
 
+    /// Construct a 3-dimensional AABB from a slice of points
+    ///
+    /// Produces the same result as [`Aabb::from_points`], but is faster for
+    /// large numbers of points. Returns `None`, if `points` is empty.
+    pub fn from_point_slice(points: &[Point<3>]) -> Option<Self> {
+        let [min, max] = crate::batch::aabb_of_points(points)?;
+        Some(Self { min, max })
+    }
+
     /// Construct a 3-dimensional AABB from a Parry AABB
     pub fn from_parry(aabb: parry3d_f64::bounding_volume::Aabb) -> Self {
         Self {
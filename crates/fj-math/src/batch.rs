@@ -0,0 +1,188 @@
+//! Operations on batches of points
+//!
+//! Transforming points and computing their bounding boxes one at a time is
+//! expensive, when done for all the points of a large approximation. The
+//! functions in this module process whole slices of points instead, using SIMD
+//! instructions where available.
+//!
+//! They are exposed through [`Transform::transform_points`] and
+//! [`Aabb::from_point_slice`].
+//!
+//! [`Transform::transform_points`]: crate::Transform::transform_points
+//! [`Aabb::from_point_slice`]: crate::Aabb::from_point_slice
+
+use crate::Point;
+
+pub use self::imp::{aabb_of_points, transform_points};
+
+#[cfg(target_arch = "x86_64")]
+mod imp {
+    use std::arch::x86_64::{
+        _mm_add_pd, _mm_loadu_pd, _mm_max_pd, _mm_min_pd, _mm_mul_pd,
+        _mm_set1_pd, _mm_set_pd, _mm_storeu_pd,
+    };
+
+    use crate::Point;
+
+    use super::coords;
+
+    /// Transform points by an affine matrix, in column-major order
+    ///
+    /// The x- and y-coordinates are computed together, using SSE2.
+    pub fn transform_points(
+        matrix: &[f64; 16],
+        points: &[Point<3>],
+    ) -> Vec<Point<3>> {
+        // SAFETY: SSE2 is part of the baseline of `x86_64`, so the intrinsics
+        // are always available. All loads and stores refer to arrays that are
+        // large enough.
+        unsafe {
+            let [c0, c1, c2, c3] =
+                [0, 4, 8, 12].map(|i| _mm_loadu_pd(matrix[i..].as_ptr()));
+
+            points
+                .iter()
+                .map(|point| {
+                    let [x, y, z] = coords(point);
+
+                    let xy = _mm_add_pd(
+                        _mm_add_pd(
+                            _mm_mul_pd(c0, _mm_set1_pd(x)),
+                            _mm_mul_pd(c1, _mm_set1_pd(y)),
+                        ),
+                        _mm_add_pd(_mm_mul_pd(c2, _mm_set1_pd(z)), c3),
+                    );
+                    let mut out = [0.; 2];
+                    _mm_storeu_pd(out.as_mut_ptr(), xy);
+
+                    let z = matrix[2] * x
+                        + matrix[6] * y
+                        + matrix[10] * z
+                        + matrix[14];
+
+                    Point::from([out[0], out[1], z])
+                })
+                .collect()
+        }
+    }
+
+    /// Compute the minimum and maximum coordinates of the points
+    ///
+    /// The x- and y-coordinates are computed together, using SSE2.
+    pub fn aabb_of_points(points: &[Point<3>]) -> Option<[Point<3>; 2]> {
+        let (first, rest) = points.split_first()?;
+        let [x, y, z] = coords(first);
+
+        // SAFETY: SSE2 is part of the baseline of `x86_64`, so the intrinsics
+        // are always available. All stores refer to arrays that are large
+        // enough.
+        unsafe {
+            let mut min_xy = _mm_set_pd(y, x);
+            let mut max_xy = min_xy;
+            let [mut min_z, mut max_z] = [z, z];
+
+            for point in rest {
+                let [x, y, z] = coords(point);
+                let xy = _mm_set_pd(y, x);
+
+                min_xy = _mm_min_pd(min_xy, xy);
+                max_xy = _mm_max_pd(max_xy, xy);
+                min_z = min_z.min(z);
+                max_z = max_z.max(z);
+            }
+
+            let mut min = [0.; 2];
+            let mut max = [0.; 2];
+            _mm_storeu_pd(min.as_mut_ptr(), min_xy);
+            _mm_storeu_pd(max.as_mut_ptr(), max_xy);
+
+            Some([
+                Point::from([min[0], min[1], min_z]),
+                Point::from([max[0], max[1], max_z]),
+            ])
+        }
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+mod imp {
+    use crate::Point;
+
+    use super::coords;
+
+    /// Transform points by an affine matrix, in column-major order
+    pub fn transform_points(
+        matrix: &[f64; 16],
+        points: &[Point<3>],
+    ) -> Vec<Point<3>> {
+        points
+            .iter()
+            .map(|point| {
+                let [x, y, z] = coords(point);
+
+                Point::from([0, 1, 2].map(|row| {
+                    matrix[row] * x
+                        + matrix[row + 4] * y
+                        + matrix[row + 8] * z
+                        + matrix[row + 12]
+                }))
+            })
+            .collect()
+    }
+
+    /// Compute the minimum and maximum coordinates of the points
+    pub fn aabb_of_points(points: &[Point<3>]) -> Option<[Point<3>; 2]> {
+        let (first, rest) = points.split_first()?;
+        let first = coords(first);
+
+        let [min, max] =
+            rest.iter().fold([first, first], |[min, max], point| {
+                let point = coords(point);
+                [
+                    [0, 1, 2].map(|i| min[i].min(point[i])),
+                    [0, 1, 2].map(|i| max[i].max(point[i])),
+                ]
+            });
+
+        Some([Point::from(min), Point::from(max)])
+    }
+}
+
+fn coords(point: &Point<3>) -> [f64; 3] {
+    point.coords.components.map(|s| s.into_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aabb, Point, Transform, Vector};
+
+    #[test]
+    fn transform_points() {
+        let transform = Transform::translation([1., 2., 3.])
+            * Transform::rotation(Vector::from([0.3, -0.5, 1.2]))
+            * Transform::scale(2.);
+
+        let points = [[0., 0., 0.], [1., 2., 3.], [-4., 0.5, 7.]]
+            .map(Point::from)
+            .to_vec();
+
+        let batch = transform.transform_points(&points);
+        for (point, transformed) in points.iter().zip(batch) {
+            let expected = transform.transform_point(point);
+            assert!(expected.distance_to(&transformed) < 1e-12.into());
+        }
+    }
+
+    #[test]
+    fn aabb_from_point_slice() {
+        let points = [[1., -2., 3.], [-4., 5., 0.], [2., 0., -6.]]
+            .map(Point::from)
+            .to_vec();
+
+        assert_eq!(
+            Aabb::<3>::from_point_slice(&points),
+            Some(Aabb::<3>::from_points(points.iter().copied())),
+        );
+        assert_eq!(Aabb::<3>::from_point_slice(&[]), None);
+    }
+}
@@ -0,0 +1,233 @@
+use approx::AbsDiffEq;
+
+use crate::{Aabb, Circle, Point, Scalar, Vector};
+
+/// An n-dimensional ellipse
+///
+/// The dimensionality of the ellipse is defined by the const generic `D`
+/// parameter.
+///
+/// Ellipse coordinates are defined in the same way as circle coordinates (see
+/// [`Circle`]), meaning the coordinate is an angle, and the ellipse is the
+/// image of a circle under the linear map that is defined by its two axes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Ellipse<const D: usize> {
+    center: Point<D>,
+    a: Vector<D>,
+    b: Vector<D>,
+}
+
+impl<const D: usize> Ellipse<D> {
+    /// Construct an ellipse
+    ///
+    /// # Panics
+    ///
+    /// Panics, if any of the following requirements are not met:
+    ///
+    /// - Neither `a` nor `b` must have a length of zero.
+    /// - `a` and `b` must be perpendicular to each other.
+    pub fn new(
+        center: impl Into<Point<D>>,
+        a: impl Into<Vector<D>>,
+        b: impl Into<Vector<D>>,
+    ) -> Self {
+        let center = center.into();
+        let a = a.into();
+        let b = b.into();
+
+        assert_ne!(
+            a.magnitude(),
+            Scalar::ZERO,
+            "ellipse semi-axis `a` must not be zero"
+        );
+        assert_ne!(
+            b.magnitude(),
+            Scalar::ZERO,
+            "ellipse semi-axis `b` must not be zero"
+        );
+        // See comment in `Circle::new`, regarding this epsilon value.
+        assert!(
+            a.dot(&b).abs() < Scalar::default_epsilon(),
+            "`a` and `b` must be perpendicular to each other"
+        );
+
+        Self { center, a, b }
+    }
+
+    /// Access the center point of the ellipse
+    pub fn center(&self) -> Point<D> {
+        self.center
+    }
+
+    /// Access the vector that defines the starting point of the ellipse
+    ///
+    /// The point where this vector points from the ellipse center, is the zero
+    /// coordinate of the ellipse's coordinate system. The length of the vector
+    /// is the length of this semi-axis.
+    pub fn a(&self) -> Vector<D> {
+        self.a
+    }
+
+    /// Access the vector that defines the plane of the ellipse
+    ///
+    /// Also defines the direction of the ellipse's coordinate system. This
+    /// vector is perpendicular to [`Self::a`], and its length is the length of
+    /// this semi-axis.
+    pub fn b(&self) -> Vector<D> {
+        self.b
+    }
+
+    /// Access the length of the ellipse's largest semi-axis
+    pub fn semi_major_axis(&self) -> Scalar {
+        self.a.magnitude().max(self.b.magnitude())
+    }
+
+    /// Indicate whether the ellipse is a circle
+    ///
+    /// Returns the circle, if it is.
+    pub fn to_circle(&self) -> Option<Circle<D>> {
+        if self.a.magnitude() == self.b.magnitude() {
+            return Some(Circle::new(self.center, self.a, self.b));
+        }
+
+        None
+    }
+
+    /// Create a new instance that is reversed
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self
+    }
+
+    /// Convert a `D`-dimensional point to ellipse coordinates
+    ///
+    /// Converts the provided point into ellipse coordinates between `0.`
+    /// (inclusive) and `PI * 2.` (exclusive).
+    ///
+    /// Like [`Circle::point_to_circle_coords`], this projects the point onto
+    /// the ellipse before computing the coordinate, and callers are advised to
+    /// be careful about the points they pass.
+    pub fn point_to_ellipse_coords(
+        &self,
+        point: impl Into<Point<D>>,
+    ) -> Point<1> {
+        let vector = point.into() - self.center;
+
+        let u = vector.dot(&self.a) / self.a.dot(&self.a);
+        let v = vector.dot(&self.b) / self.b.dot(&self.b);
+
+        let atan = Scalar::atan2(v, u);
+        let coord = if atan >= Scalar::ZERO {
+            atan
+        } else {
+            atan + Scalar::TAU
+        };
+        Point::from([coord])
+    }
+
+    /// Convert a point in ellipse coordinates into a `D`-dimensional point
+    pub fn point_from_ellipse_coords(
+        &self,
+        point: impl Into<Point<1>>,
+    ) -> Point<D> {
+        self.center + self.vector_from_ellipse_coords(point.into().coords)
+    }
+
+    /// Convert a vector in ellipse coordinates into a `D`-dimensional vector
+    pub fn vector_from_ellipse_coords(
+        &self,
+        vector: impl Into<Vector<1>>,
+    ) -> Vector<D> {
+        let angle = vector.into().t;
+        let (sin, cos) = angle.sin_cos();
+
+        self.a * cos + self.b * sin
+    }
+
+    /// Calculate an AABB for the ellipse
+    ///
+    /// This is not the tightest possible AABB, as it is computed based on the
+    /// semi-major axis.
+    pub fn aabb(&self) -> Aabb<D> {
+        let center_to_min_max = Vector::from_component(self.semi_major_axis());
+
+        Aabb {
+            min: self.center() - center_to_min_max,
+            max: self.center() + center_to_min_max,
+        }
+    }
+}
+
+impl<const D: usize> From<Circle<D>> for Ellipse<D> {
+    fn from(circle: Circle<D>) -> Self {
+        Self {
+            center: circle.center(),
+            a: circle.a(),
+            b: circle.b(),
+        }
+    }
+}
+
+impl<const D: usize> approx::AbsDiffEq for Ellipse<D> {
+    type Epsilon = <Scalar as approx::AbsDiffEq>::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        Scalar::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.center.abs_diff_eq(&other.center, epsilon)
+            && self.a.abs_diff_eq(&other.a, epsilon)
+            && self.b.abs_diff_eq(&other.b, epsilon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar};
+
+    use super::Ellipse;
+
+    #[test]
+    fn point_to_ellipse_coords() {
+        let ellipse = Ellipse::new([1., 2., 3.], [2., 0., 0.], [0., 1., 0.]);
+
+        assert_eq!(
+            ellipse.point_to_ellipse_coords([3., 2., 3.]),
+            Point::from([0.]),
+        );
+        assert_eq!(
+            ellipse.point_to_ellipse_coords([1., 3., 3.]),
+            Point::from([FRAC_PI_2]),
+        );
+        assert_eq!(
+            ellipse.point_to_ellipse_coords([-1., 2., 3.]),
+            Point::from([PI]),
+        );
+        assert_eq!(
+            ellipse.point_to_ellipse_coords([1., 1., 3.]),
+            Point::from([FRAC_PI_2 * 3.]),
+        );
+    }
+
+    #[test]
+    fn point_from_ellipse_coords() {
+        let ellipse = Ellipse::new([0., 0.], [2., 0.], [0., 1.]);
+
+        assert_abs_diff_eq!(
+            ellipse.point_from_ellipse_coords([FRAC_PI_2]),
+            Point::from([0., 1.]),
+            epsilon = Scalar::from(1e-12)
+        );
+        assert_abs_diff_eq!(
+            ellipse.point_from_ellipse_coords([PI]),
+            Point::from([-2., 0.]),
+            epsilon = Scalar::from(1e-12)
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{Circle, Point, Scalar, Vector};
+
+/// An n-dimensional involute of a circle
+///
+/// The dimensionality of the involute is defined by the const generic `D`
+/// parameter.
+///
+/// The involute is the curve traced by the end of a string that is unwound
+/// from a circle, the base circle. It is the standard tooth profile of gears.
+///
+/// Involute coordinates are the angles at which the string leaves the base
+/// circle, defined like circle coordinates (see [`Circle`]). At coordinate
+/// `0`, the involute starts on the base circle.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Involute<const D: usize> {
+    center: Point<D>,
+    a: Vector<D>,
+    b: Vector<D>,
+}
+
+impl<const D: usize> Involute<D> {
+    /// Construct an involute
+    ///
+    /// `a` and `b` define the base circle, like they do for [`Circle`]. If
+    /// they are not perpendicular or don't have the same length, the involute
+    /// is the image of the involute of a circle under the linear map that is
+    /// defined by them.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `a` or `b` have a length of zero.
+    pub fn new(
+        center: impl Into<Point<D>>,
+        a: impl Into<Vector<D>>,
+        b: impl Into<Vector<D>>,
+    ) -> Self {
+        let center = center.into();
+        let a = a.into();
+        let b = b.into();
+
+        assert_ne!(a.magnitude(), Scalar::ZERO, "`a` must not be zero");
+        assert_ne!(b.magnitude(), Scalar::ZERO, "`b` must not be zero");
+
+        Self { center, a, b }
+    }
+
+    /// Construct the involute of the provided base circle
+    pub fn from_base_circle(circle: Circle<D>) -> Self {
+        Self::new(circle.center(), circle.a(), circle.b())
+    }
+
+    /// Construct an involute from the center and radius of its base circle
+    pub fn from_center_and_radius(
+        center: impl Into<Point<D>>,
+        radius: impl Into<Scalar>,
+    ) -> Self {
+        Self::from_base_circle(Circle::from_center_and_radius(center, radius))
+    }
+
+    /// Access the center point of the base circle
+    pub fn center(&self) -> Point<D> {
+        self.center
+    }
+
+    /// Access the vector that defines the starting point of the involute
+    pub fn a(&self) -> Vector<D> {
+        self.a
+    }
+
+    /// Access the vector that defines the plane of the involute
+    ///
+    /// Also defines the direction of the involute's coordinate system.
+    pub fn b(&self) -> Vector<D> {
+        self.b
+    }
+
+    /// Create a new instance that is reversed
+    ///
+    /// Like for a circle, the coordinate `t` of the reversed involute is the
+    /// coordinate `-t` of the original one.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self
+    }
+
+    /// Convert a `D`-dimensional point into involute coordinates
+    ///
+    /// The point is expected to be on the involute. Its distance from the
+    /// center determines the magnitude of the involute coordinate. Of the two
+    /// coordinates with that magnitude, the one that is closer to the point is
+    /// returned.
+    ///
+    /// Like [`Circle::point_to_circle_coords`], this expects `a` and `b` to be
+    /// perpendicular and of equal length.
+    pub fn point_to_involute_coords(
+        &self,
+        point: impl Into<Point<D>>,
+    ) -> Point<1> {
+        let point = point.into();
+        let vector = point - self.center;
+
+        let u = vector.dot(&self.a) / self.a.dot(&self.a);
+        let v = vector.dot(&self.b) / self.b.dot(&self.b);
+
+        // At coordinate `t`, the distance from the center is the hypotenuse of
+        // the base radius and the unwound string, which has length `t` in
+        // units of the base radius.
+        let t = Scalar::max(u * u + v * v - Scalar::ONE, Scalar::ZERO).sqrt();
+
+        [t, -t]
+            .map(|t| {
+                let distance =
+                    (self.point_from_involute_coords([t]) - point).magnitude();
+                (distance, t)
+            })
+            .into_iter()
+            .min()
+            .map(|(_, t)| Point::from([t]))
+            .expect("Array is not empty")
+    }
+
+    /// Convert a point in involute coordinates into a `D`-dimensional point
+    pub fn point_from_involute_coords(
+        &self,
+        point: impl Into<Point<1>>,
+    ) -> Point<D> {
+        self.center + self.vector_from_involute_coords(point.into().coords)
+    }
+
+    /// Convert a vector in involute coordinates into a `D`-dimensional vector
+    ///
+    /// The result points from the center of the base circle to the point at
+    /// the provided coordinate.
+    pub fn vector_from_involute_coords(
+        &self,
+        vector: impl Into<Vector<1>>,
+    ) -> Vector<D> {
+        let t = vector.into().t;
+        let (sin, cos) = t.sin_cos();
+
+        self.a * (cos + t * sin) + self.b * (sin - t * cos)
+    }
+
+    /// Compute the derivative of the involute at the provided coordinate
+    pub fn derivative(&self, point: impl Into<Point<1>>) -> Vector<D> {
+        let t = point.into().t;
+        let (sin, cos) = t.sin_cos();
+
+        (self.a * cos + self.b * sin) * t
+    }
+
+    /// Compute an upper bound for the length of the second derivative
+    ///
+    /// Returns a value that is at least the length of the second derivative at
+    /// any coordinate `t` with `|t| <= max`. This is used to determine how
+    /// densely the involute needs to be approximated.
+    pub fn second_derivative_bound(&self, max: Scalar) -> Scalar {
+        let axes = self.a.magnitude() + self.b.magnitude();
+        axes * (max.abs() + 1.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar};
+
+    use super::Involute;
+
+    #[test]
+    fn point_from_involute_coords() {
+        let involute = Involute::from_center_and_radius([0., 0.], 1.);
+
+        assert_eq!(
+            involute.point_from_involute_coords([0.]),
+            Point::from([1., 0.])
+        );
+
+        // After unwinding a quarter turn, the string leaves the base circle at
+        // its top, and has the length of a quarter of its circumference.
+        assert_abs_diff_eq!(
+            involute.point_from_involute_coords([FRAC_PI_2]),
+            Point::from([FRAC_PI_2, 1.]),
+            epsilon = Scalar::from(1e-12)
+        );
+    }
+
+    #[test]
+    fn point_to_involute_coords() {
+        let involute = Involute::from_center_and_radius([1., 2.], 2.);
+
+        for t in [0., 1., -1., PI, -5.] {
+            let point = involute.point_from_involute_coords([t]);
+            assert_abs_diff_eq!(
+                involute.point_to_involute_coords(point),
+                Point::from([t]),
+                epsilon = Scalar::from(1e-9)
+            );
+        }
+    }
+
+    #[test]
+    fn reverse() {
+        let involute = Involute::from_center_and_radius([1., 2.], 2.);
+        let reversed = involute.reverse();
+
+        for t in [0., 1., PI, -2.] {
+            assert_abs_diff_eq!(
+                reversed.point_from_involute_coords([t]),
+                involute.point_from_involute_coords([-t]),
+                epsilon = Scalar::from(1e-12)
+            );
+        }
+    }
+}
@@ -27,22 +27,38 @@
 //! [`From`]/[`Into`] documentation fails to provide any reasons for its
 //! mandate.
 //!
+//!
+//! ## Optional features
+//!
+//! - `mint`: Enables conversions between the types of this crate and those of
+//!   [mint], for interoperability with other libraries.
+//!
 //! [Fornjot]: https://www.fornjot.app/
 //! [nalgebra]: https://nalgebra.org/
 //! [Parry]: https://www.parry.rs/
+//! [mint]: https://crates.io/crates/mint
 
 mod aabb;
 mod arc;
+mod batch;
 mod circle;
 mod coordinates;
+mod ellipse;
+mod involute;
 mod line;
+#[cfg(feature = "mint")]
+mod mint;
+mod obb;
 mod plane;
 mod point;
 mod poly_chain;
 mod scalar;
 mod segment;
+mod spiral;
+mod torus;
 mod transform;
 mod triangle;
+mod unit;
 mod vector;
 
 pub use self::{
@@ -50,13 +66,19 @@ pub use self::{
     arc::Arc,
     circle::Circle,
     coordinates::{Uv, Xyz, T},
+    ellipse::Ellipse,
+    involute::Involute,
     line::Line,
+    obb::Obb,
     plane::Plane,
     point::Point,
     poly_chain::PolyChain,
     scalar::{Scalar, Sign},
     segment::Segment,
+    spiral::Spiral,
+    torus::Torus,
     transform::Transform,
     triangle::{Triangle, Winding},
+    unit::Unit,
     vector::Vector,
 };
@@ -0,0 +1,99 @@
+//! Conversions between the types of this crate and those of [`mint`]
+//!
+//! [`mint`] defines plain math types that many libraries in the Rust ecosystem
+//! can convert from and into. This makes it a convenient bridge to graphics and
+//! simulation libraries that don't depend on nalgebra.
+//!
+//! [`mint`]: ::mint
+
+use crate::{Point, Transform, Vector};
+
+impl From<::mint::Point2<f64>> for Point<2> {
+    fn from(point: ::mint::Point2<f64>) -> Self {
+        Self::from([point.x, point.y])
+    }
+}
+
+impl From<::mint::Point3<f64>> for Point<3> {
+    fn from(point: ::mint::Point3<f64>) -> Self {
+        Self::from([point.x, point.y, point.z])
+    }
+}
+
+impl From<Point<2>> for ::mint::Point2<f64> {
+    fn from(point: Point<2>) -> Self {
+        <[f64; 2]>::from(point).into()
+    }
+}
+
+impl From<Point<3>> for ::mint::Point3<f64> {
+    fn from(point: Point<3>) -> Self {
+        <[f64; 3]>::from(point).into()
+    }
+}
+
+impl From<Point<3>> for ::mint::Point3<f32> {
+    fn from(point: Point<3>) -> Self {
+        <[f32; 3]>::from(point).into()
+    }
+}
+
+impl From<::mint::Vector2<f64>> for Vector<2> {
+    fn from(vector: ::mint::Vector2<f64>) -> Self {
+        Self::from([vector.x, vector.y])
+    }
+}
+
+impl From<::mint::Vector3<f64>> for Vector<3> {
+    fn from(vector: ::mint::Vector3<f64>) -> Self {
+        Self::from([vector.x, vector.y, vector.z])
+    }
+}
+
+impl From<Vector<2>> for ::mint::Vector2<f64> {
+    fn from(vector: Vector<2>) -> Self {
+        <[f64; 2]>::from(vector).into()
+    }
+}
+
+impl From<Vector<3>> for ::mint::Vector3<f64> {
+    fn from(vector: Vector<3>) -> Self {
+        <[f64; 3]>::from(vector).into()
+    }
+}
+
+impl From<Vector<3>> for ::mint::Vector3<f32> {
+    fn from(vector: Vector<3>) -> Self {
+        <[f32; 3]>::from(vector).into()
+    }
+}
+
+impl From<Transform> for ::mint::ColumnMatrix4<f64> {
+    fn from(transform: Transform) -> Self {
+        let data: [f64; 16] = transform
+            .data()
+            .try_into()
+            .expect("Transform matrix has 16 elements");
+        data.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, Transform, Vector};
+
+    #[test]
+    fn round_trip() {
+        let point = Point::from([1., 2., 3.]);
+        let mint: ::mint::Point3<f64> = point.into();
+        assert_eq!(Point::from(mint), point);
+
+        let vector = Vector::from([3., 2., 1.]);
+        let mint: ::mint::Vector3<f64> = vector.into();
+        assert_eq!(Vector::from(mint), vector);
+
+        let matrix: ::mint::ColumnMatrix4<f64> =
+            Transform::translation([1., 2., 3.]).into();
+        assert_eq!(<[f64; 4]>::from(matrix.w), [1., 2., 3., 1.]);
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+
+use nalgebra::{Matrix3, SymmetricEigen};
+
+use super::{Aabb, Point, Scalar, Vector};
+
+/// The resolution at which points are considered duplicates, relative to the
+/// size of all points
+const DEDUP_RESOLUTION: f64 = 1e-9;
+
+/// An oriented bounding box (OBB)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Obb {
+    /// The center of the OBB
+    pub center: Point<3>,
+
+    /// The axes of the OBB
+    ///
+    /// The axes are normalized, orthogonal to each other, and form a
+    /// right-handed coordinate system.
+    pub axes: [Vector<3>; 3],
+
+    /// The half-extents of the OBB along each of its axes
+    pub half_extents: [Scalar; 3],
+}
+
+impl Obb {
+    /// Construct an OBB with the provided axes from a list of points
+    ///
+    /// The axes must be normalized and orthogonal to each other. The axes of
+    /// the resulting OBB are sorted by decreasing extent, and the last one is
+    /// flipped, if necessary, to keep the coordinate system right-handed.
+    ///
+    /// Returns `None`, if `points` is empty.
+    pub fn from_points_and_axes(
+        points: impl IntoIterator<Item = impl Into<Point<3>>>,
+        axes: [Vector<3>; 3],
+    ) -> Option<Self> {
+        let mut ranges: Option<[[Scalar; 2]; 3]> = None;
+
+        for point in points {
+            let coords = point.into().coords;
+            let projected = axes.map(|axis| coords.dot(&axis));
+
+            ranges = Some(match ranges {
+                Some(mut ranges) => {
+                    for ([min, max], p) in ranges.iter_mut().zip(projected) {
+                        *min = Ord::min(*min, p);
+                        *max = Scalar::max(*max, p);
+                    }
+                    ranges
+                }
+                None => projected.map(|p| [p, p]),
+            });
+        }
+
+        let ranges = ranges?;
+
+        let mut order = [0, 1, 2];
+        order.sort_by_key(|&i| {
+            let [min, max] = ranges[i];
+            -(max - min)
+        });
+
+        let mut sorted_axes = order.map(|i| axes[i]);
+        if sorted_axes[0].cross(&sorted_axes[1]).dot(&sorted_axes[2])
+            < Scalar::ZERO
+        {
+            sorted_axes[2] = -sorted_axes[2];
+        }
+
+        let center = ranges
+            .iter()
+            .zip(axes)
+            .fold(Point::origin(), |center, (&[min, max], axis)| {
+                center + axis * ((min + max) / 2.)
+            });
+        let half_extents = order.map(|i| {
+            let [min, max] = ranges[i];
+            (max - min) / 2.
+        });
+
+        Some(Self {
+            center,
+            axes: sorted_axes,
+            half_extents,
+        })
+    }
+
+    /// Construct an OBB from a list of points
+    ///
+    /// The axes of the OBB are determined by a principal component analysis
+    /// of the points. As that is only an approximation of the minimal OBB, the
+    /// OBB is compared to the AABB of the points, and the smaller of both is
+    /// returned.
+    ///
+    /// Returns `None`, if `points` is empty.
+    pub fn from_points(
+        points: impl IntoIterator<Item = impl Into<Point<3>>>,
+    ) -> Option<Self> {
+        let points = points
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<Point<3>>>();
+        if points.is_empty() {
+            return None;
+        }
+
+        // Duplicate points would skew the principal component analysis. Points
+        // that have been computed in different ways can differ by floating-
+        // point error, so they are compared on a grid that is much finer than
+        // the points' extent, instead of exactly.
+        let resolution = Aabb::<3>::from_points(points.iter().copied())
+            .size()
+            .magnitude()
+            * DEDUP_RESOLUTION;
+        let points = points
+            .into_iter()
+            .map(|point| {
+                let key = point.coords.components.map(|c| {
+                    if resolution > Scalar::ZERO {
+                        (c / resolution).round().into_f64() as i64
+                    } else {
+                        0
+                    }
+                });
+                (key, point)
+            })
+            .collect::<BTreeMap<_, _>>()
+            .into_values()
+            .collect::<Vec<_>>();
+
+        let num_points = points.len() as f64;
+        let mean = points
+            .iter()
+            .fold(nalgebra::Vector3::zeros(), |sum, point| {
+                sum + point.coords.to_na()
+            })
+            / num_points;
+        let covariance = points.iter().fold(Matrix3::zeros(), |sum, point| {
+            let d = point.coords.to_na() - mean;
+            sum + d * d.transpose()
+        }) / num_points;
+
+        let eigenvectors = SymmetricEigen::new(covariance).eigenvectors;
+        let principal_axes = [0, 1, 2].map(|i| {
+            Vector::from(eigenvectors.column(i).into_owned()).normalize()
+        });
+        let standard_axes =
+            [Vector::unit_x(), Vector::unit_y(), Vector::unit_z()];
+
+        [principal_axes, standard_axes]
+            .into_iter()
+            .filter_map(|axes| Self::from_points_and_axes(points.clone(), axes))
+            .min_by_key(|obb| obb.volume())
+    }
+
+    /// Compute the size of the OBB along each of its axes
+    pub fn size(&self) -> [Scalar; 3] {
+        self.half_extents.map(|half_extent| half_extent * 2.)
+    }
+
+    /// Compute the volume of the OBB
+    pub fn volume(&self) -> Scalar {
+        let [a, b, c] = self.size();
+        a * b * c
+    }
+
+    /// Access the vertices of the OBB
+    pub fn vertices(&self) -> [Point<3>; 8] {
+        let [a, b, c] = [0, 1, 2].map(|i| self.axes[i] * self.half_extents[i]);
+
+        [
+            self.center - a - b - c,
+            self.center + a - b - c,
+            self.center + a + b - c,
+            self.center - a + b - c,
+            self.center - a - b + c,
+            self.center + a - b + c,
+            self.center + a + b + c,
+            self.center - a + b + c,
+        ]
+    }
+
+    /// Determine whether the OBB contains a given point
+    pub fn contains(&self, point: impl Into<Point<3>>) -> bool {
+        let offset = point.into() - self.center;
+
+        self.axes
+            .iter()
+            .zip(self.half_extents)
+            .all(|(axis, half_extent)| offset.dot(axis).abs() <= half_extent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_4;
+
+    use crate::{Point, Scalar, Transform, Vector};
+
+    use super::Obb;
+
+    #[test]
+    fn from_points() {
+        // A box of size 4x2x1, rotated around the z-axis.
+        let transform = Transform::rotation(Vector::unit_z() * FRAC_PI_4);
+        let points = [
+            [-2., -1., 0.],
+            [2., -1., 0.],
+            [2., 1., 0.],
+            [-2., 1., 0.],
+            [-2., -1., 1.],
+            [2., -1., 1.],
+            [2., 1., 1.],
+            [-2., 1., 1.],
+        ]
+        .map(|point| transform.transform_point(&Point::from(point)));
+
+        let obb = Obb::from_points(points).expect("Points are not empty");
+
+        let [a, b, c] = obb.size();
+        assert!((a - Scalar::from(4.)).abs() < Scalar::from(1e-9));
+        assert!((b - Scalar::from(2.)).abs() < Scalar::from(1e-9));
+        assert!((c - Scalar::from(1.)).abs() < Scalar::from(1e-9));
+
+        for point in points {
+            assert!(obb.contains(point + (obb.center - point) * 1e-9));
+        }
+        assert!(!obb.contains([2., 0., 0.5]));
+    }
+
+    #[test]
+    fn from_points_with_near_duplicates() {
+        // A box of size 3x2x1, with one corner duplicated, as if it had been
+        // computed in a slightly different way.
+        let mut points = vec![
+            [-1.5, -1., 0.],
+            [1.5, -1., 0.],
+            [1.5, 1., 0.],
+            [-1.5, 1., 0.],
+            [-1.5, -1., 1.],
+            [1.5, -1., 1.],
+            [1.5, 1., 1.],
+            [-1.5, 1., 1.],
+        ];
+        points.push([1.5, 1. - 1e-15, 1.]);
+
+        let transform = Transform::rotation(Vector::unit_x() * FRAC_PI_4);
+        let points = points
+            .into_iter()
+            .map(|point| transform.transform_point(&Point::from(point)));
+
+        let obb = Obb::from_points(points).expect("Points are not empty");
+
+        for (size, expected) in obb.size().into_iter().zip([3., 2., 1.]) {
+            assert!((size - Scalar::from(expected)).abs() < Scalar::from(1e-9));
+        }
+    }
+
+    #[test]
+    fn from_points_empty() {
+        assert!(Obb::from_points(Vec::<Point<3>>::new()).is_none());
+    }
+}
@@ -127,6 +127,12 @@ impl<const D: usize> From<nalgebra::Point<f64, D>> for Point<D> {
     }
 }
 
+impl<const D: usize> From<Point<D>> for nalgebra::Point<f64, D> {
+    fn from(point: Point<D>) -> Self {
+        point.to_na()
+    }
+}
+
 impl<const D: usize> From<Point<D>> for [f32; D] {
     fn from(point: Point<D>) -> Self {
         point.coords.into()
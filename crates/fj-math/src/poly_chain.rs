@@ -1,4 +1,4 @@
-use crate::{Point, Segment};
+use crate::{Point, Scalar, Segment};
 
 /// A polygonal chain
 ///
@@ -38,6 +38,11 @@ impl<const D: usize> PolyChain<D> {
         Self { points }
     }
 
+    /// Access the points of the polygonal chain
+    pub fn points(&self) -> &[Point<D>] {
+        &self.points
+    }
+
     /// Access the segments of the polygonal chain
     pub fn segments(&self) -> Vec<Segment<D>> {
         let mut segments = Vec::new();
@@ -71,6 +76,58 @@ impl<const D: usize> PolyChain<D> {
         self.points.reverse();
         self
     }
+
+    /// Simplify the polygonal chain, by removing points
+    ///
+    /// Uses the Ramer-Douglas-Peucker algorithm. Points are removed, as long
+    /// as every removed point is within `tolerance` of the simplified chain.
+    /// This is meant for polylines that consist of a large number of tiny
+    /// segments, like imported profiles, or the output of procedural
+    /// generators.
+    ///
+    /// The first and last points are always kept, which means closed chains
+    /// stay closed. A closed chain might collapse to fewer than 3 distinct
+    /// points though, if it is small compared to `tolerance`.
+    #[must_use]
+    pub fn simplify(&self, tolerance: impl Into<Scalar>) -> Self {
+        let tolerance = tolerance.into();
+
+        let Some(last) = self.points.len().checked_sub(1) else {
+            return self.clone();
+        };
+
+        let mut keep = vec![false; self.points.len()];
+        keep[0] = true;
+        keep[last] = true;
+
+        // Using an explicit stack instead of recursion, as chains can be
+        // very long.
+        let mut ranges = vec![(0, last)];
+        while let Some((start, end)) = ranges.pop() {
+            let segment = [self.points[start], self.points[end]];
+
+            let farthest = (start + 1..end)
+                .map(|i| (i, distance_to_segment(self.points[i], segment)))
+                .max_by_key(|&(_, distance)| distance);
+
+            if let Some((i, distance)) = farthest {
+                if distance > tolerance {
+                    keep[i] = true;
+                    ranges.push((start, i));
+                    ranges.push((i, end));
+                }
+            }
+        }
+
+        let points = self
+            .points
+            .iter()
+            .zip(keep)
+            .filter_map(|(&point, keep)| keep.then_some(point))
+            .collect();
+
+        Self { points }
+    }
 }
 
 impl<P, Ps, const D: usize> From<Ps> for PolyChain<D>
@@ -82,3 +139,77 @@ where
         Self::from_points(points)
     }
 }
+
+fn distance_to_segment<const D: usize>(
+    point: Point<D>,
+    [a, b]: [Point<D>; 2],
+) -> Scalar {
+    let ab = b - a;
+
+    let length_squared = ab.dot(&ab);
+    if length_squared == Scalar::ZERO {
+        return point.distance_to(&a);
+    }
+
+    let t = ((point - a).dot(&ab) / length_squared)
+        .max(Scalar::ZERO)
+        .min(Scalar::ONE);
+    point.distance_to(&(a + ab * t))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Point;
+
+    use super::PolyChain;
+
+    #[test]
+    fn simplify() {
+        // A wavy line, whose waves are smaller than the tolerance, with a
+        // single large step in the middle.
+        let chain = PolyChain::from_points(
+            [
+                [0., 0.],
+                [1., 0.01],
+                [2., -0.01],
+                [3., 0.],
+                [3., 1.],
+                [4., 1.01],
+                [5., 0.99],
+                [6., 1.],
+            ]
+            .map(Point::<2>::from),
+        );
+
+        let simplified = chain.simplify(0.1);
+        assert_eq!(
+            simplified.points(),
+            [[0., 0.], [3., 0.], [3., 1.], [6., 1.]].map(Point::from)
+        );
+
+        let simplified = chain.simplify(0.001);
+        assert_eq!(simplified, chain);
+    }
+
+    #[test]
+    fn simplify_closed() {
+        let chain = PolyChain::from_points(
+            [
+                [0., 0.],
+                [1., 0.],
+                [2., 0.],
+                [2., 2.],
+                [1., 2.001],
+                [0., 2.],
+            ]
+            .map(Point::<2>::from),
+        )
+        .close();
+
+        let simplified = chain.simplify(0.01);
+        assert_eq!(
+            simplified.points(),
+            [[0., 0.], [2., 0.], [2., 2.], [0., 2.], [0., 0.]].map(Point::from)
+        );
+    }
+}
@@ -8,6 +8,8 @@ use std::{
 
 use decorum::R64;
 
+use crate::Unit;
+
 /// A rational, finite scalar value
 ///
 /// This is a wrapper around `f64`. On construction, it checks that the `f64`
@@ -55,6 +57,38 @@ impl Scalar {
         Self::from_f64(scalar as f64)
     }
 
+    /// Construct a `Scalar` from a length in the given unit
+    ///
+    /// Converts `value` from `unit` into `model_unit`, which is the unit the
+    /// model that uses the returned value is specified in.
+    pub fn from_length(value: f64, unit: Unit, model_unit: Unit) -> Self {
+        unit.convert(value, model_unit)
+    }
+
+    /// Construct a `Scalar` from a length in millimeters
+    ///
+    /// The returned value is in millimeters, the default model unit. Use
+    /// [`Scalar::from_length`] for models that use another unit.
+    pub fn from_mm(value: f64) -> Self {
+        Self::from_length(value, Unit::Millimeter, Unit::Millimeter)
+    }
+
+    /// Construct a `Scalar` from a length in centimeters
+    ///
+    /// The returned value is in millimeters, the default model unit. Use
+    /// [`Scalar::from_length`] for models that use another unit.
+    pub fn from_cm(value: f64) -> Self {
+        Self::from_length(value, Unit::Centimeter, Unit::Millimeter)
+    }
+
+    /// Construct a `Scalar` from a length in inches
+    ///
+    /// The returned value is in millimeters, the default model unit. Use
+    /// [`Scalar::from_length`] for models that use another unit.
+    pub fn from_inches(value: f64) -> Self {
+        Self::from_length(value, Unit::Inch, Unit::Millimeter)
+    }
+
     /// Convert the scalar into an `f32`
     pub fn into_f32(self) -> f32 {
         self.0 as f32
@@ -113,6 +147,14 @@ impl Scalar {
         self.0.max(other.into().0).into()
     }
 
+    /// Compute the least non-negative remainder of dividing by `rhs`
+    ///
+    /// Useful for mapping a coordinate into the range of a periodic
+    /// parameterization, for example an angle into `0.` to `PI * 2.`.
+    pub fn rem_euclid(self, rhs: impl Into<Self>) -> Self {
+        self.0.rem_euclid(rhs.into().0).into()
+    }
+
     /// Compute the largest integer smaller than or equal to this scalar
     pub fn floor(self) -> Self {
         self.0.floor().into()
@@ -128,6 +170,11 @@ impl Scalar {
         self.0.round().into()
     }
 
+    /// Compute the square root
+    pub fn sqrt(self) -> Self {
+        self.0.sqrt().into()
+    }
+
     /// Compute the cosine
     pub fn cos(self) -> Self {
         self.0.cos().into()
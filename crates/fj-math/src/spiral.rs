@@ -0,0 +1,251 @@
+use crate::{Point, Scalar, Vector};
+
+/// An n-dimensional Archimedean spiral
+///
+/// The dimensionality of the spiral is defined by the const generic `D`
+/// parameter.
+///
+/// Spiral coordinates are angles, like circle coordinates (see
+/// [`Circle`](crate::Circle)). Unlike a circle, the radius of the spiral
+/// changes linearly with the angle, which means the spiral's coordinate system
+/// is not periodic. The radius at coordinate `t` is `start + growth * t`, in
+/// units of the length of the spiral's axes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Spiral<const D: usize> {
+    center: Point<D>,
+    a: Vector<D>,
+    b: Vector<D>,
+    start: Scalar,
+    growth: Scalar,
+}
+
+impl<const D: usize> Spiral<D> {
+    /// Construct a spiral
+    ///
+    /// `a` and `b` define the plane of the spiral, and the direction of its
+    /// coordinate system, like for an ellipse. If they are perpendicular and
+    /// have the same length, the spiral is circular. Otherwise, it is the image
+    /// of a circular spiral under the linear map that is defined by them.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `a` or `b` have a length of zero.
+    pub fn new(
+        center: impl Into<Point<D>>,
+        a: impl Into<Vector<D>>,
+        b: impl Into<Vector<D>>,
+        start: impl Into<Scalar>,
+        growth: impl Into<Scalar>,
+    ) -> Self {
+        let center = center.into();
+        let a = a.into();
+        let b = b.into();
+
+        assert_ne!(a.magnitude(), Scalar::ZERO, "`a` must not be zero");
+        assert_ne!(b.magnitude(), Scalar::ZERO, "`b` must not be zero");
+
+        Self {
+            center,
+            a,
+            b,
+            start: start.into(),
+            growth: growth.into(),
+        }
+    }
+
+    /// Construct a spiral from its center, start radius, and pitch
+    ///
+    /// The pitch is the distance between neighboring turns of the spiral.
+    pub fn from_center_and_pitch(
+        center: impl Into<Point<D>>,
+        start_radius: impl Into<Scalar>,
+        pitch: impl Into<Scalar>,
+    ) -> Self {
+        let mut a = [Scalar::ZERO; D];
+        let mut b = [Scalar::ZERO; D];
+
+        a[0] = Scalar::ONE;
+        b[1] = Scalar::ONE;
+
+        Self::new(center, a, b, start_radius, pitch.into() / Scalar::TAU)
+    }
+
+    /// Access the center point of the spiral
+    pub fn center(&self) -> Point<D> {
+        self.center
+    }
+
+    /// Access the vector that defines the direction of the zero coordinate
+    pub fn a(&self) -> Vector<D> {
+        self.a
+    }
+
+    /// Access the vector that defines the plane of the spiral
+    ///
+    /// Also defines the direction of the spiral's coordinate system.
+    pub fn b(&self) -> Vector<D> {
+        self.b
+    }
+
+    /// Access the radius of the spiral at the zero coordinate
+    pub fn start(&self) -> Scalar {
+        self.start
+    }
+
+    /// Access how much the radius of the spiral grows per radian
+    pub fn growth(&self) -> Scalar {
+        self.growth
+    }
+
+    /// Create a new instance that is reversed
+    ///
+    /// Like for a circle, the coordinate `t` of the reversed spiral is the
+    /// coordinate `-t` of the original one.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self.growth = -self.growth;
+        self
+    }
+
+    /// Convert a `D`-dimensional point into spiral coordinates
+    ///
+    /// The point is expected to be on the spiral. Its angle determines the
+    /// spiral coordinate up to a multiple of full turns, its distance from the
+    /// center determines the turn it is on.
+    ///
+    /// Like [`Ellipse::point_to_ellipse_coords`], this expects `a` and `b` to
+    /// be perpendicular.
+    ///
+    /// [`Ellipse::point_to_ellipse_coords`]: crate::Ellipse::point_to_ellipse_coords
+    pub fn point_to_spiral_coords(
+        &self,
+        point: impl Into<Point<D>>,
+    ) -> Point<1> {
+        let vector = point.into() - self.center;
+
+        let u = vector.dot(&self.a) / self.a.dot(&self.a);
+        let v = vector.dot(&self.b) / self.b.dot(&self.b);
+
+        let angle = Scalar::atan2(v, u);
+        if self.growth == Scalar::ZERO {
+            return Point::from([angle]);
+        }
+
+        let radius = (u * u + v * v).sqrt();
+        let t = (radius - self.start) / self.growth;
+        let turns = ((t - angle) / Scalar::TAU).round();
+
+        Point::from([angle + turns * Scalar::TAU])
+    }
+
+    /// Convert a point in spiral coordinates into a `D`-dimensional point
+    pub fn point_from_spiral_coords(
+        &self,
+        point: impl Into<Point<1>>,
+    ) -> Point<D> {
+        self.center + self.vector_from_spiral_coords(point.into().coords)
+    }
+
+    /// Convert a vector in spiral coordinates into a `D`-dimensional vector
+    ///
+    /// The result points from the center of the spiral to the point at the
+    /// provided coordinate.
+    pub fn vector_from_spiral_coords(
+        &self,
+        vector: impl Into<Vector<1>>,
+    ) -> Vector<D> {
+        let t = vector.into().t;
+        let (sin, cos) = t.sin_cos();
+
+        (self.a * cos + self.b * sin) * (self.start + self.growth * t)
+    }
+
+    /// Compute the derivative of the spiral at the provided coordinate
+    pub fn derivative(&self, point: impl Into<Point<1>>) -> Vector<D> {
+        let t = point.into().t;
+        let (sin, cos) = t.sin_cos();
+
+        let radius = self.start + self.growth * t;
+
+        (self.b * cos - self.a * sin) * radius
+            + (self.a * cos + self.b * sin) * self.growth
+    }
+
+    /// Compute an upper bound for the length of the second derivative
+    ///
+    /// Returns a value that is at least the length of the second derivative at
+    /// any coordinate `t` with `|t| <= max`. This is used to determine how
+    /// densely the spiral needs to be approximated.
+    pub fn second_derivative_bound(&self, max: Scalar) -> Scalar {
+        let axes = self.a.magnitude() + self.b.magnitude();
+        let radius = self.start.abs() + self.growth.abs() * max.abs();
+
+        axes * (radius + self.growth.abs() * 2.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar, Vector};
+
+    use super::Spiral;
+
+    #[test]
+    fn point_from_spiral_coords() {
+        let spiral = Spiral::from_center_and_pitch([1., 1.], 1., 2.);
+
+        assert_eq!(
+            spiral.point_from_spiral_coords([0.]),
+            Point::from([2., 1.])
+        );
+        assert_abs_diff_eq!(
+            spiral.point_from_spiral_coords([TAU]),
+            Point::from([4., 1.]),
+            epsilon = Scalar::from(1e-12)
+        );
+        assert_abs_diff_eq!(
+            spiral.point_from_spiral_coords([FRAC_PI_2]),
+            Point::from([1., 2.5]),
+            epsilon = Scalar::from(1e-12)
+        );
+    }
+
+    #[test]
+    fn point_to_spiral_coords() {
+        let spiral = Spiral::from_center_and_pitch([1., 1.], 1., 2.);
+
+        for t in [0., 1., PI, 5., 10.] {
+            let point = spiral.point_from_spiral_coords([t]);
+            assert_abs_diff_eq!(
+                spiral.point_to_spiral_coords(point),
+                Point::from([t]),
+                epsilon = Scalar::from(1e-12)
+            );
+        }
+    }
+
+    #[test]
+    fn reverse() {
+        let spiral = Spiral::new([0., 0.], [1., 0.], [0., 1.], 1., 0.5);
+        let reversed = spiral.reverse();
+
+        for t in [0., 1., PI, -2.] {
+            assert_abs_diff_eq!(
+                reversed.point_from_spiral_coords([t]),
+                spiral.point_from_spiral_coords([-t]),
+                epsilon = Scalar::from(1e-12)
+            );
+        }
+
+        assert_abs_diff_eq!(
+            spiral.derivative([0.]),
+            Vector::from([0.5, 1.]),
+            epsilon = Scalar::from(1e-12)
+        );
+    }
+}
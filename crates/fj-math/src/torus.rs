@@ -0,0 +1,255 @@
+use crate::{Aabb, Circle, Point, Scalar, Vector};
+
+/// A torus
+///
+/// The torus is defined by its major circle, which runs through the center of
+/// the tube, and the minor radius, which is the radius of the tube.
+///
+/// Torus coordinates are 2-dimensional. The u-coordinate is the angle around
+/// the major circle, using the coordinate system of that circle. The
+/// v-coordinate is the angle around the tube, starting at the outside of the
+/// torus and moving into the direction of the torus' axis (as defined by
+/// [`Torus::axis`]) first.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Torus {
+    major: Circle<3>,
+    minor_radius: Scalar,
+}
+
+impl Torus {
+    /// Construct a torus from its major circle and minor radius
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `minor_radius` is not positive.
+    pub fn new(major: Circle<3>, minor_radius: impl Into<Scalar>) -> Self {
+        let minor_radius = minor_radius.into();
+
+        assert!(
+            minor_radius > Scalar::ZERO,
+            "torus minor radius must be positive"
+        );
+
+        Self {
+            major,
+            minor_radius,
+        }
+    }
+
+    /// Construct a torus from a center point, axis, and its two radii
+    ///
+    /// The orientation of the major circle's coordinate system around the axis
+    /// is chosen arbitrarily.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `axis` has a length of zero, or if any of the radii is not
+    /// positive.
+    pub fn from_center_axis_and_radii(
+        center: impl Into<Point<3>>,
+        axis: impl Into<Vector<3>>,
+        major_radius: impl Into<Scalar>,
+        minor_radius: impl Into<Scalar>,
+    ) -> Self {
+        let axis = axis.into();
+        let major_radius = major_radius.into();
+
+        assert_ne!(
+            axis.magnitude(),
+            Scalar::ZERO,
+            "torus axis must not be zero"
+        );
+        let axis = axis.normalize();
+
+        // Pick whichever coordinate axis is least aligned with the torus axis,
+        // to make sure the cross product below is well-conditioned.
+        let reference = if axis.x.abs() < Scalar::from(0.9) {
+            Vector::unit_x()
+        } else {
+            Vector::unit_y()
+        };
+
+        let a = axis.cross(&reference).normalize();
+        let b = axis.cross(&a).normalize();
+
+        let major = Circle::new(center, a * major_radius, b * major_radius);
+
+        Self::new(major, minor_radius)
+    }
+
+    /// Access the major circle of the torus
+    ///
+    /// This is the circle that runs through the center of the tube.
+    pub fn major(&self) -> Circle<3> {
+        self.major
+    }
+
+    /// Access the center point of the torus
+    pub fn center(&self) -> Point<3> {
+        self.major.center()
+    }
+
+    /// Access the axis of the torus
+    ///
+    /// The returned vector is normalized. Its direction is defined by the
+    /// coordinate system of the major circle, according to the right-hand
+    /// rule.
+    pub fn axis(&self) -> Vector<3> {
+        self.major.a().cross(&self.major.b()).normalize()
+    }
+
+    /// Access the major radius of the torus
+    pub fn major_radius(&self) -> Scalar {
+        self.major.radius()
+    }
+
+    /// Access the minor radius of the torus
+    pub fn minor_radius(&self) -> Scalar {
+        self.minor_radius
+    }
+
+    /// Convert a point in torus coordinates into a 3-dimensional point
+    pub fn point_from_torus_coords(
+        &self,
+        point: impl Into<Point<2>>,
+    ) -> Point<3> {
+        let point = point.into();
+
+        // Both coordinates are periodic. Wrapping them into the first period
+        // makes sure that points on the seams are computed exactly the same,
+        // regardless of the side of the seam they come from.
+        let [u, v] =
+            [point.u, point.v].map(|coord| coord.rem_euclid(Scalar::TAU));
+
+        self.major.point_from_circle_coords([u]) + self.tube_vector(u, v)
+    }
+
+    /// Compute the torus normal at the provided point in torus coordinates
+    ///
+    /// The normal points away from the tube center. It is normalized.
+    pub fn normal_at(&self, point: impl Into<Point<2>>) -> Vector<3> {
+        let point = point.into();
+        self.tube_vector(point.u, point.v).normalize()
+    }
+
+    /// Calculate an AABB for the torus
+    pub fn aabb(&self) -> Aabb<3> {
+        let extent =
+            Vector::from_component(self.major_radius() + self.minor_radius());
+
+        Aabb {
+            min: self.center() - extent,
+            max: self.center() + extent,
+        }
+    }
+
+    /// Create a new instance with a reversed major circle
+    ///
+    /// This flips the direction of the u-coordinate, and as a result, also the
+    /// direction of the torus axis.
+    #[must_use]
+    pub fn reverse(self) -> Self {
+        Self {
+            major: self.major.reverse(),
+            minor_radius: self.minor_radius,
+        }
+    }
+
+    fn tube_vector(&self, u: Scalar, v: Scalar) -> Vector<3> {
+        let radial = self.major.vector_from_circle_coords([u]).normalize();
+        let (sin, cos) = v.sin_cos();
+
+        (radial * cos + self.axis() * sin) * self.minor_radius
+    }
+}
+
+impl approx::AbsDiffEq for Torus {
+    type Epsilon = <Scalar as approx::AbsDiffEq>::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        Scalar::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.major.abs_diff_eq(&other.major, epsilon)
+            && self.minor_radius.abs_diff_eq(&other.minor_radius, epsilon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Circle, Point, Scalar, Vector};
+
+    use super::Torus;
+
+    #[test]
+    fn point_from_torus_coords() {
+        let torus =
+            Torus::new(Circle::from_center_and_radius([0., 0., 0.], 2.), 1.);
+
+        assert_abs_diff_eq!(
+            torus.point_from_torus_coords([0., 0.]),
+            Point::from([3., 0., 0.]),
+            epsilon = Scalar::from(1e-12)
+        );
+        assert_abs_diff_eq!(
+            torus.point_from_torus_coords([FRAC_PI_2, 0.]),
+            Point::from([0., 3., 0.]),
+            epsilon = Scalar::from(1e-12)
+        );
+        assert_abs_diff_eq!(
+            torus.point_from_torus_coords([0., FRAC_PI_2]),
+            Point::from([2., 0., 1.]),
+            epsilon = Scalar::from(1e-12)
+        );
+        assert_abs_diff_eq!(
+            torus.point_from_torus_coords([0., PI]),
+            Point::from([1., 0., 0.]),
+            epsilon = Scalar::from(1e-12)
+        );
+    }
+
+    #[test]
+    fn normal_at() {
+        let torus =
+            Torus::new(Circle::from_center_and_radius([0., 0., 0.], 2.), 1.);
+
+        assert_abs_diff_eq!(
+            torus.normal_at([0., 0.]),
+            Vector::from([1., 0., 0.]),
+            epsilon = Scalar::from(1e-12)
+        );
+        assert_abs_diff_eq!(
+            torus.normal_at([0., FRAC_PI_2]),
+            Vector::from([0., 0., 1.]),
+            epsilon = Scalar::from(1e-12)
+        );
+    }
+
+    #[test]
+    fn from_center_axis_and_radii() {
+        let torus = Torus::from_center_axis_and_radii(
+            [1., 2., 3.],
+            [0., 0., 2.],
+            2.,
+            0.5,
+        );
+
+        assert_eq!(torus.center(), Point::from([1., 2., 3.]));
+        assert_abs_diff_eq!(
+            torus.axis(),
+            Vector::from([0., 0., 1.]),
+            epsilon = Scalar::from(1e-12)
+        );
+        assert_abs_diff_eq!(
+            torus.major_radius(),
+            Scalar::from(2.),
+            epsilon = Scalar::from(1e-12)
+        );
+        assert_eq!(torus.minor_radius(), Scalar::from(0.5));
+    }
+}
@@ -2,7 +2,7 @@ use std::ops;
 
 use nalgebra::Perspective3;
 
-use crate::{Circle, Line, Scalar};
+use crate::{Circle, Ellipse, Involute, Line, Plane, Scalar, Spiral, Torus};
 
 use super::{Aabb, Point, Segment, Triangle, Vector};
 
@@ -40,6 +40,23 @@ impl Transform {
         ))
     }
 
+    /// Construct a reflection across a plane
+    pub fn reflection(plane: &Plane) -> Self {
+        let normal = plane.normal().to_na();
+        let distance = normal.dot(&plane.origin().coords.to_na());
+
+        let linear =
+            nalgebra::Matrix3::identity() - normal * normal.transpose() * 2.;
+        let translation = normal * distance * 2.;
+
+        let mut matrix = linear.to_homogeneous();
+        for i in 0..3 {
+            matrix[(i, 3)] = translation[i];
+        }
+
+        Self(nalgebra::Transform::from_matrix_unchecked(matrix))
+    }
+
     /// Construct a scaling
     pub fn scale(scaling_factor: f64) -> Self {
         Self(nalgebra::Transform::from_matrix_unchecked(
@@ -52,6 +69,20 @@ impl Transform {
         Point::from(self.0.transform_point(&point.to_na()))
     }
 
+    /// Transform a batch of points
+    ///
+    /// Produces the same result as calling [`Transform::transform_point`] for
+    /// each point, within floating-point tolerance, but is faster for large
+    /// numbers of points. The operations are done in a different order, so the
+    /// results can differ in the last bits.
+    pub fn transform_points(&self, points: &[Point<3>]) -> Vec<Point<3>> {
+        let matrix = self
+            .data()
+            .try_into()
+            .expect("Transform matrix has 16 elements");
+        crate::batch::transform_points(matrix, points)
+    }
+
     /// Inverse transform given point
     pub fn inverse_transform_point(&self, point: &Point<3>) -> Point<3> {
         Point::from(self.0.inverse_transform_point(&point.to_na()))
@@ -62,6 +93,24 @@ impl Transform {
         Vector::from(self.0.transform_vector(&vector.to_na()))
     }
 
+    /// Transform the given normal
+    ///
+    /// Normals are transformed by the inverse transpose of the transform, to
+    /// keep them perpendicular to their surface under non-uniform scaling, for
+    /// example. The result is normalized.
+    pub fn transform_normal(&self, normal: &Vector<3>) -> Vector<3> {
+        let linear = self.0.matrix().fixed_view::<3, 3>(0, 0).into_owned();
+        let normal = match linear.try_inverse() {
+            Some(inverse) => inverse.transpose() * normal.to_na(),
+            // The transform collapses space into a lower dimension, so there
+            // is no meaningful normal. Transforming it like any other vector
+            // is as good as anything.
+            None => linear * normal.to_na(),
+        };
+
+        Vector::from(normal).normalize()
+    }
+
     /// Transform the given line
     pub fn transform_line(&self, line: &Line<3>) -> Line<3> {
         Line::from_origin_and_direction(
@@ -95,6 +144,46 @@ impl Transform {
         )
     }
 
+    /// Transform the given ellipse
+    pub fn transform_ellipse(&self, ellipse: &Ellipse<3>) -> Ellipse<3> {
+        Ellipse::new(
+            self.transform_point(&ellipse.center()),
+            self.transform_vector(&ellipse.a()),
+            self.transform_vector(&ellipse.b()),
+        )
+    }
+
+    /// Transform the given spiral
+    pub fn transform_spiral(&self, spiral: &Spiral<3>) -> Spiral<3> {
+        Spiral::new(
+            self.transform_point(&spiral.center()),
+            self.transform_vector(&spiral.a()),
+            self.transform_vector(&spiral.b()),
+            spiral.start(),
+            spiral.growth(),
+        )
+    }
+
+    /// Transform the given involute
+    pub fn transform_involute(&self, involute: &Involute<3>) -> Involute<3> {
+        Involute::new(
+            self.transform_point(&involute.center()),
+            self.transform_vector(&involute.a()),
+            self.transform_vector(&involute.b()),
+        )
+    }
+
+    /// Transform the given torus
+    ///
+    /// The minor radius is scaled by the same factor as the major radius. This
+    /// is only correct for transforms that scale uniformly.
+    pub fn transform_torus(&self, torus: &Torus) -> Torus {
+        let major = self.transform_circle(&torus.major());
+        let scale = major.radius() / torus.major_radius();
+
+        Torus::new(major, torus.minor_radius() * scale)
+    }
+
     /// Inverse transform
     pub fn inverse(&self) -> Self {
         Self(self.0.inverse())
@@ -157,6 +246,18 @@ impl Transform {
     }
 }
 
+impl From<nalgebra::Transform<f64, nalgebra::TAffine, 3>> for Transform {
+    fn from(transform: nalgebra::Transform<f64, nalgebra::TAffine, 3>) -> Self {
+        Self(transform)
+    }
+}
+
+impl From<Transform> for nalgebra::Transform<f64, nalgebra::TAffine, 3> {
+    fn from(transform: Transform) -> Self {
+        transform.0
+    }
+}
+
 impl ops::Mul<Self> for Transform {
     type Output = Self;
 
@@ -169,7 +270,7 @@ impl ops::Mul<Self> for Transform {
 mod tests {
     use approx::assert_abs_diff_eq;
 
-    use crate::{Line, Point, Scalar, Vector};
+    use crate::{Line, Plane, Point, Scalar, Vector};
 
     use super::Transform;
 
@@ -194,6 +295,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn transform_normal() {
+        // A non-uniform scaling, stretching along the x-axis.
+        let transform =
+            Transform::from(nalgebra::Transform::from_matrix_unchecked(
+                nalgebra::Matrix4::new_nonuniform_scaling(
+                    &nalgebra::Vector3::new(2., 1., 1.),
+                ),
+            ));
+
+        let tangent = Vector::from([1., -1., 0.]);
+        let normal = Vector::from([1., 1., 0.]);
+
+        let tangent = transform.transform_vector(&tangent);
+        let normal = transform.transform_normal(&normal);
+
+        assert_abs_diff_eq!(
+            tangent.dot(&normal),
+            Scalar::ZERO,
+            epsilon = Scalar::from(1e-12),
+        );
+        assert_abs_diff_eq!(
+            normal.magnitude(),
+            Scalar::ONE,
+            epsilon = Scalar::from(1e-12),
+        );
+    }
+
     #[test]
     fn extract_rotation_translation() {
         let rotation =
@@ -224,4 +353,22 @@ mod tests {
             epsilon = 1e-8,
         );
     }
+
+    #[test]
+    fn reflection() {
+        let plane =
+            Plane::from_parametric([0., 0., 1.], [1., 0., 0.], [0., 1., 0.]);
+        let transform = Transform::reflection(&plane);
+
+        assert_abs_diff_eq!(
+            transform.transform_point(&Point::from([1., 2., 3.])),
+            Point::from([1., 2., -1.]),
+            epsilon = Scalar::from(1e-8)
+        );
+        assert_abs_diff_eq!(
+            transform.transform_vector(&Vector::from([1., 2., 3.])),
+            Vector::from([1., 2., -3.]),
+            epsilon = Scalar::from(1e-8)
+        );
+    }
 }
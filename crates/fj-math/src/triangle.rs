@@ -1,4 +1,4 @@
-use parry3d_f64::query::{Ray, RayCast as _};
+use parry3d_f64::query::{PointQuery as _, Ray, RayCast as _};
 
 use crate::Vector;
 
@@ -102,6 +102,14 @@ impl Triangle<3> {
             .map(Into::into)
     }
 
+    /// Compute the point on the triangle that is closest to the provided point
+    pub fn closest_point(&self, point: impl Into<Point<3>>) -> Point<3> {
+        self.to_parry()
+            .project_local_point(&point.into().to_na(), true)
+            .point
+            .into()
+    }
+
     /// Compute the triangle's normal
     pub fn normal(&self) -> Vector<3> {
         self.to_parry()
@@ -151,7 +159,7 @@ impl Winding {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Point, Vector};
+    use crate::{Point, Scalar, Vector};
 
     use super::Triangle;
 
@@ -195,4 +203,20 @@ mod tests {
             Triangle::from([[0.0, 0.0, 0.0], [2.0, 1.0, 0.0], [2.0, 0.0, 0.0]]);
         assert_eq!(triangle.normal(), Vector::from([0.0, 0.0, -1.0]));
     }
+
+    #[test]
+    fn closest_point() {
+        let triangle =
+            Triangle::from([[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+
+        let assert_closest_point = |point: [f64; 3], expected: [f64; 3]| {
+            let closest = triangle.closest_point(point);
+            let distance = closest.distance_to(&Point::from(expected));
+            assert!(distance < Scalar::from(1e-12));
+        };
+
+        assert_closest_point([0.25, 0.25, 1.0], [0.25, 0.25, 0.0]); // face
+        assert_closest_point([0.5, -1.0, 0.0], [0.5, 0.0, 0.0]); // edge
+        assert_closest_point([-1.0, -1.0, 0.0], [0.0, 0.0, 0.0]); // vertex
+    }
 }
@@ -0,0 +1,102 @@
+use std::fmt;
+
+use crate::Scalar;
+
+/// A unit of length
+///
+/// Fornjot's geometry is unitless, which means the unit that a model's values
+/// are specified in has to be tracked separately. Models can declare the unit
+/// they use, which allows exporters and the viewer to convert or annotate
+/// their output accordingly.
+///
+/// The default unit is [`Unit::Millimeter`], which is what most file formats
+/// (and most 3D printing software) assume, when no unit is specified.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Unit {
+    /// Millimeters
+    #[default]
+    Millimeter,
+
+    /// Centimeters
+    Centimeter,
+
+    /// Meters
+    Meter,
+
+    /// Inches
+    Inch,
+}
+
+impl Unit {
+    /// Return how many millimeters one of this unit is
+    pub fn in_millimeters(self) -> Scalar {
+        let millimeters = match self {
+            Self::Millimeter => 1.,
+            Self::Centimeter => 10.,
+            Self::Meter => 1000.,
+            Self::Inch => 25.4,
+        };
+
+        Scalar::from_f64(millimeters)
+    }
+
+    /// Return the factor that converts values in this unit into `target`
+    pub fn conversion_factor(self, target: Unit) -> Scalar {
+        if self == target {
+            return Scalar::ONE;
+        }
+
+        self.in_millimeters() / target.in_millimeters()
+    }
+
+    /// Convert a value from this unit into `target`
+    pub fn convert(self, value: impl Into<Scalar>, target: Unit) -> Scalar {
+        value.into() * self.conversion_factor(target)
+    }
+
+    /// Return the symbol of the unit
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Millimeter => "mm",
+            Self::Centimeter => "cm",
+            Self::Meter => "m",
+            Self::Inch => "in",
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Scalar;
+
+    use super::Unit;
+
+    #[test]
+    fn convert() {
+        assert_eq!(
+            Unit::Inch.convert(1., Unit::Millimeter),
+            Scalar::from(25.4)
+        );
+        assert_eq!(
+            Unit::Millimeter.convert(20., Unit::Centimeter),
+            Scalar::from(2.)
+        );
+        assert_eq!(Unit::Meter.convert(3., Unit::Meter), Scalar::from(3.));
+    }
+
+    #[test]
+    fn scalar_helpers() {
+        assert_eq!(Scalar::from_inches(2.), Scalar::from(50.8));
+        assert_eq!(Scalar::from_cm(1.5), Scalar::from(15.));
+        assert_eq!(
+            Scalar::from_length(1., Unit::Meter, Unit::Centimeter),
+            Scalar::from(100.)
+        );
+    }
+}
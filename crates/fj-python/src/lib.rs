@@ -0,0 +1,337 @@
+//! # Fornjot Python Bindings
+//!
+//! [Fornjot] is an early-stage b-rep CAD kernel written in Rust. The kernel is
+//! split into multiple libraries that can be used semi-independently, and this
+//! is one of those.
+//!
+//! This library provides Python bindings for the kernel. It allows sketching,
+//! sweeping, and transforming shapes, and exporting them to 3MF, STL, or OBJ
+//! files, from Python scripts.
+//!
+//! Build it and install it into the current Python environment using
+//! [maturin], by running `maturin develop --release` in this directory. Then
+//! use it like this:
+//!
+//! ``` python
+//! import fornjot
+//!
+//! kernel = fornjot.Kernel()
+//!
+//! sketch = (
+//!     fornjot.Sketch(0.0, 0.0)
+//!     .line_to(2.0, 0.0)
+//!     .line_to(2.0, 1.0)
+//!     .line_to(0.0, 1.0)
+//!     .close()
+//!     .circle(1.0, 0.5, 0.25)
+//! )
+//! solid = kernel.sweep(sketch, [0.0, 0.0, 0.5])
+//! solid = kernel.translate(solid, [1.0, 0.0, 0.0])
+//!
+//! kernel.export(solid, "plate.3mf")
+//! ```
+//!
+//! [Fornjot]: https://www.fornjot.app/
+//! [maturin]: https://www.maturin.rs/
+
+use std::path::{Path, PathBuf};
+
+use fj_core::{
+    algorithms::bounding_volume::BoundingVolume,
+    bindings::{self, BindingsError},
+    objects,
+    storage::Handle,
+};
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+};
+
+/// The Python module
+#[pymodule]
+fn fornjot(_py: Python<'_>, module: &PyModule) -> PyResult<()> {
+    module.add_class::<Kernel>()?;
+    module.add_class::<Sketch>()?;
+    module.add_class::<Solid>()?;
+    Ok(())
+}
+
+/// The kernel, which creates shapes and operates on them
+///
+/// All shapes are validated as they are created. Validation errors are
+/// reported, when a shape is exported, or when calling `validate` explicitly.
+#[pyclass(unsendable)]
+#[derive(Default)]
+pub struct Kernel {
+    inner: bindings::Kernel,
+}
+
+#[pymethods]
+impl Kernel {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sweep a sketch in the xy-plane along the provided path
+    fn sweep(&mut self, sketch: &Sketch, path: [f64; 3]) -> PyResult<Solid> {
+        let handle =
+            self.inner.sweep(&sketch.inner, path).map_err(Error::from)?;
+        Ok(Solid { handle })
+    }
+
+    /// Translate a solid by the provided offset
+    fn translate(
+        &mut self,
+        solid: &Solid,
+        offset: [f64; 3],
+    ) -> PyResult<Solid> {
+        let handle = self
+            .inner
+            .translate(&solid.handle, offset)
+            .map_err(Error::from)?;
+        Ok(Solid { handle })
+    }
+
+    /// Rotate a solid around the origin
+    ///
+    /// The direction of `axis_angle` defines the rotation axis, its length the
+    /// angle of the rotation, in radians.
+    fn rotate(
+        &mut self,
+        solid: &Solid,
+        axis_angle: [f64; 3],
+    ) -> PyResult<Solid> {
+        let handle = self
+            .inner
+            .rotate(&solid.handle, axis_angle)
+            .map_err(Error::from)?;
+        Ok(Solid { handle })
+    }
+
+    /// Raise an error, if any shape that was created so far is invalid
+    fn validate(&mut self) -> PyResult<()> {
+        self.inner
+            .validate()
+            .map_err(|err| Error::from(BindingsError::from(err)).into())
+    }
+
+    /// Export a solid to a file
+    ///
+    /// The file format is determined by the extension of `path`. 3MF, STL, and
+    /// OBJ files are supported.
+    ///
+    /// If no `tolerance` is provided, a default is derived from the size of
+    /// the solid.
+    #[pyo3(signature = (solid, path, tolerance = None))]
+    fn export(
+        &mut self,
+        solid: &Solid,
+        path: PathBuf,
+        tolerance: Option<f64>,
+    ) -> PyResult<()> {
+        export(&mut self.inner, &solid.handle, &path, tolerance)?;
+        Ok(())
+    }
+}
+
+/// A sketch, defined by tracing its outline
+///
+/// Start a sketch by providing the coordinates of its first point. Every
+/// method returns a new sketch, which makes it possible to chain calls.
+#[pyclass]
+#[derive(Clone)]
+pub struct Sketch {
+    inner: bindings::Sketch,
+}
+
+#[pymethods]
+impl Sketch {
+    #[new]
+    fn new(x: f64, y: f64) -> PyResult<Self> {
+        let inner = bindings::Sketch::new([x, y]).map_err(Error::from)?;
+        Ok(Self { inner })
+    }
+
+    /// Start a new cycle at the provided point
+    fn move_to(&self, x: f64, y: f64) -> PyResult<Self> {
+        self.with(|sketch| sketch.move_to([x, y]))
+    }
+
+    /// Add a line from the current point to the provided one
+    fn line_to(&self, x: f64, y: f64) -> PyResult<Self> {
+        self.with(|sketch| sketch.line_to([x, y]))
+    }
+
+    /// Add an arc from the current point to the provided one
+    ///
+    /// The angle is in radians. Positive angles result in counter-clockwise
+    /// arcs, negative angles in clockwise ones.
+    fn arc_to(&self, x: f64, y: f64, angle: f64) -> PyResult<Self> {
+        self.with(|sketch| sketch.arc_to([x, y], angle))
+    }
+
+    /// Close the current cycle
+    fn close(&self) -> PyResult<Self> {
+        self.with(bindings::Sketch::close)
+    }
+
+    /// Add a circle as a separate cycle
+    fn circle(&self, x: f64, y: f64, radius: f64) -> PyResult<Self> {
+        self.with(|sketch| sketch.circle([x, y], radius))
+    }
+}
+
+impl Sketch {
+    fn with(
+        &self,
+        f: impl FnOnce(&bindings::Sketch) -> Result<bindings::Sketch, BindingsError>,
+    ) -> PyResult<Self> {
+        let inner = f(&self.inner).map_err(Error::from)?;
+        Ok(Self { inner })
+    }
+}
+
+/// A solid, created by a [`Kernel`]
+#[pyclass]
+#[derive(Clone)]
+pub struct Solid {
+    handle: Handle<objects::Solid>,
+}
+
+#[pymethods]
+impl Solid {
+    /// Compute the axis-aligned bounding box of the solid
+    ///
+    /// Returns the minimum and maximum corners of the bounding box, or `None`,
+    /// if the solid is empty.
+    fn bounding_box(&self) -> Option<([f64; 3], [f64; 3])> {
+        let aabb = self.handle.aabb()?;
+        Some((aabb.min.into(), aabb.max.into()))
+    }
+}
+
+fn export(
+    kernel: &mut bindings::Kernel,
+    solid: &Handle<objects::Solid>,
+    path: &Path,
+    tolerance: Option<f64>,
+) -> Result<(), Error> {
+    let mesh = kernel.triangulate(solid, tolerance)?;
+    fj_export::export(&mesh, path)?;
+    Ok(())
+}
+
+/// Error that is raised as a Python exception
+///
+/// Invalid input raises a `ValueError`, failing to write a file raises a
+/// `RuntimeError`.
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error(transparent)]
+    Bindings(#[from] BindingsError),
+
+    #[error(transparent)]
+    Export(#[from] fj_export::Error),
+}
+
+impl From<Error> for PyErr {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Bindings(_) => PyValueError::new_err(err.to_string()),
+            Error::Export(_) => PyRuntimeError::new_err(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::{export, Error, Kernel, Sketch};
+
+    fn rectangle() -> Sketch {
+        Sketch::new(0., 0.)
+            .and_then(|sketch| sketch.line_to(2., 0.))
+            .and_then(|sketch| sketch.line_to(2., 1.))
+            .and_then(|sketch| sketch.line_to(0., 1.))
+            .expect("Rectangle is valid")
+    }
+
+    #[test]
+    fn build_sketch() -> anyhow::Result<()> {
+        let mut kernel = Kernel::new();
+
+        let sketch = rectangle().close()?.circle(1., 0.5, 0.25)?;
+        let solid = kernel.sweep(&sketch, [0., 0., 0.5])?;
+
+        assert_eq!(solid.bounding_box(), Some(([0., 0., 0.], [2., 1., 0.5])),);
+        kernel.validate()?;
+
+        // Unclosed cycles are reported as errors instead of panicking.
+        assert!(rectangle().move_to(3., 3.).is_err());
+        assert!(rectangle().circle(3., 3., 1.).is_err());
+        assert!(kernel.sweep(&rectangle(), [0., 0., 1.]).is_err());
+        assert!(rectangle().close()?.close().is_err());
+        assert!(rectangle().arc_to(0., 0., 7.).is_err());
+        assert!(rectangle().line_to(f64::NAN, 0.).is_err());
+        assert!(kernel.translate(&solid, [f64::INFINITY, 0., 0.]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn export_paths() -> anyhow::Result<()> {
+        let mut kernel = Kernel::new();
+        let solid = kernel.sweep(&rectangle().close()?, [0., 0., 0.5])?;
+
+        let dir =
+            env::temp_dir().join(format!("fj-python-export-{}", process::id()));
+        fs::create_dir_all(&dir)?;
+
+        for file in ["model.3mf", "model.stl", "model.obj", "MODEL.STL"] {
+            let path = dir.join(file);
+            export(&mut kernel.inner, &solid.handle, &path, None)?;
+            assert!(fs::metadata(&path)?.len() > 0, "{file} is empty");
+        }
+
+        for file in ["model", "model.step"] {
+            let result =
+                export(&mut kernel.inner, &solid.handle, &dir.join(file), None);
+            assert!(
+                matches!(result, Err(Error::Export(_))),
+                "{file}: {result:?}"
+            );
+        }
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn error_mapping() -> anyhow::Result<()> {
+        let mut kernel = Kernel::new();
+        let solid = kernel.sweep(&rectangle().close()?, [0., 0., 0.5])?;
+
+        // Invalid input is a `ValueError`, failing to write the file a
+        // `RuntimeError`.
+        let invalid_tolerance = export(
+            &mut kernel.inner,
+            &solid.handle,
+            &env::temp_dir().join("model.3mf"),
+            Some(-1.),
+        );
+        assert!(matches!(invalid_tolerance, Err(Error::Bindings(_))));
+
+        let invalid_path = export(
+            &mut kernel.inner,
+            &solid.handle,
+            &env::temp_dir().join("does-not-exist").join("model.stl"),
+            None,
+        );
+        assert!(matches!(invalid_path, Err(Error::Export(_))));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+//! Analysis overlays that replace the colors of the model
+
+use fj_interop::mesh::Color;
+use fj_math::{Scalar, Vector};
+
+/// Draft analysis, coloring the model by the angle to a pull direction
+///
+/// Parts made in a mold need to be pulled out of it after molding. Surfaces
+/// that are parallel to the pull direction, or even face away from it, make
+/// that difficult or impossible. Draft analysis shows these surfaces:
+///
+/// - Green, where the draft angle is at least [`DraftAnalysis::min_draft`].
+/// - Yellow, where the draft angle is too small in either direction.
+/// - Red, where the surface faces away from the pull direction (undercut).
+#[derive(Clone, Copy, Debug)]
+pub struct DraftAnalysis {
+    /// The direction in which the part is pulled out of the mold
+    pub pull_direction: Vector<3>,
+
+    /// The minimum draft angle that is acceptable, in radians
+    pub min_draft: Scalar,
+}
+
+impl DraftAnalysis {
+    /// Compute the draft angle of a surface with the provided normal
+    ///
+    /// The draft angle is zero for surfaces that are parallel to the pull
+    /// direction, positive for surfaces that face towards it, and negative for
+    /// surfaces that face away from it.
+    pub fn draft_angle(&self, normal: Vector<3>) -> Scalar {
+        let normal = normal.normalize();
+        let pull_direction = self.pull_direction.normalize();
+
+        let sin = normal.dot(&pull_direction).into_f64().clamp(-1., 1.);
+        Scalar::from_f64(sin.asin())
+    }
+
+    /// Compute the color of a surface with the provided normal
+    pub fn color(&self, normal: Vector<3>) -> Color {
+        let draft_angle = self.draft_angle(normal);
+
+        if draft_angle >= self.min_draft {
+            Color([0, 200, 0, 255])
+        } else if draft_angle > -self.min_draft {
+            Color([230, 200, 0, 255])
+        } else {
+            Color([220, 0, 0, 255])
+        }
+    }
+}
+
+impl Default for DraftAnalysis {
+    fn default() -> Self {
+        Self {
+            pull_direction: Vector::unit_z(),
+            min_draft: Scalar::from_f64(1_f64.to_radians()),
+        }
+    }
+}
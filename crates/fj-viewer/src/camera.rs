@@ -1,10 +1,10 @@
 //! Viewer camera module
-use std::f64::consts::FRAC_PI_2;
+use std::f64::consts::{FRAC_PI_2, PI};
 
-use fj_interop::{mesh::Mesh, model::Model};
+use fj_interop::{mesh::Triangle, model::Model};
 use fj_math::{Aabb, Point, Scalar, Transform, Vector};
 
-use crate::screen::NormalizedScreenPosition;
+use crate::{graphics::Picked, screen::NormalizedScreenPosition};
 
 /// The camera abstraction
 ///
@@ -20,6 +20,9 @@ pub struct Camera {
     /// The distance to the far plane
     far_plane: f64,
 
+    /// The horizontal field of view, in radians
+    field_of_view_in_x: f64,
+
     /// The rotational part of the transform
     pub rotation: Transform,
 
@@ -31,13 +34,22 @@ impl Camera {
     const DEFAULT_NEAR_PLANE: f64 = 0.0001;
     const DEFAULT_FAR_PLANE: f64 = 1000.0;
 
-    const INITIAL_FIELD_OF_VIEW_IN_X: f64 = FRAC_PI_2; // 90 degrees
+    /// The default horizontal field of view
+    pub const DEFAULT_FIELD_OF_VIEW_IN_X: f64 = FRAC_PI_2; // 90 degrees
+
+    /// The smallest ratio between the near and far planes
+    ///
+    /// The depth buffer has limited precision. If the near plane is much closer
+    /// than the far plane, surfaces that are close to each other start to
+    /// flicker.
+    const MIN_NEAR_FAR_RATIO: f64 = 1e-4;
 
     /// Returns a new camera aligned for viewing a bounding box
     pub fn new() -> Self {
         Self {
             near_plane: Self::DEFAULT_NEAR_PLANE,
             far_plane: Self::DEFAULT_FAR_PLANE,
+            field_of_view_in_x: Self::DEFAULT_FIELD_OF_VIEW_IN_X,
 
             rotation: Transform::identity(),
             translation: Transform::identity(),
@@ -56,7 +68,12 @@ impl Camera {
 
     /// Returns the horizontal field of view of the camera.
     pub fn field_of_view_in_x(&self) -> f64 {
-        Self::INITIAL_FIELD_OF_VIEW_IN_X
+        self.field_of_view_in_x
+    }
+
+    /// Set the horizontal field of view of the camera, in radians
+    pub fn set_field_of_view_in_x(&mut self, field_of_view_in_x: f64) {
+        self.field_of_view_in_x = field_of_view_in_x;
     }
 
     /// Returns the position of the camera in world space.
@@ -79,19 +96,39 @@ impl Camera {
     }
 
     /// Compute the point on the model, that the cursor currently points to.
+    ///
+    /// If it is already known what is under the cursor, pass it as `picked`.
+    /// Otherwise, every triangle of the model is checked.
     pub fn focus_point(
         &self,
         cursor: Option<NormalizedScreenPosition>,
         model: &Model,
+        picked: Option<Picked>,
     ) -> FocusPoint {
-        self.calculate_focus_point(cursor, &model.mesh)
-            .unwrap_or_else(|| FocusPoint(model.aabb.center()))
+        let all_triangles =
+            || self.calculate_focus_point(cursor, model.mesh.triangles());
+
+        let focus_point = match picked {
+            Some(Picked::Background) => None,
+            Some(Picked::Triangle(index)) => {
+                // Picking and ray casting might disagree about a cursor that
+                // is right on the edge of a triangle.
+                self.calculate_focus_point(
+                    cursor,
+                    model.mesh.triangles().nth(index),
+                )
+                .or_else(all_triangles)
+            }
+            None => all_triangles(),
+        };
+
+        focus_point.unwrap_or_else(|| FocusPoint(model.aabb.center()))
     }
 
     fn calculate_focus_point(
         &self,
         cursor: Option<NormalizedScreenPosition>,
-        mesh: &Mesh<Point<3>>,
+        triangles: impl IntoIterator<Item = Triangle>,
     ) -> Option<FocusPoint> {
         // Transform camera and cursor positions to model space.
         let origin = self.position();
@@ -100,7 +137,7 @@ impl Camera {
 
         let mut min_t = None;
 
-        for triangle in mesh.triangles() {
+        for triangle in triangles {
             let t =
                 triangle
                     .inner
@@ -155,7 +192,7 @@ impl Camera {
             // Having computed those points, figuring out how far the camera
             // needs to be from the model is just a bit of trigonometry.
             let distance_from_model =
-                furthest_point / (Self::INITIAL_FIELD_OF_VIEW_IN_X / 2.).atan();
+                furthest_point / (self.field_of_view_in_x / 2.).atan();
 
             // And finally, the distance from the origin is trivial now.
             highest_point + distance_from_model
@@ -177,48 +214,52 @@ impl Camera {
     }
 
     /// Update the max and minimum rendering distance for this camera.
-    pub fn update_planes(&mut self, aabb: &Aabb<3>) {
-        let view_transform = self.camera_to_model();
-        let view_direction = Vector::from([0., 0., -1.]);
-
-        let mut dist_min = f64::INFINITY;
-        let mut dist_max = f64::NEG_INFINITY;
-
-        for vertex in aabb.vertices() {
-            let point = view_transform.transform_point(&vertex);
+    pub fn update_planes(
+        &mut self,
+        aabb: &Aabb<3>,
+        clipping_planes: ClippingPlanes,
+    ) {
+        let (near_plane, far_plane) = match clipping_planes {
+            ClippingPlanes::Automatic => self.fit_planes(aabb),
+            ClippingPlanes::Fixed { near, far } => (near, far),
+        };
 
-            // Project `point` onto `view_direction`. See this Wikipedia page:
-            // https://en.wikipedia.org/wiki/Vector_projection
-            //
-            // Let's rename the variables first, so they fit the names in that
-            // page.
-            let (a, b) = (point.coords, view_direction);
-            let a1 = b * a.dot(&b) / b.dot(&b);
+        self.near_plane = near_plane;
+        self.far_plane = far_plane;
+    }
 
-            let dist = a1.magnitude().into_f64();
+    fn fit_planes(&self, aabb: &Aabb<3>) -> (f64, f64) {
+        let view_transform = self.camera_to_model();
 
-            if dist < dist_min {
-                dist_min = dist;
-            }
-            if dist > dist_max {
-                dist_max = dist;
-            }
+        // The camera looks along the negative z-axis, so the depth of a point
+        // is its negated z-coordinate in camera space. Points behind the
+        // camera have a negative depth.
+        let depths = aabb.vertices().map(|vertex| {
+            -view_transform.transform_point(&vertex).z.into_f64()
+        });
+        let min_depth = depths.into_iter().fold(f64::INFINITY, f64::min);
+        let max_depth = depths.into_iter().fold(f64::NEG_INFINITY, f64::max);
+
+        if max_depth <= 0. || !max_depth.is_finite() {
+            // The model is completely behind the camera, or there is no model.
+            return (Self::DEFAULT_NEAR_PLANE, Self::DEFAULT_FAR_PLANE);
         }
 
-        self.near_plane = if dist_min > 0. {
-            // Setting `self.near_plane` to `dist_min` should theoretically
-            // work, but results in the front of the model being clipped. I
-            // wasn't able to figure out why, and for the time being, this
-            // factor seems to work well enough.
-            dist_min * 0.5
-        } else {
-            Self::DEFAULT_NEAR_PLANE
-        };
-        self.far_plane = if dist_max > 0. {
-            dist_max
-        } else {
-            Self::DEFAULT_FAR_PLANE
-        };
+        // Leave some room, so the back of the model isn't clipped due to
+        // numerical inaccuracy.
+        let far_plane = max_depth * 1.01;
+
+        // Setting the near plane to `min_depth` should theoretically work, but
+        // results in the front of the model being clipped. This factor seems
+        // to work well enough.
+        //
+        // If the camera is within the bounding box of the model, the near plane
+        // must be very close to the camera, but is limited by the precision of
+        // the depth buffer.
+        let near_plane =
+            f64::max(min_depth * 0.5, far_plane * Self::MIN_NEAR_FAR_RATIO);
+
+        (near_plane, far_plane)
     }
 }
 
@@ -234,3 +275,84 @@ impl Default for Camera {
 /// falling back to the center point of the model's bounding volume otherwise.
 #[derive(Clone, Copy)]
 pub struct FocusPoint(pub Point<3>);
+
+/// How the near and far clipping planes of the camera are determined
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClippingPlanes {
+    /// Fit the planes to the bounding box of the model in every frame
+    ///
+    /// This works for models of any size, from very small to very large.
+    Automatic,
+
+    /// Use fixed distances from the camera
+    Fixed {
+        /// The distance to the near plane
+        near: f64,
+
+        /// The distance to the far plane
+        far: f64,
+    },
+}
+
+impl ClippingPlanes {
+    /// Check whether the clipping planes are valid
+    ///
+    /// Fixed clipping planes must be at a positive, finite distance, and the
+    /// far plane must be further away than the near plane.
+    pub fn validate(&self) -> Result<(), InvalidCameraConfig> {
+        let Self::Fixed { near, far } = *self else {
+            return Ok(());
+        };
+
+        if !near.is_finite() || near <= 0. {
+            return Err(InvalidCameraConfig::NearPlane(near));
+        }
+        if !far.is_finite() || far <= near {
+            return Err(InvalidCameraConfig::FarPlane { near, far });
+        }
+
+        Ok(())
+    }
+}
+
+/// Check whether a horizontal field of view, in radians, is valid
+pub fn validate_field_of_view(
+    field_of_view_in_x: f64,
+) -> Result<(), InvalidCameraConfig> {
+    if !field_of_view_in_x.is_finite()
+        || field_of_view_in_x <= 0.
+        || field_of_view_in_x >= PI
+    {
+        return Err(InvalidCameraConfig::FieldOfView(field_of_view_in_x));
+    }
+
+    Ok(())
+}
+
+/// Invalid configuration of the camera
+#[derive(Clone, Copy, Debug, PartialEq, thiserror::Error)]
+pub enum InvalidCameraConfig {
+    /// The field of view is not between zero and a half turn
+    #[error(
+        "Field of view must be larger than 0 and smaller than 180 degrees \
+        (was {0} radians)"
+    )]
+    FieldOfView(f64),
+
+    /// The near plane is not at a positive distance from the camera
+    #[error("Near clipping plane must be at a positive distance (was {0})")]
+    NearPlane(f64),
+
+    /// The far plane is not further away than the near plane
+    #[error(
+        "Far clipping plane ({far}) must be further away than near clipping \
+        plane ({near})"
+    )]
+    FarPlane {
+        /// The distance to the near plane
+        near: f64,
+
+        /// The distance to the far plane
+        far: f64,
+    },
+}
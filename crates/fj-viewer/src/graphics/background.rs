@@ -0,0 +1,112 @@
+use fj_interop::mesh::Color;
+
+use crate::theme::Background;
+
+use super::{DEPTH_FORMAT, SAMPLE_COUNT};
+
+/// Renders the background behind the model
+///
+/// A solid background only requires clearing the frame, which is done by
+/// [`clear_color`]. For a gradient, the frame is cleared to the
+/// bottom color, and the top color is blended on top of that.
+#[derive(Debug)]
+pub struct BackgroundRenderer {
+    gradient_pipeline: wgpu::RenderPipeline,
+}
+
+impl BackgroundRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+    ) -> Self {
+        let pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Background Pipeline Layout"),
+                bind_group_layouts: &[],
+                push_constant_ranges: &[],
+            });
+
+        let shader =
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Background Shader"),
+                source: wgpu::ShaderSource::Wgsl(
+                    include_str!("background.wgsl").into(),
+                ),
+            });
+
+        let gradient_pipeline =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Background Gradient Pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vertex",
+                    buffers: &[],
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                // The background is drawn in the same render pass as the
+                // model, so it has to match its depth buffer, without
+                // affecting it.
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: DEPTH_FORMAT,
+                    depth_write_enabled: false,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: SAMPLE_COUNT,
+                    ..Default::default()
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "frag_gradient",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: color_format,
+                        blend: Some(wgpu::BlendState {
+                            color: wgpu::BlendComponent {
+                                src_factor: wgpu::BlendFactor::Constant,
+                                dst_factor: wgpu::BlendFactor::OneMinusSrc,
+                                operation: wgpu::BlendOperation::Add,
+                            },
+                            alpha: wgpu::BlendComponent::REPLACE,
+                        }),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview: None,
+            });
+
+        Self { gradient_pipeline }
+    }
+
+    /// Draw the background
+    ///
+    /// Must be called first in a render pass that has been cleared to
+    /// [`clear_color`].
+    pub fn draw<'r>(
+        &'r self,
+        render_pass: &mut wgpu::RenderPass<'r>,
+        background: Background,
+    ) {
+        if let Background::Gradient { top, .. } = background {
+            render_pass.set_pipeline(&self.gradient_pipeline);
+            render_pass.set_blend_constant(to_wgpu(top));
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+}
+
+/// The color that the frame is cleared to, before drawing the background
+pub fn clear_color(background: Background) -> wgpu::Color {
+    match background {
+        Background::Solid(color) => to_wgpu(color),
+        Background::Gradient { bottom, .. } => to_wgpu(bottom),
+    }
+}
+
+/// Convert a color into the representation that wgpu expects
+pub fn to_wgpu(Color([r, g, b, a]): Color) -> wgpu::Color {
+    let [r, g, b, a] = [r, g, b, a].map(|channel| f64::from(channel) / 255.);
+    wgpu::Color { r, g, b, a }
+}
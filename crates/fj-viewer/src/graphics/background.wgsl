@@ -0,0 +1,24 @@
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    // 0.0 at the bottom of the screen, 1.0 at the top
+    @location(0) height: f32,
+};
+
+@vertex
+fn vertex(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // A single triangle that covers the whole screen.
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.height = uv.y;
+    return out;
+}
+
+// The render pass is cleared to the bottom color, and the pipeline blends the
+// top color on top of that, according to the height. The top color is the
+// blend constant.
+@fragment
+fn frag_gradient(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(clamp(in.height, 0.0, 1.0));
+}
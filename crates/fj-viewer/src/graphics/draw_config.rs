@@ -1,11 +1,34 @@
+use crate::camera::{Camera, ClippingPlanes};
+
 /// High level configuration for rendering the active model
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct DrawConfig {
     /// Toggle for displaying the shaded model
     pub draw_model: bool,
 
     /// Toggle for displaying the wireframe model
     pub draw_mesh: bool,
+
+    /// Toggle for displaying the edges of the model
+    pub draw_edges: bool,
+
+    /// Toggle for displaying the model with zebra stripes, instead of shaded
+    pub draw_zebra: bool,
+
+    /// Toggle for outlining silhouettes and sharp edges of the model
+    pub draw_outline: bool,
+
+    /// Toggle for shadows that the model casts onto itself
+    pub draw_shadows: bool,
+
+    /// Toggle for darkening creases and corners of the model
+    pub draw_ambient_occlusion: bool,
+
+    /// The horizontal field of view of the camera, in radians
+    pub field_of_view_in_x: f64,
+
+    /// How the clipping planes of the camera are determined
+    pub clipping_planes: ClippingPlanes,
 }
 
 impl Default for DrawConfig {
@@ -13,6 +36,13 @@ impl Default for DrawConfig {
         Self {
             draw_model: true,
             draw_mesh: false,
+            draw_edges: false,
+            draw_zebra: false,
+            draw_outline: false,
+            draw_shadows: false,
+            draw_ambient_occlusion: false,
+            field_of_view_in_x: Camera::DEFAULT_FIELD_OF_VIEW_IN_X,
+            clipping_planes: ClippingPlanes::Automatic,
         }
     }
 }
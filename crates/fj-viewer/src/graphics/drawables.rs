@@ -6,6 +6,8 @@ use super::{
 pub struct Drawables<'r> {
     pub model: Drawable<'r>,
     pub mesh: Option<Drawable<'r>>,
+    pub zebra: Drawable<'r>,
+    pub edges: Drawable<'r>,
 }
 
 impl<'r> Drawables<'r> {
@@ -16,7 +18,16 @@ impl<'r> Drawables<'r> {
             .as_ref()
             .map(|pipeline| Drawable::new(&geometries.mesh, pipeline));
 
-        Self { model, mesh }
+        let zebra = Drawable::new(&geometries.mesh, &pipelines.zebra);
+
+        let edges = Drawable::new(&geometries.edges, &pipelines.edges);
+
+        Self {
+            model,
+            mesh,
+            zebra,
+            edges,
+        }
     }
 }
 
@@ -7,13 +7,19 @@ use super::vertices::{Vertex, Vertices};
 #[derive(Debug)]
 pub struct Geometries {
     pub mesh: Geometry,
+    pub edges: Geometry,
 }
 
 impl Geometries {
-    pub fn new(device: &wgpu::Device, mesh: &Vertices) -> Self {
+    pub fn new(
+        device: &wgpu::Device,
+        mesh: &Vertices,
+        edges: &Vertices,
+    ) -> Self {
         let mesh = Geometry::new(device, mesh.vertices(), mesh.indices());
+        let edges = Geometry::new(device, edges.vertices(), edges.indices());
 
-        Self { mesh }
+        Self { mesh, edges }
     }
 }
 
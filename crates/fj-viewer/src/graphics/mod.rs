@@ -1,14 +1,18 @@
 //! Rendering primitives, routines, and structures.
 
+mod background;
 mod device;
 mod draw_config;
 mod drawables;
 mod geometries;
 mod model;
 mod navigation_cube;
+mod picking;
 mod pipelines;
 mod renderer;
+mod screen_space;
 mod shaders;
+mod shadow;
 mod texture;
 mod transform;
 mod uniforms;
@@ -17,7 +21,9 @@ mod vertices;
 pub use self::{
     device::DeviceError,
     draw_config::DrawConfig,
+    picking::Picked,
     renderer::{Renderer, RendererInitError},
+    vertices::Vertices,
 };
 
 pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
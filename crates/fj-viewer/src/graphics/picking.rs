@@ -0,0 +1,369 @@
+use std::sync::mpsc;
+
+use wgpu::util::DeviceExt as _;
+
+use crate::{camera::Camera, screen::NormalizedScreenPosition};
+
+use super::{transform::Transform, vertices::Vertices, DEPTH_FORMAT};
+
+/// The format of the texture that the ID of each triangle is rendered into
+const ID_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
+/// Finds the triangle under the cursor, by rendering triangle IDs
+///
+/// Each triangle of the mesh is rendered with its index as the color, and the
+/// pixel under the cursor is read back. This is a lot faster than casting a ray
+/// against every triangle, for dense meshes. Only the pixel under the cursor
+/// is rendered, thanks to a scissor rectangle.
+#[derive(Debug)]
+pub struct PickingRenderer {
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    readback_buffer: wgpu::Buffer,
+    positions: Option<Positions>,
+    targets: Targets,
+}
+
+impl PickingRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+    ) -> Self {
+        let uniform_buffer =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Picking Uniform Buffer"),
+                contents: bytemuck::cast_slice(&[Transform::identity()]),
+                usage: wgpu::BufferUsages::UNIFORM
+                    | wgpu::BufferUsages::COPY_DST,
+            });
+
+        let bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+                label: Some("picking_bind_group_layout"),
+            });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("picking_bind_group"),
+        });
+
+        let pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Picking Pipeline Layout"),
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let shader =
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Picking Shader"),
+                source: wgpu::ShaderSource::Wgsl(
+                    include_str!("picking.wgsl").into(),
+                ),
+            });
+
+        let pipeline =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Picking Pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vertex",
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: std::mem::size_of::<[f32; 3]>() as u64,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &wgpu::vertex_attr_array![
+                            0 => Float32x3,
+                        ],
+                    }],
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    ..Default::default()
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::LessEqual,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "frag_id",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: ID_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview: None,
+            });
+
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Picking Readback Buffer"),
+            size: std::mem::size_of::<u32>() as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let targets = Targets::new(device, config);
+
+        Self {
+            pipeline,
+            uniform_buffer,
+            bind_group,
+            readback_buffer,
+            positions: None,
+            targets,
+        }
+    }
+
+    /// Recreate the textures, after the size of the surface has changed
+    pub fn handle_resize(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+    ) {
+        self.targets = Targets::new(device, config);
+    }
+
+    /// Update the triangles that can be picked
+    ///
+    /// The triangles are numbered in the order of the indices of `mesh`.
+    pub fn update_geometry(&mut self, device: &wgpu::Device, mesh: &Vertices) {
+        // The ID of a triangle is derived from the index of its vertices. This
+        // only works, if the vertices are not shared between triangles.
+        let positions = mesh
+            .indices()
+            .iter()
+            .map(|&index| mesh.vertices()[index as usize].position)
+            .collect::<Vec<_>>();
+
+        self.positions = (!positions.is_empty()).then(|| Positions {
+            buffer: device.create_buffer_init(
+                &wgpu::util::BufferInitDescriptor {
+                    label: Some("Picking Vertex Buffer"),
+                    contents: bytemuck::cast_slice(&positions),
+                    usage: wgpu::BufferUsages::VERTEX,
+                },
+            ),
+            num_vertices: positions
+                .len()
+                .try_into()
+                .expect("`usize` couldn't be cast to `u32`"),
+        });
+    }
+
+    /// Find the triangle under the cursor
+    ///
+    /// Blocks until the GPU has rendered the pixel under the cursor. Returns
+    /// `None`, if reading it back failed.
+    pub fn pick(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        camera: &Camera,
+        cursor: NormalizedScreenPosition,
+    ) -> Option<Picked> {
+        let Some(positions) = &self.positions else {
+            return Some(Picked::Background);
+        };
+
+        let [width, height] = self.targets.size;
+        let aspect_ratio = f64::from(width) / f64::from(height);
+
+        // This is the inverse of how the window converts the cursor position
+        // into normalized coordinates.
+        let x = (cursor.x + 1.) / 2. * f64::from(width);
+        let y = (1. - cursor.y * aspect_ratio) / 2. * f64::from(height);
+        if x < 0. || y < 0. || x >= f64::from(width) || y >= f64::from(height) {
+            return Some(Picked::Background);
+        }
+        let [x, y] = [x, y].map(|coord| coord as u32);
+
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[Transform::for_vertices(
+                camera,
+                aspect_ratio,
+            )]),
+        );
+
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Picking Encoder"),
+            });
+
+        {
+            let mut render_pass =
+                encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    color_attachments: &[Some(
+                        wgpu::RenderPassColorAttachment {
+                            view: &self.targets.id_view,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(
+                                    wgpu::Color::TRANSPARENT,
+                                ),
+                                store: wgpu::StoreOp::Store,
+                            },
+                        },
+                    )],
+                    depth_stencil_attachment: Some(
+                        wgpu::RenderPassDepthStencilAttachment {
+                            view: &self.targets.depth_view,
+                            depth_ops: Some(wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(1.0),
+                                store: wgpu::StoreOp::Discard,
+                            }),
+                            stencil_ops: None,
+                        },
+                    ),
+                    ..Default::default()
+                });
+            render_pass.set_scissor_rect(x, y, 1, 1);
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &self.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, positions.buffer.slice(..));
+            render_pass.draw(0..positions.num_vertices, 0..1);
+        }
+
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.targets.id_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &self.readback_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: None,
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        queue.submit(Some(encoder.finish()));
+
+        let slice = self.readback_buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            // The receiver is still around, as we wait for the result below.
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        match receiver.try_recv() {
+            Ok(Ok(())) => {}
+            Ok(Err(_)) => return None,
+            Err(_) => {
+                // On some platforms, like WebGL, waiting is not supported.
+                // Cancel the mapping, so the buffer can be used again.
+                self.readback_buffer.unmap();
+                return None;
+            }
+        }
+
+        let id = {
+            let data = slice.get_mapped_range();
+            u32::from_le_bytes([data[0], data[1], data[2], data[3]])
+        };
+        self.readback_buffer.unmap();
+
+        let picked = match id.checked_sub(1) {
+            Some(index) => Picked::Triangle(index as usize),
+            None => Picked::Background,
+        };
+        Some(picked)
+    }
+}
+
+/// The result of picking
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Picked {
+    /// The cursor points at no triangle
+    Background,
+
+    /// The cursor points at the triangle with this index
+    Triangle(usize),
+}
+
+/// The positions of the vertices of all triangles that can be picked
+#[derive(Debug)]
+struct Positions {
+    buffer: wgpu::Buffer,
+    num_vertices: u32,
+}
+
+/// The textures that the IDs of the triangles are rendered into
+#[derive(Debug)]
+struct Targets {
+    size: [u32; 2],
+    id_texture: wgpu::Texture,
+    id_view: wgpu::TextureView,
+    depth_view: wgpu::TextureView,
+}
+
+impl Targets {
+    fn new(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> Self {
+        let create_texture = |format, usage| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: None,
+                size: wgpu::Extent3d {
+                    width: config.width,
+                    height: config.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage,
+                view_formats: &[],
+            })
+        };
+
+        let id_texture = create_texture(
+            ID_FORMAT,
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_SRC,
+        );
+        let id_view =
+            id_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_view = create_texture(
+            DEPTH_FORMAT,
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+        )
+        .create_view(&wgpu::TextureViewDescriptor::default());
+
+        Self {
+            size: [config.width, config.height],
+            id_texture,
+            id_view,
+            depth_view,
+        }
+    }
+}
@@ -0,0 +1,30 @@
+struct Uniforms {
+    transform: mat4x4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    // The index of the triangle, plus one. Zero is the background.
+    @location(0) @interpolate(flat) id: u32,
+};
+
+// The vertices are not shared between triangles, so every three of them make
+// up a triangle.
+@vertex
+fn vertex(
+    @builtin(vertex_index) index: u32,
+    @location(0) position: vec3<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = uniforms.transform * vec4<f32>(position, 1.0);
+    out.id = index / 3u + 1u;
+    return out;
+}
+
+@fragment
+fn frag_id(in: VertexOutput) -> @location(0) u32 {
+    return in.id;
+}
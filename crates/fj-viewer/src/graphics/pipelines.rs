@@ -1,5 +1,3 @@
-use std::mem::size_of;
-
 use super::{
     shaders::{Shader, Shaders},
     vertices::Vertex,
@@ -10,6 +8,8 @@ use super::{
 pub struct Pipelines {
     pub model: Pipeline,
     pub mesh: Option<Pipeline>,
+    pub zebra: Pipeline,
+    pub edges: Pipeline,
 }
 
 impl Pipelines {
@@ -37,6 +37,24 @@ impl Pipelines {
             color_format,
         );
 
+        let zebra = Pipeline::new(
+            device,
+            &pipeline_layout,
+            shaders.zebra(),
+            wgpu::PrimitiveTopology::TriangleList,
+            wgpu::PolygonMode::Fill,
+            color_format,
+        );
+
+        let edges = Pipeline::new(
+            device,
+            &pipeline_layout,
+            shaders.edges(),
+            wgpu::PrimitiveTopology::LineList,
+            wgpu::PolygonMode::Fill,
+            color_format,
+        );
+
         let mesh = if features.contains(wgpu::Features::POLYGON_MODE_LINE) {
             // We need this feature, otherwise initializing the pipeline will
             // panic.
@@ -53,7 +71,12 @@ impl Pipelines {
             None
         };
 
-        Self { model, mesh }
+        Self {
+            model,
+            mesh,
+            zebra,
+            edges,
+        }
     }
 }
 
@@ -76,15 +99,7 @@ impl Pipeline {
                 vertex: wgpu::VertexState {
                     module: shader.module,
                     entry_point: "vertex",
-                    buffers: &[wgpu::VertexBufferLayout {
-                        array_stride: size_of::<Vertex>() as u64,
-                        step_mode: wgpu::VertexStepMode::Vertex,
-                        attributes: &wgpu::vertex_attr_array![
-                            0 => Float32x3,
-                            1 => Float32x3,
-                            2 => Float32x4,
-                        ],
-                    }],
+                    buffers: &[Vertex::layout()],
                 },
                 primitive: wgpu::PrimitiveState {
                     topology,
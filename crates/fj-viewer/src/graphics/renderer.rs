@@ -4,16 +4,30 @@ use thiserror::Error;
 use tracing::{debug, error, trace};
 use wgpu::util::DeviceExt as _;
 
+use fj_math::{Aabb, Point};
+
 use crate::{
     camera::Camera,
-    screen::{Screen, ScreenSize},
+    screen::{NormalizedScreenPosition, Screen, ScreenSize},
+    stereo::Stereo,
+    theme::{Background, Theme},
 };
 
 use super::{
-    device::Device, draw_config::DrawConfig, drawables::Drawables,
-    geometries::Geometries, navigation_cube::NavigationCubeRenderer,
-    pipelines::Pipelines, transform::Transform, uniforms::Uniforms,
-    vertices::Vertices, DeviceError, DEPTH_FORMAT, SAMPLE_COUNT,
+    background::{self, BackgroundRenderer},
+    device::Device,
+    draw_config::DrawConfig,
+    drawables::Drawables,
+    geometries::Geometries,
+    navigation_cube::NavigationCubeRenderer,
+    picking::{Picked, PickingRenderer},
+    pipelines::Pipelines,
+    screen_space::{Effects, ScreenSpaceRenderer},
+    shadow::ShadowRenderer,
+    transform::Transform,
+    uniforms::Uniforms,
+    vertices::Vertices,
+    DeviceError, DEPTH_FORMAT, SAMPLE_COUNT,
 };
 
 /// Graphics rendering state and target abstraction
@@ -29,10 +43,23 @@ pub struct Renderer {
     uniform_buffer: wgpu::Buffer,
     bind_group: wgpu::BindGroup,
 
+    /// The uniforms of the right eye, in stereo
+    ///
+    /// The left eye uses the regular uniforms.
+    right_eye_uniform_buffer: wgpu::Buffer,
+    right_eye_bind_group: wgpu::BindGroup,
+
     geometries: Geometries,
     pipelines: Pipelines,
 
+    /// A sphere around the model, as center and radius
+    bounds: Option<(Point<3>, f64)>,
+
+    background_renderer: BackgroundRenderer,
     navigation_cube_renderer: NavigationCubeRenderer,
+    picking_renderer: PickingRenderer,
+    screen_space_renderer: ScreenSpaceRenderer,
+    shadow_renderer: ShadowRenderer,
 }
 
 impl Renderer {
@@ -134,42 +161,97 @@ impl Renderer {
                     | wgpu::BufferUsages::COPY_DST,
             },
         );
-        let bind_group_layout = device.device.create_bind_group_layout(
-            &wgpu::BindGroupLayoutDescriptor {
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::all(),
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: wgpu::BufferSize::new(size_of::<
-                            Uniforms,
-                        >(
-                        )
-                            as u64),
+        let shadow_renderer =
+            ShadowRenderer::new(&device.device, &uniform_buffer);
+
+        let bind_group_layout =
+            device.device.create_bind_group_layout(
+                &wgpu::BindGroupLayoutDescriptor {
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::all(),
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: wgpu::BufferSize::new(
+                                    size_of::<Uniforms>() as u64,
+                                ),
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Depth,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 2,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(
+                                wgpu::SamplerBindingType::Comparison,
+                            ),
+                            count: None,
+                        },
+                    ],
+                    label: None,
+                },
+            );
+
+        // Each view of the model, like each eye in stereo, needs its own
+        // uniforms.
+        let create_bind_group = |uniform_buffer: &wgpu::Buffer| {
+            device.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer(
+                            wgpu::BufferBinding {
+                                buffer: uniform_buffer,
+                                offset: 0,
+                                size: None,
+                            },
+                        ),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(
+                            shadow_renderer.view(),
+                        ),
                     },
-                    count: None,
-                }],
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::Sampler(
+                            shadow_renderer.sampler(),
+                        ),
+                    },
+                ],
+                label: None,
+            })
+        };
+        let bind_group = create_bind_group(&uniform_buffer);
+
+        let right_eye_uniform_buffer = device.device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
                 label: None,
+                contents: bytemuck::cast_slice(&[Uniforms::default()]),
+                usage: wgpu::BufferUsages::UNIFORM
+                    | wgpu::BufferUsages::COPY_DST,
             },
         );
-        let bind_group =
-            device.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                layout: &bind_group_layout,
-                entries: &[wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::Buffer(
-                        wgpu::BufferBinding {
-                            buffer: &uniform_buffer,
-                            offset: 0,
-                            size: None,
-                        },
-                    ),
-                }],
-                label: None,
-            });
+        let right_eye_bind_group = create_bind_group(&right_eye_uniform_buffer);
 
-        let geometries = Geometries::new(&device.device, &Vertices::empty());
+        let geometries = Geometries::new(
+            &device.device,
+            &Vertices::empty(),
+            &Vertices::empty(),
+        );
         let pipelines = Pipelines::new(
             &device.device,
             &bind_group_layout,
@@ -177,11 +259,21 @@ impl Renderer {
             features,
         );
 
+        let background_renderer =
+            BackgroundRenderer::new(&device.device, color_format);
         let navigation_cube_renderer = NavigationCubeRenderer::new(
             &device.device,
             &device.queue,
             &surface_config,
         );
+        let picking_renderer =
+            PickingRenderer::new(&device.device, &surface_config);
+        let screen_space_renderer = ScreenSpaceRenderer::new(
+            &device.device,
+            &bind_group_layout,
+            &surface_config,
+            screen.scale_factor(),
+        );
 
         Ok(Self {
             surface,
@@ -193,17 +285,39 @@ impl Renderer {
 
             uniform_buffer,
             bind_group,
+            right_eye_uniform_buffer,
+            right_eye_bind_group,
 
             geometries,
             pipelines,
+            bounds: None,
 
+            background_renderer,
             navigation_cube_renderer,
+            picking_renderer,
+            screen_space_renderer,
+            shadow_renderer,
         })
     }
 
     /// Updates the geometry of the model being rendered.
-    pub fn update_geometry(&mut self, mesh: Vertices) {
-        self.geometries = Geometries::new(&self.device.device, &mesh);
+    ///
+    /// The edges are drawn as lines, on top of the mesh. Only the mesh can be
+    /// picked.
+    pub fn update_geometry(&mut self, mesh: Vertices, edges: Vertices) {
+        self.geometries = Geometries::new(&self.device.device, &mesh, &edges);
+        self.picking_renderer
+            .update_geometry(&self.device.device, &mesh);
+
+        let points = mesh
+            .vertices()
+            .iter()
+            .map(|vertex| Point::from(vertex.position.map(f64::from)))
+            .collect::<Vec<_>>();
+        self.bounds = Aabb::<3>::from_point_slice(&points).map(|aabb| {
+            let radius = (aabb.size() / 2.).magnitude().into_f64();
+            (aabb.center(), radius)
+        });
     }
 
     /// Resizes the render surface.
@@ -225,26 +339,120 @@ impl Renderer {
             &self.device.device,
             &self.surface_config,
         );
+        self.screen_space_renderer
+            .handle_resize(&self.device.device, &self.surface_config);
+        self.picking_renderer
+            .handle_resize(&self.device.device, &self.surface_config);
+    }
+
+    /// Find the triangle of the current geometry that is under the cursor
+    ///
+    /// Returns `None`, if the GPU couldn't provide the result.
+    pub fn pick(
+        &self,
+        camera: &Camera,
+        cursor: NormalizedScreenPosition,
+    ) -> Option<Picked> {
+        self.picking_renderer.pick(
+            &self.device.device,
+            &self.device.queue,
+            camera,
+            cursor,
+        )
+    }
+
+    /// Updates the ratio of physical pixels to logical pixels
+    pub fn handle_scale_factor_change(&mut self, scale_factor: f64) {
+        self.screen_space_renderer
+            .set_scale_factor(&self.device.queue, scale_factor);
     }
 
     /// Draws the renderer, camera, and config state to the window.
+    ///
+    /// If `stereo` is provided, the model is drawn once for each eye, side by
+    /// side.
     pub fn draw(
         &mut self,
         camera: &Camera,
         config: &DrawConfig,
+        theme: Theme,
+        background: Background,
+        stereo: Option<&Stereo>,
     ) -> Result<(), DrawError> {
+        let width = self.surface_config.width as f32;
+        let height = self.surface_config.height as f32;
         let aspect_ratio = f64::from(self.surface_config.width)
             / f64::from(self.surface_config.height);
-        let uniforms = Uniforms {
-            transform: Transform::for_vertices(camera, aspect_ratio),
+        let transform_light = match self.bounds {
+            Some((center, radius)) if config.draw_shadows && radius > 0. => {
+                Some(Transform::for_light(camera, center, radius))
+            }
+            _ => None,
+        };
+        let uniforms = |transform| Uniforms {
+            transform,
             transform_normals: Transform::for_normals(camera),
+            transform_light: transform_light
+                .unwrap_or_else(Transform::identity),
+            shadows: transform_light.is_some().into(),
+            _padding: [0; 3],
         };
 
-        self.device.queue.write_buffer(
-            &self.uniform_buffer,
-            0,
-            bytemuck::cast_slice(&[uniforms]),
-        );
+        let views =
+            match stereo {
+                Some(stereo) => {
+                    let aspect_ratio = aspect_ratio / 2.;
+                    for (eye, uniform_buffer) in [
+                        (&stereo.left, &self.uniform_buffer),
+                        (&stereo.right, &self.right_eye_uniform_buffer),
+                    ] {
+                        self.device.queue.write_buffer(
+                            uniform_buffer,
+                            0,
+                            bytemuck::cast_slice(&[uniforms(
+                                Transform::for_eye(camera, eye, aspect_ratio),
+                            )]),
+                        );
+                    }
+
+                    vec![
+                        View {
+                            viewport: Viewport {
+                                x: 0.,
+                                width: width / 2.,
+                                height,
+                            },
+                            bind_group: &self.bind_group,
+                        },
+                        View {
+                            viewport: Viewport {
+                                x: width / 2.,
+                                width: width / 2.,
+                                height,
+                            },
+                            bind_group: &self.right_eye_bind_group,
+                        },
+                    ]
+                }
+                None => {
+                    self.device.queue.write_buffer(
+                        &self.uniform_buffer,
+                        0,
+                        bytemuck::cast_slice(&[uniforms(
+                            Transform::for_vertices(camera, aspect_ratio),
+                        )]),
+                    );
+
+                    vec![View {
+                        viewport: Viewport {
+                            x: 0.,
+                            width,
+                            height,
+                        },
+                        bind_group: &self.bind_group,
+                    }]
+                }
+            };
 
         let surface_texture = match self.surface.get_current_texture() {
             Ok(surface_texture) => surface_texture,
@@ -268,6 +476,11 @@ impl Renderer {
             &wgpu::CommandEncoderDescriptor { label: None },
         );
 
+        if config.draw_model && transform_light.is_some() {
+            self.shadow_renderer
+                .draw(&mut encoder, &self.geometries.mesh);
+        }
+
         // Need this block here, as a render pass only takes effect once it's
         // dropped.
         {
@@ -278,7 +491,9 @@ impl Renderer {
                             view: &self.frame_buffer,
                             resolve_target: Some(&color_view),
                             ops: wgpu::Operations {
-                                load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
+                                load: wgpu::LoadOp::Clear(
+                                    background::clear_color(background),
+                                ),
                                 // Not necessary, due to MSAA being enabled.
                                 store: wgpu::StoreOp::Discard,
                             },
@@ -296,28 +511,58 @@ impl Renderer {
                     ),
                     ..Default::default()
                 });
-            render_pass.set_bind_group(0, &self.bind_group, &[]);
+            self.background_renderer.draw(&mut render_pass, background);
 
             let drawables = Drawables::new(&self.geometries, &self.pipelines);
 
-            if config.draw_model {
-                drawables.model.draw(&mut render_pass);
-            }
+            for view in &views {
+                view.viewport.apply(&mut render_pass);
+                render_pass.set_bind_group(0, view.bind_group, &[]);
+
+                if config.draw_model {
+                    if config.draw_zebra {
+                        drawables.zebra.draw(&mut render_pass);
+                    } else {
+                        drawables.model.draw(&mut render_pass);
+                    }
+                }
+
+                if let Some(drawable) = &drawables.mesh {
+                    if config.draw_mesh {
+                        drawable.draw(&mut render_pass);
+                    }
+                }
 
-            if let Some(drawable) = drawables.mesh {
-                if config.draw_mesh {
-                    drawable.draw(&mut render_pass);
+                if config.draw_edges {
+                    drawables.edges.draw(&mut render_pass);
                 }
             }
         }
 
-        self.navigation_cube_renderer.draw(
-            &color_view,
-            &mut encoder,
-            &self.device.queue,
-            aspect_ratio,
-            camera.rotation,
-        );
+        if config.draw_model {
+            self.screen_space_renderer.draw(
+                &color_view,
+                &mut encoder,
+                &views,
+                &self.geometries.mesh,
+                Effects {
+                    outline: config.draw_outline,
+                    outline_color: theme.outline(),
+                    ambient_occlusion: config.draw_ambient_occlusion,
+                },
+            );
+        }
+
+        // The navigation cube would only be visible to one eye, in stereo.
+        if stereo.is_none() {
+            self.navigation_cube_renderer.draw(
+                &color_view,
+                &mut encoder,
+                &self.device.queue,
+                aspect_ratio,
+                camera.rotation,
+            );
+        }
 
         let command_buffer = encoder.finish();
         self.device.queue.submit(Some(command_buffer));
@@ -373,6 +618,26 @@ impl Renderer {
     }
 }
 
+/// A view of the model, as drawn into a part of the screen
+pub struct View<'r> {
+    pub viewport: Viewport,
+    pub bind_group: &'r wgpu::BindGroup,
+}
+
+/// The part of the screen that a view is drawn into, in pixels
+#[derive(Clone, Copy, Debug)]
+pub struct Viewport {
+    pub x: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    pub fn apply(&self, render_pass: &mut wgpu::RenderPass) {
+        render_pass.set_viewport(self.x, 0., self.width, self.height, 0., 1.);
+    }
+}
+
 /// Error describing the set of render surface initialization errors
 #[derive(Error, Debug)]
 pub enum RendererInitError {
@@ -0,0 +1,382 @@
+use fj_interop::mesh::Color;
+use wgpu::util::DeviceExt as _;
+
+use super::{
+    background::to_wgpu, geometries::Geometry, renderer::View,
+    shaders::Shaders, vertices::Vertex, DEPTH_FORMAT,
+};
+
+/// The format of the texture that holds the normal and depth of each pixel
+const NORMAL_DEPTH_FORMAT: wgpu::TextureFormat =
+    wgpu::TextureFormat::Rgba16Float;
+
+/// Renders screen-space effects as a post-processing step
+///
+/// The model is rendered a second time, into a texture that contains the
+/// normal and depth of each pixel. The effects are computed from that texture
+/// in separate passes, which are blended over the image below:
+///
+/// - Outlines, at silhouettes, depth discontinuities, and sharp features.
+/// - Ambient occlusion, in creases and corners that light hardly reaches.
+#[derive(Debug)]
+pub struct ScreenSpaceRenderer {
+    normal_depth_pipeline: wgpu::RenderPipeline,
+    outline_pipeline: wgpu::RenderPipeline,
+    ambient_occlusion_pipeline: wgpu::RenderPipeline,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    scale_factor_buffer: wgpu::Buffer,
+    targets: Targets,
+}
+
+impl ScreenSpaceRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        uniform_bind_group_layout: &wgpu::BindGroupLayout,
+        config: &wgpu::SurfaceConfiguration,
+        scale_factor: f64,
+    ) -> Self {
+        let normal_depth_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Normal/Depth Pipeline Layout"),
+                bind_group_layouts: &[uniform_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let shaders = Shaders::new(device);
+        let shader = shaders.normal_depth();
+
+        let normal_depth_pipeline =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Normal/Depth Pipeline"),
+                layout: Some(&normal_depth_pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: shader.module,
+                    entry_point: "vertex",
+                    buffers: &[Vertex::layout()],
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    ..Default::default()
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::LessEqual,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: shader.module,
+                    entry_point: shader.frag_entry,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: NORMAL_DEPTH_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview: None,
+            });
+
+        let texture_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float {
+                                filterable: false,
+                            },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+                label: Some("normal_depth_bind_group_layout"),
+            });
+
+        let effect_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Screen-Space Effect Pipeline Layout"),
+                bind_group_layouts: &[&texture_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let effect_shader =
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Screen-Space Effect Shader"),
+                source: wgpu::ShaderSource::Wgsl(
+                    include_str!("screen_space.wgsl").into(),
+                ),
+            });
+
+        let effect_pipeline = |label, frag_entry, blend| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&effect_pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &effect_shader,
+                    entry_point: "vertex",
+                    buffers: &[],
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &effect_shader,
+                    entry_point: frag_entry,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: config.format,
+                        blend: Some(blend),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview: None,
+            })
+        };
+
+        // The color of the outlines depends on the theme. It's passed as the
+        // blend constant, which saves us from having to bind a buffer.
+        let outline_pipeline = effect_pipeline(
+            "Outline Pipeline",
+            "frag_outline",
+            wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Constant,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent::OVER,
+            },
+        );
+        let ambient_occlusion_pipeline = effect_pipeline(
+            "Ambient Occlusion Pipeline",
+            "frag_ambient_occlusion",
+            wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+        );
+
+        let scale_factor_buffer =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Scale Factor Buffer"),
+                contents: bytemuck::cast_slice(&scale_factor_uniform(
+                    scale_factor,
+                )),
+                usage: wgpu::BufferUsages::UNIFORM
+                    | wgpu::BufferUsages::COPY_DST,
+            });
+
+        let targets = Targets::new(
+            device,
+            &texture_bind_group_layout,
+            &scale_factor_buffer,
+            config,
+        );
+
+        Self {
+            normal_depth_pipeline,
+            outline_pipeline,
+            ambient_occlusion_pipeline,
+            texture_bind_group_layout,
+            scale_factor_buffer,
+            targets,
+        }
+    }
+
+    /// Recreate the textures, after the size of the surface has changed
+    pub fn handle_resize(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+    ) {
+        self.targets = Targets::new(
+            device,
+            &self.texture_bind_group_layout,
+            &self.scale_factor_buffer,
+            config,
+        );
+    }
+
+    /// Update the ratio of physical pixels to logical pixels
+    ///
+    /// The effects are specified in logical pixels, so outlines and the radius
+    /// of ambient occlusion look the same on high-DPI screens.
+    pub fn set_scale_factor(&self, queue: &wgpu::Queue, scale_factor: f64) {
+        queue.write_buffer(
+            &self.scale_factor_buffer,
+            0,
+            bytemuck::cast_slice(&scale_factor_uniform(scale_factor)),
+        );
+    }
+
+    pub fn draw(
+        &self,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+        views: &[View],
+        geometry: &Geometry,
+        effects: Effects,
+    ) {
+        if !effects.outline && !effects.ambient_occlusion {
+            return;
+        }
+
+        {
+            let mut render_pass =
+                encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    color_attachments: &[Some(
+                        wgpu::RenderPassColorAttachment {
+                            view: &self.targets.normal_depth_view,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(
+                                    wgpu::Color::TRANSPARENT,
+                                ),
+                                store: wgpu::StoreOp::Store,
+                            },
+                        },
+                    )],
+                    depth_stencil_attachment: Some(
+                        wgpu::RenderPassDepthStencilAttachment {
+                            view: &self.targets.depth_view,
+                            depth_ops: Some(wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(1.0),
+                                store: wgpu::StoreOp::Discard,
+                            }),
+                            stencil_ops: None,
+                        },
+                    ),
+                    ..Default::default()
+                });
+            render_pass.set_pipeline(&self.normal_depth_pipeline);
+            render_pass.set_vertex_buffer(0, geometry.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(
+                geometry.index_buffer.slice(..),
+                wgpu::IndexFormat::Uint32,
+            );
+            for view in views {
+                view.viewport.apply(&mut render_pass);
+                render_pass.set_bind_group(0, view.bind_group, &[]);
+                render_pass.draw_indexed(0..geometry.num_indices, 0, 0..1);
+            }
+        }
+
+        let mut render_pass =
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+        render_pass.set_bind_group(0, &self.targets.bind_group, &[]);
+
+        // Outlines go last, so they are drawn on top of the ambient occlusion.
+        if effects.ambient_occlusion {
+            render_pass.set_pipeline(&self.ambient_occlusion_pipeline);
+            render_pass.draw(0..3, 0..1);
+        }
+        if effects.outline {
+            render_pass.set_pipeline(&self.outline_pipeline);
+            render_pass.set_blend_constant(to_wgpu(effects.outline_color));
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+}
+
+/// The screen-space effects to draw
+#[derive(Clone, Copy, Debug)]
+pub struct Effects {
+    pub outline: bool,
+    pub outline_color: Color,
+    pub ambient_occlusion: bool,
+}
+
+/// The textures that the normal and depth of each pixel are rendered into
+#[derive(Debug)]
+struct Targets {
+    normal_depth_view: wgpu::TextureView,
+    depth_view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Targets {
+    fn new(
+        device: &wgpu::Device,
+        texture_bind_group_layout: &wgpu::BindGroupLayout,
+        scale_factor_buffer: &wgpu::Buffer,
+        config: &wgpu::SurfaceConfiguration,
+    ) -> Self {
+        let create_view = |format, usage| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: None,
+                    size: wgpu::Extent3d {
+                        width: config.width,
+                        height: config.height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+
+        let normal_depth_view = create_view(
+            NORMAL_DEPTH_FORMAT,
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING,
+        );
+        let depth_view =
+            create_view(DEPTH_FORMAT, wgpu::TextureUsages::RENDER_ATTACHMENT);
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(
+                        &normal_depth_view,
+                    ),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: scale_factor_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("normal_depth_bind_group"),
+        });
+
+        Self {
+            normal_depth_view,
+            depth_view,
+            bind_group,
+        }
+    }
+}
+
+/// The contents of the scale factor buffer
+///
+/// Uniform buffers are aligned to 16 bytes, hence the padding.
+fn scale_factor_uniform(scale_factor: f64) -> [f32; 4] {
+    [scale_factor as f32, 0., 0., 0.]
+}
@@ -0,0 +1,149 @@
+// Normals in the color channels, depth in the alpha channel. The depth of the
+// background is zero.
+@group(0) @binding(0)
+var normal_depth: texture_2d<f32>;
+
+// The ratio of physical pixels to logical pixels. Widths and distances below
+// are in logical pixels, and need to be multiplied with this.
+@group(0) @binding(1)
+var<uniform> scale_factor: f32;
+
+// Neighboring pixels whose normals differ by more than about 30 degrees are on
+// different sides of a sharp edge.
+const normal_threshold: f32 = 0.866;
+
+// Neighboring pixels whose depth differs by more than this fraction are on
+// different surfaces.
+const depth_threshold: f32 = 0.05;
+
+// The radius in which ambient occlusion is sampled, in pixels
+const occlusion_radius: f32 = 24.0;
+
+// The number of pairs of samples that ambient occlusion is computed from
+const occlusion_samples: i32 = 8;
+
+// Differences in depth, relative to the depth of the pixel, beyond which
+// neighbors don't occlude the pixel anymore. Without this, objects in the
+// foreground would darken everything behind them.
+const occlusion_range: f32 = 0.1;
+
+const pi: f32 = 3.14159265359;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+};
+
+@vertex
+fn vertex(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // A single triangle that covers the whole screen.
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    return out;
+}
+
+@fragment
+fn frag_outline(in: VertexOutput) -> @location(0) vec4<f32> {
+    let pixel = vec2<i32>(in.position.xy);
+    let center = load(pixel);
+
+    var offsets = array<vec2<i32>, 4>(
+        vec2<i32>(1, 0),
+        vec2<i32>(-1, 0),
+        vec2<i32>(0, 1),
+        vec2<i32>(0, -1),
+    );
+
+    // Outlines are one logical pixel wide.
+    let width = max(i32(round(scale_factor)), 1);
+
+    var edge = 0.0;
+    for (var i = 0; i < 4; i += 1) {
+        let neighbor = load(pixel + offsets[i] * width);
+        edge = max(edge, edge_between(center, neighbor));
+    }
+
+    // The pipeline multiplies this with the color of the outline, which is
+    // the blend constant, and blends it over the color below.
+    return vec4<f32>(edge);
+}
+
+@fragment
+fn frag_ambient_occlusion(in: VertexOutput) -> @location(0) vec4<f32> {
+    let pixel = vec2<i32>(in.position.xy);
+    let depth = load(pixel).w;
+
+    if depth <= 0.0 {
+        return vec4<f32>(0.0);
+    }
+
+    // Compare the depth of the pixel to pairs of neighbors on opposite sides.
+    // On a plane, the depth changes linearly, and the average depth of both
+    // neighbors matches the depth of the pixel. If the neighbors are closer to
+    // the camera on average, the pixel is in a crease.
+    var occlusion = 0.0;
+    var num_pairs = 0.0;
+    for (var i = 0; i < occlusion_samples; i += 1) {
+        let angle = f32(i) / f32(occlusion_samples) * pi;
+
+        // Vary the distance of the samples, to cover creases of different
+        // sizes.
+        let distance =
+            occlusion_radius * scale_factor * (f32(i % 4) + 1.0) / 4.0;
+        let offset =
+            vec2<i32>(vec2<f32>(cos(angle), sin(angle)) * distance);
+
+        let a = load(pixel + offset).w;
+        let b = load(pixel - offset).w;
+
+        // Neighbors on the background or on other objects say nothing about
+        // the surface of this one.
+        let range = depth * occlusion_range;
+        if a <= 0.0 || b <= 0.0 || abs(a - depth) > range
+            || abs(b - depth) > range {
+            continue;
+        }
+
+        occlusion += clamp((depth - (a + b) / 2.0) / range * 4.0, 0.0, 1.0);
+        num_pairs += 1.0;
+    }
+
+    if num_pairs == 0.0 {
+        return vec4<f32>(0.0);
+    }
+
+    // We use premultiplied alpha blending, so this darkens the color below.
+    let strength = 0.6;
+    return vec4<f32>(0.0, 0.0, 0.0, occlusion / num_pairs * strength);
+}
+
+fn load(pixel: vec2<i32>) -> vec4<f32> {
+    let size = vec2<i32>(textureDimensions(normal_depth));
+    return textureLoad(normal_depth, clamp(pixel, vec2<i32>(0, 0), size - 1), 0);
+}
+
+fn edge_between(a: vec4<f32>, b: vec4<f32>) -> f32 {
+    let a_is_model = a.w > 0.0;
+    let b_is_model = b.w > 0.0;
+
+    // Silhouette
+    if a_is_model != b_is_model {
+        return 1.0;
+    }
+    if !a_is_model {
+        return 0.0;
+    }
+
+    // Depth discontinuity, where one part of the model is in front of another
+    if abs(a.w - b.w) / min(a.w, b.w) > depth_threshold {
+        return 1.0;
+    }
+
+    // Sharp feature
+    if dot(a.xyz, b.xyz) < normal_threshold {
+        return 1.0;
+    }
+
+    return 0.0;
+}
@@ -1,21 +1,37 @@
 struct Uniforms {
     transform: mat4x4<f32>,
     transform_normals: mat4x4<f32>,
+    // Transforms from model space into the light's clip space
+    transform_light: mat4x4<f32>,
+    // Non-zero, if shadows are enabled
+    shadows: u32,
 };
 
 @group(0) @binding(0)
 var<uniform> uniforms: Uniforms;
 
+@group(0) @binding(1)
+var shadow_map: texture_depth_2d;
+
+@group(0) @binding(2)
+var shadow_sampler: sampler_comparison;
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) normal: vec3<f32>,
-    @location(2) color: vec4<f32>,
+    @location(2) surface_normal: vec3<f32>,
+    @location(3) color: vec4<f32>,
 }
 
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) normal: vec3<f32>,
-    @location(1) color: vec4<f32>,
+    @location(1) surface_normal: vec3<f32>,
+    @location(2) color: vec4<f32>,
+    // The distance from the camera, along its viewing direction
+    @location(3) depth: f32,
+    // The position in the light's clip space
+    @location(4) light_position: vec3<f32>,
 };
 
 struct FragmentOutput {
@@ -26,7 +42,12 @@ struct FragmentOutput {
 fn vertex(in: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     out.normal = (uniforms.transform_normals * vec4<f32>(in.normal, 0.0)).xyz;
+    out.surface_normal =
+        (uniforms.transform_normals * vec4<f32>(in.surface_normal, 0.0)).xyz;
     out.position = uniforms.transform * vec4<f32>(in.position, 1.0);
+    out.depth = out.position.w;
+    out.light_position =
+        (uniforms.transform_light * vec4<f32>(in.position, 1.0)).xyz;
     // We use premultiplied alpha blending.
     out.color = vec4<f32>(in.color.rgb * in.color.a, in.color.a);
 
@@ -45,14 +66,86 @@ fn frag_model(in: VertexOutput) -> FragmentOutput {
     let f_normal = max(1.0 - f_angle, 0.0);
 
     var out: FragmentOutput;
-    out.color = vec4<f32>(in.color.rgb * f_normal, in.color.a);
+    out.color = vec4<f32>(in.color.rgb * f_normal * shadow(in), in.color.a);
 
     return out;
 }
 
+// How much light reaches the fragment, from 0.0 for full shadow to 1.0
+fn shadow(in: VertexOutput) -> f32 {
+    if uniforms.shadows == 0u {
+        return 1.0;
+    }
+
+    let uv = vec2<f32>(
+        in.light_position.x * 0.5 + 0.5,
+        in.light_position.y * -0.5 + 0.5,
+    );
+    if any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0)) {
+        return 1.0;
+    }
+
+    let lit = textureSampleCompareLevel(
+        shadow_map,
+        shadow_sampler,
+        uv,
+        in.light_position.z,
+    );
+
+    // Shadows are not completely dark, as they are still lit by the light
+    // that comes from the camera.
+    return mix(0.6, 1.0, lit);
+}
+
 @fragment
 fn frag_mesh(in: VertexOutput) -> FragmentOutput {
     var out: FragmentOutput;
     out.color = vec4<f32>(1.0 - in.color.rgb, in.color.a);
     return out;
 }
+
+@fragment
+fn frag_edges(in: VertexOutput) -> FragmentOutput {
+    var out: FragmentOutput;
+    out.color = in.color;
+    return out;
+}
+
+// The number of stripes across the half-sphere of reflected directions
+const zebra_stripes: f32 = 12.0;
+
+@fragment
+fn frag_zebra(in: VertexOutput) -> FragmentOutput {
+    // Reflect the view direction on the exact surface normal, and look up the
+    // reflected direction in an environment of horizontal stripes. Where two
+    // surfaces meet with continuous tangents, the stripes connect. Where the
+    // curvature is continuous too, the stripes also keep their direction.
+    let view = vec3<f32>(0.0, 0.0, -1.0);
+    let reflected = reflect(view, normalize(in.surface_normal));
+
+    let elevation = asin(clamp(reflected.y, -1.0, 1.0));
+    let stripe = step(0.5, fract(elevation / pi * zebra_stripes));
+
+    // Keep a bit of shading, so the shape of the model remains visible.
+    let shading = 0.25 + 0.75 * abs(dot(view, normalize(in.normal)));
+    let brightness = mix(0.1, 1.0, stripe) * shading;
+
+    var out: FragmentOutput;
+    out.color = vec4<f32>(vec3<f32>(brightness), 1.0);
+
+    return out;
+}
+
+// Writes the data that screen-space effects are computed from: the normal in
+// the color channels, and the depth in the alpha channel.
+@fragment
+fn frag_normal_depth(in: VertexOutput) -> FragmentOutput {
+    var out: FragmentOutput;
+    out.color = vec4<f32>(normalize(in.normal), in.depth);
+    return out;
+}
+
+@vertex
+fn vertex_shadow(in: VertexInput) -> @builtin(position) vec4<f32> {
+    return uniforms.transform_light * vec4<f32>(in.position, 1.0);
+}
@@ -15,6 +15,10 @@ impl Shaders {
         Self(module)
     }
 
+    pub fn module(&self) -> &wgpu::ShaderModule {
+        &self.0
+    }
+
     pub fn model(&self) -> Shader {
         Shader {
             module: &self.0,
@@ -28,6 +32,27 @@ impl Shaders {
             frag_entry: "frag_mesh",
         }
     }
+
+    pub fn zebra(&self) -> Shader {
+        Shader {
+            module: &self.0,
+            frag_entry: "frag_zebra",
+        }
+    }
+
+    pub fn edges(&self) -> Shader {
+        Shader {
+            module: &self.0,
+            frag_entry: "frag_edges",
+        }
+    }
+
+    pub fn normal_depth(&self) -> Shader {
+        Shader {
+            module: &self.0,
+            frag_entry: "frag_normal_depth",
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -1,6 +1,8 @@
 use bytemuck::{Pod, Zeroable};
 
-use crate::camera::Camera;
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{camera::Camera, stereo::Eye};
 
 #[derive(Clone, Copy, Pod, Zeroable)]
 #[repr(transparent)]
@@ -15,10 +17,115 @@ impl Transform {
     ///
     /// The returned transform is used for transforming vertices on the GPU.
     pub fn for_vertices(camera: &Camera, aspect_ratio: f64) -> Self {
+        Self::project(camera.camera_to_model(), camera, aspect_ratio)
+    }
+
+    /// Compute transform used for the vertices, as seen by one eye
+    ///
+    /// Like [`Transform::for_vertices`], but the view is offset to the position
+    /// of the eye, and projected with its field of view, if it has one.
+    pub fn for_eye(camera: &Camera, eye: &Eye, aspect_ratio: f64) -> Self {
+        let view = fj_math::Transform::translation(-eye.offset)
+            * camera.camera_to_model();
+
+        let Some(field_of_view) = eye.field_of_view else {
+            return Self::project(view, camera, aspect_ratio);
+        };
+
+        let near = camera.near_plane();
+        let far = camera.far_plane();
+        let [left, right, up, down] = [
+            field_of_view.left,
+            field_of_view.right,
+            field_of_view.up,
+            field_of_view.down,
+        ]
+        .map(|angle| angle.tan() * near);
+
+        // An off-axis perspective projection. It follows the same conventions
+        // as the symmetric one that `project` uses, so both eyes behave the
+        // same way, regardless of their field of view.
+        #[rustfmt::skip]
+        let projection = nalgebra::Matrix4::new(
+            2. * near / (right - left), 0., (right + left) / (right - left), 0.,
+            0., 2. * near / (up - down), (up + down) / (up - down), 0.,
+            0., 0., (far + near) / (near - far), 2. * far * near / (near - far),
+            0., 0., -1., 0.,
+        );
+
+        let matrix = projection * view.get_inner().matrix();
+
+        let mut native = [0.0; 16];
+        native.copy_from_slice(matrix.as_slice());
+
+        Self(native.map(|val| val as f32))
+    }
+
+    /// Compute transform used for shadows
+    ///
+    /// The returned transform projects from model space into the clip space of
+    /// the light that casts shadows. The projection covers a sphere around the
+    /// model, defined by `center` and `radius`.
+    pub fn for_light(camera: &Camera, center: Point<3>, radius: f64) -> Self {
+        // The light comes from the upper left, from behind the camera. If it
+        // came from the camera itself, as the light that shades the model
+        // does, all shadows would be hidden behind the objects casting them.
+        let direction = camera
+            .rotation
+            .inverse()
+            .transform_vector(&Vector::from([1., -1., -1.]))
+            .normalize();
+
+        let eye = center - direction * (radius * 2.);
+        let up = if direction.x.abs() < Scalar::from(0.9) {
+            Vector::unit_x()
+        } else {
+            Vector::unit_y()
+        };
+        let view = nalgebra::Isometry3::look_at_rh(
+            &eye.to_na(),
+            &center.to_na(),
+            &up.to_na(),
+        );
+
+        let projection = nalgebra::Orthographic3::new(
+            -radius,
+            radius,
+            -radius,
+            radius,
+            radius,
+            radius * 3.,
+        );
+
+        // nalgebra projects depth into the range from -1 to 1, but wgpu
+        // expects 0 to 1.
+        #[rustfmt::skip]
+        let depth_range = nalgebra::Matrix4::new(
+            1., 0., 0., 0.,
+            0., 1., 0., 0.,
+            0., 0., 0.5, 0.5,
+            0., 0., 0., 1.,
+        );
+
+        let matrix =
+            depth_range * projection.to_homogeneous() * view.to_homogeneous();
+
+        let mut native = [0.0; 16];
+        native.copy_from_slice(matrix.as_slice());
+
+        Self(native.map(|val| val as f32))
+    }
+
+    /// Project a view with the field of view of the camera
+    fn project(
+        view: fj_math::Transform,
+        camera: &Camera,
+        aspect_ratio: f64,
+    ) -> Self {
         let field_of_view_in_y = 2.
             * ((camera.field_of_view_in_x() / 2.).tan() / aspect_ratio).atan();
 
-        let transform = camera.camera_to_model().project_to_array(
+        let transform = view.project_to_array(
             aspect_ratio,
             field_of_view_in_y,
             camera.near_plane(),
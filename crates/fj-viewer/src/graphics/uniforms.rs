@@ -7,6 +7,13 @@ use super::transform::Transform;
 pub struct Uniforms {
     pub transform: Transform,
     pub transform_normals: Transform,
+    pub transform_light: Transform,
+
+    /// Non-zero, if shadows are enabled
+    pub shadows: u32,
+
+    // The shader's uniforms are aligned to 16 bytes.
+    pub _padding: [u32; 3],
 }
 
 impl Default for Uniforms {
@@ -14,6 +21,9 @@ impl Default for Uniforms {
         Self {
             transform: Transform::identity(),
             transform_normals: Transform::identity(),
+            transform_light: Transform::identity(),
+            shadows: 0,
+            _padding: [0; 3],
         }
     }
 }
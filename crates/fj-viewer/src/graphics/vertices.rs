@@ -1,5 +1,8 @@
 use bytemuck::{Pod, Zeroable};
-use fj_interop::mesh::{Index, Mesh};
+use fj_interop::mesh::{Color, Index, Mesh};
+use fj_math::PolyChain;
+
+use crate::DraftAnalysis;
 
 #[derive(Debug)]
 pub struct Vertices {
@@ -24,26 +27,42 @@ impl Vertices {
     }
 }
 
-impl From<&Mesh<fj_math::Point<3>>> for Vertices {
-    fn from(mesh: &Mesh<fj_math::Point<3>>) -> Self {
+impl Vertices {
+    /// Build the vertices of a mesh, optionally colored by a draft analysis
+    ///
+    /// Uses the exact surface normals of the mesh's triangles, where available,
+    /// and falls back to the normals of the triangles themselves.
+    pub fn from_mesh(
+        mesh: &Mesh<fj_math::Point<3>>,
+        draft_analysis: Option<&DraftAnalysis>,
+    ) -> Self {
         let mut m = Mesh::new();
 
-        for triangle in mesh.triangles() {
-            let [a, b, c] = triangle.inner.points();
+        for (triangle, normals) in mesh.triangles_with_normals() {
+            let points = triangle.inner.points();
+            let [a, b, c] = points;
 
             let normal = (b - a).cross(&(c - a)).normalize();
-            let color = triangle.color;
+            let normals = normals.unwrap_or([normal; 3]);
 
-            m.push_vertex((a, normal, color));
-            m.push_vertex((b, normal, color));
-            m.push_vertex((c, normal, color));
+            for (point, surface_normal) in points.into_iter().zip(normals) {
+                let color = match draft_analysis {
+                    Some(draft_analysis) => {
+                        draft_analysis.color(surface_normal)
+                    }
+                    None => triangle.color,
+                };
+
+                m.push_vertex((point, normal, surface_normal, color));
+            }
         }
 
         let vertices = m
             .vertices()
-            .map(|(vertex, normal, color)| Vertex {
+            .map(|(vertex, normal, surface_normal, color)| Vertex {
                 position: vertex.into(),
                 normal: normal.into(),
+                surface_normal: surface_normal.into(),
                 color: color.0.map(|v| f32::from(v) / 255.0),
             })
             .collect();
@@ -54,10 +73,64 @@ impl From<&Mesh<fj_math::Point<3>>> for Vertices {
     }
 }
 
+impl Vertices {
+    /// Build the vertices of the model's edges, as a list of line segments
+    pub fn from_edges(edges: &[PolyChain<3>], color: Color) -> Self {
+        let color = color.0.map(|v| f32::from(v) / 255.0);
+
+        let vertices = edges
+            .iter()
+            .flat_map(|edge| edge.segments())
+            .flat_map(|segment| segment.points())
+            .map(|point| Vertex {
+                position: point.into(),
+                // Lines aren't lit, so their normals don't matter.
+                normal: [0.; 3],
+                surface_normal: [0.; 3],
+                color,
+            })
+            .collect::<Vec<_>>();
+
+        let indices = (0..vertices.len())
+            .map(|index| {
+                Index::try_from(index)
+                    .expect("`usize` couldn't be cast to `Index`")
+            })
+            .collect();
+
+        Self { vertices, indices }
+    }
+}
+
+impl From<&Mesh<fj_math::Point<3>>> for Vertices {
+    fn from(mesh: &Mesh<fj_math::Point<3>>) -> Self {
+        Self::from_mesh(mesh, None)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
 #[repr(C)]
 pub struct Vertex {
     pub position: [f32; 3],
     pub normal: [f32; 3],
+    pub surface_normal: [f32; 3],
     pub color: [f32; 4],
 }
+
+impl Vertex {
+    /// The layout of a buffer of vertices, as the shaders expect it
+    pub fn layout() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+            0 => Float32x3,
+            1 => Float32x3,
+            2 => Float32x3,
+            3 => Float32x4,
+        ];
+
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Vertex>() as u64,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
@@ -0,0 +1,145 @@
+/// The state of a gamepad
+///
+/// Unlike [`InputEvent`]s, which describe a change that is applied once, this
+/// describes input that is applied continuously, for as long as it lasts. Pass
+/// it to [`Viewer::handle_gamepad_input`] whenever it changes.
+///
+/// This type doesn't depend on any specific gamepad library. `fj-window` fills
+/// it in using `gilrs`, but other applications can use the library of their
+/// choice. All values are expected to be in the range from -1 to 1, or from 0
+/// to 1 for the triggers.
+///
+/// [`InputEvent`]: crate::InputEvent
+/// [`Viewer::handle_gamepad_input`]: crate::Viewer::handle_gamepad_input
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GamepadInput {
+    /// The left stick, which rotates the model around the focus point
+    ///
+    /// Positive values point right and up.
+    pub left_stick: [f64; 2],
+
+    /// The right stick, which moves the model
+    ///
+    /// Positive values point right and up.
+    pub right_stick: [f64; 2],
+
+    /// The left trigger, which zooms out
+    pub left_trigger: f64,
+
+    /// The right trigger, which zooms in
+    pub right_trigger: f64,
+}
+
+impl GamepadInput {
+    /// Stick deflections below this are ignored
+    ///
+    /// Sticks rarely return to exactly zero. Without this, the model would
+    /// drift slowly.
+    pub const DEAD_ZONE: f64 = 0.15;
+
+    /// The rotation at full deflection of the left stick, in radians per second
+    pub const ROTATION_SPEED: f64 = 2.;
+
+    /// The movement at full deflection of the right stick
+    ///
+    /// In normalized screen coordinates per second, meaning that at full
+    /// deflection, the model moves across half of the screen in a second.
+    pub const MOVEMENT_SPEED: f64 = 1.;
+
+    /// The zoom with a fully pressed trigger
+    ///
+    /// As a fraction of the distance to the focus point, per second.
+    pub const ZOOM_SPEED: f64 = 1.;
+
+    /// The left stick, with the dead zone applied
+    pub(crate) fn rotation(&self) -> [f64; 2] {
+        dead_zone(self.left_stick)
+    }
+
+    /// The right stick, with the dead zone applied
+    pub(crate) fn movement(&self) -> [f64; 2] {
+        dead_zone(self.right_stick)
+    }
+
+    /// The combined triggers, positive for zooming in
+    pub(crate) fn zoom(&self) -> f64 {
+        let zoom = self.right_trigger - self.left_trigger;
+        if zoom.abs() < Self::DEAD_ZONE {
+            return 0.;
+        }
+
+        zoom
+    }
+
+    /// Indicate whether the input moves the camera at all
+    pub(crate) fn is_active(&self) -> bool {
+        self.rotation() != [0.; 2]
+            || self.movement() != [0.; 2]
+            || self.zoom() != 0.
+    }
+}
+
+/// Rescale the stick, so its deflection starts at zero at the edge of the dead
+/// zone, instead of jumping there
+fn dead_zone(stick: [f64; 2]) -> [f64; 2] {
+    let [x, y] = stick;
+    let magnitude = x.hypot(y);
+    if magnitude < GamepadInput::DEAD_ZONE {
+        return [0.; 2];
+    }
+
+    let scale = ((magnitude - GamepadInput::DEAD_ZONE)
+        / (1. - GamepadInput::DEAD_ZONE))
+        .min(1.)
+        / magnitude;
+    [x * scale, y * scale]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GamepadInput;
+
+    #[test]
+    fn dead_zone() {
+        let input = |left_stick| GamepadInput {
+            left_stick,
+            ..GamepadInput::default()
+        };
+
+        // Inside of the dead zone, the stick is ignored.
+        let inside = input([0.1, -0.1]);
+        assert_eq!(inside.rotation(), [0.; 2]);
+        assert!(!inside.is_active());
+
+        // Beyond it, the deflection is rescaled to start at zero. Halfway
+        // between the dead zone and full deflection is half deflection.
+        let halfway = (1. + GamepadInput::DEAD_ZONE) / 2.;
+        let [x, y] = input([halfway, 0.]).rotation();
+        assert!((x - 0.5).abs() < 1e-12);
+        assert_eq!(y, 0.);
+
+        // The direction is kept, and full deflection stays at full deflection.
+        let [x, y] = input([0.6, 0.8]).rotation();
+        assert!((x - 0.6).abs() < 1e-12);
+        assert!((y - 0.8).abs() < 1e-12);
+
+        // Deflections beyond the range are capped.
+        let [x, y] = input([0., -2.]).rotation();
+        assert_eq!(x, 0.);
+        assert!((y + 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn zoom() {
+        let input = |left_trigger, right_trigger| GamepadInput {
+            left_trigger,
+            right_trigger,
+            ..GamepadInput::default()
+        };
+
+        assert_eq!(input(0., 0.1).zoom(), 0.);
+        assert_eq!(input(0., 0.5).zoom(), 0.5);
+        assert_eq!(input(0.75, 0.25).zoom(), -0.5);
+        assert!(input(0., 1.).is_active());
+    }
+}
@@ -1,28 +1,263 @@
-use super::{movement::Movement, rotation::Rotation, zoom::Zoom, InputEvent};
-use crate::camera::{Camera, FocusPoint};
+use std::time::Duration;
+
+use super::{
+    movement::Movement, rotation::Rotation, zoom::Zoom, GamepadInput,
+    InputEvent,
+};
+use crate::{
+    camera::{Camera, FocusPoint},
+    screen::NormalizedScreenPosition,
+};
 
 /// Input handling abstraction
 ///
 /// Takes user input and applies them to application state.
+///
+/// Rotation and zoom can optionally be smoothed. In that case, they are not
+/// applied right away, but spread over the following frames by
+/// [`InputHandler::update`]. Since the amount that is applied depends on the
+/// time that passed, not the number of frames, the camera moves the same way
+/// regardless of the frame rate.
 #[derive(Default)]
-pub struct InputHandler;
+pub struct InputHandler {
+    /// The time constant of the smoothing, in seconds
+    ///
+    /// After this time, about 63% of an input has been applied. `None`, if
+    /// input is applied right away.
+    smoothing: Option<f64>,
+
+    /// The rotation and zoom that haven't been applied yet
+    pending: Option<Pending>,
+
+    /// The latest state of the gamepad
+    gamepad: GamepadInput,
+}
 
 impl InputHandler {
+    /// Set the time constant of the smoothing, in seconds
+    ///
+    /// Pass `None` to disable smoothing.
+    pub fn set_smoothing(&mut self, smoothing: Option<f64>) {
+        self.smoothing = smoothing;
+    }
+
     /// Handle an input event
     pub fn handle_event(
+        &mut self,
         event: InputEvent,
         focus_point: FocusPoint,
         camera: &mut Camera,
     ) {
         match event {
+            // Translation follows the cursor, which only works without delay.
             InputEvent::Translation { previous, current } => {
                 Movement::apply(previous, current, focus_point, camera);
             }
             InputEvent::Rotation { angle_x, angle_y } => {
-                Rotation::apply(angle_x, angle_y, focus_point, camera);
+                if self.smoothing.is_some() {
+                    let pending = self.pending(focus_point);
+                    pending.angle_x += angle_x;
+                    pending.angle_y += angle_y;
+                } else {
+                    Rotation::apply(angle_x, angle_y, focus_point, camera);
+                }
             }
             InputEvent::Zoom(zoom_delta) => {
-                Zoom::apply(zoom_delta, focus_point, camera);
+                if self.smoothing.is_some() {
+                    // Each zoom scales the distance to the focus point by
+                    // `1 - zoom_delta`. Keeping track of the logarithm of
+                    // that factor means it can be split up over frames in
+                    // any way, with the same result. A zoom that would move
+                    // the camera past the focus point is clamped.
+                    self.pending(focus_point).zoom_scale +=
+                        (1. - zoom_delta).max(f64::EPSILON).ln();
+                } else {
+                    Zoom::apply(zoom_delta, focus_point, camera);
+                }
+            }
+        }
+    }
+
+    /// Apply the part of the pending input that is due after `delta_time`
+    ///
+    /// Call this once per frame, with the time since the previous frame. Does
+    /// nothing, if smoothing is disabled.
+    pub fn update(&mut self, delta_time: Duration, camera: &mut Camera) {
+        let Some(pending) = &mut self.pending else {
+            return;
+        };
+
+        // With smoothing disabled in the meantime, apply everything at once.
+        let fraction = match self.smoothing {
+            Some(smoothing) if smoothing > 0. => {
+                1. - (-delta_time.as_secs_f64() / smoothing).exp()
+            }
+            _ => 1.,
+        };
+
+        let [angle_x, angle_y, zoom_scale] =
+            [pending.angle_x, pending.angle_y, pending.zoom_scale]
+                .map(|value| value * fraction);
+
+        Rotation::apply(angle_x, angle_y, pending.focus_point, camera);
+        Zoom::apply(1. - zoom_scale.exp(), pending.focus_point, camera);
+
+        pending.angle_x -= angle_x;
+        pending.angle_y -= angle_y;
+        pending.zoom_scale -= zoom_scale;
+
+        if pending.is_negligible() {
+            self.pending = None;
+        }
+    }
+
+    /// Update the state of the gamepad
+    pub fn handle_gamepad(&mut self, gamepad: GamepadInput) {
+        self.gamepad = gamepad;
+    }
+
+    /// Apply the gamepad input that is due after `delta_time`
+    ///
+    /// Gamepad input is applied continuously, so unlike other input, this
+    /// needs to be called every frame, even if nothing has changed. Pass
+    /// `allow_rotation: false` to ignore the stick that rotates the model.
+    pub fn update_gamepad(
+        &mut self,
+        delta_time: Duration,
+        focus_point: FocusPoint,
+        allow_rotation: bool,
+        camera: &mut Camera,
+    ) {
+        let delta_time = delta_time.as_secs_f64();
+
+        if allow_rotation {
+            let [x, y] = self.gamepad.rotation();
+            let speed = GamepadInput::ROTATION_SPEED * delta_time;
+            if x != 0. || y != 0. {
+                Rotation::apply(-y * speed, x * speed, focus_point, camera);
+            }
+        }
+
+        let [x, y] = self.gamepad.movement();
+        if x != 0. || y != 0. {
+            let speed = GamepadInput::MOVEMENT_SPEED * delta_time;
+            let previous = NormalizedScreenPosition { x: 0., y: 0. };
+            let current = NormalizedScreenPosition {
+                x: x * speed,
+                y: y * speed,
+            };
+            Movement::apply(previous, current, focus_point, camera);
+        }
+
+        let zoom = self.gamepad.zoom();
+        if zoom != 0. {
+            let zoom_delta = zoom * GamepadInput::ZOOM_SPEED * delta_time;
+            Zoom::apply(zoom_delta, focus_point, camera);
+        }
+    }
+
+    /// Indicate whether there is pending input that moves the camera
+    pub fn is_moving(&self) -> bool {
+        self.pending.is_some() || self.gamepad.is_active()
+    }
+
+    /// Discard any pending input
+    pub fn stop(&mut self) {
+        self.pending = None;
+    }
+
+    fn pending(&mut self, focus_point: FocusPoint) -> &mut Pending {
+        let pending = self.pending.get_or_insert(Pending {
+            focus_point,
+            angle_x: 0.,
+            angle_y: 0.,
+            zoom_scale: 0.,
+        });
+
+        // Use the latest focus point, in case the user started a new gesture
+        // while the previous one was still being applied.
+        pending.focus_point = focus_point;
+        pending
+    }
+}
+
+struct Pending {
+    /// The focus point that rotation and zoom refer to
+    ///
+    /// It is stored here, as the viewer removes its focus point when the mouse
+    /// button is released, while input might still be pending.
+    focus_point: FocusPoint,
+
+    angle_x: f64,
+    angle_y: f64,
+
+    /// The logarithm of the factor, that scales the distance to the focus point
+    zoom_scale: f64,
+}
+
+impl Pending {
+    fn is_negligible(&self) -> bool {
+        const EPSILON: f64 = 1e-6;
+
+        [self.angle_x, self.angle_y, self.zoom_scale]
+            .iter()
+            .all(|value| value.abs() < EPSILON)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use fj_math::{Point, Transform};
+
+    use crate::{
+        camera::{Camera, FocusPoint},
+        input::InputEvent,
+    };
+
+    use super::InputHandler;
+
+    #[test]
+    fn smoothing_is_independent_of_frame_rate() {
+        let move_camera = |num_frames: u32| {
+            let mut camera = Camera::new();
+            camera.translation = Transform::translation([0., 0., 10.]);
+
+            let mut input_handler = InputHandler::default();
+            input_handler.set_smoothing(Some(0.1));
+
+            let focus_point = FocusPoint(Point::from([1., 2., 0.]));
+            for event in [
+                InputEvent::Rotation {
+                    angle_x: 0.5,
+                    angle_y: 0.3,
+                },
+                InputEvent::Zoom(0.4),
+            ] {
+                input_handler.handle_event(event, focus_point, &mut camera);
+            }
+
+            // The same total time, split into a different number of frames
+            let total_time = Duration::from_millis(150);
+            for _ in 0..num_frames {
+                input_handler.update(total_time / num_frames, &mut camera);
+            }
+
+            camera
+        };
+
+        let reference = move_camera(1);
+        for num_frames in [3, 10, 60] {
+            let camera = move_camera(num_frames);
+
+            for (a, b) in [
+                (&camera.rotation, &reference.rotation),
+                (&camera.translation, &reference.translation),
+            ] {
+                for (a, b) in a.data().iter().zip(b.data()) {
+                    assert!((a - b).abs() < 1e-9, "{num_frames} frames");
+                }
             }
         }
     }
@@ -1,9 +1,12 @@
 //! User input parsing and propagation.
 
 mod event;
+mod gamepad;
 mod handler;
 mod movement;
 mod rotation;
 mod zoom;
 
-pub use self::{event::InputEvent, handler::InputHandler};
+pub use self::{
+    event::InputEvent, gamepad::GamepadInput, handler::InputHandler,
+};
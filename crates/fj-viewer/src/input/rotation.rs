@@ -18,8 +18,11 @@ impl Rotation {
         let right_vector = right_vector(&camera_rotation);
         let up_vector = up_vector(&camera_rotation);
 
-        let rotation = Transform::rotation(right_vector * angle_x)
-            * Transform::rotation(up_vector * angle_y);
+        // Rotate around a single axis, instead of around both vectors one
+        // after the other. That way, splitting a rotation into parts, as input
+        // smoothing does, results in the same rotation.
+        let rotation =
+            Transform::rotation(right_vector * angle_x + up_vector * angle_y);
 
         let transform = camera.camera_to_model()
             * rotate_around
@@ -1,18 +1,32 @@
-use fj_math::{Transform, Vector};
+use fj_math::Transform;
 
 use crate::camera::{Camera, FocusPoint};
 
 pub struct Zoom;
 
 impl Zoom {
+    /// Move the camera towards or away from the focus point
+    ///
+    /// The camera moves along the line through the focus point, so the point
+    /// stays where it is on the screen. If the focus point is the point under
+    /// the cursor, this zooms towards the cursor.
     pub fn apply(
         zoom_delta: f64,
         focus_point: FocusPoint,
         camera: &mut Camera,
     ) {
-        let distance = (focus_point.0 - camera.position()).magnitude();
-        let displacement = zoom_delta * distance.into_f64();
-        camera.translation = camera.translation
-            * Transform::translation(Vector::from([0.0, 0.0, displacement]));
+        // The focus point in camera space, where the camera is at the origin.
+        let focus_point =
+            camera.camera_to_model().transform_point(&focus_point.0);
+
+        let distance = focus_point.coords.magnitude();
+        if distance.is_zero() {
+            return;
+        }
+
+        let displacement = -focus_point.coords.normalize()
+            * (zoom_delta * distance.into_f64());
+        camera.translation =
+            camera.translation * Transform::translation(displacement);
     }
 }
@@ -8,16 +8,25 @@
 //!
 //! [Fornjot]: https://www.fornjot.app/
 
+mod analysis;
 mod assets;
 mod camera;
 mod graphics;
 mod input;
+mod saved_view;
 mod screen;
+mod stereo;
+mod theme;
 mod viewer;
 
 pub use self::{
+    analysis::DraftAnalysis,
+    camera::{validate_field_of_view, ClippingPlanes, InvalidCameraConfig},
     graphics::{DeviceError, RendererInitError},
-    input::InputEvent,
+    input::{GamepadInput, InputEvent},
+    saved_view::{ParseSavedViewError, SavedView},
     screen::{NormalizedScreenPosition, Screen, ScreenSize},
-    viewer::Viewer,
+    stereo::{Eye, FieldOfView, Stereo},
+    theme::{Background, ParseBackgroundError, ParseThemeError, Theme},
+    viewer::{RedrawMode, Viewer},
 };
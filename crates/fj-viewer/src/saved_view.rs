@@ -0,0 +1,296 @@
+//! Saved views of a model
+
+use std::{fmt, str::FromStr};
+
+use fj_math::Transform;
+
+use crate::{
+    camera::{validate_field_of_view, Camera, ClippingPlanes},
+    graphics::DrawConfig,
+};
+
+/// A saved view of a model
+///
+/// Contains the state of the camera and the draw configuration, so the view can
+/// be restored later. The theme and background are not part of it, as they are
+/// preferences of the user, rather than properties of the view. See [`Viewer::save_view`] and [`Viewer::restore_view`].
+///
+/// Saved views can be converted into a string and parsed from one, so
+/// applications can persist them between sessions. The format is plain text,
+/// with one `key value` pair per line.
+///
+/// [`Viewer::save_view`]: crate::Viewer::save_view
+/// [`Viewer::restore_view`]: crate::Viewer::restore_view
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SavedView {
+    rotation: [f64; 16],
+    translation: [f64; 16],
+    draw_config: DrawConfig,
+}
+
+impl SavedView {
+    pub(crate) fn new(camera: &Camera, draw_config: DrawConfig) -> Self {
+        Self {
+            rotation: to_array(&camera.rotation),
+            translation: to_array(&camera.translation),
+            draw_config,
+        }
+    }
+
+    pub(crate) fn restore(&self, camera: &mut Camera) -> DrawConfig {
+        camera.rotation = from_array(&self.rotation);
+        camera.translation = from_array(&self.translation);
+        self.draw_config
+    }
+}
+
+impl fmt::Display for SavedView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (key, matrix) in [
+            ("rotation", &self.rotation),
+            ("translation", &self.translation),
+        ] {
+            write!(f, "{key}")?;
+            for value in matrix {
+                write!(f, " {value}")?;
+            }
+            writeln!(f)?;
+        }
+
+        let DrawConfig {
+            draw_model,
+            draw_mesh,
+            draw_edges,
+            draw_zebra,
+            draw_outline,
+            draw_shadows,
+            draw_ambient_occlusion,
+            field_of_view_in_x,
+            clipping_planes,
+        } = self.draw_config;
+        writeln!(f, "draw_model {draw_model}")?;
+        writeln!(f, "draw_mesh {draw_mesh}")?;
+        writeln!(f, "draw_edges {draw_edges}")?;
+        writeln!(f, "draw_zebra {draw_zebra}")?;
+        writeln!(f, "draw_outline {draw_outline}")?;
+        writeln!(f, "draw_shadows {draw_shadows}")?;
+        writeln!(f, "draw_ambient_occlusion {draw_ambient_occlusion}")?;
+        writeln!(f, "field_of_view_in_x {field_of_view_in_x}")?;
+        match clipping_planes {
+            ClippingPlanes::Automatic => {
+                writeln!(f, "clipping_planes automatic")?;
+            }
+            ClippingPlanes::Fixed { near, far } => {
+                writeln!(f, "clipping_planes {near} {far}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for SavedView {
+    type Err = ParseSavedViewError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rotation = None;
+        let mut translation = None;
+        let mut draw_config = DrawConfig::default();
+
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let invalid = || ParseSavedViewError::InvalidValue {
+                key: key.to_string(),
+                value: value.to_string(),
+            };
+
+            match key {
+                "rotation" => {
+                    rotation = Some(parse_matrix(value).ok_or_else(invalid)?);
+                }
+                "translation" => {
+                    translation =
+                        Some(parse_matrix(value).ok_or_else(invalid)?);
+                }
+                "draw_model" => {
+                    draw_config.draw_model =
+                        value.parse().map_err(|_| invalid())?;
+                }
+                "draw_mesh" => {
+                    draw_config.draw_mesh =
+                        value.parse().map_err(|_| invalid())?;
+                }
+                "draw_edges" => {
+                    draw_config.draw_edges =
+                        value.parse().map_err(|_| invalid())?;
+                }
+                "draw_zebra" => {
+                    draw_config.draw_zebra =
+                        value.parse().map_err(|_| invalid())?;
+                }
+                "draw_outline" => {
+                    draw_config.draw_outline =
+                        value.parse().map_err(|_| invalid())?;
+                }
+                "draw_shadows" => {
+                    draw_config.draw_shadows =
+                        value.parse().map_err(|_| invalid())?;
+                }
+                "draw_ambient_occlusion" => {
+                    draw_config.draw_ambient_occlusion =
+                        value.parse().map_err(|_| invalid())?;
+                }
+                "field_of_view_in_x" => {
+                    let field_of_view_in_x =
+                        value.parse().map_err(|_| invalid())?;
+                    validate_field_of_view(field_of_view_in_x)
+                        .map_err(|_| invalid())?;
+
+                    draw_config.field_of_view_in_x = field_of_view_in_x;
+                }
+                "clipping_planes" => {
+                    let clipping_planes =
+                        parse_clipping_planes(value).ok_or_else(invalid)?;
+                    clipping_planes.validate().map_err(|_| invalid())?;
+
+                    draw_config.clipping_planes = clipping_planes;
+                }
+                // Ignore unknown keys, so views that were saved by a newer
+                // version can still be restored.
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            rotation: rotation
+                .ok_or(ParseSavedViewError::MissingKey("rotation"))?,
+            translation: translation
+                .ok_or(ParseSavedViewError::MissingKey("translation"))?,
+            draw_config,
+        })
+    }
+}
+
+/// Error parsing a [`SavedView`]
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum ParseSavedViewError {
+    /// A required key is missing
+    #[error("Saved view is missing `{0}`")]
+    MissingKey(&'static str),
+
+    /// The value of a key could not be parsed
+    #[error("Invalid value for `{key}` in saved view: `{value}`")]
+    InvalidValue {
+        /// The key whose value could not be parsed
+        key: String,
+
+        /// The value that could not be parsed
+        value: String,
+    },
+}
+
+fn parse_matrix(value: &str) -> Option<[f64; 16]> {
+    let values = value
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<f64>, _>>()
+        .ok()?;
+
+    values.try_into().ok()
+}
+
+fn parse_clipping_planes(value: &str) -> Option<ClippingPlanes> {
+    if value == "automatic" {
+        return Some(ClippingPlanes::Automatic);
+    }
+
+    let (near, far) = value.split_once(' ')?;
+    Some(ClippingPlanes::Fixed {
+        near: near.parse().ok()?,
+        far: far.trim().parse().ok()?,
+    })
+}
+
+fn to_array(transform: &Transform) -> [f64; 16] {
+    let mut array = [0.; 16];
+    array.copy_from_slice(transform.data());
+    array
+}
+
+fn from_array(array: &[f64; 16]) -> Transform {
+    Transform::from(nalgebra::Transform::from_matrix_unchecked(
+        nalgebra::Matrix4::from_column_slice(array),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Transform, Vector};
+
+    use crate::{camera::ClippingPlanes, graphics::DrawConfig};
+
+    use super::{to_array, ParseSavedViewError, SavedView};
+
+    #[test]
+    fn round_trip() {
+        let saved_view = SavedView {
+            rotation: to_array(&Transform::rotation(Vector::from([
+                0.1, 0.2, 0.3,
+            ]))),
+            translation: to_array(&Transform::translation([1., -2., 3.5])),
+            draw_config: DrawConfig {
+                draw_model: false,
+                draw_mesh: true,
+                draw_edges: false,
+                draw_zebra: true,
+                draw_outline: false,
+                draw_shadows: true,
+                draw_ambient_occlusion: true,
+                field_of_view_in_x: 0.7,
+                clipping_planes: ClippingPlanes::Fixed {
+                    near: 0.01,
+                    far: 500.,
+                },
+            },
+        };
+
+        let parsed = saved_view.to_string().parse::<SavedView>();
+        assert_eq!(parsed, Ok(saved_view));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "translation".parse::<SavedView>(),
+            Err(ParseSavedViewError::InvalidValue {
+                key: "translation".into(),
+                value: String::new(),
+            })
+        );
+        assert_eq!(
+            "draw_model yes".parse::<SavedView>(),
+            Err(ParseSavedViewError::InvalidValue {
+                key: "draw_model".into(),
+                value: "yes".into(),
+            })
+        );
+        assert_eq!(
+            "".parse::<SavedView>(),
+            Err(ParseSavedViewError::MissingKey("rotation"))
+        );
+
+        for (key, value) in [
+            ("field_of_view_in_x", "0"),
+            ("clipping_planes", "0 10"),
+            ("clipping_planes", "10 1"),
+        ] {
+            assert_eq!(
+                format!("{key} {value}").parse::<SavedView>(),
+                Err(ParseSavedViewError::InvalidValue {
+                    key: key.into(),
+                    value: value.into(),
+                })
+            );
+        }
+    }
+}
@@ -10,6 +10,17 @@ pub trait Screen {
     /// Access the size of the screen
     fn size(&self) -> ScreenSize;
 
+    /// Access the ratio of physical pixels to logical pixels
+    ///
+    /// The size of the screen is in physical pixels. Anything that should look
+    /// the same size on screens of different pixel density is scaled by this.
+    ///
+    /// Defaults to `1.0`, so screens that don't know their pixel density don't
+    /// need to implement this.
+    fn scale_factor(&self) -> f64 {
+        1.0
+    }
+
     /// Access the window
     fn window(&self) -> &Self::Window;
 }
@@ -0,0 +1,78 @@
+//! Stereoscopic rendering
+
+use fj_math::Vector;
+
+/// Settings for stereoscopic rendering
+///
+/// In stereo, the model is drawn once for each eye, side by side. The view of
+/// the left eye goes into the left half of the screen, the view of the right
+/// eye into the right half. See [`Viewer::set_stereo`].
+///
+/// This is also the integration point for headsets. An application that talks
+/// to a headset, for example through OpenXR, can update the eyes with the views
+/// that the headset reports, before every frame. Distances are in model units,
+/// so if the positions of the eyes are converted into the unit of the model,
+/// the model appears at its true scale.
+///
+/// [`Viewer::set_stereo`]: crate::Viewer::set_stereo
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stereo {
+    /// The left eye
+    pub left: Eye,
+
+    /// The right eye
+    pub right: Eye,
+}
+
+impl Stereo {
+    /// Side-by-side stereo, for eyes that are `eye_separation` apart
+    ///
+    /// Both eyes use the field of view of the camera. `eye_separation` is in
+    /// model units. The average distance between human eyes is about 63 mm.
+    pub fn side_by_side(eye_separation: f64) -> Self {
+        let eye = |x| Eye {
+            offset: Vector::from([x, 0., 0.]),
+            field_of_view: None,
+        };
+
+        Self {
+            left: eye(-eye_separation / 2.),
+            right: eye(eye_separation / 2.),
+        }
+    }
+}
+
+/// The view of one eye
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Eye {
+    /// The position of the eye, relative to the camera, in camera space
+    ///
+    /// The camera looks along the negative z-axis, with the positive y-axis
+    /// pointing up.
+    pub offset: Vector<3>,
+
+    /// The field of view of the eye
+    ///
+    /// `None`, to use the field of view of the camera. Headsets usually report
+    /// a separate field of view for each eye, which is not symmetric.
+    pub field_of_view: Option<FieldOfView>,
+}
+
+/// A field of view that is not necessarily symmetric
+///
+/// All angles are in radians, measured from the direction that the eye looks
+/// in. Angles towards the left and down are negative, as in OpenXR.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FieldOfView {
+    /// The angle of the left edge
+    pub left: f64,
+
+    /// The angle of the right edge
+    pub right: f64,
+
+    /// The angle of the top edge
+    pub up: f64,
+
+    /// The angle of the bottom edge
+    pub down: f64,
+}
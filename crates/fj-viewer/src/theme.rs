@@ -0,0 +1,150 @@
+//! Color themes and backgrounds of the viewer
+
+use std::{fmt, str::FromStr};
+
+use fj_interop::mesh::Color;
+
+/// A color theme of the viewer
+///
+/// The theme determines the default background, and the colors of everything
+/// that is drawn on top of the model, like outlines.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Theme {
+    /// Dark overlays on a bright background
+    #[default]
+    Light,
+
+    /// Bright overlays on a dark background
+    Dark,
+}
+
+impl Theme {
+    /// The background that is used, unless another one has been configured
+    pub fn background(&self) -> Background {
+        match self {
+            Self::Light => Background::Solid(Color([255, 255, 255, 255])),
+            Self::Dark => Background::Gradient {
+                top: Color([72, 74, 80, 255]),
+                bottom: Color([24, 25, 28, 255]),
+            },
+        }
+    }
+
+    /// The color of outlines
+    pub fn outline(&self) -> Color {
+        match self {
+            Self::Light => Color([0, 0, 0, 255]),
+            Self::Dark => Color([230, 230, 230, 255]),
+        }
+    }
+
+    /// The respective other theme
+    pub fn toggled(&self) -> Self {
+        match self {
+            Self::Light => Self::Dark,
+            Self::Dark => Self::Light,
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Theme {
+    type Err = ParseThemeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "light" => Ok(Self::Light),
+            "dark" => Ok(Self::Dark),
+            _ => Err(ParseThemeError(s.to_string())),
+        }
+    }
+}
+
+/// The background behind the model
+///
+/// Backgrounds can be converted into a string and parsed from one. A solid
+/// background is written as a single color, like `#ffffff`. A gradient is
+/// written as two colors, the top one first, like `#484a50 #18191c`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Background {
+    /// A single color
+    Solid(Color),
+
+    /// A vertical gradient between two colors
+    Gradient {
+        /// The color at the top of the window
+        top: Color,
+
+        /// The color at the bottom of the window
+        bottom: Color,
+    },
+}
+
+impl fmt::Display for Background {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Solid(color) => write_color(f, color),
+            Self::Gradient { top, bottom } => {
+                write_color(f, top)?;
+                write!(f, " ")?;
+                write_color(f, bottom)
+            }
+        }
+    }
+}
+
+impl FromStr for Background {
+    type Err = ParseBackgroundError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseBackgroundError(s.to_string());
+
+        let colors = s
+            .split_whitespace()
+            .map(|color| parse_color(color).ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match colors.as_slice() {
+            [color] => Ok(Self::Solid(*color)),
+            [top, bottom] => Ok(Self::Gradient {
+                top: *top,
+                bottom: *bottom,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Error parsing a [`Theme`]
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[error("Unknown theme `{0}`; expected `light` or `dark`")]
+pub struct ParseThemeError(pub String);
+
+/// Error parsing a [`Background`]
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[error("Invalid background `{0}`; expected one or two `#rrggbb` colors")]
+pub struct ParseBackgroundError(pub String);
+
+fn write_color(f: &mut fmt::Formatter, color: &Color) -> fmt::Result {
+    let [r, g, b, _] = color.0;
+    write!(f, "#{r:02x}{g:02x}{b:02x}")
+}
+
+fn parse_color(s: &str) -> Option<Color> {
+    let hex = s.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color([channel(0)?, channel(2)?, channel(4)?, 255]))
+}
@@ -1,12 +1,20 @@
-use fj_interop::model::Model;
+use std::{collections::BTreeMap, mem, time::Duration};
+
+use fj_interop::{mesh::Mesh, model::Model};
+use fj_math::{Point, Vector};
 use tracing::warn;
 
 use crate::{
-    camera::{Camera, FocusPoint},
-    graphics::{DrawConfig, Renderer},
-    input::InputHandler,
-    InputEvent, NormalizedScreenPosition, RendererInitError, Screen,
-    ScreenSize,
+    camera::{
+        validate_field_of_view, Camera, ClippingPlanes, FocusPoint,
+        InvalidCameraConfig,
+    },
+    graphics::{DrawConfig, Renderer, Vertices},
+    input::{GamepadInput, InputHandler},
+    stereo::Stereo,
+    theme::{Background, Theme},
+    DraftAnalysis, InputEvent, NormalizedScreenPosition, RendererInitError,
+    SavedView, Screen, ScreenSize,
 };
 
 /// The Fornjot model viewer
@@ -14,9 +22,24 @@ pub struct Viewer {
     camera: Camera,
     cursor: Option<NormalizedScreenPosition>,
     draw_config: DrawConfig,
+    theme: Theme,
+    background: Option<Background>,
     focus_point: Option<FocusPoint>,
+    input_handler: InputHandler,
     renderer: Renderer,
     model: Option<Model>,
+    parameter_spaces: Vec<Model>,
+    parameter_space: Option<ParameterSpaceView>,
+    draft_analysis: Option<DraftAnalysis>,
+    saved_views: BTreeMap<String, SavedView>,
+    geometry_outdated: bool,
+    gpu_picking: bool,
+    stereo: Option<Stereo>,
+    redraw_mode: RedrawMode,
+    redraw_requested: bool,
+
+    /// The camera and draw configuration of the last frame that was drawn
+    last_frame: Option<SavedView>,
 }
 
 impl Viewer {
@@ -28,9 +51,22 @@ impl Viewer {
             camera: Camera::default(),
             cursor: None,
             draw_config: DrawConfig::default(),
+            theme: Theme::default(),
+            background: None,
             focus_point: None,
+            input_handler: InputHandler::default(),
             renderer,
             model: None,
+            parameter_spaces: Vec::new(),
+            parameter_space: None,
+            draft_analysis: None,
+            saved_views: BTreeMap::new(),
+            gpu_picking: true,
+            stereo: None,
+            redraw_mode: RedrawMode::default(),
+            redraw_requested: true,
+            last_frame: None,
+            geometry_outdated: false,
         })
     }
 
@@ -49,35 +85,468 @@ impl Viewer {
         self.draw_config.draw_mesh = !self.draw_config.draw_mesh;
     }
 
+    /// Toggle the "draw edges" setting
+    ///
+    /// The edges are drawn as lines, approximated from the exact geometry of
+    /// the model's half-edges.
+    pub fn toggle_draw_edges(&mut self) {
+        self.draw_config.draw_edges = !self.draw_config.draw_edges;
+    }
+
+    /// Toggle the "draw zebra" setting
+    ///
+    /// Zebra stripes are reflection lines, which show the continuity of
+    /// surfaces across the boundaries of faces.
+    pub fn toggle_draw_zebra(&mut self) {
+        self.draw_config.draw_zebra = !self.draw_config.draw_zebra;
+    }
+
+    /// Toggle the "draw outline" setting
+    ///
+    /// Outlines emphasize the silhouette and the sharp edges of the model,
+    /// which makes its shape easier to read.
+    pub fn toggle_draw_outline(&mut self) {
+        self.draw_config.draw_outline = !self.draw_config.draw_outline;
+    }
+
+    /// Toggle the "draw shadows" setting
+    ///
+    /// Shadows are cast by a light that shines from the upper left, and make
+    /// the depth of complex models easier to perceive.
+    pub fn toggle_draw_shadows(&mut self) {
+        self.draw_config.draw_shadows = !self.draw_config.draw_shadows;
+    }
+
+    /// Toggle the "draw ambient occlusion" setting
+    ///
+    /// Ambient occlusion darkens creases and corners of the model, which light
+    /// hardly reaches.
+    pub fn toggle_draw_ambient_occlusion(&mut self) {
+        self.draw_config.draw_ambient_occlusion =
+            !self.draw_config.draw_ambient_occlusion;
+    }
+
+    /// Set the horizontal field of view of the camera, in radians
+    ///
+    /// Returns an error, if the field of view is not larger than zero and
+    /// smaller than a half turn. The field of view is left unchanged then.
+    pub fn set_field_of_view(
+        &mut self,
+        field_of_view_in_x: f64,
+    ) -> Result<(), InvalidCameraConfig> {
+        validate_field_of_view(field_of_view_in_x)?;
+        self.draw_config.field_of_view_in_x = field_of_view_in_x;
+        Ok(())
+    }
+
+    /// Set how the clipping planes of the camera are determined
+    ///
+    /// Returns an error, if the clipping planes are invalid. See
+    /// [`ClippingPlanes::validate`]. They are left unchanged then.
+    pub fn set_clipping_planes(
+        &mut self,
+        clipping_planes: ClippingPlanes,
+    ) -> Result<(), InvalidCameraConfig> {
+        clipping_planes.validate()?;
+        self.draw_config.clipping_planes = clipping_planes;
+        Ok(())
+    }
+
+    /// Set the color theme
+    ///
+    /// Unlike the draw configuration, the theme is not part of saved views.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+
+        // The edges are colored according to the theme.
+        self.geometry_outdated = true;
+    }
+
+    /// Switch between the light and the dark theme
+    pub fn toggle_theme(&mut self) {
+        self.set_theme(self.theme.toggled());
+    }
+
+    /// Set the background behind the model
+    ///
+    /// Pass `None`, to use the background of the current theme. Like the
+    /// theme, the background is not part of saved views.
+    pub fn set_background(&mut self, background: Option<Background>) {
+        self.background = background;
+    }
+
+    /// Toggle the draft analysis overlay
+    ///
+    /// While the overlay is active, the model is colored according to the
+    /// draft angle of its surfaces. See [`DraftAnalysis`].
+    pub fn toggle_draft_analysis(&mut self) {
+        self.draft_analysis = match self.draft_analysis {
+            Some(_) => None,
+            None => Some(DraftAnalysis::default()),
+        };
+        self.geometry_outdated = true;
+    }
+
+    /// Switch the draft analysis to the next pull direction
+    ///
+    /// Cycles through the positive and negative directions of the z-, x-, and
+    /// y-axes. Does nothing, unless the draft analysis overlay is active.
+    pub fn cycle_pull_direction(&mut self) {
+        let Some(draft_analysis) = &mut self.draft_analysis else {
+            return;
+        };
+
+        let directions = [
+            Vector::unit_z(),
+            -Vector::unit_z(),
+            Vector::unit_x(),
+            -Vector::unit_x(),
+            Vector::unit_y(),
+            -Vector::unit_y(),
+        ];
+        let next = directions
+            .iter()
+            .position(|&direction| direction == draft_analysis.pull_direction)
+            .map_or(0, |i| (i + 1) % directions.len());
+
+        draft_analysis.pull_direction = directions[next];
+        self.geometry_outdated = true;
+    }
+
+    /// Access the draft analysis overlay, if it is active
+    pub fn draft_analysis(&self) -> Option<&DraftAnalysis> {
+        self.draft_analysis.as_ref()
+    }
+
+    /// Set the draft analysis overlay, or disable it by passing `None`
+    pub fn set_draft_analysis(
+        &mut self,
+        draft_analysis: Option<DraftAnalysis>,
+    ) {
+        self.draft_analysis = draft_analysis;
+        self.geometry_outdated = true;
+    }
+
+    /// Save the current view of the model under the provided name
+    ///
+    /// Saves the state of the camera and the draw configuration. A view that
+    /// was previously saved under the same name is replaced.
+    ///
+    /// While a parameter space is displayed, the view of the model that is
+    /// restored when toggling back is saved.
+    pub fn save_view(&mut self, name: impl Into<String>) -> SavedView {
+        let camera = match &self.parameter_space {
+            Some(view) => &view.model_camera,
+            None => &self.camera,
+        };
+
+        let saved_view = SavedView::new(camera, self.draw_config);
+        self.saved_views.insert(name.into(), saved_view);
+
+        saved_view
+    }
+
+    /// Restore the view of the model that was saved under the provided name
+    ///
+    /// Returns `false`, if no view has been saved under that name.
+    pub fn restore_view(&mut self, name: &str) -> bool {
+        let Some(saved_view) = self.saved_views.get(name) else {
+            warn!("No view saved as `{name}`");
+            return false;
+        };
+
+        let camera = match &mut self.parameter_space {
+            Some(view) => &mut view.model_camera,
+            None => &mut self.camera,
+        };
+        self.draw_config = saved_view.restore(camera);
+        self.input_handler.stop();
+
+        true
+    }
+
+    /// Access the saved views
+    ///
+    /// Applications can use this to persist the saved views between sessions,
+    /// by converting them into strings, and later parsing them again and
+    /// passing them to [`Viewer::insert_saved_view`].
+    pub fn saved_views(&self) -> &BTreeMap<String, SavedView> {
+        &self.saved_views
+    }
+
+    /// Add a saved view under the provided name
+    ///
+    /// Returns the view that was previously saved under that name, if any.
+    pub fn insert_saved_view(
+        &mut self,
+        name: impl Into<String>,
+        saved_view: SavedView,
+    ) -> Option<SavedView> {
+        self.saved_views.insert(name.into(), saved_view)
+    }
+
+    /// Remove the view that was saved under the provided name
+    ///
+    /// Returns the removed view, or `None`, if no view was saved under that
+    /// name.
+    pub fn remove_saved_view(&mut self, name: &str) -> Option<SavedView> {
+        self.saved_views.remove(name)
+    }
+
     /// Handle the model being updated
     pub fn handle_model_update(&mut self, model: Model) {
-        self.renderer.update_geometry((&model.mesh).into());
-
         let aabb = model.aabb;
         if self.model.replace(model).is_none() {
-            self.camera.init_planes(&aabb);
+            // If a parameter space is being displayed, the camera for the model
+            // has been put aside.
+            let camera = match &mut self.parameter_space {
+                Some(view) => &mut view.model_camera,
+                None => &mut self.camera,
+            };
+            camera.init_planes(&aabb);
+        }
+
+        self.geometry_outdated = true;
+    }
+
+    /// Handle the parameter spaces of the model's faces being updated
+    ///
+    /// Expects one model per face, that shows the face in its surface's
+    /// coordinate system. See [`Viewer::toggle_parameter_space`].
+    pub fn handle_parameter_space_update(
+        &mut self,
+        parameter_spaces: Vec<Model>,
+    ) {
+        self.parameter_spaces = parameter_spaces;
+
+        if let Some(view) = &mut self.parameter_space {
+            if self.parameter_spaces.is_empty() {
+                self.toggle_parameter_space();
+                return;
+            }
+
+            view.face = view.face.min(self.parameter_spaces.len() - 1);
+            self.geometry_outdated = true;
+        }
+    }
+
+    /// Toggle between displaying the model and the parameter space of a face
+    ///
+    /// While a parameter space is displayed, the view can be moved and zoomed,
+    /// but not rotated. The model's view is restored, when toggling back.
+    pub fn toggle_parameter_space(&mut self) {
+        self.input_handler.stop();
+
+        match self.parameter_space.take() {
+            Some(view) => {
+                self.camera = view.model_camera;
+            }
+            None => {
+                if self.parameter_spaces.is_empty() {
+                    warn!("No parameter spaces available");
+                    return;
+                }
+
+                self.parameter_space = Some(ParameterSpaceView {
+                    face: 0,
+                    model_camera: mem::take(&mut self.camera),
+                });
+                self.init_parameter_space_camera();
+            }
+        }
+
+        self.geometry_outdated = true;
+    }
+
+    /// Display the parameter space of the next face
+    ///
+    /// Does nothing, unless a parameter space is being displayed.
+    pub fn select_next_face(&mut self) {
+        self.select_face(|face, num_faces| (face + 1) % num_faces);
+    }
+
+    /// Display the parameter space of the previous face
+    ///
+    /// Does nothing, unless a parameter space is being displayed.
+    pub fn select_previous_face(&mut self) {
+        self.select_face(|face, num_faces| (face + num_faces - 1) % num_faces);
+    }
+
+    /// Access the index of the face whose parameter space is being displayed
+    pub fn selected_face(&self) -> Option<usize> {
+        self.parameter_space.as_ref().map(|view| view.face)
+    }
+
+    /// Handle a batch of triangles being added to the model
+    ///
+    /// This allows for displaying a model progressively, while it is still
+    /// being triangulated. The model must have been passed to
+    /// [`Viewer::handle_model_update`] before, possibly with an empty mesh.
+    ///
+    /// The geometry is uploaded to the GPU once per frame, regardless of how
+    /// many batches arrive within that frame.
+    pub fn handle_mesh_batch(&mut self, batch: Mesh<Point<3>>) {
+        let Some(model) = &mut self.model else {
+            warn!("Ignoring triangles, as no model has been set");
+            return;
+        };
+
+        for (triangle, normals) in batch.triangles_with_normals() {
+            match normals {
+                Some(normals) => model.mesh.push_triangle_with_normals(
+                    triangle.inner,
+                    normals,
+                    triangle.color,
+                ),
+                None => {
+                    model.mesh.push_triangle(triangle.inner, triangle.color)
+                }
+            }
         }
+        self.geometry_outdated = true;
     }
 
     /// Handle an input event
     pub fn handle_input_event(&mut self, event: InputEvent) {
-        if let Some(focus_point) = self.focus_point {
-            InputHandler::handle_event(event, focus_point, &mut self.camera);
+        // Parameter spaces are flat. Rotating them only gets in the way.
+        if self.parameter_space.is_some()
+            && matches!(event, InputEvent::Rotation { .. })
+        {
+            return;
+        }
+
+        // Zooming always refers to the point under the cursor, not to the
+        // focus point of a gesture that might be in progress.
+        let focus_point = match event {
+            InputEvent::Zoom(_) => self.focus_point_under_cursor(),
+            _ => self.focus_point,
+        };
+
+        if let Some(focus_point) = focus_point {
+            self.input_handler.handle_event(
+                event,
+                focus_point,
+                &mut self.camera,
+            );
+        }
+    }
+
+    /// Set whether rotation and zoom are smoothed
+    ///
+    /// Smoothed input is spread over the following frames, which makes the
+    /// camera come to rest gradually. After `time_constant` seconds, about 63%
+    /// of an input has been applied. Pass `None` to apply input right away,
+    /// which is the default.
+    pub fn set_input_smoothing(&mut self, time_constant: Option<f64>) {
+        self.input_handler.set_smoothing(time_constant);
+    }
+
+    /// Advance time-dependent state, like smoothed input
+    ///
+    /// Call this once per frame before [`Viewer::draw`], with the time that
+    /// passed since the previous frame.
+    pub fn update(&mut self, delta_time: Duration) {
+        self.input_handler.update(delta_time, &mut self.camera);
+
+        // A gamepad has no cursor that could point at the model. Rotating and
+        // zooming around its center is the most predictable alternative.
+        let focus_point = self.focus_point.or_else(|| {
+            self.displayed_model()
+                .map(|model| FocusPoint(model.aabb.center()))
+        });
+        if let Some(focus_point) = focus_point {
+            // Parameter spaces are flat. Rotating them only gets in the way.
+            let allow_rotation = self.parameter_space.is_none();
+
+            self.input_handler.update_gamepad(
+                delta_time,
+                focus_point,
+                allow_rotation,
+                &mut self.camera,
+            );
+        }
+    }
+
+    /// Handle the state of a gamepad changing
+    ///
+    /// The left stick rotates the model, the right stick moves it, and the
+    /// triggers zoom. The input is applied in [`Viewer::update`], for as long
+    /// as it lasts.
+    pub fn handle_gamepad_input(&mut self, input: GamepadInput) {
+        self.input_handler.handle_gamepad(input);
+    }
+
+    /// Set whether the GPU finds what is under the cursor
+    ///
+    /// When rotating or zooming, the viewer needs to know which point of the
+    /// model is under the cursor. By default, the GPU renders the ID of each
+    /// triangle, to find that out. This is much faster than casting a ray
+    /// against each triangle, which is used instead, if this is disabled.
+    pub fn set_gpu_picking(&mut self, gpu_picking: bool) {
+        self.gpu_picking = gpu_picking;
+    }
+
+    /// Enable or disable stereoscopic rendering
+    ///
+    /// Pass `None` to draw the model once, with the field of view of the
+    /// camera, which is the default. See [`Stereo`] for details.
+    pub fn set_stereo(&mut self, stereo: Option<Stereo>) {
+        self.stereo = stereo;
+        self.redraw_requested = true;
+    }
+
+    /// Set when the viewer needs to be redrawn
+    ///
+    /// See [`Viewer::needs_redraw`].
+    pub fn set_redraw_mode(&mut self, redraw_mode: RedrawMode) {
+        self.redraw_mode = redraw_mode;
+    }
+
+    /// Request that the viewer is redrawn, even if nothing seems to have changed
+    ///
+    /// Only has an effect in [`RedrawMode::OnDemand`].
+    pub fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+    }
+
+    /// Indicate whether [`Viewer::draw`] would change what is on screen
+    ///
+    /// Applications can use this to only draw, if the viewer has changed. This
+    /// saves power, while a static model is being displayed.
+    ///
+    /// Always returns `true` in [`RedrawMode::Continuous`].
+    pub fn needs_redraw(&self) -> bool {
+        match self.redraw_mode {
+            RedrawMode::Continuous => true,
+            RedrawMode::OnDemand => {
+                self.redraw_requested
+                    || self.geometry_outdated
+                    || self.input_handler.is_moving()
+                    || self.last_frame
+                        != Some(SavedView::new(&self.camera, self.draw_config))
+            }
         }
     }
 
     /// Handle the screen being resized
     pub fn handle_screen_resize(&mut self, screen_size: ScreenSize) {
         self.renderer.handle_resize(screen_size);
+        self.redraw_requested = true;
+    }
+
+    /// Handle the scale factor of the screen changing
+    ///
+    /// This happens when the window is moved to a screen with a different
+    /// pixel density. See [`Screen::scale_factor`].
+    pub fn handle_scale_factor_change(&mut self, scale_factor: f64) {
+        self.renderer.handle_scale_factor_change(scale_factor);
+        self.redraw_requested = true;
     }
 
     /// Compute and store a focus point, unless one is already stored
     pub fn add_focus_point(&mut self) {
-        if let Some(model) = &self.model {
-            if self.focus_point.is_none() {
-                self.focus_point =
-                    Some(self.camera.focus_point(self.cursor, model));
-            }
+        if self.focus_point.is_none() {
+            self.focus_point = self.focus_point_under_cursor();
         }
     }
 
@@ -89,15 +558,113 @@ impl Viewer {
     /// Draw the graphics
     pub fn draw(&mut self) {
         let aabb = self
-            .model
-            .as_ref()
+            .displayed_model()
             .map(|shape| shape.aabb)
             .unwrap_or_default();
 
-        self.camera.update_planes(&aabb);
+        self.camera
+            .set_field_of_view_in_x(self.draw_config.field_of_view_in_x);
+        self.camera
+            .update_planes(&aabb, self.draw_config.clipping_planes);
+
+        if self.geometry_outdated {
+            if let Some(model) = self.displayed_model() {
+                // Parameter spaces are flat, so the draft analysis would tell
+                // nothing about the model.
+                let draft_analysis = match self.parameter_space {
+                    Some(_) => None,
+                    None => self.draft_analysis.as_ref(),
+                };
+
+                let geometry = Vertices::from_mesh(&model.mesh, draft_analysis);
+                let edges =
+                    Vertices::from_edges(&model.edges, self.theme.outline());
+                self.renderer.update_geometry(geometry, edges);
+            }
+            self.geometry_outdated = false;
+        }
 
-        if let Err(err) = self.renderer.draw(&self.camera, &self.draw_config) {
+        if let Err(err) = self.renderer.draw(
+            &self.camera,
+            &self.draw_config,
+            self.theme,
+            self.background.unwrap_or_else(|| self.theme.background()),
+            self.stereo.as_ref(),
+        ) {
             warn!("Draw error: {}", err);
         }
+
+        self.redraw_requested = false;
+        self.last_frame = Some(SavedView::new(&self.camera, self.draw_config));
+    }
+
+    fn displayed_model(&self) -> Option<&Model> {
+        match &self.parameter_space {
+            Some(view) => self.parameter_spaces.get(view.face),
+            None => self.model.as_ref(),
+        }
+    }
+
+    fn focus_point_under_cursor(&self) -> Option<FocusPoint> {
+        let model = self.displayed_model()?;
+
+        // Picking can only find triangles that have been uploaded to the GPU.
+        // It also expects the model to be drawn once, across the whole screen.
+        let picked = match self.cursor {
+            Some(cursor)
+                if self.gpu_picking
+                    && !self.geometry_outdated
+                    && self.stereo.is_none() =>
+            {
+                self.renderer.pick(&self.camera, cursor)
+            }
+            _ => None,
+        };
+
+        Some(self.camera.focus_point(self.cursor, model, picked))
+    }
+
+    fn select_face(&mut self, next: impl FnOnce(usize, usize) -> usize) {
+        let Some(view) = &mut self.parameter_space else {
+            return;
+        };
+
+        view.face = next(view.face, self.parameter_spaces.len());
+        self.init_parameter_space_camera();
+        self.geometry_outdated = true;
+    }
+
+    fn init_parameter_space_camera(&mut self) {
+        self.input_handler.stop();
+        self.camera = Camera::default();
+        if let Some(model) = self.displayed_model() {
+            let aabb = model.aabb;
+            self.camera.init_planes(&aabb);
+        }
     }
 }
+
+/// When the viewer needs to be redrawn
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RedrawMode {
+    /// Redraw on every frame
+    ///
+    /// Useful for measuring performance, or for applications that draw other
+    /// content that changes, independently of the viewer.
+    Continuous,
+
+    /// Only redraw, if the view or the model have changed
+    ///
+    /// This is the default.
+    #[default]
+    OnDemand,
+}
+
+/// The parameter space of a face, as it is being displayed
+struct ParameterSpaceView {
+    /// The index of the face
+    face: usize,
+
+    /// The camera that displays the model, while it isn't displayed
+    model_camera: Camera,
+}
@@ -0,0 +1,273 @@
+//! # Fornjot WebAssembly Bindings
+//!
+//! [Fornjot] is an early-stage b-rep CAD kernel written in Rust. The kernel is
+//! split into multiple libraries that can be used semi-independently, and this
+//! is one of those.
+//!
+//! This library provides [wasm-bindgen] bindings for the kernel, so web
+//! applications can create shapes and triangulate them client-side. It is
+//! independent of `fj-viewer`. The resulting meshes are returned as typed
+//! arrays, which can be passed to WebGL, WebGPU, or any JavaScript 3D library
+//! directly.
+//!
+//! ``` js
+//! import init, { Kernel, Sketch } from "./fj_wasm.js";
+//!
+//! await init();
+//!
+//! const kernel = new Kernel();
+//! const sketch = new Sketch(0, 0)
+//!     .line_to(2, 0)
+//!     .line_to(2, 1)
+//!     .line_to(0, 1)
+//!     .close();
+//! const solid = kernel.sweep(sketch, 0, 0, 0.5);
+//!
+//! const mesh = kernel.triangulate(solid);
+//! const positions = mesh.vertices(); // `Float32Array`
+//! const indices = mesh.indices(); // `Uint32Array`
+//! ```
+//!
+//! [Fornjot]: https://www.fornjot.app/
+//! [wasm-bindgen]: https://rustwasm.github.io/wasm-bindgen/
+
+use fj_core::{
+    algorithms::bounding_volume::BoundingVolume, bindings, objects,
+    storage::Handle,
+};
+use fj_interop::mesh::Mesh;
+use fj_math::Point;
+use wasm_bindgen::prelude::*;
+
+/// The kernel, which creates shapes and operates on them
+///
+/// All shapes are validated as they are created. Validation errors are
+/// reported, when a shape is triangulated, or when calling `validate`
+/// explicitly.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Kernel {
+    inner: bindings::Kernel,
+}
+
+#[wasm_bindgen]
+impl Kernel {
+    /// Construct a new instance of `Kernel`
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sweep a sketch in the xy-plane along the provided path
+    pub fn sweep(
+        &mut self,
+        sketch: &Sketch,
+        x: f64,
+        y: f64,
+        z: f64,
+    ) -> Result<Solid, JsError> {
+        let handle = self.inner.sweep(&sketch.inner, [x, y, z])?;
+        Ok(Solid { handle })
+    }
+
+    /// Translate a solid by the provided offset
+    pub fn translate(
+        &mut self,
+        solid: &Solid,
+        x: f64,
+        y: f64,
+        z: f64,
+    ) -> Result<Solid, JsError> {
+        let handle = self.inner.translate(&solid.handle, [x, y, z])?;
+        Ok(Solid { handle })
+    }
+
+    /// Rotate a solid around the origin
+    ///
+    /// The direction of the vector defines the rotation axis, its length the
+    /// angle of the rotation, in radians.
+    pub fn rotate(
+        &mut self,
+        solid: &Solid,
+        x: f64,
+        y: f64,
+        z: f64,
+    ) -> Result<Solid, JsError> {
+        let handle = self.inner.rotate(&solid.handle, [x, y, z])?;
+        Ok(Solid { handle })
+    }
+
+    /// Return an error, if any shape that was created so far is invalid
+    pub fn validate(&mut self) -> Result<(), JsError> {
+        self.inner.validate()?;
+        Ok(())
+    }
+
+    /// Triangulate a solid
+    ///
+    /// If no `tolerance` is provided, a default is derived from the size of
+    /// the solid.
+    pub fn triangulate(
+        &mut self,
+        solid: &Solid,
+        tolerance: Option<f64>,
+    ) -> Result<TriangleMesh, JsError> {
+        let mesh = self.inner.triangulate(&solid.handle, tolerance)?;
+        Ok(TriangleMesh { mesh })
+    }
+}
+
+/// A sketch, defined by tracing its outline
+///
+/// Start a sketch by providing the coordinates of its first point. Every
+/// method returns a new sketch, which makes it possible to chain calls.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Sketch {
+    inner: bindings::Sketch,
+}
+
+#[wasm_bindgen]
+impl Sketch {
+    /// Start a sketch at the provided point
+    #[wasm_bindgen(constructor)]
+    pub fn new(x: f64, y: f64) -> Result<Sketch, JsError> {
+        let inner = bindings::Sketch::new([x, y])?;
+        Ok(Self { inner })
+    }
+
+    /// Start a new cycle at the provided point
+    pub fn move_to(&self, x: f64, y: f64) -> Result<Sketch, JsError> {
+        let inner = self.inner.move_to([x, y])?;
+        Ok(Self { inner })
+    }
+
+    /// Add a line from the current point to the provided one
+    pub fn line_to(&self, x: f64, y: f64) -> Result<Sketch, JsError> {
+        let inner = self.inner.line_to([x, y])?;
+        Ok(Self { inner })
+    }
+
+    /// Add an arc from the current point to the provided one
+    ///
+    /// The angle is in radians. Positive angles result in counter-clockwise
+    /// arcs, negative angles in clockwise ones.
+    pub fn arc_to(
+        &self,
+        x: f64,
+        y: f64,
+        angle: f64,
+    ) -> Result<Sketch, JsError> {
+        let inner = self.inner.arc_to([x, y], angle)?;
+        Ok(Self { inner })
+    }
+
+    /// Close the current cycle
+    pub fn close(&self) -> Result<Sketch, JsError> {
+        let inner = self.inner.close()?;
+        Ok(Self { inner })
+    }
+
+    /// Add a circle as a separate cycle
+    pub fn circle(
+        &self,
+        x: f64,
+        y: f64,
+        radius: f64,
+    ) -> Result<Sketch, JsError> {
+        let inner = self.inner.circle([x, y], radius)?;
+        Ok(Self { inner })
+    }
+}
+
+/// A solid, created by a [`Kernel`]
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Solid {
+    handle: Handle<objects::Solid>,
+}
+
+#[wasm_bindgen]
+impl Solid {
+    /// Compute the axis-aligned bounding box of the solid
+    ///
+    /// Returns the minimum and maximum corners of the bounding box, as six
+    /// consecutive coordinates, or `undefined`, if the solid is empty.
+    pub fn bounding_box(&self) -> Option<Vec<f64>> {
+        let aabb = self.handle.aabb()?;
+        let [min, max] = [aabb.min, aabb.max].map(<[f64; 3]>::from);
+        Some(min.into_iter().chain(max).collect())
+    }
+}
+
+/// A triangle mesh, as produced by [`Kernel::triangulate`]
+#[wasm_bindgen]
+pub struct TriangleMesh {
+    mesh: Mesh<Point<3>>,
+}
+
+#[wasm_bindgen]
+impl TriangleMesh {
+    /// Access the vertices of the mesh, as consecutive x, y, z coordinates
+    pub fn vertices(&self) -> Vec<f32> {
+        self.mesh.vertices().flat_map(<[f32; 3]>::from).collect()
+    }
+
+    /// Access the indices of the mesh
+    ///
+    /// Each consecutive group of three indices forms a triangle.
+    pub fn indices(&self) -> Vec<u32> {
+        self.mesh.indices().collect()
+    }
+
+    /// Access the colors of the triangles, as consecutive RGBA values
+    pub fn colors(&self) -> Vec<u8> {
+        self.mesh
+            .triangles()
+            .flat_map(|triangle| triangle.color.0)
+            .collect()
+    }
+}
+
+// These tests don't call any of the methods that return `JsError`, as creating
+// one requires a JavaScript runtime.
+#[cfg(test)]
+mod tests {
+    use fj_core::bindings;
+
+    use super::{Kernel, Sketch, Solid, TriangleMesh};
+
+    #[test]
+    fn triangulate_sketch() -> anyhow::Result<()> {
+        let mut kernel = Kernel::new();
+        let sketch = Sketch {
+            inner: bindings::Sketch::new([0., 0.])?
+                .line_to([2., 0.])?
+                .line_to([2., 1.])?
+                .line_to([0., 1.])?
+                .close()?,
+        };
+
+        let solid = Solid {
+            handle: kernel.inner.sweep(&sketch.inner, [0., 0., 0.5])?,
+        };
+        let mesh = TriangleMesh {
+            mesh: kernel.inner.triangulate(&solid.handle, None)?,
+        };
+
+        let vertices = mesh.vertices();
+        let indices = mesh.indices();
+        let colors = mesh.colors();
+
+        assert_eq!(vertices.len() % 3, 0);
+        assert_eq!(indices.len() % 3, 0);
+        assert_eq!(colors.len(), indices.len() / 3 * 4);
+        assert!(indices
+            .iter()
+            .all(|&index| (index as usize) < vertices.len() / 3));
+
+        assert_eq!(solid.bounding_box(), Some(vec![0., 0., 0., 2., 1., 0.5]),);
+
+        Ok(())
+    }
+}
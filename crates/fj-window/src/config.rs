@@ -0,0 +1,57 @@
+use fj_viewer::{
+    validate_field_of_view, Background, ClippingPlanes, InvalidCameraConfig,
+    Theme, Viewer,
+};
+
+/// Configuration of the window, and the viewer within it
+///
+/// Settings that are `None` keep the viewer's defaults.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Config {
+    /// Invert the direction in which the mouse wheel zooms
+    pub invert_zoom: bool,
+
+    /// The horizontal field of view of the camera, in radians
+    pub field_of_view_in_x: Option<f64>,
+
+    /// How the clipping planes of the camera are determined
+    pub clipping_planes: Option<ClippingPlanes>,
+
+    /// The color theme of the viewer
+    pub theme: Option<Theme>,
+
+    /// The background behind the model, instead of the one of the theme
+    pub background: Option<Background>,
+}
+
+impl Config {
+    /// Check whether the configuration is valid
+    pub fn validate(&self) -> Result<(), InvalidCameraConfig> {
+        if let Some(field_of_view_in_x) = self.field_of_view_in_x {
+            validate_field_of_view(field_of_view_in_x)?;
+        }
+        if let Some(clipping_planes) = self.clipping_planes {
+            clipping_planes.validate()?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn apply(
+        &self,
+        viewer: &mut Viewer,
+    ) -> Result<(), InvalidCameraConfig> {
+        if let Some(field_of_view_in_x) = self.field_of_view_in_x {
+            viewer.set_field_of_view(field_of_view_in_x)?;
+        }
+        if let Some(clipping_planes) = self.clipping_planes {
+            viewer.set_clipping_planes(clipping_planes)?;
+        }
+        if let Some(theme) = self.theme {
+            viewer.set_theme(theme);
+        }
+        viewer.set_background(self.background);
+
+        Ok(())
+    }
+}
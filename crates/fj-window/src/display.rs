@@ -1,7 +1,18 @@
-use fj_interop::model::Model;
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Instant,
+};
+
+use fj_interop::{mesh::Mesh, model::Model};
+use fj_math::Point;
 use fj_viewer::{
-    InputEvent, NormalizedScreenPosition, RendererInitError, Screen,
-    ScreenSize, Viewer,
+    InputEvent, InvalidCameraConfig, NormalizedScreenPosition,
+    RendererInitError, Screen, Viewer,
 };
 use futures::executor::block_on;
 use winit::{
@@ -11,23 +22,146 @@ use winit::{
         ElementState, Event, KeyEvent, MouseButton, MouseScrollDelta,
         WindowEvent,
     },
-    event_loop::EventLoop,
+    event_loop::{EventLoop, EventLoopBuilder, EventLoopProxy},
     keyboard::{Key, NamedKey},
 };
 
-use crate::window::{self, Window};
+#[cfg(feature = "gamepad")]
+use winit::event_loop::ControlFlow;
+
+#[cfg(feature = "gamepad")]
+use crate::gamepad::Gamepads;
+use crate::{
+    window::{self, Window},
+    Config,
+};
 
 /// Display the provided mesh in a window that processes input
-pub fn display(model: Model, invert_zoom: bool) -> Result<(), Error> {
-    let event_loop = EventLoop::new()?;
+pub fn display(model: Model, config: Config) -> Result<(), Error> {
+    display_progressive(model, config, |_| {})
+}
+
+/// Display a model, while its mesh is still being produced
+///
+/// Calls `produce_mesh` on a separate thread. It can use the provided
+/// [`MeshSender`] to send batches of triangles to the window, which adds them
+/// to the mesh of `model` as they arrive. This allows large models to appear
+/// progressively, instead of blocking until all of them has been triangulated.
+///
+/// Returns, once the window has been closed and `produce_mesh` has returned.
+pub fn display_progressive(
+    model: Model,
+    config: Config,
+    produce_mesh: impl FnOnce(MeshSender) + Send,
+) -> Result<(), Error> {
+    let event_loop = EventLoopBuilder::with_user_event().build()?;
+    let closed = Arc::new(AtomicBool::new(false));
+    let sender = MeshSender {
+        proxy: event_loop.create_proxy(),
+        closed: closed.clone(),
+    };
+
+    thread::scope(|scope| {
+        scope.spawn(move || produce_mesh(sender));
+
+        let result = run(event_loop, model, config);
+        closed.store(true, Ordering::Release);
+
+        result
+    })
+}
+
+/// Sends batches of triangles to a window, while it displays a model
+///
+/// See [`display_progressive`].
+pub struct MeshSender {
+    proxy: EventLoopProxy<ModelUpdate>,
+    closed: Arc<AtomicBool>,
+}
+
+impl MeshSender {
+    /// Send a batch of triangles to the window
+    ///
+    /// Returns an error, if the window has been closed. There's no point in
+    /// producing more triangles at that point.
+    pub fn send(&self, batch: Mesh<Point<3>>) -> Result<(), WindowClosed> {
+        self.proxy
+            .send_event(ModelUpdate::Batch(batch))
+            .map_err(|_| WindowClosed)
+    }
+
+    /// Replace the displayed model with a new one
+    ///
+    /// Batches of triangles that are sent afterwards are added to the new
+    /// model. The camera is left as it is, which makes this suitable for
+    /// reloading a model after it has changed.
+    ///
+    /// Returns an error, if the window has been closed.
+    pub fn replace(&self, model: Model) -> Result<(), WindowClosed> {
+        self.proxy
+            .send_event(ModelUpdate::Replace(model))
+            .map_err(|_| WindowClosed)
+    }
+
+    /// Send the parameter spaces of the model's faces to the window
+    ///
+    /// Expects one model per face, as created by `ParameterSpaces` from
+    /// `fj-core`. The window displays them on request, to help with debugging
+    /// the triangulation of a face.
+    ///
+    /// Returns an error, if the window has been closed.
+    pub fn send_parameter_spaces(
+        &self,
+        parameter_spaces: Vec<Model>,
+    ) -> Result<(), WindowClosed> {
+        self.proxy
+            .send_event(ModelUpdate::ParameterSpaces(parameter_spaces))
+            .map_err(|_| WindowClosed)
+    }
+
+    /// Indicate whether the window has been closed
+    ///
+    /// Useful for producers that wait for something before sending the next
+    /// update, and would otherwise keep the application from exiting.
+    pub fn is_window_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+}
+
+/// An update to the displayed model, sent through a [`MeshSender`]
+enum ModelUpdate {
+    Batch(Mesh<Point<3>>),
+    Replace(Model),
+    ParameterSpaces(Vec<Model>),
+}
+
+/// The window has been closed
+///
+/// Returned by [`MeshSender::send`] and [`MeshSender::replace`].
+#[derive(Debug, thiserror::Error)]
+#[error("Window has been closed")]
+pub struct WindowClosed;
+
+fn run(
+    event_loop: EventLoop<ModelUpdate>,
+    model: Model,
+    config: Config,
+) -> Result<(), Error> {
     let window = Window::new(&event_loop)?;
+    window
+        .window()
+        .set_title(&format!("Fornjot (unit: {})", model.unit));
     let mut viewer = block_on(Viewer::new(&window))?;
+    config.apply(&mut viewer)?;
 
     viewer.handle_model_update(model);
 
+    #[cfg(feature = "gamepad")]
+    let mut gamepads = Gamepads::new();
     let mut held_mouse_button = None;
-    let mut new_size = None;
+    let mut resized = false;
     let mut stop_drawing = false;
+    let mut last_frame = Instant::now();
 
     event_loop.run(move |event, event_loop_window_target| {
         let input_event = input_event(
@@ -35,7 +169,7 @@ pub fn display(model: Model, invert_zoom: bool) -> Result<(), Error> {
             &window,
             &held_mouse_button,
             viewer.cursor(),
-            invert_zoom,
+            config.invert_zoom,
         );
         if let Some(input_event) = input_event {
             viewer.handle_input_event(input_event);
@@ -70,16 +204,57 @@ pub fn display(model: Model, invert_zoom: bool) -> Result<(), Error> {
                 Key::Character("2") => {
                     viewer.toggle_draw_mesh();
                 }
+                Key::Character("3") => {
+                    viewer.toggle_parameter_space();
+                }
+                Key::Character("4") => {
+                    viewer.toggle_draft_analysis();
+                }
+                Key::Character("5") => {
+                    viewer.cycle_pull_direction();
+                }
+                Key::Character("6") => {
+                    viewer.toggle_draw_zebra();
+                }
+                Key::Character("7") => {
+                    viewer.toggle_draw_outline();
+                }
+                Key::Character("8") => {
+                    viewer.toggle_draw_shadows();
+                }
+                Key::Character("9") => {
+                    viewer.toggle_draw_ambient_occlusion();
+                }
+                Key::Character("0") => {
+                    viewer.toggle_theme();
+                }
+                Key::Character("e") => {
+                    viewer.toggle_draw_edges();
+                }
+                Key::Character("[") => {
+                    viewer.select_previous_face();
+                }
+                Key::Character("]") => {
+                    viewer.select_next_face();
+                }
                 _ => {}
             },
             Event::WindowEvent {
-                event: WindowEvent::Resized(size),
+                event: WindowEvent::Resized(_),
                 ..
             } => {
-                new_size = Some(ScreenSize {
-                    width: size.width,
-                    height: size.height,
-                });
+                resized = true;
+            }
+            Event::WindowEvent {
+                event: WindowEvent::ScaleFactorChanged { scale_factor, .. },
+                ..
+            } => {
+                viewer.handle_scale_factor_change(scale_factor);
+
+                // Not every platform sends a `Resized` event after the scale
+                // factor changed, even though the size in physical pixels has
+                // changed along with it.
+                resized = true;
             }
             Event::WindowEvent {
                 event: WindowEvent::MouseInput { state, button, .. },
@@ -94,12 +269,43 @@ pub fn display(model: Model, invert_zoom: bool) -> Result<(), Error> {
                     viewer.remove_focus_point();
                 }
             },
-            Event::WindowEvent {
-                event: WindowEvent::MouseWheel { .. },
-                ..
-            } => viewer.add_focus_point(),
+            Event::UserEvent(ModelUpdate::Batch(batch)) => {
+                viewer.handle_mesh_batch(batch);
+            }
+            Event::UserEvent(ModelUpdate::Replace(model)) => {
+                viewer.handle_model_update(model);
+            }
+            Event::UserEvent(ModelUpdate::ParameterSpaces(
+                parameter_spaces,
+            )) => {
+                viewer.handle_parameter_space_update(parameter_spaces);
+            }
             Event::AboutToWait => {
-                window.window().request_redraw();
+                #[cfg(feature = "gamepad")]
+                {
+                    if let Some(gamepad_input) = gamepads.poll() {
+                        viewer.handle_gamepad_input(gamepad_input);
+                    }
+
+                    // Gamepads don't wake up the event loop. Keep polling
+                    // them, for as long as one is connected.
+                    let control_flow = if gamepads.is_connected() {
+                        ControlFlow::wait_duration(Gamepads::POLL_INTERVAL)
+                    } else {
+                        ControlFlow::Wait
+                    };
+                    event_loop_window_target.set_control_flow(control_flow);
+                }
+
+                if resized || viewer.needs_redraw() {
+                    window.window().request_redraw();
+                } else {
+                    // Nothing is drawn while the viewer is idle. Don't count
+                    // that time towards the next frame, or the first frame
+                    // after the idle period would apply smoothed input all at
+                    // once.
+                    last_frame = Instant::now();
+                }
             }
             Event::WindowEvent {
                 event: WindowEvent::RedrawRequested,
@@ -107,13 +313,23 @@ pub fn display(model: Model, invert_zoom: bool) -> Result<(), Error> {
             } => {
                 // Only do a screen resize once per frame. This protects against
                 // spurious resize events that cause issues with the renderer.
-                if let Some(size) = new_size.take() {
+                //
+                // The size is queried from the window, instead of taken from
+                // the events, as the last event doesn't necessarily carry the
+                // current size. This happens when the window is moved between
+                // screens with different scale factors.
+                if mem::take(&mut resized) {
+                    let size = window.size();
                     stop_drawing = size.width == 0 || size.height == 0;
                     if !stop_drawing {
                         viewer.handle_screen_resize(size);
                     }
                 }
 
+                let now = Instant::now();
+                viewer.update(now - last_frame);
+                last_frame = now;
+
                 if !stop_drawing {
                     viewer.draw();
                 }
@@ -139,6 +355,10 @@ pub enum Error {
     /// Error initializing graphics
     #[error("Error initializing graphics")]
     Graphics(#[from] RendererInitError),
+
+    /// Invalid configuration of the camera
+    #[error("Invalid camera configuration")]
+    Camera(#[from] InvalidCameraConfig),
 }
 
 fn input_event<T>(
@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use fj_viewer::GamepadInput;
+use gilrs::{Axis, Button, Gilrs};
+use tracing::warn;
+
+/// Reads gamepads using `gilrs`, and converts their state into [`GamepadInput`]
+pub struct Gamepads {
+    gilrs: Option<Gilrs>,
+}
+
+impl Gamepads {
+    /// How often gamepads are polled, while one is connected
+    ///
+    /// `gilrs` doesn't wake up the event loop, so it needs to be polled
+    /// regularly. Polling stops while no gamepad is connected.
+    pub const POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+    pub fn new() -> Self {
+        // Gamepads are an optional convenience. If they are not available on
+        // this platform, the window should work regardless.
+        let gilrs = Gilrs::new()
+            .map_err(|err| warn!("Gamepads not available: {err}"))
+            .ok();
+
+        Self { gilrs }
+    }
+
+    /// Indicate whether any gamepad is connected
+    pub fn is_connected(&self) -> bool {
+        self.gilrs
+            .as_ref()
+            .is_some_and(|gilrs| gilrs.gamepads().next().is_some())
+    }
+
+    /// Process pending gamepad events
+    ///
+    /// Returns the new input of the gamepad that the latest event came from, if
+    /// there were any events.
+    pub fn poll(&mut self) -> Option<GamepadInput> {
+        let gilrs = self.gilrs.as_mut()?;
+
+        let mut latest = None;
+        while let Some(event) = gilrs.next_event() {
+            latest = Some(event.id);
+        }
+
+        let gamepad = gilrs.connected_gamepad(latest?);
+        let input = match gamepad {
+            Some(gamepad) => gamepad_input(
+                |axis| gamepad.value(axis),
+                |button| {
+                    gamepad
+                        .button_data(button)
+                        .map(|data| data.value())
+                        .unwrap_or(0.)
+                },
+            ),
+            // The gamepad was disconnected. Don't leave the model spinning.
+            None => GamepadInput::default(),
+        };
+
+        Some(input)
+    }
+}
+
+/// Map the axes and triggers of a gamepad to [`GamepadInput`]
+///
+/// `gilrs` already reports stick axes with positive values pointing right and
+/// up, which is what [`GamepadInput`] expects.
+fn gamepad_input(
+    axis: impl Fn(Axis) -> f32,
+    button: impl Fn(Button) -> f32,
+) -> GamepadInput {
+    let value = |value: f32| f64::from(value).clamp(-1., 1.);
+    let trigger = |value: f32| f64::from(value).clamp(0., 1.);
+
+    GamepadInput {
+        left_stick: [
+            value(axis(Axis::LeftStickX)),
+            value(axis(Axis::LeftStickY)),
+        ],
+        right_stick: [
+            value(axis(Axis::RightStickX)),
+            value(axis(Axis::RightStickY)),
+        ],
+        left_trigger: trigger(button(Button::LeftTrigger2)),
+        right_trigger: trigger(button(Button::RightTrigger2)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_viewer::GamepadInput;
+    use gilrs::{Axis, Button};
+
+    use super::gamepad_input;
+
+    #[test]
+    fn axis_mapping() {
+        let input = gamepad_input(
+            |axis| match axis {
+                Axis::LeftStickX => 0.25,
+                Axis::LeftStickY => -0.5,
+                Axis::RightStickX => -1.5,
+                Axis::RightStickY => 1.,
+                _ => 0.,
+            },
+            |button| match button {
+                Button::LeftTrigger2 => 0.75,
+                Button::RightTrigger2 => -0.5,
+                _ => 1.,
+            },
+        );
+
+        assert_eq!(
+            input,
+            GamepadInput {
+                left_stick: [0.25, -0.5],
+                right_stick: [-1., 1.],
+                left_trigger: 0.75,
+                right_trigger: 0.,
+            }
+        );
+    }
+}
@@ -4,14 +4,20 @@
 //! split into multiple libraries that can be used semi-independently, and this
 //! is one of those.
 //!
-//! This library provides a window abstraction based on Winit.
+//! This library provides a window abstraction based on Winit. If the `gamepad`
+//! feature is enabled, gamepads are read using gilrs. It is disabled by
+//! default, as gilrs requires the libudev headers to build on Linux.
 //!
 //! [Fornjot]: https://www.fornjot.app/
 
+mod config;
 mod display;
+#[cfg(feature = "gamepad")]
+mod gamepad;
 mod window;
 
 pub use self::{
-    display::{display, Error},
+    config::Config,
+    display::{display, display_progressive, Error, MeshSender, WindowClosed},
     window::WindowError,
 };
@@ -49,6 +49,10 @@ impl Screen for Window {
         }
     }
 
+    fn scale_factor(&self) -> f64 {
+        self.0.scale_factor()
+    }
+
     fn window(&self) -> &winit::window::Window {
         &self.0
     }
@@ -1,4 +1,4 @@
-use std::{num::ParseFloatError, path::PathBuf, str::FromStr};
+use std::{net::SocketAddr, num::ParseFloatError, path::PathBuf, str::FromStr};
 
 use fj_core::algorithms::approx::{InvalidTolerance, Tolerance};
 use fj_math::Scalar;
@@ -16,10 +16,28 @@ use fj_math::Scalar;
 /// [`handle_model`]: crate::handle_model()
 #[derive(clap::Parser)]
 pub struct Args {
+    /// What to do instead of displaying the model
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Export model to this path
     #[arg(short, long, value_name = "PATH")]
     pub export: Option<PathBuf>,
 
+    /// Display model, colored by how it differs from the reference at this path
+    ///
+    /// Added material is shown in green, removed material in red. The
+    /// reference is a snapshot of a previous version of the model, as created
+    /// by `compare --save`.
+    #[arg(long, value_name = "PATH")]
+    pub diff: Option<PathBuf>,
+
+    /// Serve model over HTTP at this address, instead of displaying it
+    ///
+    /// See the `server` module for the protocol.
+    #[arg(long, value_name = "ADDRESS")]
+    pub serve: Option<SocketAddr>,
+
     /// How much the export can deviate from the original model
     #[arg(short, long, value_parser = parse_tolerance)]
     pub tolerance: Option<Tolerance>,
@@ -27,6 +45,37 @@ pub struct Args {
     /// Ignore validation errors
     #[arg(short, long)]
     pub ignore_validation: bool,
+
+    /// Make the parameter spaces of the model's faces available in the viewer
+    ///
+    /// Press `3` to switch between the model and the parameter space of a
+    /// face, and `[` or `]` to select the previous or next face.
+    #[arg(long)]
+    pub parameter_spaces: bool,
+
+    /// Re-use approximations from the cache at this path, and update it
+    ///
+    /// This saves approximating geometry that hasn't changed since the last
+    /// run. Only models that are loaded at runtime, like model plugins, make
+    /// use of this.
+    #[arg(long, value_name = "PATH")]
+    pub approx_cache: Option<PathBuf>,
+
+    /// Print how much time processing the model spent in each kernel operation
+    #[arg(long)]
+    pub timings: bool,
+
+    /// Override a parameter that the model declares
+    ///
+    /// Only models that declare parameters, like model plugins, make use of
+    /// this.
+    #[arg(
+        short,
+        long = "parameter",
+        value_name = "NAME=VALUE",
+        value_parser = parse_parameter
+    )]
+    pub parameters: Vec<(String, f64)>,
 }
 
 impl Args {
@@ -39,6 +88,25 @@ impl Args {
     }
 }
 
+/// A command, that is run instead of displaying the model
+#[derive(clap::Subcommand)]
+pub enum Command {
+    /// Compare model to a reference, failing if it deviates
+    ///
+    /// The reference is a snapshot, as created by the `snapshot` module.
+    Compare {
+        /// The path of the reference
+        #[arg(value_name = "PATH")]
+        reference: PathBuf,
+
+        /// Save the model as the reference, instead of comparing to it
+        ///
+        /// Overwrites the reference, if it exists already.
+        #[arg(long)]
+        save: bool,
+    },
+}
+
 fn parse_tolerance(input: &str) -> Result<Tolerance, ArgsError> {
     let tolerance = f64::from_str(input)?;
     let tolerance = Scalar::from_f64(tolerance);
@@ -47,8 +115,20 @@ fn parse_tolerance(input: &str) -> Result<Tolerance, ArgsError> {
     Ok(tolerance)
 }
 
+fn parse_parameter(input: &str) -> Result<(String, f64), ArgsError> {
+    let parameter = input.split_once('=').and_then(|(name, value)| {
+        let value = f64::from_str(value.trim()).ok()?;
+        Some((name.trim().to_owned(), value))
+    });
+
+    parameter.ok_or_else(|| ArgsError::ParseParameter(input.to_owned()))
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ArgsError {
+    #[error("Expected parameter in the form `NAME=VALUE`, got `{0}`")]
+    ParseParameter(String),
+
     #[error("Error parsing tolerance")]
     ParseTolerance(#[from] ParseFloatError),
 
@@ -0,0 +1,217 @@
+//! # Configuration file
+//!
+//! Fornjot reads its configuration from `fj.toml` in the current directory, if
+//! that file exists. All settings are optional:
+//!
+//! ``` toml
+//! # Invert the direction in which the mouse wheel zooms.
+//! invert_zoom = true
+//!
+//! # The horizontal field of view of the camera, in degrees.
+//! field_of_view = 60.0
+//!
+//! # Fixed distances to the clipping planes of the camera. By default, they are
+//! # fitted to the model in every frame.
+//! clipping_planes = { near = 0.1, far = 1000.0 }
+//!
+//! # The color theme of the viewer, `light` or `dark`.
+//! theme = "dark"
+//!
+//! # The background behind the model, instead of the one of the theme. Either
+//! # one color, or the top and bottom colors of a gradient.
+//! background = "#606060 #202020"
+//! ```
+
+use std::{fmt, fs, io, path::Path, str::FromStr};
+
+use fj_viewer::{Background, ClippingPlanes, InvalidCameraConfig, Theme};
+use serde::{de, Deserialize, Deserializer};
+
+/// The configuration, as loaded from the configuration file
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Invert the direction in which the mouse wheel zooms
+    #[serde(default)]
+    pub invert_zoom: bool,
+
+    /// The horizontal field of view of the camera, in degrees
+    pub field_of_view: Option<f64>,
+
+    /// Fixed distances to the clipping planes of the camera
+    pub clipping_planes: Option<ClippingPlanesConfig>,
+
+    /// The color theme of the viewer
+    #[serde(default, deserialize_with = "from_str")]
+    pub theme: Option<Theme>,
+
+    /// The background behind the model, instead of the one of the theme
+    #[serde(default, deserialize_with = "from_str")]
+    pub background: Option<Background>,
+}
+
+impl Config {
+    /// The name of the configuration file
+    pub const FILE_NAME: &'static str = "fj.toml";
+
+    /// Load the configuration file from the current directory
+    ///
+    /// Returns the default configuration, if there is no configuration file.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(Path::new(Self::FILE_NAME))
+    }
+
+    /// Load the configuration file from the provided path
+    ///
+    /// Returns the default configuration, if the file doesn't exist.
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        Self::from_toml(&source)
+    }
+
+    /// Parse the configuration from TOML
+    ///
+    /// Returns an error, if the camera settings are invalid.
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(source)?;
+
+        // Validate the settings right away, instead of when the window opens.
+        config.window_config().validate()?;
+
+        Ok(config)
+    }
+
+    /// Convert the configuration into the configuration of the window
+    pub fn window_config(&self) -> fj_window::Config {
+        fj_window::Config {
+            invert_zoom: self.invert_zoom,
+            field_of_view_in_x: self.field_of_view.map(f64::to_radians),
+            clipping_planes: self.clipping_planes.map(
+                |ClippingPlanesConfig { near, far }| ClippingPlanes::Fixed {
+                    near,
+                    far,
+                },
+            ),
+            theme: self.theme,
+            background: self.background,
+        }
+    }
+}
+
+/// Fixed distances to the clipping planes of the camera
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClippingPlanesConfig {
+    /// The distance to the near plane
+    pub near: f64,
+
+    /// The distance to the far plane
+    pub far: f64,
+}
+
+fn from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let value = String::deserialize(deserializer)?;
+    value.parse().map(Some).map_err(de::Error::custom)
+}
+
+/// Error loading the configuration file
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// Failed to read the file
+    #[error("Failed to read configuration file")]
+    Io(#[from] io::Error),
+
+    /// Failed to parse the file
+    #[error("Failed to parse configuration file")]
+    Toml(#[from] toml::de::Error),
+
+    /// The camera settings are invalid
+    #[error("Invalid configuration: {0}")]
+    Camera(#[from] InvalidCameraConfig),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_3;
+
+    use fj_interop::mesh::Color;
+    use fj_viewer::{Background, ClippingPlanes, InvalidCameraConfig, Theme};
+
+    use super::{Config, ConfigError};
+
+    #[test]
+    fn window_config() -> anyhow::Result<()> {
+        let config = Config::from_toml(
+            r##"
+                invert_zoom = true
+                field_of_view = 60.0
+                clipping_planes = { near = 0.1, far = 1000.0 }
+                theme = "dark"
+                background = "#ff0000"
+            "##,
+        )?
+        .window_config();
+
+        assert!(config.invert_zoom);
+        assert!(config
+            .field_of_view_in_x
+            .is_some_and(|fov| (fov - FRAC_PI_3).abs() < 1e-12));
+        assert_eq!(
+            config.clipping_planes,
+            Some(ClippingPlanes::Fixed {
+                near: 0.1,
+                far: 1000.0
+            })
+        );
+        assert_eq!(config.theme, Some(Theme::Dark));
+        assert_eq!(
+            config.background,
+            Some(Background::Solid(Color([255, 0, 0, 255])))
+        );
+
+        let config = Config::from_toml("")?.window_config();
+        assert!(!config.invert_zoom);
+        assert_eq!(config.field_of_view_in_x, None);
+        assert_eq!(config.clipping_planes, None);
+        assert_eq!(config.theme, None);
+        assert_eq!(config.background, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_config() {
+        assert!(matches!(
+            Config::from_toml("field_of_view = 0.0"),
+            Err(ConfigError::Camera(InvalidCameraConfig::FieldOfView(_)))
+        ));
+        assert!(matches!(
+            Config::from_toml("clipping_planes = { near = 0.0, far = 1.0 }"),
+            Err(ConfigError::Camera(InvalidCameraConfig::NearPlane(_)))
+        ));
+        assert!(matches!(
+            Config::from_toml("clipping_planes = { near = 2.0, far = 1.0 }"),
+            Err(ConfigError::Camera(InvalidCameraConfig::FarPlane { .. }))
+        ));
+        assert!(matches!(
+            Config::from_toml("invert_zom = true"),
+            Err(ConfigError::Toml(_))
+        ));
+        assert!(matches!(
+            Config::from_toml(r#"theme = "blue""#),
+            Err(ConfigError::Toml(_))
+        ));
+    }
+}
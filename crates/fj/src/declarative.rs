@@ -0,0 +1,495 @@
+//! # Declarative model descriptions
+//!
+//! Simple models don't need the full power of Rust. This module can load a
+//! declarative description of a model from JSON or TOML, and build it using the
+//! kernel. This makes it possible to define models without compiling any Rust
+//! code, and to generate them from other tools.
+//!
+//! A model consists of any number of solids. Each solid is created from a
+//! primitive or by sweeping a sketch, and then modified by a list of
+//! operations:
+//!
+//! ``` toml
+//! [[solids]]
+//! box = { size = [4.0, 2.0, 1.0] }
+//! operations = [{ translate = { offset = [0.0, 0.0, 1.0] } }]
+//!
+//! [[solids]]
+//! sweep.path = [0.0, 0.0, 1.0]
+//! sweep.sketch.cycles = [
+//!     { start = [0.0, 0.0], segments = [
+//!         { line_to = [1.0, 0.0] },
+//!         { arc_to = { point = [0.0, 1.0], angle = 1.5 } },
+//!     ] },
+//! ]
+//! sweep.sketch.circles = [{ center = [0.3, 0.3], radius = 0.1 }]
+//! ```
+//!
+//! Use [`load`] to load a model from a file, or [`ModelDescription`] for more
+//! control.
+
+use std::{f64::consts::TAU, fs, path::Path};
+
+use fj_core::{
+    algorithms::transform::TransformObject,
+    objects::Solid,
+    operations::{
+        build::SketchBuilder, insert::Insert, primitives::BuildPrimitive,
+        sweep::SweepSketch,
+    },
+    services::Services,
+};
+use fj_math::Transform;
+
+/// Load a model description from a file and build it
+///
+/// The format of the file is determined by its extension, which must be either
+/// `json` or `toml`.
+pub fn load(path: &Path, services: &mut Services) -> Result<Solid, LoadError> {
+    let source = fs::read_to_string(path)?;
+
+    let description = match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("json") => {
+            ModelDescription::from_json(&source)?
+        }
+        Some(ext) if ext.eq_ignore_ascii_case("toml") => {
+            ModelDescription::from_toml(&source)?
+        }
+        _ => {
+            return Err(LoadError::UnknownFormat(
+                path.to_string_lossy().into_owned(),
+            ))
+        }
+    };
+
+    description.build(services)
+}
+
+/// A declarative description of a model
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelDescription {
+    /// The solids that make up the model
+    pub solids: Vec<SolidDescription>,
+}
+
+impl ModelDescription {
+    /// Parse a model description from JSON
+    pub fn from_json(source: &str) -> Result<Self, LoadError> {
+        Ok(serde_json::from_str(source)?)
+    }
+
+    /// Parse a model description from TOML
+    pub fn from_toml(source: &str) -> Result<Self, LoadError> {
+        Ok(toml::from_str(source)?)
+    }
+
+    /// Build the model
+    ///
+    /// Returns a single solid that contains the shells of all solids in the
+    /// description.
+    pub fn build(&self, services: &mut Services) -> Result<Solid, LoadError> {
+        let mut shells = Vec::new();
+
+        for solid in &self.solids {
+            let solid = solid.build(services)?;
+            shells.extend(solid.shells().iter().cloned());
+        }
+
+        Ok(Solid::new(shells))
+    }
+}
+
+/// A declarative description of a solid
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct SolidDescription {
+    /// The shape that the solid starts out as
+    #[serde(flatten)]
+    pub source: SolidSource,
+
+    /// The operations that are applied to the solid, in order
+    #[serde(default)]
+    pub operations: Vec<OperationDescription>,
+}
+
+impl SolidDescription {
+    fn build(&self, services: &mut Services) -> Result<Solid, LoadError> {
+        let mut solid = self.source.build(services)?.insert(services);
+
+        for operation in &self.operations {
+            let transform = operation.transform()?;
+            solid = solid.transform(&transform, services);
+        }
+
+        Ok(solid.clone_object())
+    }
+}
+
+/// The shape that a solid starts out as
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SolidSource {
+    /// A box; see [`BuildPrimitive::box_from_dims`]
+    Box {
+        /// The size of the box
+        size: [f64; 3],
+    },
+
+    /// A cylinder; see [`BuildPrimitive::cylinder`]
+    Cylinder {
+        /// The radius of the cylinder
+        radius: f64,
+
+        /// The height of the cylinder
+        height: f64,
+    },
+
+    /// A cone; see [`BuildPrimitive::cone`]
+    Cone {
+        /// The radius of the cone's base
+        radius: f64,
+
+        /// The height of the cone
+        height: f64,
+
+        /// The number of segments to approximate the cone with
+        segments: usize,
+    },
+
+    /// A sphere; see [`BuildPrimitive::sphere`]
+    Sphere {
+        /// The radius of the sphere
+        radius: f64,
+
+        /// The number of segments to approximate the sphere with
+        segments: usize,
+    },
+
+    /// A sketch in the xy-plane, swept along a path
+    Sweep {
+        /// The sketch to sweep
+        sketch: SketchDescription,
+
+        /// The path to sweep the sketch along
+        path: [f64; 3],
+    },
+}
+
+impl SolidSource {
+    fn build(&self, services: &mut Services) -> Result<Solid, LoadError> {
+        let solid = match *self {
+            Self::Box { size } => {
+                for extent in size {
+                    positive("Box size", extent)?;
+                }
+                Solid::box_from_dims(size, services)
+            }
+            Self::Cylinder { radius, height } => {
+                positive("Cylinder radius", radius)?;
+                positive("Cylinder height", height)?;
+                Solid::cylinder(radius, height, services)
+            }
+            Self::Cone {
+                radius,
+                height,
+                segments,
+            } => {
+                if segments < 3 {
+                    return Err(LoadError::Invalid(
+                        "Cone requires at least 3 segments".into(),
+                    ));
+                }
+                Solid::cone(radius, height, segments, services)
+            }
+            Self::Sphere { radius, segments } => {
+                if segments < 4 {
+                    return Err(LoadError::Invalid(
+                        "Sphere requires at least 4 segments".into(),
+                    ));
+                }
+                Solid::sphere(radius, segments, services)
+            }
+            Self::Sweep { ref sketch, path } => {
+                let surface = services.objects.surfaces.xy_plane();
+                sketch
+                    .builder()?
+                    .build(services)
+                    .sweep_sketch(surface, path, services)
+            }
+        };
+
+        Ok(solid)
+    }
+}
+
+/// A declarative description of a sketch
+///
+/// See [`SketchBuilder`] for how the cycles of the sketch are interpreted.
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SketchDescription {
+    /// The cycles of the sketch, made up of segments
+    #[serde(default)]
+    pub cycles: Vec<CycleDescription>,
+
+    /// Cycles of the sketch that are circles
+    #[serde(default)]
+    pub circles: Vec<CircleDescription>,
+}
+
+impl SketchDescription {
+    fn builder(&self) -> Result<SketchBuilder, LoadError> {
+        let start = match (self.cycles.first(), self.circles.first()) {
+            (Some(cycle), _) => cycle.start,
+            (None, Some(circle)) => circle.center,
+            (None, None) => {
+                return Err(LoadError::Invalid("Sketch is empty".into()))
+            }
+        };
+
+        let mut builder = SketchBuilder::start_at(start);
+
+        for (i, cycle) in self.cycles.iter().enumerate() {
+            if i > 0 {
+                builder = builder.move_to(cycle.start);
+            }
+            builder = cycle.trace(builder)?;
+        }
+        for circle in &self.circles {
+            if circle.radius <= 0. {
+                return Err(LoadError::Invalid(format!(
+                    "Circle radius ({}) must be positive",
+                    circle.radius
+                )));
+            }
+            builder = builder.circle(circle.center, circle.radius);
+        }
+
+        Ok(builder)
+    }
+}
+
+/// A cycle of a sketch, made up of segments
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CycleDescription {
+    /// The point the cycle starts at
+    pub start: [f64; 2],
+
+    /// The segments of the cycle
+    ///
+    /// The cycle is closed automatically, with a line segment from the end of
+    /// the last segment back to `start`, if necessary.
+    pub segments: Vec<SegmentDescription>,
+}
+
+impl CycleDescription {
+    fn trace(
+        &self,
+        mut builder: SketchBuilder,
+    ) -> Result<SketchBuilder, LoadError> {
+        if self.segments.is_empty() {
+            return Err(LoadError::Invalid("Cycle has no segments".into()));
+        }
+
+        for segment in &self.segments {
+            builder = match *segment {
+                SegmentDescription::LineTo(point) => builder.line_to(point),
+                SegmentDescription::ArcTo { point, angle } => {
+                    if !(-TAU < angle && angle < TAU) {
+                        return Err(LoadError::Invalid(format!(
+                            "Arc angle ({angle}) must be in the range \
+                            (-2pi, 2pi)"
+                        )));
+                    }
+                    builder.arc_to(point, angle)
+                }
+            };
+        }
+
+        Ok(builder.close())
+    }
+}
+
+/// A segment of a cycle
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentDescription {
+    /// A line from the current point to the provided one
+    LineTo([f64; 2]),
+
+    /// An arc from the current point to the provided one
+    ArcTo {
+        /// The end point of the arc
+        point: [f64; 2],
+
+        /// The angle of the arc, in radians; see [`SketchBuilder::arc_to`]
+        angle: f64,
+    },
+}
+
+/// A circle in a sketch
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CircleDescription {
+    /// The center of the circle
+    pub center: [f64; 2],
+
+    /// The radius of the circle
+    pub radius: f64,
+}
+
+/// An operation that is applied to a solid
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationDescription {
+    /// Translate the solid
+    Translate {
+        /// The offset to translate the solid by
+        offset: [f64; 3],
+    },
+
+    /// Rotate the solid around the origin
+    Rotate {
+        /// The rotation axis, with its length being the angle in radians
+        axis_angle: [f64; 3],
+    },
+
+    /// Scale the solid uniformly, relative to the origin
+    Scale {
+        /// The scaling factor
+        factor: f64,
+    },
+}
+
+impl OperationDescription {
+    fn transform(&self) -> Result<Transform, LoadError> {
+        let transform = match *self {
+            Self::Translate { offset } => Transform::translation(offset),
+            Self::Rotate { axis_angle } => Transform::rotation(axis_angle),
+            Self::Scale { factor } => {
+                if factor <= 0. {
+                    return Err(LoadError::Invalid(format!(
+                        "Scaling factor ({factor}) must be positive"
+                    )));
+                }
+                Transform::scale(factor)
+            }
+        };
+
+        Ok(transform)
+    }
+}
+
+/// Return an error, unless `value` is finite and positive
+fn positive(name: &str, value: f64) -> Result<(), LoadError> {
+    if value.is_finite() && value > 0. {
+        Ok(())
+    } else {
+        Err(LoadError::Invalid(format!(
+            "{name} ({value}) must be finite and positive"
+        )))
+    }
+}
+
+/// Error loading a model description
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    /// Failed to read the file
+    #[error("Failed to read model description")]
+    Io(#[from] std::io::Error),
+
+    /// The file extension doesn't identify a supported format
+    #[error("Unknown model description format: `{0}`; expected JSON or TOML")]
+    UnknownFormat(String),
+
+    /// Failed to parse JSON
+    #[error("Failed to parse JSON model description")]
+    Json(#[from] serde_json::Error),
+
+    /// Failed to parse TOML
+    #[error("Failed to parse TOML model description")]
+    Toml(#[from] toml::de::Error),
+
+    /// The description is well-formed, but doesn't describe a valid model
+    #[error("Invalid model description: {0}")]
+    Invalid(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_core::services::Services;
+
+    use super::{LoadError, ModelDescription};
+
+    #[test]
+    fn json_and_toml() -> anyhow::Result<()> {
+        let json = ModelDescription::from_json(
+            r#"{
+                "solids": [
+                    {
+                        "box": { "size": [4.0, 2.0, 1.0] },
+                        "operations": [
+                            { "translate": { "offset": [0.0, 0.0, 1.0] } }
+                        ]
+                    }
+                ]
+            }"#,
+        )?;
+        let toml = ModelDescription::from_toml(
+            r#"
+                [[solids]]
+                box = { size = [4.0, 2.0, 1.0] }
+                operations = [{ translate = { offset = [0.0, 0.0, 1.0] } }]
+
+                [[solids]]
+                sweep.path = [0.0, 0.0, 1.0]
+                sweep.sketch.cycles = [
+                    { start = [10.0, 0.0], segments = [
+                        { line_to = [11.0, 0.0] },
+                        { arc_to = { point = [10.0, 1.0], angle = 1.5 } },
+                    ] },
+                ]
+                sweep.sketch.circles = [{ center = [10.3, 0.3], radius = 0.1 }]
+            "#,
+        )?;
+
+        let mut services = Services::new();
+
+        let solid = json.build(&mut services)?;
+        assert_eq!(solid.shells().len(), 1);
+
+        let solid = toml.build(&mut services)?;
+        assert_eq!(solid.shells().len(), 2);
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_dimensions() -> anyhow::Result<()> {
+        for source in [
+            "box = { size = [0.0, 1.0, 1.0] }",
+            "box = { size = [1.0, -1.0, 1.0] }",
+            "box = { size = [1.0, 1.0, inf] }",
+            "box = { size = [nan, 1.0, 1.0] }",
+            "cylinder = { radius = 0.0, height = 1.0 }",
+            "cylinder = { radius = 1.0, height = -inf }",
+            "cylinder = { radius = nan, height = 1.0 }",
+        ] {
+            let description =
+                ModelDescription::from_toml(&format!("[[solids]]\n{source}"))?;
+
+            let mut services = Services::new();
+            let result = description.build(&mut services);
+
+            assert!(
+                matches!(result, Err(LoadError::Invalid(_))),
+                "{source}: {result:?}"
+            );
+            services.drop_and_validate()?;
+        }
+
+        Ok(())
+    }
+}
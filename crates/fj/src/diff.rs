@@ -0,0 +1,593 @@
+//! # Geometric comparison of meshes
+//!
+//! Refactoring a kernel operation shouldn't change the models it produces,
+//! beyond the tolerance of their approximation. [`diff_meshes`] verifies that,
+//! by comparing the triangle meshes of a model from before and after a change.
+//!
+//! Use the `compare` command-line subcommand (see [`Command::Compare`]) to
+//! compare a model against a reference that has been stored previously, or
+//! the `--diff` argument (see [`Args::diff`]) to review the changes visually in
+//! the viewer, as colored by [`diff_visualization`].
+//!
+//! [`Command::Compare`]: crate::Command::Compare
+//! [`Args::diff`]: crate::Args::diff
+
+use std::{collections::HashMap, fmt, path::Path};
+
+use fj_core::algorithms::approx::Tolerance;
+use fj_interop::{
+    mesh::{Color, Mesh},
+    mesh_builder::{IndexedMesh, MeshBuilder},
+    model::Model,
+};
+use fj_math::{Aabb, Point, Scalar, Triangle};
+use parry3d_f64::{query::PointQuery, shape::TriMesh};
+
+use crate::{snapshot::Snapshot, Error};
+
+/// Compare a mesh to the reference snapshot at `path`, or save it there
+///
+/// Prints the result of the comparison, and returns an error, if the mesh
+/// deviates from the reference. Also returns an error, if the reference doesn't
+/// exist, unless `save` is set.
+pub(crate) fn compare_to_reference(
+    mesh: &Mesh<Point<3>>,
+    path: &Path,
+    save: bool,
+    tolerance: Tolerance,
+) -> Result<(), Error> {
+    let snapshot = Snapshot::from_mesh(mesh);
+
+    if save {
+        snapshot.save(path)?;
+        println!("Saved reference `{}`", path.display());
+        return Ok(());
+    }
+
+    if !path.exists() {
+        return Err(Error::MissingReference(path.to_path_buf()));
+    }
+
+    let reference = Snapshot::load(path)?;
+    let diff = snapshot.diff(&reference, tolerance.inner());
+
+    if !diff.is_empty() {
+        println!("{diff}");
+        return Err(Error::Deviation(path.to_path_buf()));
+    }
+
+    println!("Model matches reference `{}`", path.display());
+    Ok(())
+}
+
+/// Color a model by how it differs from a reference mesh
+///
+/// See [`diff_visualization`]. The bounding box of the result includes the
+/// reference, so removed material is not cut off in the viewer.
+pub(crate) fn diff_to_reference(
+    model: Model,
+    reference: &Mesh<Point<3>>,
+    tolerance: Tolerance,
+) -> Model {
+    let mesh = diff_visualization(reference, &model.mesh, tolerance.inner());
+    let aabb = Aabb::<3>::from_points(mesh.vertices());
+
+    Model {
+        mesh,
+        aabb,
+        ..model
+    }
+}
+
+/// Color the difference between two triangle meshes, to review it visually
+///
+/// Returns a mesh that contains all triangles of `after`. Those that deviate
+/// from `before` by more than `tolerance` are colored according to the change:
+/// [`ADDED`], if they lie outside of `before`; [`REMOVED`], if they lie inside
+/// of it. All others are colored [`UNCHANGED`].
+///
+/// Material that has been removed is not visible from the triangles of `after`
+/// alone. The triangles of `before` that lie outside of `after` are included
+/// too, colored [`REMOVED_GHOST`].
+///
+/// # Panics
+///
+/// Panics, if `tolerance` is not positive.
+pub fn diff_visualization(
+    before: &Mesh<Point<3>>,
+    after: &Mesh<Point<3>>,
+    tolerance: impl Into<Scalar>,
+) -> Mesh<Point<3>> {
+    let tolerance = tolerance.into();
+    assert!(tolerance > Scalar::ZERO, "Diff tolerance must be positive");
+
+    let [before, after] = [before, after].map(|mesh| {
+        MeshBuilder::new()
+            .build(mesh)
+            .expect("32-bit indices can address any number of vertices")
+    });
+    let [before_trimesh, after_trimesh] = [&before, &after].map(to_trimesh);
+    let [before, after] = [&before, &after].map(triangles);
+
+    let mut mesh = Mesh::new();
+
+    for &triangle in &after {
+        let color = match deviation(triangle, before_trimesh.as_ref()) {
+            Some((point, distance)) if distance > tolerance => {
+                if encloses(&before, point) {
+                    REMOVED
+                } else {
+                    ADDED
+                }
+            }
+            _ => UNCHANGED,
+        };
+
+        mesh.push_triangle(triangle, color);
+    }
+
+    for &triangle in &before {
+        if let Some((point, distance)) =
+            deviation(triangle, after_trimesh.as_ref())
+        {
+            if distance > tolerance && !encloses(&after, point) {
+                mesh.push_triangle(triangle, REMOVED_GHOST);
+            }
+        }
+    }
+
+    mesh
+}
+
+/// The color of triangles that don't deviate, in [`diff_visualization`]
+pub const UNCHANGED: Color = Color([200, 200, 200, 255]);
+
+/// The color of added material, in [`diff_visualization`]
+pub const ADDED: Color = Color([0, 190, 0, 255]);
+
+/// The color of surfaces of removed material, in [`diff_visualization`]
+pub const REMOVED: Color = Color([220, 0, 0, 255]);
+
+/// The color of removed material's former surface, in [`diff_visualization`]
+pub const REMOVED_GHOST: Color = Color([220, 0, 0, 64]);
+
+/// Compare two triangle meshes
+///
+/// The deviation between the meshes is measured from each vertex of either
+/// mesh to the closest point on the other mesh. Vertices that deviate by more
+/// than `tolerance` form the changed regions of the result.
+///
+/// # Panics
+///
+/// Panics, if `tolerance` is not positive.
+pub fn diff_meshes(
+    before: &Mesh<Point<3>>,
+    after: &Mesh<Point<3>>,
+    tolerance: impl Into<Scalar>,
+) -> MeshDiff {
+    let tolerance = tolerance.into();
+    assert!(tolerance > Scalar::ZERO, "Diff tolerance must be positive");
+
+    let [before, after] = [before, after].map(|mesh| {
+        MeshBuilder::new()
+            .build(mesh)
+            .expect("32-bit indices can address any number of vertices")
+    });
+    let [before_trimesh, after_trimesh] = [&before, &after].map(to_trimesh);
+
+    let deviations_before = deviations(&before, after_trimesh.as_ref());
+    let deviations_after = deviations(&after, before_trimesh.as_ref());
+
+    let max_deviation = deviations_before
+        .iter()
+        .chain(&deviations_after)
+        .copied()
+        .fold(Scalar::ZERO, Scalar::max);
+
+    let mut regions = changed_regions(&before, &deviations_before, tolerance);
+    regions.extend(changed_regions(&after, &deviations_after, tolerance));
+    let changed_regions = merge_regions(regions, tolerance);
+
+    MeshDiff {
+        tolerance,
+        max_deviation,
+        volume_before: volume(&before),
+        volume_after: volume(&after),
+        changed_regions,
+    }
+}
+
+/// The result of comparing two triangle meshes
+///
+/// Returned by [`diff_meshes`].
+#[derive(Clone, Debug)]
+pub struct MeshDiff {
+    /// The tolerance that the meshes were compared with
+    pub tolerance: Scalar,
+
+    /// The maximum distance between a vertex of one mesh and the other mesh
+    pub max_deviation: Scalar,
+
+    /// The volume enclosed by the first mesh
+    pub volume_before: Scalar,
+
+    /// The volume enclosed by the second mesh
+    pub volume_after: Scalar,
+
+    /// The regions in which the meshes deviate by more than the tolerance
+    pub changed_regions: Vec<ChangedRegion>,
+}
+
+impl MeshDiff {
+    /// Compute the difference in volume, from the first mesh to the second
+    pub fn volume_difference(&self) -> Scalar {
+        self.volume_after - self.volume_before
+    }
+
+    /// Indicate whether the meshes match within the tolerance
+    pub fn is_within_tolerance(&self) -> bool {
+        self.changed_regions.is_empty()
+    }
+}
+
+impl fmt::Display for MeshDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Maximum deviation: {}", self.max_deviation)?;
+        writeln!(
+            f,
+            "Volume: {} -> {} (difference: {})",
+            self.volume_before,
+            self.volume_after,
+            self.volume_difference(),
+        )?;
+
+        if self.is_within_tolerance() {
+            write!(f, "No changes beyond tolerance ({})", self.tolerance)?;
+        } else {
+            write!(
+                f,
+                "Changed regions (beyond tolerance of {}):",
+                self.tolerance,
+            )?;
+            for region in &self.changed_regions {
+                write!(
+                    f,
+                    "\n    {:?} to {:?} (maximum deviation: {})",
+                    region.aabb.min, region.aabb.max, region.max_deviation,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A region in which two meshes deviate by more than the tolerance
+#[derive(Clone, Debug)]
+pub struct ChangedRegion {
+    /// The bounding box of the region
+    pub aabb: Aabb<3>,
+
+    /// The maximum deviation within the region
+    pub max_deviation: Scalar,
+}
+
+fn to_trimesh(mesh: &IndexedMesh) -> Option<TriMesh> {
+    if mesh.colors.is_empty() {
+        return None;
+    }
+
+    let vertices = mesh.vertices.iter().map(|point| point.to_na()).collect();
+    let indices = mesh
+        .indices
+        .to_u32()
+        .chunks_exact(3)
+        .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+        .collect();
+
+    Some(TriMesh::new(vertices, indices))
+}
+
+fn deviations(mesh: &IndexedMesh, other: Option<&TriMesh>) -> Vec<Scalar> {
+    mesh.vertices
+        .iter()
+        .map(|point| match other {
+            Some(other) => Scalar::from_f64(
+                other.distance_to_local_point(&point.to_na(), false),
+            ),
+            None => Scalar::MAX,
+        })
+        .collect()
+}
+
+fn triangles(mesh: &IndexedMesh) -> Vec<Triangle<3>> {
+    mesh.indices
+        .to_u32()
+        .chunks_exact(3)
+        .filter_map(|triangle| {
+            Triangle::from_points(
+                [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize]),
+            )
+            .ok()
+        })
+        .collect()
+}
+
+/// Find the point of a triangle that deviates the most from another mesh
+///
+/// Only the vertices and the center of the triangle are considered. Returns
+/// `None`, if there is no other mesh to deviate from.
+fn deviation(
+    triangle: Triangle<3>,
+    other: Option<&TriMesh>,
+) -> Option<(Point<3>, Scalar)> {
+    let [a, b, c] = triangle.points();
+    let center = Point {
+        coords: (a.coords + b.coords + c.coords) / 3.,
+    };
+
+    let Some(other) = other else {
+        return Some((center, Scalar::MAX));
+    };
+
+    [a, b, c, center]
+        .into_iter()
+        .map(|point| {
+            let distance = other.distance_to_local_point(&point.to_na(), false);
+            (point, Scalar::from_f64(distance))
+        })
+        .max_by_key(|&(_, distance)| distance)
+}
+
+/// Determine whether a closed mesh encloses a point
+///
+/// Computes the generalized winding number of the mesh around the point, which
+/// stays meaningful, if the mesh has small gaps.
+fn encloses(mesh: &[Triangle<3>], point: Point<3>) -> bool {
+    let solid_angle = mesh
+        .iter()
+        .map(|triangle| {
+            let [a, b, c] = triangle.points().map(|vertex| vertex - point);
+            let [la, lb, lc] = [a, b, c].map(|v| v.magnitude());
+
+            let numerator = a.dot(&b.cross(&c));
+            let denominator =
+                la * lb * lc + a.dot(&b) * lc + a.dot(&c) * lb + b.dot(&c) * la;
+
+            numerator.atan2(denominator) * 2.
+        })
+        .fold(Scalar::ZERO, |sum, angle| sum + angle);
+
+    solid_angle / (Scalar::PI * 4.) > Scalar::from(0.5)
+}
+
+/// Find the regions of a mesh that deviate by more than the tolerance
+///
+/// Triangles that have a deviating vertex are grouped with the triangles they
+/// share vertices with.
+fn changed_regions(
+    mesh: &IndexedMesh,
+    deviations: &[Scalar],
+    tolerance: Scalar,
+) -> Vec<ChangedRegion> {
+    let mut groups = (0..mesh.vertices.len()).collect::<Vec<_>>();
+    let root = |groups: &mut Vec<usize>, mut i: usize| {
+        while groups[i] != i {
+            groups[i] = groups[groups[i]];
+            i = groups[i];
+        }
+        i
+    };
+
+    let indices = mesh.indices.to_u32();
+    let changed = indices
+        .chunks_exact(3)
+        .map(|triangle| {
+            triangle.iter().map(|&i| i as usize).collect::<Vec<_>>()
+        })
+        .filter(|triangle| triangle.iter().any(|&i| deviations[i] > tolerance))
+        .collect::<Vec<_>>();
+
+    for triangle in &changed {
+        for &i in &triangle[1..] {
+            let [a, b] = [triangle[0], i].map(|i| root(&mut groups, i));
+            groups[a] = b;
+        }
+    }
+
+    let mut regions = HashMap::<usize, ChangedRegion>::new();
+    for triangle in &changed {
+        let group = root(&mut groups, triangle[0]);
+        let aabb =
+            Aabb::<3>::from_points(triangle.iter().map(|&i| mesh.vertices[i]));
+        let max_deviation = triangle
+            .iter()
+            .map(|&i| deviations[i])
+            .fold(Scalar::ZERO, Scalar::max);
+
+        regions
+            .entry(group)
+            .and_modify(|region| {
+                region.aabb = region.aabb.merged(&aabb);
+                region.max_deviation = region.max_deviation.max(max_deviation);
+            })
+            .or_insert(ChangedRegion {
+                aabb,
+                max_deviation,
+            });
+    }
+
+    regions.into_values().collect()
+}
+
+/// Merge regions whose bounding boxes overlap
+///
+/// Both meshes contribute regions for the same change, which are merged here.
+fn merge_regions(
+    mut regions: Vec<ChangedRegion>,
+    tolerance: Scalar,
+) -> Vec<ChangedRegion> {
+    let overlap = |a: &Aabb<3>, b: &Aabb<3>| {
+        (0..3).all(|i| {
+            a.min.coords.components[i] <= b.max.coords.components[i] + tolerance
+                && b.min.coords.components[i]
+                    <= a.max.coords.components[i] + tolerance
+        })
+    };
+
+    let mut merged = Vec::<ChangedRegion>::new();
+    while let Some(mut region) = regions.pop() {
+        // Merging two regions can make the result overlap regions that have
+        // already been checked, so check them again.
+        while let Some(i) = merged
+            .iter()
+            .position(|other| overlap(&region.aabb, &other.aabb))
+        {
+            let other = merged.swap_remove(i);
+            region.aabb = region.aabb.merged(&other.aabb);
+            region.max_deviation =
+                region.max_deviation.max(other.max_deviation);
+        }
+
+        merged.push(region);
+    }
+
+    merged
+}
+
+/// Compute the volume enclosed by a mesh
+///
+/// Sums up the signed volumes of the tetrahedra that are formed by each
+/// triangle and the origin. The result is only meaningful for closed meshes.
+fn volume(mesh: &IndexedMesh) -> Scalar {
+    mesh.indices
+        .to_u32()
+        .chunks_exact(3)
+        .map(|triangle| {
+            let [a, b, c] =
+                [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize].coords);
+            a.dot(&b.cross(&c)) / 6.
+        })
+        .fold(Scalar::ZERO, |volume, v| volume + v)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use fj_core::{
+        algorithms::{
+            approx::Tolerance, transform::TransformObject,
+            triangulate::Triangulate,
+        },
+        objects::Solid,
+        operations::primitives::BuildPrimitive,
+        services::Services,
+    };
+    use fj_math::Scalar;
+
+    use crate::Error;
+
+    use super::{
+        compare_to_reference, diff_meshes, diff_visualization, ADDED, REMOVED,
+        REMOVED_GHOST, UNCHANGED,
+    };
+
+    #[test]
+    fn compare_to_saved_reference() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let small = Solid::box_from_dims([2., 2., 2.], &mut services);
+        let large = Solid::box_from_dims([2., 2., 3.], &mut services);
+        let [small, large] =
+            [small, large].map(|solid| (&solid, tolerance).triangulate());
+
+        let path = env::temp_dir()
+            .join(format!("fj-compare-{}", process::id()))
+            .join("reference.json");
+
+        // A missing reference is an error, unless it is saved explicitly.
+        assert!(matches!(
+            compare_to_reference(&small, &path, false, tolerance),
+            Err(Error::MissingReference(_))
+        ));
+        assert!(compare_to_reference(&small, &path, true, tolerance).is_ok());
+
+        assert!(compare_to_reference(&small, &path, false, tolerance).is_ok());
+        assert!(matches!(
+            compare_to_reference(&large, &path, false, tolerance),
+            Err(Error::Deviation(_))
+        ));
+
+        if let Some(dir) = path.parent() {
+            fs::remove_dir_all(dir)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn diff_meshes_of_boxes() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let a = Solid::box_from_dims([2., 2., 2.], &mut services);
+        let b = Solid::box_from_dims([2., 2., 2.], &mut services)
+            .translate([0., 0., 1e-6], &mut services);
+        let c = Solid::box_from_dims([2., 2., 3.], &mut services);
+
+        let [a, b, c] =
+            [a, b, c].map(|solid| (&solid, tolerance).triangulate());
+
+        let diff = diff_meshes(&a, &b, 1e-3);
+        assert!(diff.is_within_tolerance());
+        assert!(diff.max_deviation < Scalar::from(1e-3));
+
+        let diff = diff_meshes(&a, &c, 1e-3);
+        assert!(!diff.is_within_tolerance());
+        assert_eq!(diff.changed_regions.len(), 1);
+        assert_eq!(diff.max_deviation, Scalar::ONE);
+        assert!(
+            (diff.volume_difference() - Scalar::from(4.)).abs()
+                < Scalar::from(1e-9)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn diff_visualization_of_boxes() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let small = Solid::box_from_dims([2., 2., 2.], &mut services);
+        let large = Solid::box_from_dims([2., 2., 3.], &mut services);
+
+        let [small, large] =
+            [small, large].map(|solid| (&solid, tolerance).triangulate());
+
+        let colors = |before, after| {
+            diff_visualization(before, after, 1e-3)
+                .triangles()
+                .map(|triangle| triangle.color)
+                .collect::<Vec<_>>()
+        };
+
+        let grown = colors(&small, &large);
+        assert!(grown.contains(&ADDED));
+        assert!(grown.contains(&UNCHANGED));
+        assert!(!grown.contains(&REMOVED));
+        assert!(!grown.contains(&REMOVED_GHOST));
+
+        let shrunk = colors(&large, &small);
+        assert!(shrunk.contains(&REMOVED));
+        assert!(shrunk.contains(&REMOVED_GHOST));
+        assert!(!shrunk.contains(&ADDED));
+
+        let unchanged = colors(&small, &small);
+        assert!(unchanged.iter().all(|&color| color == UNCHANGED));
+
+        Ok(())
+    }
+}
@@ -1,19 +1,28 @@
-use std::{error::Error as _, fmt, mem, ops::Deref};
+use std::{
+    error::Error as _, fmt, io, mem, ops::Deref, path::PathBuf, sync::OnceLock,
+};
 
 use fj_core::{
     algorithms::{
-        approx::{InvalidTolerance, Tolerance},
+        approx::{polyline::EdgePolylines, InvalidTolerance, Tolerance},
         bounding_volume::BoundingVolume,
+        debug::ParameterSpaces,
         triangulate::Triangulate,
     },
     services::Services,
     validate::ValidationErrors,
 };
-use fj_interop::model::Model;
-use fj_math::{Aabb, Point, Scalar};
+use fj_interop::{mesh::Mesh, model::Model};
+use fj_math::{Aabb, Point, Unit};
 use tracing_subscriber::prelude::*;
 
-use crate::Args;
+use crate::{
+    config::{Config, ConfigError},
+    server::Server,
+    snapshot::{Snapshot, SnapshotError},
+    timing::Timings,
+    Args, Command,
+};
 
 /// Export or display a model, according to CLI arguments
 ///
@@ -23,21 +32,79 @@ use crate::Args;
 ///
 /// This function is used by Fornjot's own testing infrastructure, but is useful
 /// beyond that, when using Fornjot directly to define a model.
+///
+/// The model is assumed to be specified in millimeters. Use
+/// [`handle_model_with_unit`], if that is not the case.
+///
+/// Call [`init_tracing`] before constructing the model, to include its
+/// construction in the timings that `--timings` reports.
 pub fn handle_model<M>(
     model: impl Deref<Target = M>,
     services: Services,
 ) -> Result
 where
-    for<'r> (&'r M, Tolerance): Triangulate,
+    for<'r> (&'r M, Tolerance): Triangulate + EdgePolylines,
     M: BoundingVolume<3>,
 {
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::fmt::layer())
-        .with(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
+    handle_model_with_unit(model, Unit::Millimeter, services)
+}
 
+/// Export or display a model that is specified in the given unit
+///
+/// Exported files are converted from `unit` into the unit that the respective
+/// file format expects, and the viewer displays the unit of the model.
+///
+/// See [`handle_model`] for more information.
+pub fn handle_model_with_unit<M>(
+    model: impl Deref<Target = M>,
+    unit: Unit,
+    services: Services,
+) -> Result
+where
+    for<'r> (&'r M, Tolerance): Triangulate + EdgePolylines,
+    M: BoundingVolume<3>,
+{
+    handle_model_inner(model, unit, services, None)
+}
+
+/// Export or display a model, with support for viewing its parameter spaces
+///
+/// Works like [`handle_model_with_unit`], but also supports the
+/// `--parameter-spaces` argument (see [`Args::parameter_spaces`]). This
+/// requires the model to support [`ParameterSpaces`], which is why the other
+/// functions don't.
+pub fn handle_model_with_parameter_spaces<M>(
+    model: impl Deref<Target = M>,
+    unit: Unit,
+    services: Services,
+) -> Result
+where
+    for<'r> (&'r M, Tolerance): Triangulate + EdgePolylines + ParameterSpaces,
+    M: BoundingVolume<3>,
+{
+    let parameter_spaces: ParameterSpacesFn<M> =
+        |model, tolerance, unit| (model, tolerance).parameter_spaces(unit);
+
+    handle_model_inner(model, unit, services, Some(parameter_spaces))
+}
+
+/// Computes the models that show the parameter spaces of a model's faces
+type ParameterSpacesFn<M> = fn(&M, Tolerance, Unit) -> Vec<Model>;
+
+fn handle_model_inner<M>(
+    model: impl Deref<Target = M>,
+    unit: Unit,
+    services: Services,
+    parameter_spaces: Option<ParameterSpacesFn<M>>,
+) -> Result
+where
+    for<'r> (&'r M, Tolerance): Triangulate + EdgePolylines,
+    M: BoundingVolume<3>,
+{
     let args = Args::parse();
 
+    let timings = init_tracing();
+
     if args.ignore_validation {
         mem::forget(services);
     } else {
@@ -49,39 +116,166 @@ where
         max: Point::origin(),
     });
 
-    let tolerance = match args.tolerance {
-        None => {
-            // Compute a reasonable default for the tolerance value. To do
-            // this, we just look at the smallest non-zero extent of the
-            // bounding box and divide that by some value.
+    let tolerance = tolerance(&args, &aabb)?;
 
-            let mut min_extent = Scalar::MAX;
-            for extent in aabb.size().components {
-                if extent > Scalar::ZERO && extent < min_extent {
-                    min_extent = extent;
-                }
-            }
+    if let Some(path) = args.export {
+        let mesh = (model.deref(), tolerance).triangulate();
 
-            let tolerance = min_extent / Scalar::from_f64(1000.);
-            Tolerance::from_scalar(tolerance)?
+        if args.timings {
+            print!("{}", timings.report());
         }
-        Some(user_defined_tolerance) => user_defined_tolerance,
-    };
 
-    let mesh = (model.deref(), tolerance).triangulate();
+        crate::export::export_with_unit(&mesh, &path, unit)?;
+        return Ok(());
+    }
 
-    if let Some(path) = args.export {
-        crate::export::export(&mesh, &path)?;
+    if let Some(Command::Compare { reference, save }) = &args.command {
+        let mesh = (model.deref(), tolerance).triangulate();
+        return crate::diff::compare_to_reference(
+            &mesh, reference, *save, tolerance,
+        );
+    }
+
+    if let Some(path) = &args.diff {
+        let reference = Snapshot::load(path)?.to_mesh();
+        let model_for_display = Model {
+            mesh: (model.deref(), tolerance).triangulate(),
+            edges: (model.deref(), tolerance).edge_polylines(),
+            aabb,
+            unit,
+        };
+
+        let model_for_display = crate::diff::diff_to_reference(
+            model_for_display,
+            &reference,
+            tolerance,
+        );
+        crate::window::display(
+            model_for_display,
+            Config::load()?.window_config(),
+        )?;
+        return Ok(());
+    }
+
+    if let Some(address) = args.serve {
+        let mesh = (model.deref(), tolerance).triangulate();
+
+        if args.timings {
+            print!("{}", timings.report());
+        }
+
+        let server = Server::bind(address).map_err(Error::Serve)?;
+        server.set_model(&mesh, aabb, unit);
+
+        println!("Serving model at http://{address}/");
+        server.run().map_err(Error::Serve)?;
         return Ok(());
     }
 
-    let model = Model { mesh, aabb };
+    let model = model.deref();
+    let edges = (model, tolerance).edge_polylines();
+
+    // Triangulating a large model can take a while. Start out with an empty
+    // mesh and let the viewer add faces as they are triangulated, instead of
+    // keeping the window from showing up until everything is done.
+    let model_for_display = Model {
+        mesh: Mesh::new(),
+        edges,
+        aabb,
+        unit,
+    };
+
+    // Only the batches are sent to the thread that triangulates the model, so
+    // the model itself doesn't need to be shared between threads.
+    let batches = (model, tolerance).batches();
+    let parameter_spaces = if !args.parameter_spaces {
+        None
+    } else if let Some(parameter_spaces) = parameter_spaces {
+        Some(parameter_spaces(model, tolerance, unit))
+    } else {
+        eprintln!(
+            "Ignoring `--parameter-spaces`; only supported by \
+            `handle_model_with_parameter_spaces`"
+        );
+        None
+    };
+
+    crate::window::display_progressive(
+        model_for_display,
+        Config::load()?.window_config(),
+        move |sender| {
+            for batch in batches {
+                if sender.send(batch).is_err() {
+                    break;
+                }
+            }
 
-    crate::window::display(model, false)?;
+            if let Some(parameter_spaces) = parameter_spaces {
+                // The window might have been closed already. Nothing to do
+                // then.
+                let _ = sender.send_parameter_spaces(parameter_spaces);
+            }
+        },
+    )?;
+
+    if args.timings {
+        print!("{}", timings.report());
+    }
 
     Ok(())
 }
 
+/// Set up logging, and the collection of timings, if requested
+///
+/// Logging is configured through the `RUST_LOG` environment variable. Timings
+/// are only collected, if the `--timings` argument was passed (see [`Args`]).
+///
+/// Call this first thing in `main`, before constructing the model. Otherwise,
+/// the timings miss everything that happened before, and the report that
+/// [`handle_model`] prints only covers the processing of the finished model.
+/// [`handle_model`] calls this function itself, so this is optional.
+///
+/// Only the first call sets up anything. Later calls return the same
+/// [`Timings`].
+///
+/// # Panics
+///
+/// Panics, if another global subscriber has been set up already.
+pub fn init_tracing() -> Timings {
+    static TIMINGS: OnceLock<Timings> = OnceLock::new();
+
+    TIMINGS
+        .get_or_init(|| {
+            let args = Args::parse();
+
+            let timings = Timings::new();
+            tracing_subscriber::registry()
+                .with(tracing_subscriber::fmt::layer().with_filter(
+                    tracing_subscriber::EnvFilter::from_default_env(),
+                ))
+                .with(args.timings.then(|| {
+                    timings.layer().with_filter(
+                        tracing_subscriber::EnvFilter::new("fj_core=debug"),
+                    )
+                }))
+                .init();
+
+            timings
+        })
+        .clone()
+}
+
+/// Determine the tolerance to use for a model with the provided bounding box
+pub(crate) fn tolerance(
+    args: &Args,
+    aabb: &Aabb<3>,
+) -> std::result::Result<Tolerance, InvalidTolerance> {
+    match args.tolerance {
+        None => Tolerance::from_aabb(aabb),
+        Some(user_defined_tolerance) => Ok(user_defined_tolerance),
+    }
+}
+
 /// Return value of [`handle_model`]
 pub type Result = std::result::Result<(), Error>;
 
@@ -92,6 +286,10 @@ pub enum Error {
     #[error("Failed to set up logger")]
     Tracing(#[from] tracing::subscriber::SetGlobalDefaultError),
 
+    /// Error loading configuration file
+    #[error("Error loading configuration file")]
+    Config(#[from] ConfigError),
+
     /// Error displaying model
     #[error("Error displaying model")]
     Display(#[from] crate::window::Error),
@@ -100,6 +298,35 @@ pub enum Error {
     #[error("Error exporting model")]
     Export(#[from] crate::export::Error),
 
+    /// Error loading or running model plugin
+    #[cfg(feature = "plugins")]
+    #[error("Error loading model plugin")]
+    Plugin(#[from] crate::plugin::PluginError),
+
+    /// Error evaluating script
+    #[cfg(feature = "scripting")]
+    #[error("Error evaluating script")]
+    Script(#[from] crate::script::ScriptError),
+
+    /// Model deviates from reference
+    #[error("Model deviates from reference `{}`", .0.display())]
+    Deviation(PathBuf),
+
+    /// Reference to compare the model to doesn't exist
+    #[error(
+        "Reference `{}` doesn't exist; use `--save` to create it",
+        .0.display()
+    )]
+    MissingReference(PathBuf),
+
+    /// Error loading or saving reference
+    #[error("Error loading or saving reference")]
+    Snapshot(#[from] SnapshotError),
+
+    /// Error serving model
+    #[error("Error serving model")]
+    Serve(#[source] io::Error),
+
     /// Invalid tolerance
     #[error(transparent)]
     Tolerance(#[from] InvalidTolerance),
@@ -7,16 +7,41 @@
 //! This crate serves as a convenient entryway to Fornjot, re-exporting all
 //! crates that make up Fornjot.
 //!
+//! ## Optional features
+//!
+//! - `plugins`: Enables the [`plugin`] module, which can load models from
+//!   dynamic libraries at runtime.
+//! - `scripting`: Enables the [`script`] module, which can evaluate models
+//!   defined in the Rhai scripting language at runtime.
+//!
 //! [Fornjot]: https://www.fornjot.app/
 
+pub mod config;
+pub mod declarative;
+pub mod diff;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+#[cfg(feature = "scripting")]
+pub mod script;
+pub mod server;
+pub mod snapshot;
+pub mod timing;
+
 mod args;
 mod handle_model;
+#[cfg(any(feature = "plugins", feature = "scripting"))]
+mod reload;
 
 pub use self::{
-    args::Args,
-    handle_model::{handle_model, Error, Result},
+    args::{Args, Command},
+    handle_model::{
+        handle_model, handle_model_with_parameter_spaces,
+        handle_model_with_unit, init_tracing, Error, Result,
+    },
 };
 
+pub use fj_math::Unit;
+
 pub use fj_core as core;
 pub use fj_export as export;
 pub use fj_interop as interop;
@@ -0,0 +1,453 @@
+//! # Model plugins
+//!
+//! Models can be compiled into dynamic libraries, which an application loads
+//! at runtime. [`handle_plugin`] displays such a model, and loads the library
+//! again whenever it changes. This keeps the viewer running, while the model is
+//! being modified and recompiled.
+//!
+//! To turn a model into a plugin, set its crate type to `cdylib`, enable the
+//! `plugins` feature of this crate, and register the model using
+//! [`register_plugin!`]:
+//!
+//! ```
+//! use fj::{
+//!     core::{
+//!         objects::Solid,
+//!         operations::{insert::Insert, primitives::BuildPrimitive},
+//!         services::Services,
+//!         storage::Handle,
+//!     },
+//!     plugin::{ParameterDeclaration, Parameters},
+//! };
+//!
+//! fj::register_plugin! {
+//!     name: "cuboid",
+//!     parameters: &[
+//!         ParameterDeclaration { name: "x", default: 3. },
+//!         ParameterDeclaration { name: "y", default: 2. },
+//!         ParameterDeclaration { name: "z", default: 1. },
+//!     ],
+//!     model: model,
+//! }
+//!
+//! fn model(parameters: &Parameters, services: &mut Services) -> Handle<Solid> {
+//!     let size = ["x", "y", "z"].map(|name| parameters.get(name));
+//!     Solid::box_from_dims(size, services).insert(services)
+//! }
+//! ```
+//!
+//! ## Compatibility
+//!
+//! The interface between application and plugin passes Rust types, which don't
+//! have a stable ABI. Application and plugin must use the same version of
+//! Fornjot, and must have been compiled by the same compiler. The version of
+//! Fornjot and the version of the plugin interface ([`API_VERSION`]) are
+//! checked when loading a plugin. The compiler version can't be checked.
+//!
+//! This module is only available, if the `plugins` feature is enabled.
+
+use std::{
+    collections::BTreeMap,
+    env, fs, io, mem,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use fj_core::{objects::Solid, services::Services, storage::Handle};
+use libloading::Library;
+
+use crate::reload::{self, LoadedModel};
+
+/// The version of the plugin interface
+///
+/// Incremented, whenever [`PluginDeclaration`] or the functions that
+/// [`register_plugin!`] generates change.
+pub const API_VERSION: u32 = 1;
+
+/// The version of Fornjot that the application or plugin uses
+pub const FJ_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Export or display the model plugin at `path`
+///
+/// Works like [`handle_model`], but loads the model from a plugin first. When
+/// displaying the model, the plugin is loaded again whenever it changes, and
+/// the window is updated with the result. Errors that happen while reloading
+/// the plugin are printed, and the previous version of the model is kept.
+///
+/// Parameters that the plugin declares can be overridden using the command-line
+/// arguments (see [`Args::parameters`]).
+///
+/// The model is assumed to be specified in millimeters.
+///
+/// [`handle_model`]: crate::handle_model()
+/// [`Args::parameters`]: crate::Args::parameters
+pub fn handle_plugin(path: impl AsRef<Path>) -> crate::Result {
+    let path = path.as_ref();
+
+    reload::export_or_display(path, |args| {
+        let plugin = Plugin::load(path)?;
+
+        let mut parameters = plugin.default_parameters();
+        for (name, value) in &args.parameters {
+            parameters.set(name, *value)?;
+        }
+
+        let mut services = Services::new();
+        let solid = match plugin.model(&parameters, &mut services) {
+            Ok(solid) => solid,
+            Err(err) => {
+                // The plugin panicked, so `services` might be in any state.
+                // Dropping it could panic again.
+                mem::forget(services);
+                return Err(err.into());
+            }
+        };
+
+        LoadedModel::new(solid, services, args)
+    })
+}
+
+/// A model plugin that has been loaded from a dynamic library
+///
+/// Libraries are never unloaded, as the objects that a plugin creates can refer
+/// to its code.
+#[derive(Clone, Copy)]
+pub struct Plugin {
+    declaration: &'static PluginDeclaration,
+}
+
+impl Plugin {
+    /// Load a plugin from the dynamic library at `path`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PluginError> {
+        let copy = LibraryCopy::new(path.as_ref())?;
+
+        // SAFETY: Loading a library runs its initialization code. We have to
+        // trust the plugin here, same as with any other code that the user
+        // decides to run.
+        let library = unsafe { Library::new(&copy.path) }?;
+
+        // SAFETY: This function has the same signature in all versions of the
+        // plugin interface, and uses the C ABI.
+        let api_version = unsafe {
+            library.get::<extern "C" fn() -> u32>(b"fj_plugin_api_version\0")
+        }?();
+        if api_version != API_VERSION {
+            return Err(PluginError::IncompatibleApi {
+                expected: API_VERSION,
+                found: api_version,
+            });
+        }
+
+        // SAFETY: The plugin uses the same version of the plugin interface, so
+        // the signature matches. We've done what we can to make sure the ABI
+        // matches too. See module documentation.
+        let declaration = unsafe {
+            library.get::<fn() -> &'static PluginDeclaration>(
+                b"fj_plugin_declaration\0",
+            )
+        }?();
+        if declaration.fj_version != FJ_VERSION {
+            return Err(PluginError::IncompatibleVersion {
+                expected: FJ_VERSION,
+                found: declaration.fj_version.to_owned(),
+            });
+        }
+
+        // The declaration lives as long as the library, so keep it loaded.
+        mem::forget(library);
+
+        Ok(Self { declaration })
+    }
+
+    /// Access the name of the plugin
+    pub fn name(&self) -> &'static str {
+        self.declaration.name
+    }
+
+    /// Access the parameters that the plugin declares
+    pub fn parameters(&self) -> &'static [ParameterDeclaration] {
+        self.declaration.parameters
+    }
+
+    /// Create a set of parameters, with the defaults the plugin declares
+    pub fn default_parameters(&self) -> Parameters {
+        Parameters::from_declarations(self.parameters())
+    }
+
+    /// Build the model that the plugin defines
+    ///
+    /// Returns an error, if the plugin panics. `services` might be in an
+    /// inconsistent state then.
+    pub fn model(
+        &self,
+        parameters: &Parameters,
+        services: &mut Services,
+    ) -> Result<Handle<Solid>, PluginError> {
+        let model = self.declaration.model;
+
+        panic::catch_unwind(AssertUnwindSafe(|| model(parameters, services)))
+            .map_err(|_| PluginError::Panicked)
+    }
+}
+
+/// The declaration of a model plugin
+///
+/// Generated by [`register_plugin!`]. There should be no need to use this type
+/// directly.
+pub struct PluginDeclaration {
+    /// The version of Fornjot that the plugin uses
+    pub fj_version: &'static str,
+
+    /// The name of the model
+    pub name: &'static str,
+
+    /// The parameters of the model
+    pub parameters: &'static [ParameterDeclaration],
+
+    /// The function that builds the model
+    pub model: fn(&Parameters, &mut Services) -> Handle<Solid>,
+}
+
+/// The declaration of a model parameter
+#[derive(Clone, Copy, Debug)]
+pub struct ParameterDeclaration {
+    /// The name of the parameter
+    pub name: &'static str,
+
+    /// The value of the parameter, if it isn't overridden
+    pub default: f64,
+}
+
+/// The values of a model's parameters
+#[derive(Clone, Debug, Default)]
+pub struct Parameters {
+    values: BTreeMap<String, f64>,
+}
+
+impl Parameters {
+    /// Create a set of parameters from their declarations
+    ///
+    /// Each parameter starts out with its default value.
+    pub fn from_declarations(declarations: &[ParameterDeclaration]) -> Self {
+        let values = declarations
+            .iter()
+            .map(|declaration| {
+                (declaration.name.to_owned(), declaration.default)
+            })
+            .collect();
+
+        Self { values }
+    }
+
+    /// Access the value of a parameter
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the parameter has not been declared.
+    pub fn get(&self, name: &str) -> f64 {
+        self.values.get(name).copied().unwrap_or_else(|| {
+            panic!("Parameter `{name}` has not been declared")
+        })
+    }
+
+    /// Override the value of a parameter
+    ///
+    /// Returns an error, if the parameter has not been declared.
+    pub fn set(&mut self, name: &str, value: f64) -> Result<(), PluginError> {
+        match self.values.get_mut(name) {
+            Some(v) => {
+                *v = value;
+                Ok(())
+            }
+            None => Err(PluginError::UnknownParameter(name.to_owned())),
+        }
+    }
+}
+
+/// Register a model plugin
+///
+/// Expects the name of the model, its parameters (a slice of
+/// [`ParameterDeclaration`]s), and the function that builds the model. See the
+/// [module documentation] for an example.
+///
+/// Must be used only once per library.
+///
+/// [`ParameterDeclaration`]: crate::plugin::ParameterDeclaration
+/// [module documentation]: crate::plugin
+#[macro_export]
+macro_rules! register_plugin {
+    (
+        name: $name:expr,
+        parameters: $parameters:expr,
+        model: $model:expr $(,)?
+    ) => {
+        #[no_mangle]
+        pub extern "C" fn fj_plugin_api_version() -> u32 {
+            $crate::plugin::API_VERSION
+        }
+
+        #[no_mangle]
+        pub fn fj_plugin_declaration(
+        ) -> &'static $crate::plugin::PluginDeclaration {
+            static DECLARATION: $crate::plugin::PluginDeclaration =
+                $crate::plugin::PluginDeclaration {
+                    fj_version: $crate::plugin::FJ_VERSION,
+                    name: $name,
+                    parameters: $parameters,
+                    model: $model,
+                };
+
+            &DECLARATION
+        }
+    };
+}
+
+/// Error loading or running a model plugin
+#[derive(Debug, thiserror::Error)]
+pub enum PluginError {
+    /// Failed to copy the library
+    #[error("Failed to copy plugin library")]
+    Io(#[from] io::Error),
+
+    /// Failed to load the library, or to find the plugin interface in it
+    #[error("Failed to load plugin library")]
+    Library(#[from] libloading::Error),
+
+    /// The plugin uses a different version of the plugin interface
+    #[error(
+        "Plugin uses version {found} of the plugin interface; expected \
+        {expected}"
+    )]
+    IncompatibleApi {
+        /// The version of the interface that the application uses
+        expected: u32,
+
+        /// The version of the interface that the plugin uses
+        found: u32,
+    },
+
+    /// The plugin uses a different version of Fornjot
+    #[error("Plugin uses Fornjot {found}; expected {expected}")]
+    IncompatibleVersion {
+        /// The version of Fornjot that the application uses
+        expected: &'static str,
+
+        /// The version of Fornjot that the plugin uses
+        found: String,
+    },
+
+    /// A parameter was overridden, but the plugin doesn't declare it
+    #[error("Model has no parameter `{0}`")]
+    UnknownParameter(String),
+
+    /// The plugin panicked while building the model
+    #[error("Plugin panicked while building the model")]
+    Panicked,
+}
+
+/// A copy of a library at a unique path, to load it from
+///
+/// Libraries are never unloaded, and loading one from the same path again
+/// would result in the previously loaded version. Besides, some platforms
+/// prevent a loaded library from being overwritten, which would keep the
+/// plugin from being recompiled.
+///
+/// The copy is removed when this is dropped, which happens right after the
+/// library has been loaded (or has failed to load). On most platforms, a loaded
+/// library stays loaded after its file has been removed. Where that's not the
+/// case (on Windows, a loaded library can't be removed), the copy is left in
+/// the temporary directory.
+struct LibraryCopy {
+    path: PathBuf,
+}
+
+impl LibraryCopy {
+    fn new(path: &Path) -> io::Result<Self> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        let file_name = path.file_name().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "Path has no file name")
+        })?;
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+        let copy = env::temp_dir().join(format!(
+            "fj-plugin-{}-{id}-{}",
+            process::id(),
+            file_name.to_string_lossy(),
+        ));
+        fs::copy(path, &copy)?;
+
+        Ok(Self { path: copy })
+    }
+}
+
+impl Drop for LibraryCopy {
+    fn drop(&mut self) {
+        // Failing to remove the copy only wastes some disk space. Nothing to
+        // do about it, and not worth bothering the user with.
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::{ParameterDeclaration, Parameters, Plugin, PluginError};
+
+    #[test]
+    fn load_errors() -> anyhow::Result<()> {
+        let dir = env::temp_dir();
+        let name = format!("fj-plugin-test-{}", process::id());
+
+        assert!(matches!(
+            Plugin::load(dir.join(&name).join("missing.so")),
+            Err(PluginError::Io(_))
+        ));
+
+        let not_a_library = dir.join(format!("{name}-not-a-library.so"));
+        fs::write(&not_a_library, "not a library")?;
+        let result = Plugin::load(&not_a_library);
+        fs::remove_file(&not_a_library)?;
+        assert!(matches!(result, Err(PluginError::Library(_))));
+
+        // The copy that the library was loaded from has been removed.
+        let copies = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let file_name = entry.file_name();
+                let file_name = file_name.to_string_lossy();
+                file_name.starts_with("fj-plugin-")
+                    && file_name.ends_with(&format!("{name}-not-a-library.so"))
+            })
+            .count();
+        assert_eq!(copies, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parameters() {
+        let mut parameters = Parameters::from_declarations(&[
+            ParameterDeclaration {
+                name: "a",
+                default: 1.,
+            },
+            ParameterDeclaration {
+                name: "b",
+                default: 2.,
+            },
+        ]);
+        assert_eq!(parameters.get("a"), 1.);
+
+        assert!(parameters.set("b", 3.).is_ok());
+        assert_eq!(parameters.get("b"), 3.);
+
+        assert!(matches!(
+            parameters.set("c", 4.),
+            Err(PluginError::UnknownParameter(_))
+        ));
+    }
+}
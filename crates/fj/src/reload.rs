@@ -0,0 +1,385 @@
+//! Export or display models that are loaded at runtime
+//!
+//! Models that are loaded at runtime, as opposed to being compiled into the
+//! application, can be reloaded while they are being displayed. This module
+//! contains the infrastructure that the different kinds of runtime-loaded
+//! models share.
+
+use std::{
+    fs, mem,
+    ops::ControlFlow,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+use fj_core::{
+    algorithms::{
+        approx::{
+            edge::HalfEdgeApproxCache, polyline::EdgePolylines, Tolerance,
+        },
+        bounding_volume::BoundingVolume,
+        debug::ParameterSpaces,
+        triangulate::Triangulate,
+    },
+    objects::Solid,
+    services::Services,
+    storage::Handle,
+};
+use fj_interop::{mesh::Mesh, model::Model};
+use fj_math::{Aabb, Point, Unit};
+use fj_window::MeshSender;
+
+use crate::{
+    config::Config,
+    handle_model::{init_tracing, tolerance},
+    server::Server,
+    snapshot::Snapshot,
+    Args, Command, Error,
+};
+
+/// A model that has been loaded, and is ready to be exported or displayed
+pub struct LoadedModel {
+    solid: Handle<Solid>,
+    model: Model,
+    tolerance: Tolerance,
+}
+
+impl LoadedModel {
+    /// Validate the provided solid and prepare it for display
+    ///
+    /// The model is assumed to be specified in millimeters.
+    pub fn new(
+        solid: Handle<Solid>,
+        services: Services,
+        args: &Args,
+    ) -> Result<Self, Error> {
+        if args.ignore_validation {
+            mem::forget(services);
+        } else {
+            services.drop_and_validate()?;
+        }
+
+        let aabb = solid.aabb().unwrap_or(Aabb {
+            min: Point::origin(),
+            max: Point::origin(),
+        });
+        let tolerance = tolerance(args, &aabb)?;
+
+        // The mesh is sent to the window in batches, as it is triangulated.
+        let model = Model {
+            mesh: Mesh::new(),
+            edges: (&*solid, tolerance).edge_polylines(),
+            aabb,
+            unit: Unit::Millimeter,
+        };
+
+        Ok(Self {
+            solid,
+            model,
+            tolerance,
+        })
+    }
+}
+
+/// Export, serve, or display a model, reloading it whenever `path` changes
+///
+/// Errors that happen while reloading the model are printed, and the previous
+/// version of the model is kept.
+pub fn export_or_display(
+    path: &Path,
+    load: impl Fn(&Args) -> Result<LoadedModel, Error> + Sync,
+) -> crate::Result {
+    let args = Args::parse();
+
+    let timings = init_tracing();
+
+    let LoadedModel {
+        solid,
+        model,
+        tolerance,
+    } = load(&args)?;
+
+    if let Some(export_path) = &args.export {
+        let mesh = with_approx_cache(&args, |cache| {
+            (&*solid, tolerance, cache).triangulate()
+        });
+
+        if args.timings {
+            print!("{}", timings.report());
+        }
+
+        crate::export::export_with_unit(&mesh, export_path, model.unit)?;
+        return Ok(());
+    }
+
+    if let Some(Command::Compare { reference, save }) = &args.command {
+        let mesh = with_approx_cache(&args, |cache| {
+            (&*solid, tolerance, cache).triangulate()
+        });
+        return crate::diff::compare_to_reference(
+            &mesh, reference, *save, tolerance,
+        );
+    }
+
+    if let Some(diff_path) = &args.diff {
+        let reference = Snapshot::load(diff_path)?.to_mesh();
+        let diff = |solid: &Solid, model: Model, tolerance: Tolerance| {
+            let model = Model {
+                mesh: with_approx_cache(&args, |cache| {
+                    (solid, tolerance, cache).triangulate()
+                }),
+                ..model
+            };
+            crate::diff::diff_to_reference(model, &reference, tolerance)
+        };
+
+        crate::window::display_progressive(
+            diff(&solid, model, tolerance),
+            Config::load()?.window_config(),
+            |sender| {
+                watch(
+                    path,
+                    &args,
+                    &load,
+                    || !sender.is_window_closed(),
+                    |loaded| {
+                        let model =
+                            diff(&loaded.solid, loaded.model, loaded.tolerance);
+                        match sender.replace(model) {
+                            Ok(()) => ControlFlow::Continue(()),
+                            Err(_) => ControlFlow::Break(()),
+                        }
+                    },
+                );
+            },
+        )?;
+        return Ok(());
+    }
+
+    if let Some(address) = args.serve {
+        let server = Server::bind(address).map_err(Error::Serve)?;
+        let serve = |solid: &Solid, model: &Model, tolerance: Tolerance| {
+            let mesh = with_approx_cache(&args, |cache| {
+                (solid, tolerance, cache).triangulate()
+            });
+            server.set_model(&mesh, model.aabb, model.unit);
+        };
+        serve(&solid, &model, tolerance);
+
+        println!("Serving model at http://{address}/");
+
+        // The server only stops on error. The watcher needs to stop then too,
+        // or the scope would never end, and the error would never be returned.
+        let server_stopped = AtomicBool::new(false);
+        return thread::scope(|scope| {
+            scope.spawn(|| {
+                watch(
+                    path,
+                    &args,
+                    &load,
+                    || !server_stopped.load(Ordering::Relaxed),
+                    |loaded| {
+                        serve(&loaded.solid, &loaded.model, loaded.tolerance);
+                        ControlFlow::Continue(())
+                    },
+                );
+            });
+
+            let result = server.run().map_err(Error::Serve);
+            server_stopped.store(true, Ordering::Relaxed);
+            result
+        });
+    }
+
+    let config = Config::load()?.window_config();
+    crate::window::display_progressive(model, config, |sender| {
+        send_mesh(&solid, tolerance, &sender, &args);
+        watch(
+            path,
+            &args,
+            &load,
+            || !sender.is_window_closed(),
+            |LoadedModel {
+                 solid,
+                 model,
+                 tolerance,
+             }| {
+                if sender.replace(model).is_err() {
+                    return ControlFlow::Break(());
+                }
+                send_mesh(&solid, tolerance, &sender, &args);
+                ControlFlow::Continue(())
+            },
+        );
+    })?;
+
+    if args.timings {
+        print!("{}", timings.report());
+    }
+
+    Ok(())
+}
+
+/// How often the model is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+fn watch(
+    path: &Path,
+    args: &Args,
+    load: &impl Fn(&Args) -> Result<LoadedModel, Error>,
+    keep_watching: impl Fn() -> bool,
+    mut update: impl FnMut(LoadedModel) -> ControlFlow<()>,
+) {
+    let modified = |path: &Path| {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    };
+    let mut last_modified = modified(path);
+
+    while keep_watching() {
+        thread::sleep(POLL_INTERVAL);
+
+        let current = modified(path);
+        if current == last_modified {
+            continue;
+        }
+        last_modified = current;
+
+        match load(args) {
+            Ok(loaded) => {
+                if update(loaded).is_break() {
+                    break;
+                }
+            }
+            Err(err) => {
+                eprintln!("Failed to reload `{}`: {err:?}", path.display());
+            }
+        }
+    }
+}
+
+fn send_mesh(
+    solid: &Solid,
+    tolerance: Tolerance,
+    sender: &MeshSender,
+    args: &Args,
+) {
+    with_approx_cache(args, |cache| {
+        (solid, tolerance, cache).triangulate_in_batches(|batch| match sender
+            .send(batch)
+        {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(_) => ControlFlow::Break(()),
+        });
+    });
+
+    if args.parameter_spaces {
+        let parameter_spaces =
+            (solid, tolerance).parameter_spaces(Unit::Millimeter);
+        // The window might have been closed already. Nothing to do then.
+        let _ = sender.send_parameter_spaces(parameter_spaces);
+    }
+}
+
+/// Call `f` with the approximation cache, if one was requested
+///
+/// The cache is loaded from the path passed via `--approx-cache`, and saved
+/// there again afterwards. Without that argument, `f` gets an empty cache.
+fn with_approx_cache<R>(
+    args: &Args,
+    f: impl FnOnce(&mut HalfEdgeApproxCache) -> R,
+) -> R {
+    let Some(path) = &args.approx_cache else {
+        return f(&mut HalfEdgeApproxCache::default());
+    };
+
+    // If the cache doesn't exist yet, or has been written by an incompatible
+    // version, everything is approximated from scratch. It is overwritten
+    // below, so that's not worth reporting.
+    let mut cache = HalfEdgeApproxCache::load(path).unwrap_or_default();
+    let result = f(&mut cache);
+
+    if let Err(err) = cache.save(path) {
+        eprintln!(
+            "Failed to save approximation cache to `{}`: {err}",
+            path.display()
+        );
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        env,
+        fs::{self, File},
+        ops::ControlFlow,
+        process,
+        time::{Duration, SystemTime},
+    };
+
+    use fj_core::{
+        objects::Solid,
+        operations::{insert::Insert, primitives::BuildPrimitive},
+        services::Services,
+    };
+
+    use crate::{Args, Error};
+
+    use super::{watch, LoadedModel};
+
+    #[test]
+    fn reload_errors_keep_watching() -> anyhow::Result<()> {
+        let path =
+            env::temp_dir().join(format!("fj-reload-test-{}", process::id()));
+        let file = File::create(&path)?;
+        let args = <Args as clap::Parser>::parse_from(["fj"]);
+
+        // Every poll sees a modified file. The first reload fails, the second
+        // one succeeds.
+        let polls = Cell::new(0);
+        let loads = Cell::new(0);
+        let mut updates = 0;
+
+        watch(
+            &path,
+            &args,
+            &|args: &Args| {
+                loads.set(loads.get() + 1);
+                if loads.get() == 1 {
+                    return Err(Error::Deviation(path.clone()));
+                }
+
+                let mut services = Services::new();
+                let solid = Solid::box_from_dims([1., 1., 1.], &mut services)
+                    .insert(&mut services);
+                LoadedModel::new(solid, services, args)
+            },
+            || {
+                polls.set(polls.get() + 1);
+                file.set_modified(
+                    SystemTime::UNIX_EPOCH + Duration::from_secs(polls.get()),
+                )
+                .is_ok()
+                    && polls.get() <= 2
+            },
+            |_| {
+                updates += 1;
+                ControlFlow::Continue(())
+            },
+        );
+
+        drop(file);
+        fs::remove_file(&path)?;
+
+        assert_eq!(loads.get(), 2);
+        assert_eq!(updates, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,305 @@
+//! # Scripting support
+//!
+//! Having to recompile a model after every change slows down prototyping. This
+//! module evaluates models that are defined in [Rhai], a scripting language for
+//! Rust, at runtime. [`handle_script`] displays such a model, and reloads it
+//! whenever the script changes, resulting in a quick edit-save-reload loop.
+//!
+//! Scripts have access to the following functions:
+//!
+//! - `sketch(x, y)`: Start a sketch at the provided point. Sketches provide the
+//!   methods `move_to(x, y)`, `line_to(x, y)`, `arc_to(x, y, angle)`,
+//!   `close()`, and `circle(x, y, radius)`, which work like their equivalents
+//!   in [`SketchBuilder`].
+//! - `sweep(sketch, x, y, z)`: Sweep a sketch in the xy-plane along a path.
+//! - `cuboid(x, y, z)`: Create a box, centered on the origin in x and y.
+//! - `cylinder(radius, height)`: Create a cylinder.
+//! - `translate(solid, x, y, z)`: Translate a solid.
+//! - `rotate(solid, x, y, z)`: Rotate a solid around the origin. The direction
+//!   of the vector defines the axis, its length the angle in radians.
+//!
+//! All numbers must be floating-point numbers, so `1.0` instead of `1`. The
+//! last expression of the script must evaluate to a solid, or an array of
+//! solids:
+//!
+//! ``` rhai
+//! let plate = sketch(0.0, 0.0)
+//!     .line_to(2.0, 0.0)
+//!     .line_to(2.0, 1.0)
+//!     .line_to(0.0, 1.0)
+//!     .close()
+//!     .circle(1.0, 0.5, 0.25)
+//!     .sweep(0.0, 0.0, 0.5);
+//!
+//! [plate, cylinder(0.25, 1.0).translate(3.0, 0.0, 0.0)]
+//! ```
+//!
+//! This module is only available, if the `scripting` feature is enabled.
+//!
+//! [Rhai]: https://rhai.rs/
+
+use std::{cell::RefCell, fs, io, path::Path, rc::Rc};
+
+use fj_core::{
+    bindings::{BindingsError, Kernel, Sketch},
+    objects::Solid,
+    operations::insert::Insert,
+    services::Services,
+    storage::Handle,
+};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, FLOAT};
+
+use crate::reload::{self, LoadedModel};
+
+/// Export or display a model that is defined by the script at `path`
+///
+/// Works like [`handle_model`], but evaluates the script first. When displaying
+/// the model, the script is evaluated again whenever it changes, and the
+/// window is updated with the result. Errors that happen while reloading the
+/// script are printed, and the previous version of the model is kept.
+///
+/// The model is assumed to be specified in millimeters.
+///
+/// [`handle_model`]: crate::handle_model()
+pub fn handle_script(path: impl AsRef<Path>) -> crate::Result {
+    let path = path.as_ref();
+
+    reload::export_or_display(path, |args| {
+        let source = fs::read_to_string(path).map_err(ScriptError::from)?;
+        let (solid, services) = eval_script(&source)?;
+
+        LoadedModel::new(solid, services, args)
+    })
+}
+
+/// Evaluate a script and return the solid it defines
+///
+/// If the script evaluates to an array of solids, their shells are combined
+/// into a single solid.
+///
+/// The returned [`Services`] have not been validated yet. This is left to the
+/// caller, for example by calling [`Services::drop_and_validate`].
+pub fn eval_script(
+    source: &str,
+) -> Result<(Handle<Solid>, Services), ScriptError> {
+    let kernel = Rc::new(RefCell::new(Kernel::new()));
+    let result = engine(&kernel).eval::<Dynamic>(source);
+
+    // If the script failed, dropping the kernel discards any validation errors.
+    // The caller is getting an error anyway.
+    let mut kernel = Rc::try_unwrap(kernel)
+        .ok()
+        .expect("Engine has been dropped; no other references to kernel")
+        .into_inner();
+
+    let solids = result
+        .map_err(|err| ScriptError::Eval(err.to_string()))
+        .and_then(solids)?;
+
+    let shells = solids
+        .iter()
+        .flat_map(|solid| solid.shells().iter().cloned())
+        .collect::<Vec<_>>();
+    let solid = Solid::new(shells).insert(kernel.services());
+
+    Ok((solid, kernel.into_services()))
+}
+
+/// Error evaluating a script
+#[derive(Debug, thiserror::Error)]
+pub enum ScriptError {
+    /// Failed to read the script
+    #[error("Failed to read script")]
+    Io(#[from] io::Error),
+
+    /// The script failed to evaluate
+    #[error("Error evaluating script: {0}")]
+    Eval(String),
+
+    /// The script evaluated to something that is not a solid
+    #[error(
+        "Script must evaluate to a solid or an array of solids, not `{0}`"
+    )]
+    InvalidResult(String),
+}
+
+fn solids(value: Dynamic) -> Result<Vec<Handle<Solid>>, ScriptError> {
+    if value.is_array() {
+        value.cast::<Array>().into_iter().map(solid).collect()
+    } else {
+        solid(value).map(|solid| vec![solid])
+    }
+}
+
+fn solid(value: Dynamic) -> Result<Handle<Solid>, ScriptError> {
+    let type_name = value.type_name();
+    value
+        .try_cast::<ScriptSolid>()
+        .map(|solid| solid.0)
+        .ok_or_else(|| ScriptError::InvalidResult(type_name.to_owned()))
+}
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn engine(kernel: &Rc<RefCell<Kernel>>) -> Engine {
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<ScriptSketch>("Sketch")
+        .register_fn("sketch", ScriptSketch::new)
+        .register_fn("move_to", ScriptSketch::move_to)
+        .register_fn("line_to", ScriptSketch::line_to)
+        .register_fn("arc_to", ScriptSketch::arc_to)
+        .register_fn("close", ScriptSketch::close)
+        .register_fn("circle", ScriptSketch::circle)
+        .register_type_with_name::<ScriptSolid>("Solid");
+
+    let k = kernel.clone();
+    engine.register_fn(
+        "sweep",
+        move |sketch: ScriptSketch,
+              x: FLOAT,
+              y: FLOAT,
+              z: FLOAT|
+              -> ScriptResult<ScriptSolid> {
+            let solid =
+                k.borrow_mut().sweep(&sketch.0, [x, y, z]).map_err(error)?;
+            Ok(ScriptSolid(solid))
+        },
+    );
+
+    let k = kernel.clone();
+    engine.register_fn(
+        "cuboid",
+        move |x: FLOAT, y: FLOAT, z: FLOAT| -> ScriptResult<ScriptSolid> {
+            let solid = k.borrow_mut().cuboid([x, y, z]).map_err(error)?;
+            Ok(ScriptSolid(solid))
+        },
+    );
+
+    let k = kernel.clone();
+    engine.register_fn(
+        "cylinder",
+        move |radius: FLOAT, height: FLOAT| -> ScriptResult<ScriptSolid> {
+            let solid =
+                k.borrow_mut().cylinder(radius, height).map_err(error)?;
+            Ok(ScriptSolid(solid))
+        },
+    );
+
+    let k = kernel.clone();
+    engine.register_fn(
+        "translate",
+        move |solid: ScriptSolid,
+              x: FLOAT,
+              y: FLOAT,
+              z: FLOAT|
+              -> ScriptResult<ScriptSolid> {
+            let solid = k
+                .borrow_mut()
+                .translate(&solid.0, [x, y, z])
+                .map_err(error)?;
+            Ok(ScriptSolid(solid))
+        },
+    );
+
+    let k = kernel.clone();
+    engine.register_fn(
+        "rotate",
+        move |solid: ScriptSolid,
+              x: FLOAT,
+              y: FLOAT,
+              z: FLOAT|
+              -> ScriptResult<ScriptSolid> {
+            let solid =
+                k.borrow_mut().rotate(&solid.0, [x, y, z]).map_err(error)?;
+            Ok(ScriptSolid(solid))
+        },
+    );
+
+    engine
+}
+
+fn error(err: BindingsError) -> Box<EvalAltResult> {
+    err.to_string().into()
+}
+
+#[derive(Clone)]
+struct ScriptSketch(Sketch);
+
+impl ScriptSketch {
+    fn new(x: FLOAT, y: FLOAT) -> ScriptResult<Self> {
+        Sketch::new([x, y]).map(Self).map_err(error)
+    }
+
+    fn move_to(self, x: FLOAT, y: FLOAT) -> ScriptResult<Self> {
+        self.0.move_to([x, y]).map(Self).map_err(error)
+    }
+
+    fn line_to(self, x: FLOAT, y: FLOAT) -> ScriptResult<Self> {
+        self.0.line_to([x, y]).map(Self).map_err(error)
+    }
+
+    fn arc_to(self, x: FLOAT, y: FLOAT, angle: FLOAT) -> ScriptResult<Self> {
+        self.0.arc_to([x, y], angle).map(Self).map_err(error)
+    }
+
+    fn close(self) -> ScriptResult<Self> {
+        self.0.close().map(Self).map_err(error)
+    }
+
+    fn circle(self, x: FLOAT, y: FLOAT, radius: FLOAT) -> ScriptResult<Self> {
+        self.0.circle([x, y], radius).map(Self).map_err(error)
+    }
+}
+
+#[derive(Clone)]
+struct ScriptSolid(Handle<Solid>);
+
+#[cfg(test)]
+mod tests {
+    use super::{eval_script, ScriptError};
+
+    #[test]
+    fn eval_script_with_multiple_solids() -> anyhow::Result<()> {
+        let (solid, services) = eval_script(
+            "
+            let plate = sketch(0.0, 0.0)
+                .line_to(2.0, 0.0)
+                .line_to(2.0, 1.0)
+                .line_to(0.0, 1.0)
+                .close()
+                .sweep(0.0, 0.0, 0.5);
+
+            [plate, cuboid(1.0, 1.0, 1.0).translate(4.0, 0.0, 0.0)]
+            ",
+        )?;
+
+        assert_eq!(solid.shells().len(), 2);
+        services.drop_and_validate()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn eval_script_errors() {
+        assert!(matches!(
+            eval_script(
+                "sketch(0.0, 0.0).line_to(1.0, 0.0).sweep(0.0, 0.0, 1.0)"
+            ),
+            Err(ScriptError::Eval(_))
+        ));
+        assert!(matches!(
+            eval_script("cuboid(1.0, 1.0, 1.0).rotate(0.0 / 0.0, 0.0, 0.0)"),
+            Err(ScriptError::Eval(_))
+        ));
+        assert!(matches!(
+            eval_script("sketch(1.0e308 * 10.0, 0.0)"),
+            Err(ScriptError::Eval(_))
+        ));
+        assert!(matches!(
+            eval_script("1.0"),
+            Err(ScriptError::InvalidResult(_))
+        ));
+    }
+}
@@ -0,0 +1,536 @@
+//! # Serving models over the network
+//!
+//! Triangulating a complex model can take a lot of time. [`Server`] makes it
+//! possible to do that on a powerful machine, and display the result using a
+//! remote or web-based frontend. It runs without a window, and speaks a minimal
+//! subset of HTTP/1.1:
+//!
+//! - `GET /model`: Information about the current model, as JSON.
+//! - `GET /model/mesh`: The mesh of the current model, in binary form.
+//! - `POST /evaluate`: Build the model that the request body describes, and
+//!   respond with its mesh, in binary form. The body must be a JSON model
+//!   description, as understood by [`declarative`]. The tolerance can be
+//!   specified using a `tolerance` query parameter (`/evaluate?tolerance=0.1`).
+//!   Otherwise a default is derived from the size of the model.
+//!
+//! Responses allow cross-origin requests, so web frontends that are served from
+//! elsewhere can access them.
+//!
+//! The server is meant for trusted networks, but protects itself against the
+//! most basic kinds of abuse: The sizes of request heads and bodies are
+//! limited, connections that don't send anything time out, and the number of
+//! connections that are handled at once is bounded.
+//!
+//! ## Binary mesh format
+//!
+//! Meshes are sent as indexed triangle lists, encoded by [`encode_mesh`]. All
+//! numbers are little-endian.
+//!
+//! - 4 bytes: The magic number `FJM1`
+//! - `u32`: The number of vertices, `V`
+//! - `u32`: The number of triangles, `T`
+//! - `V` times three `f32`s: The position of each vertex
+//! - `T` times three `u32`s: The vertex indices of each triangle
+//! - `T` times four `u8`s: The RGBA color of each triangle
+//!
+//! [`declarative`]: crate::declarative
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    panic, str,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use fj_core::{
+    algorithms::{
+        approx::Tolerance, bounding_volume::BoundingVolume,
+        triangulate::Triangulate,
+    },
+    operations::insert::Insert,
+    services::Services,
+};
+use fj_interop::{mesh::Mesh, mesh_builder::MeshBuilder};
+use fj_math::{Aabb, Point, Scalar, Unit};
+
+use crate::declarative::ModelDescription;
+
+/// The magic number at the start of every encoded mesh
+pub const MESH_MAGIC: [u8; 4] = *b"FJM1";
+
+/// Serves models over HTTP
+///
+/// See [module documentation] for the details.
+///
+/// [module documentation]: self
+pub struct Server {
+    listener: TcpListener,
+    model: RwLock<Option<ServedModel>>,
+    connections: AtomicUsize,
+}
+
+impl Server {
+    /// Create a server that listens on the provided address
+    ///
+    /// The server has no model to serve, until [`Server::set_model`] is called.
+    pub fn bind(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+
+        Ok(Self {
+            listener,
+            model: RwLock::new(None),
+            connections: AtomicUsize::new(0),
+        })
+    }
+
+    /// Access the address that the server listens on
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Set the model that is served
+    ///
+    /// Replaces the previous model. Can be called while the server is running.
+    pub fn set_model(&self, mesh: &Mesh<Point<3>>, aabb: Aabb<3>, unit: Unit) {
+        let model = ServedModel {
+            info: serde_json::json!({
+                "unit": unit.symbol(),
+                "aabb": {
+                    "min": aabb.min.coords.components.map(Scalar::into_f64),
+                    "max": aabb.max.coords.components.map(Scalar::into_f64),
+                },
+                "triangles": mesh.triangles().count(),
+            })
+            .to_string(),
+            mesh: encode_mesh(mesh),
+        };
+
+        *self.model.write().unwrap_or_else(|err| err.into_inner()) =
+            Some(model);
+    }
+
+    /// Run the server
+    ///
+    /// Handles each connection on a separate thread, up to
+    /// [`MAX_CONNECTIONS`] at once. Further connections are rejected, until
+    /// some have been handled. Only returns, if accepting a connection fails.
+    pub fn run(&self) -> io::Result<()> {
+        thread::scope(|scope| {
+            for stream in self.listener.incoming() {
+                let stream = stream?;
+
+                if let Err(err) = set_timeouts(&stream) {
+                    eprintln!("Error setting up connection: {err}");
+                    continue;
+                }
+
+                let connections =
+                    self.connections.fetch_add(1, Ordering::AcqRel);
+                if connections >= MAX_CONNECTIONS {
+                    self.connections.fetch_sub(1, Ordering::AcqRel);
+
+                    // Rejecting happens on the accepting thread, but the write
+                    // timeout keeps a client from blocking it for long.
+                    let _ = Response::text(
+                        Status::ServiceUnavailable,
+                        "Too many connections",
+                    )
+                    .write_to(&stream);
+                    continue;
+                }
+
+                scope.spawn(move || {
+                    if let Err(err) = self.handle_connection(stream) {
+                        eprintln!("Error handling connection: {err}");
+                    }
+                    self.connections.fetch_sub(1, Ordering::AcqRel);
+                });
+            }
+
+            Ok(())
+        })
+    }
+
+    fn handle_connection(&self, stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(&stream);
+
+        let head = {
+            let mut head = (&mut reader).take(MAX_HEAD_SIZE);
+            let mut lines = Vec::new();
+
+            loop {
+                let mut line = String::new();
+                head.read_line(&mut line)?;
+
+                // A line without a line break has been cut off by the size
+                // limit, or by the client closing the connection.
+                if !line.ends_with('\n') {
+                    break None;
+                }
+
+                let line = line.trim_end().to_owned();
+                if line.is_empty() {
+                    break Some(lines);
+                }
+                lines.push(line);
+            }
+        };
+        let Some(head) = head else {
+            return Response::text(
+                Status::RequestHeaderFieldsTooLarge,
+                "Request head too large or incomplete",
+            )
+            .write_to(&stream);
+        };
+        let mut lines = head.iter();
+        let request_line = lines.next().map_or("", String::as_str);
+
+        let mut content_length = 0;
+        for line in lines {
+            if let Some((name, value)) = line.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            return Response::text(Status::BadRequest, "Malformed request")
+                .write_to(&stream);
+        };
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+        if content_length > MAX_BODY_SIZE {
+            return Response::text(Status::PayloadTooLarge, "Body too large")
+                .write_to(&stream);
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+
+        let response = match (method, path) {
+            ("GET", "/model") => self.with_model(|model| {
+                Response::new(
+                    Status::Ok,
+                    "application/json",
+                    model.info.clone().into_bytes(),
+                )
+            }),
+            ("GET", "/model/mesh") => self.with_model(|model| {
+                Response::new(
+                    Status::Ok,
+                    "application/octet-stream",
+                    model.mesh.clone(),
+                )
+            }),
+            ("POST", "/evaluate") => {
+                panic::catch_unwind(|| evaluate(&body, query)).unwrap_or_else(
+                    |_| {
+                        Response::text(
+                            Status::InternalServerError,
+                            "Kernel panicked while evaluating model",
+                        )
+                    },
+                )
+            }
+            ("OPTIONS", _) => Response::new(Status::NoContent, "", Vec::new()),
+            _ => Response::text(Status::NotFound, "Not found"),
+        };
+
+        response.write_to(&stream)
+    }
+
+    fn with_model(&self, f: impl FnOnce(&ServedModel) -> Response) -> Response {
+        let model = self.model.read().unwrap_or_else(|err| err.into_inner());
+
+        match model.as_ref() {
+            Some(model) => f(model),
+            None => Response::text(Status::NotFound, "No model available yet"),
+        }
+    }
+}
+
+/// Encode a mesh into the binary format that the server uses
+///
+/// See [module documentation] for a description of the format.
+///
+/// [module documentation]: self
+pub fn encode_mesh(mesh: &Mesh<Point<3>>) -> Vec<u8> {
+    let mesh = MeshBuilder::new()
+        .build(mesh)
+        .expect("32-bit indices can address any number of vertices");
+    let indices = mesh.indices.to_u32();
+
+    let num_vertices = mesh.vertices.len() as u32;
+    let num_triangles = mesh.colors.len() as u32;
+
+    let mut bytes = Vec::with_capacity(
+        12 + mesh.vertices.len() * 12
+            + indices.len() * 4
+            + mesh.colors.len() * 4,
+    );
+
+    bytes.extend(MESH_MAGIC);
+    bytes.extend(num_vertices.to_le_bytes());
+    bytes.extend(num_triangles.to_le_bytes());
+
+    for vertex in &mesh.vertices {
+        for coord in vertex.coords.components {
+            bytes.extend((coord.into_f64() as f32).to_le_bytes());
+        }
+    }
+    for index in indices {
+        bytes.extend(index.to_le_bytes());
+    }
+    for color in &mesh.colors {
+        bytes.extend(color.0);
+    }
+
+    bytes
+}
+
+/// The maximum number of connections that the server handles at once
+pub const MAX_CONNECTIONS: usize = 64;
+
+/// The maximum size of a request body that the server accepts
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+/// The maximum size of a request line and headers that the server accepts
+const MAX_HEAD_SIZE: u64 = 16 * 1024;
+
+/// How long the server waits for a client to send or receive data
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn set_timeouts(stream: &TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    Ok(())
+}
+
+struct ServedModel {
+    info: String,
+    mesh: Vec<u8>,
+}
+
+fn evaluate(body: &[u8], query: &str) -> Response {
+    let description = match str::from_utf8(body)
+        .map_err(|err| err.to_string())
+        .and_then(|body| {
+            ModelDescription::from_json(body).map_err(|err| err.to_string())
+        }) {
+        Ok(description) => description,
+        Err(err) => return Response::text(Status::BadRequest, err),
+    };
+
+    // Dropping `Services` with validation errors would panic, so make sure to
+    // validate them, whether building the model succeeded or not.
+    let mut services = Services::new();
+    let solid = description
+        .build(&mut services)
+        .map(|solid| solid.insert(&mut services));
+    let validation = services.drop_and_validate();
+
+    let solid = match (solid, validation) {
+        (Ok(solid), Ok(())) => solid,
+        (Err(err), _) => {
+            return Response::text(Status::BadRequest, err.to_string())
+        }
+        (Ok(_), Err(errors)) => {
+            return Response::text(Status::BadRequest, errors.to_string())
+        }
+    };
+
+    let tolerance = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("tolerance="))
+        .map(|tolerance| {
+            tolerance
+                .parse::<f64>()
+                .map_err(|err| err.to_string())
+                .and_then(|tolerance| {
+                    Tolerance::from_scalar(tolerance)
+                        .map_err(|err| err.to_string())
+                })
+        })
+        .unwrap_or_else(|| {
+            let aabb = solid.aabb().unwrap_or(Aabb {
+                min: Point::origin(),
+                max: Point::origin(),
+            });
+            Tolerance::from_aabb(&aabb).map_err(|err| err.to_string())
+        });
+    let tolerance = match tolerance {
+        Ok(tolerance) => tolerance,
+        Err(err) => return Response::text(Status::BadRequest, err),
+    };
+
+    let mesh = (&*solid, tolerance).triangulate();
+    Response::new(Status::Ok, "application/octet-stream", encode_mesh(&mesh))
+}
+
+#[derive(Clone, Copy)]
+enum Status {
+    Ok,
+    NoContent,
+    BadRequest,
+    NotFound,
+    PayloadTooLarge,
+    RequestHeaderFieldsTooLarge,
+    InternalServerError,
+    ServiceUnavailable,
+}
+
+impl Status {
+    fn line(&self) -> &'static str {
+        match self {
+            Self::Ok => "200 OK",
+            Self::NoContent => "204 No Content",
+            Self::BadRequest => "400 Bad Request",
+            Self::NotFound => "404 Not Found",
+            Self::PayloadTooLarge => "413 Payload Too Large",
+            Self::RequestHeaderFieldsTooLarge => {
+                "431 Request Header Fields Too Large"
+            }
+            Self::InternalServerError => "500 Internal Server Error",
+            Self::ServiceUnavailable => "503 Service Unavailable",
+        }
+    }
+}
+
+struct Response {
+    status: Status,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: Status, content_type: &'static str, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            content_type,
+            body,
+        }
+    }
+
+    fn text(status: Status, text: impl Into<String>) -> Self {
+        Self::new(
+            status,
+            "text/plain; charset=utf-8",
+            text.into().into_bytes(),
+        )
+    }
+
+    fn write_to(&self, mut stream: &TcpStream) -> io::Result<()> {
+        let mut head = format!(
+            "HTTP/1.1 {}\r\n\
+            Access-Control-Allow-Origin: *\r\n\
+            Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+            Access-Control-Allow-Headers: Content-Type\r\n\
+            Content-Length: {}\r\n\
+            Connection: close\r\n",
+            self.status.line(),
+            self.body.len(),
+        );
+        if !self.content_type.is_empty() {
+            head.push_str(&format!("Content-Type: {}\r\n", self.content_type));
+        }
+        head.push_str("\r\n");
+
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(&self.body)?;
+        stream.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        thread,
+    };
+
+    use fj_interop::mesh::{Color, Mesh};
+    use fj_math::{Aabb, Unit};
+
+    use super::{Server, MAX_HEAD_SIZE, MESH_MAGIC};
+
+    #[test]
+    fn serve_model() -> anyhow::Result<()> {
+        let server = Server::bind("127.0.0.1:0")?;
+        let address = server.local_addr()?;
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+            Color::default(),
+        );
+        server.set_model(
+            &mesh,
+            Aabb::<3>::from_points(mesh.vertices()),
+            Unit::Millimeter,
+        );
+
+        thread::spawn(move || server.run());
+
+        let request = |request: &str| -> anyhow::Result<Vec<u8>> {
+            let mut stream = TcpStream::connect(address)?;
+            stream.write_all(request.as_bytes())?;
+
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response)?;
+
+            Ok(response)
+        };
+        let body = |response: &[u8]| {
+            let end_of_head = response
+                .windows(4)
+                .position(|window| window == b"\r\n\r\n")
+                .expect("Response has a head");
+            response[end_of_head + 4..].to_vec()
+        };
+
+        let response = request("GET /model/mesh HTTP/1.1\r\n\r\n")?;
+        assert!(response.starts_with(b"HTTP/1.1 200 OK"));
+        let mesh = body(&response);
+        assert_eq!(mesh[..4], MESH_MAGIC);
+        assert_eq!(mesh.len(), 12 + 3 * 12 + 3 * 4 + 4);
+
+        let description = r#"{ "solids": [{ "box": { "size": [1, 1, 1] } }] }"#;
+        let response = request(&format!(
+            "POST /evaluate HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+            description.len(),
+            description,
+        ))?;
+        assert!(response.starts_with(b"HTTP/1.1 200 OK"));
+        assert_eq!(body(&response)[..4], MESH_MAGIC);
+
+        let response = request("POST /evaluate HTTP/1.1\r\n\r\n")?;
+        assert!(response.starts_with(b"HTTP/1.1 400 Bad Request"));
+
+        let description = r#"{ "solids": [{ "box": { "size": [0, 1, 1] } }] }"#;
+        let response = request(&format!(
+            "POST /evaluate HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+            description.len(),
+            description,
+        ))?;
+        assert!(response.starts_with(b"HTTP/1.1 400 Bad Request"));
+
+        // Send exactly as much as the server reads, so it doesn't close the
+        // connection with unread data, which would reset it.
+        let request_line = "GET /model HTTP/1.1\r\nX-Padding: ";
+        let response = request(&format!(
+            "{request_line}{}",
+            "x".repeat(MAX_HEAD_SIZE as usize - request_line.len()),
+        ))?;
+        assert!(response
+            .starts_with(b"HTTP/1.1 431 Request Header Fields Too Large"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,428 @@
+//! # Snapshot testing for models
+//!
+//! Changes to a model, or to Fornjot itself, can change the geometry of a model
+//! in unintended ways. Snapshot tests catch this, by comparing the triangulated
+//! model against a reference that has been stored previously:
+//!
+//! ``` no_run
+//! use fj::{
+//!     core::{
+//!         objects::Solid, operations::primitives::BuildPrimitive,
+//!         services::Services,
+//!     },
+//!     snapshot::Snapshot,
+//! };
+//!
+//! let mut services = Services::new();
+//! let model = Solid::box_from_dims([3., 2., 1.], &mut services);
+//!
+//! Snapshot::of_model(&model, 0.01).assert_matches("box", 1e-6);
+//! ```
+//!
+//! References are stored in the `snapshots/` directory of the crate that is
+//! being tested. If a reference doesn't exist yet, it is created. To update
+//! existing references after an intended change, run the tests with the
+//! `FJ_UPDATE_SNAPSHOTS` environment variable set. When running in CI (as
+//! indicated by the `CI` environment variable), missing references are an
+//! error instead.
+//!
+//! The comparison is done in two steps. First, a hash of the mesh is compared
+//! against the hash of the reference. If those don't match, the triangles of
+//! both are compared, allowing their vertices to deviate by a tolerance. This
+//! keeps tiny numerical differences, like those between platforms, from
+//! failing the test.
+
+use std::{
+    collections::HashMap,
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use fj_core::algorithms::{approx::Tolerance, triangulate::Triangulate};
+use fj_interop::mesh::{Color, Mesh};
+use fj_math::{Point, Scalar};
+
+/// A snapshot of a triangulated model
+///
+/// The triangles of the model are stored in a canonical order, which makes
+/// snapshots independent of the order in which they were triangulated.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Snapshot {
+    hash: u64,
+    triangles: Vec<[[f64; 3]; 3]>,
+}
+
+impl Snapshot {
+    /// Triangulate a model and create a snapshot of the result
+    pub fn of_model<M>(model: &M, tolerance: impl Into<Tolerance>) -> Self
+    where
+        for<'r> (&'r M, Tolerance): Triangulate,
+    {
+        let mesh = (model, tolerance.into()).triangulate();
+        Self::from_mesh(&mesh)
+    }
+
+    /// Create a snapshot of a mesh
+    pub fn from_mesh(mesh: &Mesh<Point<3>>) -> Self {
+        let mut triangles = mesh
+            .triangles()
+            .map(|triangle| {
+                let points = triangle.inner.points().map(|point| {
+                    point.coords.components.map(|coord| {
+                        // Both zeros compare as equal, but have different bit
+                        // patterns. Make sure they hash the same.
+                        if coord == Scalar::ZERO {
+                            0.
+                        } else {
+                            coord.into_f64()
+                        }
+                    })
+                });
+
+                // Rotate the triangle, so its smallest vertex comes first. This
+                // keeps its winding intact.
+                let first = (0..3)
+                    .min_by(|&a, &b| compare_points(&points[a], &points[b]))
+                    .expect("Triangle has vertices");
+                [0, 1, 2].map(|i| points[(first + i) % 3])
+            })
+            .collect::<Vec<_>>();
+
+        triangles.sort_by(|a, b| {
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| compare_points(a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        // FNV-1a. The hash must be the same on all platforms and with all
+        // compiler versions, which rules out the hashers from the standard
+        // library.
+        let mut hash = 0xcbf29ce484222325_u64;
+        for coord in triangles.iter().flatten().flatten() {
+            for byte in coord.to_le_bytes() {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+
+        Self { hash, triangles }
+    }
+
+    /// Access the hash of the snapshot
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Access the number of triangles in the snapshot
+    pub fn num_triangles(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Convert the snapshot back into a mesh
+    ///
+    /// Triangles are in canonical order, and have the default color.
+    pub fn to_mesh(&self) -> Mesh<Point<3>> {
+        let mut mesh = Mesh::new();
+
+        for triangle in &self.triangles {
+            mesh.push_triangle(to_points(triangle), Color::default());
+        }
+
+        mesh
+    }
+
+    /// Compare this snapshot to a reference
+    ///
+    /// Triangles are considered to match, if each of their vertices is within
+    /// `tolerance` of the respective vertex of the other triangle.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `tolerance` is not positive.
+    pub fn diff(
+        &self,
+        reference: &Self,
+        tolerance: impl Into<Scalar>,
+    ) -> SnapshotDiff {
+        let tolerance = tolerance.into().into_f64();
+        assert!(tolerance > 0., "Snapshot tolerance must be positive");
+
+        let cell = |triangle: &[[f64; 3]; 3]| {
+            [0, 1, 2].map(|i| {
+                let center =
+                    triangle.iter().map(|point| point[i]).sum::<f64>() / 3.;
+                (center / tolerance).floor() as i64
+            })
+        };
+
+        // The centers of matching triangles are within the tolerance of each
+        // other, so they are in the same cell of the grid, or in neighboring
+        // ones.
+        let mut grid = HashMap::<_, Vec<_>>::new();
+        for (i, triangle) in reference.triangles.iter().enumerate() {
+            grid.entry(cell(triangle)).or_default().push(i);
+        }
+
+        let mut matched = vec![false; reference.triangles.len()];
+        let mut unexpected = Vec::new();
+
+        for triangle in &self.triangles {
+            let [x, y, z] = cell(triangle);
+
+            let neighbors = (-1..=1).flat_map(|dx| {
+                (-1..=1).flat_map(move |dy| {
+                    (-1..=1).map(move |dz| [x + dx, y + dy, z + dz])
+                })
+            });
+            let found = neighbors
+                .filter_map(|cell| grid.get(&cell))
+                .flatten()
+                .copied()
+                .find(|&i| {
+                    !matched[i]
+                        && triangles_match(
+                            triangle,
+                            &reference.triangles[i],
+                            tolerance,
+                        )
+                });
+
+            match found {
+                Some(i) => matched[i] = true,
+                None => unexpected.push(to_points(triangle)),
+            }
+        }
+
+        let missing = reference
+            .triangles
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(triangle, _)| to_points(triangle))
+            .collect();
+
+        SnapshotDiff {
+            missing,
+            unexpected,
+        }
+    }
+
+    /// Load a snapshot from a file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Save the snapshot to a file
+    ///
+    /// Creates the parent directory of the file, if necessary.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+
+        Ok(())
+    }
+
+    /// Assert that the snapshot matches the stored reference with this name
+    ///
+    /// See [module documentation] for where references are stored, and how
+    /// they are updated.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the snapshot doesn't match the reference.
+    ///
+    /// [module documentation]: self
+    pub fn assert_matches(&self, name: &str, tolerance: impl Into<Scalar>) {
+        let directory = env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join("snapshots");
+
+        self.assert_matches_file(
+            directory.join(format!("{name}.json")),
+            tolerance,
+        );
+    }
+
+    /// Assert that the snapshot matches the reference in the provided file
+    ///
+    /// Works like [`Snapshot::assert_matches`], but uses the provided path for
+    /// the reference.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the snapshot doesn't match the reference.
+    pub fn assert_matches_file(
+        &self,
+        path: impl AsRef<Path>,
+        tolerance: impl Into<Scalar>,
+    ) {
+        let path = path.as_ref();
+
+        let update = env::var_os("FJ_UPDATE_SNAPSHOTS").is_some();
+        let exists = path.exists();
+
+        if update || !exists {
+            if !exists && env::var_os("CI").is_some() {
+                panic!("Snapshot `{}` does not exist", path.display());
+            }
+
+            self.save(path).unwrap_or_else(|err| {
+                panic!("Failed to save snapshot `{}`: {err}", path.display())
+            });
+            return;
+        }
+
+        let reference = Self::load(path).unwrap_or_else(|err| {
+            panic!("Failed to load snapshot `{}`: {err}", path.display())
+        });
+        if self.hash == reference.hash {
+            return;
+        }
+
+        let diff = self.diff(&reference, tolerance);
+        if !diff.is_empty() {
+            panic!(
+                "Model does not match snapshot `{}`:\n{diff}\n\
+                Set `FJ_UPDATE_SNAPSHOTS` to update the snapshot, if this \
+                change is intended.",
+                path.display(),
+            );
+        }
+    }
+}
+
+/// The difference between a snapshot and its reference
+///
+/// Returned by [`Snapshot::diff`].
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotDiff {
+    /// Triangles of the reference that have no match in the snapshot
+    pub missing: Vec<[Point<3>; 3]>,
+
+    /// Triangles of the snapshot that have no match in the reference
+    pub unexpected: Vec<[Point<3>; 3]>,
+}
+
+impl SnapshotDiff {
+    /// Indicate whether snapshot and reference match
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+impl fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const MAX_LISTED: usize = 5;
+
+        for (label, triangles) in
+            [("Missing", &self.missing), ("Unexpected", &self.unexpected)]
+        {
+            if triangles.is_empty() {
+                continue;
+            }
+
+            writeln!(f, "{label} triangles: {}", triangles.len())?;
+            for [a, b, c] in triangles.iter().take(MAX_LISTED) {
+                writeln!(f, "    [{a:?}, {b:?}, {c:?}]")?;
+            }
+            if triangles.len() > MAX_LISTED {
+                writeln!(f, "    ...")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Error loading or saving a [`Snapshot`]
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    /// Failed to read or write the file
+    #[error("Failed to read or write snapshot")]
+    Io(#[from] io::Error),
+
+    /// Failed to parse or serialize the snapshot
+    #[error("Failed to parse or serialize snapshot")]
+    Json(#[from] serde_json::Error),
+}
+
+fn compare_points(a: &[f64; 3], b: &[f64; 3]) -> std::cmp::Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| a.total_cmp(b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(std::cmp::Ordering::Equal)
+}
+
+fn triangles_match(
+    a: &[[f64; 3]; 3],
+    b: &[[f64; 3]; 3],
+    tolerance: f64,
+) -> bool {
+    let distance = |a: &[f64; 3], b: &[f64; 3]| {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f64>()
+            .sqrt()
+    };
+
+    // A small change in a vertex can change which one is the smallest, so the
+    // triangles might be rotated differently. The winding must be the same,
+    // though.
+    (0..3).any(|offset| {
+        (0..3).all(|i| distance(&a[i], &b[(i + offset) % 3]) <= tolerance)
+    })
+}
+
+fn to_points(triangle: &[[f64; 3]; 3]) -> [Point<3>; 3] {
+    triangle.map(Point::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_core::{
+        algorithms::transform::TransformObject, objects::Solid,
+        operations::primitives::BuildPrimitive, services::Services,
+    };
+
+    use super::Snapshot;
+
+    #[test]
+    fn snapshot_diff() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let model = Solid::box_from_dims([3., 2., 1.], &mut services);
+        let snapshot = Snapshot::of_model(&model, 0.01);
+        assert_eq!(snapshot, Snapshot::of_model(&model, 0.01));
+        assert!(snapshot.num_triangles() > 0);
+
+        let nudged = model.clone().translate([1e-9, 0., 0.], &mut services);
+        let nudged = Snapshot::of_model(&nudged, 0.01);
+        assert_ne!(snapshot.hash(), nudged.hash());
+        assert!(nudged.diff(&snapshot, 1e-6).is_empty());
+
+        let moved = model.translate([1., 0., 0.], &mut services);
+        let moved = Snapshot::of_model(&moved, 0.01);
+        let diff = moved.diff(&snapshot, 1e-6);
+        assert_eq!(diff.missing.len(), snapshot.num_triangles());
+        assert_eq!(diff.unexpected.len(), moved.num_triangles());
+
+        let path = std::env::temp_dir()
+            .join(format!("fj-snapshot-test-{}.json", std::process::id()));
+        snapshot.save(&path)?;
+        assert_eq!(Snapshot::load(&path)?, snapshot);
+        std::fs::remove_file(path)?;
+
+        services.drop_and_validate()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+//! Timing of kernel operations
+//!
+//! See [`Timings`].
+
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use tracing::{span, Subscriber};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+/// Measures how much time is spent in the kernel's operations
+///
+/// The kernel emits [`tracing`] spans around its major operations, like
+/// sweeping, transforming, approximating, triangulating, and validating. Add
+/// the layer returned by [`Timings::layer`] to your subscriber, then call
+/// [`Timings::report`] to find out which of them dominates the build time of a
+/// model.
+///
+/// To include the construction of a model in the report, the subscriber must
+/// be set up before the model is constructed.
+#[derive(Clone, Default)]
+pub struct Timings {
+    inner: Arc<Mutex<BTreeMap<&'static str, TimingEntry>>>,
+}
+
+impl Timings {
+    /// Construct a new instance of `Timings`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a layer that records timings into this instance
+    pub fn layer(&self) -> TimingLayer {
+        TimingLayer {
+            timings: self.clone(),
+        }
+    }
+
+    /// Create a report of the timings recorded so far
+    pub fn report(&self) -> TimingReport {
+        let mut entries = self
+            .inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| Reverse(entry.total));
+
+        TimingReport { entries }
+    }
+
+    fn record(&self, name: &'static str, elapsed: Duration) {
+        let mut inner =
+            self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let entry = inner.entry(name).or_insert(TimingEntry {
+            name,
+            count: 0,
+            total: Duration::ZERO,
+        });
+        entry.count += 1;
+        entry.total += elapsed;
+    }
+}
+
+/// A [`Layer`] that records the time spent in each kind of span
+///
+/// Time is measured while a span is entered, and attributed to the name of the
+/// span. Time spent in nested spans is counted towards their parents too.
+///
+/// See [`Timings`].
+pub struct TimingLayer {
+    timings: Timings,
+}
+
+impl<S> Layer<S> for TimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().replace(Entered(Instant::now()));
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(Entered(start)) = span.extensions_mut().remove() {
+                self.timings.record(span.name(), start.elapsed());
+            }
+        }
+    }
+}
+
+/// The time at which a span was entered
+struct Entered(Instant);
+
+/// A report of the time spent in each kind of span
+///
+/// Returned by [`Timings::report`].
+#[derive(Clone, Debug)]
+pub struct TimingReport {
+    entries: Vec<TimingEntry>,
+}
+
+impl TimingReport {
+    /// Access the entries of the report, sorted by total time, descending
+    pub fn entries(&self) -> &[TimingEntry] {
+        &self.entries
+    }
+}
+
+impl fmt::Display for TimingReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<16} {:>10} {:>14}", "operation", "count", "total")?;
+
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{:<16} {:>10} {:>14}",
+                entry.name,
+                entry.count,
+                format!("{:.3?}", entry.total),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The time spent in one kind of span
+#[derive(Clone, Debug)]
+pub struct TimingEntry {
+    /// The name of the span
+    pub name: &'static str,
+
+    /// How often the span was entered
+    pub count: u64,
+
+    /// The total time spent in the span
+    pub total: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::prelude::*;
+
+    use super::Timings;
+
+    #[test]
+    fn record_spans() {
+        let timings = Timings::new();
+        let subscriber = tracing_subscriber::registry().with(timings.layer());
+
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..3 {
+                let _span = tracing::info_span!("a").entered();
+                let _span = tracing::info_span!("b").entered();
+            }
+        });
+
+        let report = timings.report();
+        let counts = report
+            .entries()
+            .iter()
+            .map(|entry| (entry.name, entry.count))
+            .collect::<Vec<_>>();
+
+        // "a" contains "b", so it must be reported first.
+        assert_eq!(counts, [("a", 3), ("b", 3)]);
+    }
+}
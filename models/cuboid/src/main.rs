@@ -1,6 +1,8 @@
 use fj::{core::services::Services, handle_model};
 
 fn main() -> fj::Result {
+    fj::init_tracing();
+
     let mut services = Services::new();
     let model = cuboid::model([3., 2., 1.], &mut services);
     handle_model(model, services)?;
@@ -4,7 +4,6 @@ use fj::{
         operations::{
             build::{BuildCycle, BuildRegion, BuildSketch},
             insert::Insert,
-            reverse::Reverse,
             sweep::SweepSketch,
             update::{UpdateRegion, UpdateSketch},
         },
@@ -31,7 +30,6 @@ pub fn model(
                     inner,
                     services,
                 )
-                .reverse(services)
                 .insert(services)])
                 .insert(services),
         )
@@ -1,6 +1,8 @@
 use fj::{core::services::Services, handle_model};
 
 fn main() -> fj::Result {
+    fj::init_tracing();
+
     let mut services = Services::new();
     let model = spacer::model(1., 0.5, 1., &mut services);
     handle_model(model, services)?;